@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+use RustQuant::autodiff::*;
+
+// Compares the reverse pass over the regular `Graph` with the
+// structure-of-arrays `CompactGraph`, for a large graph with several outputs.
+//
+// Run in release mode:
+//
+//      cargo run --release --example reverse_pass_benchmark
+
+const INPUTS: usize = 64;
+const STEPS: usize = 20_000;
+const OUTPUTS: usize = 8;
+const REPEATS: u32 = 20;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..REPEATS {
+        f();
+    }
+    start.elapsed() / REPEATS
+}
+
+fn main() {
+    let g = Graph::new();
+
+    let inputs: Vec<f64> = (0..INPUTS).map(|i| 1.0 + i as f64 / 100.0).collect();
+    let x = g.vars(&inputs);
+
+    // A long chain of mixed operations, with several outputs along the way.
+    let mut z = x[0];
+    let mut outputs = Vec::with_capacity(OUTPUTS);
+
+    for i in 0..STEPS {
+        z = (z * x[i % INPUTS]).sin() + x[(i * 7) % INPUTS].ln() / 10.0;
+
+        if (i + 1) % (STEPS / OUTPUTS) == 0 {
+            outputs.push(z);
+        }
+    }
+
+    println!("Vertices: {}", g.len());
+    println!("Outputs:  {}", outputs.len());
+
+    let compact = CompactGraph::new(&g);
+
+    // One reverse sweep per output.
+    let graph_single = time(|| {
+        for output in &outputs {
+            std::hint::black_box(output.accumulate());
+        }
+    });
+    let compact_single = time(|| {
+        for output in &outputs {
+            std::hint::black_box(compact.accumulate(output));
+        }
+    });
+
+    // All outputs at once.
+    let graph_multi = time(|| {
+        std::hint::black_box(outputs.accumulate());
+    });
+    let compact_multi = time(|| {
+        std::hint::black_box(compact.accumulate_many(&outputs));
+    });
+
+    println!();
+    println!("One sweep per output:");
+    println!("  Graph:        {graph_single:?}");
+    println!("  CompactGraph: {compact_single:?}");
+    println!(
+        "  Speedup:      {:.2}x",
+        graph_single.as_secs_f64() / compact_single.as_secs_f64()
+    );
+    println!();
+    println!("All outputs in one sweep ({LANES} lanes per chunk):");
+    println!("  Graph (MultiAdjoints):        {graph_multi:?}");
+    println!("  CompactGraph (lane batched):  {compact_multi:?}");
+    println!(
+        "  Speedup:                      {:.2}x",
+        graph_multi.as_secs_f64() / compact_multi.as_secs_f64()
+    );
+    println!(
+        "  Speedup vs. one sweep/output: {:.2}x",
+        graph_single.as_secs_f64() / compact_multi.as_secs_f64()
+    );
+}
@@ -19,7 +19,7 @@
 
 use ndarray::Array2;
 
-use super::{Variable, VariableArray};
+use super::{gradient::adjoint, Gradient, Variable, VariableArray};
 
 /// Trait to reverse accumulate the gradient for different types.
 pub trait Accumulate<OUT> {
@@ -38,6 +38,12 @@ impl Accumulate<Vec<f64>> for Variable<'_> {
         // The seed is the derivative of the output with respect to itself.
         // dy/dy = 1
         let mut adjoints = vec![0.0; self.graph.len()];
+
+        // Detached variables are constants.
+        if self.is_detached() {
+            return adjoints;
+        }
+
         adjoints[self.index] = 1.0; // SEED
 
         // Traverse the graph backwards and update the adjoints for the parent vertices.
@@ -67,3 +73,232 @@ impl<'v> Accumulate<Array2<Vec<f64>>> for VariableArray<'v> {
         adjoints
     }
 }
+
+impl Variable<'_> {
+    /// Reverse accumulate the gradient into a user-provided buffer, rather
+    /// than allocating a new vector (see [`Adjoints`] for a reusable buffer
+    /// that grows with the graph).
+    ///
+    /// The first `self.graph.len()` entries of `adjoints` are overwritten,
+    /// so that `adjoints[i]` is the adjoint of the vertex at index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `adjoints` is shorter than the graph.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(2.0);
+    /// let y = g.var(3.0);
+    /// let z = x * y;
+    ///
+    /// let mut adjoints = [0.0; 8];
+    /// z.accumulate_into(&mut adjoints);
+    ///
+    /// assert_eq!(adjoints[x.index], 3.0);
+    /// assert_eq!(adjoints[y.index], 2.0);
+    /// ```
+    #[inline]
+    pub fn accumulate_into(&self, adjoints: &mut [f64]) {
+        let vertices = self.graph.vertices.borrow();
+
+        assert!(
+            adjoints.len() >= vertices.len(),
+            "Buffer ({}) is shorter than the graph ({}).",
+            adjoints.len(),
+            vertices.len()
+        );
+
+        adjoints[..vertices.len()].fill(0.0);
+
+        // Detached variables are constants.
+        if self.is_detached() {
+            return;
+        }
+
+        adjoints[self.index] = 1.0; // SEED
+
+        // Vertices recorded after the output do not contribute to it.
+        for index in (0..=self.index).rev() {
+            let vertex = &vertices[index];
+            let deriv = adjoints[index];
+
+            adjoints[vertex.parents[0]] += vertex.partials[0] * deriv;
+            adjoints[vertex.parents[1]] += vertex.partials[1] * deriv;
+        }
+    }
+}
+
+/// Reusable workspace for reverse accumulation.
+///
+/// The buffer is only reallocated when the graph grows beyond its
+/// capacity, so repeated gradient evaluations (e.g. inside a calibration
+/// loop) do not allocate.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Adjoints {
+    /// The adjoints of the most recent accumulation.
+    values: Vec<f64>,
+}
+
+impl Adjoints {
+    /// Create a new, empty workspace.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    /// Create a new workspace with space for a graph of `capacity` vertices.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of vertices the workspace can hold without allocating.
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Reverse accumulate the gradient of `output` into the workspace,
+    /// and return the adjoints of every vertex in the graph.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    /// let mut adjoints = Adjoints::new();
+    ///
+    /// for i in 0..10 {
+    ///     g.clear();
+    ///
+    ///     let x = g.var(i as f64);
+    ///     let y = x * x;
+    ///
+    ///     adjoints.accumulate(&y);
+    ///
+    ///     assert_eq!(adjoints.wrt(&x), 2.0 * i as f64);
+    /// }
+    /// ```
+    #[inline]
+    pub fn accumulate(&mut self, output: &Variable) -> &[f64] {
+        self.values.resize(output.graph.len(), 0.0);
+        output.accumulate_into(&mut self.values);
+
+        &self.values
+    }
+
+    /// The adjoints of the most recent accumulation.
+    #[must_use]
+    #[inline]
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+/// `wrt` a single variable.
+impl<'v> Gradient<&Variable<'v>, f64> for Adjoints {
+    #[inline]
+    fn wrt(&self, variable: &Variable<'v>) -> f64 {
+        adjoint(&self.values, variable)
+    }
+}
+
+/// `wrt` a borrowed slice of variables.
+impl<'v> Gradient<&[Variable<'v>], Vec<f64>> for Adjoints {
+    #[inline]
+    fn wrt(&self, variables: &[Variable<'v>]) -> Vec<f64> {
+        variables
+            .iter()
+            .map(|var| adjoint(&self.values, var))
+            .collect()
+    }
+}
+
+/// `wrt` a borrowed vector of variables.
+impl<'v> Gradient<&Vec<Variable<'v>>, Vec<f64>> for Adjoints {
+    #[inline]
+    fn wrt(&self, variables: &Vec<Variable<'v>>) -> Vec<f64> {
+        self.wrt(&variables[..])
+    }
+}
+
+/// `wrt` a borrowed array of variables.
+impl<'v, const N: usize> Gradient<&[Variable<'v>; N], Vec<f64>> for Adjoints {
+    #[inline]
+    fn wrt(&self, variables: &[Variable<'v>; N]) -> Vec<f64> {
+        self.wrt(&variables[..])
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_accumulate {
+    use crate::autodiff::*;
+
+    #[test]
+    fn test_accumulate_into_matches_accumulate() {
+        let g = Graph::new();
+
+        let x = g.var(0.5);
+        let y = g.var(1.5);
+
+        let z = (x * y).sin() + (x / y).exp();
+        let _unused = z.ln() * x;
+
+        let mut buffer = vec![f64::NAN; g.len() + 3];
+        z.accumulate_into(&mut buffer);
+
+        assert_eq!(&buffer[..g.len()], &z.accumulate()[..]);
+        assert!(buffer[g.len()].is_nan());
+    }
+
+    #[test]
+    fn test_workspace_does_not_reallocate() {
+        let g = Graph::new();
+        let mut adjoints = Adjoints::new();
+
+        for i in 0..100 {
+            g.clear();
+
+            let params = g.vars(&[1.0, 2.0, f64::from(i)]);
+            let loss = (params[0] * params[1] - params[2]).powi(2);
+
+            let gradient = adjoints.accumulate(&loss).to_vec();
+
+            assert_eq!(gradient, loss.accumulate());
+            assert_eq!(adjoints.wrt(&params), loss.accumulate().wrt(&params));
+        }
+
+        let capacity = adjoints.capacity();
+        let ptr = adjoints.as_slice().as_ptr();
+
+        g.clear();
+        let x = g.var(3.0);
+        let _ = adjoints.accumulate(&(x * x + 1.0));
+
+        assert_eq!(adjoints.capacity(), capacity);
+        assert_eq!(adjoints.as_slice().as_ptr(), ptr);
+        assert_eq!(adjoints.wrt(&x), 6.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "shorter than the graph")]
+    fn test_accumulate_into_short_buffer() {
+        let g = Graph::new();
+        let x = g.var(1.0);
+        let z = x.exp();
+
+        z.accumulate_into(&mut [0.0; 1]);
+    }
+}
@@ -0,0 +1,291 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! This module contains the storage for the vertices of a `Graph`.
+//!
+//! By default the vertices are stored contiguously in a single growable
+//! vector, which is reallocated (and copied) whenever it runs out of
+//! capacity. For large graphs, the arena can instead allocate the vertices
+//! in fixed-size blocks: when a block is full a new one is allocated, and
+//! existing vertices are never moved.
+//!
+//! Blocks are kept when the arena is truncated or cleared, so re-recording
+//! a graph (e.g. after [`Graph::rewind_to`](crate::autodiff::Graph::rewind_to))
+//! does not allocate.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::Vertex;
+use std::ops::{Index, IndexMut};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ARENA STRUCT AND IMPLEMENTATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Storage for the vertices of a `Graph`.
+#[derive(Debug, Clone, Default)]
+pub struct VertexArena {
+    /// The blocks of vertices.
+    blocks: Vec<Vec<Vertex>>,
+    /// Size of each block, or `None` for a single growable block.
+    block_size: Option<usize>,
+    /// Total number of vertices.
+    len: usize,
+}
+
+impl VertexArena {
+    /// Instantiate a new arena with a single growable block.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            block_size: None,
+            len: 0,
+        }
+    }
+
+    /// Instantiate a new arena with a single growable block,
+    /// with space for at least `capacity` vertices.
+    #[must_use]
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            blocks: vec![Vec::with_capacity(capacity)],
+            block_size: None,
+            len: 0,
+        }
+    }
+
+    /// Instantiate a new arena that allocates vertices in fixed-size blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    #[must_use]
+    #[inline]
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert!(block_size > 0, "Block size must be positive.");
+
+        Self {
+            blocks: Vec::new(),
+            block_size: Some(block_size),
+            len: 0,
+        }
+    }
+
+    /// Returns the block size (`None` for a single growable block).
+    #[must_use]
+    #[inline]
+    pub const fn block_size(&self) -> Option<usize> {
+        self.block_size
+    }
+
+    /// Returns the number of vertices in the arena.
+    #[must_use]
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the arena contains no vertices.
+    #[must_use]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of vertices that can be stored without allocating.
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.blocks.iter().map(Vec::capacity).sum()
+    }
+
+    /// Reserve space for at least `additional` more vertices.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        match self.block_size {
+            None => {
+                if self.blocks.is_empty() {
+                    self.blocks.push(Vec::new());
+                }
+                self.blocks[0].reserve(additional);
+            }
+            Some(size) => {
+                let required = (self.len + additional).div_ceil(size);
+
+                while self.blocks.len() < required {
+                    self.blocks.push(Vec::with_capacity(size));
+                }
+            }
+        }
+    }
+
+    /// Push a vertex onto the end of the arena.
+    #[inline]
+    pub fn push(&mut self, vertex: Vertex) {
+        let block = match self.block_size {
+            None => 0,
+            Some(size) => self.len / size,
+        };
+
+        if block == self.blocks.len() {
+            self.blocks
+                .push(Vec::with_capacity(self.block_size.unwrap_or(0)));
+        }
+
+        self.blocks[block].push(vertex);
+        self.len += 1;
+    }
+
+    /// Shorten the arena, keeping the first `len` vertices.
+    /// The allocated blocks are kept for re-use.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        match self.block_size {
+            None => self.blocks[0].truncate(len),
+            Some(size) => {
+                for (i, block) in self.blocks.iter_mut().enumerate() {
+                    block.truncate(len.saturating_sub(i * size).min(size));
+                }
+            }
+        }
+
+        self.len = len;
+    }
+
+    /// Remove all vertices. The allocated blocks are kept for re-use.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Iterate over the vertices.
+    #[must_use]
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Vertex> + ExactSizeIterator + '_ {
+        (0..self.len).map(move |i| &self[i])
+    }
+
+    /// Iterate mutably over the vertices.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Vertex> + '_ {
+        self.blocks.iter_mut().flatten()
+    }
+
+    /// Block and offset of the vertex at `index`.
+    #[inline]
+    fn locate(&self, index: usize) -> (usize, usize) {
+        match self.block_size {
+            None => (0, index),
+            Some(size) => (index / size, index % size),
+        }
+    }
+}
+
+impl Index<usize> for VertexArena {
+    type Output = Vertex;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        let (block, offset) = self.locate(index);
+        &self.blocks[block][offset]
+    }
+}
+
+impl IndexMut<usize> for VertexArena {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let (block, offset) = self.locate(index);
+        &mut self.blocks[block][offset]
+    }
+}
+
+impl Extend<Vertex> for VertexArena {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Vertex>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|vertex| self.push(vertex));
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_arena {
+    use super::*;
+    use crate::autodiff::*;
+
+    #[test]
+    fn test_blocks_do_not_move() {
+        let mut arena = VertexArena::with_block_size(4);
+
+        arena.push(Vertex::new_unary(1.0, 0));
+        let first = std::ptr::addr_of!(arena[0]);
+
+        for i in 1..100 {
+            arena.push(Vertex::new_unary(i as f64, i));
+        }
+
+        assert_eq!(arena.len(), 100);
+        assert_eq!(arena.capacity(), 100);
+        assert_eq!(std::ptr::addr_of!(arena[0]), first);
+        assert_eq!(arena[57].partials[0], 57.0);
+        assert_eq!(arena.iter().rev().next().unwrap().parents[0], 99);
+    }
+
+    #[test]
+    fn test_truncate_reuses_blocks() {
+        let mut arena = VertexArena::with_block_size(8);
+
+        arena.extend((0..20).map(|i| Vertex::new_unary(i as f64, i)));
+        let capacity = arena.capacity();
+
+        arena.truncate(5);
+        assert_eq!(arena.len(), 5);
+        assert_eq!(arena.iter().count(), 5);
+
+        arena.extend((0..15).map(|i| Vertex::new_unary(-(i as f64), i)));
+        assert_eq!(arena.len(), 20);
+        assert_eq!(arena.capacity(), capacity);
+        assert_eq!(arena[4].partials[0], 4.0);
+        assert_eq!(arena[5].partials[0], 0.0);
+        assert_eq!(arena[19].partials[0], -14.0);
+    }
+
+    #[test]
+    fn test_graph_with_arena() {
+        let g = Graph::with_block_size(3);
+
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+
+        let z = (x * y).sin() + (x / y).exp() - y.ln();
+        let grad = z.accumulate();
+
+        let h = Graph::new();
+
+        let a = h.var(1.0);
+        let b = h.var(2.0);
+
+        let c = (a * b).sin() + (a / b).exp() - b.ln();
+        let expected = c.accumulate();
+
+        assert_eq!(g.len(), h.len());
+        assert_eq!(grad.wrt(&[x, y]), expected.wrt(&[a, b]));
+    }
+}
@@ -0,0 +1,256 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Custom (user-defined) operations for the `Graph`.
+//!
+//! When the derivatives of a function are known in closed form (e.g. the
+//! Black-Scholes price and its Greeks), the function can be recorded as a
+//! single operation with hand-written partials, rather than as the hundreds
+//! of elementary operations needed to evaluate it.
+//!
+//! Operations with more than two inputs are recorded as a short chain of
+//! binary vertices: $n$ inputs require $n - 1$ vertices.
+//!
+//! Note: custom operations are treated as locally linear when computing
+//! Hessians, since no second order partials are supplied.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Arity, Graph, Variable};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// CUSTOM OPERATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Graph {
+    /// Record a custom unary operation $y = f(x)$.
+    ///
+    /// # Arguments:
+    /// * `x` - The input variable.
+    /// * `value` - The value $f(x)$.
+    /// * `partial` - The derivative $f'(x)$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` belongs to a different graph.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(2.0);
+    ///
+    /// // y = x^3
+    /// let y = g.custom_unary(x, 8.0, 12.0);
+    ///
+    /// assert_eq!(y.value, 8.0);
+    /// assert_eq!(y.accumulate().wrt(&x), 12.0);
+    /// ```
+    #[inline]
+    pub fn custom_unary<'v>(&'v self, x: Variable<'v>, value: f64, partial: f64) -> Variable<'v> {
+        assert!(std::ptr::eq(self, x.graph));
+
+        Variable {
+            graph: self,
+            value,
+            index: self.push(Arity::Unary, &[x.index], &[partial]),
+        }
+    }
+
+    /// Record a custom binary operation $z = f(x, y)$.
+    ///
+    /// # Arguments:
+    /// * `x` - The first input variable.
+    /// * `y` - The second input variable.
+    /// * `value` - The value $f(x, y)$.
+    /// * `partials` - The partial derivatives $[\partial f / \partial x, \partial f / \partial y]$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` or `y` belong to a different graph.
+    #[inline]
+    pub fn custom_binary<'v>(
+        &'v self,
+        x: Variable<'v>,
+        y: Variable<'v>,
+        value: f64,
+        partials: [f64; 2],
+    ) -> Variable<'v> {
+        assert!(std::ptr::eq(self, x.graph) && std::ptr::eq(self, y.graph));
+
+        Variable {
+            graph: self,
+            value,
+            index: self.push(Arity::Binary, &[x.index, y.index], &partials),
+        }
+    }
+
+    /// Record a custom n-ary operation $y = f(x_1, \ldots, x_n)$.
+    ///
+    /// # Arguments:
+    /// * `inputs` - The input variables.
+    /// * `value` - The value $f(x_1, \ldots, x_n)$.
+    /// * `partials` - The partial derivatives $\partial f / \partial x_i$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inputs` and `partials` have different lengths,
+    /// or if any input belongs to a different graph.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.vars(&[1.0, 2.0, 3.0]);
+    ///
+    /// // y = x0 * x1 * x2
+    /// let y = g.custom(&x, 6.0, &[6.0, 3.0, 2.0]);
+    ///
+    /// assert_eq!(y.accumulate().wrt(&x), vec![6.0, 3.0, 2.0]);
+    /// ```
+    pub fn custom<'v>(
+        &'v self,
+        inputs: &[Variable<'v>],
+        value: f64,
+        partials: &[f64],
+    ) -> Variable<'v> {
+        assert_eq!(inputs.len(), partials.len());
+        assert!(inputs.iter().all(|x| std::ptr::eq(self, x.graph)));
+
+        let index = match inputs.len() {
+            0 => self.push(Arity::Nullary, &[], &[]),
+            1 => self.push(Arity::Unary, &[inputs[0].index], &[partials[0]]),
+            _ => {
+                // Chain the inputs through binary vertices:
+                //  v_1 = (x_1, x_2), v_k = (v_{k-1}, x_{k+1}).
+                // The intermediate vertices pass the adjoint through
+                // with a partial of one.
+                let mut index = self.push(
+                    Arity::Binary,
+                    &[inputs[0].index, inputs[1].index],
+                    &[partials[0], partials[1]],
+                );
+
+                for (x, &partial) in inputs.iter().zip(partials).skip(2) {
+                    index = self.push(Arity::Binary, &[index, x.index], &[1.0, partial]);
+                }
+
+                index
+            }
+        };
+
+        Variable {
+            graph: self,
+            value,
+            index,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_custom {
+    use crate::{assert_approx_equal, autodiff::*};
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    fn norm_cdf(x: Variable<'_>) -> Variable<'_> {
+        0.5 * (1.0 + (x / std::f64::consts::SQRT_2).erf())
+    }
+
+    // Black-Scholes call price, recorded as a single custom operation.
+    fn black_scholes<'v>(
+        g: &'v Graph,
+        s: Variable<'v>,
+        k: f64,
+        t: f64,
+        r: Variable<'v>,
+        v: Variable<'v>,
+    ) -> Variable<'v> {
+        let n = Normal::new(0.0, 1.0).unwrap();
+
+        let d1 =
+            ((s.value / k).ln() + (r.value + 0.5 * v.value.powi(2)) * t) / (v.value * t.sqrt());
+        let d2 = d1 - v.value * t.sqrt();
+        let df = (-r.value * t).exp();
+
+        let price = s.value * n.cdf(d1) - k * df * n.cdf(d2);
+        let delta = n.cdf(d1);
+        let rho = k * t * df * n.cdf(d2);
+        let vega = s.value * n.pdf(d1) * t.sqrt();
+
+        g.custom(&[s, r, v], price, &[delta, rho, vega])
+    }
+
+    #[test]
+    fn test_custom_matches_elementary() {
+        let g = Graph::new();
+
+        let s = g.var(100.0);
+        let r = g.var(0.05);
+        let v = g.var(0.2);
+
+        let before = g.len();
+        let c = black_scholes(&g, s, 110.0, 1.0, r, v);
+
+        // Three inputs require two vertices.
+        assert_eq!(g.len() - before, 2);
+
+        let elementary = {
+            let (k, t) = (110.0_f64, 1.0_f64);
+            let d1 = ((s / k).ln() + (r + 0.5 * v * v) * t) / (v * t.sqrt());
+            let d2 = d1 - v * t.sqrt();
+
+            s * norm_cdf(d1) - k * (-r * t).exp() * norm_cdf(d2)
+        };
+
+        let custom = c.accumulate();
+        let reference = elementary.accumulate();
+
+        assert_approx_equal!(c.value, elementary.value, 1e-10);
+
+        for (a, b) in custom.wrt(&[s, r, v]).iter().zip(reference.wrt(&[s, r, v])) {
+            assert_approx_equal!(a, b, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_custom_composes() {
+        let g = Graph::new();
+
+        let x = g.var(0.5);
+        let y = g.var(2.0);
+
+        // z = sin(x) * y, with sin recorded as a custom unary operation.
+        let s = g.custom_unary(x, x.value.sin(), x.value.cos());
+        let z = g.custom_binary(s, y, s.value * y.value, [y.value, s.value]);
+        let w = z.exp();
+
+        let grad = w.accumulate();
+
+        assert_approx_equal!(grad.wrt(&x), w.value * y.value * 0.5_f64.cos(), 1e-12);
+        assert_approx_equal!(grad.wrt(&y), w.value * 0.5_f64.sin(), 1e-12);
+    }
+
+    #[test]
+    fn test_custom_constant() {
+        let g = Graph::new();
+
+        let c = g.custom(&[], 3.0, &[]);
+
+        assert_approx_equal!(c.value, 3.0, f64::EPSILON);
+        assert_eq!(c.accumulate().len(), 1);
+    }
+}
@@ -0,0 +1,822 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Forward (tangent) mode automatic differentiation via dual numbers.
+//!
+//! A dual number $a + b \epsilon$ (with $\epsilon^2 = 0$) carries a value
+//! $a$ and a tangent $b$. Evaluating a function on $x + \dot{x} \epsilon$
+//! yields $f(x) + f'(x) \dot{x} \epsilon$, so one forward pass gives the
+//! directional derivative of $f$ in the direction $\dot{x}$.
+//!
+//! Unlike the reverse mode `Graph`, nothing is recorded, so there is no
+//! memory overhead. Forward mode is preferable when the number of inputs
+//! is small compared to the number of outputs.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Log, Max, Min, Powf, Powi};
+use std::f64::consts::{LN_10, LN_2, PI};
+use std::fmt::Display;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCT AND IMPLEMENTATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Dual number for forward mode automatic differentiation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Dual {
+    /// The value (real part).
+    pub value: f64,
+    /// The tangent (dual part), i.e. the directional derivative.
+    pub tangent: f64,
+}
+
+impl Dual {
+    /// Instantiate a new dual number.
+    #[must_use]
+    #[inline]
+    pub const fn new(value: f64, tangent: f64) -> Self {
+        Self { value, tangent }
+    }
+
+    /// Instantiate a variable (tangent of one), i.e. the direction $x$.
+    #[must_use]
+    #[inline]
+    pub const fn variable(value: f64) -> Self {
+        Self::new(value, 1.0)
+    }
+
+    /// Instantiate a constant (tangent of zero).
+    #[must_use]
+    #[inline]
+    pub const fn constant(value: f64) -> Self {
+        Self::new(value, 0.0)
+    }
+
+    /// Function to return the value.
+    #[must_use]
+    #[inline]
+    pub const fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Function to return the tangent.
+    #[must_use]
+    #[inline]
+    pub const fn tangent(&self) -> f64 {
+        self.tangent
+    }
+
+    /// Apply the chain rule for a unary function with value `f` and
+    /// derivative `df` at `self.value`.
+    #[must_use]
+    #[inline]
+    pub fn chain(self, f: f64, df: f64) -> Self {
+        Self::new(f, df * self.tangent)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// HELPER FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Derivative of a scalar function $f: \mathbb{R} \rightarrow \mathbb{R}$.
+///
+/// Returns `(f(x), f'(x))`.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+///
+/// let (f, df) = derivative(|x| x * x.sin(), 1.0);
+///
+/// assert!((f - 1.0_f64.sin()).abs() < 1e-15);
+/// assert!((df - (1.0_f64.sin() + 1.0_f64.cos())).abs() < 1e-15);
+/// ```
+pub fn derivative<F>(f: F, x: f64) -> (f64, f64)
+where
+    F: Fn(Dual) -> Dual,
+{
+    let y = f(Dual::variable(x));
+
+    (y.value, y.tangent)
+}
+
+/// Jacobian-vector product of $f: \mathbb{R}^n \rightarrow \mathbb{R}^m$.
+///
+/// Evaluates $f$ at `x` and its directional derivative $J_f(x) \cdot v$
+/// in a single forward pass. Returns `(f(x), J v)`.
+///
+/// # Panics
+///
+/// Panics if `x` and `v` have different lengths.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+///
+/// // f(x, y) = (x * y, x + y)
+/// let f = |x: &[Dual]| vec![x[0] * x[1], x[0] + x[1]];
+///
+/// let (value, jvp) = jacobian_vector_product(f, &[2.0, 3.0], &[1.0, 0.0]);
+///
+/// assert_eq!(value, vec![6.0, 5.0]);
+/// assert_eq!(jvp, vec![3.0, 1.0]);
+/// ```
+pub fn jacobian_vector_product<F>(f: F, x: &[f64], v: &[f64]) -> (Vec<f64>, Vec<f64>)
+where
+    F: Fn(&[Dual]) -> Vec<Dual>,
+{
+    assert_eq!(x.len(), v.len());
+
+    let inputs: Vec<Dual> = x.iter().zip(v).map(|(&a, &b)| Dual::new(a, b)).collect();
+
+    f(&inputs).into_iter().map(|y| (y.value, y.tangent)).unzip()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OVERLOADING: STANDARD MATH OPERATORS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl From<f64> for Dual {
+    #[inline]
+    fn from(value: f64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl Neg for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self::new(-self.value, -self.tangent)
+    }
+}
+
+/// Dual + Dual
+impl Add<Dual> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Dual) -> Self::Output {
+        Self::new(self.value + other.value, self.tangent + other.tangent)
+    }
+}
+
+/// Dual + f64
+impl Add<f64> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: f64) -> Self::Output {
+        Self::new(self.value + other, self.tangent)
+    }
+}
+
+/// f64 + Dual
+impl Add<Dual> for f64 {
+    type Output = Dual;
+
+    #[inline]
+    fn add(self, other: Dual) -> Self::Output {
+        other + self
+    }
+}
+
+/// Dual - Dual
+impl Sub<Dual> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Dual) -> Self::Output {
+        Self::new(self.value - other.value, self.tangent - other.tangent)
+    }
+}
+
+/// Dual - f64
+impl Sub<f64> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: f64) -> Self::Output {
+        Self::new(self.value - other, self.tangent)
+    }
+}
+
+/// f64 - Dual
+impl Sub<Dual> for f64 {
+    type Output = Dual;
+
+    #[inline]
+    fn sub(self, other: Dual) -> Self::Output {
+        Dual::new(self - other.value, -other.tangent)
+    }
+}
+
+/// Dual * Dual
+impl Mul<Dual> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: Dual) -> Self::Output {
+        Self::new(
+            self.value * other.value,
+            self.tangent * other.value + self.value * other.tangent,
+        )
+    }
+}
+
+/// Dual * f64
+impl Mul<f64> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: f64) -> Self::Output {
+        Self::new(self.value * other, self.tangent * other)
+    }
+}
+
+/// f64 * Dual
+impl Mul<Dual> for f64 {
+    type Output = Dual;
+
+    #[inline]
+    fn mul(self, other: Dual) -> Self::Output {
+        other * self
+    }
+}
+
+/// Dual / Dual
+impl Div<Dual> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, other: Dual) -> Self::Output {
+        Self::new(
+            self.value / other.value,
+            (self.tangent * other.value - self.value * other.tangent) / other.value.powi(2),
+        )
+    }
+}
+
+/// Dual / f64
+impl Div<f64> for Dual {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, other: f64) -> Self::Output {
+        Self::new(self.value / other, self.tangent / other)
+    }
+}
+
+/// f64 / Dual
+impl Div<Dual> for f64 {
+    type Output = Dual;
+
+    #[inline]
+    fn div(self, other: Dual) -> Self::Output {
+        Dual::new(
+            self / other.value,
+            -self * other.tangent / other.value.powi(2),
+        )
+    }
+}
+
+impl AddAssign<Dual> for Dual {
+    #[inline]
+    fn add_assign(&mut self, other: Dual) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<f64> for Dual {
+    #[inline]
+    fn add_assign(&mut self, other: f64) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign<Dual> for Dual {
+    #[inline]
+    fn sub_assign(&mut self, other: Dual) {
+        *self = *self - other;
+    }
+}
+
+impl SubAssign<f64> for Dual {
+    #[inline]
+    fn sub_assign(&mut self, other: f64) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<Dual> for Dual {
+    #[inline]
+    fn mul_assign(&mut self, other: Dual) {
+        *self = *self * other;
+    }
+}
+
+impl MulAssign<f64> for Dual {
+    #[inline]
+    fn mul_assign(&mut self, other: f64) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign<Dual> for Dual {
+    #[inline]
+    fn div_assign(&mut self, other: Dual) {
+        *self = *self / other;
+    }
+}
+
+impl DivAssign<f64> for Dual {
+    #[inline]
+    fn div_assign(&mut self, other: f64) {
+        *self = *self / other;
+    }
+}
+
+impl Sum<Dual> for Dual {
+    #[inline]
+    fn sum<I: Iterator<Item = Dual>>(iter: I) -> Self {
+        iter.fold(Dual::constant(0.0), |x, y| x + y)
+    }
+}
+
+impl Product<Dual> for Dual {
+    #[inline]
+    fn product<I: Iterator<Item = Dual>>(iter: I) -> Self {
+        iter.fold(Dual::constant(1.0), |x, y| x * y)
+    }
+}
+
+impl PartialEq<f64> for Dual {
+    #[inline]
+    fn eq(&self, other: &f64) -> bool {
+        self.value == *other
+    }
+}
+
+impl PartialOrd for Dual {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl Display for Dual {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} + {:?}\u{03b5}", self.value, self.tangent)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OVERLOADING: POWER, LOG AND MIN/MAX TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Dual ^ Dual
+impl Powf<Dual> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn powf(&self, other: Dual) -> Self::Output {
+        let value = self.value.powf(other.value);
+
+        Dual::new(
+            value,
+            other.value * self.value.powf(other.value - 1.0) * self.tangent
+                + value * self.value.ln() * other.tangent,
+        )
+    }
+}
+
+// Dual ^ f64
+impl Powf<f64> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn powf(&self, n: f64) -> Self::Output {
+        self.chain(self.value.powf(n), n * self.value.powf(n - 1.0))
+    }
+}
+
+// f64 ^ Dual
+impl Powf<Dual> for f64 {
+    type Output = Dual;
+
+    #[inline]
+    fn powf(&self, other: Dual) -> Self::Output {
+        let value = f64::powf(*self, other.value);
+
+        other.chain(value, value * f64::ln(*self))
+    }
+}
+
+// Dual ^ i32
+impl Powi<i32> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn powi(&self, n: i32) -> Self::Output {
+        self.chain(self.value.powi(n), f64::from(n) * self.value.powi(n - 1))
+    }
+}
+
+// log_base(Dual), base: Dual
+impl Log<Dual> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn log(&self, base: Dual) -> Self::Output {
+        self.ln() / base.ln()
+    }
+}
+
+// log_base(Dual), base: f64
+impl Log<f64> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn log(&self, base: f64) -> Self::Output {
+        self.chain(self.value.log(base), (self.value * base.ln()).recip())
+    }
+}
+
+impl Min<Dual> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn min(&self, rhs: Dual) -> Self::Output {
+        if self.value < rhs.value {
+            *self
+        } else {
+            rhs
+        }
+    }
+}
+
+impl Min<f64> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn min(&self, rhs: f64) -> Self::Output {
+        Min::min(self, Dual::constant(rhs))
+    }
+}
+
+impl Max<Dual> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn max(&self, rhs: Dual) -> Self::Output {
+        if self.value > rhs.value {
+            *self
+        } else {
+            rhs
+        }
+    }
+}
+
+impl Max<f64> for Dual {
+    type Output = Dual;
+
+    #[inline]
+    fn max(&self, rhs: f64) -> Self::Output {
+        Max::max(self, Dual::constant(rhs))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OVERLOADING: PRIMITIVE FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Dual {
+    /// Absolute value function.
+    /// d/dx abs(x) = sign(x)
+    #[must_use]
+    #[inline]
+    pub fn abs(self) -> Self {
+        self.chain(self.value.abs(), self.value.signum())
+    }
+
+    /// Inverse cosine function.
+    /// d/dx cos^-1(x) = - 1 / sqrt(1 - x^2)
+    #[must_use]
+    #[inline]
+    pub fn acos(self) -> Self {
+        self.chain(
+            self.value.acos(),
+            -(1.0 - self.value.powi(2)).sqrt().recip(),
+        )
+    }
+
+    /// Inverse hyperbolic cosine function.
+    /// d/dx cosh^-1(x) = 1 / ( sqrt(x-1) * sqrt(x+1) )
+    #[must_use]
+    #[inline]
+    pub fn acosh(self) -> Self {
+        self.chain(
+            self.value.acosh(),
+            ((self.value - 1.0).sqrt() * (self.value + 1.0).sqrt()).recip(),
+        )
+    }
+
+    /// Inverse sine function.
+    /// d/dx sin^-1(x) = 1 / sqrt(1 - x^2)
+    #[must_use]
+    #[inline]
+    pub fn asin(self) -> Self {
+        self.chain(self.value.asin(), (1.0 - self.value.powi(2)).sqrt().recip())
+    }
+
+    /// Inverse hyperbolic sine function.
+    /// d/dx sinh^-1(x) = 1 / sqrt(1 + x^2)
+    #[must_use]
+    #[inline]
+    pub fn asinh(self) -> Self {
+        self.chain(
+            self.value.asinh(),
+            (1.0 + self.value.powi(2)).sqrt().recip(),
+        )
+    }
+
+    /// Inverse tangent function.
+    /// d/dx tan^-1(x) = 1 / (1 + x^2)
+    #[must_use]
+    #[inline]
+    pub fn atan(self) -> Self {
+        self.chain(self.value.atan(), (1.0 + self.value.powi(2)).recip())
+    }
+
+    /// Inverse hyperbolic tangent function.
+    /// d/dx tanh^-1(x) = 1 / (1 - x^2)
+    #[must_use]
+    #[inline]
+    pub fn atanh(self) -> Self {
+        self.chain(self.value.atanh(), (1.0 - self.value.powi(2)).recip())
+    }
+
+    /// Cuberoot function.
+    /// d/dx cuberoot(x) = 1 / ( 3 * x^(2/3) )
+    #[must_use]
+    #[inline]
+    pub fn cbrt(self) -> Self {
+        self.chain(
+            self.value.cbrt(),
+            (3.0 * self.value.powf(2.0 / 3.0)).recip(),
+        )
+    }
+
+    /// Cosine function.
+    /// d/dx cos(x) = -sin(x)
+    #[must_use]
+    #[inline]
+    pub fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+
+    /// Hyperbolic cosine function.
+    /// d/dx cosh(x) = sinh(x)
+    #[must_use]
+    #[inline]
+    pub fn cosh(self) -> Self {
+        self.chain(self.value.cosh(), self.value.sinh())
+    }
+
+    /// Exponential function (base *e*).
+    /// d/dx exp(x) = exp(x)
+    #[must_use]
+    #[inline]
+    pub fn exp(self) -> Self {
+        let value = self.value.exp();
+
+        self.chain(value, value)
+    }
+
+    /// Exponential function (base 2)
+    /// d/dx 2^x = 2^x * ln(2)
+    #[must_use]
+    #[inline]
+    pub fn exp2(self) -> Self {
+        let value = self.value.exp2();
+
+        self.chain(value, value * LN_2)
+    }
+
+    /// Exponential function minus 1 function.
+    /// d/dx exp(x) - 1 = exp(x)
+    #[must_use]
+    #[inline]
+    pub fn exp_m1(self) -> Self {
+        self.chain(self.value.exp_m1(), self.value.exp())
+    }
+
+    /// Logarithm (natural) of `x`.
+    /// d/dx ln(x) = 1 / x
+    #[must_use]
+    #[inline]
+    pub fn ln(self) -> Self {
+        self.chain(self.value.ln(), self.value.recip())
+    }
+
+    /// Logarithm (natural) of `1 + x`.
+    /// d/dx ln(1+x) = 1 / (1+x)
+    #[must_use]
+    #[inline]
+    pub fn ln_1p(self) -> Self {
+        self.chain(self.value.ln_1p(), (1.0 + self.value).recip())
+    }
+
+    /// Logarithm (base 10).
+    /// d/dx log_10(x) = 1 / (x * ln(10))
+    #[must_use]
+    #[inline]
+    pub fn log10(self) -> Self {
+        self.chain(self.value.log10(), (self.value * LN_10).recip())
+    }
+
+    /// Logarithm (base 2).
+    /// d/dx log_2(x) = 1 / (x * ln(2))
+    #[must_use]
+    #[inline]
+    pub fn log2(self) -> Self {
+        self.chain(self.value.log2(), (self.value * LN_2).recip())
+    }
+
+    /// Reciprocal function.
+    /// d/dx 1 / x =  - 1 / x^2
+    #[must_use]
+    #[inline]
+    pub fn recip(self) -> Self {
+        self.chain(self.value.recip(), -self.value.powi(2).recip())
+    }
+
+    /// Sine function.
+    /// d/dx sin(x) = cos(x)
+    #[must_use]
+    #[inline]
+    pub fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    /// Hyperbolic sine function.
+    /// d/dx sinh(x) =  cosh(x)
+    #[must_use]
+    #[inline]
+    pub fn sinh(self) -> Self {
+        self.chain(self.value.sinh(), self.value.cosh())
+    }
+
+    /// Square root function.
+    /// d/dx sqrt(x) =  1 / 2*sqrt(x)
+    #[must_use]
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        let value = self.value.sqrt();
+
+        self.chain(value, (2.0 * value).recip())
+    }
+
+    /// Tangent function.
+    /// d/dx tan(x) = 1 / cos^2(x) = sec^2(x)
+    #[must_use]
+    #[inline]
+    pub fn tan(self) -> Self {
+        self.chain(self.value.tan(), self.value.cos().powi(2).recip())
+    }
+
+    /// Hyperbolic tangent function.
+    /// d/dx tanh(x) = sech^2(x) = 1 / cosh^2(x)
+    #[must_use]
+    #[inline]
+    pub fn tanh(self) -> Self {
+        self.chain(self.value.tanh(), self.value.cosh().powi(2).recip())
+    }
+
+    /// Error function.
+    /// d/dx erf(x) = 2e^(-x^2) / sqrt(PI)
+    #[must_use]
+    #[inline]
+    pub fn erf(self) -> Self {
+        use statrs::function::erf::erf;
+
+        self.chain(
+            erf(self.value),
+            2.0 * (-self.value.powi(2)).exp() / PI.sqrt(),
+        )
+    }
+
+    /// Error function (complementary).
+    /// d/dx erfc(x) = -2e^(-x^2) / sqrt(PI)
+    #[must_use]
+    #[inline]
+    pub fn erfc(self) -> Self {
+        use statrs::function::erf::erfc;
+
+        self.chain(
+            erfc(self.value),
+            -2.0 * (-self.value.powi(2)).exp() / PI.sqrt(),
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_dual {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::autodiff::{Accumulate, Gradient, Graph};
+
+    use std::f64::EPSILON as EPS;
+
+    #[test]
+    fn test_arithmetic() {
+        let x = Dual::variable(3.0);
+
+        let z = (x * x + 2.0 * x - 1.0) / x;
+
+        // z = x + 2 - 1/x,  dz/dx = 1 + 1/x^2
+        assert_approx_equal!(z.value, 3.0 + 2.0 - 1.0 / 3.0, EPS);
+        assert_approx_equal!(z.tangent, 1.0 + 1.0 / 9.0, EPS);
+    }
+
+    #[test]
+    fn test_matches_reverse_mode() {
+        let (f, df) = derivative(|x| (x * 2.0).cosh() / (x.tanh() * x.sinh()), 1.0);
+
+        let g = Graph::new();
+        let x = g.var(1.0);
+        let z = (x * 2.0).cosh() / (x.tanh() * x.sinh());
+        let grad = z.accumulate();
+
+        assert_approx_equal!(f, z.value, 1e-12);
+        assert_approx_equal!(df, grad.wrt(&x), 1e-12);
+    }
+
+    #[test]
+    fn test_primitive_functions() {
+        let x = 0.5;
+        let h = 1e-6;
+
+        let functions: Vec<(fn(Dual) -> Dual, fn(f64) -> f64)> = vec![
+            (Dual::exp, f64::exp),
+            (Dual::ln, f64::ln),
+            (Dual::sqrt, f64::sqrt),
+            (Dual::sin, f64::sin),
+            (Dual::cos, f64::cos),
+            (Dual::tan, f64::tan),
+            (Dual::asin, f64::asin),
+            (Dual::acos, f64::acos),
+            (Dual::atan, f64::atan),
+            (Dual::atanh, f64::atanh),
+            (Dual::log2, f64::log2),
+            (Dual::log10, f64::log10),
+            (Dual::cbrt, f64::cbrt),
+            (Dual::recip, f64::recip),
+        ];
+
+        for (dual_fn, f64_fn) in functions {
+            let (value, tangent) = derivative(dual_fn, x);
+            let fd = (f64_fn(x + h) - f64_fn(x - h)) / (2.0 * h);
+
+            assert_approx_equal!(value, f64_fn(x), EPS);
+            assert_approx_equal!(tangent, fd, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_powers() {
+        let x = Dual::variable(2.0);
+
+        assert_approx_equal!(x.powi(3).tangent, 12.0, EPS);
+        assert_approx_equal!(x.powf(0.5).tangent, 0.5 / 2.0_f64.sqrt(), EPS);
+        assert_approx_equal!(Powf::powf(&2.0, x).tangent, 4.0 * 2.0_f64.ln(), EPS);
+        assert_approx_equal!(x.powf(x).tangent, 4.0 * (1.0 + 2.0_f64.ln()), 1e-14);
+    }
+
+    #[test]
+    fn test_jacobian_vector_product() {
+        let f = |x: &[Dual]| vec![x[0] * x[1].sin(), x[0].exp() + x[1]];
+
+        let (value, jvp) = jacobian_vector_product(f, &[1.0, 2.0], &[0.0, 1.0]);
+
+        assert_approx_equal!(value[0], 2.0_f64.sin(), EPS);
+        assert_approx_equal!(jvp[0], 2.0_f64.cos(), EPS);
+        assert_approx_equal!(jvp[1], 1.0, EPS);
+    }
+}
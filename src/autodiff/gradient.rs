@@ -31,11 +31,22 @@ pub trait Gradient<IN, OUT> {
     fn wrt(&self, variables: IN) -> OUT;
 }
 
+/// Adjoint of `variable` in `adjoints`.
+/// Detached variables are constants, so their adjoint is zero.
+#[inline]
+pub(crate) fn adjoint(adjoints: &[f64], variable: &Variable) -> f64 {
+    if variable.is_detached() {
+        0.0
+    } else {
+        adjoints[variable.index]
+    }
+}
+
 /// `wrt` a single variable.
 impl<'v> Gradient<&Variable<'v>, f64> for Vec<f64> {
     #[inline]
     fn wrt(&self, variable: &Variable) -> f64 {
-        self[variable.index]
+        adjoint(self, variable)
     }
 }
 
@@ -43,7 +54,7 @@ impl<'v> Gradient<&Variable<'v>, f64> for Vec<f64> {
 impl<'v> Gradient<&Vec<Variable<'v>>, Vec<f64>> for Vec<f64> {
     #[inline]
     fn wrt(&self, variables: &Vec<Variable<'v>>) -> Vec<f64> {
-        variables.iter().map(|&var| self[var.index]).collect()
+        variables.iter().map(|var| adjoint(self, var)).collect()
     }
 }
 
@@ -51,7 +62,7 @@ impl<'v> Gradient<&Vec<Variable<'v>>, Vec<f64>> for Vec<f64> {
 impl<'v> Gradient<&[Variable<'v>], Vec<f64>> for Vec<f64> {
     #[inline]
     fn wrt(&self, variables: &[Variable<'v>]) -> Vec<f64> {
-        variables.iter().map(|&var| self[var.index]).collect()
+        variables.iter().map(|var| adjoint(self, var)).collect()
     }
 }
 
@@ -59,7 +70,7 @@ impl<'v> Gradient<&[Variable<'v>], Vec<f64>> for Vec<f64> {
 impl<'v, const N: usize> Gradient<[Variable<'v>; N], Vec<f64>> for Vec<f64> {
     #[inline]
     fn wrt(&self, variables: [Variable<'v>; N]) -> Vec<f64> {
-        variables.iter().map(|&var| self[var.index]).collect()
+        variables.iter().map(|var| adjoint(self, var)).collect()
     }
 }
 
@@ -67,7 +78,7 @@ impl<'v, const N: usize> Gradient<[Variable<'v>; N], Vec<f64>> for Vec<f64> {
 impl<'v, const N: usize> Gradient<&[Variable<'v>; N], Vec<f64>> for Vec<f64> {
     #[inline]
     fn wrt(&self, variables: &[Variable<'v>; N]) -> Vec<f64> {
-        variables.iter().map(|&var| self[var.index]).collect()
+        variables.iter().map(|var| adjoint(self, var)).collect()
     }
 }
 
@@ -17,21 +17,50 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{Arity, Variable, Vertex};
-use std::cell::RefCell;
+use crate::autodiff::{Arity, Operation, PushHook, Variable, Vertex, VertexArena};
+use std::cell::{Cell, RefCell};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // GRAPH STRUCTS AND IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-/// Struct to contain the graph (Wengert list), as a vector of `Vertex`s.
+/// Struct to contain the graph (Wengert list), as an arena of `Vertex`s.
 #[derive(Debug, Clone)]
 pub struct Graph {
-    /// Vector containing the vertices in the Wengert List.
-    pub vertices: RefCell<Vec<Vertex>>,
+    /// Arena containing the vertices in the Wengert List.
+    pub vertices: RefCell<VertexArena>,
+    /// Number of active `Pause` guards. While non-zero, no vertices are recorded.
+    pub paused: Cell<usize>,
+    /// Callback invoked on each push (see [`Graph::set_hook`]).
+    pub hook: RefCell<Option<PushHook>>,
 }
 // pub struct Graph(RefCell<Rc<[Vertex]>>);
 
+/// RAII guard that suspends recording on a `Graph` while it is alive.
+/// See [`Graph::pause`].
+#[derive(Debug)]
+pub struct Pause<'g> {
+    graph: &'g Graph,
+}
+
+impl Drop for Pause<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.graph.paused.set(self.graph.paused.get() - 1);
+    }
+}
+
+/// A position in the graph, used to rewind (truncate) the graph.
+///
+/// Checkpoints allow long computations (e.g. Monte Carlo simulations) to
+/// re-use the same memory: record a segment, run the reverse pass on it,
+/// then rewind to the checkpoint before recording the next segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Checkpoint {
+    /// Length of the graph when the checkpoint was taken.
+    pub position: usize,
+}
+
 impl Default for Graph {
     #[inline]
     fn default() -> Self {
@@ -41,12 +70,18 @@ impl Default for Graph {
 
 /// Implementation for the `Graph` struct.
 impl Graph {
+    /// Index of variables that have no vertex in the graph, i.e. variables
+    /// computed while recording was paused. These are treated as constants.
+    pub const DETACHED: usize = usize::MAX;
+
     /// Instantiate a new graph.
     #[must_use]
     #[inline]
     pub const fn new() -> Self {
         Self {
-            vertices: RefCell::new(Vec::new()),
+            vertices: RefCell::new(VertexArena::new()),
+            paused: Cell::new(0),
+            hook: RefCell::new(None),
             // vertices: RefCell::new(Rc::new([])),
         }
     }
@@ -56,18 +91,50 @@ impl Graph {
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
         Graph {
-            vertices: RefCell::new(Vec::with_capacity(capacity)),
+            vertices: RefCell::new(VertexArena::with_capacity(capacity)),
+            paused: Cell::new(0),
+            hook: RefCell::new(None),
             // vertices: RefCell::new(Rc::new([])),
         }
     }
 
+    /// Instantiate a new graph that allocates its vertices in fixed-size
+    /// blocks, so that vertices are never reallocated (or copied) as the
+    /// graph grows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    #[must_use]
+    #[inline]
+    pub fn with_block_size(block_size: usize) -> Self {
+        Graph {
+            vertices: RefCell::new(VertexArena::with_block_size(block_size)),
+            paused: Cell::new(0),
+            hook: RefCell::new(None),
+        }
+    }
+
+    /// Reserve space for at least `additional` more vertices.
+    #[inline]
+    pub fn reserve(&self, additional: usize) {
+        self.vertices.borrow_mut().reserve(additional);
+    }
+
+    /// Returns the number of vertices the graph can hold without allocating.
+    #[must_use]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vertices.borrow().capacity()
+    }
+
     /// Join two graphs together.
     #[must_use]
     #[inline]
     pub fn join(&self, other: &Self) -> Self {
         let graph = self.clone();
-        let other = other.vertices.borrow_mut().clone();
-        graph.vertices.borrow_mut().extend(other);
+        let other = other.vertices.borrow();
+        graph.vertices.borrow_mut().extend(other.iter().copied());
         graph
     }
 
@@ -107,6 +174,160 @@ impl Graph {
         self.vertices.borrow_mut().clear();
     }
 
+    /// Returns a checkpoint at the current end of the graph.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    /// let x = g.var(2.0);
+    ///
+    /// let checkpoint = g.checkpoint();
+    /// let _y = x.exp() * x.sin();
+    /// assert_eq!(g.len(), 4);
+    ///
+    /// g.rewind_to(checkpoint);
+    /// assert_eq!(g.len(), 1);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            position: self.len(),
+        }
+    }
+
+    /// Rewinds the graph to a checkpoint, discarding every vertex recorded
+    /// after the checkpoint was taken.
+    ///
+    /// Any `Variable` created after the checkpoint refers to a discarded
+    /// vertex and must not be used afterwards. Variables created before the
+    /// checkpoint are unaffected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint lies beyond the end of the graph
+    /// (e.g. the graph was cleared or rewound past it).
+    #[inline]
+    pub fn rewind_to(&self, checkpoint: Checkpoint) {
+        let mut vertices = self.vertices.borrow_mut();
+
+        assert!(
+            checkpoint.position <= vertices.len(),
+            "Checkpoint ({}) is beyond the end of the graph ({}).",
+            checkpoint.position,
+            vertices.len()
+        );
+
+        vertices.truncate(checkpoint.position);
+    }
+
+    /// Prunes (dead-code eliminates) the graph for the given output vertex.
+    ///
+    /// Only the vertices that `output` depends on are kept, along with every
+    /// input vertex. The kept vertices are compacted to the front of the
+    /// graph, and their parent indices remapped, so the reverse sweep only
+    /// visits vertices that contribute to the output.
+    ///
+    /// Returns the mapping from the old indices to the new indices
+    /// (`None` for removed vertices). Existing `Variable`s must be
+    /// re-indexed with this mapping (see [`Variable::prune`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output` is not a vertex of the graph.
+    pub fn prune(&self, output: usize) -> Vec<Option<usize>> {
+        let mut vertices = self.vertices.borrow_mut();
+        let n = vertices.len();
+
+        assert!(output < n, "Output ({output}) is not in the graph ({n}).");
+
+        // Mark the vertices the output depends on, and the inputs.
+        let mut keep = vec![false; n];
+        keep[output] = true;
+
+        for index in (0..n).rev() {
+            if vertices[index].operation == Operation::Input {
+                keep[index] = true;
+            } else if keep[index] {
+                for parent in vertices[index].parents {
+                    keep[parent] = true;
+                }
+            }
+        }
+
+        // Compact the kept vertices, remapping their parents.
+        let mut map = vec![None; n];
+        let mut len = 0;
+
+        for index in 0..n {
+            if !keep[index] {
+                continue;
+            }
+
+            map[index] = Some(len);
+
+            let mut vertex = vertices[index];
+            for parent in &mut vertex.parents {
+                *parent = map[*parent].unwrap_or(len);
+            }
+
+            vertices[len] = vertex;
+            len += 1;
+        }
+
+        vertices.truncate(len);
+
+        map
+    }
+
+    /// Suspends recording until the returned guard is dropped.
+    ///
+    /// While paused, operations on `Variable`s compute their values as usual,
+    /// but push no vertices to the graph. The resulting variables are
+    /// detached: they are treated as constants by any operations recorded
+    /// after the pause, so they neither grow the graph nor contribute to
+    /// derivatives. This is useful for helper computations that only drive
+    /// control flow (e.g. choosing a branch, or sorting paths).
+    ///
+    /// Pauses can be nested.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(2.0);
+    /// let len = g.len();
+    ///
+    /// let threshold = {
+    ///     let _pause = g.pause();
+    ///     (x * x).exp()
+    /// };
+    ///
+    /// assert_eq!(g.len(), len);
+    /// assert!(threshold.is_detached());
+    ///
+    /// // The detached variable is a constant.
+    /// let y = x * threshold;
+    ///
+    /// assert_eq!(y.accumulate().wrt(&x), 4.0_f64.exp());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn pause(&self) -> Pause<'_> {
+        self.paused.set(self.paused.get() + 1);
+
+        Pause { graph: self }
+    }
+
+    /// Returns true if recording is currently paused.
+    #[must_use]
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.paused.get() > 0
+    }
+
     /// Zeroes the adjoints in the graph.
     #[inline]
     pub fn zero(&self) {
@@ -121,12 +342,74 @@ impl Graph {
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
     /// Pushes a vertex to the graph.
+    ///
+    /// The second order partials are set to zero, which is correct for
+    /// operations that are linear in their arguments (e.g. `x + y`).
+    /// Non-linear operations should use [`Graph::push_second_order`]
+    /// so that Hessians can be computed.
     #[inline]
     pub fn push(&self, arity: Arity, parents: &[usize], partials: &[f64]) -> usize {
+        self.push_second_order(arity, parents, partials, [0.0; 3])
+    }
+
+    /// Pushes a vertex, including its second order partials, to the graph.
+    ///
+    /// The second order partials are ordered as
+    /// `[d^2/dx^2, d^2/dxdy, d^2/dy^2]`, where `x` and `y` are the first and
+    /// second parents respectively.
+    #[inline]
+    pub fn push_second_order(
+        &self,
+        arity: Arity,
+        parents: &[usize],
+        partials: &[f64],
+        second_partials: [f64; 3],
+    ) -> usize {
+        self.record(arity, parents, partials, second_partials, None)
+    }
+
+    /// Pushes a vertex produced by a known `Operation` to the graph.
+    ///
+    /// The arity is inferred from the number of parents. Recording the
+    /// operation allows the graph to be replayed with new input values.
+    #[inline]
+    pub fn push_operation(
+        &self,
+        operation: Operation,
+        parents: &[usize],
+        partials: &[f64],
+        second_partials: [f64; 3],
+    ) -> usize {
+        let arity = match parents.len() {
+            0 => Arity::Nullary,
+            1 => Arity::Unary,
+            _ => Arity::Binary,
+        };
+
+        self.record(arity, parents, partials, second_partials, Some(operation))
+    }
+
+    /// Constructs a vertex, pushes it to the graph, and invokes the hook.
+    #[inline]
+    fn record(
+        &self,
+        arity: Arity,
+        parents: &[usize],
+        partials: &[f64],
+        second_partials: [f64; 3],
+        operation: Option<Operation>,
+    ) -> usize {
+        // Nothing is recorded while paused, and operations on
+        // detached variables only are themselves constant.
+        if self.is_paused() || (!parents.is_empty() && parents.iter().all(|&p| p == Self::DETACHED))
+        {
+            return Self::DETACHED;
+        }
+
         let mut vertices = self.vertices.borrow_mut();
         let len = vertices.len();
 
-        let vertex = match arity {
+        let mut vertex = match arity {
             // Nullary operator pushback.
             //
             // The vertex pushed to the graph is the result of a **nullary** operation.
@@ -142,6 +425,8 @@ impl Graph {
                 Vertex {
                     partials: [0.0, 0.0],
                     parents: [len, len],
+                    second_partials: [0.0; 3],
+                    operation: Operation::Input,
                 }
             }
             // Unary operator pushback.
@@ -159,6 +444,8 @@ impl Graph {
                 Vertex {
                     partials: [partials[0], 0.0],
                     parents: [parents[0], len],
+                    second_partials: [second_partials[0], 0.0, 0.0],
+                    operation: Operation::Custom,
                 }
             }
             // Binary operator pushback.
@@ -176,16 +463,92 @@ impl Graph {
                 Vertex {
                     partials: [partials[0], partials[1]],
                     parents: [parents[0], parents[1]],
+                    second_partials,
+                    operation: Operation::Custom,
                 }
             }
         };
 
+        // Detached parents are constants: replace them with a
+        // self-reference, and zero the (second order) partials wrt them.
+        for k in 0..2 {
+            if vertex.parents[k] == Self::DETACHED {
+                vertex.parents[k] = len;
+                vertex.partials[k] = 0.0;
+                vertex.second_partials[k] = 0.0;
+                vertex.second_partials[k + 1] = 0.0;
+            }
+        }
+
+        // Vertices with detached parents cannot be replayed,
+        // so they are left as custom operations.
+        if let Some(operation) = operation {
+            if !parents.contains(&Self::DETACHED) {
+                vertex.operation = operation;
+            }
+        }
+
         vertices.push(vertex);
+        drop(vertices);
+
+        if let Some(hook) = self.hook.borrow().as_ref() {
+            (hook.0)(len, &vertex);
+        }
 
         len
     }
 }
 
+impl<'v> Variable<'v> {
+    /// Prunes the graph for this variable (see [`Graph::prune`]), and returns
+    /// this variable re-indexed into the pruned graph.
+    ///
+    /// The given `variables` are re-indexed in place. Inputs are always kept,
+    /// while intermediate variables that were removed (i.e. this variable does
+    /// not depend on them) become detached: they remain valid, and are treated
+    /// as constants, so their gradient is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this variable is detached.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(1.0);
+    /// let y = g.var(2.0);
+    ///
+    /// let _unused = (x * y).exp().sin();
+    /// let z = x * y;
+    ///
+    /// let mut inputs = [x, y];
+    /// let z = z.prune(&mut inputs);
+    ///
+    /// assert_eq!(g.len(), 3);
+    /// assert_eq!(z.accumulate().wrt(&inputs), vec![2.0, 1.0]);
+    /// ```
+    #[must_use]
+    pub fn prune(&self, variables: &mut [Variable<'v>]) -> Self {
+        let map = self.graph.prune(self.index);
+
+        for variable in variables.iter_mut() {
+            variable.index = map
+                .get(variable.index)
+                .copied()
+                .flatten()
+                .unwrap_or(Graph::DETACHED);
+        }
+
+        Variable {
+            graph: self.graph,
+            index: map[self.index].unwrap_or(Graph::DETACHED),
+            value: self.value,
+        }
+    }
+}
+
 // /// Nullary operator pushback.
 // ///
 // /// The vertex pushed to the graph is the result of a **nullary** operation.
@@ -252,3 +615,217 @@ impl Graph {
 //     len
 // }
 // }
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_graph {
+    use crate::{assert_approx_equal, autodiff::*};
+
+    #[test]
+    fn test_checkpoint_rewind() {
+        let g = Graph::new();
+
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+        let checkpoint = g.checkpoint();
+
+        let _ = (x * y).sin() + x.exp();
+        assert!(g.len() > checkpoint.position);
+
+        g.rewind_to(checkpoint);
+        assert_eq!(g.len(), 2);
+
+        // Re-record after rewinding: the inputs are still valid.
+        let z = x * y;
+        let grad = z.accumulate();
+
+        assert_approx_equal!(grad.wrt(&x), 2.0, f64::EPSILON);
+        assert_approx_equal!(grad.wrt(&y), 1.0, f64::EPSILON);
+    }
+
+    #[test]
+    fn test_checkpoint_bounded_memory() {
+        let g = Graph::new();
+
+        let s = g.var(100.0);
+        let v = g.var(0.2);
+        let checkpoint = g.checkpoint();
+
+        let mut delta = 0.0;
+        let mut vega = 0.0;
+
+        for z in [-1.0, -0.5, 0.0, 0.5, 1.0] {
+            let payoff = Max::max(&(s * (v * z).exp() - 100.0), 0.0);
+            let grad = payoff.accumulate();
+
+            delta += grad.wrt(&s);
+            vega += grad.wrt(&v);
+
+            g.rewind_to(checkpoint);
+            assert_eq!(g.len(), checkpoint.position);
+        }
+
+        let expected_delta = (0.2_f64 * 0.5).exp() + (0.2_f64).exp();
+        let expected_vega = 100.0 * (0.5 * (0.2_f64 * 0.5).exp() + (0.2_f64).exp());
+
+        assert_approx_equal!(delta, expected_delta, 1e-12);
+        assert_approx_equal!(vega, expected_vega, 1e-10);
+    }
+
+    #[test]
+    fn test_prune() {
+        let g = Graph::new();
+
+        let x = g.var(0.5);
+        let y = g.var(1.5);
+
+        // Interleave dead computations with the live ones.
+        let a = x.sin() * y;
+        let dead = (a.exp() + x).ln();
+        let b = a + y.sqrt();
+        let _after = b * b;
+        let z = g.var(3.0);
+
+        let expected = b.accumulate().wrt(&[x, y]);
+
+        let mut variables = [x, y, z, a, dead];
+        let b = b.prune(&mut variables);
+        let [x, y, z, a, dead] = variables;
+
+        // Inputs, x.sin(), a, y.sqrt() and b.
+        assert_eq!(g.len(), 7);
+        assert_eq!((x.index, y.index, z.index), (0, 1, 6));
+        assert!(!a.is_detached() && dead.is_detached());
+
+        let grad = b.accumulate();
+
+        assert_eq!(grad.wrt(&[x, y]), expected);
+        assert_approx_equal!(grad.wrt(&z), 0.0, f64::EPSILON);
+
+        // The pruned graph can still be replayed.
+        let values = g.replay(&[0.5, 1.5, 3.0]).unwrap();
+        assert_approx_equal!(values[b.index], b.value, f64::EPSILON);
+    }
+
+    #[test]
+    fn test_pause() {
+        let g = Graph::new();
+
+        let x = g.var(3.0);
+        let y = g.var(4.0);
+        let len = g.len();
+
+        let (a, b) = {
+            let _outer = g.pause();
+            let a = (x * y).sin();
+            let b = {
+                let _inner = g.pause();
+                x.exp() + y
+            };
+            assert!(g.is_paused());
+            (a, b)
+        };
+
+        assert!(!g.is_paused());
+        assert_eq!(g.len(), len);
+        assert!(a.is_detached() && b.is_detached());
+        assert_approx_equal!(a.value, 12.0_f64.sin(), f64::EPSILON);
+
+        // Operations on detached variables only stay detached.
+        assert!((a * b + 1.0).is_detached());
+        assert_eq!(g.len(), len);
+
+        // Detached variables are constants wrt the recorded inputs.
+        let z = x * a + (y / b).exp();
+        let grad = z.accumulate();
+
+        assert_approx_equal!(grad.wrt(&x), a.value, f64::EPSILON);
+        assert_approx_equal!(grad.wrt(&y), (y.value / b.value).exp() / b.value, 1e-15);
+        assert_eq!(a.accumulate(), vec![0.0; g.len()]);
+
+        // Hessians ignore the detached variables too.
+        let h = (x * a).powi(2).hessian(&[x, y]);
+        assert_approx_equal!(h[0][0], 2.0 * a.value * a.value, 1e-14);
+        assert_approx_equal!(h[1][1], 0.0, f64::EPSILON);
+    }
+
+    #[test]
+    fn test_prune_wrt_removed_variable() {
+        let g = Graph::new();
+
+        let x = g.var(0.5);
+        let y = g.var(1.5);
+
+        let unused = (x * y).exp();
+        let z = x * y;
+
+        let mut variables = [x, y, unused];
+        let z = z.prune(&mut variables);
+        let [x, y, unused] = variables;
+
+        assert!(!x.is_detached() && !y.is_detached());
+        assert!(unused.is_detached());
+
+        let grad = z.accumulate();
+        assert_approx_equal!(grad.wrt(&x), y.value, f64::EPSILON);
+        assert_approx_equal!(grad.wrt(&unused), 0.0, f64::EPSILON);
+        assert_eq!(grad.wrt(&variables), vec![y.value, x.value, 0.0]);
+
+        // Removed variables can still be used, as constants.
+        let w = z * unused;
+        assert_approx_equal!(w.accumulate().wrt(&x), y.value * unused.value, 1e-15);
+        assert_approx_equal!(w.accumulate().wrt(&unused), 0.0, f64::EPSILON);
+    }
+
+    #[test]
+    fn test_paused_wrt_and_from() {
+        let g = Graph::new();
+
+        let x = g.var(2.0);
+        let c = {
+            let _pause = g.pause();
+            x * 3.0
+        };
+
+        // Differentiating wrt a detached variable.
+        let z = x * c;
+        let grad = z.accumulate();
+
+        assert_approx_equal!(grad.wrt(&x), c.value, f64::EPSILON);
+        assert_approx_equal!(grad.wrt(&c), 0.0, f64::EPSILON);
+        assert_eq!(grad.wrt(&[x, c]), vec![c.value, 0.0]);
+
+        let mut adjoints = Adjoints::new();
+        adjoints.accumulate(&z);
+        assert_eq!(adjoints.wrt(&[x, c]), vec![c.value, 0.0]);
+
+        assert_eq!(
+            z.hessian_vector_product(&[x, c], &[1.0, 1.0]),
+            vec![0.0, 0.0]
+        );
+
+        // Differentiating a detached output.
+        assert_eq!(c.accumulate().wrt(&[x, c]), vec![0.0, 0.0]);
+        assert_eq!(c.hessian(&[x, c]), vec![vec![0.0; 2]; 2]);
+
+        let jacobian = [z, c].jacobian(&[x, c]);
+        assert_approx_equal!(jacobian.get(0, 0), c.value, f64::EPSILON);
+        assert_approx_equal!(jacobian.get(1, 0), 0.0, f64::EPSILON);
+
+        assert!(!g.to_dot(Some(&c)).contains("->"));
+    }
+
+    #[test]
+    #[should_panic(expected = "beyond the end of the graph")]
+    fn test_rewind_past_end() {
+        let g = Graph::new();
+        let _x = g.var(1.0);
+        let checkpoint = g.checkpoint();
+
+        g.clear();
+        g.rewind_to(checkpoint);
+    }
+}
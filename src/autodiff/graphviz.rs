@@ -15,7 +15,8 @@
 //! language. At the moment, I'm simply trying to make a function that outputs
 //! `dot` code for a given graph.
 
-use crate::autodiff::{Graph, Variable};
+use crate::autodiff::{Graph, Operation, Variable};
+use std::fmt::Write;
 
 // impl std::fmt::Display for Graph {
 //     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -78,6 +79,108 @@ pub fn graphviz(graph: &Graph, vars: &[Variable]) -> String {
     dot
 }
 
+impl Graph {
+    /// Graphviz dot string of the graph, with the operation of each vertex
+    /// and the partial derivative along each edge.
+    ///
+    /// If `output` is given, only the vertices that `output` depends on
+    /// are included.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(1.0);
+    /// let y = g.var(2.0);
+    /// let z = x * y;
+    /// let _w = y.exp();
+    ///
+    /// let dot = g.to_dot(Some(&z));
+    ///
+    /// assert!(dot.contains("2 [label=\"#2: mul\"]"));
+    /// assert!(!dot.contains("exp"));
+    /// ```
+    #[must_use]
+    pub fn to_dot(&self, output: Option<&Variable>) -> String {
+        let vertices = self.vertices.borrow();
+
+        // Vertices to include in the output.
+        let included = match output {
+            None => vec![true; vertices.len()],
+            // Detached outputs do not depend on any vertex.
+            Some(output) if output.is_detached() => vec![false; vertices.len()],
+            Some(output) => {
+                let mut reached = vec![false; vertices.len()];
+                reached[output.index] = true;
+
+                for index in (0..=output.index).rev() {
+                    if reached[index] {
+                        for &parent in &vertices[index].parents {
+                            reached[parent] = true;
+                        }
+                    }
+                }
+
+                reached
+            }
+        };
+
+        let mut dot = String::from(
+            "digraph Graph {\n\tbgcolor=\"transparent\";\n\trankdir=\"LR\";\n\tnode [shape=box3d];\n",
+        );
+
+        // Define the nodes.
+        for (index, vertex) in vertices.iter().enumerate() {
+            if !included[index] {
+                continue;
+            }
+
+            let operation = vertex.operation;
+
+            let label = match operation.constant() {
+                Some(constant) => format!("#{}: {} ({})", index, operation.name(), constant),
+                None => format!("#{}: {}", index, operation.name()),
+            };
+
+            let color = if operation == Operation::Input {
+                ", color=\"red\""
+            } else {
+                ""
+            };
+
+            let _ = writeln!(dot, "\t{index} [label=\"{label}\"{color}];");
+        }
+
+        // Define the edges.
+        for (index, vertex) in vertices.iter().enumerate() {
+            if !included[index] {
+                continue;
+            }
+
+            for (i, &parent) in vertex.parents.iter().enumerate() {
+                // Skip self-references (unused parents) and duplicate
+                // edges for operations with a constant operand.
+                if parent == index || (i == 1 && parent == vertex.parents[0]) {
+                    continue;
+                }
+
+                let partial = if parent == vertex.parents[1] && i == 0 {
+                    vertex.partials[0] + vertex.partials[1]
+                } else {
+                    vertex.partials[i]
+                };
+
+                let _ = writeln!(dot, "\t{parent} -> {index} [label=\"{partial:.4}\"];");
+            }
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
 #[cfg(test)]
 mod test_graphviz {
     use crate::autodiff::{Accumulate, Gradient, Powf};
@@ -131,4 +234,31 @@ mod test_graphviz {
 
         print!("{}", graphviz(&graph, &[x, y]));
     }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = Graph::new();
+        let x = graph.var(2.0);
+        let y = graph.var(3.0);
+
+        let z = x * y + 1.0;
+        let _unused = y.sin();
+
+        let dot = graph.to_dot(None);
+
+        assert!(dot.contains("0 [label=\"#0: input\", color=\"red\"];"));
+        assert!(dot.contains("2 [label=\"#2: mul\"];"));
+        assert!(dot.contains("3 [label=\"#3: add (1)\"];"));
+        assert!(dot.contains("4 [label=\"#4: sin\"];"));
+        assert!(dot.contains("0 -> 2 [label=\"3.0000\"];"));
+        assert!(dot.contains("1 -> 2 [label=\"2.0000\"];"));
+        assert!(dot.contains("2 -> 3 [label=\"1.0000\"];"));
+        assert_eq!(dot.matches("->").count(), 4);
+
+        // Only the vertices that `z` depends on.
+        let dot = graph.to_dot(Some(&z));
+
+        assert!(!dot.contains("sin"));
+        assert_eq!(dot.matches("->").count(), 3);
+    }
 }
@@ -7,11 +7,255 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! This module contains the `Hessian` trait.
+//!
+//! Hessians are computed via forward-over-reverse (tangent-over-adjoint)
+//! mode, using the second order partials recorded in each `Vertex`:
+//!
+//! 1. A forward sweep propagates the tangents of the vertices in a chosen
+//!    direction $v$.
+//! 2. A reverse sweep propagates the adjoints, along with their tangents.
+//!
+//! The tangents of the adjoints of the inputs are then the Hessian-vector
+//! product $H v$. The full Hessian is obtained with one such pair of sweeps
+//! per input variable.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::Variable;
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // HESSIAN TRAIT AND IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-/// Return the derivative/s *with-respect-to* the chosen variables.
+/// Return the second order derivatives *with-respect-to* the chosen variables.
 pub trait Hessian<IN, OUT> {
-    todo!();
+    /// Returns the Hessian matrix *with-respect-to* the chosen variables.
+    fn hessian(&self, variables: IN) -> OUT;
+}
+
+impl<'v> Variable<'v> {
+    /// Hessian-vector product $H v$ of the function represented by this
+    /// variable, *with-respect-to* the chosen variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `variables` and `direction` have different lengths.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(2.0);
+    /// let y = g.var(3.0);
+    ///
+    /// // f = x^2 * y,  H = [[2y, 2x], [2x, 0]]
+    /// let f = x * x * y;
+    ///
+    /// assert_eq!(f.hessian_vector_product(&[x, y], &[1.0, 0.0]), vec![6.0, 4.0]);
+    /// ```
+    #[must_use]
+    pub fn hessian_vector_product(
+        &self,
+        variables: &[Variable<'v>],
+        direction: &[f64],
+    ) -> Vec<f64> {
+        assert_eq!(variables.len(), direction.len());
+
+        // Detached variables are constants.
+        if self.is_detached() {
+            return vec![0.0; variables.len()];
+        }
+
+        let vertices = self.graph.vertices.borrow();
+        let n = self.index + 1;
+
+        // Forward sweep: tangents of each vertex in the chosen direction.
+        // Detached variables, and those recorded after the output, do not
+        // contribute to it.
+        let mut tangents = vec![0.0; n];
+
+        for (variable, &d) in variables.iter().zip(direction) {
+            if let Some(tangent) = tangents.get_mut(variable.index) {
+                *tangent += d;
+            }
+        }
+
+        for (index, vertex) in vertices.iter().enumerate().take(n) {
+            tangents[index] += vertex.partials[0] * tangents[vertex.parents[0]]
+                + vertex.partials[1] * tangents[vertex.parents[1]];
+        }
+
+        // Reverse sweep: adjoints and the tangents of the adjoints.
+        let mut adjoints = vec![0.0; n];
+        let mut adjoint_tangents = vec![0.0; n];
+        adjoints[self.index] = 1.0; // SEED
+
+        for (index, vertex) in vertices.iter().enumerate().take(n).rev() {
+            let [p0, p1] = vertex.parents;
+            let [d0, d1] = vertex.partials;
+            let [h00, h01, h11] = vertex.second_partials;
+
+            let adjoint = adjoints[index];
+            let adjoint_tangent = adjoint_tangents[index];
+
+            // Tangents of the partials (i.e. the product rule).
+            let dd0 = h00 * tangents[p0] + h01 * tangents[p1];
+            let dd1 = h01 * tangents[p0] + h11 * tangents[p1];
+
+            adjoints[p0] += d0 * adjoint;
+            adjoints[p1] += d1 * adjoint;
+
+            adjoint_tangents[p0] += d0 * adjoint_tangent + dd0 * adjoint;
+            adjoint_tangents[p1] += d1 * adjoint_tangent + dd1 * adjoint;
+        }
+
+        variables
+            .iter()
+            .map(|variable| adjoint_tangents.get(variable.index).copied().unwrap_or(0.0))
+            .collect()
+    }
+}
+
+/// `hessian` wrt a borrowed slice of variables.
+impl<'v> Hessian<&[Variable<'v>], Vec<Vec<f64>>> for Variable<'v> {
+    #[inline]
+    fn hessian(&self, variables: &[Variable<'v>]) -> Vec<Vec<f64>> {
+        let mut direction = vec![0.0; variables.len()];
+
+        (0..variables.len())
+            .map(|i| {
+                direction[i] = 1.0;
+                let column = self.hessian_vector_product(variables, &direction);
+                direction[i] = 0.0;
+                column
+            })
+            .collect()
+    }
+}
+
+/// `hessian` wrt a borrowed array of variables.
+impl<'v, const N: usize> Hessian<&[Variable<'v>; N], Vec<Vec<f64>>> for Variable<'v> {
+    #[inline]
+    fn hessian(&self, variables: &[Variable<'v>; N]) -> Vec<Vec<f64>> {
+        self.hessian(&variables[..])
+    }
+}
+
+/// `hessian` wrt a borrowed vector of variables.
+impl<'v> Hessian<&Vec<Variable<'v>>, Vec<Vec<f64>>> for Variable<'v> {
+    #[inline]
+    fn hessian(&self, variables: &Vec<Variable<'v>>) -> Vec<Vec<f64>> {
+        self.hessian(&variables[..])
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod test_hessian {
+    use crate::{assert_approx_equal, autodiff::*};
+
+    use std::f64::EPSILON as EPS;
+
+    #[test]
+    fn test_polynomial() {
+        let g = Graph::new();
+
+        let x = g.var(2.0);
+        let y = g.var(3.0);
+
+        // f = x^3 y + x y^2
+        let f = x.powi(3) * y + x * y * y;
+
+        let hessian = f.hessian(&[x, y]);
+
+        // f_xx = 6xy, f_xy = 3x^2 + 2y, f_yy = 2x
+        assert_approx_equal!(hessian[0][0], 36.0, EPS);
+        assert_approx_equal!(hessian[0][1], 18.0, EPS);
+        assert_approx_equal!(hessian[1][0], 18.0, EPS);
+        assert_approx_equal!(hessian[1][1], 4.0, EPS);
+    }
+
+    #[test]
+    fn test_transcendental() {
+        let g = Graph::new();
+
+        let x = g.var(0.5);
+        let y = g.var(1.5);
+
+        // f = sin(x) exp(y) + ln(x y)
+        let f = x.sin() * y.exp() + (x * y).ln();
+
+        let hessian = f.hessian(&[x, y]);
+
+        let (a, b) = (0.5_f64, 1.5_f64);
+
+        assert_approx_equal!(hessian[0][0], -a.sin() * b.exp() - 1.0 / (a * a), 1e-12);
+        assert_approx_equal!(hessian[0][1], a.cos() * b.exp(), 1e-12);
+        assert_approx_equal!(hessian[1][1], a.sin() * b.exp() - 1.0 / (b * b), 1e-12);
+    }
+
+    #[test]
+    fn test_against_finite_differences() {
+        fn f<'v>(x: Variable<'v>, y: Variable<'v>) -> Variable<'v> {
+            (x.powf(y) + x.log(y) + Powf::powf(&2.0, x) / y).sqrt()
+                + (x / y).atan()
+                + y.erf()
+                + x.atan2(y) * x.hypot(y)
+                + (y / 2.0).inv_norm_cdf()
+        }
+
+        fn gradient(a: f64, b: f64) -> Vec<f64> {
+            let g = Graph::new();
+            let x = g.var(a);
+            let y = g.var(b);
+            f(x, y).accumulate().wrt(&[x, y])
+        }
+
+        let (a, b) = (1.3, 0.7);
+        let h = 1e-6;
+
+        let g = Graph::new();
+        let x = g.var(a);
+        let y = g.var(b);
+        let hessian = f(x, y).hessian(&[x, y]);
+
+        let dx: Vec<f64> = gradient(a + h, b)
+            .iter()
+            .zip(gradient(a - h, b))
+            .map(|(u, d)| (u - d) / (2.0 * h))
+            .collect();
+        let dy: Vec<f64> = gradient(a, b + h)
+            .iter()
+            .zip(gradient(a, b - h))
+            .map(|(u, d)| (u - d) / (2.0 * h))
+            .collect();
+
+        assert_approx_equal!(hessian[0][0], dx[0], 1e-6);
+        assert_approx_equal!(hessian[0][1], dx[1], 1e-6);
+        assert_approx_equal!(hessian[1][0], dy[0], 1e-6);
+        assert_approx_equal!(hessian[1][1], dy[1], 1e-6);
+    }
+
+    #[test]
+    fn test_linear_function_has_zero_hessian() {
+        let g = Graph::new();
+
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+
+        let f = 3.0 * x - y / 2.0 + 1.0;
+
+        for row in f.hessian(&[x, y]) {
+            for h in row {
+                assert_approx_equal!(h, 0.0, EPS);
+            }
+        }
+    }
 }
@@ -0,0 +1,1003 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Exact second derivatives via hyper-dual numbers.
+//!
+//! A hyper-dual number $a + b_1 \epsilon_1 + b_2 \epsilon_2 + c\,\epsilon_1 \epsilon_2$
+//! (with $\epsilon_1^2 = \epsilon_2^2 = 0$) extends the [`Dual`](crate::autodiff::Dual)
+//! number with a second infinitesimal. Evaluating a function on
+//! $x + \dot{x}_1 \epsilon_1 + \dot{x}_2 \epsilon_2$ yields
+//!
+//! $$
+//! f(x) + f'(x) \dot{x}_1 \epsilon_1 + f'(x) \dot{x}_2 \epsilon_2
+//!     + f''(x) \dot{x}_1 \dot{x}_2 \epsilon_1 \epsilon_2
+//! $$
+//!
+//! so one forward pass gives a second derivative (e.g. Gamma), or a mixed
+//! partial derivative (e.g. Vanna) when the two directions differ. Unlike
+//! finite differences there is no truncation or cancellation error, and
+//! unlike nested tapes nothing is recorded.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Log, Max, Min, Powf, Powi};
+use nalgebra::DMatrix;
+use std::f64::consts::{LN_10, LN_2, PI};
+use std::fmt::Display;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCT AND IMPLEMENTATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hyper-dual number for exact second order forward mode differentiation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HyperDual {
+    /// The value (real part).
+    pub value: f64,
+    /// The $\epsilon_1$ part, i.e. the derivative in the first direction.
+    pub eps1: f64,
+    /// The $\epsilon_2$ part, i.e. the derivative in the second direction.
+    pub eps2: f64,
+    /// The $\epsilon_1 \epsilon_2$ part, i.e. the second derivative.
+    pub eps12: f64,
+}
+
+impl HyperDual {
+    /// Instantiate a new hyper-dual number.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::similar_names)]
+    pub const fn new(value: f64, eps1: f64, eps2: f64, eps12: f64) -> Self {
+        Self {
+            value,
+            eps1,
+            eps2,
+            eps12,
+        }
+    }
+
+    /// Instantiate a variable in both directions,
+    /// so that `eps12` holds $f''(x)$.
+    #[must_use]
+    #[inline]
+    pub const fn variable(value: f64) -> Self {
+        Self::new(value, 1.0, 1.0, 0.0)
+    }
+
+    /// Instantiate a constant (all infinitesimal parts zero).
+    #[must_use]
+    #[inline]
+    pub const fn constant(value: f64) -> Self {
+        Self::new(value, 0.0, 0.0, 0.0)
+    }
+
+    /// Function to return the value.
+    #[must_use]
+    #[inline]
+    pub const fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Apply the chain rule for a unary function with value `f`,
+    /// first derivative `df` and second derivative `d2f` at `self.value`.
+    #[must_use]
+    #[inline]
+    pub fn chain(self, f: f64, df: f64, d2f: f64) -> Self {
+        Self::new(
+            f,
+            df * self.eps1,
+            df * self.eps2,
+            df * self.eps12 + d2f * self.eps1 * self.eps2,
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// HELPER FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Second derivative of a scalar function $f: \mathbb{R} \rightarrow \mathbb{R}$.
+///
+/// Returns `(f(x), f'(x), f''(x))`.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+///
+/// let (f, df, d2f) = second_derivative(|x| x.powi(3), 2.0);
+///
+/// assert_eq!((f, df, d2f), (8.0, 12.0, 12.0));
+/// ```
+pub fn second_derivative<F>(f: F, x: f64) -> (f64, f64, f64)
+where
+    F: Fn(HyperDual) -> HyperDual,
+{
+    let y = f(HyperDual::variable(x));
+
+    (y.value, y.eps1, y.eps12)
+}
+
+/// Value, gradient and Hessian of $f: \mathbb{R}^n \rightarrow \mathbb{R}$.
+///
+/// Each entry of the (symmetric) Hessian requires one evaluation of $f$,
+/// so $n (n + 1) / 2$ evaluations are performed in total.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+///
+/// // f(x, y) = x^2 * y
+/// let (value, gradient, hessian) = hyper_dual_hessian(|x| x[0] * x[0] * x[1], &[2.0, 3.0]);
+///
+/// assert_eq!(value, 12.0);
+/// assert_eq!(gradient, vec![12.0, 4.0]);
+/// assert_eq!(hessian[(0, 0)], 6.0);
+/// assert_eq!(hessian[(0, 1)], 4.0);
+/// assert_eq!(hessian[(1, 1)], 0.0);
+/// ```
+pub fn hyper_dual_hessian<F>(f: F, x: &[f64]) -> (f64, Vec<f64>, DMatrix<f64>)
+where
+    F: Fn(&[HyperDual]) -> HyperDual,
+{
+    let n = x.len();
+
+    let mut value = f64::NAN;
+    let mut gradient = vec![0.0; n];
+    let mut hessian = DMatrix::zeros(n, n);
+
+    let mut inputs: Vec<HyperDual> = x.iter().map(|&a| HyperDual::constant(a)).collect();
+
+    if n == 0 {
+        return (f(&inputs).value, gradient, hessian);
+    }
+
+    for i in 0..n {
+        for j in i..n {
+            inputs[i].eps1 = 1.0;
+            inputs[j].eps2 = 1.0;
+
+            let y = f(&inputs);
+
+            inputs[i].eps1 = 0.0;
+            inputs[j].eps2 = 0.0;
+
+            if i == j {
+                value = y.value;
+                gradient[i] = y.eps1;
+            }
+
+            hessian[(i, j)] = y.eps12;
+            hessian[(j, i)] = y.eps12;
+        }
+    }
+
+    (value, gradient, hessian)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OVERLOADING: STANDARD MATH OPERATORS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl From<f64> for HyperDual {
+    #[inline]
+    fn from(value: f64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl Neg for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Self::new(-self.value, -self.eps1, -self.eps2, -self.eps12)
+    }
+}
+
+/// HyperDual + HyperDual
+impl Add<HyperDual> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: HyperDual) -> Self::Output {
+        Self::new(
+            self.value + other.value,
+            self.eps1 + other.eps1,
+            self.eps2 + other.eps2,
+            self.eps12 + other.eps12,
+        )
+    }
+}
+
+/// HyperDual + f64
+impl Add<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: f64) -> Self::Output {
+        Self::new(self.value + other, self.eps1, self.eps2, self.eps12)
+    }
+}
+
+/// f64 + HyperDual
+impl Add<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn add(self, other: HyperDual) -> Self::Output {
+        other + self
+    }
+}
+
+/// HyperDual - HyperDual
+impl Sub<HyperDual> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: HyperDual) -> Self::Output {
+        self + (-other)
+    }
+}
+
+/// HyperDual - f64
+impl Sub<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: f64) -> Self::Output {
+        self + (-other)
+    }
+}
+
+/// f64 - HyperDual
+impl Sub<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn sub(self, other: HyperDual) -> Self::Output {
+        -other + self
+    }
+}
+
+/// HyperDual * HyperDual
+impl Mul<HyperDual> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: HyperDual) -> Self::Output {
+        Self::new(
+            self.value * other.value,
+            self.eps1 * other.value + self.value * other.eps1,
+            self.eps2 * other.value + self.value * other.eps2,
+            self.eps12 * other.value
+                + self.eps1 * other.eps2
+                + self.eps2 * other.eps1
+                + self.value * other.eps12,
+        )
+    }
+}
+
+/// HyperDual * f64
+impl Mul<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: f64) -> Self::Output {
+        Self::new(
+            self.value * other,
+            self.eps1 * other,
+            self.eps2 * other,
+            self.eps12 * other,
+        )
+    }
+}
+
+/// f64 * HyperDual
+impl Mul<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn mul(self, other: HyperDual) -> Self::Output {
+        other * self
+    }
+}
+
+/// HyperDual / HyperDual
+impl Div<HyperDual> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: HyperDual) -> Self::Output {
+        self * other.recip()
+    }
+}
+
+/// HyperDual / f64
+impl Div<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, other: f64) -> Self::Output {
+        Self::new(
+            self.value / other,
+            self.eps1 / other,
+            self.eps2 / other,
+            self.eps12 / other,
+        )
+    }
+}
+
+/// f64 / HyperDual
+impl Div<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: HyperDual) -> Self::Output {
+        other.recip() * self
+    }
+}
+
+impl AddAssign<HyperDual> for HyperDual {
+    #[inline]
+    fn add_assign(&mut self, other: HyperDual) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<f64> for HyperDual {
+    #[inline]
+    fn add_assign(&mut self, other: f64) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign<HyperDual> for HyperDual {
+    #[inline]
+    fn sub_assign(&mut self, other: HyperDual) {
+        *self = *self - other;
+    }
+}
+
+impl SubAssign<f64> for HyperDual {
+    #[inline]
+    fn sub_assign(&mut self, other: f64) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<HyperDual> for HyperDual {
+    #[inline]
+    fn mul_assign(&mut self, other: HyperDual) {
+        *self = *self * other;
+    }
+}
+
+impl MulAssign<f64> for HyperDual {
+    #[inline]
+    fn mul_assign(&mut self, other: f64) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign<HyperDual> for HyperDual {
+    #[inline]
+    fn div_assign(&mut self, other: HyperDual) {
+        *self = *self / other;
+    }
+}
+
+impl DivAssign<f64> for HyperDual {
+    #[inline]
+    fn div_assign(&mut self, other: f64) {
+        *self = *self / other;
+    }
+}
+
+impl Sum<HyperDual> for HyperDual {
+    #[inline]
+    fn sum<I: Iterator<Item = HyperDual>>(iter: I) -> Self {
+        iter.fold(HyperDual::constant(0.0), |x, y| x + y)
+    }
+}
+
+impl Product<HyperDual> for HyperDual {
+    #[inline]
+    fn product<I: Iterator<Item = HyperDual>>(iter: I) -> Self {
+        iter.fold(HyperDual::constant(1.0), |x, y| x * y)
+    }
+}
+
+impl PartialEq<f64> for HyperDual {
+    #[inline]
+    fn eq(&self, other: &f64) -> bool {
+        self.value == *other
+    }
+}
+
+impl PartialOrd for HyperDual {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl Display for HyperDual {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} + {:?}\u{03b5}\u{2081} + {:?}\u{03b5}\u{2082} + {:?}\u{03b5}\u{2081}\u{03b5}\u{2082}",
+            self.value, self.eps1, self.eps2, self.eps12
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OVERLOADING: POWER, LOG AND MIN/MAX TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// HyperDual ^ HyperDual
+impl Powf<HyperDual> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn powf(&self, other: HyperDual) -> Self::Output {
+        (other * self.ln()).exp()
+    }
+}
+
+// HyperDual ^ f64
+impl Powf<f64> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn powf(&self, n: f64) -> Self::Output {
+        self.chain(
+            self.value.powf(n),
+            n * self.value.powf(n - 1.0),
+            n * (n - 1.0) * self.value.powf(n - 2.0),
+        )
+    }
+}
+
+// f64 ^ HyperDual
+impl Powf<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn powf(&self, other: HyperDual) -> Self::Output {
+        let value = f64::powf(*self, other.value);
+        let ln = f64::ln(*self);
+
+        other.chain(value, value * ln, value * ln * ln)
+    }
+}
+
+// HyperDual ^ i32
+impl Powi<i32> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn powi(&self, n: i32) -> Self::Output {
+        let n_f64 = f64::from(n);
+
+        self.chain(
+            self.value.powi(n),
+            n_f64 * self.value.powi(n - 1),
+            n_f64 * (n_f64 - 1.0) * self.value.powi(n - 2),
+        )
+    }
+}
+
+// log_base(HyperDual), base: HyperDual
+impl Log<HyperDual> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn log(&self, base: HyperDual) -> Self::Output {
+        self.ln() / base.ln()
+    }
+}
+
+// log_base(HyperDual), base: f64
+impl Log<f64> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn log(&self, base: f64) -> Self::Output {
+        self.ln() / base.ln()
+    }
+}
+
+impl Min<HyperDual> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn min(&self, rhs: HyperDual) -> Self::Output {
+        if self.value < rhs.value {
+            *self
+        } else {
+            rhs
+        }
+    }
+}
+
+impl Min<f64> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn min(&self, rhs: f64) -> Self::Output {
+        Min::min(self, HyperDual::constant(rhs))
+    }
+}
+
+impl Max<HyperDual> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn max(&self, rhs: HyperDual) -> Self::Output {
+        if self.value > rhs.value {
+            *self
+        } else {
+            rhs
+        }
+    }
+}
+
+impl Max<f64> for HyperDual {
+    type Output = HyperDual;
+
+    #[inline]
+    fn max(&self, rhs: f64) -> Self::Output {
+        Max::max(self, HyperDual::constant(rhs))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OVERLOADING: PRIMITIVE FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HyperDual {
+    /// Absolute value function.
+    /// d/dx abs(x) = sign(x), d^2/dx^2 abs(x) = 0
+    #[must_use]
+    #[inline]
+    pub fn abs(self) -> Self {
+        self.chain(self.value.abs(), self.value.signum(), 0.0)
+    }
+
+    /// Inverse cosine function.
+    /// d/dx cos^-1(x) = - 1 / sqrt(1 - x^2)
+    #[must_use]
+    #[inline]
+    pub fn acos(self) -> Self {
+        let s = 1.0 - self.value.powi(2);
+
+        self.chain(
+            self.value.acos(),
+            -s.sqrt().recip(),
+            -self.value * s.powf(-1.5),
+        )
+    }
+
+    /// Inverse hyperbolic cosine function.
+    /// d/dx cosh^-1(x) = 1 / sqrt(x^2 - 1)
+    #[must_use]
+    #[inline]
+    pub fn acosh(self) -> Self {
+        let s = self.value.powi(2) - 1.0;
+
+        self.chain(
+            self.value.acosh(),
+            s.sqrt().recip(),
+            -self.value * s.powf(-1.5),
+        )
+    }
+
+    /// Inverse sine function.
+    /// d/dx sin^-1(x) = 1 / sqrt(1 - x^2)
+    #[must_use]
+    #[inline]
+    pub fn asin(self) -> Self {
+        let s = 1.0 - self.value.powi(2);
+
+        self.chain(
+            self.value.asin(),
+            s.sqrt().recip(),
+            self.value * s.powf(-1.5),
+        )
+    }
+
+    /// Inverse hyperbolic sine function.
+    /// d/dx sinh^-1(x) = 1 / sqrt(1 + x^2)
+    #[must_use]
+    #[inline]
+    pub fn asinh(self) -> Self {
+        let s = 1.0 + self.value.powi(2);
+
+        self.chain(
+            self.value.asinh(),
+            s.sqrt().recip(),
+            -self.value * s.powf(-1.5),
+        )
+    }
+
+    /// Inverse tangent function.
+    /// d/dx tan^-1(x) = 1 / (1 + x^2)
+    #[must_use]
+    #[inline]
+    pub fn atan(self) -> Self {
+        let s = 1.0 + self.value.powi(2);
+
+        self.chain(self.value.atan(), s.recip(), -2.0 * self.value / s.powi(2))
+    }
+
+    /// Inverse hyperbolic tangent function.
+    /// d/dx tanh^-1(x) = 1 / (1 - x^2)
+    #[must_use]
+    #[inline]
+    pub fn atanh(self) -> Self {
+        let s = 1.0 - self.value.powi(2);
+
+        self.chain(self.value.atanh(), s.recip(), 2.0 * self.value / s.powi(2))
+    }
+
+    /// Cuberoot function.
+    /// d/dx cuberoot(x) = 1 / ( 3 * x^(2/3) )
+    #[must_use]
+    #[inline]
+    pub fn cbrt(self) -> Self {
+        let value = self.value.cbrt();
+
+        self.chain(
+            value,
+            (3.0 * value.powi(2)).recip(),
+            -2.0 / (9.0 * value.powi(5)),
+        )
+    }
+
+    /// Cosine function.
+    /// d/dx cos(x) = -sin(x)
+    #[must_use]
+    #[inline]
+    pub fn cos(self) -> Self {
+        let (sin, cos) = self.value.sin_cos();
+
+        self.chain(cos, -sin, -cos)
+    }
+
+    /// Hyperbolic cosine function.
+    /// d/dx cosh(x) = sinh(x)
+    #[must_use]
+    #[inline]
+    pub fn cosh(self) -> Self {
+        let value = self.value.cosh();
+
+        self.chain(value, self.value.sinh(), value)
+    }
+
+    /// Exponential function (base *e*).
+    /// d/dx exp(x) = exp(x)
+    #[must_use]
+    #[inline]
+    pub fn exp(self) -> Self {
+        let value = self.value.exp();
+
+        self.chain(value, value, value)
+    }
+
+    /// Exponential function (base 2)
+    /// d/dx 2^x = 2^x * ln(2)
+    #[must_use]
+    #[inline]
+    pub fn exp2(self) -> Self {
+        let value = self.value.exp2();
+
+        self.chain(value, value * LN_2, value * LN_2 * LN_2)
+    }
+
+    /// Exponential function minus 1 function.
+    /// d/dx exp(x) - 1 = exp(x)
+    #[must_use]
+    #[inline]
+    pub fn exp_m1(self) -> Self {
+        let exp = self.value.exp();
+
+        self.chain(self.value.exp_m1(), exp, exp)
+    }
+
+    /// Logarithm (natural) of `x`.
+    /// d/dx ln(x) = 1 / x
+    #[must_use]
+    #[inline]
+    pub fn ln(self) -> Self {
+        let recip = self.value.recip();
+
+        self.chain(self.value.ln(), recip, -recip * recip)
+    }
+
+    /// Logarithm (natural) of `1 + x`.
+    /// d/dx ln(1+x) = 1 / (1+x)
+    #[must_use]
+    #[inline]
+    pub fn ln_1p(self) -> Self {
+        let recip = (1.0 + self.value).recip();
+
+        self.chain(self.value.ln_1p(), recip, -recip * recip)
+    }
+
+    /// Logarithm (base 10).
+    /// d/dx log_10(x) = 1 / (x * ln(10))
+    #[must_use]
+    #[inline]
+    pub fn log10(self) -> Self {
+        self.ln() / LN_10
+    }
+
+    /// Logarithm (base 2).
+    /// d/dx log_2(x) = 1 / (x * ln(2))
+    #[must_use]
+    #[inline]
+    pub fn log2(self) -> Self {
+        self.ln() / LN_2
+    }
+
+    /// Reciprocal function.
+    /// d/dx 1 / x =  - 1 / x^2
+    #[must_use]
+    #[inline]
+    pub fn recip(self) -> Self {
+        let recip = self.value.recip();
+
+        self.chain(recip, -recip * recip, 2.0 * recip.powi(3))
+    }
+
+    /// Sine function.
+    /// d/dx sin(x) = cos(x)
+    #[must_use]
+    #[inline]
+    pub fn sin(self) -> Self {
+        let (sin, cos) = self.value.sin_cos();
+
+        self.chain(sin, cos, -sin)
+    }
+
+    /// Hyperbolic sine function.
+    /// d/dx sinh(x) =  cosh(x)
+    #[must_use]
+    #[inline]
+    pub fn sinh(self) -> Self {
+        let value = self.value.sinh();
+
+        self.chain(value, self.value.cosh(), value)
+    }
+
+    /// Square root function.
+    /// d/dx sqrt(x) =  1 / 2*sqrt(x)
+    #[must_use]
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        let value = self.value.sqrt();
+
+        self.chain(value, (2.0 * value).recip(), -(4.0 * value.powi(3)).recip())
+    }
+
+    /// Tangent function.
+    /// d/dx tan(x) = 1 + tan^2(x)
+    #[must_use]
+    #[inline]
+    pub fn tan(self) -> Self {
+        let value = self.value.tan();
+        let sec2 = 1.0 + value.powi(2);
+
+        self.chain(value, sec2, 2.0 * value * sec2)
+    }
+
+    /// Hyperbolic tangent function.
+    /// d/dx tanh(x) = 1 - tanh^2(x)
+    #[must_use]
+    #[inline]
+    pub fn tanh(self) -> Self {
+        let value = self.value.tanh();
+        let sech2 = 1.0 - value.powi(2);
+
+        self.chain(value, sech2, -2.0 * value * sech2)
+    }
+
+    /// Error function.
+    /// d/dx erf(x) = 2e^(-x^2) / sqrt(PI)
+    #[must_use]
+    #[inline]
+    pub fn erf(self) -> Self {
+        use statrs::function::erf::erf;
+
+        let df = 2.0 * (-self.value.powi(2)).exp() / PI.sqrt();
+
+        self.chain(erf(self.value), df, -2.0 * self.value * df)
+    }
+
+    /// Error function (complementary).
+    /// d/dx erfc(x) = -2e^(-x^2) / sqrt(PI)
+    #[must_use]
+    #[inline]
+    pub fn erfc(self) -> Self {
+        use statrs::function::erf::erfc;
+
+        let df = -2.0 * (-self.value.powi(2)).exp() / PI.sqrt();
+
+        self.chain(erfc(self.value), df, -2.0 * self.value * df)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hyperdual {
+    use super::*;
+    use crate::assert_approx_equal;
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    use std::f64::EPSILON as EPS;
+
+    fn norm_cdf(x: HyperDual) -> HyperDual {
+        0.5 * (1.0 + (x / std::f64::consts::SQRT_2).erf())
+    }
+
+    fn black_scholes(s: HyperDual, k: f64, t: f64, r: f64, v: HyperDual) -> HyperDual {
+        let d1 = ((s / k).ln() + (r + 0.5 * v * v) * t) / (v * t.sqrt());
+        let d2 = d1 - v * t.sqrt();
+
+        s * norm_cdf(d1) - k * (-r * t).exp() * norm_cdf(d2)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let x = HyperDual::variable(3.0);
+
+        let z = (x * x + 2.0 * x - 1.0) / x;
+
+        // z = x + 2 - 1/x,  z' = 1 + 1/x^2,  z'' = -2/x^3
+        assert_approx_equal!(z.value, 3.0 + 2.0 - 1.0 / 3.0, 1e-15);
+        assert_approx_equal!(z.eps1, 1.0 + 1.0 / 9.0, 1e-15);
+        assert_approx_equal!(z.eps2, 1.0 + 1.0 / 9.0, 1e-15);
+        assert_approx_equal!(z.eps12, -2.0 / 27.0, 1e-15);
+    }
+
+    #[test]
+    fn test_primitive_functions() {
+        let x = 0.5;
+        let h = 1e-4;
+
+        let functions: Vec<(fn(HyperDual) -> HyperDual, fn(f64) -> f64)> = vec![
+            (HyperDual::exp, f64::exp),
+            (HyperDual::exp2, f64::exp2),
+            (HyperDual::exp_m1, f64::exp_m1),
+            (HyperDual::ln, f64::ln),
+            (HyperDual::ln_1p, f64::ln_1p),
+            (HyperDual::sqrt, f64::sqrt),
+            (HyperDual::sin, f64::sin),
+            (HyperDual::cos, f64::cos),
+            (HyperDual::tan, f64::tan),
+            (HyperDual::sinh, f64::sinh),
+            (HyperDual::cosh, f64::cosh),
+            (HyperDual::tanh, f64::tanh),
+            (HyperDual::asin, f64::asin),
+            (HyperDual::acos, f64::acos),
+            (HyperDual::atan, f64::atan),
+            (HyperDual::asinh, f64::asinh),
+            (HyperDual::atanh, f64::atanh),
+            (HyperDual::log2, f64::log2),
+            (HyperDual::log10, f64::log10),
+            (HyperDual::cbrt, f64::cbrt),
+            (HyperDual::recip, f64::recip),
+        ];
+
+        for (hyper_fn, f64_fn) in functions {
+            let (value, df, d2f) = second_derivative(hyper_fn, x);
+
+            let fd1 = (f64_fn(x + h) - f64_fn(x - h)) / (2.0 * h);
+            let fd2 = (f64_fn(x + h) - 2.0 * f64_fn(x) + f64_fn(x - h)) / (h * h);
+
+            assert_approx_equal!(value, f64_fn(x), EPS);
+            assert_approx_equal!(df, fd1, 1e-6);
+            assert_approx_equal!(d2f, fd2, 1e-5);
+        }
+
+        let (_, df, d2f) = second_derivative(HyperDual::acosh, 1.5);
+        assert_approx_equal!(df, 1.25_f64.sqrt().recip(), 1e-15);
+        assert_approx_equal!(d2f, -1.5 * 1.25_f64.powf(-1.5), 1e-15);
+
+        // statrs' erf is not smooth enough for second order finite differences.
+        let density = 2.0 * (-x * x).exp() / PI.sqrt();
+
+        let (_, df, d2f) = second_derivative(HyperDual::erf, x);
+        assert_approx_equal!(df, density, EPS);
+        assert_approx_equal!(d2f, -2.0 * x * density, EPS);
+
+        let (_, df, d2f) = second_derivative(HyperDual::erfc, x);
+        assert_approx_equal!(df, -density, EPS);
+        assert_approx_equal!(d2f, 2.0 * x * density, EPS);
+    }
+
+    #[test]
+    fn test_powers() {
+        let x = HyperDual::variable(2.0);
+
+        assert_approx_equal!(x.powi(3).eps12, 12.0, EPS);
+        assert_approx_equal!(x.powf(0.5).eps12, -0.25 * 2.0_f64.powf(-1.5), EPS);
+
+        let ln2 = 2.0_f64.ln();
+        assert_approx_equal!(Powf::powf(&2.0, x).eps12, 4.0 * ln2 * ln2, 1e-14);
+
+        // d^2/dx^2 x^x = x^x ((1 + ln x)^2 + 1/x)
+        assert_approx_equal!(x.powf(x).eps12, 4.0 * ((1.0 + ln2).powi(2) + 0.5), 1e-13);
+    }
+
+    #[test]
+    fn test_black_scholes_second_order_greeks() {
+        let (s, k, t, r, v) = (100.0_f64, 110.0, 0.75_f64, 0.05, 0.2);
+
+        let n = Normal::new(0.0, 1.0).unwrap();
+
+        let d1 = ((s / k).ln() + (r + 0.5 * v * v) * t) / (v * t.sqrt());
+        let d2 = d1 - v * t.sqrt();
+
+        let gamma = n.pdf(d1) / (s * v * t.sqrt());
+        let vanna = -n.pdf(d1) * d2 / v;
+        let volga = s * n.pdf(d1) * t.sqrt() * d1 * d2 / v;
+
+        // Gamma: both directions in the spot.
+        let price = black_scholes(HyperDual::variable(s), k, t, r, HyperDual::constant(v));
+        assert_approx_equal!(price.eps12, gamma, 1e-12);
+
+        // Vanna: spot in the first direction, volatility in the second.
+        let price = black_scholes(
+            HyperDual::new(s, 1.0, 0.0, 0.0),
+            k,
+            t,
+            r,
+            HyperDual::new(v, 0.0, 1.0, 0.0),
+        );
+        assert_approx_equal!(price.eps1, n.cdf(d1), 1e-12);
+        assert_approx_equal!(price.eps2, s * n.pdf(d1) * t.sqrt(), 1e-10);
+        assert_approx_equal!(price.eps12, vanna, 1e-12);
+
+        // Volga: both directions in the volatility.
+        let price = black_scholes(HyperDual::constant(s), k, t, r, HyperDual::variable(v));
+        assert_approx_equal!(price.eps12, volga, 1e-10);
+    }
+
+    #[test]
+    fn test_hyper_dual_hessian() {
+        // f(x, y) = sin(x * y) + x^3
+        let f = |x: &[HyperDual]| (x[0] * x[1]).sin() + x[0].powi(3);
+
+        let (value, gradient, hessian) = hyper_dual_hessian(f, &[0.5, 2.0]);
+
+        let (x, y) = (0.5_f64, 2.0_f64);
+
+        assert_approx_equal!(value, (x * y).sin() + x.powi(3), EPS);
+        assert_approx_equal!(gradient[0], y * (x * y).cos() + 3.0 * x * x, EPS);
+        assert_approx_equal!(gradient[1], x * (x * y).cos(), EPS);
+        assert_approx_equal!(hessian[(0, 0)], -y * y * (x * y).sin() + 6.0 * x, 1e-15);
+        assert_approx_equal!(
+            hessian[(0, 1)],
+            (x * y).cos() - x * y * (x * y).sin(),
+            1e-15
+        );
+        assert_approx_equal!(hessian[(1, 0)], hessian[(0, 1)], EPS);
+        assert_approx_equal!(hessian[(1, 1)], -x * x * (x * y).sin(), 1e-15);
+    }
+}
@@ -0,0 +1,530 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! This module contains the `Jacobian` trait, for functions
+//! $f: \mathbb{R}^n \rightarrow \mathbb{R}^m$ (vector output).
+//!
+//! One reverse sweep is performed per output. Each sweep starts at the
+//! output's vertex (rather than the end of the graph) and only visits
+//! vertices the output depends on, so the structural zeros of the Jacobian
+//! are detected and not stored.
+//!
+//! When the Jacobian is small and dense (e.g. a price and a few risk measures
+//! of the same inputs), [`MultiAdjoints`] instead propagates one adjoint per
+//! output through a single reverse sweep of the graph.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Accumulate, Gradient, Variable};
+use nalgebra::DMatrix;
+use std::collections::HashMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// SPARSE JACOBIAN STRUCT AND IMPLEMENTATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Jacobian matrix in compressed sparse row (CSR) format.
+///
+/// Row `i` contains the partial derivatives of output `i`, and column `j`
+/// corresponds to input `j`. Only structurally non-zero entries
+/// (i.e. where the output depends on the input) are stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseJacobian {
+    /// Number of rows (outputs).
+    pub nrows: usize,
+    /// Number of columns (inputs).
+    pub ncols: usize,
+    /// Row `i` is stored in `col_indices[row_offsets[i]..row_offsets[i + 1]]`.
+    pub row_offsets: Vec<usize>,
+    /// Column index of each stored entry.
+    pub col_indices: Vec<usize>,
+    /// Value of each stored entry.
+    pub values: Vec<f64>,
+}
+
+impl SparseJacobian {
+    /// Number of stored (structurally non-zero) entries.
+    #[must_use]
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the entry at row `i` and column `j` (zero if not stored).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    #[must_use]
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        assert!(i < self.nrows && j < self.ncols, "Index out of bounds.");
+
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+
+        self.col_indices[range.clone()]
+            .iter()
+            .position(|&col| col == j)
+            .map_or(0.0, |k| self.values[range.start + k])
+    }
+
+    /// Iterate over the stored entries of row `i`, as `(column, value)` pairs.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+
+        self.col_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    /// Convert to a dense matrix.
+    #[must_use]
+    pub fn to_dense(&self) -> DMatrix<f64> {
+        let mut dense = DMatrix::zeros(self.nrows, self.ncols);
+
+        for i in 0..self.nrows {
+            for (j, value) in self.row(i) {
+                dense[(i, j)] = value;
+            }
+        }
+
+        dense
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// JACOBIAN TRAIT AND IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Return the Jacobian of a vector of outputs *with-respect-to* the chosen
+/// variables.
+pub trait Jacobian<IN, OUT> {
+    /// Returns the Jacobian *with-respect-to* the chosen variables.
+    fn jacobian(&self, variables: IN) -> OUT;
+}
+
+/// `jacobian` of a slice of outputs wrt a borrowed slice of variables.
+impl<'v> Jacobian<&[Variable<'v>], SparseJacobian> for [Variable<'v>] {
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.vars(&[1.0, 2.0, 3.0]);
+    ///
+    /// // f(x) = (x0 * x1, sin(x2))
+    /// let f = vec![x[0] * x[1], x[2].sin()];
+    ///
+    /// let jacobian = f.jacobian(&x[..]);
+    ///
+    /// assert_eq!(jacobian.nnz(), 3);
+    /// assert_eq!(jacobian.get(0, 0), 2.0);
+    /// assert_eq!(jacobian.get(0, 1), 1.0);
+    /// assert_eq!(jacobian.get(0, 2), 0.0);
+    /// assert_eq!(jacobian.get(1, 2), 3.0_f64.cos());
+    /// ```
+    fn jacobian(&self, variables: &[Variable<'v>]) -> SparseJacobian {
+        let columns: HashMap<usize, usize> = variables
+            .iter()
+            .enumerate()
+            .map(|(j, variable)| (variable.index, j))
+            .collect();
+
+        let mut row_offsets = Vec::with_capacity(self.len() + 1);
+        let mut col_indices = Vec::new();
+        let mut values = Vec::new();
+
+        row_offsets.push(0);
+
+        let n = self
+            .iter()
+            .filter(|output| !output.is_detached())
+            .map(|output| output.index + 1)
+            .max()
+            .unwrap_or(0);
+        let mut adjoints = vec![0.0; n];
+        let mut reached = vec![false; n];
+
+        for output in self {
+            // Detached outputs are constants, so their rows are empty.
+            if output.is_detached() {
+                row_offsets.push(col_indices.len());
+                continue;
+            }
+
+            let vertices = output.graph.vertices.borrow();
+
+            adjoints[..=output.index].fill(0.0);
+            reached[..=output.index].fill(false);
+
+            adjoints[output.index] = 1.0; // SEED
+            reached[output.index] = true;
+
+            let mut row = Vec::new();
+
+            for index in (0..=output.index).rev() {
+                if !reached[index] {
+                    continue;
+                }
+
+                if let Some(&j) = columns.get(&index) {
+                    row.push((j, adjoints[index]));
+                }
+
+                let vertex = &vertices[index];
+                let deriv = adjoints[index];
+
+                for k in 0..2 {
+                    let parent = vertex.parents[k];
+
+                    if parent != index {
+                        reached[parent] = true;
+                        adjoints[parent] += vertex.partials[k] * deriv;
+                    }
+                }
+            }
+
+            row.sort_unstable_by_key(|&(j, _)| j);
+
+            for (j, value) in row {
+                col_indices.push(j);
+                values.push(value);
+            }
+
+            row_offsets.push(col_indices.len());
+        }
+
+        SparseJacobian {
+            nrows: self.len(),
+            ncols: variables.len(),
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+}
+
+/// `jacobian` of a slice of outputs wrt a borrowed array of variables.
+impl<'v, const N: usize> Jacobian<&[Variable<'v>; N], SparseJacobian> for [Variable<'v>] {
+    #[inline]
+    fn jacobian(&self, variables: &[Variable<'v>; N]) -> SparseJacobian {
+        self.jacobian(&variables[..])
+    }
+}
+
+/// `jacobian` of a slice of outputs wrt a borrowed vector of variables.
+impl<'v> Jacobian<&Vec<Variable<'v>>, SparseJacobian> for [Variable<'v>] {
+    #[inline]
+    fn jacobian(&self, variables: &Vec<Variable<'v>>) -> SparseJacobian {
+        self.jacobian(&variables[..])
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// VECTOR (MULTI-OUTPUT) REVERSE ACCUMULATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Adjoints of several outputs, accumulated in a single reverse sweep.
+///
+/// Each vertex carries one adjoint per seed, stored contiguously:
+/// the adjoints of vertex `index` are `adjoints[index * seeds..][..seeds]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiAdjoints {
+    /// Number of seeds (adjoint directions).
+    pub seeds: usize,
+    /// The adjoints, stored vertex-major.
+    pub adjoints: Vec<f64>,
+}
+
+impl MultiAdjoints {
+    /// Reverse accumulate several weighted combinations of the outputs
+    /// in a single sweep.
+    ///
+    /// Row `i` of `seeds` holds the weights of the outputs in the $i$-th
+    /// adjoint direction, so the identity matrix gives the gradient of each
+    /// output separately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seeds` does not have one column per output,
+    /// or if the outputs belong to different graphs.
+    #[must_use]
+    pub fn seeded(outputs: &[Variable<'_>], seeds: &DMatrix<f64>) -> Self {
+        assert_eq!(seeds.ncols(), outputs.len(), "One seed weight per output.");
+
+        let m = seeds.nrows();
+
+        let Some(first) = outputs.first() else {
+            return Self {
+                seeds: m,
+                adjoints: Vec::new(),
+            };
+        };
+
+        assert!(
+            outputs.iter().all(|y| std::ptr::eq(y.graph, first.graph)),
+            "Outputs must belong to the same graph."
+        );
+
+        let mut adjoints = vec![0.0; first.graph.len() * m];
+
+        // SEED
+        for (j, output) in outputs.iter().enumerate() {
+            // Detached outputs are constants.
+            if output.is_detached() {
+                continue;
+            }
+
+            for i in 0..m {
+                adjoints[output.index * m + i] += seeds[(i, j)];
+            }
+        }
+
+        let end = outputs
+            .iter()
+            .filter(|y| !y.is_detached())
+            .map(|y| y.index + 1)
+            .max()
+            .unwrap_or(0);
+
+        let vertices = first.graph.vertices.borrow();
+        let mut deriv = vec![0.0; m];
+
+        for index in (0..end).rev() {
+            deriv.copy_from_slice(&adjoints[index * m..][..m]);
+
+            if deriv.iter().all(|&d| d == 0.0) {
+                continue;
+            }
+
+            let vertex = &vertices[index];
+
+            for k in 0..2 {
+                let (parent, partial) = (vertex.parents[k], vertex.partials[k]);
+
+                if partial == 0.0 {
+                    continue;
+                }
+
+                for (adjoint, d) in adjoints[parent * m..][..m].iter_mut().zip(&deriv) {
+                    *adjoint += partial * d;
+                }
+            }
+        }
+
+        Self { seeds: m, adjoints }
+    }
+
+    /// Returns the adjoints of the vertex at `index`, one per seed.
+    #[must_use]
+    #[inline]
+    pub fn get(&self, index: usize) -> &[f64] {
+        &self.adjoints[index * self.seeds..][..self.seeds]
+    }
+}
+
+impl Accumulate<MultiAdjoints> for [Variable<'_>] {
+    /// Reverse accumulate the gradients of all outputs in a single sweep.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.vars(&[1.0, 2.0]);
+    ///
+    /// let price = x[0] * x[1];
+    /// let risk = x[0].exp() + x[1];
+    ///
+    /// let jacobian = [price, risk].accumulate().wrt(&x);
+    ///
+    /// assert_eq!(jacobian.shape(), (2, 2));
+    /// assert_eq!(jacobian[(0, 0)], 2.0);
+    /// assert_eq!(jacobian[(1, 0)], 1.0_f64.exp());
+    /// assert_eq!(jacobian[(1, 1)], 1.0);
+    /// ```
+    #[inline]
+    fn accumulate(&self) -> MultiAdjoints {
+        MultiAdjoints::seeded(self, &DMatrix::identity(self.len(), self.len()))
+    }
+}
+
+/// `wrt` a single variable: one partial derivative per seed.
+impl<'v> Gradient<&Variable<'v>, Vec<f64>> for MultiAdjoints {
+    #[inline]
+    fn wrt(&self, variable: &Variable<'v>) -> Vec<f64> {
+        self.get(variable.index).to_vec()
+    }
+}
+
+/// `wrt` a borrowed slice of variables: the (seeds x variables) Jacobian.
+impl<'v> Gradient<&[Variable<'v>], DMatrix<f64>> for MultiAdjoints {
+    #[inline]
+    fn wrt(&self, variables: &[Variable<'v>]) -> DMatrix<f64> {
+        DMatrix::from_fn(self.seeds, variables.len(), |i, j| {
+            self.get(variables[j].index)[i]
+        })
+    }
+}
+
+/// `wrt` a borrowed vector of variables.
+impl<'v> Gradient<&Vec<Variable<'v>>, DMatrix<f64>> for MultiAdjoints {
+    #[inline]
+    fn wrt(&self, variables: &Vec<Variable<'v>>) -> DMatrix<f64> {
+        self.wrt(&variables[..])
+    }
+}
+
+/// `wrt` a borrowed array of variables.
+impl<'v, const N: usize> Gradient<&[Variable<'v>; N], DMatrix<f64>> for MultiAdjoints {
+    #[inline]
+    fn wrt(&self, variables: &[Variable<'v>; N]) -> DMatrix<f64> {
+        self.wrt(&variables[..])
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod test_jacobian {
+    use crate::{assert_approx_equal, autodiff::*};
+
+    use std::f64::EPSILON as EPS;
+
+    #[test]
+    fn test_matches_gradients() {
+        let g = Graph::new();
+
+        let x = g.vars(&[0.5, 1.5, 2.5]);
+
+        let f = vec![
+            x[0] * x[1] + x[2].exp(),
+            (x[0] / x[2]).sin(),
+            x[1].sqrt() * x[0],
+        ];
+
+        let jacobian = f.jacobian(&x);
+
+        for (i, output) in f.iter().enumerate() {
+            let gradient = output.accumulate().wrt(&x);
+
+            for (j, &partial) in gradient.iter().enumerate() {
+                assert_approx_equal!(jacobian.get(i, j), partial, EPS);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sparsity_pattern() {
+        let g = Graph::new();
+
+        let x = g.vars(&[1.0, 2.0, 3.0, 4.0]);
+
+        // Tridiagonal-ish dependence structure.
+        let f = vec![x[0] * x[1], x[1] + x[2], x[2] * x[3], x[3].ln()];
+
+        let jacobian = f.jacobian(&x);
+
+        assert_eq!(jacobian.nnz(), 7);
+        assert_eq!(jacobian.row_offsets, vec![0, 2, 4, 6, 7]);
+        assert_eq!(jacobian.col_indices, vec![0, 1, 1, 2, 2, 3, 3]);
+
+        let dense = jacobian.to_dense();
+
+        assert_eq!(dense.shape(), (4, 4));
+        assert_approx_equal!(dense[(0, 0)], 2.0, EPS);
+        assert_approx_equal!(dense[(2, 3)], 3.0, EPS);
+        assert_approx_equal!(dense[(3, 3)], 0.25, EPS);
+        assert_approx_equal!(dense[(3, 0)], 0.0, EPS);
+    }
+
+    #[test]
+    fn test_empty_outputs() {
+        let g = Graph::new();
+        let x = g.vars(&[1.0, 2.0]);
+
+        let f: Vec<Variable> = Vec::new();
+        let jacobian = f.jacobian(&x);
+
+        assert_eq!(jacobian.nrows, 0);
+        assert_eq!(jacobian.ncols, 2);
+        assert_eq!(jacobian.nnz(), 0);
+    }
+
+    #[test]
+    fn test_multi_adjoints_match_gradients() {
+        let g = Graph::new();
+
+        let x = g.vars(&[0.5, 1.5, 2.5]);
+
+        let f = vec![
+            x[0] * x[1] + x[2].exp(),
+            (x[0] / x[2]).sin(),
+            x[1].sqrt() * x[0],
+        ];
+
+        let before = g.len();
+        let jacobian = f.accumulate().wrt(&x);
+
+        // No vertices are recorded by the sweep.
+        assert_eq!(g.len(), before);
+        assert_eq!(jacobian.shape(), (3, 3));
+
+        for (i, output) in f.iter().enumerate() {
+            let gradient = output.accumulate().wrt(&x);
+
+            for (j, &partial) in gradient.iter().enumerate() {
+                assert_approx_equal!(jacobian[(i, j)], partial, EPS);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_adjoints_seeded() {
+        let g = Graph::new();
+
+        let x = g.vars(&[1.0, 2.0]);
+
+        // Outputs recorded in the middle of the graph.
+        let a = x[0] * x[1];
+        let b = x[0] + x[1].ln();
+        let _c = a * b;
+
+        // Seeds: a + b, and 2a - b.
+        let seeds = nalgebra::DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 2.0, -1.0]);
+        let adjoints = MultiAdjoints::seeded(&[a, b], &seeds);
+
+        let first = (a + b).accumulate().wrt(&x);
+        let second = (2.0 * a - b).accumulate().wrt(&x);
+
+        for j in 0..2 {
+            assert_approx_equal!(adjoints.wrt(&x[j])[0], first[j], EPS);
+            assert_approx_equal!(adjoints.wrt(&x[j])[1], second[j], EPS);
+        }
+    }
+
+    #[test]
+    fn test_multi_adjoints_detached() {
+        let g = Graph::new();
+
+        let x = g.var(3.0);
+        let c = {
+            let _pause = g.pause();
+            x * 2.0
+        };
+
+        let jacobian = [x * x, c].accumulate().wrt(&[x]);
+
+        assert_approx_equal!(jacobian[(0, 0)], 6.0, EPS);
+        assert_approx_equal!(jacobian[(1, 0)], 0.0, EPS);
+    }
+}
@@ -3,22 +3,24 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 //! Reverse mode automatic differentation.
-//! Currently only gradients can be computed.
-//! Suggestions on how to extend the functionality to Hessian matrices are
-//! definitely welcome.
+//! Gradients are computed via reverse accumulation, and Hessians via
+//! forward-over-reverse accumulation (see [`Hessian`]).
 //!
-//! Additionally, only functions $f: \mathbb{R}^n \rightarrow \mathbb{R}$
-//! (scalar output) are supported. However, you can manually apply the
-//! differentiation to multiple functions that could represent a vector output.
+//! For functions $f: \mathbb{R}^n \rightarrow \mathbb{R}^m$ (vector output),
+//! the [`Jacobian`] trait performs one reverse sweep per output and returns
+//! a [`SparseJacobian`]. For a few outputs of the same inputs, accumulating
+//! a slice of outputs (see [`MultiAdjoints`]) performs a single reverse sweep
+//! and returns a small dense Jacobian.
 //!
 //! - [x] Reverse (Adjoint) Mode
 //!   - Implementation via Operator and Function Overloading.
 //!   - Useful when number of outputs is *smaller* than number of inputs.
 //!     - i.e for functions $f:\mathbb{R}^n \rightarrow \mathbb{R}^m$, where $m \ll n$
-//! - [ ] Forward (Tangent) Mode
-//!   - Implementation via Dual Numbers.
+//! - [x] Forward (Tangent) Mode
+//!   - Implementation via Dual Numbers (see [`Dual`]).
 //!   - Useful when number of outputs is *larger* than number of inputs.
 //!     - i.e. for functions $f:\mathbb{R}^n \rightarrow \mathbb{R}^m$, where $m \gg n$
+//!   - Exact second derivatives via Hyper-Dual Numbers (see [`HyperDual`]).
 //!
 //! ```
 //! use RustQuant::autodiff::*;
@@ -49,6 +51,9 @@
 //!
 //! ```ignore
 //! println!("{}", graphviz(&graph, &variables));
+//!
+//! // Or, with operation labels, limited to the vertices `f` depends on:
+//! println!("{}", graph.to_dot(Some(&f)));
 //! ```  
 //!
 //! The computation graph from computing Black-Scholes Greeks is shown at the
@@ -65,10 +70,33 @@
 pub mod accumulate;
 pub use accumulate::*;
 
+/// Arena storage for the vertices of the [`Graph`].
+pub mod arena;
+pub use arena::*;
+
+/// Structure-of-arrays snapshot of the [`Graph`], for vectorised reverse passes.
+pub mod compact;
+pub use compact::*;
+
+/// Custom (user-defined) operations for the [`Graph`].
+pub mod custom;
+
+/// Forward (tangent) mode via dual numbers.
+pub mod dual;
+pub use dual::*;
+
 /// Implements the gradient computation.
 pub mod gradient;
 pub use gradient::*;
 
+/// Exact second derivatives via hyper-dual numbers.
+pub mod hyperdual;
+pub use hyperdual::*;
+
+/// Implements the Hessian computation.
+pub mod hessian;
+pub use hessian::*;
+
 /// The Graph (aka. tape or Wengert List).
 pub mod graph;
 pub use graph::*;
@@ -77,6 +105,26 @@ pub use graph::*;
 pub mod graphviz;
 pub use graphviz::*;
 
+/// Parallel reverse accumulation over thread-local graphs.
+pub mod parallel;
+pub use parallel::*;
+
+/// Implements the (sparse) Jacobian computation.
+pub mod jacobian;
+pub use jacobian::*;
+
+/// Numeric trait shared by `f64` and the automatic differentiation types.
+pub mod real;
+pub use real::*;
+
+/// Serialization and replay of the [`Graph`].
+pub mod replay;
+pub use replay::*;
+
+/// Statistics and profiling hooks for the [`Graph`].
+pub mod stats;
+pub use stats::*;
+
 /// Implements [`Vertex`] (nodes) for the `Graph`.
 pub mod vertex;
 pub use vertex::*;
@@ -116,6 +164,8 @@ pub use overloading::{
 
 /// `Variable`s for `autodiff`.
 pub mod variables {
+    /// Vectorized (batched) variables, with one vertex per elementwise operation.
+    pub mod array;
     /// Implements `Variable`s for `nalgebra`.
     pub mod nalgebra;
     /// Implements `Variable`s for `ndarray`.
@@ -123,4 +173,4 @@ pub mod variables {
     /// Base trait for all `Variable`s.
     pub mod variable;
 }
-pub use variables::{nalgebra::*, ndarray::*, variable::*};
+pub use variables::{array::*, nalgebra::*, ndarray::*, variable::*};
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::ops::{Add, AddAssign};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -67,9 +67,12 @@ impl<'v> Add<Variable<'v>> for Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value + other.value,
-            index: self
-                .graph
-                .push(Arity::Binary, &[self.index, other.index], &[1.0, 1.0]),
+            index: self.graph.push_operation(
+                Operation::Add,
+                &[self.index, other.index],
+                &[1.0, 1.0],
+                [0.0; 3],
+            ),
         }
     }
 }
@@ -97,9 +100,12 @@ impl<'v> Add<f64> for Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value + other,
-            index: self
-                .graph
-                .push(Arity::Binary, &[self.index, self.index], &[1.0, 0.0]),
+            index: self.graph.push_operation(
+                Operation::AddConst(other),
+                &[self.index, self.index],
+                &[1.0, 0.0],
+                [0.0; 3],
+            ),
         }
     }
 }
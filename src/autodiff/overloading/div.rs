@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::ops::{Div, DivAssign};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -116,10 +116,11 @@ impl<'v> Div<Variable<'v>> for f64 {
         Variable {
             graph: other.graph,
             value: self / other.value,
-            index: other.graph.push(
-                Arity::Binary,
+            index: other.graph.push_operation(
+                Operation::ConstDiv(self),
                 &[other.index, other.index],
                 &[0.0, -self / (other.value * other.value)],
+                [0.0, 0.0, 2.0 * self / other.value.powi(3)],
             ),
         }
     }
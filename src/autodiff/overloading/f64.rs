@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::ops::Neg;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -54,9 +54,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.abs(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.signum()]),
+            index: self.graph.push_operation(
+                Operation::Abs,
+                &[self.index],
+                &[self.value.signum()],
+                [0.0; 3],
+            ),
         }
     }
 
@@ -80,10 +83,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.acos(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Acos,
                 &[self.index],
                 &[((1.0 - self.value.powi(2)).sqrt()).recip().neg()],
+                [-self.value / (1.0 - self.value.powi(2)).powf(1.5), 0.0, 0.0],
             ),
         }
     }
@@ -108,10 +112,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.acosh(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Acosh,
                 &[self.index],
                 &[((self.value - 1.0).sqrt() * (self.value + 1.0).sqrt()).recip()],
+                [-self.value / (self.value.powi(2) - 1.0).powf(1.5), 0.0, 0.0],
             ),
         }
     }
@@ -138,14 +143,15 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.asin(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Asin,
                 &[self.index],
                 &[if (self.value > -1.0) && (self.value < 1.0) {
                     ((1.0 - self.value.powi(2)).sqrt()).recip()
                 } else {
                     f64::NAN
                 }],
+                [self.value / (1.0 - self.value.powi(2)).powf(1.5), 0.0, 0.0],
             ),
         }
     }
@@ -171,10 +177,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.asinh(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Asinh,
                 &[self.index],
                 &[((1.0 + self.value.powi(2)).sqrt()).recip()],
+                [-self.value / (1.0 + self.value.powi(2)).powf(1.5), 0.0, 0.0],
             ),
         }
     }
@@ -200,10 +207,62 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.atan(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Atan,
                 &[self.index],
                 &[((1.0 + self.value.powi(2)).recip())],
+                [
+                    -2.0 * self.value / (1.0 + self.value.powi(2)).powi(2),
+                    0.0,
+                    0.0,
+                ],
+            ),
+        }
+    }
+
+    /// Four quadrant inverse tangent of `self` (y) and `other` (x).
+    /// d/dy atan2(y, x) = x / (x^2 + y^2)
+    /// d/dx atan2(y, x) = -y / (x^2 + y^2)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` belong to different graphs.
+    ///
+    /// ```
+    /// use RustQuant::assert_approx_equal;
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let y = g.var(1.0);
+    /// let x = g.var(-1.0);
+    /// let z = y.atan2(x);
+    /// let grad = z.accumulate();
+    ///
+    /// assert_approx_equal!(z.value, 3.0 * std::f64::consts::FRAC_PI_4, 1e-15);
+    /// assert_approx_equal!(grad.wrt(&y), -0.5, 1e-15);
+    /// assert_approx_equal!(grad.wrt(&x), -0.5, 1e-15);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn atan2(self, other: Variable<'v>) -> Self {
+        assert!(std::ptr::eq(self.graph, other.graph));
+
+        let (y, x) = (self.value, other.value);
+        let r2 = x.powi(2) + y.powi(2);
+
+        Variable {
+            graph: self.graph,
+            value: y.atan2(x),
+            index: self.graph.push_operation(
+                Operation::Atan2,
+                &[self.index, other.index],
+                &[x / r2, -y / r2],
+                [
+                    -2.0 * x * y / r2.powi(2),
+                    (y.powi(2) - x.powi(2)) / r2.powi(2),
+                    2.0 * x * y / r2.powi(2),
+                ],
             ),
         }
     }
@@ -230,10 +289,15 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.atanh(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Atanh,
                 &[self.index],
                 &[((1.0 - self.value.powi(2)).recip())],
+                [
+                    2.0 * self.value / (1.0 - self.value.powi(2)).powi(2),
+                    0.0,
+                    0.0,
+                ],
             ),
         }
     }
@@ -260,10 +324,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.cbrt(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Cbrt,
                 &[self.index],
                 &[((3.0 * self.value.powf(2.0 / 3.0)).recip())],
+                [-2.0 / (9.0 * self.value.powf(5.0 / 3.0)), 0.0, 0.0],
             ),
         }
     }
@@ -291,9 +356,12 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.cos(),
             // index: self.graph.push_unary(self.index, self.value.sin().neg()),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.sin().neg()]),
+            index: self.graph.push_operation(
+                Operation::Cos,
+                &[self.index],
+                &[self.value.sin().neg()],
+                [self.value.cos().neg(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -319,9 +387,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.cosh(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.sinh()]),
+            index: self.graph.push_operation(
+                Operation::Cosh,
+                &[self.index],
+                &[self.value.sinh()],
+                [self.value.cosh(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -346,9 +417,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.exp(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.exp()]),
+            index: self.graph.push_operation(
+                Operation::Exp,
+                &[self.index],
+                &[self.value.exp()],
+                [self.value.exp(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -374,10 +448,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.exp2(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Exp2,
                 &[self.index],
                 &[2_f64.powf(self.value) * 2_f64.ln()],
+                [self.value.exp2() * std::f64::consts::LN_2.powi(2), 0.0, 0.0],
             ),
         }
     }
@@ -405,9 +480,58 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.exp_m1(),
             // index: self.graph.push_unary(self.index, self.value.exp()),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.exp()]),
+            index: self.graph.push_operation(
+                Operation::ExpM1,
+                &[self.index],
+                &[self.value.exp()],
+                [self.value.exp(), 0.0, 0.0],
+            ),
+        }
+    }
+
+    /// Length of the hypotenuse, sqrt(x^2 + y^2), without overflow or underflow.
+    /// d/dx hypot(x, y) = x / hypot(x, y)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` belong to different graphs.
+    ///
+    /// ```
+    /// use RustQuant::assert_approx_equal;
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(3.0);
+    /// let y = g.var(4.0);
+    /// let z = x.hypot(y);
+    /// let grad = z.accumulate();
+    ///
+    /// assert_approx_equal!(z.value, 5.0, 1e-15);
+    /// assert_approx_equal!(grad.wrt(&x), 0.6, 1e-15);
+    /// assert_approx_equal!(grad.wrt(&y), 0.8, 1e-15);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn hypot(self, other: Variable<'v>) -> Self {
+        assert!(std::ptr::eq(self.graph, other.graph));
+
+        let (x, y) = (self.value, other.value);
+        let h = x.hypot(y);
+
+        Variable {
+            graph: self.graph,
+            value: h,
+            index: self.graph.push_operation(
+                Operation::Hypot,
+                &[self.index, other.index],
+                &[x / h, y / h],
+                [
+                    y.powi(2) / h.powi(3),
+                    -x * y / h.powi(3),
+                    x.powi(2) / h.powi(3),
+                ],
+            ),
         }
     }
 
@@ -432,9 +556,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.ln(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.recip()]),
+            index: self.graph.push_operation(
+                Operation::Ln,
+                &[self.index],
+                &[self.value.recip()],
+                [self.value.powi(2).recip().neg(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -460,9 +587,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.ln_1p(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[(1.0 + self.value).recip()]),
+            index: self.graph.push_operation(
+                Operation::Ln1p,
+                &[self.index],
+                &[(1.0 + self.value).recip()],
+                [(1.0 + self.value).powi(2).recip().neg(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -488,9 +618,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.log10(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.recip()]),
+            index: self.graph.push_operation(
+                Operation::Log10,
+                &[self.index],
+                &[self.value.recip()],
+                [self.value.powi(2).recip().neg(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -516,9 +649,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.log2(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.recip()]),
+            index: self.graph.push_operation(
+                Operation::Log2,
+                &[self.index],
+                &[self.value.recip()],
+                [self.value.powi(2).recip().neg(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -555,10 +691,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.recip(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Recip,
                 &[self.index],
                 &[self.value.powi(2).recip().neg()],
+                [2.0 * self.value.powi(3).recip(), 0.0, 0.0],
             ),
         }
     }
@@ -585,9 +722,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.sin(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.cos()]),
+            index: self.graph.push_operation(
+                Operation::Sin,
+                &[self.index],
+                &[self.value.cos()],
+                [self.value.sin().neg(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -613,9 +753,12 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.sinh(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.cosh()]),
+            index: self.graph.push_operation(
+                Operation::Sinh,
+                &[self.index],
+                &[self.value.cosh()],
+                [self.value.sinh(), 0.0, 0.0],
+            ),
         }
     }
 
@@ -640,10 +783,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.sqrt(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Sqrt,
                 &[self.index],
                 &[(2.0 * self.value.sqrt()).recip()],
+                [(4.0 * self.value.powf(1.5)).recip().neg(), 0.0, 0.0],
             ),
         }
     }
@@ -670,10 +814,15 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.tan(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Tan,
                 &[self.index],
                 &[(self.value.cos().powi(2)).recip()],
+                [
+                    2.0 * self.value.tan() * self.value.cos().powi(2).recip(),
+                    0.0,
+                    0.0,
+                ],
             ),
         }
     }
@@ -700,10 +849,15 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.tanh(),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Tanh,
                 &[self.index],
                 &[(self.value.cosh().powi(2)).recip()],
+                [
+                    -2.0 * self.value.tanh() * self.value.cosh().powi(2).recip(),
+                    0.0,
+                    0.0,
+                ],
             ),
         }
     }
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // OVERLOADING: LOGARITHM
@@ -26,6 +26,9 @@ pub trait Log<T> {
 // f(x,y) = log_{x}(y)
 // df/dx = -ln(y) / (x * ln^2(x))
 // df/dy = 1 / (y * ln(x))
+// d2f/dx2 = ln(y) * (ln(x) + 2) / (x^2 * ln^3(x))
+// d2f/dxdy = -1 / (x * y * ln^2(x))
+// d2f/dy2 = -1 / (y^2 * ln(x))
 impl<'v> Log<Variable<'v>> for Variable<'v> {
     type Output = Variable<'v>;
 
@@ -34,13 +37,19 @@ impl<'v> Log<Variable<'v>> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: f64::log(self.value, base.value),
-            index: self.graph.push(
-                Arity::Binary,
-                &[self.index, base.index],
+            index: self.graph.push_operation(
+                Operation::Log,
+                &[base.index, self.index],
                 &[
                     -f64::ln(self.value) / (base.value * f64::ln(base.value).powi(2)),
                     1.0 / (self.value * f64::ln(base.value)),
                 ],
+                [
+                    f64::ln(self.value) * (f64::ln(base.value) + 2.0)
+                        / (base.value.powi(2) * f64::ln(base.value).powi(3)),
+                    -1.0 / (base.value * self.value * f64::ln(base.value).powi(2)),
+                    -1.0 / (self.value.powi(2) * f64::ln(base.value)),
+                ],
             ),
         }
     }
@@ -58,13 +67,19 @@ impl<'v> Log<Variable<'v>> for f64 {
         Self::Output {
             graph: base.graph,
             value: f64::log(*self, base.value),
-            index: base.graph.push(
-                Arity::Binary,
+            index: base.graph.push_operation(
+                Operation::ConstLog(*self),
                 &[base.index, base.index],
                 &[
                     -f64::ln(*self) / (base.value * f64::ln(base.value).powi(2)),
                     0.0,
                 ],
+                [
+                    f64::ln(*self) * (f64::ln(base.value) + 2.0)
+                        / (base.value.powi(2) * f64::ln(base.value).powi(3)),
+                    0.0,
+                    0.0,
+                ],
             ),
         }
     }
@@ -82,10 +97,11 @@ impl<'v> Log<f64> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: f64::log(self.value, base),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::LogConst(base),
                 &[self.index, self.index],
                 &[0.0, 1.0 / (f64::ln(base) * self.value)],
+                [0.0, 0.0, -1.0 / (f64::ln(base) * self.value.powi(2))],
             ),
         }
     }
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // OVERLOADING: MIN
@@ -33,13 +33,14 @@ impl<'v> Min<Variable<'v>> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: self.value.min(rhs.value),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::Min,
                 &[self.index, rhs.index],
                 &[
                     if self.value < rhs.value { 1.0 } else { 0.0 },
                     if self.value > rhs.value { 1.0 } else { 0.0 },
                 ],
+                [0.0; 3],
             ),
         }
     }
@@ -54,10 +55,11 @@ impl<'v> Min<f64> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: self.value.min(rhs),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::MinConst(rhs),
                 &[self.index, self.index],
                 &[if self.value < rhs { 1.0 } else { 0.0 }, 0.0],
+                [0.0; 3],
             ),
         }
     }
@@ -72,10 +74,11 @@ impl<'v> Min<Variable<'v>> for f64 {
         Self::Output {
             graph: rhs.graph,
             value: f64::min(*self, rhs.value),
-            index: rhs.graph.push(
-                Arity::Binary,
+            index: rhs.graph.push_operation(
+                Operation::ConstMin(*self),
                 &[rhs.index, rhs.index],
                 &[0.0, if self < &rhs.value { 1.0 } else { 0.0 }],
+                [0.0; 3],
             ),
         }
     }
@@ -105,13 +108,14 @@ impl<'v> Max<Variable<'v>> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: self.value.max(rhs.value),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::Max,
                 &[self.index, rhs.index],
                 &[
                     if self.value > rhs.value { 1.0 } else { 0.0 },
                     if self.value < rhs.value { 1.0 } else { 0.0 },
                 ],
+                [0.0; 3],
             ),
         }
     }
@@ -126,10 +130,11 @@ impl<'v> Max<f64> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: self.value.max(rhs),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::MaxConst(rhs),
                 &[self.index, self.index],
                 &[if self.value > rhs { 1.0 } else { 0.0 }, 0.0],
+                [0.0; 3],
             ),
         }
     }
@@ -144,10 +149,11 @@ impl<'v> Max<Variable<'v>> for f64 {
         Self::Output {
             graph: rhs.graph,
             value: f64::max(*self, rhs.value),
-            index: rhs.graph.push(
-                Arity::Binary,
+            index: rhs.graph.push_operation(
+                Operation::ConstMax(*self),
                 &[rhs.index, rhs.index],
                 &[0.0, if self > &rhs.value { 1.0 } else { 0.0 }],
+                [0.0; 3],
             ),
         }
     }
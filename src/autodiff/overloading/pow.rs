@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // OVERLOADING: POWER FUNCTION TRAITS
@@ -32,13 +32,19 @@ impl<'v> Powf<Variable<'v>> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: self.value.powf(other.value),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::Powf,
                 &[self.index, other.index],
                 &[
                     other.value * f64::powf(self.value, other.value - 1.),
                     f64::powf(self.value, other.value) * f64::ln(self.value),
                 ],
+                [
+                    other.value * (other.value - 1.) * f64::powf(self.value, other.value - 2.),
+                    f64::powf(self.value, other.value - 1.)
+                        * (1. + other.value * f64::ln(self.value)),
+                    f64::powf(self.value, other.value) * f64::ln(self.value).powi(2),
+                ],
             ),
         }
     }
@@ -53,10 +59,11 @@ impl<'v> Powf<f64> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: f64::powf(self.value, n),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::PowfConst(n),
                 &[self.index, self.index],
                 &[n * f64::powf(self.value, n - 1.0), 0.0],
+                [n * (n - 1.0) * f64::powf(self.value, n - 2.0), 0.0, 0.0],
             ),
         }
     }
@@ -71,10 +78,15 @@ impl<'v> Powf<Variable<'v>> for f64 {
         Self::Output {
             graph: other.graph,
             value: f64::powf(*self, other.value),
-            index: other.graph.push(
-                Arity::Binary,
+            index: other.graph.push_operation(
+                Operation::ConstPowf(*self),
                 &[other.index, other.index],
-                &[0.0, other.value * f64::powf(*self, other.value - 1.0)],
+                &[0.0, f64::powf(*self, other.value) * f64::ln(*self)],
+                [
+                    0.0,
+                    0.0,
+                    f64::powf(*self, other.value) * f64::ln(*self).powi(2),
+                ],
             ),
         }
     }
@@ -100,13 +112,19 @@ impl<'v> Powi<Variable<'v>> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: self.value.powf(other.value),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::Powf,
                 &[self.index, other.index],
                 &[
                     other.value * f64::powf(self.value, other.value - 1.),
                     f64::powf(self.value, other.value) * f64::ln(self.value),
                 ],
+                [
+                    other.value * (other.value - 1.) * f64::powf(self.value, other.value - 2.),
+                    f64::powf(self.value, other.value - 1.)
+                        * (1. + other.value * f64::ln(self.value)),
+                    f64::powf(self.value, other.value) * f64::ln(self.value).powi(2),
+                ],
             ),
         }
     }
@@ -121,10 +139,15 @@ impl<'v> Powi<i32> for Variable<'v> {
         Self::Output {
             graph: self.graph,
             value: f64::powi(self.value, n),
-            index: self.graph.push(
-                Arity::Binary,
+            index: self.graph.push_operation(
+                Operation::Powi(n),
                 &[self.index, self.index],
                 &[f64::from(n) * f64::powi(self.value, n - 1), 0.0],
+                [
+                    f64::from(n) * f64::from(n - 1) * f64::powi(self.value, n - 2),
+                    0.0,
+                    0.0,
+                ],
             ),
         }
     }
@@ -139,10 +162,15 @@ impl<'v> Powi<Variable<'v>> for f64 {
         Self::Output {
             graph: other.graph,
             value: f64::powf(*self, other.value),
-            index: other.graph.push(
-                Arity::Binary,
+            index: other.graph.push_operation(
+                Operation::ConstPowf(*self),
                 &[other.index, other.index],
-                &[0.0, other.value * f64::powf(*self, other.value - 1.0)],
+                &[0.0, f64::powf(*self, other.value) * f64::ln(*self)],
+                [
+                    0.0,
+                    0.0,
+                    f64::powf(*self, other.value) * f64::ln(*self).powi(2),
+                ],
             ),
         }
     }
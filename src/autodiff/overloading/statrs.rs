@@ -9,8 +9,8 @@
 
 //! Overloading functions from `statrs` crate.
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
-use std::f64::consts::PI;
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
+use std::f64::consts::{PI, SQRT_2};
 use std::ops::Neg;
 
 impl<'v> Variable<'v> {
@@ -38,10 +38,15 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: erf(self.value),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Erf,
                 &[self.index],
                 &[2.0 * self.value.powi(2).neg().exp() / PI.sqrt()],
+                [
+                    -4.0 * self.value * self.value.powi(2).neg().exp() / PI.sqrt(),
+                    0.0,
+                    0.0,
+                ],
             ),
         }
     }
@@ -70,10 +75,51 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: erfc(self.value),
-            index: self.graph.push(
-                Arity::Unary,
+            index: self.graph.push_operation(
+                Operation::Erfc,
                 &[self.index],
                 &[((2.0 * self.value.powi(2).neg().exp()).neg() / PI.sqrt())],
+                [
+                    4.0 * self.value * self.value.powi(2).neg().exp() / PI.sqrt(),
+                    0.0,
+                    0.0,
+                ],
+            ),
+        }
+    }
+
+    /// Inverse of the standard normal CDF (the quantile function).
+    /// d/dp N^-1(p) = 1 / n(N^-1(p)), where n is the standard normal density.
+    ///
+    /// ```
+    /// use RustQuant::assert_approx_equal;
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let p = g.var(0.975);
+    /// let z = p.inv_norm_cdf();
+    /// let grad = z.accumulate();
+    ///
+    /// assert_approx_equal!(z.value,       1.95996398454, 1e-10);
+    /// assert_approx_equal!(grad.wrt(&p), 17.11008308033, 1e-8);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn inv_norm_cdf(self) -> Self {
+        use statrs::function::erf::erfc_inv;
+
+        let value = -SQRT_2 * erfc_inv(2.0 * self.value);
+        let partial = (2.0 * PI).sqrt() * (0.5 * value.powi(2)).exp();
+
+        Variable {
+            graph: self.graph,
+            value,
+            index: self.graph.push_operation(
+                Operation::InvNormCdf,
+                &[self.index],
+                &[partial],
+                [value * partial.powi(2), 0.0, 0.0],
             ),
         }
     }
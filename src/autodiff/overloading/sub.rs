@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::ops::{Add, Neg, Sub, SubAssign};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -115,9 +115,12 @@ impl<'v> Sub<Variable<'v>> for f64 {
         Variable {
             graph: other.graph,
             value: self - other.value,
-            index: other
-                .graph
-                .push(Arity::Binary, &[other.index, other.index], &[0.0, -1.0]),
+            index: other.graph.push_operation(
+                Operation::ConstSub(self),
+                &[other.index, other.index],
+                &[0.0, -1.0],
+                [0.0; 3],
+            ),
         }
     }
 }
@@ -0,0 +1,262 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Numeric trait shared by `f64` and the automatic differentiation types.
+//!
+//! Code written against [`Real`] (arithmetic, also with `f64` constants,
+//! and the elementary functions) can be evaluated with `f64` for a plain
+//! valuation, with `Variable` for reverse mode (adjoint) sensitivities, or
+//! with `Dual`/`HyperDual` for forward mode sensitivities.
+//!
+//! Since `f64` is not generic over `Real`, constants must appear on the
+//! right hand side of binary operations, e.g. `x * 2.0` rather than `2.0 * x`.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Dual, HyperDual, Powf, Powi, Variable};
+use std::f64::consts::{PI, SQRT_2};
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// REAL TRAIT
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Real number: `f64`, or a type that carries derivatives alongside an `f64`.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+///
+/// fn discounted<T: Real>(amount: T, rate: T, time: f64) -> T {
+///     amount * (-rate * time).exp()
+/// }
+///
+/// // Plain valuation.
+/// let pv = discounted(100.0, 0.05, 2.0);
+///
+/// // Adjoint sensitivities.
+/// let g = Graph::new();
+/// let rate = g.var(0.05);
+/// let gradient = discounted(g.var(100.0), rate, 2.0).accumulate();
+///
+/// assert!((gradient.wrt(&rate) + 2.0 * pv).abs() < 1e-12);
+/// ```
+pub trait Real:
+    Copy
+    + Debug
+    + Neg<Output = Self>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Add<f64, Output = Self>
+    + Sub<f64, Output = Self>
+    + Mul<f64, Output = Self>
+    + Div<f64, Output = Self>
+    + Powi<i32, Output = Self>
+    + Powf<f64, Output = Self>
+{
+    /// The value, without any derivative information.
+    fn value(&self) -> f64;
+    /// Absolute value.
+    #[must_use]
+    fn abs(self) -> Self;
+    /// Complementary error function.
+    #[must_use]
+    fn erfc(self) -> Self;
+    /// Exponential function.
+    #[must_use]
+    fn exp(self) -> Self;
+    /// Natural logarithm.
+    #[must_use]
+    fn ln(self) -> Self;
+    /// Reciprocal (inverse), `1 / x`.
+    #[must_use]
+    fn recip(self) -> Self;
+    /// Square root.
+    #[must_use]
+    fn sqrt(self) -> Self;
+
+    /// Standard normal cumulative distribution function.
+    #[must_use]
+    #[inline]
+    fn norm_cdf(self) -> Self {
+        // Same formula as `Gaussian::cdf`, so that `f64` results agree.
+        (-self / SQRT_2).erfc() * 0.5
+    }
+
+    /// Standard normal probability density function.
+    #[must_use]
+    #[inline]
+    fn norm_pdf(self) -> Self {
+        (self.powi(2) * -0.5).exp() / (2.0 * PI).sqrt()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// f64 ^ i32
+impl Powi<i32> for f64 {
+    type Output = f64;
+
+    #[inline]
+    fn powi(&self, n: i32) -> Self::Output {
+        f64::powi(*self, n)
+    }
+}
+
+// f64 ^ f64
+impl Powf<f64> for f64 {
+    type Output = f64;
+
+    #[inline]
+    fn powf(&self, n: f64) -> Self::Output {
+        f64::powf(*self, n)
+    }
+}
+
+impl Real for f64 {
+    #[inline]
+    fn value(&self) -> f64 {
+        *self
+    }
+
+    #[inline]
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    #[inline]
+    fn erfc(self) -> Self {
+        statrs::function::erf::erfc(self)
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+
+    #[inline]
+    fn recip(self) -> Self {
+        f64::recip(self)
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+}
+
+macro_rules! impl_real {
+    ($($t:ty),*) => {
+        $(
+            impl Real for $t {
+                #[inline]
+                fn value(&self) -> f64 {
+                    <$t>::value(self)
+                }
+
+                #[inline]
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                #[inline]
+                fn erfc(self) -> Self {
+                    <$t>::erfc(self)
+                }
+
+                #[inline]
+                fn exp(self) -> Self {
+                    <$t>::exp(self)
+                }
+
+                #[inline]
+                fn ln(self) -> Self {
+                    <$t>::ln(self)
+                }
+
+                #[inline]
+                fn recip(self) -> Self {
+                    <$t>::recip(self)
+                }
+
+                #[inline]
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_real!(Variable<'_>, Dual, HyperDual);
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_real {
+    use crate::assert_approx_equal;
+    use crate::autodiff::*;
+    use crate::statistics::distributions::{Distribution, Gaussian};
+
+    fn f<T: Real>(x: T, y: T) -> T {
+        (x / y).ln().norm_cdf() * x.sqrt() + (y * 2.0).norm_pdf() - x.recip().powi(2)
+    }
+
+    #[test]
+    fn test_f64_matches_gaussian() {
+        let n = Gaussian::default();
+
+        for x in [-3.0, -0.5, 0.0, 0.7, 2.5] {
+            assert_eq!(x.norm_cdf(), n.cdf(x));
+            assert_eq!(x.norm_pdf(), n.pdf(x));
+        }
+    }
+
+    #[test]
+    fn test_modes_agree() {
+        let (x, y) = (1.3, 0.8);
+
+        let value = f(x, y);
+
+        // Reverse mode.
+        let g = Graph::new();
+        let (xv, yv) = (g.var(x), g.var(y));
+        let z = f(xv, yv);
+        let gradient = z.accumulate();
+
+        // Forward mode.
+        let dx = f(Dual::variable(x), Dual::constant(y));
+        let dy = f(Dual::constant(x), Dual::variable(y));
+
+        assert_approx_equal!(z.value, value, 1e-15);
+        assert_approx_equal!(dx.value, value, 1e-15);
+        assert_approx_equal!(gradient.wrt(&xv), dx.tangent, 1e-12);
+        assert_approx_equal!(gradient.wrt(&yv), dy.tangent, 1e-12);
+
+        // Second order.
+        let (v, d, d2) = second_derivative(|x| f(x, HyperDual::constant(y)), x);
+
+        assert_approx_equal!(v, value, 1e-15);
+        assert_approx_equal!(d, dx.tangent, 1e-12);
+        assert!(d2.is_finite());
+    }
+}
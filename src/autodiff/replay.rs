@@ -0,0 +1,612 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! This module contains the serialization and replay of a `Graph`.
+//!
+//! Each `Vertex` records the `Operation` that produced it, so a recorded
+//! graph can be saved (via `serde`), loaded in another process, and
+//! re-evaluated with new input values. The values and partials of every
+//! vertex are recomputed in a single forward sweep, after which the graph
+//! can be differentiated as usual.
+//!
+//! Graphs containing custom operations (see [`Graph::custom`]) can be
+//! serialized, but not replayed, since their partials are not known.
+//!
+//! Note: non-finite partials (e.g. `NaN`) cannot be represented in JSON.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Graph, Operation, Vertex, VertexArena};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use statrs::function::erf::{erf, erfc, erfc_inv};
+use std::cell::{Cell, RefCell};
+use std::f64::consts::{PI, SQRT_2};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// REPLAY
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Error type for replaying a `Graph`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ReplayError {
+    /// The number of input values does not match the number of inputs.
+    #[error("Expected {expected} input values, found {found}.")]
+    InputCount {
+        /// Number of input vertices in the graph.
+        expected: usize,
+        /// Number of input values supplied.
+        found: usize,
+    },
+
+    /// The graph contains a custom operation, which cannot be re-evaluated.
+    #[error("Vertex {index} is a custom operation and cannot be replayed.")]
+    CustomOperation {
+        /// Index of the custom vertex.
+        index: usize,
+    },
+}
+
+impl Graph {
+    /// Returns the indices of the input vertices, in the order
+    /// they were added to the graph.
+    #[must_use]
+    pub fn inputs(&self) -> Vec<usize> {
+        self.vertices
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, vertex)| vertex.operation == Operation::Input)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Replays the graph with new input values.
+    ///
+    /// The values and partials of every vertex are recomputed, and the new
+    /// values are returned (indexed by vertex). `inputs` must contain one
+    /// value per input vertex, in the order given by [`Graph::inputs`].
+    ///
+    /// # Errors
+    ///
+    /// - `ReplayError::InputCount` if the number of input values is wrong.
+    /// - `ReplayError::CustomOperation` if the graph contains a custom operation.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(1.0);
+    /// let y = g.var(2.0);
+    /// let z = x * y.sin();
+    ///
+    /// let values = g.replay(&[3.0, 4.0]).unwrap();
+    /// let z = Variable::new(&g, z.index, values[z.index]);
+    ///
+    /// assert_eq!(z.value, 3.0 * 4.0_f64.sin());
+    /// assert_eq!(z.accumulate().wrt(&x), 4.0_f64.sin());
+    /// ```
+    pub fn replay(&self, inputs: &[f64]) -> Result<Vec<f64>, ReplayError> {
+        let mut vertices = self.vertices.borrow_mut();
+        let mut values = vec![0.0; vertices.len()];
+        let mut inputs = inputs.iter();
+
+        let expected = vertices
+            .iter()
+            .filter(|vertex| vertex.operation == Operation::Input)
+            .count();
+
+        if inputs.len() != expected {
+            return Err(ReplayError::InputCount {
+                expected,
+                found: inputs.len(),
+            });
+        }
+
+        for index in 0..values.len() {
+            let vertex = &mut vertices[index];
+
+            if vertex.operation == Operation::Input {
+                values[index] = *inputs.next().unwrap_or(&0.0);
+                continue;
+            }
+
+            // Unary vertices have their second parent set to themselves,
+            // and this value is never used.
+            let x = values[vertex.parents[0]];
+            let y = values[vertex.parents[1]];
+
+            let (value, partials, second_partials) =
+                evaluate(vertex.operation, x, y).ok_or(ReplayError::CustomOperation { index })?;
+
+            values[index] = value;
+            vertex.partials = partials;
+            vertex.second_partials = second_partials;
+        }
+
+        Ok(values)
+    }
+}
+
+/// Returns the value, partials and second order partials of an operation,
+/// where `x` and `y` are the values of the first and second parents.
+///
+/// These mirror the partials recorded by the overloaded operators.
+#[allow(clippy::too_many_lines)]
+fn evaluate(operation: Operation, x: f64, y: f64) -> Option<(f64, [f64; 2], [f64; 3])> {
+    let unary = |value: f64, partial: f64, second: f64| (value, [partial, 0.0], [second, 0.0, 0.0]);
+    let step = |condition: bool| if condition { 1.0 } else { 0.0 };
+
+    Some(match operation {
+        Operation::Input | Operation::Custom => return None,
+        Operation::Add => (x + y, [1.0, 1.0], [0.0; 3]),
+        Operation::AddConst(c) => (x + c, [1.0, 0.0], [0.0; 3]),
+        Operation::ConstSub(c) => (c - x, [0.0, -1.0], [0.0; 3]),
+        Operation::Mul => (x * y, [y, x], [0.0, 1.0, 0.0]),
+        Operation::MulConst(c) => (x * c, [c, 0.0], [0.0; 3]),
+        Operation::ConstDiv(c) => (c / x, [0.0, -c / (x * x)], [0.0, 0.0, 2.0 * c / x.powi(3)]),
+        Operation::Powf => (
+            x.powf(y),
+            [y * x.powf(y - 1.0), x.powf(y) * x.ln()],
+            [
+                y * (y - 1.0) * x.powf(y - 2.0),
+                x.powf(y - 1.0) * (1.0 + y * x.ln()),
+                x.powf(y) * x.ln().powi(2),
+            ],
+        ),
+        Operation::PowfConst(n) => (
+            x.powf(n),
+            [n * x.powf(n - 1.0), 0.0],
+            [n * (n - 1.0) * x.powf(n - 2.0), 0.0, 0.0],
+        ),
+        Operation::Powi(n) => (
+            x.powi(n),
+            [f64::from(n) * x.powi(n - 1), 0.0],
+            [f64::from(n) * f64::from(n - 1) * x.powi(n - 2), 0.0, 0.0],
+        ),
+        Operation::ConstPowf(c) => (
+            c.powf(x),
+            [0.0, c.powf(x) * c.ln()],
+            [0.0, 0.0, c.powf(x) * c.ln().powi(2)],
+        ),
+        // Parents are ordered as [base, argument].
+        Operation::Log => (
+            y.log(x),
+            [-y.ln() / (x * x.ln().powi(2)), 1.0 / (y * x.ln())],
+            [
+                y.ln() * (x.ln() + 2.0) / (x.powi(2) * x.ln().powi(3)),
+                -1.0 / (x * y * x.ln().powi(2)),
+                -1.0 / (y.powi(2) * x.ln()),
+            ],
+        ),
+        Operation::LogConst(b) => (
+            x.log(b),
+            [0.0, 1.0 / (b.ln() * x)],
+            [0.0, 0.0, -1.0 / (b.ln() * x.powi(2))],
+        ),
+        Operation::ConstLog(c) => (
+            c.log(x),
+            [-c.ln() / (x * x.ln().powi(2)), 0.0],
+            [
+                c.ln() * (x.ln() + 2.0) / (x.powi(2) * x.ln().powi(3)),
+                0.0,
+                0.0,
+            ],
+        ),
+        Operation::Min => (x.min(y), [step(x < y), step(x > y)], [0.0; 3]),
+        Operation::MinConst(c) => (x.min(c), [step(x < c), 0.0], [0.0; 3]),
+        Operation::ConstMin(c) => (c.min(x), [0.0, step(c < x)], [0.0; 3]),
+        Operation::Max => (x.max(y), [step(x > y), step(x < y)], [0.0; 3]),
+        Operation::MaxConst(c) => (x.max(c), [step(x > c), 0.0], [0.0; 3]),
+        Operation::ConstMax(c) => (c.max(x), [0.0, step(c > x)], [0.0; 3]),
+        // Parents are ordered as [y, x].
+        Operation::Atan2 => {
+            let r2 = x * x + y * y;
+
+            (
+                x.atan2(y),
+                [y / r2, -x / r2],
+                [
+                    -2.0 * x * y / r2.powi(2),
+                    (x * x - y * y) / r2.powi(2),
+                    2.0 * x * y / r2.powi(2),
+                ],
+            )
+        }
+        Operation::Hypot => {
+            let h = x.hypot(y);
+
+            (
+                h,
+                [x / h, y / h],
+                [y * y / h.powi(3), -x * y / h.powi(3), x * x / h.powi(3)],
+            )
+        }
+        Operation::Abs => unary(x.abs(), x.signum(), 0.0),
+        Operation::Acos => unary(
+            x.acos(),
+            -(1.0 - x.powi(2)).sqrt().recip(),
+            -x / (1.0 - x.powi(2)).powf(1.5),
+        ),
+        Operation::Acosh => unary(
+            x.acosh(),
+            ((x - 1.0).sqrt() * (x + 1.0).sqrt()).recip(),
+            -x / (x.powi(2) - 1.0).powf(1.5),
+        ),
+        Operation::Asin => unary(
+            x.asin(),
+            if (x > -1.0) && (x < 1.0) {
+                (1.0 - x.powi(2)).sqrt().recip()
+            } else {
+                f64::NAN
+            },
+            x / (1.0 - x.powi(2)).powf(1.5),
+        ),
+        Operation::Asinh => unary(
+            x.asinh(),
+            (1.0 + x.powi(2)).sqrt().recip(),
+            -x / (1.0 + x.powi(2)).powf(1.5),
+        ),
+        Operation::Atan => unary(
+            x.atan(),
+            (1.0 + x.powi(2)).recip(),
+            -2.0 * x / (1.0 + x.powi(2)).powi(2),
+        ),
+        Operation::Atanh => unary(
+            x.atanh(),
+            (1.0 - x.powi(2)).recip(),
+            2.0 * x / (1.0 - x.powi(2)).powi(2),
+        ),
+        Operation::Cbrt => unary(
+            x.cbrt(),
+            (3.0 * x.powf(2.0 / 3.0)).recip(),
+            -2.0 / (9.0 * x.powf(5.0 / 3.0)),
+        ),
+        Operation::Cos => unary(x.cos(), -x.sin(), -x.cos()),
+        Operation::Cosh => unary(x.cosh(), x.sinh(), x.cosh()),
+        Operation::Erf => unary(
+            erf(x),
+            2.0 * (-x.powi(2)).exp() / PI.sqrt(),
+            -4.0 * x * (-x.powi(2)).exp() / PI.sqrt(),
+        ),
+        Operation::Erfc => unary(
+            erfc(x),
+            -(2.0 * (-x.powi(2)).exp()) / PI.sqrt(),
+            4.0 * x * (-x.powi(2)).exp() / PI.sqrt(),
+        ),
+        Operation::InvNormCdf => {
+            let value = -SQRT_2 * erfc_inv(2.0 * x);
+            let partial = (2.0 * PI).sqrt() * (0.5 * value * value).exp();
+
+            unary(value, partial, value * partial * partial)
+        }
+        Operation::Exp => unary(x.exp(), x.exp(), x.exp()),
+        Operation::Exp2 => unary(
+            x.exp2(),
+            2_f64.powf(x) * 2_f64.ln(),
+            x.exp2() * std::f64::consts::LN_2.powi(2),
+        ),
+        Operation::ExpM1 => unary(x.exp_m1(), x.exp(), x.exp()),
+        Operation::Ln => unary(x.ln(), x.recip(), -x.powi(2).recip()),
+        Operation::Ln1p => unary(x.ln_1p(), (1.0 + x).recip(), -(1.0 + x).powi(2).recip()),
+        Operation::Log10 => unary(x.log10(), x.recip(), -x.powi(2).recip()),
+        Operation::Log2 => unary(x.log2(), x.recip(), -x.powi(2).recip()),
+        Operation::Recip => unary(x.recip(), -x.powi(2).recip(), 2.0 * x.powi(3).recip()),
+        Operation::Sin => unary(x.sin(), x.cos(), -x.sin()),
+        Operation::Sinh => unary(x.sinh(), x.cosh(), x.sinh()),
+        Operation::Sqrt => unary(
+            x.sqrt(),
+            (2.0 * x.sqrt()).recip(),
+            -(4.0 * x.powf(1.5)).recip(),
+        ),
+        Operation::Tan => unary(
+            x.tan(),
+            x.cos().powi(2).recip(),
+            2.0 * x.tan() * x.cos().powi(2).recip(),
+        ),
+        Operation::Tanh => unary(
+            x.tanh(),
+            x.cosh().powi(2).recip(),
+            -2.0 * x.tanh() * x.cosh().powi(2).recip(),
+        ),
+    })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// SERIALIZATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Operation {
+    /// Tag used to serialize the operation: its name and constant operand.
+    fn tag(self) -> (&'static str, Option<f64>) {
+        match self {
+            Self::Input => ("Input", None),
+            Self::Custom => ("Custom", None),
+            Self::Add => ("Add", None),
+            Self::AddConst(c) => ("AddConst", Some(c)),
+            Self::ConstSub(c) => ("ConstSub", Some(c)),
+            Self::Mul => ("Mul", None),
+            Self::MulConst(c) => ("MulConst", Some(c)),
+            Self::ConstDiv(c) => ("ConstDiv", Some(c)),
+            Self::Powf => ("Powf", None),
+            Self::PowfConst(c) => ("PowfConst", Some(c)),
+            Self::Powi(n) => ("Powi", Some(f64::from(n))),
+            Self::ConstPowf(c) => ("ConstPowf", Some(c)),
+            Self::Log => ("Log", None),
+            Self::LogConst(c) => ("LogConst", Some(c)),
+            Self::ConstLog(c) => ("ConstLog", Some(c)),
+            Self::Min => ("Min", None),
+            Self::MinConst(c) => ("MinConst", Some(c)),
+            Self::ConstMin(c) => ("ConstMin", Some(c)),
+            Self::Max => ("Max", None),
+            Self::MaxConst(c) => ("MaxConst", Some(c)),
+            Self::ConstMax(c) => ("ConstMax", Some(c)),
+            Self::Atan2 => ("Atan2", None),
+            Self::Hypot => ("Hypot", None),
+            Self::Abs => ("Abs", None),
+            Self::Acos => ("Acos", None),
+            Self::Acosh => ("Acosh", None),
+            Self::Asin => ("Asin", None),
+            Self::Asinh => ("Asinh", None),
+            Self::Atan => ("Atan", None),
+            Self::Atanh => ("Atanh", None),
+            Self::Cbrt => ("Cbrt", None),
+            Self::Cos => ("Cos", None),
+            Self::Cosh => ("Cosh", None),
+            Self::Erf => ("Erf", None),
+            Self::Erfc => ("Erfc", None),
+            Self::InvNormCdf => ("InvNormCdf", None),
+            Self::Exp => ("Exp", None),
+            Self::Exp2 => ("Exp2", None),
+            Self::ExpM1 => ("ExpM1", None),
+            Self::Ln => ("Ln", None),
+            Self::Ln1p => ("Ln1p", None),
+            Self::Log10 => ("Log10", None),
+            Self::Log2 => ("Log2", None),
+            Self::Recip => ("Recip", None),
+            Self::Sin => ("Sin", None),
+            Self::Sinh => ("Sinh", None),
+            Self::Sqrt => ("Sqrt", None),
+            Self::Tan => ("Tan", None),
+            Self::Tanh => ("Tanh", None),
+        }
+    }
+
+    /// Inverse of `Operation::tag`.
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_tag(name: &str, constant: Option<f64>) -> Option<Self> {
+        Some(match (name, constant) {
+            ("Input", None) => Self::Input,
+            ("Custom", None) => Self::Custom,
+            ("Add", None) => Self::Add,
+            ("AddConst", Some(c)) => Self::AddConst(c),
+            ("ConstSub", Some(c)) => Self::ConstSub(c),
+            ("Mul", None) => Self::Mul,
+            ("MulConst", Some(c)) => Self::MulConst(c),
+            ("ConstDiv", Some(c)) => Self::ConstDiv(c),
+            ("Powf", None) => Self::Powf,
+            ("PowfConst", Some(c)) => Self::PowfConst(c),
+            ("Powi", Some(n)) => Self::Powi(n as i32),
+            ("ConstPowf", Some(c)) => Self::ConstPowf(c),
+            ("Log", None) => Self::Log,
+            ("LogConst", Some(c)) => Self::LogConst(c),
+            ("ConstLog", Some(c)) => Self::ConstLog(c),
+            ("Min", None) => Self::Min,
+            ("MinConst", Some(c)) => Self::MinConst(c),
+            ("ConstMin", Some(c)) => Self::ConstMin(c),
+            ("Max", None) => Self::Max,
+            ("MaxConst", Some(c)) => Self::MaxConst(c),
+            ("ConstMax", Some(c)) => Self::ConstMax(c),
+            ("Atan2", None) => Self::Atan2,
+            ("Hypot", None) => Self::Hypot,
+            ("Abs", None) => Self::Abs,
+            ("Acos", None) => Self::Acos,
+            ("Acosh", None) => Self::Acosh,
+            ("Asin", None) => Self::Asin,
+            ("Asinh", None) => Self::Asinh,
+            ("Atan", None) => Self::Atan,
+            ("Atanh", None) => Self::Atanh,
+            ("Cbrt", None) => Self::Cbrt,
+            ("Cos", None) => Self::Cos,
+            ("Cosh", None) => Self::Cosh,
+            ("Erf", None) => Self::Erf,
+            ("Erfc", None) => Self::Erfc,
+            ("InvNormCdf", None) => Self::InvNormCdf,
+            ("Exp", None) => Self::Exp,
+            ("Exp2", None) => Self::Exp2,
+            ("ExpM1", None) => Self::ExpM1,
+            ("Ln", None) => Self::Ln,
+            ("Ln1p", None) => Self::Ln1p,
+            ("Log10", None) => Self::Log10,
+            ("Log2", None) => Self::Log2,
+            ("Recip", None) => Self::Recip,
+            ("Sin", None) => Self::Sin,
+            ("Sinh", None) => Self::Sinh,
+            ("Sqrt", None) => Self::Sqrt,
+            ("Tan", None) => Self::Tan,
+            ("Tanh", None) => Self::Tanh,
+            _ => return None,
+        })
+    }
+}
+
+/// Serialized form of a vertex:
+/// `(operation, constant, parents, partials, second_partials)`.
+type SerializedVertex = (String, Option<f64>, [usize; 2], [f64; 2], [f64; 3]);
+
+impl Serialize for Vertex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, constant) = self.operation.tag();
+
+        (
+            name,
+            constant,
+            self.parents,
+            self.partials,
+            self.second_partials,
+        )
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Vertex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (name, constant, parents, partials, second_partials) =
+            SerializedVertex::deserialize(deserializer)?;
+
+        let operation = Operation::from_tag(&name, constant)
+            .ok_or_else(|| D::Error::custom(format!("unknown operation: {name}")))?;
+
+        Ok(Vertex {
+            partials,
+            parents,
+            second_partials,
+            operation,
+        })
+    }
+}
+
+impl Serialize for Graph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.vertices.borrow().iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Graph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vertices = Vec::<Vertex>::deserialize(deserializer)?;
+
+        if let Some(index) =
+            (0..vertices.len()).find(|&i| vertices[i].parents.iter().any(|&parent| parent > i))
+        {
+            return Err(D::Error::custom(format!(
+                "vertex {index} refers to a later vertex"
+            )));
+        }
+
+        let mut arena = VertexArena::with_capacity(vertices.len());
+        arena.extend(vertices);
+
+        Ok(Graph {
+            vertices: RefCell::new(arena),
+            paused: Cell::new(0),
+            hook: RefCell::new(None),
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_replay {
+    use crate::{assert_approx_equal, autodiff::*};
+
+    // Exercises every replayable operation.
+    fn f<'v>(x: Variable<'v>, y: Variable<'v>) -> Variable<'v> {
+        let a = x * y + 2.0 - x / y + 3.0 / y - 1.5 * x.powf(y) + x.powi(3) + x.powf(0.5);
+        let b = Powf::powf(&2.0, y) + x.log(y) + x.log(3.0) + Log::log(&5.0, y);
+        let c = Min::min(&x, y) + Min::min(&x, 0.7) + Min::min(&0.2, y);
+        let d = Max::max(&x, y) + Max::max(&x, 0.7) + Max::max(&0.2, y);
+        let e = x.abs() + (x / 2.0).acos() + (y + 1.0).acosh() + (x / 2.0).asin() + x.asinh();
+        let h = x.atan() + (x / 2.0).atanh() + x.cbrt() + x.cos() + x.cosh() + x.erf() + x.erfc();
+        let k = x.atan2(y) + x.hypot(y) + (x / 2.0).inv_norm_cdf();
+        let i = x.exp() + x.exp2() + x.exp_m1() + x.ln() + x.ln_1p() + x.log10() + x.log2();
+        let j = x.recip() + x.sin() + x.sinh() + x.sqrt() + x.tan() + x.tanh() - y;
+
+        a * b + c * d + e * h + i * j + k
+    }
+
+    #[test]
+    fn test_replay_matches_recording() {
+        let g = Graph::new();
+        let _ = f(g.var(0.6), g.var(1.4));
+
+        let h = Graph::new();
+        let x = h.var(0.9);
+        let y = h.var(1.1);
+        let expected = f(x, y);
+
+        let values = g.replay(&[0.9, 1.1]).unwrap();
+
+        assert_eq!(g.len(), h.len());
+        assert_approx_equal!(values[g.len() - 1], expected.value, 1e-12);
+
+        let g_vertices = g.vertices.borrow();
+        let h_vertices = h.vertices.borrow();
+
+        for (a, b) in g_vertices.iter().zip(h_vertices.iter()) {
+            assert_eq!(a.operation, b.operation);
+            assert_eq!(a.parents, b.parents);
+
+            for k in 0..2 {
+                assert_approx_equal!(a.partials[k], b.partials[k], 1e-12);
+            }
+            for k in 0..3 {
+                assert_approx_equal!(a.second_partials[k], b.second_partials[k], 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialize_and_replay() {
+        let g = Graph::new();
+        let x = g.var(100.0);
+        let v = g.var(0.2);
+        let _ = Max::max(&(x * (v * 0.5).exp() - 100.0), 0.0);
+
+        let json = serde_json::to_string(&g).unwrap();
+        let h: Graph = serde_json::from_str(&json).unwrap();
+
+        for (a, b) in g.vertices.borrow().iter().zip(h.vertices.borrow().iter()) {
+            assert_eq!(a.operation, b.operation);
+            assert_eq!(a.parents, b.parents);
+            assert_approx_equal!(a.partials[0], b.partials[0], 1e-12);
+        }
+
+        // Re-evaluate the deserialized graph with new inputs.
+        assert_eq!(h.inputs(), vec![0, 1]);
+
+        let values = h.replay(&[110.0, 0.3]).unwrap();
+        let output = Variable::new(&h, h.len() - 1, values[h.len() - 1]);
+        let grad = output.accumulate();
+
+        assert_approx_equal!(output.value, 110.0 * 0.15_f64.exp() - 100.0, 1e-10);
+        assert_approx_equal!(grad[0], 0.15_f64.exp(), 1e-12);
+        assert_approx_equal!(grad[1], 55.0 * 0.15_f64.exp(), 1e-10);
+    }
+
+    #[test]
+    fn test_replay_errors() {
+        let g = Graph::new();
+        let x = g.var(1.0);
+        let y = x.sin();
+
+        assert_eq!(
+            g.replay(&[1.0, 2.0]),
+            Err(ReplayError::InputCount {
+                expected: 1,
+                found: 2
+            })
+        );
+
+        let _ = g.custom_unary(y, 0.0, 1.0);
+
+        assert_eq!(
+            g.replay(&[1.0]),
+            Err(ReplayError::CustomOperation { index: 2 })
+        );
+
+        assert!(serde_json::from_str::<Graph>(r#"[["Foo",null,[0,0],[0,0],[0,0,0]]]"#).is_err());
+        assert!(serde_json::from_str::<Graph>(r#"[["Sin",null,[1,0],[0,0],[0,0,0]]]"#).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Statistics and profiling hooks for the `Graph`.
+//!
+//! [`Graph::stats`] summarises the recorded graph (size, memory footprint,
+//! depth, and the number of vertices per arity and per operation), and
+//! [`Graph::set_hook`] installs a callback that is invoked every time a
+//! vertex is pushed, e.g. to attribute vertices to the parts of a pricer
+//! that recorded them.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Graph, Vertex};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Summary statistics of a `Graph`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphStats {
+    /// Number of vertices.
+    pub vertices: usize,
+    /// Memory used by the recorded vertices, in bytes.
+    pub bytes: usize,
+    /// Memory allocated for vertices (including spare capacity), in bytes.
+    pub allocated_bytes: usize,
+    /// Length of the longest chain of operations from an input to any vertex.
+    pub depth: usize,
+    /// Number of vertices with no parents (inputs and constants).
+    pub nullary: usize,
+    /// Number of vertices with one parent.
+    pub unary: usize,
+    /// Number of vertices with two parents.
+    pub binary: usize,
+    /// Number of vertices per operation name (see [`Operation::name`](crate::autodiff::Operation::name)).
+    pub operations: BTreeMap<&'static str, usize>,
+}
+
+/// Callback invoked with the index and contents of each vertex pushed to a
+/// `Graph`. See [`Graph::set_hook`].
+#[derive(Clone)]
+pub struct PushHook(pub(crate) Arc<HookFn>);
+
+type HookFn = dyn Fn(usize, &Vertex) + Send + Sync;
+
+impl fmt::Debug for PushHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PushHook")
+    }
+}
+
+impl Graph {
+    /// Returns summary statistics of the graph.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    ///
+    /// let g = Graph::new();
+    ///
+    /// let x = g.var(1.0);
+    /// let y = g.var(2.0);
+    /// let _z = (x * y).sin().exp();
+    ///
+    /// let stats = g.stats();
+    ///
+    /// assert_eq!(stats.vertices, 5);
+    /// assert_eq!((stats.nullary, stats.unary, stats.binary), (2, 2, 1));
+    /// assert_eq!(stats.depth, 3);
+    /// assert_eq!(stats.operations["sin"], 1);
+    /// ```
+    #[must_use]
+    pub fn stats(&self) -> GraphStats {
+        let vertices = self.vertices.borrow();
+
+        let mut stats = GraphStats {
+            vertices: vertices.len(),
+            bytes: vertices.len() * std::mem::size_of::<Vertex>(),
+            allocated_bytes: vertices.capacity() * std::mem::size_of::<Vertex>(),
+            depth: 0,
+            nullary: 0,
+            unary: 0,
+            binary: 0,
+            operations: BTreeMap::new(),
+        };
+
+        let mut depths = Vec::with_capacity(vertices.len());
+
+        for (index, vertex) in vertices.iter().enumerate() {
+            // Self-references are placeholders for missing parents.
+            let parents = vertex.parents.iter().filter(|&&parent| parent != index);
+
+            let depth = parents
+                .clone()
+                .map(|&parent| depths[parent] + 1)
+                .max()
+                .unwrap_or(0);
+
+            match parents.count() {
+                0 => stats.nullary += 1,
+                1 => stats.unary += 1,
+                _ => stats.binary += 1,
+            }
+
+            *stats.operations.entry(vertex.operation.name()).or_insert(0) += 1;
+
+            stats.depth = stats.depth.max(depth);
+            depths.push(depth);
+        }
+
+        stats
+    }
+
+    /// Installs a callback that is invoked with the index and contents of
+    /// each vertex pushed to the graph, replacing any existing callback.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let g = Graph::new();
+    /// let x = g.var(1.0);
+    ///
+    /// let count = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&count);
+    ///
+    /// g.set_hook(move |_, _| {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    ///
+    /// let _y = x.exp() * x;
+    ///
+    /// assert_eq!(count.load(Ordering::Relaxed), 2);
+    /// ```
+    pub fn set_hook<F>(&self, hook: F)
+    where
+        F: Fn(usize, &Vertex) + Send + Sync + 'static,
+    {
+        *self.hook.borrow_mut() = Some(PushHook(Arc::new(hook)));
+    }
+
+    /// Removes the callback installed with [`Graph::set_hook`].
+    #[inline]
+    pub fn clear_hook(&self) {
+        *self.hook.borrow_mut() = None;
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_stats {
+    use crate::autodiff::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_stats() {
+        let g = Graph::new();
+
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+
+        // Depth increases by one per operation along the longest chain.
+        let mut z = x * y;
+        for _ in 0..10 {
+            z = z.exp() + y;
+        }
+
+        let stats = g.stats();
+
+        assert_eq!(stats.vertices, g.len());
+        assert_eq!(stats.nullary, 2);
+        assert_eq!(stats.unary, 10);
+        assert_eq!(stats.binary, 11);
+        assert_eq!(stats.depth, 21);
+        assert_eq!(stats.bytes, g.len() * std::mem::size_of::<Vertex>());
+        assert!(stats.allocated_bytes >= stats.bytes);
+        assert_eq!(stats.operations["input"], 2);
+        assert_eq!(stats.operations["mul"], 1);
+        assert_eq!(stats.operations["exp"], 10);
+        assert_eq!(stats.operations["add"], 10);
+
+        g.clear();
+        assert_eq!(g.stats().depth, 0);
+        assert_eq!(g.stats().vertices, 0);
+    }
+
+    #[test]
+    fn test_hook_profiles_sections() {
+        let g = Graph::new();
+
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+
+        // Attribute each recorded vertex to the current section of the pricer.
+        let section = Arc::new(Mutex::new("setup"));
+        let profile = Arc::new(Mutex::new(Vec::new()));
+
+        {
+            let section = Arc::clone(&section);
+            let profile = Arc::clone(&profile);
+
+            g.set_hook(move |index, vertex| {
+                profile
+                    .lock()
+                    .unwrap()
+                    .push((*section.lock().unwrap(), index, vertex.operation));
+            });
+        }
+
+        *section.lock().unwrap() = "discount";
+        let df = (-x).exp();
+
+        *section.lock().unwrap() = "payoff";
+        let _v = Max::max(&(y - 1.5), 0.0) * df;
+
+        g.clear_hook();
+        let _w = x + y;
+
+        let profile = profile.lock().unwrap();
+
+        assert_eq!(profile.len(), 5);
+        assert_eq!(profile.iter().filter(|p| p.0 == "discount").count(), 2);
+        assert_eq!(profile.iter().filter(|p| p.0 == "payoff").count(), 3);
+        assert_eq!(profile[1], ("discount", 3, Operation::Exp));
+        assert_eq!(profile[2].2, Operation::AddConst(-1.5));
+    }
+}
@@ -0,0 +1,629 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! This module contains vectorized (batched) variables.
+//!
+//! An `ArrayVariable` wraps an `Array1<f64>`, and each elementwise operation
+//! on it records a **single** vertex in an `ArrayGraph`, with vector partials.
+//! For example, simulating 100,000 Monte Carlo paths records one vertex per
+//! arithmetic operation, rather than 100,000.
+//!
+//! Scalars are arrays of length one, and are broadcast against arrays of any
+//! length. The reverse pass reduces (sums) the adjoints accordingly, so the
+//! derivative of e.g. the mean payoff *with-respect-to* a scalar parameter is
+//! obtained in a single batched sweep.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Accumulate, Gradient};
+use ndarray::Array1;
+use std::cell::RefCell;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Vertex of an `ArrayGraph`.
+///
+/// The partials are arrays that are broadcast (elementwise) against the
+/// adjoint of the vertex. Unused parents point to the vertex itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayVertex {
+    /// Array that contains the (elementwise) partial derivatives wrt to x and y.
+    pub partials: [Array1<f64>; 2],
+    /// Array that contains the indices of the parent vertices.
+    pub parents: [usize; 2],
+    /// Length of the value of the vertex.
+    pub len: usize,
+}
+
+/// Graph (Wengert list) of vectorized operations.
+#[derive(Debug, Clone, Default)]
+pub struct ArrayGraph {
+    /// Vector containing the vertices in the Wengert List.
+    pub vertices: RefCell<Vec<ArrayVertex>>,
+}
+
+/// A vector-valued variable, recorded in an `ArrayGraph`.
+#[derive(Debug, Clone)]
+pub struct ArrayVariable<'v> {
+    /// Pointer to the graph.
+    pub graph: &'v ArrayGraph,
+    /// Index to the vertex.
+    pub index: usize,
+    /// Value associated to the vertex.
+    pub value: Array1<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Array of length one.
+fn scalar(value: f64) -> Array1<f64> {
+    Array1::from_elem(1, value)
+}
+
+impl ArrayGraph {
+    /// Instantiate a new graph.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            vertices: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Add a new (vector) input variable to the graph.
+    #[inline]
+    pub fn var(&self, value: Array1<f64>) -> ArrayVariable<'_> {
+        ArrayVariable {
+            graph: self,
+            index: self.push(value.len(), []),
+            value,
+        }
+    }
+
+    /// Add a new scalar input variable (an array of length one) to the graph.
+    #[inline]
+    pub fn scalar(&self, value: f64) -> ArrayVariable<'_> {
+        self.var(scalar(value))
+    }
+
+    /// Returns the number of vertices in the graph.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vertices.borrow().len()
+    }
+
+    /// Returns true if the graph contains no vertices.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.borrow().is_empty()
+    }
+
+    /// Clears the entire graph.
+    #[inline]
+    pub fn clear(&self) {
+        self.vertices.borrow_mut().clear();
+    }
+
+    /// Pushes a vertex, with a value of length `len`, to the graph.
+    /// Each edge is a `(parent, partial)` pair.
+    #[inline]
+    pub fn push<const N: usize>(&self, len: usize, edges: [(usize, Array1<f64>); N]) -> usize {
+        let mut vertices = self.vertices.borrow_mut();
+        let index = vertices.len();
+
+        let mut vertex = ArrayVertex {
+            partials: [Array1::zeros(0), Array1::zeros(0)],
+            parents: [index; 2],
+            len,
+        };
+
+        for (k, (parent, partial)) in edges.into_iter().enumerate() {
+            vertex.parents[k] = parent;
+            vertex.partials[k] = partial;
+        }
+
+        vertices.push(vertex);
+
+        index
+    }
+}
+
+impl ArrayVariable<'_> {
+    /// Returns the length of the value.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Returns true if the value is empty.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Records an elementwise unary operation.
+    #[inline]
+    fn unary(&self, value: Array1<f64>, partial: Array1<f64>) -> Self {
+        Self {
+            graph: self.graph,
+            index: self.graph.push(value.len(), [(self.index, partial)]),
+            value,
+        }
+    }
+
+    /// Records an elementwise binary operation.
+    #[inline]
+    fn binary(&self, other: &Self, value: Array1<f64>, dx: Array1<f64>, dy: Array1<f64>) -> Self {
+        assert!(std::ptr::eq(self.graph, other.graph));
+
+        Self {
+            graph: self.graph,
+            index: self
+                .graph
+                .push(value.len(), [(self.index, dx), (other.index, dy)]),
+            value,
+        }
+    }
+
+    /// Absolute value function.
+    /// d/dx abs(x) = sign(x)
+    #[must_use]
+    #[inline]
+    pub fn abs(&self) -> Self {
+        self.unary(self.value.mapv(f64::abs), self.value.mapv(f64::signum))
+    }
+
+    /// Cosine function.
+    /// d/dx cos(x) = -sin(x)
+    #[must_use]
+    #[inline]
+    pub fn cos(&self) -> Self {
+        self.unary(self.value.mapv(f64::cos), self.value.mapv(|x| -x.sin()))
+    }
+
+    /// Exponential function (base *e*).
+    /// d/dx exp(x) = exp(x)
+    #[must_use]
+    #[inline]
+    pub fn exp(&self) -> Self {
+        let value = self.value.mapv(f64::exp);
+        self.unary(value.clone(), value)
+    }
+
+    /// Natural logarithm.
+    /// d/dx ln(x) = 1 / x
+    #[must_use]
+    #[inline]
+    pub fn ln(&self) -> Self {
+        self.unary(self.value.mapv(f64::ln), self.value.mapv(f64::recip))
+    }
+
+    /// Power function with a real exponent.
+    /// d/dx x^n = n * x^(n - 1)
+    #[must_use]
+    #[inline]
+    pub fn powf(&self, n: f64) -> Self {
+        self.unary(
+            self.value.mapv(|x| x.powf(n)),
+            self.value.mapv(|x| n * x.powf(n - 1.0)),
+        )
+    }
+
+    /// Power function with an integer exponent.
+    /// d/dx x^n = n * x^(n - 1)
+    #[must_use]
+    #[inline]
+    pub fn powi(&self, n: i32) -> Self {
+        self.unary(
+            self.value.mapv(|x| x.powi(n)),
+            self.value.mapv(|x| f64::from(n) * x.powi(n - 1)),
+        )
+    }
+
+    /// Sine function.
+    /// d/dx sin(x) = cos(x)
+    #[must_use]
+    #[inline]
+    pub fn sin(&self) -> Self {
+        self.unary(self.value.mapv(f64::sin), self.value.mapv(f64::cos))
+    }
+
+    /// Square root function.
+    /// d/dx sqrt(x) = 1 / (2 * sqrt(x))
+    #[must_use]
+    #[inline]
+    pub fn sqrt(&self) -> Self {
+        let value = self.value.mapv(f64::sqrt);
+        let partial = value.mapv(|x| 0.5 / x);
+        self.unary(value, partial)
+    }
+
+    /// Elementwise maximum with a constant, e.g. a call payoff `max(S - K, 0)`.
+    #[must_use]
+    #[inline]
+    pub fn max(&self, c: f64) -> Self {
+        self.unary(
+            self.value.mapv(|x| x.max(c)),
+            self.value.mapv(|x| if x > c { 1.0 } else { 0.0 }),
+        )
+    }
+
+    /// Elementwise minimum with a constant.
+    #[must_use]
+    #[inline]
+    pub fn min(&self, c: f64) -> Self {
+        self.unary(
+            self.value.mapv(|x| x.min(c)),
+            self.value.mapv(|x| if x < c { 1.0 } else { 0.0 }),
+        )
+    }
+
+    /// Sum of the elements (a scalar).
+    #[must_use]
+    #[inline]
+    pub fn sum(&self) -> Self {
+        self.unary(scalar(self.value.sum()), scalar(1.0))
+    }
+
+    /// Mean of the elements (a scalar).
+    #[must_use]
+    #[inline]
+    pub fn mean(&self) -> Self {
+        let n = self.len() as f64;
+        self.unary(scalar(self.value.sum() / n), scalar(n.recip()))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OPERATOR OVERLOADING
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<'v> Add<&ArrayVariable<'v>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn add(self, other: &ArrayVariable<'v>) -> Self::Output {
+        self.binary(other, &self.value + &other.value, scalar(1.0), scalar(1.0))
+    }
+}
+
+impl<'v> Sub<&ArrayVariable<'v>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn sub(self, other: &ArrayVariable<'v>) -> Self::Output {
+        self.binary(other, &self.value - &other.value, scalar(1.0), scalar(-1.0))
+    }
+}
+
+impl<'v> Mul<&ArrayVariable<'v>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn mul(self, other: &ArrayVariable<'v>) -> Self::Output {
+        self.binary(
+            other,
+            &self.value * &other.value,
+            other.value.clone(),
+            self.value.clone(),
+        )
+    }
+}
+
+impl<'v> Div<&ArrayVariable<'v>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn div(self, other: &ArrayVariable<'v>) -> Self::Output {
+        let recip = other.value.mapv(f64::recip);
+        let value = &self.value * &recip;
+        let dy = -&value * &recip;
+
+        self.binary(other, value, recip, dy)
+    }
+}
+
+impl<'v> Add<f64> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn add(self, other: f64) -> Self::Output {
+        self.unary(&self.value + other, scalar(1.0))
+    }
+}
+
+impl<'v> Sub<f64> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn sub(self, other: f64) -> Self::Output {
+        self.unary(&self.value - other, scalar(1.0))
+    }
+}
+
+impl<'v> Mul<f64> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn mul(self, other: f64) -> Self::Output {
+        self.unary(&self.value * other, scalar(other))
+    }
+}
+
+impl<'v> Div<f64> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn div(self, other: f64) -> Self::Output {
+        self.unary(&self.value / other, scalar(other.recip()))
+    }
+}
+
+impl<'v> Add<&ArrayVariable<'v>> for f64 {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn add(self, other: &ArrayVariable<'v>) -> Self::Output {
+        other + self
+    }
+}
+
+impl<'v> Sub<&ArrayVariable<'v>> for f64 {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn sub(self, other: &ArrayVariable<'v>) -> Self::Output {
+        other.unary(self - &other.value, scalar(-1.0))
+    }
+}
+
+impl<'v> Mul<&ArrayVariable<'v>> for f64 {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn mul(self, other: &ArrayVariable<'v>) -> Self::Output {
+        other * self
+    }
+}
+
+impl<'v> Div<&ArrayVariable<'v>> for f64 {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn div(self, other: &ArrayVariable<'v>) -> Self::Output {
+        let value = self / &other.value;
+        let partial = -&value / &other.value;
+
+        other.unary(value, partial)
+    }
+}
+
+impl<'v> Add<&Array1<f64>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn add(self, other: &Array1<f64>) -> Self::Output {
+        self.unary(&self.value + other, scalar(1.0))
+    }
+}
+
+impl<'v> Sub<&Array1<f64>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn sub(self, other: &Array1<f64>) -> Self::Output {
+        self.unary(&self.value - other, scalar(1.0))
+    }
+}
+
+impl<'v> Mul<&Array1<f64>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn mul(self, other: &Array1<f64>) -> Self::Output {
+        self.unary(&self.value * other, other.clone())
+    }
+}
+
+impl<'v> Div<&Array1<f64>> for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: &Array1<f64>) -> Self::Output {
+        let recip = other.mapv(f64::recip);
+        self.unary(&self.value * &recip, recip)
+    }
+}
+
+impl<'v> Neg for &ArrayVariable<'v> {
+    type Output = ArrayVariable<'v>;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self * -1.0
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// REVERSE ACCUMULATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Accumulate<Vec<Array1<f64>>> for ArrayVariable<'_> {
+    /// Batched reverse pass: the adjoint of each vertex is an array.
+    ///
+    /// If the variable is not a scalar, every element is seeded with one,
+    /// so the result is the gradient of the sum of the elements.
+    #[inline]
+    fn accumulate(&self) -> Vec<Array1<f64>> {
+        let vertices = self.graph.vertices.borrow();
+
+        let mut adjoints: Vec<Array1<f64>> = vertices
+            .iter()
+            .map(|vertex| Array1::zeros(vertex.len))
+            .collect();
+        adjoints[self.index].fill(1.0); // SEED
+
+        for index in (0..=self.index).rev() {
+            let vertex = &vertices[index];
+            let adjoint = std::mem::take(&mut adjoints[index]);
+
+            for k in 0..2 {
+                let parent = vertex.parents[k];
+
+                if parent == index {
+                    continue;
+                }
+
+                let contribution = &vertex.partials[k] * &adjoint;
+                let target = &mut adjoints[parent];
+
+                // Reduce the contribution if the parent was broadcast.
+                if target.len() == 1 && contribution.len() > 1 {
+                    target[0] += contribution.sum();
+                } else {
+                    *target += &contribution;
+                }
+            }
+
+            adjoints[index] = adjoint;
+        }
+
+        adjoints
+    }
+}
+
+/// `wrt` a single array variable.
+impl<'v> Gradient<&ArrayVariable<'v>, Array1<f64>> for Vec<Array1<f64>> {
+    #[inline]
+    fn wrt(&self, variable: &ArrayVariable<'v>) -> Array1<f64> {
+        self[variable.index].clone()
+    }
+}
+
+/// `wrt` a borrowed slice of array variables.
+impl<'v> Gradient<&[ArrayVariable<'v>], Vec<Array1<f64>>> for Vec<Array1<f64>> {
+    #[inline]
+    fn wrt(&self, variables: &[ArrayVariable<'v>]) -> Vec<Array1<f64>> {
+        variables
+            .iter()
+            .map(|variable| self[variable.index].clone())
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_array {
+    use crate::{assert_approx_equal, autodiff::*};
+    use ndarray::{array, Array1};
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    #[test]
+    fn test_matches_scalar_graph() {
+        let values = [0.5, 1.5, 2.5];
+
+        // Batched.
+        let g = ArrayGraph::new();
+        let a = g.scalar(2.0);
+        let x = g.var(Array1::from(values.to_vec()));
+
+        let y = (&(&(&a * &x).sin() + &x.powi(2)) / &(1.0 + &x.exp())).mean();
+        let grad = y.accumulate();
+
+        // Scalar.
+        let h = Graph::new();
+        let b = h.var(2.0);
+        let z: Vec<Variable> = values.iter().map(|&v| h.var(v)).collect();
+
+        let w = z
+            .iter()
+            .map(|&z| ((b * z).sin() + z.powi(2)) / (1.0 + z.exp()))
+            .sum::<Variable>()
+            / 3.0;
+        let expected = w.accumulate();
+
+        assert_eq!(y.len(), 1);
+        assert_approx_equal!(y.value[0], w.value, 1e-14);
+        assert_approx_equal!(grad.wrt(&a)[0], expected.wrt(&b), 1e-14);
+
+        for (i, d) in grad.wrt(&x).iter().enumerate() {
+            assert_approx_equal!(*d, expected.wrt(&z[i]), 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_black_scholes_monte_carlo() {
+        let n = 100_000;
+        let normal = Normal::new(0.0, 1.0).unwrap();
+
+        // Stratified standard normal samples.
+        let z = Array1::from_shape_fn(n, |i| normal.inverse_cdf((i as f64 + 0.5) / n as f64));
+
+        let (k, t) = (100.0, 1.0);
+
+        let g = ArrayGraph::new();
+        let s = g.scalar(100.0);
+        let r = g.scalar(0.05);
+        let v = g.scalar(0.2);
+
+        // S_T = S exp((r - v^2 / 2) T + v sqrt(T) Z)
+        let drift = &(&r - &(&v.powi(2) * 0.5)) * t;
+        let diffusion = &(&v * t.sqrt()) * &z;
+        let s_t = &s * &(&drift + &diffusion).exp();
+
+        let price = &(&s_t - k).max(0.0).mean() * &(&(-&r) * t).exp();
+        let grad = price.accumulate();
+
+        // One vertex per operation, regardless of the number of paths.
+        assert!(g.len() < 20);
+
+        let d1 = ((100.0_f64 / k).ln() + (0.05 + 0.5 * 0.2_f64.powi(2)) * t) / (0.2 * t.sqrt());
+        let d2 = d1 - 0.2 * t.sqrt();
+
+        let call = 100.0 * normal.cdf(d1) - k * (-0.05 * t).exp() * normal.cdf(d2);
+        let delta = normal.cdf(d1);
+        let vega = 100.0 * normal.pdf(d1) * t.sqrt();
+        let rho = k * t * (-0.05 * t).exp() * normal.cdf(d2);
+
+        assert_approx_equal!(price.value[0], call, 1e-3);
+        assert_approx_equal!(grad.wrt(&s)[0], delta, 1e-4);
+        assert_approx_equal!(grad.wrt(&v)[0], vega, 1e-2);
+        assert_approx_equal!(grad.wrt(&r)[0], rho, 1e-2);
+    }
+
+    #[test]
+    fn test_vector_output() {
+        let g = ArrayGraph::new();
+        let x = g.var(array![1.0, 2.0, 3.0]);
+        let c = array![4.0, 5.0, 6.0];
+
+        // d/dx sum(c / x - x) = -c / x^2 - 1
+        let y = &(&(1.0 / &x) * &c) - &x;
+        let grad = y.accumulate().wrt(&x);
+
+        for (d, e) in grad.iter().zip([-5.0, -2.25, -1.0 - 6.0 / 9.0]) {
+            assert_approx_equal!(*d, e, 1e-15);
+        }
+    }
+}
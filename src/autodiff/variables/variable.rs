@@ -90,6 +90,14 @@ impl<'v> Variable<'v> {
         self.graph
     }
 
+    /// Check if variable is detached from the graph, i.e. it was computed
+    /// while recording was paused (see [`Graph::pause`]).
+    #[must_use]
+    #[inline]
+    pub fn is_detached(&self) -> bool {
+        self.index == Graph::DETACHED
+    }
+
     /// Check if variable is finite.
     #[must_use]
     #[inline]
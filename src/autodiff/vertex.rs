@@ -21,8 +21,13 @@ pub struct Vertex {
     pub partials: [f64; 2],
     /// Array that contains the indices of the parent vertices.
     pub parents: [usize; 2],
-    // /// Operation.
-    // pub operation: Operation,
+    /// Array that contains the second order partial derivatives,
+    /// i.e. [d^2/dx^2, d^2/dxdy, d^2/dy^2].
+    /// These are only needed for computing Hessians.
+    pub second_partials: [f64; 3],
+    /// The operation that produced the vertex.
+    /// This is needed to re-evaluate (replay) the graph.
+    pub operation: Operation,
 }
 
 /// Enumeration for the operation type.
@@ -45,24 +50,179 @@ pub enum Arity {
 }
 
 /// Enumeration for the operation type.
-#[derive(Clone, Copy, Debug)]
-enum Operation {
-    _ADD,
-    _SUB,
-    _MUL,
-    _DIV,
-    _POW,
-    _SIN,
-    _COS,
-    _TAN,
-    _EXP,
-    _LOG,
-    _SQRT,
-    _ABS,
-    _NEG,
-    _INV,
-    _MAX,
-    _MIN,
+///
+/// Operations involving a constant (`f64`) operand store the constant,
+/// e.g. `x + 2.0` is recorded as `AddConst(2.0)`, and `2.0 - x` as
+/// `ConstSub(2.0)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    /// An input variable.
+    Input,
+    /// A custom (user-defined) operation. These cannot be replayed.
+    Custom,
+    /// x + y
+    Add,
+    /// x + c
+    AddConst(f64),
+    /// c - x
+    ConstSub(f64),
+    /// x * y
+    Mul,
+    /// x * c
+    MulConst(f64),
+    /// c / x
+    ConstDiv(f64),
+    /// x ^ y
+    Powf,
+    /// x ^ c
+    PowfConst(f64),
+    /// x ^ n
+    Powi(i32),
+    /// c ^ x
+    ConstPowf(f64),
+    /// log_y(x) (parents are ordered as `[y, x]`).
+    Log,
+    /// log_c(x)
+    LogConst(f64),
+    /// log_x(c)
+    ConstLog(f64),
+    /// min(x, y)
+    Min,
+    /// min(x, c)
+    MinConst(f64),
+    /// min(c, x)
+    ConstMin(f64),
+    /// max(x, y)
+    Max,
+    /// max(x, c)
+    MaxConst(f64),
+    /// max(c, x)
+    ConstMax(f64),
+    /// tan^-1(y / x) (parents are ordered as `[y, x]`).
+    Atan2,
+    /// sqrt(x^2 + y^2)
+    Hypot,
+    /// |x|
+    Abs,
+    /// cos^-1(x)
+    Acos,
+    /// cosh^-1(x)
+    Acosh,
+    /// sin^-1(x)
+    Asin,
+    /// sinh^-1(x)
+    Asinh,
+    /// tan^-1(x)
+    Atan,
+    /// tanh^-1(x)
+    Atanh,
+    /// x^(1/3)
+    Cbrt,
+    /// cos(x)
+    Cos,
+    /// cosh(x)
+    Cosh,
+    /// erf(x)
+    Erf,
+    /// erfc(x)
+    Erfc,
+    /// Inverse of the standard normal CDF.
+    InvNormCdf,
+    /// e^x
+    Exp,
+    /// 2^x
+    Exp2,
+    /// e^x - 1
+    ExpM1,
+    /// ln(x)
+    Ln,
+    /// ln(1 + x)
+    Ln1p,
+    /// log_10(x)
+    Log10,
+    /// log_2(x)
+    Log2,
+    /// 1 / x
+    Recip,
+    /// sin(x)
+    Sin,
+    /// sinh(x)
+    Sinh,
+    /// sqrt(x)
+    Sqrt,
+    /// tan(x)
+    Tan,
+    /// tanh(x)
+    Tanh,
+}
+
+impl Operation {
+    /// The constant operand of the operation, if any.
+    #[must_use]
+    pub fn constant(&self) -> Option<f64> {
+        match *self {
+            Self::AddConst(c)
+            | Self::ConstSub(c)
+            | Self::MulConst(c)
+            | Self::ConstDiv(c)
+            | Self::PowfConst(c)
+            | Self::ConstPowf(c)
+            | Self::LogConst(c)
+            | Self::ConstLog(c)
+            | Self::MinConst(c)
+            | Self::ConstMin(c)
+            | Self::MaxConst(c)
+            | Self::ConstMax(c) => Some(c),
+            Self::Powi(n) => Some(f64::from(n)),
+            _ => None,
+        }
+    }
+
+    /// Short name of the operation (e.g. `"sin"`).
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Custom => "custom",
+            Self::Add | Self::AddConst(_) => "add",
+            Self::ConstSub(_) => "sub",
+            Self::Mul | Self::MulConst(_) => "mul",
+            Self::ConstDiv(_) => "div",
+            Self::Powf | Self::PowfConst(_) | Self::ConstPowf(_) => "powf",
+            Self::Powi(_) => "powi",
+            Self::Log | Self::LogConst(_) | Self::ConstLog(_) => "log",
+            Self::Min | Self::MinConst(_) | Self::ConstMin(_) => "min",
+            Self::Max | Self::MaxConst(_) | Self::ConstMax(_) => "max",
+            Self::Atan2 => "atan2",
+            Self::Hypot => "hypot",
+            Self::Abs => "abs",
+            Self::Acos => "acos",
+            Self::Acosh => "acosh",
+            Self::Asin => "asin",
+            Self::Asinh => "asinh",
+            Self::Atan => "atan",
+            Self::Atanh => "atanh",
+            Self::Cbrt => "cbrt",
+            Self::Cos => "cos",
+            Self::Cosh => "cosh",
+            Self::Erf => "erf",
+            Self::Erfc => "erfc",
+            Self::InvNormCdf => "inv_norm_cdf",
+            Self::Exp => "exp",
+            Self::Exp2 => "exp2",
+            Self::ExpM1 => "exp_m1",
+            Self::Ln => "ln",
+            Self::Ln1p => "ln_1p",
+            Self::Log10 => "log10",
+            Self::Log2 => "log2",
+            Self::Recip => "recip",
+            Self::Sin => "sin",
+            Self::Sinh => "sinh",
+            Self::Sqrt => "sqrt",
+            Self::Tan => "tan",
+            Self::Tanh => "tanh",
+        }
+    }
 }
 
 impl Vertex {
@@ -72,6 +232,12 @@ impl Vertex {
         self.partials
     }
 
+    /// Get the second order partials of the vertex.
+    #[must_use]
+    pub const fn get_second_partials(&self) -> [f64; 3] {
+        self.second_partials
+    }
+
     /// Get the parents of the vertex.
     #[must_use]
     pub const fn get_parents(&self) -> [usize; 2] {
@@ -89,6 +255,8 @@ impl Vertex {
         Self {
             partials: [partial_x, partial_y],
             parents: [parent_x, parent_y],
+            second_partials: [0.0; 3],
+            operation: Operation::Custom,
         }
     }
 
@@ -98,6 +266,8 @@ impl Vertex {
         Self {
             partials: [partial_x, 0.0],
             parents: [parent_x, 0],
+            second_partials: [0.0; 3],
+            operation: Operation::Custom,
         }
     }
 
@@ -107,13 +277,18 @@ impl Vertex {
         Self {
             partials: [0.0; 2],
             parents: [0; 2],
+            second_partials: [0.0; 3],
+            operation: Operation::Input,
         }
     }
 }
 
 impl PartialEq for Vertex {
     fn eq(&self, other: &Self) -> bool {
-        self.partials == other.partials && self.parents == other.parents
+        self.partials == other.partials
+            && self.parents == other.parents
+            && self.second_partials == other.second_partials
+            && self.operation == other.operation
     }
 }
 
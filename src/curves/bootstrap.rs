@@ -0,0 +1,555 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bootstrapping of a [`YieldCurve`] from deposits, FRAs, futures and swaps.
+//!
+//! The instruments are sorted by maturity, and each adds a point to the
+//! curve at its maturity date: the zero rate that reprices the instrument
+//! given the points before it. Interpolations that are not local
+//! (monotone cubic) move the earlier segments when a point is added, so
+//! the bootstrap is repeated until the rates no longer change.
+//!
+//! With a discount curve (e.g. an OIS curve bootstrapped beforehand), the
+//! bootstrapped curve is a projection curve: swaps are discounted on the
+//! discount curve and their floating legs projected on the curve being
+//! bootstrapped, as in a [`CurveContext`](crate::curves::CurveContext).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, CurveError, CurveInterpolation, YieldCurve};
+use crate::math::solve_decreasing;
+use crate::time::{DayCountConvention, DayCounter, Schedule};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market instrument used to bootstrap a curve. Rates are simple rates
+/// over accrual periods measured with the bootstrapper's day count.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurveInstrument {
+    /// Deposit from the valuation date to the maturity date.
+    Deposit {
+        /// Maturity date.
+        maturity: OffsetDateTime,
+        /// Deposit rate.
+        rate: f64,
+    },
+
+    /// Forward rate agreement from the start date to the end date.
+    ForwardRateAgreement {
+        /// Start of the accrual period.
+        start: OffsetDateTime,
+        /// End of the accrual period.
+        end: OffsetDateTime,
+        /// FRA rate.
+        rate: f64,
+    },
+
+    /// Interest rate future on the period from the start date to the end
+    /// date, quoted as `100 - rate` (in percent).
+    Future {
+        /// Start of the accrual period.
+        start: OffsetDateTime,
+        /// End of the accrual period.
+        end: OffsetDateTime,
+        /// Futures price.
+        price: f64,
+        /// Convexity adjustment: the futures rate less the forward rate.
+        convexity_adjustment: f64,
+    },
+
+    /// Par swap: a fixed leg against a floating leg with the same accrual
+    /// periods.
+    Swap {
+        /// Effective date followed by the fixed leg payment dates.
+        dates: Vec<OffsetDateTime>,
+        /// Par swap rate.
+        rate: f64,
+    },
+}
+
+/// Bootstraps a [`YieldCurve`] of continuously compounded zero rates
+/// (Actual/365) from market instruments.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::time::DayCountConvention;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+///
+/// let curve = CurveBootstrapper::new(today, DayCountConvention::Actual360)
+///     .with_instrument(CurveInstrument::Deposit {
+///         maturity: today + Duration::days(91),
+///         rate: 0.030,
+///     })
+///     .with_instrument(CurveInstrument::ForwardRateAgreement {
+///         start: today + Duration::days(91),
+///         end: today + Duration::days(182),
+///         rate: 0.032,
+///     })
+///     .bootstrap()
+///     .unwrap();
+///
+/// // The deposit is repriced.
+/// let df = curve.discount_factor(today + Duration::days(91));
+/// assert!((1.0 / df - 1.0 - 0.030 * 91.0 / 360.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveBootstrapper {
+    /// Valuation date: the initial date of the curve.
+    pub valuation_date: OffsetDateTime,
+    /// Day count convention of the accrual periods of the instruments.
+    pub day_count_convention: DayCountConvention,
+    /// Interpolation of the bootstrapped curve.
+    pub interpolation: CurveInterpolation,
+    /// Market instruments.
+    pub instruments: Vec<CurveInstrument>,
+    /// Curve discounting the swaps, if different from the bootstrapped
+    /// curve (dual-curve bootstrapping).
+    pub discount_curve: Option<YieldCurve>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CurveInstrument {
+    /// Par swap with the fixed leg on the dates of a schedule (the first
+    /// date being the effective date).
+    #[must_use]
+    pub fn swap(schedule: &Schedule, rate: f64) -> Self {
+        Self::Swap {
+            dates: schedule.dates.clone(),
+            rate,
+        }
+    }
+
+    /// Maturity date of the instrument: the date of its point on the curve.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a swap has no dates.
+    #[must_use]
+    pub fn maturity(&self) -> OffsetDateTime {
+        match self {
+            Self::Deposit { maturity, .. } => *maturity,
+            Self::ForwardRateAgreement { end, .. } | Self::Future { end, .. } => *end,
+            Self::Swap { dates, .. } => *dates.last().expect("The swap has no dates."),
+        }
+    }
+
+    /// Market rate of the instrument. For futures, the forward rate: the
+    /// futures rate less the convexity adjustment.
+    #[must_use]
+    pub fn quoted_rate(&self) -> f64 {
+        match self {
+            Self::Deposit { rate, .. }
+            | Self::ForwardRateAgreement { rate, .. }
+            | Self::Swap { rate, .. } => *rate,
+            Self::Future {
+                price,
+                convexity_adjustment,
+                ..
+            } => (100.0 - price) / 100.0 - convexity_adjustment,
+        }
+    }
+
+    /// Rate of the instrument implied by a curve, comparable to
+    /// [`CurveInstrument::quoted_rate`].
+    #[must_use]
+    pub fn implied_rate<C: Curve>(
+        &self,
+        curve: &C,
+        valuation_date: OffsetDateTime,
+        day_count_convention: &DayCountConvention,
+    ) -> f64 {
+        self.implied_rate_with_discount_curve(curve, curve, valuation_date, day_count_convention)
+    }
+
+    /// Rate of the instrument implied by a projection curve, with swaps
+    /// discounted on a separate discount curve:
+    ///
+    /// $$
+    /// S = \frac{\sum_i \tau_i P_d(t_i) F_i}{\sum_i \tau_i P_d(t_i)}
+    /// $$
+    ///
+    /// where the forwards $F_i$ come from the projection curve. Deposits,
+    /// FRAs and futures only depend on the projection curve.
+    #[must_use]
+    pub fn implied_rate_with_discount_curve<P: Curve, D: Curve>(
+        &self,
+        projection_curve: &P,
+        discount_curve: &D,
+        valuation_date: OffsetDateTime,
+        day_count_convention: &DayCountConvention,
+    ) -> f64 {
+        let curve = projection_curve;
+        let tau = |start, end| DayCounter::day_count_factor(start, end, day_count_convention);
+
+        match self {
+            Self::Deposit { maturity, .. } => {
+                (1.0 / curve.discount_factor(*maturity) - 1.0) / tau(valuation_date, *maturity)
+            }
+            Self::ForwardRateAgreement { start, end, .. } | Self::Future { start, end, .. } => {
+                (curve.discount_factor(*start) / curve.discount_factor(*end) - 1.0)
+                    / tau(*start, *end)
+            }
+            Self::Swap { dates, .. } => {
+                let (floating, annuity) = dates.windows(2).fold((0.0, 0.0), |(pv, annuity), w| {
+                    let df = discount_curve.discount_factor(w[1]);
+                    let forward = curve.discount_factor(w[0]) / curve.discount_factor(w[1]) - 1.0;
+                    (pv + forward * df, annuity + tau(w[0], w[1]) * df)
+                });
+
+                floating / annuity
+            }
+        }
+    }
+}
+
+impl CurveBootstrapper {
+    /// New bootstrapper without instruments, with log-linear
+    /// interpolation of the discount factors.
+    #[must_use]
+    pub const fn new(
+        valuation_date: OffsetDateTime,
+        day_count_convention: DayCountConvention,
+    ) -> Self {
+        Self {
+            valuation_date,
+            day_count_convention,
+            interpolation: CurveInterpolation::LogLinearDiscount,
+            instruments: Vec::new(),
+            discount_curve: None,
+        }
+    }
+
+    /// Sets the interpolation of the bootstrapped curve.
+    #[must_use]
+    pub const fn with_interpolation(mut self, interpolation: CurveInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Discounts the swaps on another curve, so that the bootstrapped curve
+    /// is a projection curve.
+    #[must_use]
+    pub fn with_discount_curve(mut self, discount_curve: YieldCurve) -> Self {
+        self.discount_curve = Some(discount_curve);
+        self
+    }
+
+    /// Adds a market instrument.
+    #[must_use]
+    pub fn with_instrument(mut self, instrument: CurveInstrument) -> Self {
+        self.instruments.push(instrument);
+        self
+    }
+
+    /// Bootstraps the curve. The rate at the valuation date is the rate of
+    /// the first point (flat at the short end).
+    ///
+    /// # Errors
+    ///
+    /// - `CurveError::NoPoints` if there are no instruments.
+    /// - `CurveError::DateOutsideRange` if an instrument matures on or
+    ///   before the valuation date or the previous instrument, or starts
+    ///   before the valuation date.
+    /// - `CurveError::NoConvergence` if an instrument cannot be repriced.
+    pub fn bootstrap(&self) -> Result<YieldCurve, CurveError> {
+        const MAX_PASSES: usize = 100;
+
+        if self.instruments.is_empty() {
+            return Err(CurveError::NoPoints);
+        }
+
+        let mut instruments: Vec<&CurveInstrument> = self.instruments.iter().collect();
+        instruments.sort_by_key(|instrument| instrument.maturity());
+        self.check_dates(&instruments)?;
+
+        let mut curve = YieldCurve::new(BTreeMap::from([(self.valuation_date, 0.0)]))
+            .with_interpolation(self.interpolation);
+
+        // First pass: add the points one at a time, then repeat on the full
+        // curve until the rates settle.
+        for pass in 0..MAX_PASSES {
+            let mut change = 0.0_f64;
+
+            for (i, instrument) in instruments.iter().enumerate() {
+                let maturity = instrument.maturity();
+                let guess = curve.rates.get(&maturity).copied().unwrap_or(0.0);
+
+                let rate = self.solve(&mut curve, instrument, i == 0)?;
+
+                if pass > 0 {
+                    change = change.max((rate - guess).abs());
+                }
+            }
+
+            if pass > 0 && change < 1e-14 {
+                return Ok(curve);
+            }
+        }
+
+        Err(CurveError::NoConvergence)
+    }
+
+    // Checks that the instruments mature after the valuation date and each
+    // other, and start on or after the valuation date.
+    fn check_dates(&self, instruments: &[&CurveInstrument]) -> Result<(), CurveError> {
+        let mut previous = self.valuation_date;
+
+        for instrument in instruments {
+            let start = match instrument {
+                CurveInstrument::Deposit { .. } => self.valuation_date,
+                CurveInstrument::ForwardRateAgreement { start, .. }
+                | CurveInstrument::Future { start, .. } => *start,
+                CurveInstrument::Swap { dates, .. } => dates[0],
+            };
+
+            if instrument.maturity() <= previous || start < self.valuation_date {
+                return Err(CurveError::DateOutsideRange);
+            }
+            previous = instrument.maturity();
+        }
+
+        Ok(())
+    }
+
+    // Solves for the rate at the maturity of the instrument that reprices
+    // it (by Brent's method), and sets it on the curve.
+    fn solve(
+        &self,
+        curve: &mut YieldCurve,
+        instrument: &CurveInstrument,
+        first: bool,
+    ) -> Result<f64, CurveError> {
+        let maturity = instrument.maturity();
+        let target = instrument.quoted_rate();
+
+        let mut residual = |rate: f64| {
+            curve.update_rate(maturity, rate);
+            if first {
+                curve.update_rate(self.valuation_date, rate);
+            }
+            let implied = match &self.discount_curve {
+                Some(discount_curve) => instrument.implied_rate_with_discount_curve(
+                    &*curve,
+                    discount_curve,
+                    self.valuation_date,
+                    &self.day_count_convention,
+                ),
+                None => instrument.implied_rate(
+                    &*curve,
+                    self.valuation_date,
+                    &self.day_count_convention,
+                ),
+            };
+            target - implied
+        };
+
+        let rate =
+            solve_decreasing(&mut residual, target).map_err(|_| CurveError::NoConvergence)?;
+        residual(rate);
+
+        Ok(rate)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bootstrap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::days;
+
+    const INTERPOLATIONS: [CurveInterpolation; 7] = [
+        CurveInterpolation::Linear,
+        CurveInterpolation::LogLinearDiscount,
+        CurveInterpolation::MonotoneCubic,
+        CurveInterpolation::CubicSpline,
+        CurveInterpolation::Steffen,
+        CurveInterpolation::Hyman,
+        CurveInterpolation::Akima,
+    ];
+
+    // Deposits, FRAs, a future and semi-annual swaps.
+    fn bootstrapper(quote: impl Fn(&CurveInstrument) -> f64) -> CurveBootstrapper {
+        let swap_dates = |years: i64| (0..=2 * years).map(|i| days(182 * i)).collect::<Vec<_>>();
+
+        let instruments = vec![
+            CurveInstrument::Deposit {
+                maturity: days(30),
+                rate: 0.0,
+            },
+            CurveInstrument::Deposit {
+                maturity: days(91),
+                rate: 0.0,
+            },
+            CurveInstrument::ForwardRateAgreement {
+                start: days(91),
+                end: days(182),
+                rate: 0.0,
+            },
+            CurveInstrument::Future {
+                start: days(182),
+                end: days(273),
+                price: 100.0,
+                convexity_adjustment: 0.0005,
+            },
+            CurveInstrument::Swap {
+                dates: swap_dates(2),
+                rate: 0.0,
+            },
+            CurveInstrument::Swap {
+                dates: swap_dates(5),
+                rate: 0.0,
+            },
+            CurveInstrument::Swap {
+                dates: swap_dates(10),
+                rate: 0.0,
+            },
+        ];
+
+        let mut bootstrapper = CurveBootstrapper::new(days(0), DayCountConvention::Actual360);
+
+        for mut instrument in instruments {
+            let rate = quote(&instrument);
+            match &mut instrument {
+                CurveInstrument::Future {
+                    price,
+                    convexity_adjustment,
+                    ..
+                } => *price = 100.0 * (1.0 - rate - *convexity_adjustment),
+                CurveInstrument::Deposit { rate: r, .. }
+                | CurveInstrument::ForwardRateAgreement { rate: r, .. }
+                | CurveInstrument::Swap { rate: r, .. } => *r = rate,
+            }
+            bootstrapper = bootstrapper.with_instrument(instrument);
+        }
+
+        bootstrapper
+    }
+
+    #[test]
+    fn test_flat_curve() {
+        // Quotes from a flat 4% continuously compounded curve.
+        let flat = YieldCurve::new(BTreeMap::from([(days(0), 0.04), (days(10_000), 0.04)]));
+        let quote =
+            |i: &CurveInstrument| i.implied_rate(&flat, days(0), &DayCountConvention::Actual360);
+
+        for interpolation in INTERPOLATIONS {
+            let curve = bootstrapper(quote)
+                .with_interpolation(interpolation)
+                .bootstrap()
+                .unwrap();
+
+            for rate in curve.rates.values() {
+                assert_approx_equal!(rate, &0.04, 1e-10);
+            }
+            assert_approx_equal!(curve.rate(days(500)), 0.04, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_repricing() {
+        // Quotes from an upward sloping curve.
+        let market = YieldCurve::new(BTreeMap::from([
+            (days(0), 0.02),
+            (days(365), 0.03),
+            (days(3650), 0.045),
+        ]));
+        let quote =
+            |i: &CurveInstrument| i.implied_rate(&market, days(0), &DayCountConvention::Actual360);
+
+        for interpolation in INTERPOLATIONS {
+            let bootstrapper = bootstrapper(quote).with_interpolation(interpolation);
+            let curve = bootstrapper.bootstrap().unwrap();
+
+            assert_eq!(curve.rates.len(), bootstrapper.instruments.len() + 1);
+            assert_eq!(curve.interpolation, interpolation);
+
+            for instrument in &bootstrapper.instruments {
+                let implied =
+                    instrument.implied_rate(&curve, days(0), &DayCountConvention::Actual360);
+                assert_approx_equal!(implied, instrument.quoted_rate(), 1e-12);
+            }
+
+            // Discount factors decrease with maturity.
+            let dfs = curve.discount_factors(&(1..=36).map(|i| days(100 * i)).collect::<Vec<_>>());
+            assert!(dfs.windows(2).all(|w| w[1] < w[0]));
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_errors() {
+        let empty = CurveBootstrapper::new(days(0), DayCountConvention::Actual360);
+        assert!(matches!(empty.bootstrap(), Err(CurveError::NoPoints)));
+
+        let expired = empty.with_instrument(CurveInstrument::Deposit {
+            maturity: days(0),
+            rate: 0.03,
+        });
+        assert!(matches!(
+            expired.bootstrap(),
+            Err(CurveError::DateOutsideRange)
+        ));
+    }
+
+    #[test]
+    fn test_dual_curve() {
+        // OIS discounting at 3%, quotes off an upward sloping projection
+        // curve.
+        let ois = YieldCurve::new(BTreeMap::from([(days(0), 0.03), (days(10_000), 0.03)]));
+        let projection = YieldCurve::new(BTreeMap::from([
+            (days(0), 0.025),
+            (days(365), 0.035),
+            (days(3650), 0.05),
+        ]));
+        let dcc = DayCountConvention::Actual360;
+        let quote = |i: &CurveInstrument| {
+            i.implied_rate_with_discount_curve(&projection, &ois, days(0), &dcc)
+        };
+
+        let bootstrapper = bootstrapper(quote).with_discount_curve(ois.clone());
+        let curve = bootstrapper.bootstrap().unwrap();
+
+        for instrument in &bootstrapper.instruments {
+            let implied = instrument.implied_rate_with_discount_curve(&curve, &ois, days(0), &dcc);
+            assert_approx_equal!(implied, instrument.quoted_rate(), 1e-12);
+        }
+
+        // Single-curve bootstrapping of the same quotes gives another curve.
+        let single = CurveBootstrapper {
+            discount_curve: None,
+            ..bootstrapper.clone()
+        }
+        .bootstrap()
+        .unwrap();
+        let long_end = bootstrapper.instruments[6].maturity();
+        assert!((single.rate(long_end) - curve.rate(long_end)).abs() > 1e-6);
+
+        // Discounting on the projection curve itself is single-curve.
+        let swap = &bootstrapper.instruments[6];
+        assert_approx_equal!(
+            swap.implied_rate_with_discount_curve(&projection, &projection, days(0), &dcc),
+            swap.implied_rate(&projection, days(0), &dcc),
+            1e-14
+        );
+    }
+}
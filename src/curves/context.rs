@@ -0,0 +1,207 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Dual-curve (multi-curve) pricing context.
+//!
+//! Since 2008, the rates of the floating legs of derivatives (e.g. the
+//! 3M and 6M IBOR indices) are no longer consistent with the rates used
+//! to discount collateralised cash flows (the overnight index, OIS). A
+//! [`CurveContext`] holds one discount curve and a projection curve per
+//! floating rate index: forward rates come from the projection curve of
+//! the index, and all cash flows are discounted on the discount curve.
+//! Without a projection curve for an index, the discount curve is used
+//! (single-curve pricing).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, YieldCurve};
+use crate::time::{DayCountConvention, DayCounter};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Discount curve and projection curves (by floating rate index) consumed
+/// by interest rate pricers.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::time::DayCountConvention;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let flat = |rate| YieldCurve::new(BTreeMap::from([
+///     (today, rate),
+///     (today + Duration::days(3650), rate),
+/// ]));
+///
+/// let context = CurveContext::new(flat(0.03)).with_projection_curve("EURIBOR6M", flat(0.035));
+///
+/// let (start, end) = (today + Duration::days(365), today + Duration::days(547));
+/// let dcc = DayCountConvention::Actual365;
+///
+/// // The 6M forward is projected on its own curve, above the OIS forward.
+/// assert!(
+///     context.forward_rate("EURIBOR6M", start, end, &dcc)
+///         > context.forward_rate("ESTR", start, end, &dcc)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveContext {
+    /// Curve discounting the cash flows (e.g. the OIS curve).
+    pub discount_curve: YieldCurve,
+    /// Curves projecting the forward rates of floating rate indices.
+    pub projection_curves: BTreeMap<String, YieldCurve>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CurveContext {
+    /// New context with a discount curve, which also projects every index
+    /// until projection curves are added.
+    #[must_use]
+    pub fn new(discount_curve: YieldCurve) -> Self {
+        Self {
+            discount_curve,
+            projection_curves: BTreeMap::new(),
+        }
+    }
+
+    /// Adds (or replaces) the projection curve of a floating rate index.
+    #[must_use]
+    pub fn with_projection_curve(mut self, index: &str, curve: YieldCurve) -> Self {
+        self.projection_curves.insert(index.to_string(), curve);
+        self
+    }
+
+    /// Valuation date: the initial date of the discount curve.
+    #[must_use]
+    pub fn valuation_date(&self) -> OffsetDateTime {
+        self.discount_curve.initial_date()
+    }
+
+    /// Projection curve of an index, or the discount curve if the index has
+    /// no curve of its own.
+    #[must_use]
+    pub fn projection_curve(&self, index: &str) -> &YieldCurve {
+        self.projection_curves
+            .get(index)
+            .unwrap_or(&self.discount_curve)
+    }
+
+    /// Discount factor from the valuation date to `date`.
+    #[must_use]
+    pub fn discount_factor(&self, date: OffsetDateTime) -> f64 {
+        self.discount_curve.discount_factor(date)
+    }
+
+    /// Simple forward rate of an index over the accrual period from `start`
+    /// to `end`, from its projection curve:
+    ///
+    /// $$
+    /// F = \frac{1}{\tau} \left( \frac{P(t_s)}{P(t_e)} - 1 \right)
+    /// $$
+    #[must_use]
+    pub fn forward_rate(
+        &self,
+        index: &str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        day_count_convention: &DayCountConvention,
+    ) -> f64 {
+        let curve = self.projection_curve(index);
+        let tau = DayCounter::day_count_factor(start, end, day_count_convention);
+
+        (curve.discount_factor(start) / curve.discount_factor(end) - 1.0) / tau
+    }
+
+    /// Present value of cash flows `(date, amount)` on the discount curve.
+    #[must_use]
+    pub fn present_value(&self, cashflows: &[(OffsetDateTime, f64)]) -> f64 {
+        cashflows
+            .iter()
+            .map(|(date, amount)| amount * self.discount_factor(*date))
+            .sum()
+    }
+
+    /// The context with all its curves shifted by `shift` (a parallel
+    /// shift of the zero rates).
+    #[must_use]
+    pub fn shifted(&self, shift: f64) -> Self {
+        Self {
+            discount_curve: self.discount_curve.shifted(shift),
+            projection_curves: self
+                .projection_curves
+                .iter()
+                .map(|(index, curve)| (index.clone(), curve.shifted(shift)))
+                .collect(),
+        }
+    }
+}
+
+impl From<YieldCurve> for CurveContext {
+    fn from(curve: YieldCurve) -> Self {
+        Self::new(curve)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_context {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{days, flat_curve};
+
+    #[test]
+    fn test_single_and_dual_curve() {
+        let dcc = DayCountConvention::Actual365;
+        let single = CurveContext::from(flat_curve(0.03));
+        let dual = single
+            .clone()
+            .with_projection_curve("IBOR3M", flat_curve(0.035));
+
+        assert_eq!(dual.valuation_date(), days(0));
+        assert_eq!(dual.projection_curve("OIS"), &dual.discount_curve);
+
+        // Continuously compounded forwards over 365 days.
+        assert_approx_equal!(
+            single.forward_rate("IBOR3M", days(365), days(730), &dcc),
+            0.03_f64.exp_m1(),
+            1e-12
+        );
+        assert_approx_equal!(
+            dual.forward_rate("IBOR3M", days(365), days(730), &dcc),
+            0.035_f64.exp_m1(),
+            1e-12
+        );
+
+        // Discounting is unchanged by the projection curves.
+        let cashflows = [(days(365), 1.0), (days(730), 2.0)];
+        assert_approx_equal!(
+            dual.present_value(&cashflows),
+            (-0.03_f64).exp() + 2.0 * (-0.06_f64).exp(),
+            1e-12
+        );
+        assert_approx_equal!(
+            dual.present_value(&cashflows),
+            single.present_value(&cashflows),
+            1e-15
+        );
+    }
+}
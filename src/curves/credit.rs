@@ -0,0 +1,391 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Credit curves and risky discounting.
+//!
+//! A [`CreditCurve`] holds the survival probabilities $Q(t)$ of an issuer,
+//! as a [`YieldCurve`] holds its discount factors. Combined with a discount
+//! curve and a recovery rate, it values the cash flows of any instrument
+//! with default risk: the promised cash flows are discounted with the
+//! risky discount factors $P(t) Q(t)$, and on default before the maturity
+//! $T$ the recovery rate $R$ of the notional $N$ is paid:
+//!
+//! $$
+//! V = \sum_i c_i P(t_i) Q(t_i) - R N \int_0^T P(u) \, dQ(u)
+//! $$
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, HazardRateCurve, YieldCurve};
+use crate::time::{DayCountConvention, DayCounter};
+use std::collections::{BTreeMap, BTreeSet};
+use time::{Duration, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Survival probabilities by date, interpolated log-linearly (a piecewise
+/// constant hazard rate), with times in years (Actual/365) from the initial
+/// date.
+///
+/// ```
+/// use RustQuant::curves::CreditCurve;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+///
+/// // A 120bp spread with 40% recovery: a 2% hazard rate.
+/// let curve = CreditCurve::from_spread(today, 0.012, 0.4);
+///
+/// let q = curve.survival_probability(today + Duration::days(1825));
+/// assert!((q - (-0.1_f64).exp()).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditCurve {
+    /// Initial date of the curve, where the survival probability is one.
+    pub initial_date: OffsetDateTime,
+    /// Survival probabilities by date.
+    pub survival_probabilities: BTreeMap<OffsetDateTime, f64>,
+}
+
+/// Discount curve with default risk: a discount curve, the credit curve of
+/// the issuer and a recovery rate.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let maturity = today + Duration::days(1825);
+///
+/// let discount = YieldCurve::new(BTreeMap::from([(today, 0.03), (maturity, 0.03)]));
+/// let risky = RiskyDiscountCurve::new(discount, CreditCurve::flat(today, 0.02), 0.4);
+///
+/// // A zero-coupon bond is worth its risky discount factor, plus the
+/// // value of the recovery.
+/// let value = risky.present_value(&[(maturity, 100.0)], 100.0);
+/// assert!(value > 100.0 * risky.discount_factor(maturity));
+/// assert!(value < 100.0 * risky.discount_curve.discount_factor(maturity));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskyDiscountCurve {
+    /// Risk-free discount curve.
+    pub discount_curve: YieldCurve,
+    /// Credit curve of the issuer.
+    pub credit_curve: CreditCurve,
+    /// Fraction of the notional recovered on default.
+    pub recovery_rate: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CreditCurve {
+    /// New credit curve.
+    #[must_use]
+    pub const fn new(
+        initial_date: OffsetDateTime,
+        survival_probabilities: BTreeMap<OffsetDateTime, f64>,
+    ) -> Self {
+        Self {
+            initial_date,
+            survival_probabilities,
+        }
+    }
+
+    /// Curve with a constant hazard rate.
+    #[must_use]
+    pub fn flat(initial_date: OffsetDateTime, hazard_rate: f64) -> Self {
+        Self::from(&HazardRateCurve::flat(initial_date, hazard_rate))
+    }
+
+    /// Curve with the constant hazard rate implied by a credit spread and a
+    /// recovery rate (the credit triangle): $\lambda = s / (1 - R)$.
+    #[must_use]
+    pub fn from_spread(initial_date: OffsetDateTime, spread: f64, recovery_rate: f64) -> Self {
+        Self::flat(initial_date, spread / (1.0 - recovery_rate))
+    }
+
+    /// Probability of surviving from the initial date to `date`: log-linear
+    /// between the dates of the curve, and extrapolated with the hazard
+    /// rate of the last segment.
+    #[must_use]
+    pub fn survival_probability(&self, date: OffsetDateTime) -> f64 {
+        if date <= self.initial_date {
+            return 1.0;
+        }
+
+        let t = self.year_fraction(date);
+        let before = self
+            .survival_probabilities
+            .range(..=date)
+            .next_back()
+            .filter(|(pillar, _)| **pillar > self.initial_date);
+        let (t0, q0) = before.map_or((0.0, 1.0), |(pillar, q)| (self.year_fraction(*pillar), *q));
+
+        (-self.hazard_rate(date) * (t - t0)).exp() * q0
+    }
+
+    /// Probability of defaulting between the initial date and `date`.
+    #[must_use]
+    pub fn default_probability(&self, date: OffsetDateTime) -> f64 {
+        1.0 - self.survival_probability(date)
+    }
+
+    /// Hazard rate on `date`: constant between the dates of the curve, and
+    /// that of the last segment beyond (zero if the curve has no dates).
+    #[must_use]
+    pub fn hazard_rate(&self, date: OffsetDateTime) -> f64 {
+        let pillars: Vec<(f64, f64)> = std::iter::once((0.0, 1.0))
+            .chain(
+                self.survival_probabilities
+                    .iter()
+                    .filter(|(pillar, _)| **pillar > self.initial_date)
+                    .map(|(pillar, q)| (self.year_fraction(*pillar), *q)),
+            )
+            .collect();
+
+        let t = self.year_fraction(date);
+        let segment = pillars
+            .windows(2)
+            .find(|w| t <= w[1].0)
+            .or_else(|| pillars.windows(2).last());
+
+        segment.map_or(0.0, |w| (w[0].1 / w[1].1).ln() / (w[1].0 - w[0].0))
+    }
+
+    /// The curve with its hazard rates shifted by `shift`.
+    #[must_use]
+    pub fn shifted(&self, shift: f64) -> Self {
+        Self {
+            initial_date: self.initial_date,
+            survival_probabilities: self
+                .survival_probabilities
+                .iter()
+                .map(|(date, q)| (*date, q * (-shift * self.year_fraction(*date)).exp()))
+                .collect(),
+        }
+    }
+
+    /// Year fraction (Actual/365) from the initial date of the curve.
+    #[must_use]
+    pub fn year_fraction(&self, date: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(self.initial_date, date, &DayCountConvention::Actual365)
+    }
+}
+
+impl From<&HazardRateCurve> for CreditCurve {
+    /// Survival probabilities on the dates of the hazard rate curve, which
+    /// the log-linear interpolation reproduces everywhere.
+    fn from(curve: &HazardRateCurve) -> Self {
+        let survival_probabilities = curve
+            .hazard_rates
+            .keys()
+            .filter(|date| **date > curve.initial_date)
+            .map(|date| (*date, curve.survival_probability(*date)))
+            .collect::<BTreeMap<_, _>>();
+
+        if survival_probabilities.is_empty() {
+            // A single rate on the initial date: one year of it.
+            let date = curve.initial_date + Duration::days(365);
+            return Self::new(
+                curve.initial_date,
+                BTreeMap::from([(date, curve.survival_probability(date))]),
+            );
+        }
+
+        Self::new(curve.initial_date, survival_probabilities)
+    }
+}
+
+impl RiskyDiscountCurve {
+    /// New risky discount curve.
+    #[must_use]
+    pub const fn new(
+        discount_curve: YieldCurve,
+        credit_curve: CreditCurve,
+        recovery_rate: f64,
+    ) -> Self {
+        Self {
+            discount_curve,
+            credit_curve,
+            recovery_rate,
+        }
+    }
+
+    /// Risky discount factor to `date`: the discount factor times the
+    /// survival probability.
+    #[must_use]
+    pub fn discount_factor(&self, date: OffsetDateTime) -> f64 {
+        self.discount_curve.discount_factor(date) * self.credit_curve.survival_probability(date)
+    }
+
+    /// Value of a unit paid on default between `start` and `end`:
+    ///
+    /// $$
+    /// -\int_{t_s}^{t_e} P(u) \, dQ(u)
+    /// $$
+    ///
+    /// exact for constant forward and hazard rates between the dates of
+    /// the curves.
+    #[must_use]
+    pub fn default_value(&self, start: OffsetDateTime, end: OffsetDateTime) -> f64 {
+        let start = start.max(self.credit_curve.initial_date);
+        if end <= start {
+            return 0.0;
+        }
+
+        let mut dates = BTreeSet::from([start, end]);
+        dates.extend(
+            self.discount_curve
+                .rates
+                .keys()
+                .chain(self.credit_curve.survival_probabilities.keys())
+                .filter(|date| start < **date && **date < end),
+        );
+        let dates: Vec<OffsetDateTime> = dates.into_iter().collect();
+
+        dates
+            .windows(2)
+            .map(|w| {
+                let (q0, q1) = (
+                    self.credit_curve.survival_probability(w[0]),
+                    self.credit_curve.survival_probability(w[1]),
+                );
+                let (d0, d1) = (self.discount_factor(w[0]), self.discount_factor(w[1]));
+
+                // Hazard rate over the decay rate of the risky discount
+                // factor, times its change.
+                let ratio = (q0 / q1).ln() / (d0 / d1).ln();
+                if ratio.is_finite() {
+                    ratio * (d0 - d1)
+                } else {
+                    (q0 - q1) * self.discount_curve.discount_factor(w[0])
+                }
+            })
+            .sum()
+    }
+
+    /// Present value of promised cash flows `(date, amount)` with default
+    /// risk, and the recovery of `notional` on default before the last
+    /// cash flow.
+    #[must_use]
+    pub fn present_value(&self, cashflows: &[(OffsetDateTime, f64)], notional: f64) -> f64 {
+        let promised: f64 = cashflows
+            .iter()
+            .map(|(date, amount)| amount * self.discount_factor(*date))
+            .sum();
+
+        let maturity = cashflows.iter().map(|(date, _)| *date).max();
+        let recovery = maturity.map_or(0.0, |maturity| {
+            self.recovery_rate
+                * notional
+                * self.default_value(self.credit_curve.initial_date, maturity)
+        });
+
+        promised + recovery
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_credit_curve {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{days, flat_curve};
+    use crate::instruments::bonds::FixedRateBond;
+    use crate::time::{PaymentFrequency, Schedule};
+
+    #[test]
+    fn test_from_hazard_rates() {
+        let hazard = HazardRateCurve::new(
+            days(0),
+            BTreeMap::from([(days(365), 0.01), (days(1095), 0.03), (days(1825), 0.02)]),
+        );
+        let curve = CreditCurve::from(&hazard);
+
+        for n in [0, 100, 365, 700, 1500, 3000] {
+            assert_approx_equal!(
+                curve.survival_probability(days(n)),
+                hazard.survival_probability(days(n)),
+                1e-14
+            );
+            assert_approx_equal!(
+                curve.hazard_rate(days(n)),
+                hazard.hazard_rate(days(n)),
+                1e-12
+            );
+        }
+
+        // A parallel shift of the hazard rates.
+        let shifted = curve.shifted(0.01);
+        assert_approx_equal!(shifted.hazard_rate(days(700)), 0.04, 1e-12);
+    }
+
+    #[test]
+    fn test_recovery() {
+        // Without rates and with full recovery, nothing is lost on default.
+        let risky = RiskyDiscountCurve::new(flat_curve(0.0), CreditCurve::flat(days(0), 0.05), 1.0);
+        assert_approx_equal!(
+            risky.present_value(&[(days(1825), 100.0)], 100.0),
+            100.0,
+            1e-12
+        );
+
+        // With constant rates, the value of a unit on default is known.
+        let (r, h) = (0.03, 0.02);
+        let risky = RiskyDiscountCurve::new(flat_curve(r), CreditCurve::flat(days(0), h), 0.4);
+        let t = 5.0;
+        assert_approx_equal!(
+            risky.default_value(days(0), days(1825)),
+            h / (r + h) * (1.0 - (-(r + h) * t).exp()),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_bond() {
+        let schedule = Schedule::new_from_start(days(0), Duration::days(365), 5);
+        let bond = FixedRateBond::new(100.0, 0.05, &schedule, PaymentFrequency::Annually);
+        let cashflows = bond.cashflows_after(days(0));
+
+        // Without default risk, the risk-free price; with zero recovery, the
+        // hazard rate is a spread over the discount curve.
+        let riskless =
+            RiskyDiscountCurve::new(flat_curve(0.03), CreditCurve::flat(days(0), 0.0), 0.4);
+        assert_approx_equal!(
+            riskless.present_value(&cashflows, 100.0),
+            bond.dirty_price_from_curve(&flat_curve(0.03), 0.0),
+            1e-10
+        );
+
+        let credit = CreditCurve::flat(days(0), 0.02);
+        let no_recovery = RiskyDiscountCurve::new(flat_curve(0.03), credit.clone(), 0.0);
+        assert_approx_equal!(
+            no_recovery.present_value(&cashflows, 100.0),
+            bond.dirty_price_from_curve(&flat_curve(0.03), 0.02),
+            1e-10
+        );
+
+        // The recovery adds value.
+        let recovery = RiskyDiscountCurve::new(flat_curve(0.03), credit, 0.4);
+        assert!(
+            recovery.present_value(&cashflows, 100.0)
+                > no_recovery.present_value(&cashflows, 100.0)
+        );
+    }
+}
@@ -11,6 +11,9 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::math::interpolation::{
+    akima_slopes, monotone_slopes, spline_slopes, CubicHermite, MonotoneMethod, SplineBoundary,
+};
 use crate::time::{DayCountConvention, DayCounter};
 use std::{collections::BTreeMap, time::Duration};
 use time::OffsetDateTime;
@@ -110,16 +113,50 @@ pub trait Curve {
 
 #[allow(clippy::module_name_repetitions)]
 /// Yield curve struct.
+#[derive(Debug, Clone, PartialEq)]
 pub struct YieldCurve {
     /// Map of dates and rates.
     /// The dates are the keys and the rates are the values.
     /// The reason for using a [BTreeMap] is that it is sorted by date,
     /// which makes sense for a term structure.
     pub rates: BTreeMap<OffsetDateTime, f64>,
+    /// Interpolation between the dates of the curve.
+    pub interpolation: CurveInterpolation,
     // /// A model for the curve.
     // pub model: Option<M>,
 }
 
+/// Interpolation of a [`YieldCurve`] between its dates.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CurveInterpolation {
+    /// Linear on the rates (the default).
+    #[default]
+    Linear,
+
+    /// Linear on the log of the discount factors: piecewise constant
+    /// instantaneous forward rates.
+    LogLinearDiscount,
+
+    /// Monotone cubic (Fritsch-Butland) on the rates: smooth, without
+    /// overshooting between the dates.
+    MonotoneCubic,
+
+    /// Natural cubic spline on the rates: twice continuously
+    /// differentiable, but may overshoot between the dates.
+    CubicSpline,
+
+    /// Monotone cubic with Steffen's slopes on the rates.
+    Steffen,
+
+    /// Monotone cubic with Hyman's filtered spline slopes on the rates.
+    Hyman,
+
+    /// Akima cubic on the rates: less wiggly than the cubic spline, and
+    /// local, so that bumping a rate only moves the curve near its date.
+    Akima,
+}
+
 /// Curve error enum.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
@@ -129,6 +166,10 @@ pub enum CurveError {
 
     /// The curve has no points.
     NoPoints,
+
+    /// A numerical procedure on the curve (e.g. bootstrapping) did not
+    /// converge.
+    NoConvergence,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -139,7 +180,48 @@ impl YieldCurve {
     /// Creates a new yield curve.
     #[must_use]
     pub fn new(rates: BTreeMap<OffsetDateTime, f64>) -> Self {
-        Self { rates }
+        Self {
+            rates,
+            interpolation: CurveInterpolation::Linear,
+        }
+    }
+
+    /// Sets the interpolation between the dates of the curve.
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: CurveInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// The curve with all its rates shifted by `shift` (a parallel shift).
+    #[must_use]
+    pub fn shifted(&self, shift: f64) -> Self {
+        Self {
+            rates: self
+                .rates
+                .iter()
+                .map(|(date, rate)| (*date, rate + shift))
+                .collect(),
+            interpolation: self.interpolation,
+        }
+    }
+
+    // Year fraction (Actual/365) from the initial date of the curve.
+    fn year_fraction(&self, date: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(self.initial_date(), date, &DayCountConvention::Actual365)
+    }
+
+    // Cubic Hermite interpolation of the rates in year fractions, with the
+    // slopes given by `slopes` from the year fractions and rates.
+    fn cubic<S>(&self, date: OffsetDateTime, slopes: S) -> f64
+    where
+        S: Fn(&[f64], &[f64]) -> Vec<f64>,
+    {
+        let t: Vec<f64> = self.rates.keys().map(|d| self.year_fraction(*d)).collect();
+        let y: Vec<f64> = self.rates.values().copied().collect();
+        let slopes = slopes(&t, &y);
+
+        CubicHermite::new(t, y, slopes).derivatives(self.year_fraction(date))[0]
     }
 }
 
@@ -165,7 +247,7 @@ impl Curve for YieldCurve {
             rates_map.insert(*date, *rate);
         }
 
-        Self { rates: rates_map }
+        Self::new(rates_map)
     }
 
     #[allow(clippy::similar_names)]
@@ -193,7 +275,32 @@ impl Curve for YieldCurve {
                 let (x0, x1) = self.find_date_interval(date);
                 let (y0, y1) = (*self.rates.get(&x0).unwrap(), *self.rates.get(&x1).unwrap());
 
-                (y0 * (x1 - date) + y1 * (date - x0)) / (x1 - x0)
+                if x0 == x1 {
+                    return y0;
+                }
+
+                match self.interpolation {
+                    CurveInterpolation::Linear => (y0 * (x1 - date) + y1 * (date - x0)) / (x1 - x0),
+                    CurveInterpolation::LogLinearDiscount => {
+                        let (t0, t1) = (self.year_fraction(x0), self.year_fraction(x1));
+                        let t = self.year_fraction(date);
+
+                        (y0 * t0 * (t1 - t) + y1 * t1 * (t - t0)) / ((t1 - t0) * t)
+                    }
+                    CurveInterpolation::MonotoneCubic => self.cubic(date, |t, y| {
+                        monotone_slopes(t, y, MonotoneMethod::FritschButland)
+                    }),
+                    CurveInterpolation::CubicSpline => {
+                        self.cubic(date, |t, y| spline_slopes(t, y, SplineBoundary::Natural))
+                    }
+                    CurveInterpolation::Steffen => {
+                        self.cubic(date, |t, y| monotone_slopes(t, y, MonotoneMethod::Steffen))
+                    }
+                    CurveInterpolation::Hyman => {
+                        self.cubic(date, |t, y| monotone_slopes(t, y, MonotoneMethod::Hyman))
+                    }
+                    CurveInterpolation::Akima => self.cubic(date, akima_slopes),
+                }
             }
         }
     }
@@ -320,4 +427,46 @@ mod tests_curves {
 
         assert!(df1 > df2 && df2 > df3);
     }
+
+    #[test]
+    fn test_cubic_interpolations() {
+        let t0 = OffsetDateTime::UNIX_EPOCH;
+        let days = [0, 30, 90, 180, 365, 730];
+        let rates = [0.02, 0.021, 0.03, 0.031, 0.031, 0.035];
+        let dates: Vec<OffsetDateTime> = days.iter().map(|d| t0 + Duration::days(*d)).collect();
+        let curve = YieldCurve::from_dates_and_rates(&dates, &rates);
+
+        for interpolation in [
+            CurveInterpolation::MonotoneCubic,
+            CurveInterpolation::CubicSpline,
+            CurveInterpolation::Steffen,
+            CurveInterpolation::Hyman,
+            CurveInterpolation::Akima,
+        ] {
+            let curve = curve.clone().with_interpolation(interpolation);
+
+            for (date, rate) in dates.iter().zip(rates) {
+                assert!((curve.rate(*date) - rate).abs() < 1e-15);
+            }
+
+            // The monotone interpolations stay within the neighbouring
+            // rates.
+            if matches!(
+                interpolation,
+                CurveInterpolation::CubicSpline | CurveInterpolation::Akima
+            ) {
+                continue;
+            }
+            for (date, rate) in dates.windows(2).zip(rates.windows(2)) {
+                for day in 1..(date[1] - date[0]).whole_days() {
+                    let r = curve.rate(date[0] + Duration::days(day));
+
+                    assert!(
+                        rate[0].min(rate[1]) - 1e-15 <= r && r <= rate[0].max(rate[1]) + 1e-15,
+                        "{interpolation:?}: {r}"
+                    );
+                }
+            }
+        }
+    }
 }
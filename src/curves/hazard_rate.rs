@@ -0,0 +1,163 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Hazard rate (default intensity) curves.
+//!
+//! The hazard rate $\lambda(t)$ is piecewise constant between the dates of
+//! the curve, and the probability of surviving to $t$ is:
+//!
+//! $$
+//! Q(t) = \exp\left( -\int_0^t \lambda(u) \, du \right)
+//! $$
+//!
+//! Curves are bootstrapped from credit default swap spreads, see
+//! [`HazardRateCurve::bootstrap`].
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::time::{DayCountConvention, DayCounter};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Piecewise constant hazard rate curve, with times in years (Actual/365)
+/// from its initial date.
+///
+/// ```
+/// use RustQuant::curves::HazardRateCurve;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let curve = HazardRateCurve::new(
+///     today,
+///     BTreeMap::from([
+///         (today + Duration::days(365), 0.01),
+///         (today + Duration::days(730), 0.03),
+///     ]),
+/// );
+///
+/// let q = curve.survival_probability(today + Duration::days(730));
+/// assert!((q - (-0.04_f64).exp()).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HazardRateCurve {
+    /// Initial date of the curve: time 0.
+    pub initial_date: OffsetDateTime,
+    /// Hazard rates by date: each applies from the previous date (or the
+    /// initial date) to its date, and the last one beyond.
+    pub hazard_rates: BTreeMap<OffsetDateTime, f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HazardRateCurve {
+    /// New hazard rate curve.
+    #[must_use]
+    pub const fn new(
+        initial_date: OffsetDateTime,
+        hazard_rates: BTreeMap<OffsetDateTime, f64>,
+    ) -> Self {
+        Self {
+            initial_date,
+            hazard_rates,
+        }
+    }
+
+    /// Curve with a constant hazard rate.
+    #[must_use]
+    pub fn flat(initial_date: OffsetDateTime, hazard_rate: f64) -> Self {
+        Self::new(initial_date, BTreeMap::from([(initial_date, hazard_rate)]))
+    }
+
+    /// Hazard rate on `date` (zero if the curve has no dates).
+    #[must_use]
+    pub fn hazard_rate(&self, date: OffsetDateTime) -> f64 {
+        self.hazard_rates
+            .range(date..)
+            .next()
+            .or_else(|| self.hazard_rates.iter().next_back())
+            .map_or(0.0, |(_, rate)| *rate)
+    }
+
+    /// Probability of surviving from the initial date to `date`.
+    #[must_use]
+    pub fn survival_probability(&self, date: OffsetDateTime) -> f64 {
+        let t = self.year_fraction(date).max(0.0);
+        let (mut integral, mut previous, mut last) = (0.0, 0.0, 0.0);
+
+        for (pillar, rate) in &self.hazard_rates {
+            let end = self.year_fraction(*pillar);
+            if t <= end {
+                return (-(integral + rate * (t - previous).max(0.0))).exp();
+            }
+            integral += rate * (end - previous).max(0.0);
+            (previous, last) = (end.max(previous), *rate);
+        }
+
+        (-(integral + last * (t - previous))).exp()
+    }
+
+    /// Probability of defaulting between the initial date and `date`.
+    #[must_use]
+    pub fn default_probability(&self, date: OffsetDateTime) -> f64 {
+        1.0 - self.survival_probability(date)
+    }
+
+    /// Year fraction (Actual/365) from the initial date of the curve.
+    #[must_use]
+    pub fn year_fraction(&self, date: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(self.initial_date, date, &DayCountConvention::Actual365)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hazard_rate {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::Duration;
+
+    #[test]
+    fn test_survival_probability() {
+        let today = OffsetDateTime::UNIX_EPOCH;
+        let days = |n: i64| today + Duration::days(n);
+        let curve = HazardRateCurve::new(
+            today,
+            BTreeMap::from([(days(365), 0.01), (days(1095), 0.02)]),
+        );
+
+        assert_approx_equal!(curve.survival_probability(today), 1.0, 1e-15);
+        assert_approx_equal!(curve.hazard_rate(days(100)), 0.01, 1e-15);
+        assert_approx_equal!(curve.hazard_rate(days(730)), 0.02, 1e-15);
+        assert_approx_equal!(curve.hazard_rate(days(5000)), 0.02, 1e-15);
+
+        // Within the second segment, and extrapolated beyond the last date.
+        assert_approx_equal!(
+            curve.survival_probability(days(730)),
+            (-0.03_f64).exp(),
+            1e-15
+        );
+        assert_approx_equal!(
+            curve.default_probability(days(1460)),
+            1.0 - (-0.07_f64).exp(),
+            1e-15
+        );
+    }
+}
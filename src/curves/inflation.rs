@@ -0,0 +1,303 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Inflation (CPI) curves.
+//!
+//! Price indices are published monthly, and the fixing of a month is dated
+//! on its first day. The curve projects the index from its base month with
+//! annually compounded zero-coupon inflation rates $z(t)$, interpolated
+//! linearly in time, and optional monthly seasonality factors $s_m$:
+//!
+//! $$
+//! I(t) = I(0) \, (1 + z(t))^t \, \frac{S_{m(t)}}{S_{m(0)}},
+//! \qquad S_m = \prod_{k \le m} s_k
+//! $$
+//!
+//! The seasonality factors are normalised so that their product is one: the
+//! seasonality cancels over whole years.
+//!
+//! Instruments reference the index with an indexation lag of a few months,
+//! possibly interpolating linearly between the fixings of two consecutive
+//! months over the days of the payment month (as for most inflation-linked
+//! bonds).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::time::{DayCountConvention, DayCounter};
+use std::collections::BTreeMap;
+use time::{Date, Month, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Indexation of an inflation-linked cash flow: which fixing of the index
+/// applies on a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indexation {
+    /// Lag in months between the date and the month of the fixing.
+    pub lag_months: i32,
+    /// Interpolate linearly between the fixings of the lagged month and the
+    /// next, over the days of the month of the date.
+    pub interpolated: bool,
+}
+
+/// Inflation curve projecting a price index.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use std::collections::BTreeMap;
+/// use time::macros::datetime;
+///
+/// let base = datetime!(2024-01-01 0:00 UTC);
+/// let curve = InflationCurve::new(
+///     base,
+///     300.0,
+///     BTreeMap::from([(datetime!(2034-01-01 0:00 UTC), 0.025)]),
+/// );
+///
+/// // Three months lag, interpolated: the index on 16 April is half way
+/// // between the fixings of January and February.
+/// let indexation = Indexation { lag_months: 3, interpolated: true };
+/// let reference = curve.reference_index(datetime!(2024-04-16 0:00 UTC), &indexation);
+/// let (jan, feb) = (curve.index(base), curve.index(datetime!(2024-02-01 0:00 UTC)));
+///
+/// assert!((reference - (jan + feb) / 2.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct InflationCurve {
+    /// Base month of the curve (the first day of the month).
+    pub base_date: OffsetDateTime,
+    /// Fixing of the index for the base month.
+    pub base_index: f64,
+    /// Zero-coupon inflation rates (annually compounded) by date.
+    pub rates: BTreeMap<OffsetDateTime, f64>,
+    /// Monthly seasonality factors, January to December, with a product
+    /// of one.
+    pub seasonality: Option<[f64; 12]>,
+    /// Published fixings, by month (the first day of the month). They take
+    /// precedence over the projection.
+    pub fixings: BTreeMap<OffsetDateTime, f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl InflationCurve {
+    /// New curve without seasonality or fixings. The base date is moved to
+    /// the first day of its month.
+    #[must_use]
+    pub fn new(
+        base_date: OffsetDateTime,
+        base_index: f64,
+        rates: BTreeMap<OffsetDateTime, f64>,
+    ) -> Self {
+        Self {
+            base_date: add_months(base_date, 0),
+            base_index,
+            rates,
+            seasonality: None,
+            fixings: BTreeMap::new(),
+        }
+    }
+
+    /// Sets monthly seasonality factors (January to December), normalised
+    /// by their geometric mean.
+    #[must_use]
+    pub fn with_seasonality(mut self, factors: [f64; 12]) -> Self {
+        let mean = factors.iter().product::<f64>().powf(1.0 / 12.0);
+        self.seasonality = Some(factors.map(|factor| factor / mean));
+        self
+    }
+
+    /// Adds a published fixing for the month of `date`.
+    #[must_use]
+    pub fn with_fixing(mut self, date: OffsetDateTime, fixing: f64) -> Self {
+        self.fixings.insert(add_months(date, 0), fixing);
+        self
+    }
+
+    /// Zero-coupon inflation rate to `date`: linear in time between the
+    /// dates of the curve, flat outside.
+    #[must_use]
+    pub fn rate(&self, date: OffsetDateTime) -> f64 {
+        let t = self.year_fraction(date);
+        let before = self.rates.range(..=date).next_back();
+        let after = self.rates.range(date..).next();
+
+        match (before, after) {
+            (Some((d0, r0)), Some((d1, r1))) if d0 != d1 => {
+                let (t0, t1) = (self.year_fraction(*d0), self.year_fraction(*d1));
+                r0 + (r1 - r0) * (t - t0) / (t1 - t0)
+            }
+            (Some((_, rate)), _) | (None, Some((_, rate))) => *rate,
+            (None, None) => 0.0,
+        }
+    }
+
+    /// Fixing of the index for the month of `date`: the published fixing if
+    /// there is one, the base index up to the base month, and the
+    /// projection after.
+    #[must_use]
+    pub fn index(&self, date: OffsetDateTime) -> f64 {
+        let month = add_months(date, 0);
+
+        if let Some(fixing) = self.fixings.get(&month) {
+            return *fixing;
+        }
+        if month <= self.base_date {
+            return self.base_index;
+        }
+
+        let t = self.year_fraction(month);
+        let seasonal = self.seasonality.map_or(1.0, |factors| {
+            cumulative(&factors, month.month()) / cumulative(&factors, self.base_date.month())
+        });
+
+        self.base_index * (1.0 + self.rate(month)).powf(t) * seasonal
+    }
+
+    /// Reference index on `date`, with an indexation lag.
+    #[must_use]
+    pub fn reference_index(&self, date: OffsetDateTime, indexation: &Indexation) -> f64 {
+        let month = add_months(date, -indexation.lag_months);
+        let index = self.index(month);
+
+        if !indexation.interpolated {
+            return index;
+        }
+
+        let days = date.month().length(date.year());
+        let weight = f64::from(date.day() - 1) / f64::from(days);
+
+        index + weight * (self.index(add_months(month, 1)) - index)
+    }
+
+    /// Year fraction (Actual/365) from the base date.
+    #[must_use]
+    pub fn year_fraction(&self, date: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(self.base_date, date, &DayCountConvention::Actual365)
+    }
+}
+
+/// First day of the month `months` months after the month of `date`.
+///
+/// # Panics
+///
+/// Panics if the resulting year is out of the supported range.
+#[must_use]
+pub(crate) fn add_months(date: OffsetDateTime, months: i32) -> OffsetDateTime {
+    let total = date.year() * 12 + i32::from(u8::from(date.month())) - 1 + months;
+    let month = u8::try_from(total.rem_euclid(12) + 1).expect("A month is in 1..=12.");
+    let month = Month::try_from(month).expect("A month is in 1..=12.");
+    let first = Date::from_calendar_date(total.div_euclid(12), month, 1)
+        .expect("The date is out of range.");
+
+    date.replace_date(first).replace_time(time::Time::MIDNIGHT)
+}
+
+// Product of the seasonality factors from January to `month`.
+fn cumulative(factors: &[f64; 12], month: Month) -> f64 {
+    factors[..usize::from(u8::from(month))].iter().product()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_inflation_curve {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::datetime;
+
+    fn curve() -> InflationCurve {
+        InflationCurve::new(
+            datetime!(2023-01-15 0:00 UTC),
+            100.0,
+            BTreeMap::from([
+                (datetime!(2024-01-01 0:00 UTC), 0.03),
+                (datetime!(2028-01-01 0:00 UTC), 0.025),
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_index() {
+        let curve = curve();
+
+        assert_eq!(curve.base_date, datetime!(2023-01-01 0:00 UTC));
+        assert_approx_equal!(curve.index(datetime!(2023-01-20 0:00 UTC)), 100.0, 1e-12);
+        assert_approx_equal!(curve.index(datetime!(2024-01-01 0:00 UTC)), 103.0, 1e-12);
+        assert_approx_equal!(curve.rate(datetime!(2030-01-01 0:00 UTC)), 0.025, 1e-15);
+
+        // Fixings override the projection.
+        let fixed = curve
+            .clone()
+            .with_fixing(datetime!(2023-06-10 0:00 UTC), 101.7);
+        assert_approx_equal!(fixed.index(datetime!(2023-06-01 0:00 UTC)), 101.7, 1e-15);
+    }
+
+    #[test]
+    fn test_seasonality() {
+        let factors = [
+            0.996, 1.004, 1.006, 1.004, 1.002, 1.0, 0.999, 1.001, 1.0, 1.001, 0.997, 0.999,
+        ];
+        let seasonal = curve().with_seasonality(factors);
+        let plain = curve();
+
+        // Cancels over whole years, but not within.
+        for date in [
+            datetime!(2024-01-01 0:00 UTC),
+            datetime!(2027-01-01 0:00 UTC),
+        ] {
+            assert_approx_equal!(seasonal.index(date), plain.index(date), 1e-10);
+        }
+        let april = datetime!(2024-04-01 0:00 UTC);
+        assert!(seasonal.index(april) > plain.index(april));
+    }
+
+    #[test]
+    fn test_reference_index() {
+        let curve = curve();
+        let date = datetime!(2024-07-11 0:00 UTC);
+
+        let lagged = Indexation {
+            lag_months: 3,
+            interpolated: false,
+        };
+        assert_approx_equal!(
+            curve.reference_index(date, &lagged),
+            curve.index(datetime!(2024-04-01 0:00 UTC)),
+            1e-12
+        );
+
+        let interpolated = Indexation {
+            lag_months: 3,
+            interpolated: true,
+        };
+        let (april, may) = (
+            curve.index(datetime!(2024-04-01 0:00 UTC)),
+            curve.index(datetime!(2024-05-01 0:00 UTC)),
+        );
+        assert_approx_equal!(
+            curve.reference_index(date, &interpolated),
+            april + 10.0 / 31.0 * (may - april),
+            1e-12
+        );
+
+        assert_eq!(
+            add_months(datetime!(2024-02-29 12:00 UTC), -14),
+            datetime!(2022-12-01 0:00 UTC)
+        );
+    }
+}
@@ -16,6 +16,30 @@
 pub mod curve;
 pub use curve::*;
 
+/// Yield curve bootstrapping from market instruments.
+pub mod bootstrap;
+pub use bootstrap::*;
+
+/// Discounting and projection curves for dual-curve pricing.
+pub mod context;
+pub use context::*;
+
+/// Hazard rate curves for credit pricing.
+pub mod hazard_rate;
+pub use hazard_rate::*;
+
+/// Credit (survival probability) curves and risky discounting.
+pub mod credit;
+pub use credit::*;
+
+/// Inflation (price index) curves.
+pub mod inflation;
+pub use inflation::*;
+
+/// Key-rate DV01s: sensitivities to the pillars of the curves.
+pub mod sensitivity;
+pub use sensitivity::*;
+
 /// Surface implementations.
 /// Surfaces are simply [Curve]s with an additional dimension.
 /// For example, a volatility surface is a function of time and strike/moneyness.
@@ -30,3 +54,7 @@ pub use nelson_siegel::*;
 /// This model is an extension of the Nelson-Siegel model.
 pub mod nelson_siegel_svensson;
 pub use nelson_siegel_svensson::*;
+
+/// Dates and curves shared by the unit tests.
+#[cfg(test)]
+pub(crate) mod test_utils;
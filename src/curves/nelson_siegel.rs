@@ -8,7 +8,10 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::curves::{Curve, CurveModel};
+use crate::math::{solve_decreasing, LevenbergMarquardt, RootFindingError};
 use crate::time::{DayCountConvention, DayCounter};
+use nalgebra::{DMatrix, DVector};
+use std::cell::Cell;
 use time::OffsetDateTime;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -16,11 +19,44 @@ use time::OffsetDateTime;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Nelson-Siegel (1987) model parameters.
+///
+/// The zero rate (continuously compounded) at maturity `t` is
+///
+/// $$
+/// z(t) = \beta_0 + \beta_1 \frac{1 - e^{-t/\lambda}}{t/\lambda}
+///     + \beta_2 \left(\frac{1 - e^{-t/\lambda}}{t/\lambda} - e^{-t/\lambda}\right)
+/// $$
+///
+/// ```
+/// use RustQuant::curves::*;
+///
+/// let maturities = [0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0];
+/// let model = NelsonSiegel::new(0.05, -0.02, 0.01, 2.0);
+/// let rates: Vec<f64> = maturities.iter().map(|t| model.zero_rate(*t)).collect();
+///
+/// let fitted = NelsonSiegel::fit_zero_rates(&maturities, &rates).unwrap();
+///
+/// assert!((fitted.zero_rate(4.0) - model.zero_rate(4.0)).abs() < 1e-8);
+/// ```
+#[derive(Debug, Clone, Copy)]
 pub struct NelsonSiegel {
-    beta0: f64,
-    beta1: f64,
-    beta2: f64,
-    lambda: f64,
+    /// Long-term level of the rates.
+    pub beta0: f64,
+    /// Slope: the short rate is `beta0 + beta1`.
+    pub beta1: f64,
+    /// Curvature (hump).
+    pub beta2: f64,
+    /// Time scale of the slope and curvature (in years).
+    pub lambda: f64,
+}
+
+/// Bond quoted by its yield to maturity, used to fit a parametric curve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondYieldQuote {
+    /// Cash flows of the bond, as `(time in years, amount)` pairs.
+    pub cashflows: Vec<(f64, f64)>,
+    /// Yield to maturity (continuously compounded).
+    pub yield_to_maturity: f64,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -38,6 +74,163 @@ impl NelsonSiegel {
             lambda,
         }
     }
+
+    /// Zero rate (continuously compounded) at maturity `t` (in years).
+    #[must_use]
+    pub fn zero_rate(&self, t: f64) -> f64 {
+        let [slope, curvature] = loadings(t, self.lambda);
+        self.beta0 + self.beta1 * slope + self.beta2 * curvature
+    }
+
+    /// Instantaneous forward rate at time `t` (in years).
+    #[must_use]
+    pub fn instantaneous_forward_rate(&self, t: f64) -> f64 {
+        let decay = (-t / self.lambda).exp();
+        self.beta0 + self.beta1 * decay + self.beta2 * (t / self.lambda) * decay
+    }
+
+    /// Discount factor to maturity `t` (in years).
+    #[must_use]
+    pub fn discount_factor_at(&self, t: f64) -> f64 {
+        (-self.zero_rate(t) * t).exp()
+    }
+
+    /// Forward rate (continuously compounded) from `t1` to `t2`.
+    #[must_use]
+    pub fn forward_rate_between(&self, t1: f64, t2: f64) -> f64 {
+        (self.zero_rate(t2) * t2 - self.zero_rate(t1) * t1) / (t2 - t1)
+    }
+
+    /// Least-squares fit to zero rates at the given maturities.
+    ///
+    /// The betas are linear in the rates for a given `lambda`: a grid search
+    /// over `lambda` gives the starting point of a Levenberg-Marquardt fit of
+    /// all the parameters. Returns `None` if the fit fails.
+    #[must_use]
+    pub fn fit_zero_rates(maturities: &[f64], rates: &[f64]) -> Option<Self> {
+        let start = LAMBDA_GRID
+            .iter()
+            .filter_map(|&lambda| {
+                let (betas, cost) =
+                    linear_fit(maturities, rates, |t| loadings(t, lambda).to_vec())?;
+                Some((cost, [betas[0], betas[1], betas[2], lambda.ln()]))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))?
+            .1;
+
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            let model = Self::from_parameters(p);
+            maturities
+                .iter()
+                .zip(rates)
+                .map(|(t, rate)| model.zero_rate(*t) - rate)
+                .collect()
+        };
+
+        let result = LevenbergMarquardt::default().optimize(residuals, &start)?;
+        Some(Self::from_parameters(&result.minimizer))
+    }
+
+    /// Least-squares fit to the yields to maturity of bonds, starting from
+    /// a fit of the yields as zero rates at the bonds' maturities.
+    /// Returns `None` if the fit fails, or if a yield cannot be solved for.
+    #[must_use]
+    pub fn fit_bond_yields(bonds: &[BondYieldQuote]) -> Option<Self> {
+        let (maturities, yields): (Vec<f64>, Vec<f64>) = bonds
+            .iter()
+            .map(|bond| (bond.maturity(), bond.yield_to_maturity))
+            .unzip();
+        let start = Self::fit_zero_rates(&maturities, &yields)?;
+
+        let failed = Cell::new(false);
+        let residuals = |p: &[f64]| {
+            bond_yield_residuals(bonds, |t| Self::from_parameters(p).discount_factor_at(t))
+                .unwrap_or_else(|_| {
+                    failed.set(true);
+                    vec![f64::NAN; bonds.len()]
+                })
+        };
+
+        let result = LevenbergMarquardt::default().optimize(residuals, &start.to_parameters())?;
+        (!failed.get()).then(|| Self::from_parameters(&result.minimizer))
+    }
+
+    // Parameters of the fit: the betas and the log of lambda (which keeps
+    // lambda positive).
+    fn to_parameters(self) -> [f64; 4] {
+        [self.beta0, self.beta1, self.beta2, self.lambda.ln()]
+    }
+
+    fn from_parameters(p: &[f64]) -> Self {
+        Self::new(p[0], p[1], p[2], p[3].exp())
+    }
+}
+
+impl BondYieldQuote {
+    /// New bond quote.
+    #[must_use]
+    pub fn new(cashflows: &[(f64, f64)], yield_to_maturity: f64) -> Self {
+        Self {
+            cashflows: cashflows.to_vec(),
+            yield_to_maturity,
+        }
+    }
+
+    /// Fixed coupon bond with unit face value, paying `frequency` coupons
+    /// a year until `maturity` (in years).
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn fixed_coupon(
+        maturity: f64,
+        coupon_rate: f64,
+        frequency: usize,
+        yield_to_maturity: f64,
+    ) -> Self {
+        let period = 1.0 / frequency as f64;
+        let coupons = (maturity / period - 1e-9).ceil() as usize;
+
+        let mut cashflows: Vec<(f64, f64)> = (0..coupons)
+            .map(|i| (maturity - i as f64 * period, coupon_rate * period))
+            .rev()
+            .collect();
+        if let Some(last) = cashflows.last_mut() {
+            last.1 += 1.0;
+        }
+
+        Self::new(&cashflows, yield_to_maturity)
+    }
+
+    /// Maturity of the bond: the time of its last cash flow.
+    #[must_use]
+    pub fn maturity(&self) -> f64 {
+        self.cashflows.iter().map(|(t, _)| *t).fold(0.0, f64::max)
+    }
+
+    /// Price of the bond from discount factors.
+    pub fn price<F: Fn(f64) -> f64>(&self, discount_factor: F) -> f64 {
+        self.cashflows
+            .iter()
+            .map(|(t, amount)| amount * discount_factor(*t))
+            .sum()
+    }
+
+    /// Yield to maturity (continuously compounded) of the bond at a price,
+    /// by Brent's method on a bracket of the yield.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if no yield gives the price, e.g. if
+    /// the price is negative.
+    pub fn yield_from_price(&self, price: f64) -> Result<f64, RootFindingError> {
+        solve_decreasing(
+            |y| self.price(|t| (-y * t).exp()) - price,
+            self.yield_to_maturity,
+        )
+    }
 }
 
 impl CurveModel for NelsonSiegel {
@@ -48,16 +241,7 @@ impl CurveModel for NelsonSiegel {
             "Date must be in the future."
         );
 
-        let tau = DayCounter::day_count_factor(
-            OffsetDateTime::now_utc(),
-            date,
-            &DayCountConvention::Actual365,
-        );
-
-        let term1 = f64::exp(-tau / self.lambda);
-        let term2 = (tau / self.lambda) * term1;
-
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2
+        self.instantaneous_forward_rate(year_fraction(date))
     }
 
     /// Returns the spot rate for a given date.
@@ -67,31 +251,102 @@ impl CurveModel for NelsonSiegel {
             "Date must be in the future."
         );
 
-        let tau = DayCounter::day_count_factor(
-            OffsetDateTime::now_utc(),
-            date,
-            &DayCountConvention::Actual365,
-        );
-
-        let term1 = self.lambda * (1. - f64::exp(-tau / self.lambda)) / tau;
-        let term2 = term1 - f64::exp(-tau / self.lambda);
-
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2
+        self.zero_rate(year_fraction(date))
     }
 
     fn discount_factor(&self, date: OffsetDateTime) -> f64 {
-        let tau = DayCounter::day_count_factor(
-            OffsetDateTime::now_utc(),
-            date,
-            &DayCountConvention::Actual365,
-        );
+        self.discount_factor_at(year_fraction(date))
+    }
 
-        f64::exp(-self.spot_rate(date) * tau / 100.)
+    /// Fits the model to the rates of a curve, sampled at 100 dates from
+    /// its initial to its terminal date (year fractions from the initial
+    /// date). Returns the model unchanged if the fit fails.
+    fn calibrate<C: Curve>(&self, curve: C) -> Self {
+        let (maturities, rates) = sample_curve(&curve);
+        Self::fit_zero_rates(&maturities, &rates).unwrap_or(*self)
     }
+}
+
+// Year fraction (Actual/365) from now to `date`.
+pub(crate) fn year_fraction(date: OffsetDateTime) -> f64 {
+    DayCounter::day_count_factor(
+        OffsetDateTime::now_utc(),
+        date,
+        &DayCountConvention::Actual365,
+    )
+}
 
-    fn calibrate<C: Curve>(&self, _curve: C) -> Self {
-        unimplemented!()
+// Slope and curvature loadings of the Nelson-Siegel zero rate.
+pub(crate) fn loadings(t: f64, lambda: f64) -> [f64; 2] {
+    let x = t / lambda;
+
+    // Limits at t = 0.
+    if x < 1e-10 {
+        return [1.0, 0.0];
     }
+
+    let slope = -(-x).exp_m1() / x;
+    [slope, slope - (-x).exp()]
+}
+
+// Time scales of the grid search of the fits (in years).
+pub(crate) const LAMBDA_GRID: [f64; 16] = [
+    0.1, 0.15, 0.25, 0.35, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0, 7.0, 10.0, 15.0, 20.0,
+];
+
+// Linear least-squares fit of `rates` to a constant plus the `factors` at
+// each maturity. Returns the coefficients and the sum of squared residuals.
+pub(crate) fn linear_fit<F>(
+    maturities: &[f64],
+    rates: &[f64],
+    factors: F,
+) -> Option<(Vec<f64>, f64)>
+where
+    F: Fn(f64) -> Vec<f64>,
+{
+    let rows: Vec<Vec<f64>> = maturities
+        .iter()
+        .map(|t| std::iter::once(1.0).chain(factors(*t)).collect())
+        .collect();
+    let columns = rows.first()?.len();
+
+    let x = DMatrix::from_fn(rows.len(), columns, |i, j| rows[i][j]);
+    let y = DVector::from_column_slice(rates);
+
+    let betas = x.clone().svd(true, true).solve(&y, 1e-12).ok()?;
+    let cost = (x * &betas - y).norm_squared();
+
+    Some((betas.as_slice().to_vec(), cost))
+}
+
+// Yield errors of the bonds priced with the given discount factors.
+pub(crate) fn bond_yield_residuals<F>(
+    bonds: &[BondYieldQuote],
+    discount_factor: F,
+) -> Result<Vec<f64>, RootFindingError>
+where
+    F: Fn(f64) -> f64,
+{
+    bonds
+        .iter()
+        .map(|bond| {
+            Ok(bond.yield_from_price(bond.price(&discount_factor))? - bond.yield_to_maturity)
+        })
+        .collect()
+}
+
+// Maturities (from the initial date) and rates of a curve at 100 dates.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn sample_curve<C: Curve>(curve: &C) -> (Vec<f64>, Vec<f64>) {
+    let (start, end) = (curve.initial_date(), curve.terminal_date());
+
+    (1..=100)
+        .map(|i| {
+            let date = start + (end - start) * (f64::from(i) / 100.0);
+            let t = DayCounter::day_count_factor(start, date, &DayCountConvention::Actual365);
+            (t, curve.rate(date))
+        })
+        .unzip()
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -101,6 +356,7 @@ impl CurveModel for NelsonSiegel {
 #[cfg(test)]
 mod tests_nelson_siegel {
     use super::*;
+    use crate::assert_approx_equal;
     // use crate::plot_vector;
     use time::Duration;
 
@@ -130,4 +386,80 @@ mod tests_nelson_siegel {
         // plot_vector!(forward_curve, "./images/nelson_siegel_forward.png");
         // plot_vector!(discount_curve, "./images/nelson_siegel_discount.png");
     }
+
+    const MATURITIES: [f64; 12] = [
+        0.25, 0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0,
+    ];
+
+    #[test]
+    fn test_nelson_siegel_rates() {
+        let ns = NelsonSiegel::new(0.05, -0.02, 0.01, 2.0);
+
+        // Short rate limit, and the forward rate is the integral of the
+        // instantaneous forward rate.
+        assert_approx_equal!(ns.zero_rate(0.0), 0.03, 1e-12);
+        assert_approx_equal!(ns.zero_rate(1e-8), 0.03, 1e-8);
+
+        let (t1, t2) = (1.0, 3.0);
+        let steps = 10_000;
+        let integral = (0..steps)
+            .map(|i| {
+                let t = t1 + (f64::from(i) + 0.5) * (t2 - t1) / f64::from(steps);
+                ns.instantaneous_forward_rate(t)
+            })
+            .sum::<f64>()
+            / f64::from(steps);
+
+        assert_approx_equal!(ns.forward_rate_between(t1, t2), integral, 1e-9);
+        assert_approx_equal!(
+            ns.discount_factor_at(t2) / ns.discount_factor_at(t1),
+            (-ns.forward_rate_between(t1, t2) * (t2 - t1)).exp(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_nelson_siegel_fit_zero_rates() {
+        let ns = NelsonSiegel::new(0.0806, -0.0031, -0.0625, 1.58);
+        let rates: Vec<f64> = MATURITIES.iter().map(|t| ns.zero_rate(*t)).collect();
+
+        let fitted = NelsonSiegel::fit_zero_rates(&MATURITIES, &rates).unwrap();
+
+        assert_approx_equal!(fitted.beta0, ns.beta0, 1e-6);
+        assert_approx_equal!(fitted.beta1, ns.beta1, 1e-6);
+        assert_approx_equal!(fitted.beta2, ns.beta2, 1e-6);
+        assert_approx_equal!(fitted.lambda, ns.lambda, 1e-4);
+    }
+
+    #[test]
+    fn test_nelson_siegel_fit_bond_yields() {
+        let ns = NelsonSiegel::new(0.045, -0.015, 0.02, 1.5);
+
+        let bonds: Vec<BondYieldQuote> = [1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0]
+            .iter()
+            .map(|maturity| {
+                let bond = BondYieldQuote::fixed_coupon(*maturity, 0.04, 2, 0.0);
+                let price = bond.price(|t| ns.discount_factor_at(t));
+                let ytm = bond.yield_from_price(price).unwrap();
+                BondYieldQuote::new(&bond.cashflows, ytm)
+            })
+            .collect();
+
+        let fitted = NelsonSiegel::fit_bond_yields(&bonds).unwrap();
+
+        for bond in &bonds {
+            let price = bond.price(|t| fitted.discount_factor_at(t));
+            assert_approx_equal!(
+                bond.yield_from_price(price).unwrap(),
+                bond.yield_to_maturity,
+                1e-8
+            );
+        }
+        for t in MATURITIES {
+            assert_approx_equal!(fitted.zero_rate(t), ns.zero_rate(t), 1e-6);
+        }
+
+        // No yield gives a negative price.
+        assert!(bonds[0].yield_from_price(-1.0).is_err());
+    }
 }
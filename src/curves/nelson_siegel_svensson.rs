@@ -7,8 +7,12 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::curves::{Curve, CurveModel};
-use crate::time::{DayCountConvention, DayCounter};
+use crate::curves::nelson_siegel::{
+    bond_yield_residuals, linear_fit, loadings, sample_curve, year_fraction, LAMBDA_GRID,
+};
+use crate::curves::{BondYieldQuote, Curve, CurveModel};
+use crate::math::LevenbergMarquardt;
+use std::cell::Cell;
 use time::OffsetDateTime;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -16,13 +20,35 @@ use time::OffsetDateTime;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Nelson-Siegel-Svensson (1994) model parameters.
+///
+/// Extends the Nelson-Siegel zero rate with a second hump,
+/// `beta3` with time scale `lambda2`.
+///
+/// ```
+/// use RustQuant::curves::*;
+///
+/// let maturities = [0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0];
+/// let model = NelsonSiegelSvensson::new(0.05, -0.02, 0.01, -0.01, 2.0, 0.5);
+/// let rates: Vec<f64> = maturities.iter().map(|t| model.zero_rate(*t)).collect();
+///
+/// let fitted = NelsonSiegelSvensson::fit_zero_rates(&maturities, &rates).unwrap();
+///
+/// assert!((fitted.zero_rate(4.0) - model.zero_rate(4.0)).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy)]
 pub struct NelsonSiegelSvensson {
-    beta0: f64,
-    beta1: f64,
-    beta2: f64,
-    beta3: f64,
-    lambda1: f64,
-    lambda2: f64,
+    /// Long-term level of the rates.
+    pub beta0: f64,
+    /// Slope: the short rate is `beta0 + beta1`.
+    pub beta1: f64,
+    /// First curvature (hump).
+    pub beta2: f64,
+    /// Second curvature (hump).
+    pub beta3: f64,
+    /// Time scale of the slope and first curvature (in years).
+    pub lambda1: f64,
+    /// Time scale of the second curvature (in years).
+    pub lambda2: f64,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -49,6 +75,120 @@ impl NelsonSiegelSvensson {
             lambda2,
         }
     }
+
+    /// Zero rate (continuously compounded) at maturity `t` (in years).
+    #[must_use]
+    pub fn zero_rate(&self, t: f64) -> f64 {
+        let [slope, curvature1] = loadings(t, self.lambda1);
+        let [_, curvature2] = loadings(t, self.lambda2);
+
+        self.beta0 + self.beta1 * slope + self.beta2 * curvature1 + self.beta3 * curvature2
+    }
+
+    /// Instantaneous forward rate at time `t` (in years).
+    #[must_use]
+    pub fn instantaneous_forward_rate(&self, t: f64) -> f64 {
+        let decay1 = (-t / self.lambda1).exp();
+        let decay2 = (-t / self.lambda2).exp();
+
+        self.beta0
+            + self.beta1 * decay1
+            + self.beta2 * (t / self.lambda1) * decay1
+            + self.beta3 * (t / self.lambda2) * decay2
+    }
+
+    /// Discount factor to maturity `t` (in years).
+    #[must_use]
+    pub fn discount_factor_at(&self, t: f64) -> f64 {
+        (-self.zero_rate(t) * t).exp()
+    }
+
+    /// Forward rate (continuously compounded) from `t1` to `t2`.
+    #[must_use]
+    pub fn forward_rate_between(&self, t1: f64, t2: f64) -> f64 {
+        (self.zero_rate(t2) * t2 - self.zero_rate(t1) * t1) / (t2 - t1)
+    }
+
+    /// Least-squares fit to zero rates at the given maturities.
+    ///
+    /// As for [`NelsonSiegel::fit_zero_rates`], with a grid search over
+    /// pairs of distinct time scales. Returns `None` if the fit fails.
+    #[must_use]
+    pub fn fit_zero_rates(maturities: &[f64], rates: &[f64]) -> Option<Self> {
+        let start = LAMBDA_GRID
+            .iter()
+            .flat_map(|&lambda1| LAMBDA_GRID.iter().map(move |&lambda2| (lambda1, lambda2)))
+            .filter(|(lambda1, lambda2)| lambda1 > lambda2)
+            .filter_map(|(lambda1, lambda2)| {
+                let (betas, cost) = linear_fit(maturities, rates, |t| {
+                    let [slope, curvature1] = loadings(t, lambda1);
+                    vec![slope, curvature1, loadings(t, lambda2)[1]]
+                })?;
+                let parameters = [
+                    betas[0],
+                    betas[1],
+                    betas[2],
+                    betas[3],
+                    lambda1.ln(),
+                    lambda2.ln(),
+                ];
+                Some((cost, parameters))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))?
+            .1;
+
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            let model = Self::from_parameters(p);
+            maturities
+                .iter()
+                .zip(rates)
+                .map(|(t, rate)| model.zero_rate(*t) - rate)
+                .collect()
+        };
+
+        let result = LevenbergMarquardt::default().optimize(residuals, &start)?;
+        Some(Self::from_parameters(&result.minimizer))
+    }
+
+    /// Least-squares fit to the yields to maturity of bonds, starting from
+    /// a fit of the yields as zero rates at the bonds' maturities.
+    /// Returns `None` if the fit fails, or if a yield cannot be solved for.
+    #[must_use]
+    pub fn fit_bond_yields(bonds: &[BondYieldQuote]) -> Option<Self> {
+        let (maturities, yields): (Vec<f64>, Vec<f64>) = bonds
+            .iter()
+            .map(|bond| (bond.maturity(), bond.yield_to_maturity))
+            .unzip();
+        let start = Self::fit_zero_rates(&maturities, &yields)?;
+
+        let failed = Cell::new(false);
+        let residuals = |p: &[f64]| {
+            bond_yield_residuals(bonds, |t| Self::from_parameters(p).discount_factor_at(t))
+                .unwrap_or_else(|_| {
+                    failed.set(true);
+                    vec![f64::NAN; bonds.len()]
+                })
+        };
+
+        let result = LevenbergMarquardt::default().optimize(residuals, &start.to_parameters())?;
+        (!failed.get()).then(|| Self::from_parameters(&result.minimizer))
+    }
+
+    // Parameters of the fit: the betas and the logs of the time scales.
+    fn to_parameters(self) -> [f64; 6] {
+        [
+            self.beta0,
+            self.beta1,
+            self.beta2,
+            self.beta3,
+            self.lambda1.ln(),
+            self.lambda2.ln(),
+        ]
+    }
+
+    fn from_parameters(p: &[f64]) -> Self {
+        Self::new(p[0], p[1], p[2], p[3], p[4].exp(), p[5].exp())
+    }
 }
 
 impl CurveModel for NelsonSiegelSvensson {
@@ -59,17 +199,7 @@ impl CurveModel for NelsonSiegelSvensson {
             "Date must be in the future."
         );
 
-        let tau = DayCounter::day_count_factor(
-            OffsetDateTime::now_utc(),
-            date,
-            &DayCountConvention::Actual365,
-        );
-
-        let term1 = f64::exp(-tau / self.lambda1);
-        let term2 = (tau / self.lambda1) * term1;
-        let term3 = (tau / self.lambda2) * f64::exp(-tau / self.lambda2);
-
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3
+        self.instantaneous_forward_rate(year_fraction(date))
     }
 
     /// Returns the spot rate for a given date.
@@ -79,32 +209,19 @@ impl CurveModel for NelsonSiegelSvensson {
             "Date must be in the future."
         );
 
-        let tau = DayCounter::day_count_factor(
-            OffsetDateTime::now_utc(),
-            date,
-            &DayCountConvention::Actual365,
-        );
-
-        let term1 = self.lambda1 * (1. - f64::exp(-tau / self.lambda1)) / tau;
-        let term2 = term1 - f64::exp(-tau / self.lambda1);
-        let term3 = self.lambda2 * (1. - f64::exp(-tau / self.lambda2)) / tau
-            - f64::exp(-tau / self.lambda2);
-
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3
+        self.zero_rate(year_fraction(date))
     }
 
     fn discount_factor(&self, date: OffsetDateTime) -> f64 {
-        let tau = DayCounter::day_count_factor(
-            OffsetDateTime::now_utc(),
-            date,
-            &DayCountConvention::Actual365,
-        );
-
-        f64::exp(-self.spot_rate(date) * tau / 100.)
+        self.discount_factor_at(year_fraction(date))
     }
 
-    fn calibrate<C: Curve>(&self, _curve: C) -> Self {
-        unimplemented!()
+    /// Fits the model to the rates of a curve, sampled at 100 dates from
+    /// its initial to its terminal date. Returns the model unchanged if the
+    /// fit fails.
+    fn calibrate<C: Curve>(&self, curve: C) -> Self {
+        let (maturities, rates) = sample_curve(&curve);
+        Self::fit_zero_rates(&maturities, &rates).unwrap_or(*self)
     }
 }
 
@@ -115,6 +232,7 @@ impl CurveModel for NelsonSiegelSvensson {
 #[cfg(test)]
 mod tests_nelson_siegel_svensson {
     use super::*;
+    use crate::assert_approx_equal;
     // use crate::plot_vector;
     use time::Duration;
 
@@ -149,4 +267,49 @@ mod tests_nelson_siegel_svensson {
         //     "./images/nelson_siegel_svensson_discount.png"
         // );
     }
+
+    #[test]
+    fn test_nelson_siegel_svensson_fit_zero_rates() {
+        let nss = NelsonSiegelSvensson::new(0.0806, -0.0031, -0.0625, -0.0198, 1.58, 0.15);
+        let maturities = [
+            0.1, 0.25, 0.5, 0.75, 1.0, 2.0, 3.0, 4.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0,
+        ];
+        let rates: Vec<f64> = maturities.iter().map(|t| nss.zero_rate(*t)).collect();
+
+        let fitted = NelsonSiegelSvensson::fit_zero_rates(&maturities, &rates).unwrap();
+
+        for t in maturities {
+            assert_approx_equal!(fitted.zero_rate(t), nss.zero_rate(t), 1e-7);
+        }
+        assert_approx_equal!(
+            fitted.forward_rate_between(2.0, 5.0),
+            nss.forward_rate_between(2.0, 5.0),
+            1e-6
+        );
+    }
+
+    #[test]
+    fn test_nelson_siegel_svensson_fit_bond_yields() {
+        let nss = NelsonSiegelSvensson::new(0.05, -0.02, 0.01, -0.01, 2.0, 0.5);
+
+        let bonds: Vec<BondYieldQuote> = [0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0]
+            .iter()
+            .map(|maturity| {
+                let bond = BondYieldQuote::fixed_coupon(*maturity, 0.05, 2, 0.0);
+                let price = bond.price(|t| nss.discount_factor_at(t));
+                BondYieldQuote::new(&bond.cashflows, bond.yield_from_price(price).unwrap())
+            })
+            .collect();
+
+        let fitted = NelsonSiegelSvensson::fit_bond_yields(&bonds).unwrap();
+
+        for bond in &bonds {
+            let price = bond.price(|t| fitted.discount_factor_at(t));
+            assert_approx_equal!(
+                bond.yield_from_price(price).unwrap(),
+                bond.yield_to_maturity,
+                1e-7
+            );
+        }
+    }
 }
@@ -0,0 +1,285 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Key-rate DV01s: the sensitivities of a price to the individual points
+//! (pillars) of its curves, by central differences of one basis point.
+//!
+//! Two kinds of ladders are computed:
+//!
+//! - zero-rate ladders, bumping the zero rate of each pillar of a curve (or
+//!   of all the curves of a [`CurveContext`] with a pillar on that date);
+//! - par-rate ladders, bumping the quote of each market instrument of a
+//!   [`CurveBootstrapper`] and bootstrapping the curve again, so that the
+//!   sensitivities are to the hedging instruments.
+//!
+//! Ladders are keyed by the tenor of the pillars from the valuation date.
+//! With linear interpolation of the rates (or of the log of the discount
+//! factors), the zero-rate key-rate DV01s add up to the parallel DV01.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{
+    Curve, CurveBootstrapper, CurveContext, CurveError, CurveInstrument, YieldCurve,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use time::{Duration, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Key-rate DV01s keyed by the tenor of the pillars from the valuation
+/// date: the change in price for a one basis point rise of each pillar.
+pub type KeyRateLadder = BTreeMap<Duration, f64>;
+
+/// One basis point.
+const BASIS_POINT: f64 = 1e-4;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl YieldCurve {
+    /// The curve with the rate of its pillar on `date` shifted by `shift`
+    /// (unchanged if there is no pillar on that date).
+    #[must_use]
+    pub fn pillar_shifted(&self, date: OffsetDateTime, shift: f64) -> Self {
+        let mut curve = self.clone();
+        if let Some(rate) = curve.rates.get_mut(&date) {
+            *rate += shift;
+        }
+        curve
+    }
+
+    /// Zero-rate key-rate DV01s of a price on the curve.
+    ///
+    /// ```
+    /// use RustQuant::curves::*;
+    /// use std::collections::BTreeMap;
+    /// use time::{Duration, OffsetDateTime};
+    ///
+    /// let today = OffsetDateTime::UNIX_EPOCH;
+    /// let curve = YieldCurve::new(BTreeMap::from([
+    ///     (today, 0.03),
+    ///     (today + Duration::days(365), 0.035),
+    ///     (today + Duration::days(730), 0.04),
+    /// ]));
+    ///
+    /// // One year zero-coupon bond: only sensitive to the one year pillar.
+    /// let ladder = curve.key_rate_dv01s(|curve| {
+    ///     100.0 * curve.discount_factor(today + Duration::days(365))
+    /// });
+    ///
+    /// assert!(ladder[&Duration::days(0)].abs() < 1e-12);
+    /// assert!(ladder[&Duration::days(730)].abs() < 1e-12);
+    /// assert!((ladder[&Duration::days(365)] + 0.0097).abs() < 1e-4);
+    /// ```
+    #[must_use]
+    pub fn key_rate_dv01s<F: Fn(&Self) -> f64>(&self, pricer: F) -> KeyRateLadder {
+        let valuation_date = self.initial_date();
+
+        self.rates
+            .keys()
+            .map(|date| {
+                let up = pricer(&self.pillar_shifted(*date, BASIS_POINT));
+                let down = pricer(&self.pillar_shifted(*date, -BASIS_POINT));
+                (*date - valuation_date, (up - down) / 2.0)
+            })
+            .collect()
+    }
+}
+
+impl CurveContext {
+    /// The context with the rate of the pillars on `date` shifted by
+    /// `shift`, on all the curves with a pillar on that date.
+    #[must_use]
+    pub fn pillar_shifted(&self, date: OffsetDateTime, shift: f64) -> Self {
+        Self {
+            discount_curve: self.discount_curve.pillar_shifted(date, shift),
+            projection_curves: self
+                .projection_curves
+                .iter()
+                .map(|(index, curve)| (index.clone(), curve.pillar_shifted(date, shift)))
+                .collect(),
+        }
+    }
+
+    /// Zero-rate key-rate DV01s of a price on the context, on the pillars
+    /// of all its curves. A pillar date shared by several curves is bumped
+    /// on all of them at once.
+    #[must_use]
+    pub fn key_rate_dv01s<F: Fn(&Self) -> f64>(&self, pricer: F) -> KeyRateLadder {
+        let valuation_date = self.valuation_date();
+        let pillars: BTreeSet<OffsetDateTime> = std::iter::once(&self.discount_curve)
+            .chain(self.projection_curves.values())
+            .flat_map(|curve| curve.rates.keys().copied())
+            .collect();
+
+        pillars
+            .into_iter()
+            .map(|date| {
+                let up = pricer(&self.pillar_shifted(date, BASIS_POINT));
+                let down = pricer(&self.pillar_shifted(date, -BASIS_POINT));
+                (date - valuation_date, (up - down) / 2.0)
+            })
+            .collect()
+    }
+}
+
+impl CurveBootstrapper {
+    /// Par-rate key-rate DV01s of a price on the bootstrapped curve: the
+    /// change in price for a one basis point rise of the quote of each
+    /// instrument, keyed by the tenor of its maturity.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`CurveBootstrapper::bootstrap`].
+    pub fn key_rate_dv01s<F: Fn(&YieldCurve) -> f64>(
+        &self,
+        pricer: F,
+    ) -> Result<KeyRateLadder, CurveError> {
+        let mut ladder = KeyRateLadder::new();
+
+        for i in 0..self.instruments.len() {
+            let bumped = |shift: f64| -> Result<f64, CurveError> {
+                let mut bootstrapper = self.clone();
+                bootstrapper.instruments[i] = bootstrapper.instruments[i].bumped(shift);
+                Ok(pricer(&bootstrapper.bootstrap()?))
+            };
+            let dv01 = (bumped(BASIS_POINT)? - bumped(-BASIS_POINT)?) / 2.0;
+            let tenor = self.instruments[i].maturity() - self.valuation_date;
+            *ladder.entry(tenor).or_insert(0.0) += dv01;
+        }
+
+        Ok(ladder)
+    }
+}
+
+impl CurveInstrument {
+    // The instrument with its quoted rate shifted by `shift`.
+    fn bumped(&self, shift: f64) -> Self {
+        let mut instrument = self.clone();
+        match &mut instrument {
+            Self::Deposit { rate, .. }
+            | Self::ForwardRateAgreement { rate, .. }
+            | Self::Swap { rate, .. } => *rate += shift,
+            Self::Future { price, .. } => *price -= 100.0 * shift,
+        }
+        instrument
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_sensitivity {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::days;
+    use crate::curves::CurveInterpolation;
+    use crate::instruments::bonds::FixedRateBond;
+    use crate::instruments::rates::{InterestRateSwap, SwapDirection};
+    use crate::time::{DayCountConvention, PaymentFrequency, Schedule};
+
+    fn bootstrapper() -> CurveBootstrapper {
+        let swap = |years: i64, rate: f64| {
+            CurveInstrument::swap(
+                &Schedule::new_from_start(days(0), Duration::days(365), years),
+                rate,
+            )
+        };
+
+        CurveBootstrapper::new(days(0), DayCountConvention::Actual365)
+            .with_instrument(CurveInstrument::Deposit {
+                maturity: days(182),
+                rate: 0.030,
+            })
+            .with_instrument(swap(1, 0.032))
+            .with_instrument(swap(2, 0.034))
+            .with_instrument(swap(5, 0.037))
+            .with_instrument(swap(10, 0.040))
+    }
+
+    #[test]
+    fn test_zero_rate_ladder() {
+        let curve = bootstrapper().bootstrap().unwrap();
+        let context =
+            CurveContext::new(curve.clone()).with_projection_curve("IBOR", curve.shifted(0.002));
+
+        let fixed = Schedule::new_from_start(days(0), Duration::days(365), 7);
+        let floating = Schedule::new_from_start(days(0), Duration::days(182), 14);
+        let swap = InterestRateSwap::new(
+            1_000_000.0,
+            0.036,
+            SwapDirection::Payer,
+            &fixed,
+            &floating,
+            "IBOR",
+        );
+
+        // The key-rate DV01s add up to the parallel DV01 (to second order in
+        // the bump), and are concentrated on the pillars around maturity.
+        // With log-linear interpolation, the rate at the valuation date has
+        // no effect.
+        let ladder = swap.key_rate_dv01s(&context);
+        let dv01 = swap.dv01(&context);
+        assert_approx_equal!(ladder.values().sum::<f64>(), dv01, 1e-6 * dv01);
+        assert_approx_equal!(ladder[&Duration::ZERO], 0.0, 1e-12);
+        assert!(ladder[&Duration::days(1825)] + ladder[&Duration::days(3650)] > 0.9 * dv01);
+
+        // The same with log-linear interpolation of the discount factors.
+        let log_linear = curve.with_interpolation(CurveInterpolation::LogLinearDiscount);
+        let bond = FixedRateBond::new(100.0, 0.05, &fixed, PaymentFrequency::Annually);
+        let ladder = bond.key_rate_dv01s(&log_linear);
+        let parallel = (bond.dirty_price_from_curve(&log_linear, BASIS_POINT)
+            - bond.dirty_price_from_curve(&log_linear, -BASIS_POINT))
+            / 2.0;
+        assert_approx_equal!(
+            ladder.values().sum::<f64>(),
+            parallel,
+            1e-6 * parallel.abs()
+        );
+        assert!(ladder.values().all(|dv01| *dv01 <= 0.0));
+    }
+
+    #[test]
+    fn test_par_rate_ladder() {
+        let bootstrapper = bootstrapper();
+
+        // A 5y par swap only depends on the 5y quote, its DV01 being the
+        // annuity.
+        let schedule = Schedule::new_from_start(days(0), Duration::days(365), 5);
+        let swap = InterestRateSwap::new(
+            1.0,
+            0.037,
+            SwapDirection::Receiver,
+            &schedule,
+            &schedule,
+            "",
+        );
+        let ladder = bootstrapper
+            .key_rate_dv01s(|curve| swap.npv(&CurveContext::new(curve.clone())))
+            .unwrap();
+
+        let curve = bootstrapper.bootstrap().unwrap();
+        let annuity = swap.annuity(&CurveContext::new(curve));
+        for (tenor, dv01) in &ladder {
+            if *tenor == Duration::days(1825) {
+                assert_approx_equal!(*dv01, -annuity * BASIS_POINT, 1e-9);
+            } else {
+                assert_approx_equal!(*dv01, 0.0, 1e-12);
+            }
+        }
+        assert_eq!(ladder.len(), 5);
+    }
+}
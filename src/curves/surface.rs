@@ -8,35 +8,1116 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// STRUCTS, ENUMS, AND TRAITS
+// IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use super::Curve;
+use crate::instruments::{implied_volatility_black, ImpliedVolatilityError, TypeFlag};
+use crate::math::interpolation::{
+    akima_slopes, monotone_slopes, BicubicInterpolator, BilinearInterpolator, CubicHermite,
+    Extrapolation, Interpolator2D, MonotoneMethod, ThinPlateSpline,
+};
+use crate::models::{durrleman, RawSvi, SviError};
+use nalgebra::{DMatrix, DVector};
 use num_traits::Float;
-use std::collections::BTreeMap;
+use statrs::function::erf::erfc_inv;
+use std::f64::consts::SQRT_2;
 use time::OffsetDateTime;
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
 /// Surface trait.
 pub trait Surface {
     /// Returns the value of the surface for a given time and space coordinate.
     fn value<F: Float>(&self, time: OffsetDateTime, space: F) -> f64;
 }
 
+/// Implied volatility quotes of a single expiry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmileQuotes {
+    /// Time to expiry (in years).
+    pub expiry: f64,
+    /// Forward price of the underlying for the expiry.
+    pub forward: f64,
+    /// Strikes of the quotes.
+    pub strikes: Vec<f64>,
+    /// Black implied volatilities of the quotes.
+    pub volatilities: Vec<f64>,
+}
+
+/// How each expiry's smile is interpolated across strikes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmileInterpolation {
+    /// Raw SVI fitted to the total variances.
+    Svi,
+    /// Cubic smoothing spline of the total variance in log-moneyness,
+    /// extrapolated linearly. The `smoothing` parameter penalises the
+    /// integrated squared second derivative; zero gives the natural cubic
+    /// spline through the quotes.
+    SmoothingSpline {
+        /// Smoothing parameter, non-negative.
+        smoothing: f64,
+    },
+    /// Akima cubic of the total variance in log-moneyness through the
+    /// quotes, extrapolated linearly.
+    Akima,
+    /// Monotone cubic (Steffen) of the total variance in log-moneyness
+    /// through the quotes, extrapolated linearly: no overshooting between
+    /// the quotes.
+    Steffen,
+}
+
+/// Two dimensional interpolation of the total variance between the quoted
+/// expiries, in place of the linear interpolation in time (see
+/// [`VolatilitySurface::with_surface_interpolation`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceInterpolation {
+    /// Bilinear in expiry and log-moneyness, on the grid of the quoted
+    /// expiries and log-moneyness of all the quotes.
+    Bilinear,
+    /// Bicubic spline in expiry and log-moneyness, on the same grid.
+    Bicubic,
+    /// Thin plate spline through the smiles at the quoted strikes, in
+    /// expiry and log-moneyness scaled by their quoted ranges.
+    ThinPlateSpline,
+}
+
 /// Volatility surface.
-/// A volatility surface is a surface of points (volatilities) over a
-/// space dimension (e.g. strike or moneyness) and a time dimension (e.g. dates).
 ///
-/// We represent this as a map from time to a curve of volatilities.
+/// Each expiry's quotes are turned into a smile of total implied variance
+/// `w(k) = v^2 T` in log-moneyness `k = ln(K / F)`, and expiries are
+/// interpolated linearly in total variance at constant log-moneyness.
+/// Forwards are interpolated linearly in their logarithm.
+///
+/// ```
+/// use RustQuant::curves::*;
+///
+/// let strikes = vec![80.0, 90.0, 100.0, 110.0, 120.0];
+///
+/// let surface = VolatilitySurface::new(
+///     vec![
+///         SmileQuotes::new(0.5, 100.0, strikes.clone(), vec![0.26, 0.23, 0.21, 0.20, 0.20]),
+///         SmileQuotes::new(1.0, 101.0, strikes, vec![0.25, 0.23, 0.215, 0.205, 0.20]),
+///     ],
+///     SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+/// )
+/// .unwrap();
+///
+/// let v = surface.vol(95.0, 0.75);
+///
+/// assert!(v > 0.2 && v < 0.25);
+/// assert!(surface.check_arbitrage().is_empty());
+/// ```
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub struct VolatilitySurface {
+    slices: Vec<Slice>,
+    grid: Option<Grid>,
+}
+
+/// Static arbitrage found in a volatility surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArbitrageViolation {
+    /// The risk-neutral density of an expiry is negative, i.e. Durrleman's
+    /// condition `g(k) >= 0` fails.
+    Butterfly {
+        /// Expiry of the smile.
+        expiry: f64,
+        /// Log-moneyness where `g` is most negative.
+        log_moneyness: f64,
+        /// Value of `g` there.
+        density: f64,
+    },
+    /// The total variance decreases between two consecutive expiries.
+    Calendar {
+        /// The later of the two expiries.
+        expiry: f64,
+        /// Log-moneyness where the total variance decreases the most.
+        log_moneyness: f64,
+        /// Change in total variance there.
+        total_variance_change: f64,
+    },
+}
+
+/// Volatility surface errors.
 #[allow(clippy::module_name_repetitions)]
-pub struct VolatilitySurface<C: Curve> {
-    /// The volatilities of the surface.
-    pub volatilities: BTreeMap<f64, C>,
+#[derive(Debug, thiserror::Error)]
+pub enum VolatilitySurfaceError {
+    /// There are no expiries.
+    #[error("Volatility surface needs at least one expiry")]
+    NoQuotes,
+
+    /// The strikes and the quotes of an expiry have different lengths.
+    #[error("Number of strikes and quotes do not match")]
+    LengthMismatch,
+
+    /// Too few quotes to build a smile.
+    #[error("Not enough quotes to build the smile")]
+    NotEnoughQuotes,
+
+    /// An expiry, forward, strike, volatility or smoothing parameter is out of range.
+    #[error("Expiries, forwards, strikes and volatilities must be positive")]
+    NonPositiveInput,
+
+    /// A delta is not in `(-1, 0)` (puts) or `(0, 1)` (calls).
+    #[error("Deltas must be in (-1, 0) for puts or (0, 1) for calls")]
+    InvalidDelta,
+
+    /// Two smiles have the same expiry, or two quotes of a smile have the same strike.
+    #[error("Expiries and strikes must be distinct")]
+    Duplicate,
+
+    /// A price could not be inverted to an implied volatility.
+    #[error(transparent)]
+    ImpliedVolatility(#[from] ImpliedVolatilityError),
+
+    /// The SVI fit of a smile failed.
+    #[error(transparent)]
+    Svi(#[from] SviError),
+}
+
+// Total variance interpolated in expiry and log-moneyness, built from the
+// smiles. The thin plate spline takes coordinates divided by `scale`.
+#[derive(Debug, Clone)]
+enum Grid {
+    Bilinear(BilinearInterpolator),
+    Bicubic(BicubicInterpolator),
+    ThinPlateSpline {
+        spline: ThinPlateSpline,
+        scale: (f64, f64),
+    },
+}
+
+// Smile of a single expiry.
+#[derive(Debug, Clone)]
+struct Slice {
+    expiry: f64,
+    forward: f64,
+    log_moneyness: Vec<f64>,
+    smile: Smile,
+}
+
+#[derive(Debug, Clone)]
+enum Smile {
+    Svi(RawSvi),
+    Spline(SmoothingSpline),
+    Cubic(CubicHermite),
+}
+
+// Natural cubic smoothing spline (Reinsch, 1967), given by its values
+// and second derivatives at the knots.
+#[derive(Debug, Clone)]
+struct SmoothingSpline {
+    x: Vec<f64>,
+    values: Vec<f64>,
+    second_derivatives: Vec<f64>,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl SmileQuotes {
+    /// New smile quotes from strikes and implied volatilities.
+    #[must_use]
+    pub fn new(expiry: f64, forward: f64, strikes: Vec<f64>, volatilities: Vec<f64>) -> Self {
+        Self {
+            expiry,
+            forward,
+            strikes,
+            volatilities,
+        }
+    }
+
+    /// New smile quotes from forward deltas and implied volatilities, as
+    /// quoted in FX markets. Positive deltas are calls and negative deltas
+    /// are puts, and each quote's strike solves `N(d1) = delta` (calls) or
+    /// `-N(-d1) = delta` (puts).
+    ///
+    /// # Errors
+    ///
+    /// - `VolatilitySurfaceError::LengthMismatch` if the deltas and volatilities differ in length.
+    /// - `VolatilitySurfaceError::NonPositiveInput` for a non-positive expiry, forward or volatility.
+    /// - `VolatilitySurfaceError::InvalidDelta` for a delta outside `(-1, 0)` and `(0, 1)`.
+    pub fn from_deltas(
+        expiry: f64,
+        forward: f64,
+        deltas: &[f64],
+        volatilities: Vec<f64>,
+    ) -> Result<Self, VolatilitySurfaceError> {
+        if deltas.len() != volatilities.len() {
+            return Err(VolatilitySurfaceError::LengthMismatch);
+        }
+        if expiry <= 0.0 || forward <= 0.0 || volatilities.iter().any(|&v| v <= 0.0) {
+            return Err(VolatilitySurfaceError::NonPositiveInput);
+        }
+        if deltas.iter().any(|&d| d == 0.0 || d.abs() >= 1.0) {
+            return Err(VolatilitySurfaceError::InvalidDelta);
+        }
+
+        let strikes = deltas
+            .iter()
+            .zip(&volatilities)
+            .map(|(&delta, &v)| {
+                let p = if delta > 0.0 { delta } else { 1.0 + delta };
+                let d1 = -SQRT_2 * erfc_inv(2.0 * p);
+                let s = v * expiry.sqrt();
+
+                forward * (0.5 * s * s - s * d1).exp()
+            })
+            .collect();
+
+        Ok(Self::new(expiry, forward, strikes, volatilities))
+    }
+
+    /// New smile quotes from Black-76 option prices.
+    ///
+    /// # Errors
+    ///
+    /// - `VolatilitySurfaceError::LengthMismatch` if the strikes and prices differ in length.
+    /// - `VolatilitySurfaceError::ImpliedVolatility` if a price cannot be
+    ///   inverted (see [`implied_volatility_black`]).
+    pub fn from_prices(
+        expiry: f64,
+        forward: f64,
+        discount_factor: f64,
+        strikes: Vec<f64>,
+        prices: &[f64],
+        option_type: TypeFlag,
+    ) -> Result<Self, VolatilitySurfaceError> {
+        if strikes.len() != prices.len() {
+            return Err(VolatilitySurfaceError::LengthMismatch);
+        }
+
+        let volatilities = strikes
+            .iter()
+            .zip(prices)
+            .map(|(&k, &price)| {
+                implied_volatility_black(price, forward, k, expiry, discount_factor, option_type)
+            })
+            .collect::<Result<Vec<f64>, _>>()?;
+
+        Ok(Self::new(expiry, forward, strikes, volatilities))
+    }
+}
+
+impl VolatilitySurface {
+    /// Build a volatility surface from the quotes of one or more expiries.
+    ///
+    /// # Errors
+    ///
+    /// - `VolatilitySurfaceError::NoQuotes` if there are no expiries.
+    /// - `VolatilitySurfaceError::LengthMismatch` if an expiry's strikes and
+    ///   volatilities differ in length.
+    /// - `VolatilitySurfaceError::NotEnoughQuotes` if an expiry has fewer than
+    ///   two quotes (spline, Akima or Steffen) or five quotes (SVI).
+    /// - `VolatilitySurfaceError::NonPositiveInput` for a non-positive expiry,
+    ///   forward, strike or volatility, or a negative smoothing parameter.
+    /// - `VolatilitySurfaceError::Duplicate` for repeated expiries or strikes.
+    /// - `VolatilitySurfaceError::Svi` if an SVI fit fails.
+    pub fn new(
+        mut quotes: Vec<SmileQuotes>,
+        interpolation: SmileInterpolation,
+    ) -> Result<Self, VolatilitySurfaceError> {
+        if quotes.is_empty() {
+            return Err(VolatilitySurfaceError::NoQuotes);
+        }
+        if let SmileInterpolation::SmoothingSpline { smoothing } = interpolation {
+            if smoothing.is_nan() || smoothing < 0.0 {
+                return Err(VolatilitySurfaceError::NonPositiveInput);
+            }
+        }
+
+        quotes.sort_by(|a, b| a.expiry.total_cmp(&b.expiry));
+
+        if quotes.windows(2).any(|q| q[0].expiry >= q[1].expiry) {
+            return Err(VolatilitySurfaceError::Duplicate);
+        }
+
+        let slices = quotes
+            .iter()
+            .map(|q| Slice::new(q, interpolation))
+            .collect::<Result<Vec<Slice>, _>>()?;
+
+        Ok(Self { slices, grid: None })
+    }
+
+    /// The surface, with the total variance between the quoted expiries
+    /// interpolated in two dimensions from the smiles, rather than linearly
+    /// in time. The smiles are still used for the arbitrage checks, and
+    /// outside the quoted expiries.
+    ///
+    /// Unlike the linear interpolation, the bicubic and thin plate splines
+    /// are smooth in time, but may introduce calendar arbitrage between the
+    /// quoted expiries.
+    ///
+    /// # Errors
+    ///
+    /// - `VolatilitySurfaceError::NotEnoughQuotes` if there is a single
+    ///   expiry.
+    pub fn with_surface_interpolation(
+        mut self,
+        interpolation: SurfaceInterpolation,
+    ) -> Result<Self, VolatilitySurfaceError> {
+        self.grid = Some(Grid::new(&self.slices, interpolation)?);
+
+        Ok(self)
+    }
+
+    /// Expiries of the quoted smiles, in increasing order.
+    #[must_use]
+    pub fn expiries(&self) -> Vec<f64> {
+        self.slices.iter().map(|s| s.expiry).collect()
+    }
+
+    /// Forward price for an expiry, interpolated linearly in its logarithm
+    /// between the quoted expiries and flat outside them.
+    #[must_use]
+    pub fn forward(&self, expiry: f64) -> f64 {
+        let (first, last) = (&self.slices[0], &self.slices[self.slices.len() - 1]);
+
+        if expiry <= first.expiry {
+            return first.forward;
+        }
+        if expiry >= last.expiry {
+            return last.forward;
+        }
+
+        let (before, after) = self.bracket(expiry);
+        let weight = (expiry - before.expiry) / (after.expiry - before.expiry);
+
+        (before.forward.ln() * (1.0 - weight) + after.forward.ln() * weight).exp()
+    }
+
+    /// Total implied variance `w = v^2 T` at log-moneyness `k = ln(K / F(T))`.
+    ///
+    /// Between quoted expiries the total variance is interpolated linearly
+    /// in time at constant log-moneyness, which preserves the absence of
+    /// calendar arbitrage, or by the [`SurfaceInterpolation`] of the surface.
+    /// Outside them the implied volatility at constant log-moneyness is
+    /// held flat. Negative values (only possible from linear extrapolation
+    /// in log-moneyness) are floored at zero.
+    #[must_use]
+    pub fn total_variance(&self, k: f64, expiry: f64) -> f64 {
+        let (first, last) = (&self.slices[0], &self.slices[self.slices.len() - 1]);
+
+        let w = if expiry <= first.expiry {
+            self.interpolated_total_variance(k, first.expiry) * expiry / first.expiry
+        } else if expiry >= last.expiry {
+            self.interpolated_total_variance(k, last.expiry) * expiry / last.expiry
+        } else {
+            self.interpolated_total_variance(k, expiry)
+        };
+
+        w.max(0.0)
+    }
+
+    /// Implied volatility for a strike and time to expiry (in years).
+    #[must_use]
+    pub fn vol(&self, strike: f64, expiry: f64) -> f64 {
+        let k = (strike / self.forward(expiry)).ln();
+        let first = &self.slices[0];
+
+        // Flat in volatility before the first expiry, including T = 0.
+        if expiry <= first.expiry {
+            return (self.interpolated_total_variance(k, first.expiry).max(0.0) / first.expiry)
+                .sqrt();
+        }
+
+        (self.total_variance(k, expiry) / expiry).sqrt()
+    }
+
+    /// Check the quoted smiles for static arbitrage, on a grid of
+    /// log-moneyness spanning the quotes.
+    ///
+    /// Butterfly arbitrage is checked with Durrleman's condition
+    ///
+    /// $$
+    /// g(k) = \left(1 - \frac{k w'}{2 w}\right)^2 - \frac{w'^2}{4}\left(\frac{1}{w} + \frac{1}{4}\right) + \frac{w''}{2} \geq 0
+    /// $$
+    ///
+    /// and calendar arbitrage by requiring the total variance to increase
+    /// with the expiry at every log-moneyness.
+    /// At most one violation of each kind is reported per expiry.
+    #[must_use]
+    pub fn check_arbitrage(&self) -> Vec<ArbitrageViolation> {
+        const POINTS: usize = 201;
+        const TOLERANCE: f64 = 1e-10;
+
+        let k_min = self
+            .slices
+            .iter()
+            .flat_map(|s| s.log_moneyness.iter().copied())
+            .fold(f64::INFINITY, f64::min);
+        let k_max = self
+            .slices
+            .iter()
+            .flat_map(|s| s.log_moneyness.iter().copied())
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        #[allow(clippy::cast_precision_loss)]
+        let grid: Vec<f64> = (0..POINTS)
+            .map(|i| k_min + (k_max - k_min) * i as f64 / (POINTS - 1) as f64)
+            .collect();
+
+        // Most negative value of a function on the grid.
+        let worst = |f: &dyn Fn(f64) -> f64| {
+            grid.iter()
+                .map(|&k| (k, f(k)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .filter(|&(_, value)| value < -TOLERANCE)
+        };
+
+        let mut violations = Vec::new();
+
+        for slice in &self.slices {
+            if let Some((k, density)) = worst(&|k| slice.smile.durrleman(k)) {
+                violations.push(ArbitrageViolation::Butterfly {
+                    expiry: slice.expiry,
+                    log_moneyness: k,
+                    density,
+                });
+            }
+        }
+
+        for pair in self.slices.windows(2) {
+            let change = |k| pair[1].smile.total_variance(k) - pair[0].smile.total_variance(k);
+
+            if let Some((k, total_variance_change)) = worst(&change) {
+                violations.push(ArbitrageViolation::Calendar {
+                    expiry: pair[1].expiry,
+                    log_moneyness: k,
+                    total_variance_change,
+                });
+            }
+        }
+
+        violations
+    }
+
+    // Total variance at an expiry within the quoted range.
+    fn interpolated_total_variance(&self, k: f64, expiry: f64) -> f64 {
+        let (first, last) = (&self.slices[0], &self.slices[self.slices.len() - 1]);
+
+        if let Some(grid) = &self.grid {
+            return grid.total_variance(k, expiry);
+        }
+        if expiry <= first.expiry {
+            return first.smile.total_variance(k);
+        }
+        if expiry >= last.expiry {
+            return last.smile.total_variance(k);
+        }
+
+        let (before, after) = self.bracket(expiry);
+        let weight = (expiry - before.expiry) / (after.expiry - before.expiry);
+
+        before.smile.total_variance(k) * (1.0 - weight) + after.smile.total_variance(k) * weight
+    }
+
+    // Quoted smiles either side of an expiry strictly inside the quoted range.
+    fn bracket(&self, expiry: f64) -> (&Slice, &Slice) {
+        let i = self.slices.partition_point(|s| s.expiry <= expiry);
+
+        (&self.slices[i - 1], &self.slices[i])
+    }
+}
+
+impl Grid {
+    fn new(
+        slices: &[Slice],
+        interpolation: SurfaceInterpolation,
+    ) -> Result<Self, VolatilitySurfaceError> {
+        if slices.len() < 2 {
+            return Err(VolatilitySurfaceError::NotEnoughQuotes);
+        }
+
+        let expiries: Vec<f64> = slices.iter().map(|s| s.expiry).collect();
+        let mut log_moneyness: Vec<f64> = slices
+            .iter()
+            .flat_map(|s| s.log_moneyness.iter().copied())
+            .collect();
+        log_moneyness.sort_by(f64::total_cmp);
+
+        // Each smile has distinct quotes, so that the grids are valid.
+        let grid = match interpolation {
+            SurfaceInterpolation::Bilinear | SurfaceInterpolation::Bicubic => {
+                log_moneyness.dedup();
+                let total_variances = slices
+                    .iter()
+                    .map(|s| {
+                        log_moneyness
+                            .iter()
+                            .map(|k| s.smile.total_variance(*k))
+                            .collect()
+                    })
+                    .collect();
+
+                if interpolation == SurfaceInterpolation::Bilinear {
+                    Self::Bilinear(
+                        BilinearInterpolator::new(expiries, log_moneyness, total_variances)
+                            .expect("Quoted expiries and strikes are distinct.")
+                            .with_extrapolation(Extrapolation::Linear),
+                    )
+                } else {
+                    Self::Bicubic(
+                        BicubicInterpolator::new(expiries, log_moneyness, total_variances)
+                            .expect("Quoted expiries and strikes are distinct.")
+                            .with_extrapolation(Extrapolation::Linear),
+                    )
+                }
+            }
+            SurfaceInterpolation::ThinPlateSpline => {
+                let scale = (
+                    expiries[expiries.len() - 1] - expiries[0],
+                    log_moneyness[log_moneyness.len() - 1] - log_moneyness[0],
+                );
+                let (mut xs, mut ys, mut zs) = (vec![], vec![], vec![]);
+
+                for slice in slices {
+                    for &k in &slice.log_moneyness {
+                        xs.push(slice.expiry / scale.0);
+                        ys.push(k / scale.1);
+                        zs.push(slice.smile.total_variance(k));
+                    }
+                }
+
+                Self::ThinPlateSpline {
+                    spline: ThinPlateSpline::new(xs, ys, zs)
+                        .expect("Quoted expiries and strikes are distinct.")
+                        .with_extrapolation(Extrapolation::Linear),
+                    scale,
+                }
+            }
+        };
+
+        Ok(grid)
+    }
+
+    fn total_variance(&self, k: f64, expiry: f64) -> f64 {
+        match self {
+            Self::Bilinear(bilinear) => bilinear.interpolate(expiry, k),
+            Self::Bicubic(bicubic) => bicubic.interpolate(expiry, k),
+            Self::ThinPlateSpline { spline, scale } => {
+                spline.interpolate(expiry / scale.0, k / scale.1)
+            }
+        }
+        .expect("Linear extrapolation is defined everywhere.")
+    }
+}
+
+impl Slice {
+    fn new(
+        quotes: &SmileQuotes,
+        interpolation: SmileInterpolation,
+    ) -> Result<Self, VolatilitySurfaceError> {
+        let SmileQuotes {
+            expiry,
+            forward,
+            strikes,
+            volatilities,
+        } = quotes;
+
+        if strikes.len() != volatilities.len() {
+            return Err(VolatilitySurfaceError::LengthMismatch);
+        }
+        if *expiry <= 0.0
+            || *forward <= 0.0
+            || strikes.iter().chain(volatilities).any(|&x| x <= 0.0)
+        {
+            return Err(VolatilitySurfaceError::NonPositiveInput);
+        }
+
+        let mut points: Vec<(f64, f64)> = strikes
+            .iter()
+            .zip(volatilities)
+            .map(|(&k, &v)| ((k / forward).ln(), v * v * expiry))
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        if points.windows(2).any(|p| p[0].0 >= p[1].0) {
+            return Err(VolatilitySurfaceError::Duplicate);
+        }
+
+        let (log_moneyness, total_variances): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+
+        let smile = match interpolation {
+            SmileInterpolation::Svi => Smile::Svi(RawSvi::fit(&log_moneyness, &total_variances)?),
+            SmileInterpolation::SmoothingSpline { smoothing } => {
+                if log_moneyness.len() < 2 {
+                    return Err(VolatilitySurfaceError::NotEnoughQuotes);
+                }
+                Smile::Spline(SmoothingSpline::new(
+                    log_moneyness.clone(),
+                    &total_variances,
+                    smoothing,
+                ))
+            }
+            SmileInterpolation::Akima | SmileInterpolation::Steffen => {
+                if log_moneyness.len() < 2 {
+                    return Err(VolatilitySurfaceError::NotEnoughQuotes);
+                }
+                let slopes = if interpolation == SmileInterpolation::Akima {
+                    akima_slopes(&log_moneyness, &total_variances)
+                } else {
+                    monotone_slopes(&log_moneyness, &total_variances, MonotoneMethod::Steffen)
+                };
+                Smile::Cubic(CubicHermite::new(
+                    log_moneyness.clone(),
+                    total_variances,
+                    slopes,
+                ))
+            }
+        };
+
+        Ok(Self {
+            expiry: *expiry,
+            forward: *forward,
+            log_moneyness,
+            smile,
+        })
+    }
+}
+
+impl Smile {
+    fn total_variance(&self, k: f64) -> f64 {
+        self.derivatives(k).0
+    }
+
+    // Total variance and its first two derivatives.
+    fn derivatives(&self, k: f64) -> (f64, f64, f64) {
+        match self {
+            Self::Svi(svi) => (
+                svi.total_variance(k),
+                svi.total_variance_first_derivative(k),
+                svi.total_variance_second_derivative(k),
+            ),
+            Self::Spline(spline) => spline.derivatives(k),
+            Self::Cubic(cubic) => {
+                let [w, dw, d2w] = cubic
+                    .evaluate(k, Extrapolation::Linear)
+                    .expect("Linear extrapolation is defined everywhere.");
+
+                (w, dw, d2w)
+            }
+        }
+    }
+
+    // Durrleman's function g(k), proportional to the risk-neutral density.
+    fn durrleman(&self, k: f64) -> f64 {
+        let (w, dw, d2w) = self.derivatives(k);
+
+        durrleman(k, w, dw, d2w)
+    }
+}
+
+impl SmoothingSpline {
+    // Minimises sum (y_i - f(x_i))^2 + smoothing * int f''^2 over natural
+    // cubic splines f, for sorted and distinct x.
+    fn new(x: Vec<f64>, y: &[f64], smoothing: f64) -> Self {
+        let n = x.len();
+
+        if n < 3 {
+            return Self {
+                x,
+                values: y.to_vec(),
+                second_derivatives: vec![0.0; n],
+            };
+        }
+
+        let h: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+
+        // Q is n x (n - 2) second-difference matrix, R is (n - 2) x (n - 2) tridiagonal.
+        let mut q = DMatrix::zeros(n, n - 2);
+        let mut r = DMatrix::zeros(n - 2, n - 2);
+
+        for j in 0..n - 2 {
+            q[(j, j)] = 1.0 / h[j];
+            q[(j + 1, j)] = -1.0 / h[j] - 1.0 / h[j + 1];
+            q[(j + 2, j)] = 1.0 / h[j + 1];
+
+            r[(j, j)] = (h[j] + h[j + 1]) / 3.0;
+            if j + 1 < n - 2 {
+                r[(j, j + 1)] = h[j + 1] / 6.0;
+                r[(j + 1, j)] = h[j + 1] / 6.0;
+            }
+        }
+
+        let y = DVector::from_column_slice(y);
+        let lhs = &r + smoothing * q.transpose() * &q;
+        let gamma = lhs
+            .cholesky()
+            .expect("Smoothing spline system is positive definite.")
+            .solve(&(q.transpose() * &y));
+        let values = &y - smoothing * &q * &gamma;
+
+        let mut second_derivatives = vec![0.0; n];
+        second_derivatives[1..n - 1].copy_from_slice(gamma.as_slice());
+
+        Self {
+            x,
+            values: values.as_slice().to_vec(),
+            second_derivatives,
+        }
+    }
+
+    // Value and first two derivatives, extrapolated linearly.
+    fn derivatives(&self, k: f64) -> (f64, f64, f64) {
+        let n = self.x.len();
+
+        if n == 1 {
+            return (self.values[0], 0.0, 0.0);
+        }
+
+        let i = self.x.partition_point(|&x| x <= k).clamp(1, n - 1) - 1;
+        let inside = |k: f64| {
+            let h = self.x[i + 1] - self.x[i];
+            let a = (self.x[i + 1] - k) / h;
+            let b = 1.0 - a;
+            let (g0, g1) = (self.values[i], self.values[i + 1]);
+            let (c0, c1) = (self.second_derivatives[i], self.second_derivatives[i + 1]);
+
+            (
+                a * g0 + b * g1 + ((a * a * a - a) * c0 + (b * b * b - b) * c1) * h * h / 6.0,
+                (g1 - g0) / h - (3.0 * a * a - 1.0) * h * c0 / 6.0
+                    + (3.0 * b * b - 1.0) * h * c1 / 6.0,
+                a * c0 + b * c1,
+            )
+        };
+
+        let edge = if k < self.x[0] {
+            self.x[0]
+        } else if k > self.x[n - 1] {
+            self.x[n - 1]
+        } else {
+            return inside(k);
+        };
+
+        let (w, dw, _) = inside(edge);
+
+        (w + dw * (k - edge), dw, 0.0)
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_volatility_surface {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::normalised_black;
+    use statrs::distribution::{ContinuousCDF, Normal};
+
+    const STRIKES: [f64; 9] = [70.0, 80.0, 90.0, 95.0, 100.0, 105.0, 110.0, 120.0, 135.0];
+
+    // Quotes generated from an SVI smile.
+    fn quotes(svi: RawSvi, expiry: f64, forward: f64) -> SmileQuotes {
+        SmileQuotes::new(
+            expiry,
+            forward,
+            STRIKES.to_vec(),
+            STRIKES
+                .iter()
+                .map(|&k| svi.implied_volatility((k / forward).ln(), expiry))
+                .collect(),
+        )
+    }
+
+    fn surface(interpolation: SmileInterpolation) -> VolatilitySurface {
+        VolatilitySurface::new(
+            vec![
+                quotes(RawSvi::new(0.04, 0.4, -0.6, 0.05, 0.3), 2.0, 104.0),
+                quotes(RawSvi::new(0.01, 0.2, -0.5, 0.0, 0.2), 0.5, 101.0),
+            ],
+            interpolation,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_quotes_are_recovered() {
+        for interpolation in [
+            SmileInterpolation::Svi,
+            SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+            SmileInterpolation::Akima,
+            SmileInterpolation::Steffen,
+        ] {
+            let surface = surface(interpolation);
+            let short = quotes(RawSvi::new(0.01, 0.2, -0.5, 0.0, 0.2), 0.5, 101.0);
+
+            assert_eq!(surface.expiries(), vec![0.5, 2.0]);
+
+            for (&k, &v) in short.strikes.iter().zip(&short.volatilities) {
+                assert_approx_equal!(surface.vol(k, 0.5), v, 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_surface_interpolation() {
+        // Total variance linear in expiry at constant log-moneyness.
+        let svi = |expiry: f64| RawSvi::new(0.02 * expiry, 0.1 * expiry, -0.4, 0.0, 0.2);
+        let smiles = [0.25, 1.0, 2.0, 5.0].map(|expiry| quotes(svi(expiry), expiry, 100.0));
+
+        // Bilinear and bicubic interpolate the smiles on the grid of quoted
+        // strikes, the thin plate spline only the quotes.
+        for (interpolation, tolerance) in [
+            (SurfaceInterpolation::Bilinear, 1e-3),
+            (SurfaceInterpolation::Bicubic, 1e-4),
+            (SurfaceInterpolation::ThinPlateSpline, 1e-2),
+        ] {
+            let surface = VolatilitySurface::new(smiles.to_vec(), SmileInterpolation::Svi)
+                .unwrap()
+                .with_surface_interpolation(interpolation)
+                .unwrap();
+
+            // The quotes are recovered.
+            for smile in &smiles {
+                for (&k, &v) in smile.strikes.iter().zip(&smile.volatilities) {
+                    assert_approx_equal!(surface.vol(k, smile.expiry), v, 1e-6);
+                }
+            }
+
+            // Close to the exact surface between them.
+            for expiry in [0.1, 0.5, 1.5, 3.0, 8.0] {
+                for strike in [75.0, 92.5, 100.0, 107.5, 125.0] {
+                    let k = (strike / 100.0).ln();
+                    let exact = svi(expiry).implied_volatility(k, expiry);
+
+                    assert_approx_equal!(surface.vol(strike, expiry), exact, tolerance);
+                }
+            }
+        }
+
+        let single = VolatilitySurface::new(vec![smiles[0].clone()], SmileInterpolation::Svi);
+        assert!(matches!(
+            single
+                .unwrap()
+                .with_surface_interpolation(SurfaceInterpolation::Bicubic),
+            Err(VolatilitySurfaceError::NotEnoughQuotes)
+        ));
+    }
+
+    #[test]
+    fn test_time_interpolation() {
+        let surface = surface(SmileInterpolation::Svi);
+        let (short, long) = (
+            RawSvi::new(0.01, 0.2, -0.5, 0.0, 0.2),
+            RawSvi::new(0.04, 0.4, -0.6, 0.05, 0.3),
+        );
+
+        // Log-linear forward.
+        let forward = surface.forward(1.25);
+        assert_approx_equal!(forward, (101.0_f64 * 104.0).sqrt(), 1e-10);
+
+        // Total variance linear in time at constant log-moneyness.
+        for strike in [85.0, 100.0, 115.0] {
+            let k = (strike / forward).ln();
+            let w = 0.5 * (short.total_variance(k) + long.total_variance(k));
+
+            assert_approx_equal!(surface.vol(strike, 1.25), (w / 1.25).sqrt(), 1e-6);
+        }
+
+        // Flat volatility outside the quoted expiries.
+        assert_approx_equal!(surface.vol(100.0, 0.1), surface.vol(100.0, 0.5), 1e-12);
+        assert_approx_equal!(surface.vol(100.0, 0.0), surface.vol(100.0, 0.5), 1e-12);
+        assert_approx_equal!(surface.vol(100.0, 5.0), surface.vol(100.0, 2.0), 1e-12);
+    }
+
+    #[test]
+    fn test_smoothing_spline() {
+        // Noisy quotes: smoothing trades fit for a flatter smile.
+        let noise = [
+            0.004, -0.003, 0.005, -0.004, 0.003, -0.005, 0.004, -0.003, 0.002,
+        ];
+        let vols: Vec<f64> = noise.iter().map(|e| 0.2 + e).collect();
+        let build = |smoothing| {
+            VolatilitySurface::new(
+                vec![SmileQuotes::new(1.0, 100.0, STRIKES.to_vec(), vols.clone())],
+                SmileInterpolation::SmoothingSpline { smoothing },
+            )
+            .unwrap()
+        };
+
+        let roughness = |surface: &VolatilitySurface| {
+            STRIKES
+                .windows(2)
+                .map(|k| (surface.vol(k[1], 1.0) - surface.vol(k[0], 1.0)).abs())
+                .sum::<f64>()
+        };
+
+        let (exact, smooth) = (build(0.0), build(1.0));
+
+        for (&k, &v) in STRIKES.iter().zip(&vols) {
+            assert_approx_equal!(exact.vol(k, 1.0), v, 1e-12);
+        }
+        assert!(roughness(&smooth) < 0.1 * roughness(&exact));
+
+        // Very large smoothing tends to the least squares line.
+        assert_approx_equal!(build(1e9).vol(100.0, 1.0), 0.2, 1e-3);
+    }
+
+    #[test]
+    fn test_from_deltas() {
+        let (expiry, forward) = (0.5, 1.1);
+        let deltas = [-0.1, -0.25, 0.5, 0.25, 0.1];
+        let vols = vec![0.12, 0.105, 0.1, 0.102, 0.11];
+
+        let quotes = SmileQuotes::from_deltas(expiry, forward, &deltas, vols.clone()).unwrap();
+        let normal = Normal::new(0.0, 1.0).unwrap();
+
+        for ((&delta, &v), &k) in deltas.iter().zip(&vols).zip(&quotes.strikes) {
+            let s = v * expiry.sqrt();
+            let d1 = (forward / k).ln() / s + 0.5 * s;
+            let model = if delta > 0.0 {
+                normal.cdf(d1)
+            } else {
+                -normal.cdf(-d1)
+            };
+
+            assert_approx_equal!(model, delta, 1e-8);
+        }
+
+        // Put strikes below the call strikes.
+        assert!(quotes.strikes.windows(2).all(|k| k[0] < k[1]));
+
+        assert!(matches!(
+            SmileQuotes::from_deltas(expiry, forward, &[1.2], vec![0.1]),
+            Err(VolatilitySurfaceError::InvalidDelta)
+        ));
+    }
+
+    #[test]
+    fn test_from_prices() {
+        let (expiry, forward, discount_factor) = (1.5, 100.0, 0.95);
+        let vols = [0.3, 0.25, 0.22, 0.21, 0.23];
+        let strikes = vec![70.0, 85.0, 100.0, 115.0, 130.0];
+
+        let prices: Vec<f64> = strikes
+            .iter()
+            .zip(vols)
+            .map(|(&k, v)| {
+                discount_factor
+                    * (forward * k).sqrt()
+                    * normalised_black((forward / k).ln(), v * expiry.sqrt(), -1.0)
+            })
+            .collect();
+
+        let quotes = SmileQuotes::from_prices(
+            expiry,
+            forward,
+            discount_factor,
+            strikes,
+            &prices,
+            TypeFlag::Put,
+        )
+        .unwrap();
+
+        for (v, expected) in quotes.volatilities.iter().zip(vols) {
+            assert_approx_equal!(v, expected, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_arbitrage_checks() {
+        assert!(surface(SmileInterpolation::Svi)
+            .check_arbitrage()
+            .is_empty());
+
+        // Short expiry with more variance than the long one.
+        let calendar = VolatilitySurface::new(
+            vec![
+                quotes(RawSvi::new(0.04, 0.4, -0.6, 0.05, 0.3), 2.0, 100.0),
+                quotes(RawSvi::new(0.08, 0.4, -0.6, 0.05, 0.3), 1.0, 100.0),
+            ],
+            SmileInterpolation::Svi,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            calendar.check_arbitrage()[..],
+            [ArbitrageViolation::Calendar { expiry, .. }] if expiry == 2.0
+        ));
+
+        // Axel Vogt's SVI smile, which has butterfly arbitrage.
+        let vogt = RawSvi::new(-0.0410, 0.1331, 0.3060, 0.3586, 0.4153);
+        let butterfly = VolatilitySurface::new(
+            vec![SmileQuotes::new(
+                1.0,
+                1.0,
+                (-12..=12).map(|i| (f64::from(i) * 0.125).exp()).collect(),
+                (-12..=12)
+                    .map(|i| vogt.implied_volatility(f64::from(i) * 0.125, 1.0))
+                    .collect(),
+            )],
+            SmileInterpolation::Svi,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            butterfly.check_arbitrage()[..],
+            [ArbitrageViolation::Butterfly { density, .. }] if density < 0.0
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        let spline = SmileInterpolation::SmoothingSpline { smoothing: 0.0 };
+
+        assert!(matches!(
+            VolatilitySurface::new(vec![], spline),
+            Err(VolatilitySurfaceError::NoQuotes)
+        ));
+        assert!(matches!(
+            VolatilitySurface::new(
+                vec![SmileQuotes::new(1.0, 100.0, vec![90.0, 100.0], vec![0.2])],
+                spline
+            ),
+            Err(VolatilitySurfaceError::LengthMismatch)
+        ));
+        assert!(matches!(
+            VolatilitySurface::new(
+                vec![SmileQuotes::new(
+                    1.0,
+                    100.0,
+                    vec![90.0, 100.0],
+                    vec![0.2, -0.2]
+                )],
+                spline
+            ),
+            Err(VolatilitySurfaceError::NonPositiveInput)
+        ));
+        assert!(matches!(
+            VolatilitySurface::new(
+                vec![
+                    SmileQuotes::new(1.0, 100.0, vec![90.0, 100.0], vec![0.2, 0.2]),
+                    SmileQuotes::new(1.0, 100.0, vec![90.0, 100.0], vec![0.2, 0.2]),
+                ],
+                spline
+            ),
+            Err(VolatilitySurfaceError::Duplicate)
+        ));
+        assert!(matches!(
+            VolatilitySurface::new(
+                vec![SmileQuotes::new(
+                    1.0,
+                    100.0,
+                    vec![90.0, 100.0],
+                    vec![0.2, 0.2]
+                )],
+                SmileInterpolation::Svi
+            ),
+            Err(VolatilitySurfaceError::Svi(SviError::NotEnoughQuotes))
+        ));
+        assert!(matches!(
+            VolatilitySurface::new(
+                vec![SmileQuotes::new(1.0, 100.0, vec![100.0], vec![0.2])],
+                SmileInterpolation::Akima
+            ),
+            Err(VolatilitySurfaceError::NotEnoughQuotes)
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Dates and curves shared by the unit tests of the curves and the
+//! instruments priced on them. Dates are counted in days from the Unix
+//! epoch, which is the valuation date of the curves.
+
+use crate::curves::{CurveContext, YieldCurve};
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime};
+
+/// Date `n` days after the valuation date.
+pub(crate) fn days(n: i64) -> OffsetDateTime {
+    OffsetDateTime::UNIX_EPOCH + Duration::days(n)
+}
+
+/// Yield curve with the zero rates at the given days.
+pub(crate) fn yield_curve(pillars: &[(i64, f64)]) -> YieldCurve {
+    YieldCurve::new(
+        pillars
+            .iter()
+            .map(|&(n, rate)| (days(n), rate))
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
+
+/// Yield curve from `short` today to `long` in about 11 years.
+pub(crate) fn curve(short: f64, long: f64) -> YieldCurve {
+    yield_curve(&[(0, short), (4000, long)])
+}
+
+/// Flat yield curve.
+pub(crate) fn flat_curve(rate: f64) -> YieldCurve {
+    curve(rate, rate)
+}
+
+/// Single-curve context on [`curve`].
+pub(crate) fn context(short: f64, long: f64) -> CurveContext {
+    CurveContext::new(curve(short, long))
+}
@@ -0,0 +1,394 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bond futures and delivery analytics.
+//!
+//! The short delivers any bond of the deliverable basket, and receives the
+//! invoice amount: the futures price $F$ times the conversion factor $CF$
+//! of the bond, plus its accrued interest on the delivery date. The
+//! conversion factor is the clean price (per unit of face value) of the
+//! bond at a yield equal to the notional coupon of the contract, on the
+//! delivery date (as on Eurex, with the actual coupon dates of the bond).
+//!
+//! For a bond bought at the clean price $B$ for settlement today and
+//! financed at the repo rate $r$ (Actual/360) until delivery:
+//!
+//! - the gross basis is $B - F \cdot CF$;
+//! - the carry is the coupon income less the financing cost, and the net
+//!   basis the gross basis less the carry: the forward clean price less
+//!   $F \cdot CF$;
+//! - the implied repo rate is the return of buying the bond and delivering
+//!   it into the future.
+//!
+//! The cheapest-to-deliver bond has the highest implied repo rate (the
+//! lowest net basis at a given repo rate). Prices are per 100 of face value.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::bonds::FixedRateBond;
+use crate::time::{DayCountConvention, DayCounter};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bond futures contract with a deliverable basket.
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::{PaymentFrequency, Schedule};
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let bond = |first: i64, years: i64, coupon: f64| {
+///     let schedule = Schedule::new_from_start(
+///         today + Duration::days(first),
+///         Duration::days(365),
+///         years,
+///     );
+///     FixedRateBond::new(100.0, coupon, &schedule, PaymentFrequency::Annually)
+/// };
+///
+/// let future = BondFuture::new(
+///     today + Duration::days(90),
+///     0.06,
+///     vec![bond(-200, 9, 0.025), bond(-100, 10, 0.03), bond(-300, 11, 0.02)],
+/// );
+///
+/// let prices = [88.4, 91.9, 78.1];
+/// let ctd = future.cheapest_to_deliver(&prices, 125.0, today).unwrap();
+/// let analytics = future.delivery_analytics(&prices, 125.0, today, 0.03);
+///
+/// assert!(analytics
+///     .iter()
+///     .all(|bond| bond.implied_repo_rate <= analytics[ctd].implied_repo_rate));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondFuture {
+    /// Delivery date.
+    pub delivery_date: OffsetDateTime,
+    /// Notional coupon of the contract (e.g. 6%), the yield of the
+    /// conversion factors.
+    pub notional_coupon: f64,
+    /// Bonds deliverable into the contract.
+    pub deliverable_basket: Vec<FixedRateBond>,
+}
+
+/// Delivery analytics of a bond of the deliverable basket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeliveryAnalytics {
+    /// Conversion factor.
+    pub conversion_factor: f64,
+    /// Gross basis: clean price less the converted futures price.
+    pub gross_basis: f64,
+    /// Carry to delivery: coupon income less financing cost.
+    pub carry: f64,
+    /// Net basis: gross basis less carry.
+    pub net_basis: f64,
+    /// Implied repo rate (Actual/360).
+    pub implied_repo_rate: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BondFuture {
+    /// New bond futures contract.
+    #[must_use]
+    pub const fn new(
+        delivery_date: OffsetDateTime,
+        notional_coupon: f64,
+        deliverable_basket: Vec<FixedRateBond>,
+    ) -> Self {
+        Self {
+            delivery_date,
+            notional_coupon,
+            deliverable_basket,
+        }
+    }
+
+    /// Conversion factor of a bond: its clean price per unit of face value
+    /// at a yield of the notional coupon, on the delivery date.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond matures on or before the delivery date.
+    #[must_use]
+    pub fn conversion_factor(&self, bond: &FixedRateBond) -> f64 {
+        let dirty = bond.dirty_price_from_yield(self.notional_coupon, self.delivery_date);
+
+        bond.clean_price(dirty, self.delivery_date) / bond.face_value
+    }
+
+    /// Invoice amount (per 100 of face value) paid on delivery of a bond.
+    #[must_use]
+    pub fn invoice_amount(&self, bond: &FixedRateBond, futures_price: f64) -> f64 {
+        futures_price * self.conversion_factor(bond)
+            + per_hundred(bond, bond.accrued_interest(self.delivery_date))
+    }
+
+    /// Forward clean price of a bond on the delivery date: its dirty price
+    /// financed at the repo rate, less the coupons received (and
+    /// reinvested at the repo rate) and the accrued interest at delivery.
+    #[must_use]
+    pub fn forward_clean_price(
+        &self,
+        bond: &FixedRateBond,
+        clean_price: f64,
+        settlement: OffsetDateTime,
+        repo_rate: f64,
+    ) -> f64 {
+        let dirty = clean_price + per_hundred(bond, bond.accrued_interest(settlement));
+        let coupons: f64 = self
+            .coupons_to_delivery(bond, settlement)
+            .iter()
+            .map(|(tau, coupon)| coupon * (1.0 + repo_rate * tau))
+            .sum();
+
+        dirty * (1.0 + repo_rate * self.money_market(settlement))
+            - coupons
+            - per_hundred(bond, bond.accrued_interest(self.delivery_date))
+    }
+
+    /// Implied repo rate (Actual/360) of buying a bond at its clean price
+    /// for settlement, and delivering it into the future:
+    ///
+    /// $$
+    /// r = \frac{I + \sum_i C_i - D}{D \tau - \sum_i C_i \tau_i}
+    /// $$
+    ///
+    /// where $I$ is the invoice amount, $D$ the dirty price, and $C_i$ the
+    /// coupons received before delivery, reinvested over $\tau_i$.
+    #[must_use]
+    pub fn implied_repo_rate(
+        &self,
+        bond: &FixedRateBond,
+        clean_price: f64,
+        futures_price: f64,
+        settlement: OffsetDateTime,
+    ) -> f64 {
+        let dirty = clean_price + per_hundred(bond, bond.accrued_interest(settlement));
+        let coupons = self.coupons_to_delivery(bond, settlement);
+        let income: f64 = coupons.iter().map(|(_, coupon)| coupon).sum();
+        let reinvested: f64 = coupons.iter().map(|(tau, coupon)| coupon * tau).sum();
+
+        (self.invoice_amount(bond, futures_price) + income - dirty)
+            / (dirty * self.money_market(settlement) - reinvested)
+    }
+
+    /// Net basis of a bond: its forward clean price less the converted
+    /// futures price.
+    #[must_use]
+    pub fn net_basis(
+        &self,
+        bond: &FixedRateBond,
+        clean_price: f64,
+        futures_price: f64,
+        settlement: OffsetDateTime,
+        repo_rate: f64,
+    ) -> f64 {
+        self.forward_clean_price(bond, clean_price, settlement, repo_rate)
+            - futures_price * self.conversion_factor(bond)
+    }
+
+    /// Delivery analytics of the bonds of the basket, from their clean
+    /// prices (in the order of the basket).
+    #[must_use]
+    pub fn delivery_analytics(
+        &self,
+        clean_prices: &[f64],
+        futures_price: f64,
+        settlement: OffsetDateTime,
+        repo_rate: f64,
+    ) -> Vec<DeliveryAnalytics> {
+        self.deliverable_basket
+            .iter()
+            .zip(clean_prices)
+            .map(|(bond, clean_price)| {
+                let conversion_factor = self.conversion_factor(bond);
+                let gross_basis = clean_price - futures_price * conversion_factor;
+                let net_basis =
+                    self.net_basis(bond, *clean_price, futures_price, settlement, repo_rate);
+
+                DeliveryAnalytics {
+                    conversion_factor,
+                    gross_basis,
+                    carry: gross_basis - net_basis,
+                    net_basis,
+                    implied_repo_rate: self.implied_repo_rate(
+                        bond,
+                        *clean_price,
+                        futures_price,
+                        settlement,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Index in the basket of the cheapest-to-deliver bond: the highest
+    /// implied repo rate. `None` if the basket is empty.
+    #[must_use]
+    pub fn cheapest_to_deliver(
+        &self,
+        clean_prices: &[f64],
+        futures_price: f64,
+        settlement: OffsetDateTime,
+    ) -> Option<usize> {
+        self.deliverable_basket
+            .iter()
+            .zip(clean_prices)
+            .map(|(bond, clean_price)| {
+                self.implied_repo_rate(bond, *clean_price, futures_price, settlement)
+            })
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// Theoretical futures price: the lowest forward clean price over
+    /// conversion factor in the basket (ignoring the delivery options).
+    #[must_use]
+    pub fn theoretical_price(
+        &self,
+        clean_prices: &[f64],
+        settlement: OffsetDateTime,
+        repo_rate: f64,
+    ) -> f64 {
+        self.deliverable_basket
+            .iter()
+            .zip(clean_prices)
+            .map(|(bond, clean_price)| {
+                self.forward_clean_price(bond, *clean_price, settlement, repo_rate)
+                    / self.conversion_factor(bond)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    // Coupons (per 100) paid after settlement up to delivery, with the
+    // money market time from their payment to delivery.
+    fn coupons_to_delivery(
+        &self,
+        bond: &FixedRateBond,
+        settlement: OffsetDateTime,
+    ) -> Vec<(f64, f64)> {
+        bond.cashflows_after(settlement)
+            .into_iter()
+            .filter(|(date, _)| *date <= self.delivery_date)
+            .map(|(date, amount)| {
+                let tau = DayCounter::day_count_factor(
+                    date,
+                    self.delivery_date,
+                    &DayCountConvention::Actual360,
+                );
+                (tau, per_hundred(bond, amount))
+            })
+            .collect()
+    }
+
+    // Money market time from settlement to delivery.
+    fn money_market(&self, settlement: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(
+            settlement,
+            self.delivery_date,
+            &DayCountConvention::Actual360,
+        )
+    }
+}
+
+// Amount per 100 of face value of the bond.
+fn per_hundred(bond: &FixedRateBond, amount: f64) -> f64 {
+    100.0 * amount / bond.face_value
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bond_future {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::days;
+    use crate::time::{PaymentFrequency, Schedule};
+    use time::Duration;
+
+    // Annual bond with its first accrual period starting `first` days from
+    // today, in units of 1,000 face value.
+    fn bond(first: i64, years: i64, coupon: f64) -> FixedRateBond {
+        let schedule = Schedule::new_from_start(days(first), Duration::days(365), years);
+        FixedRateBond::new(1000.0, coupon, &schedule, PaymentFrequency::Annually)
+    }
+
+    fn future() -> BondFuture {
+        BondFuture::new(
+            days(120),
+            0.06,
+            vec![
+                bond(-200, 9, 0.025),
+                bond(-100, 10, 0.030),
+                bond(-300, 12, 0.045),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_conversion_factor() {
+        // Delivered on a coupon date, a bond with the notional coupon
+        // converts at par; higher coupons convert above par.
+        let future = BondFuture::new(days(365), 0.06, vec![]);
+        assert_approx_equal!(future.conversion_factor(&bond(0, 10, 0.06)), 1.0, 1e-12);
+        assert!(future.conversion_factor(&bond(0, 10, 0.08)) > 1.0);
+        assert!(future.conversion_factor(&bond(0, 10, 0.04)) < 1.0);
+    }
+
+    #[test]
+    fn test_basis_and_implied_repo() {
+        let future = future();
+        let prices = [86.0, 89.5, 99.0];
+        let (settlement, repo) = (days(0), 0.035);
+
+        // At the theoretical price, the cheapest bond has a zero net basis
+        // and an implied repo rate of the repo rate; the others are richer.
+        let price = future.theoretical_price(&prices, settlement, repo);
+        let analytics = future.delivery_analytics(&prices, price, settlement, repo);
+        let ctd = future
+            .cheapest_to_deliver(&prices, price, settlement)
+            .unwrap();
+
+        assert_approx_equal!(analytics[ctd].net_basis, 0.0, 1e-10);
+        assert_approx_equal!(analytics[ctd].implied_repo_rate, repo, 1e-12);
+        for (i, bond) in analytics.iter().enumerate() {
+            assert_approx_equal!(bond.gross_basis - bond.carry, bond.net_basis, 1e-12);
+            if i != ctd {
+                assert!(bond.net_basis > 0.0 && bond.implied_repo_rate < repo);
+            }
+        }
+
+        // The second bond pays no coupon before delivery (its next coupon is
+        // on day 265): its carry is the accrual less the financing of its
+        // dirty price. The third pays a coupon on day 65.
+        let bond = &future.deliverable_basket[1];
+        let dirty = 89.5 + bond.accrued_interest(settlement) / 10.0;
+        let carry = (bond.accrued_interest(days(120)) - bond.accrued_interest(settlement)) / 10.0
+            - dirty * repo * 120.0 / 360.0;
+        assert_approx_equal!(analytics[1].carry, carry, 1e-12);
+
+        // Invoice amount.
+        assert_approx_equal!(
+            future.invoice_amount(bond, price),
+            price * analytics[1].conversion_factor + bond.accrued_interest(days(120)) / 10.0,
+            1e-12
+        );
+    }
+}
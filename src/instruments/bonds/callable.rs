@@ -0,0 +1,141 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Fixed coupon bonds with embedded call and put schedules.
+//!
+//! The issuer of a callable bond may redeem it early on the call dates, at
+//! the call price; the holder of a puttable bond may sell it back to the
+//! issuer on the put dates, at the put price. Both are priced by backward
+//! induction on a short-rate lattice, see
+//! [`HullWhiteModel::callable_bond_price`](crate::models::HullWhiteModel::callable_bond_price).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::time::{DayCounter, Schedule};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Fixed coupon bond with call and put schedules.
+///
+/// The call and put prices are the amounts paid on exercise, in addition
+/// to the cash flow due on the exercise date (exercise dates are usually
+/// coupon dates).
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::Schedule;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let schedule = Schedule::new_from_start(today, Duration::days(365), 10);
+///
+/// // 10y 5% bond, callable at par after 3 years.
+/// let bond = schedule.dates[3..10]
+///     .iter()
+///     .fold(CallableBond::new(100.0, 0.05, &schedule), |bond, date| {
+///         bond.with_call(*date, 100.0)
+///     });
+///
+/// assert_eq!(bond.cashflows.len(), 10);
+/// assert_eq!(bond.call_schedule.len(), 7);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallableBond {
+    /// Cash flows by payment date: the coupons, and the face value repaid
+    /// at maturity.
+    pub cashflows: BTreeMap<OffsetDateTime, f64>,
+    /// Call prices by date, at which the issuer may redeem the bond.
+    pub call_schedule: BTreeMap<OffsetDateTime, f64>,
+    /// Put prices by date, at which the holder may redeem the bond.
+    pub put_schedule: BTreeMap<OffsetDateTime, f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CallableBond {
+    /// New bond without call or put schedules, paying the coupon rate on
+    /// the periods of the schedule (with its day count convention) and the
+    /// face value on the last date.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule has no dates.
+    #[must_use]
+    pub fn new(face_value: f64, coupon_rate: f64, schedule: &Schedule) -> Self {
+        let mut cashflows: BTreeMap<OffsetDateTime, f64> = schedule
+            .dates
+            .windows(2)
+            .map(|w| {
+                let tau = DayCounter::day_count_factor(w[0], w[1], &schedule.day_count_convention);
+                (w[1], face_value * coupon_rate * tau)
+            })
+            .collect();
+
+        let maturity = *schedule.dates.last().expect("The schedule has no dates.");
+        *cashflows.entry(maturity).or_insert(0.0) += face_value;
+
+        Self {
+            cashflows,
+            call_schedule: BTreeMap::new(),
+            put_schedule: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a call date, with its call price.
+    #[must_use]
+    pub fn with_call(mut self, date: OffsetDateTime, price: f64) -> Self {
+        self.call_schedule.insert(date, price);
+        self
+    }
+
+    /// Adds a put date, with its put price.
+    #[must_use]
+    pub fn with_put(mut self, date: OffsetDateTime, price: f64) -> Self {
+        self.put_schedule.insert(date, price);
+        self
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_callable_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::Duration;
+
+    #[test]
+    fn test_cashflows() {
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let schedule = Schedule::new_from_start(start, Duration::days(365), 4);
+        let bond = CallableBond::new(100.0, 0.04, &schedule)
+            .with_call(schedule.dates[2], 101.0)
+            .with_put(schedule.dates[3], 99.0);
+
+        let amounts: Vec<f64> = bond.cashflows.values().copied().collect();
+        assert_eq!(amounts.len(), 4);
+        for amount in &amounts[..3] {
+            assert_approx_equal!(*amount, 4.0, 1e-12);
+        }
+        assert_approx_equal!(amounts[3], 104.0, 1e-12);
+
+        assert_eq!(bond.call_schedule[&schedule.dates[2]], 101.0);
+        assert_eq!(bond.put_schedule[&schedule.dates[3]], 99.0);
+    }
+}
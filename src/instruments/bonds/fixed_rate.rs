@@ -0,0 +1,426 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Fixed rate bonds and their analytics.
+//!
+//! Yields are compounded at the coupon frequency $f$, with the street
+//! convention for the discounting of the cash flows $c_k$ paid after the
+//! settlement date:
+//!
+//! $$
+//! P = \sum_k \frac{c_k}{(1 + y / f)^{w + k}}
+//! $$
+//!
+//! where $w$ is the fraction (in the bond's day count convention) of the
+//! current coupon period left after settlement, and $P$ the dirty price.
+//! The clean price is the dirty price less the accrued interest.
+//!
+//! The Z-spread is the parallel shift of the continuously compounded zero
+//! rates of a curve that reprices the bond. For bonds with embedded
+//! options, see the option-adjusted spread in
+//! [`HullWhiteModel::option_adjusted_spread`](crate::models::HullWhiteModel::option_adjusted_spread).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, KeyRateLadder, YieldCurve};
+use crate::instruments::bonds::CallableBond;
+use crate::math::solve_decreasing;
+use crate::math::RootFindingError;
+use crate::time::{DayCountConvention, DayCounter, PaymentFrequency, Schedule};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Fixed rate bond.
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::{PaymentFrequency, Schedule};
+/// use time::{Duration, OffsetDateTime};
+///
+/// let issue = OffsetDateTime::UNIX_EPOCH;
+/// let schedule = Schedule::new_from_start(issue, Duration::days(182), 10);
+/// let bond = FixedRateBond::new(100.0, 0.05, &schedule, PaymentFrequency::SemiAnnually);
+///
+/// // Settlement 60 days into the second coupon period.
+/// let settlement = issue + Duration::days(242);
+/// let dirty = bond.dirty_price_from_yield(0.06, settlement);
+/// let clean = bond.clean_price(dirty, settlement);
+///
+/// assert!((bond.yield_to_maturity(dirty, settlement).unwrap() - 0.06).abs() < 1e-10);
+/// assert!((dirty - clean - bond.accrued_interest(settlement)).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedRateBond {
+    /// Face value, repaid at maturity.
+    pub face_value: f64,
+    /// Annual coupon rate.
+    pub coupon_rate: f64,
+    /// Accrual dates: the start of the first coupon period, then the
+    /// coupon dates, the last being the maturity.
+    pub dates: Vec<OffsetDateTime>,
+    /// Day count convention of the coupons and of the accrued interest.
+    pub day_count_convention: DayCountConvention,
+    /// Coupon frequency, at which yields are compounded.
+    pub frequency: PaymentFrequency,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FixedRateBond {
+    /// New bond on the dates and day count convention of a schedule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule has less than two dates.
+    #[must_use]
+    pub fn new(
+        face_value: f64,
+        coupon_rate: f64,
+        schedule: &Schedule,
+        frequency: PaymentFrequency,
+    ) -> Self {
+        assert!(
+            schedule.dates.len() > 1,
+            "The schedule must have at least two dates."
+        );
+
+        Self {
+            face_value,
+            coupon_rate,
+            dates: schedule.dates.clone(),
+            day_count_convention: schedule.day_count_convention,
+            frequency,
+        }
+    }
+
+    /// Maturity date.
+    #[must_use]
+    pub fn maturity(&self) -> OffsetDateTime {
+        self.dates[self.dates.len() - 1]
+    }
+
+    /// Cash flows (coupons, and the face value at maturity) paid after
+    /// `settlement`.
+    #[must_use]
+    pub fn cashflows_after(&self, settlement: OffsetDateTime) -> Vec<(OffsetDateTime, f64)> {
+        let maturity = self.maturity();
+
+        self.dates
+            .windows(2)
+            .filter(|w| w[1] > settlement)
+            .map(|w| {
+                let coupon = self.face_value * self.coupon_rate * self.year_fraction(w[0], w[1]);
+                let redemption = if w[1] == maturity {
+                    self.face_value
+                } else {
+                    0.0
+                };
+                (w[1], coupon + redemption)
+            })
+            .collect()
+    }
+
+    /// Interest accrued from the start of the coupon period to
+    /// `settlement`.
+    #[must_use]
+    pub fn accrued_interest(&self, settlement: OffsetDateTime) -> f64 {
+        self.dates
+            .windows(2)
+            .find(|w| w[0] <= settlement && settlement < w[1])
+            .map_or(0.0, |w| {
+                self.face_value * self.coupon_rate * self.year_fraction(w[0], settlement)
+            })
+    }
+
+    /// Dirty price from a clean price.
+    #[must_use]
+    pub fn dirty_price(&self, clean_price: f64, settlement: OffsetDateTime) -> f64 {
+        clean_price + self.accrued_interest(settlement)
+    }
+
+    /// Clean price from a dirty price.
+    #[must_use]
+    pub fn clean_price(&self, dirty_price: f64, settlement: OffsetDateTime) -> f64 {
+        dirty_price - self.accrued_interest(settlement)
+    }
+
+    /// Dirty price at a yield to maturity `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has matured on `settlement`.
+    #[must_use]
+    pub fn dirty_price_from_yield(&self, y: f64, settlement: OffsetDateTime) -> f64 {
+        let f = self.periods_per_year();
+
+        self.discounted_cashflows(settlement)
+            .iter()
+            .map(|(periods, c)| c / (1.0 + y / f).powf(*periods))
+            .sum()
+    }
+
+    /// Yield to maturity from a dirty price.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RootFindingError` if no yield reprices the bond.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has matured on `settlement`.
+    pub fn yield_to_maturity(
+        &self,
+        dirty_price: f64,
+        settlement: OffsetDateTime,
+    ) -> Result<f64, RootFindingError> {
+        solve_decreasing(
+            |y| self.dirty_price_from_yield(y, settlement) - dirty_price,
+            self.coupon_rate,
+        )
+    }
+
+    /// Macaulay duration (in years) at a yield to maturity `y`: the average
+    /// time to the cash flows, weighted by their present values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has matured on `settlement`.
+    #[must_use]
+    pub fn macaulay_duration(&self, y: f64, settlement: OffsetDateTime) -> f64 {
+        let f = self.periods_per_year();
+        let (mut price, mut weighted) = (0.0, 0.0);
+
+        for (periods, c) in self.discounted_cashflows(settlement) {
+            let pv = c / (1.0 + y / f).powf(periods);
+            price += pv;
+            weighted += periods / f * pv;
+        }
+
+        weighted / price
+    }
+
+    /// Modified duration at a yield to maturity `y`: the sensitivity
+    /// $-\frac{1}{P} \frac{dP}{dy}$ of the dirty price.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has matured on `settlement`.
+    #[must_use]
+    pub fn modified_duration(&self, y: f64, settlement: OffsetDateTime) -> f64 {
+        self.macaulay_duration(y, settlement) / (1.0 + y / self.periods_per_year())
+    }
+
+    /// Convexity at a yield to maturity `y`: $\frac{1}{P} \frac{d^2P}{dy^2}$
+    /// of the dirty price.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has matured on `settlement`.
+    #[must_use]
+    pub fn convexity(&self, y: f64, settlement: OffsetDateTime) -> f64 {
+        let f = self.periods_per_year();
+        let (mut price, mut curvature) = (0.0, 0.0);
+
+        for (periods, c) in self.discounted_cashflows(settlement) {
+            price += c / (1.0 + y / f).powf(periods);
+            curvature +=
+                c * periods * (periods + 1.0) / (f * f) / (1.0 + y / f).powf(periods + 2.0);
+        }
+
+        curvature / price
+    }
+
+    /// Dirty price on the initial date of a discount curve, with the zero
+    /// rates shifted by `spread`.
+    #[must_use]
+    pub fn dirty_price_from_curve(&self, curve: &YieldCurve, spread: f64) -> f64 {
+        let settlement = curve.initial_date();
+
+        self.cashflows_after(settlement)
+            .iter()
+            .map(|(date, c)| {
+                let t =
+                    DayCounter::day_count_factor(settlement, *date, &DayCountConvention::Actual365);
+                c * curve.discount_factor(*date) * (-spread * t).exp()
+            })
+            .sum()
+    }
+
+    /// Z-spread over a discount curve from a dirty price on the initial
+    /// date of the curve.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RootFindingError` if no spread reprices the bond.
+    pub fn z_spread(&self, curve: &YieldCurve, dirty_price: f64) -> Result<f64, RootFindingError> {
+        solve_decreasing(
+            |spread| self.dirty_price_from_curve(curve, spread) - dirty_price,
+            0.0,
+        )
+    }
+
+    /// Key-rate DV01s of the dirty price on a discount curve: the change
+    /// in price for a one basis point rise of the zero rate of each pillar,
+    /// keyed by tenor.
+    #[must_use]
+    pub fn key_rate_dv01s(&self, curve: &YieldCurve) -> KeyRateLadder {
+        curve.key_rate_dv01s(|curve| self.dirty_price_from_curve(curve, 0.0))
+    }
+
+    // Coupon frequency as a number of periods per year.
+    fn periods_per_year(&self) -> f64 {
+        f64::from(self.frequency as i32)
+    }
+
+    fn year_fraction(&self, start: OffsetDateTime, end: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(start, end, &self.day_count_convention)
+    }
+
+    // Cash flows after settlement, with their discounting periods w + k.
+    #[allow(clippy::cast_precision_loss)]
+    fn discounted_cashflows(&self, settlement: OffsetDateTime) -> Vec<(f64, f64)> {
+        let next = self
+            .dates
+            .iter()
+            .position(|date| *date > settlement)
+            .filter(|next| *next > 0)
+            .expect("The bond has matured.");
+        let (start, end) = (self.dates[next - 1], self.dates[next]);
+        let w = self.year_fraction(settlement, end) / self.year_fraction(start, end);
+
+        self.cashflows_after(settlement)
+            .iter()
+            .enumerate()
+            .map(|(k, (_, c))| (w + k as f64, *c))
+            .collect()
+    }
+}
+
+impl From<&FixedRateBond> for CallableBond {
+    /// The cash flows of the bond, without call or put schedules.
+    fn from(bond: &FixedRateBond) -> Self {
+        Self {
+            cashflows: bond.cashflows_after(bond.dates[0]).into_iter().collect(),
+            call_schedule: std::collections::BTreeMap::new(),
+            put_schedule: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_fixed_rate_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::days;
+    use std::collections::BTreeMap;
+    use time::Duration;
+
+    // 5y 6% semi-annual bond, Actual/360 on a schedule of 180 day periods.
+    fn bond() -> FixedRateBond {
+        let mut schedule = Schedule::new_from_start(days(0), Duration::days(180), 10);
+        schedule.day_count_convention = DayCountConvention::Actual360;
+
+        FixedRateBond::new(100.0, 0.06, &schedule, PaymentFrequency::SemiAnnually)
+    }
+
+    #[test]
+    fn test_yield_and_prices() {
+        let bond = bond();
+
+        // On a coupon date, a bond priced at par yields its coupon.
+        assert_approx_equal!(bond.dirty_price_from_yield(0.06, days(360)), 100.0, 1e-10);
+        assert_approx_equal!(
+            bond.yield_to_maturity(100.0, days(360)).unwrap(),
+            0.06,
+            1e-10
+        );
+        assert_approx_equal!(bond.accrued_interest(days(360)), 0.0, 1e-15);
+
+        // Between coupon dates: 45 of 180 days accrued, a quarter coupon.
+        let settlement = days(405);
+        assert_approx_equal!(bond.accrued_interest(settlement), 0.75, 1e-12);
+        assert_approx_equal!(
+            bond.dirty_price(bond.clean_price(101.0, settlement), settlement),
+            101.0,
+            1e-12
+        );
+
+        // The dirty price grows at the yield between coupon dates.
+        let y = 0.07;
+        let coupon_date = bond.dirty_price_from_yield(y, days(360));
+        assert_approx_equal!(
+            bond.dirty_price_from_yield(y, settlement),
+            coupon_date * (1.0 + y / 2.0).powf(0.25),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_duration_and_convexity() {
+        let bond = bond();
+        let (y, settlement, h) = (0.055, days(405), 1e-4);
+
+        let price = |y| bond.dirty_price_from_yield(y, settlement);
+        let (down, mid, up) = (price(y - h), price(y), price(y + h));
+
+        assert_approx_equal!(
+            bond.modified_duration(y, settlement),
+            -(up - down) / (2.0 * h) / mid,
+            1e-6
+        );
+        assert_approx_equal!(
+            bond.convexity(y, settlement),
+            (up - 2.0 * mid + down) / (h * h) / mid,
+            1e-3
+        );
+
+        // A zero-coupon bond has a Macaulay duration of its maturity.
+        let zero = FixedRateBond {
+            coupon_rate: 0.0,
+            ..bond.clone()
+        };
+        assert_approx_equal!(zero.macaulay_duration(y, days(0)), 5.0, 1e-12);
+        assert!(bond.macaulay_duration(y, days(0)) < 5.0);
+    }
+
+    #[test]
+    fn test_z_spread() {
+        let curve = YieldCurve::new(BTreeMap::from([(days(0), 0.03), (days(3650), 0.045)]));
+        let bond = bond();
+
+        let on_curve = bond.dirty_price_from_curve(&curve, 0.0);
+        assert_approx_equal!(bond.z_spread(&curve, on_curve).unwrap(), 0.0, 1e-10);
+
+        let cheap = bond.dirty_price_from_curve(&curve, 0.0125);
+        assert_approx_equal!(bond.z_spread(&curve, cheap).unwrap(), 0.0125, 1e-10);
+
+        // On a flat curve, the Z-spread is the continuously compounded yield
+        // less the curve rate.
+        let flat = YieldCurve::new(BTreeMap::from([(days(0), 0.04), (days(3650), 0.04)]));
+        let y: f64 = 0.052;
+        let continuous = 2.0 * (1.0 + y / 2.0).ln();
+        let price = bond.dirty_price_from_yield(y, days(0));
+        assert_approx_equal!(
+            bond.z_spread(&flat, price).unwrap(),
+            continuous - 0.04,
+            2e-3
+        );
+    }
+}
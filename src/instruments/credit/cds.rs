@@ -0,0 +1,689 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Credit default swaps, priced as in the ISDA standard model.
+//!
+//! The protection buyer pays a running spread $s$ on the premium leg until
+//! maturity or default, and receives $1 - R$ (per unit of notional) on
+//! default. Between the dates of the discount and hazard rate curves and
+//! the coupon dates, the forward rate $f$ and the hazard rate $\lambda$
+//! are constant, so that the legs are sums of closed-form integrals:
+//!
+//! $$
+//! \text{Protection} = (1 - R) \sum_j \frac{\lambda_j}{\lambda_j + f_j}
+//!     \left( P_{j} Q_{j} - P_{j+1} Q_{j+1} \right)
+//! $$
+//!
+//! and the premium leg is $s$ times the risky annuity (RPV01): the accrual
+//! periods discounted with the survival probabilities, plus the premium
+//! accrued up to default.
+//!
+//! Quotes follow the standard (post-2009) conventions: the par spread and
+//! the upfront are clean (they exclude the premium accrued since the start
+//! of the current period, which the buyer pays back with the first coupon),
+//! and a quoted spread is converted to an upfront on a flat hazard rate
+//! curve.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, CurveError, HazardRateCurve, YieldCurve};
+use crate::math::solve_decreasing;
+use crate::math::RootFindingError;
+use crate::time::{DayCountConvention, DayCounter, Schedule};
+use std::collections::{BTreeMap, BTreeSet};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Credit default swap errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CreditDefaultSwapError {
+    /// The upfront is not below the loss given default `1 - R`, the upfront
+    /// of an immediate default.
+    #[error("Upfront must be below the loss given default")]
+    UpfrontAboveLossGivenDefault,
+
+    /// The quoted spread or upfront implies a negative hazard rate.
+    #[error("Quoted spread or upfront implies a negative hazard rate")]
+    NegativeHazardRate,
+
+    /// The flat hazard rate was not found.
+    #[error(transparent)]
+    RootFinding(#[from] RootFindingError),
+}
+
+/// Side of a credit default swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectionSide {
+    /// Pays the premium and receives the protection.
+    Buyer,
+    /// Receives the premium and pays the protection.
+    Seller,
+}
+
+/// Credit default swap.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::{DayCountConvention, Schedule};
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let discount = YieldCurve::new(BTreeMap::from([
+///     (today, 0.03),
+///     (today + Duration::days(3650), 0.04),
+/// ]));
+///
+/// // 5y CDS with quarterly coupons, Actual/360.
+/// let mut schedule = Schedule::new_from_start(today, Duration::days(91), 20);
+/// schedule.day_count_convention = DayCountConvention::Actual360;
+/// let cds = CreditDefaultSwap::new(1e7, 0.01, 0.4, ProtectionSide::Buyer, &schedule);
+///
+/// // Quoted at 250bp with a 100bp coupon: the buyer pays an upfront.
+/// let upfront = cds.upfront_from_quoted_spread(&discount, 0.025).unwrap();
+/// assert!(upfront > 0.0);
+/// assert!((cds.quoted_spread_from_upfront(&discount, upfront).unwrap() - 0.025).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditDefaultSwap {
+    /// Notional.
+    pub notional: f64,
+    /// Running spread (coupon) of the premium leg.
+    pub spread: f64,
+    /// Recovery rate on default.
+    pub recovery_rate: f64,
+    /// Protection buyer or seller.
+    pub side: ProtectionSide,
+    /// Accrual dates of the premium leg: the protection start, then the
+    /// coupon dates, the last being the maturity.
+    pub dates: Vec<OffsetDateTime>,
+    /// Day count convention of the premium leg (Actual/360 for standard
+    /// contracts).
+    pub day_count_convention: DayCountConvention,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CreditDefaultSwap {
+    /// New credit default swap on the dates and day count convention of a
+    /// schedule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule has less than two dates.
+    #[must_use]
+    pub fn new(
+        notional: f64,
+        spread: f64,
+        recovery_rate: f64,
+        side: ProtectionSide,
+        schedule: &Schedule,
+    ) -> Self {
+        assert!(
+            schedule.dates.len() > 1,
+            "The schedule must have at least two dates."
+        );
+
+        Self {
+            notional,
+            spread,
+            recovery_rate,
+            side,
+            dates: schedule.dates.clone(),
+            day_count_convention: schedule.day_count_convention,
+        }
+    }
+
+    /// Maturity date: the end of the protection.
+    #[must_use]
+    pub fn maturity(&self) -> OffsetDateTime {
+        self.dates[self.dates.len() - 1]
+    }
+
+    /// Premium accrued from the start of the current period to `date`.
+    #[must_use]
+    pub fn accrued_premium(&self, date: OffsetDateTime) -> f64 {
+        self.dates
+            .windows(2)
+            .find(|w| w[0] <= date && date < w[1])
+            .map_or(0.0, |w| {
+                self.notional * self.spread * self.accrual(w[0], date)
+            })
+    }
+
+    /// Clean risky annuity (RPV01) per unit of notional, on the initial
+    /// date of the discount curve: the value of the premium leg for a unit
+    /// spread, including the premium accrued up to default and excluding
+    /// the premium accrued since the start of the current period.
+    #[must_use]
+    pub fn risky_annuity(
+        &self,
+        discount_curve: &YieldCurve,
+        hazard_curve: &HazardRateCurve,
+    ) -> f64 {
+        self.dirty_risky_annuity(discount_curve, hazard_curve)
+            - self.accrual_to(discount_curve.initial_date())
+    }
+
+    /// Value of the premium leg (paid by the protection buyer), including
+    /// the premium accrued since the start of the current period.
+    #[must_use]
+    pub fn premium_leg_npv(
+        &self,
+        discount_curve: &YieldCurve,
+        hazard_curve: &HazardRateCurve,
+    ) -> f64 {
+        self.notional * self.spread * self.dirty_risky_annuity(discount_curve, hazard_curve)
+    }
+
+    /// Value of the protection leg (paid by the protection seller).
+    #[must_use]
+    pub fn protection_leg_npv(
+        &self,
+        discount_curve: &YieldCurve,
+        hazard_curve: &HazardRateCurve,
+    ) -> f64 {
+        let valuation_date = discount_curve.initial_date();
+        let start = self.dates[0].max(valuation_date);
+        let maturity = self.maturity();
+
+        if maturity <= valuation_date {
+            return 0.0;
+        }
+
+        let protection: f64 = grid(start, maturity, discount_curve, hazard_curve)
+            .windows(2)
+            .map(|w| Interval::new(w[0], w[1], discount_curve, hazard_curve).protection())
+            .sum();
+
+        self.notional * (1.0 - self.recovery_rate) * protection
+    }
+
+    /// Net present value to the holder: the protection leg less the premium
+    /// leg for the buyer, the opposite for the seller.
+    #[must_use]
+    pub fn npv(&self, discount_curve: &YieldCurve, hazard_curve: &HazardRateCurve) -> f64 {
+        let npv = self.protection_leg_npv(discount_curve, hazard_curve)
+            - self.premium_leg_npv(discount_curve, hazard_curve);
+
+        match self.side {
+            ProtectionSide::Buyer => npv,
+            ProtectionSide::Seller => -npv,
+        }
+    }
+
+    /// Par spread: the running spread at which the clean value of the swap
+    /// is zero.
+    #[must_use]
+    pub fn par_spread(&self, discount_curve: &YieldCurve, hazard_curve: &HazardRateCurve) -> f64 {
+        self.protection_leg_npv(discount_curve, hazard_curve)
+            / (self.notional * self.risky_annuity(discount_curve, hazard_curve))
+    }
+
+    /// Clean upfront paid by the protection buyer, per unit of notional:
+    /// the value of the protection less the running spread on the clean
+    /// risky annuity. The cash paid on the valuation date is the upfront
+    /// less the accrued premium.
+    #[must_use]
+    pub fn upfront(&self, discount_curve: &YieldCurve, hazard_curve: &HazardRateCurve) -> f64 {
+        self.protection_leg_npv(discount_curve, hazard_curve) / self.notional
+            - self.spread * self.risky_annuity(discount_curve, hazard_curve)
+    }
+
+    /// Constant hazard rate of the flat curve on which the par spread is
+    /// the quoted spread.
+    ///
+    /// # Errors
+    ///
+    /// - `CreditDefaultSwapError::NegativeHazardRate` if the quoted spread
+    ///   is negative.
+    /// - `CreditDefaultSwapError::RootFinding` if no hazard rate reprices
+    ///   the spread.
+    pub fn flat_hazard_rate(
+        &self,
+        discount_curve: &YieldCurve,
+        quoted_spread: f64,
+    ) -> Result<f64, CreditDefaultSwapError> {
+        // The par spread is zero without default risk.
+        if quoted_spread < 0.0 || quoted_spread.is_nan() {
+            return Err(CreditDefaultSwapError::NegativeHazardRate);
+        }
+
+        let valuation_date = discount_curve.initial_date();
+        let hazard_rate = solve_decreasing(
+            |hazard_rate| {
+                let curve = HazardRateCurve::flat(valuation_date, hazard_rate);
+                quoted_spread - self.par_spread(discount_curve, &curve)
+            },
+            quoted_spread / (1.0 - self.recovery_rate),
+        )?;
+
+        Ok(hazard_rate.max(0.0))
+    }
+
+    /// Clean upfront (per unit of notional) from a quoted spread, on the
+    /// flat hazard rate curve of the quoted spread.
+    ///
+    /// # Errors
+    ///
+    /// See [`CreditDefaultSwap::flat_hazard_rate`].
+    pub fn upfront_from_quoted_spread(
+        &self,
+        discount_curve: &YieldCurve,
+        quoted_spread: f64,
+    ) -> Result<f64, CreditDefaultSwapError> {
+        let hazard_rate = self.flat_hazard_rate(discount_curve, quoted_spread)?;
+        let curve = HazardRateCurve::flat(discount_curve.initial_date(), hazard_rate);
+
+        Ok(self.upfront(discount_curve, &curve))
+    }
+
+    /// Quoted spread from a clean upfront (per unit of notional): the par
+    /// spread on the flat hazard rate curve that reprices the upfront.
+    ///
+    /// The upfront increases with the hazard rate, from minus the running
+    /// spread on the risk-free annuity, towards the loss given default.
+    ///
+    /// # Errors
+    ///
+    /// - `CreditDefaultSwapError::UpfrontAboveLossGivenDefault` if the
+    ///   upfront is not below `1 - R`.
+    /// - `CreditDefaultSwapError::NegativeHazardRate` if the upfront is
+    ///   below the upfront without default risk.
+    /// - `CreditDefaultSwapError::RootFinding` if no hazard rate reprices
+    ///   the upfront.
+    pub fn quoted_spread_from_upfront(
+        &self,
+        discount_curve: &YieldCurve,
+        upfront: f64,
+    ) -> Result<f64, CreditDefaultSwapError> {
+        let valuation_date = discount_curve.initial_date();
+
+        if upfront >= 1.0 - self.recovery_rate || upfront.is_nan() {
+            return Err(CreditDefaultSwapError::UpfrontAboveLossGivenDefault);
+        }
+        if upfront < self.upfront(discount_curve, &HazardRateCurve::flat(valuation_date, 0.0)) {
+            return Err(CreditDefaultSwapError::NegativeHazardRate);
+        }
+
+        let hazard_rate = solve_decreasing(
+            |hazard_rate| {
+                let curve = HazardRateCurve::flat(valuation_date, hazard_rate);
+                upfront - self.upfront(discount_curve, &curve)
+            },
+            self.spread / (1.0 - self.recovery_rate),
+        )?;
+
+        Ok(self.par_spread(
+            discount_curve,
+            &HazardRateCurve::flat(valuation_date, hazard_rate.max(0.0)),
+        ))
+    }
+
+    // Risky annuity per unit of notional, including the premium accrued
+    // since the start of the current period.
+    fn dirty_risky_annuity(
+        &self,
+        discount_curve: &YieldCurve,
+        hazard_curve: &HazardRateCurve,
+    ) -> f64 {
+        let valuation_date = discount_curve.initial_date();
+
+        self.dates
+            .windows(2)
+            .filter(|w| w[1] > valuation_date)
+            .map(|w| {
+                let (start, end) = (w[0], w[1]);
+                let tau = self.accrual(start, end);
+
+                // Coupon paid on survival to the end of the period.
+                let coupon = tau
+                    * discount_curve.discount_factor(end)
+                    * hazard_curve.survival_probability(end);
+
+                // Premium accrued up to default within the period.
+                let scale =
+                    tau / (hazard_curve.year_fraction(end) - hazard_curve.year_fraction(start));
+                let on_default: f64 =
+                    grid(start.max(valuation_date), end, discount_curve, hazard_curve)
+                        .windows(2)
+                        .map(|v| {
+                            let interval = Interval::new(v[0], v[1], discount_curve, hazard_curve);
+                            let accrued = hazard_curve.year_fraction(v[0])
+                                - hazard_curve.year_fraction(start);
+                            scale * interval.accrual_on_default(accrued)
+                        })
+                        .sum();
+
+                coupon + on_default
+            })
+            .sum()
+    }
+
+    // Accrual fraction of the current period up to `date`.
+    fn accrual_to(&self, date: OffsetDateTime) -> f64 {
+        self.dates
+            .windows(2)
+            .find(|w| w[0] <= date && date < w[1])
+            .map_or(0.0, |w| self.accrual(w[0], date))
+    }
+
+    fn accrual(&self, start: OffsetDateTime, end: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(start, end, &self.day_count_convention)
+    }
+}
+
+impl HazardRateCurve {
+    /// Bootstraps a hazard rate curve from par credit default swaps (with
+    /// their spreads as par spreads), adding a date at the maturity of each.
+    ///
+    /// # Errors
+    ///
+    /// - `CurveError::NoPoints` if there are no swaps.
+    /// - `CurveError::DateOutsideRange` if a swap matures on or before the
+    ///   initial date of the discount curve or the previous swap.
+    /// - `CurveError::NoConvergence` if a spread cannot be repriced.
+    pub fn bootstrap(
+        discount_curve: &YieldCurve,
+        quotes: &[CreditDefaultSwap],
+    ) -> Result<Self, CurveError> {
+        if quotes.is_empty() {
+            return Err(CurveError::NoPoints);
+        }
+
+        let mut quotes: Vec<&CreditDefaultSwap> = quotes.iter().collect();
+        quotes.sort_by_key(|cds| cds.maturity());
+
+        let valuation_date = discount_curve.initial_date();
+        let mut curve = Self::new(valuation_date, BTreeMap::new());
+        let mut previous = valuation_date;
+
+        for cds in quotes {
+            let maturity = cds.maturity();
+            if maturity <= previous {
+                return Err(CurveError::DateOutsideRange);
+            }
+            previous = maturity;
+
+            // Hazard rate of the last segment, from the credit triangle.
+            let mut residual = |hazard_rate: f64| {
+                curve.hazard_rates.insert(maturity, hazard_rate);
+                cds.spread - cds.par_spread(discount_curve, &curve)
+            };
+
+            let hazard_rate =
+                solve_decreasing(&mut residual, cds.spread / (1.0 - cds.recovery_rate))
+                    .map_err(|_| CurveError::NoConvergence)?;
+            residual(hazard_rate);
+        }
+
+        Ok(curve)
+    }
+}
+
+// Sub-interval on which the forward rate and the hazard rate are constant.
+struct Interval {
+    // Risky discount factor P(t) Q(t) at the start.
+    start: f64,
+    // Risky discount factor at the end.
+    end: f64,
+    // Length in years.
+    length: f64,
+    // Hazard rate.
+    hazard_rate: f64,
+}
+
+impl Interval {
+    fn new(
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        discount_curve: &YieldCurve,
+        hazard_curve: &HazardRateCurve,
+    ) -> Self {
+        let (q_start, q_end) = (
+            hazard_curve.survival_probability(start),
+            hazard_curve.survival_probability(end),
+        );
+        let length = hazard_curve.year_fraction(end) - hazard_curve.year_fraction(start);
+
+        Self {
+            start: discount_curve.discount_factor(start) * q_start,
+            end: discount_curve.discount_factor(end) * q_end,
+            length,
+            hazard_rate: (q_start / q_end).ln() / length,
+        }
+    }
+
+    // Decay rate of the risky discount factor: hazard rate plus forward rate.
+    fn decay(&self) -> f64 {
+        (self.start / self.end).ln() / self.length
+    }
+
+    // Value of a unit paid on default within the interval.
+    fn protection(&self) -> f64 {
+        let k = self.decay();
+
+        if (k * self.length).abs() < 1e-10 {
+            self.hazard_rate * self.length * self.start
+        } else {
+            self.hazard_rate / k * (self.start - self.end)
+        }
+    }
+
+    // Value of the time accrued on default within the interval, with
+    // `accrued` years already accrued at its start.
+    fn accrual_on_default(&self, accrued: f64) -> f64 {
+        let (k, dt) = (self.decay(), self.length);
+
+        let (first, second) = if (k * dt).abs() < 1e-10 {
+            (dt, 0.5 * dt * dt)
+        } else {
+            let decay = (-k * dt).exp();
+            ((1.0 - decay) / k, (1.0 - decay * (1.0 + k * dt)) / (k * k))
+        };
+
+        self.hazard_rate * self.start * (accrued * first + second)
+    }
+}
+
+// Dates from `start` to `end`, with the dates of the curves in between.
+fn grid(
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+    discount_curve: &YieldCurve,
+    hazard_curve: &HazardRateCurve,
+) -> Vec<OffsetDateTime> {
+    let mut dates = BTreeSet::from([start, end]);
+    dates.extend(
+        discount_curve
+            .rates
+            .keys()
+            .chain(hazard_curve.hazard_rates.keys())
+            .filter(|date| start < **date && **date < end),
+    );
+
+    dates.into_iter().collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cds {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{days, yield_curve};
+    use time::Duration;
+
+    fn discount_curve() -> YieldCurve {
+        yield_curve(&[(0, 0.02), (1000, 0.03), (4000, 0.035)])
+    }
+
+    // Quarterly CDS, Actual/360, starting `start` days from today.
+    fn quarterly(start: i64, quarters: i64, spread: f64) -> CreditDefaultSwap {
+        let mut schedule = Schedule::new_from_start(days(start), Duration::days(91), quarters);
+        schedule.day_count_convention = DayCountConvention::Actual360;
+
+        CreditDefaultSwap::new(1e6, spread, 0.4, ProtectionSide::Buyer, &schedule)
+    }
+
+    #[test]
+    fn test_legs() {
+        // Zero rates and recovery: the protection leg is the probability of
+        // default before maturity.
+        let zero = YieldCurve::new(BTreeMap::from([(days(0), 0.0), (days(4000), 0.0)]));
+        let hazard = HazardRateCurve::new(
+            days(0),
+            BTreeMap::from([(days(500), 0.01), (days(1500), 0.03)]),
+        );
+        let cds = CreditDefaultSwap {
+            recovery_rate: 0.0,
+            ..quarterly(0, 20, 0.01)
+        };
+        assert_approx_equal!(
+            cds.protection_leg_npv(&zero, &hazard),
+            1e6 * hazard.default_probability(days(1820)),
+            1e-6
+        );
+
+        // Credit triangle: on flat curves, the par spread is close to the
+        // hazard rate times the loss given default (Actual/360 premium).
+        let flat = HazardRateCurve::flat(days(0), 0.02);
+        let cds = quarterly(0, 20, 0.01);
+        assert_approx_equal!(
+            cds.par_spread(&discount_curve(), &flat),
+            0.012 * 360.0 / 365.0,
+            2e-5
+        );
+
+        // Buyer and seller.
+        let seller = CreditDefaultSwap {
+            side: ProtectionSide::Seller,
+            ..cds.clone()
+        };
+        assert_approx_equal!(
+            cds.npv(&discount_curve(), &flat),
+            -seller.npv(&discount_curve(), &flat),
+            1e-9
+        );
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let discount = discount_curve();
+        let quotes = [
+            quarterly(0, 4, 0.0060),
+            quarterly(0, 12, 0.0085),
+            quarterly(0, 20, 0.0110),
+            quarterly(0, 28, 0.0125),
+            quarterly(0, 40, 0.0140),
+        ];
+        let curve = HazardRateCurve::bootstrap(&discount, &quotes).unwrap();
+
+        assert_eq!(curve.hazard_rates.len(), 5);
+        for cds in &quotes {
+            assert_approx_equal!(cds.par_spread(&discount, &curve), cds.spread, 1e-12);
+            assert_approx_equal!(cds.npv(&discount, &curve), 0.0, 1e-6);
+        }
+        assert!(curve.hazard_rates.values().all(|rate| *rate > 0.0));
+
+        assert!(matches!(
+            HazardRateCurve::bootstrap(&discount, &[]),
+            Err(CurveError::NoPoints)
+        ));
+    }
+
+    #[test]
+    fn test_upfront() {
+        let discount = discount_curve();
+
+        // Seasoned swap, 40 days into its current period.
+        let cds = quarterly(-131, 21, 0.01);
+        let hazard = HazardRateCurve::flat(days(0), 0.03);
+
+        // The cash paid is the clean upfront less the accrued premium.
+        let accrued = cds.accrued_premium(days(0));
+        assert_approx_equal!(accrued, 1e6 * 0.01 * 40.0 / 360.0, 1e-9);
+        assert_approx_equal!(
+            cds.npv(&discount, &hazard),
+            1e6 * cds.upfront(&discount, &hazard) - accrued,
+            1e-6
+        );
+
+        // At the par spread, the upfront is zero.
+        let par = CreditDefaultSwap {
+            spread: cds.par_spread(&discount, &hazard),
+            ..cds.clone()
+        };
+        assert_approx_equal!(par.upfront(&discount, &hazard), 0.0, 1e-12);
+
+        // Quoted spread and upfront conversions.
+        let quoted = 0.025;
+        let upfront = cds.upfront_from_quoted_spread(&discount, quoted).unwrap();
+        let hazard_rate = cds.flat_hazard_rate(&discount, quoted).unwrap();
+        let flat = HazardRateCurve::flat(days(0), hazard_rate);
+        assert_approx_equal!(cds.par_spread(&discount, &flat), quoted, 1e-12);
+        assert_approx_equal!(
+            upfront,
+            (quoted - 0.01) * cds.risky_annuity(&discount, &flat),
+            1e-12
+        );
+        assert_approx_equal!(
+            cds.quoted_spread_from_upfront(&discount, upfront).unwrap(),
+            quoted,
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_quote_bounds() {
+        let discount = discount_curve();
+        let cds = quarterly(0, 20, 0.01);
+
+        // The upfront of an immediate default is the loss given default.
+        for upfront in [0.6, 0.7, 1.5, f64::NAN] {
+            assert_eq!(
+                cds.quoted_spread_from_upfront(&discount, upfront),
+                Err(CreditDefaultSwapError::UpfrontAboveLossGivenDefault)
+            );
+        }
+        let near_default = cds.quoted_spread_from_upfront(&discount, 0.55).unwrap();
+        assert!(near_default.is_finite() && near_default > 0.1);
+
+        // Without default risk, the buyer receives the spread on the
+        // risk-free annuity.
+        let risk_free = cds.upfront(&discount, &HazardRateCurve::flat(days(0), 0.0));
+        assert!(risk_free < 0.0);
+        assert_eq!(
+            cds.quoted_spread_from_upfront(&discount, risk_free - 0.01),
+            Err(CreditDefaultSwapError::NegativeHazardRate)
+        );
+        assert_approx_equal!(
+            cds.quoted_spread_from_upfront(&discount, risk_free)
+                .unwrap(),
+            0.0,
+            1e-12
+        );
+
+        assert_eq!(
+            cds.flat_hazard_rate(&discount, -0.01),
+            Err(CreditDefaultSwapError::NegativeHazardRate)
+        );
+        assert_approx_equal!(cds.flat_hazard_rate(&discount, 0.0).unwrap(), 0.0, 1e-15);
+    }
+}
@@ -0,0 +1,245 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Inflation-linked (index-linked) bonds.
+//!
+//! The coupons and the principal are scaled by the index ratio: the
+//! reference index on the payment date (with the indexation lag of the
+//! bond) over the base index of the bond, its reference index on the dated
+//! date. Some bonds (e.g. TIPS) floor the redemption at par, protecting the
+//! principal against cumulative deflation; the floor is applied to the
+//! projected index ratio.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, Indexation, InflationCurve, YieldCurve};
+use crate::time::{DayCountConvention, DayCounter, Schedule};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Inflation-linked bond paying a real coupon on an indexed principal.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::Schedule;
+/// use std::collections::BTreeMap;
+/// use time::{macros::datetime, Duration};
+///
+/// let dated = datetime!(2024-01-15 0:00 UTC);
+/// let schedule = Schedule::new_from_start(dated, Duration::days(182), 10);
+/// let indexation = Indexation { lag_months: 3, interpolated: true };
+///
+/// let inflation = InflationCurve::new(
+///     datetime!(2023-10-01 0:00 UTC),
+///     300.0,
+///     BTreeMap::from([(datetime!(2034-01-01 0:00 UTC), 0.025)]),
+/// );
+/// let bond = InflationLinkedBond::new(100.0, 0.01, &schedule, indexation, 300.0);
+///
+/// // The redemption grows with the projected index.
+/// let (date, amount) = *bond.cashflows(&inflation).last().unwrap();
+/// assert_eq!(date, schedule.dates[10]);
+/// assert!(amount > 100.0 * 1.025_f64.powf(4.9));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct InflationLinkedBond {
+    /// Face value (real principal).
+    pub face_value: f64,
+    /// Annual real coupon rate.
+    pub real_coupon_rate: f64,
+    /// Accrual dates: the dated date, then the coupon dates, the last being
+    /// the maturity.
+    pub dates: Vec<OffsetDateTime>,
+    /// Day count convention of the coupons.
+    pub day_count_convention: DayCountConvention,
+    /// Indexation of the payments.
+    pub indexation: Indexation,
+    /// Base index: the reference index on the dated date.
+    pub base_index: f64,
+    /// Floors the redemption at the face value.
+    pub deflation_floor: bool,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl InflationLinkedBond {
+    /// New bond without deflation floor, on the dates and day count
+    /// convention of a schedule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule has less than two dates.
+    #[must_use]
+    pub fn new(
+        face_value: f64,
+        real_coupon_rate: f64,
+        schedule: &Schedule,
+        indexation: Indexation,
+        base_index: f64,
+    ) -> Self {
+        assert!(
+            schedule.dates.len() > 1,
+            "The schedule must have at least two dates."
+        );
+
+        Self {
+            face_value,
+            real_coupon_rate,
+            dates: schedule.dates.clone(),
+            day_count_convention: schedule.day_count_convention,
+            indexation,
+            base_index,
+            deflation_floor: false,
+        }
+    }
+
+    /// Floors the redemption at the face value.
+    #[must_use]
+    pub const fn with_deflation_floor(mut self) -> Self {
+        self.deflation_floor = true;
+        self
+    }
+
+    /// Index ratio on `date`: its reference index over the base index.
+    #[must_use]
+    pub fn index_ratio(&self, date: OffsetDateTime, inflation_curve: &InflationCurve) -> f64 {
+        inflation_curve.reference_index(date, &self.indexation) / self.base_index
+    }
+
+    /// Projected nominal cash flows: the indexed coupons, and the indexed
+    /// redemption (floored at par with a deflation floor) at maturity.
+    #[must_use]
+    pub fn cashflows(&self, inflation_curve: &InflationCurve) -> Vec<(OffsetDateTime, f64)> {
+        let maturity = self.dates[self.dates.len() - 1];
+
+        self.dates
+            .windows(2)
+            .map(|w| {
+                let ratio = self.index_ratio(w[1], inflation_curve);
+                let tau = DayCounter::day_count_factor(w[0], w[1], &self.day_count_convention);
+                let mut amount = self.face_value * self.real_coupon_rate * tau * ratio;
+
+                if w[1] == maturity {
+                    let redemption = if self.deflation_floor {
+                        ratio.max(1.0)
+                    } else {
+                        ratio
+                    };
+                    amount += self.face_value * redemption;
+                }
+
+                (w[1], amount)
+            })
+            .collect()
+    }
+
+    /// Dirty price on the initial date of the discount curve: the projected
+    /// cash flows paid after that date, discounted.
+    #[must_use]
+    pub fn npv(&self, inflation_curve: &InflationCurve, discount_curve: &YieldCurve) -> f64 {
+        let valuation_date = discount_curve.initial_date();
+
+        self.cashflows(inflation_curve)
+            .iter()
+            .filter(|(date, _)| *date > valuation_date)
+            .map(|(date, amount)| amount * discount_curve.discount_factor(*date))
+            .sum()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_inflation_linked_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::bonds::FixedRateBond;
+    use crate::time::PaymentFrequency;
+    use std::collections::BTreeMap;
+    use time::{macros::datetime, Duration};
+
+    const INDEXATION: Indexation = Indexation {
+        lag_months: 3,
+        interpolated: true,
+    };
+
+    fn schedule() -> Schedule {
+        Schedule::new_from_start(datetime!(2024-01-01 0:00 UTC), Duration::days(365), 5)
+    }
+
+    fn discount() -> YieldCurve {
+        YieldCurve::new(BTreeMap::from([
+            (datetime!(2024-01-01 0:00 UTC), 0.035),
+            (datetime!(2030-01-01 0:00 UTC), 0.04),
+        ]))
+    }
+
+    fn inflation(rate: f64) -> InflationCurve {
+        InflationCurve::new(
+            datetime!(2023-10-01 0:00 UTC),
+            250.0,
+            BTreeMap::from([(datetime!(2025-01-01 0:00 UTC), rate)]),
+        )
+    }
+
+    #[test]
+    fn test_zero_inflation() {
+        // Without inflation, the linker is a nominal bond.
+        let bond = InflationLinkedBond::new(100.0, 0.02, &schedule(), INDEXATION, 250.0);
+        let nominal = FixedRateBond::new(100.0, 0.02, &schedule(), PaymentFrequency::Annually);
+
+        assert_approx_equal!(
+            bond.npv(&inflation(0.0), &discount()),
+            nominal.dirty_price_from_curve(&discount(), 0.0),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_indexation() {
+        let bond = InflationLinkedBond::new(100.0, 0.02, &schedule(), INDEXATION, 250.0);
+        let curve = inflation(0.03).with_fixing(datetime!(2024-10-01 0:00 UTC), 260.0);
+
+        // The first coupon (31 Dec 2024) interpolates between the September and
+        // October 2024 fixings; on 1 Jan 2025, only October counts.
+        let (date, coupon) = bond.cashflows(&curve)[0];
+        assert_eq!(date, datetime!(2024-12-31 0:00 UTC));
+        assert_approx_equal!(
+            bond.index_ratio(date, &curve),
+            curve.reference_index(date, &INDEXATION) / 250.0,
+            1e-15
+        );
+        assert_approx_equal!(coupon, 2.0 * bond.index_ratio(date, &curve), 1e-12);
+        assert_approx_equal!(
+            bond.index_ratio(datetime!(2025-01-01 0:00 UTC), &curve),
+            260.0 / 250.0,
+            1e-12
+        );
+
+        // The deflation floor only matters with deflation.
+        let floored = bond.clone().with_deflation_floor();
+        assert_approx_equal!(
+            floored.npv(&curve, &discount()),
+            bond.npv(&curve, &discount()),
+            1e-12
+        );
+        let deflation = inflation(-0.02);
+        assert!(floored.npv(&deflation, &discount()) > bond.npv(&deflation, &discount()));
+    }
+}
@@ -0,0 +1,293 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Zero-coupon inflation swaps.
+//!
+//! At maturity $T$, the inflation leg pays the growth of the reference
+//! index since the start, against a fixed rate $K$ compounded over the
+//! $\tau$ years of the swap:
+//!
+//! $$
+//! N \left( \frac{I(T)}{I(T_0)} - 1 \right)
+//! \quad \text{against} \quad
+//! N \left( (1 + K)^\tau - 1 \right)
+//! $$
+//!
+//! Both legs pay on the same date, so the par rate only depends on the
+//! inflation curve, which is bootstrapped from the quotes of par swaps.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{add_months, Curve, CurveError, Indexation, InflationCurve, YieldCurve};
+use crate::instruments::rates::SwapDirection;
+use crate::math::solve_decreasing;
+use crate::time::{DayCountConvention, DayCounter};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Zero-coupon inflation swap. The payer pays the fixed leg and receives
+/// inflation.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use time::macros::datetime;
+///
+/// let indexation = Indexation { lag_months: 3, interpolated: false };
+/// let start = datetime!(2024-04-01 0:00 UTC);
+/// let swap = |years: i32, rate: f64| {
+///     let maturity = start.replace_year(2024 + years).unwrap();
+///     ZeroCouponInflationSwap::new(1e6, rate, SwapDirection::Payer, start, maturity, indexation)
+/// };
+///
+/// let quotes = [swap(1, 0.031), swap(2, 0.028), swap(5, 0.026), swap(10, 0.025)];
+/// let curve = InflationCurve::bootstrap(datetime!(2024-01-01 0:00 UTC), 310.0, &quotes).unwrap();
+///
+/// for quote in &quotes {
+///     assert!((quote.par_rate(&curve) - quote.fixed_rate).abs() < 1e-12);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeroCouponInflationSwap {
+    /// Notional.
+    pub notional: f64,
+    /// Fixed rate, compounded annually.
+    pub fixed_rate: f64,
+    /// Payer or receiver of the fixed leg.
+    pub direction: SwapDirection,
+    /// Start date.
+    pub start_date: OffsetDateTime,
+    /// Maturity date, on which both legs pay.
+    pub maturity_date: OffsetDateTime,
+    /// Indexation of the start and maturity dates.
+    pub indexation: Indexation,
+    /// Day count convention of the compounding period (Thirty/360 by
+    /// default, giving whole years between anniversaries).
+    pub day_count_convention: DayCountConvention,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ZeroCouponInflationSwap {
+    /// New swap, compounding the fixed rate with the Thirty/360 day count.
+    #[must_use]
+    pub const fn new(
+        notional: f64,
+        fixed_rate: f64,
+        direction: SwapDirection,
+        start_date: OffsetDateTime,
+        maturity_date: OffsetDateTime,
+        indexation: Indexation,
+    ) -> Self {
+        Self {
+            notional,
+            fixed_rate,
+            direction,
+            start_date,
+            maturity_date,
+            indexation,
+            day_count_convention: DayCountConvention::Thirty360,
+        }
+    }
+
+    /// Payment of the fixed leg at maturity.
+    #[must_use]
+    pub fn fixed_leg_payment(&self) -> f64 {
+        self.notional * ((1.0 + self.fixed_rate).powf(self.years()) - 1.0)
+    }
+
+    /// Projected payment of the inflation leg at maturity.
+    #[must_use]
+    pub fn inflation_leg_payment(&self, inflation_curve: &InflationCurve) -> f64 {
+        self.notional * (self.index_ratio(inflation_curve) - 1.0)
+    }
+
+    /// Net present value to the holder, on the initial date of the discount
+    /// curve.
+    #[must_use]
+    pub fn npv(&self, inflation_curve: &InflationCurve, discount_curve: &YieldCurve) -> f64 {
+        let net = self.inflation_leg_payment(inflation_curve) - self.fixed_leg_payment();
+        let sign = match self.direction {
+            SwapDirection::Payer => 1.0,
+            SwapDirection::Receiver => -1.0,
+        };
+
+        sign * net * discount_curve.discount_factor(self.maturity_date)
+    }
+
+    /// Par rate: the fixed rate for which the swap is worth zero.
+    #[must_use]
+    pub fn par_rate(&self, inflation_curve: &InflationCurve) -> f64 {
+        self.index_ratio(inflation_curve).powf(1.0 / self.years()) - 1.0
+    }
+
+    // Growth of the reference index from the start to the maturity.
+    fn index_ratio(&self, inflation_curve: &InflationCurve) -> f64 {
+        inflation_curve.reference_index(self.maturity_date, &self.indexation)
+            / inflation_curve.reference_index(self.start_date, &self.indexation)
+    }
+
+    fn years(&self) -> f64 {
+        DayCounter::day_count_factor(
+            self.start_date,
+            self.maturity_date,
+            &self.day_count_convention,
+        )
+    }
+
+    // Last month whose fixing the swap references at maturity.
+    fn last_fixing_month(&self) -> OffsetDateTime {
+        let lag = self.indexation.lag_months - i32::from(self.indexation.interpolated);
+        add_months(self.maturity_date, -lag)
+    }
+}
+
+impl InflationCurve {
+    /// Bootstraps the zero-coupon inflation rates from par zero-coupon
+    /// inflation swaps (with their fixed rates as par rates), adding a date
+    /// at the last fixing month referenced by each.
+    ///
+    /// # Errors
+    ///
+    /// - `CurveError::NoPoints` if there are no swaps.
+    /// - `CurveError::DateOutsideRange` if a swap references a fixing on or
+    ///   before the base month or the fixings of the previous swap.
+    /// - `CurveError::NoConvergence` if a rate cannot be repriced.
+    pub fn bootstrap(
+        base_date: OffsetDateTime,
+        base_index: f64,
+        quotes: &[ZeroCouponInflationSwap],
+    ) -> Result<Self, CurveError> {
+        if quotes.is_empty() {
+            return Err(CurveError::NoPoints);
+        }
+
+        let mut quotes: Vec<&ZeroCouponInflationSwap> = quotes.iter().collect();
+        quotes.sort_by_key(|swap| swap.maturity_date);
+
+        let mut curve = Self::new(base_date, base_index, BTreeMap::new());
+        let mut previous = curve.base_date;
+
+        for swap in quotes {
+            let pillar = swap.last_fixing_month();
+            if pillar <= previous {
+                return Err(CurveError::DateOutsideRange);
+            }
+            previous = pillar;
+
+            // Rate of the new date.
+            let mut residual = |rate: f64| {
+                curve.rates.insert(pillar, rate);
+                swap.fixed_rate - swap.par_rate(&curve)
+            };
+
+            let rate = solve_decreasing(&mut residual, swap.fixed_rate)
+                .map_err(|_| CurveError::NoConvergence)?;
+            residual(rate);
+        }
+
+        Ok(curve)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_zero_coupon_inflation_swap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::datetime;
+
+    const INDEXATION: Indexation = Indexation {
+        lag_months: 3,
+        interpolated: true,
+    };
+
+    fn swap(years: i32, rate: f64) -> ZeroCouponInflationSwap {
+        let start = datetime!(2024-04-15 0:00 UTC);
+        let maturity = start.replace_year(2024 + years).unwrap();
+
+        ZeroCouponInflationSwap::new(1e6, rate, SwapDirection::Payer, start, maturity, INDEXATION)
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let base = datetime!(2024-01-01 0:00 UTC);
+        let quotes = [
+            swap(1, 0.030),
+            swap(3, 0.027),
+            swap(5, 0.026),
+            swap(10, 0.0255),
+            swap(30, 0.025),
+        ];
+        let curve = InflationCurve::bootstrap(base, 300.0, &quotes).unwrap();
+        let discount = YieldCurve::new(BTreeMap::from([
+            (datetime!(2024-04-15 0:00 UTC), 0.04),
+            (datetime!(2060-01-01 0:00 UTC), 0.04),
+        ]));
+
+        assert_eq!(curve.rates.len(), 5);
+        for quote in &quotes {
+            assert_approx_equal!(quote.par_rate(&curve), quote.fixed_rate, 1e-12);
+            assert_approx_equal!(quote.npv(&curve, &discount), 0.0, 1e-6);
+        }
+
+        assert!(matches!(
+            InflationCurve::bootstrap(base, 300.0, &[swap(2, 0.03), swap(2, 0.03)]),
+            Err(CurveError::DateOutsideRange)
+        ));
+    }
+
+    #[test]
+    fn test_legs() {
+        // Flat 2% inflation, without lag: the index grows by 2% a year.
+        let curve = InflationCurve::new(
+            datetime!(2024-01-01 0:00 UTC),
+            100.0,
+            BTreeMap::from([(datetime!(2025-01-01 0:00 UTC), 0.02)]),
+        );
+        let start = datetime!(2025-01-01 0:00 UTC);
+        let swap = ZeroCouponInflationSwap::new(
+            100.0,
+            0.03,
+            SwapDirection::Receiver,
+            start,
+            datetime!(2030-01-01 0:00 UTC),
+            Indexation {
+                lag_months: 0,
+                interpolated: false,
+            },
+        );
+        let discount = YieldCurve::new(BTreeMap::from([(start, 0.0)]));
+
+        // 1826 days from 2025 to 2030.
+        let ratio = 1.02_f64.powf(1826.0 / 365.0);
+        assert_approx_equal!(
+            swap.inflation_leg_payment(&curve),
+            100.0 * (ratio - 1.0),
+            1e-10
+        );
+        assert_approx_equal!(
+            swap.fixed_leg_payment(),
+            100.0 * (1.03_f64.powi(5) - 1.0),
+            1e-10
+        );
+        assert!(swap.npv(&curve, &discount) > 0.0);
+    }
+}
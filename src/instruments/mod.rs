@@ -22,35 +22,91 @@
 //!   - [ ] The Ho–Lee Model
 //!   - [ ] The Black–Derman–Toy Model
 //!   - [ ] The Black–Karasinski Model
-//! - [ ] Duration
-//! - [ ] Convexity
+//! - [x] Callable and puttable bonds (Hull-White trinomial tree)
+//! - [x] Yield to maturity, accrued interest, clean and dirty prices
+//! - [x] Duration
+//! - [x] Convexity
+//! - [x] Z-spread and option-adjusted spread
+//! - [x] Bond futures (conversion factors, cheapest-to-deliver, implied repo, net basis)
+//!
+//! ### :currency_exchange: Interest Rate Derivatives <a name="rates"></a>
+//!
+//! Priced against a [`CurveContext`](crate::curves::CurveContext)
+//! (separate discounting and projection curves):
+//!
+//! - [x] Interest rate swaps (NPV, par rate, DV01, cash flow table)
+//! - [x] Caps and floors (Black-76, shifted Black-76 and Bachelier)
+//! - [x] European swaptions (physical and cash settlement, implied volatility)
+//! - [x] Bermudan swaptions (Hull-White trinomial tree)
+//! - [x] Floating rate notes on compounded overnight rates (lookback, observation shift, lockout)
+//!
+//! ### :shield: Credit Derivatives <a name="credit"></a>
+//!
+//! - [x] Credit default swaps (ISDA standard model, hazard rate bootstrapping,
+//!   par spread and upfront conversion)
+//!
+//! ### :chart_with_upwards_trend: Inflation <a name="inflation"></a>
+//!
+//! - [x] Inflation-linked bonds (indexation lags, deflation floor)
+//! - [x] Zero-coupon inflation swaps (and inflation curve bootstrapping)
 //!
 //! ### :money_with_wings: Option Pricing <a name="options"></a>
 //!
 //! - Closed-form price solutions:
-//!   - [x] Heston Model
-//!   - [x] Barrier
+//!   - [x] Heston Model (numerical integration and Carr-Madan FFT)
+//!   - [x] Jump diffusion: Merton (series) and Kou (Carr-Madan FFT), with Greeks
+//!   - [x] Variance Gamma, NIG and CGMY (Fourier-cosine (COS) method)
+//!   - [x] Barrier (with rebates, and discrete monitoring correction)
 //!   - [x] European
-//!   - [x] Greeks/Sensitivities
-//!   - [x] Lookback
-//!   - [x] Asian: Continuous Geometric Average
+//!   - [x] American (Barone-Adesi-Whaley and Bjerksund-Stensland 2002 approximations)
+//!   - [x] Greeks/Sensitivities (Black-Scholes, Black-76, Garman-Kohlhagen, Merton jump diffusion)
+//!   - [x] Implied volatility (Jäckel's "Let's Be Rational", and Bachelier)
+//!   - [x] Lookback (with discrete monitoring correction)
+//!   - [x] Ladder
+//!   - [x] Binary: Cash-or-Nothing, Asset-or-Nothing and Gap (with Greeks and call-spread overhedge)
+//!   - [x] Asian: Geometric Average (continuous and discrete, fixed and floating strike)
+//!   - [x] Asian: Arithmetic Average (Turnbull-Wakeman and Curran approximations)
 //!   - [x] Forward Start
+//!   - [x] Compound (Geske) and Chooser (simple and complex)
+//!   - [x] Cliquet (local caps and floors)
+//!   - [x] FX: Garman-Kohlhagen with spot/forward and premium-adjusted deltas, ATM DNS, strike from delta
 //!   - [x] Bachelier and Modified Bachelier
 //!   - [x] Generalised Black-Scholes-Merton
-//!   - [ ] Basket
+//!   - [x] Basket (moment matching)
+//!   - [x] Spread (Kirk and Bjerksund-Stensland)
+//!   - [x] Quanto and Composite (cross-currency)
 //!   - [ ] Rainbow
-//!   - [ ] American
+//!   - [x] Variance swaps (log-contract replication) and volatility swaps (convexity adjustment)
+//!   - [x] Strategies (spreads, straddles, collars, ...) with payoff diagrams and break-even points
+//!   - [x] Warrants (with dilution)
 //!
-//! - Lattice models:
+//! - Lattice models (European, American and Bermudan exercise, discrete dividends,
+//!   early-exercise boundary):
 //!   - [x] Binomial Tree (Cox-Ross-Rubinstein)
+//!   - [x] Binomial Tree (Jarrow-Rudd)
+//!   - [x] Binomial Tree (Tian)
+//!   - [x] Trinomial Tree
+//!   - [x] Employee stock options (Hull-White: vesting, exit rate and exercise multiple)
+//!
+//! - Finite-difference (PDE) methods (implicit and Crank-Nicolson):
+//!   - [x] European
+//!   - [x] American (PSOR and penalty method, with the early-exercise boundary)
+//!   - [x] Knock-out barriers
+//!   - [x] European under Dupire local volatility
 //!
 //! The stochastic process generators can be used to price path-dependent options via Monte-Carlo.
 //!
 //! - Monte Carlo pricing:
 //!   - [x] Lookback
-//!   - [ ] Asian
-//!   - [ ] Chooser
-//!   - [ ] Barrier
+//!   - [x] Ladder
+//!   - [x] American and Bermudan (Longstaff-Schwartz least-squares Monte Carlo)
+//!   - [x] Asian (arithmetic average, geometric control variate)
+//!   - [x] Barrier (discrete monitoring)
+//!   - [x] Basket and Spread (correlated underlyings)
+//!   - [x] Scripted payoffs (max, min, average, barrier and basket combinators)
+//!   - [x] European under Dupire local volatility
+//!   - [x] Cliquet (local and global caps and floors)
+//!   - [x] Autocallable and Phoenix notes (Black-Scholes, local volatility or Heston)
 //!
 //! ```no_run
 //! use RustQuant::instruments::*;
@@ -71,6 +127,12 @@
 //! println!("Call price = {}", prices.0);
 //! println!("Put price = {}", prices.1);
 //! ```
+//!
+//! The closed-form pricers (European, generalised Black-Scholes-Merton,
+//! Bachelier, binary, power, forward start, and geometric Asian) are
+//! generic over [`Real`](crate::autodiff::Real): construct them with
+//! `Variable`s instead of `f64`s and accumulate the price to get
+//! the Greeks by automatic differentiation.
 
 /// Base trait for all instruments.
 pub mod instrument;
@@ -78,12 +140,20 @@ pub use instrument::*;
 
 /// Bond pricing models.
 pub mod bonds {
-    pub use crate::instruments::bonds::{bond::*, cox_ingersoll_ross::*, vasicek::*};
+    pub use crate::instruments::bonds::{
+        bond::*, bond_future::*, callable::*, cox_ingersoll_ross::*, fixed_rate::*, vasicek::*,
+    };
 
     /// Base bond traits.
     pub mod bond;
+    /// Bond futures and cheapest-to-deliver analytics.
+    pub mod bond_future;
+    /// Callable and puttable bonds.
+    pub mod callable;
     /// Cox-Ingersoll-Ross bond pricing model.
     pub mod cox_ingersoll_ross;
+    /// Fixed rate bonds and bond analytics.
+    pub mod fixed_rate;
     /// One-factor Hull-White bond pricing model.
     pub mod hull_white;
     /// Vasicek bond pricing model.
@@ -91,43 +161,125 @@ pub mod bonds {
 }
 pub use bonds::*;
 
+/// Credit derivatives.
+pub mod credit {
+    pub use crate::instruments::credit::cds::*;
+
+    /// Credit default swaps.
+    pub mod cds;
+}
+pub use credit::*;
+
+/// Inflation-linked instruments.
+pub mod inflation {
+    pub use crate::instruments::inflation::{linked_bond::*, zero_coupon_swap::*};
+
+    /// Inflation-linked bonds.
+    pub mod linked_bond;
+    /// Zero-coupon inflation swaps.
+    pub mod zero_coupon_swap;
+}
+pub use inflation::*;
+
 /// Option pricers and sensitivity functions.
 pub mod options {
     pub use crate::instruments::options::{
-        american::*, asian::*, bachelier::*, barrier::*, binary::*, binomial::*,
-        black_scholes_merton::*, european::*, forward_start::*, greeks::*, heston::*, lookback::*,
-        merton_jump_diffusion::*, option::*, power::*,
+        american::*, american_approximation::*, asian::*, autocallable::*, bachelier::*,
+        barrier::*, basket::*, binary::*, binomial::*, black_scholes_merton::*, chooser::*,
+        cliquet::*, compound::*, cos::*, european::*, finite_difference::*, forward_start::*,
+        fx::*, greeks::*, heston::*, implied_volatility::*, kou::*, ladder::*, lattice::*, levy::*,
+        local_volatility::*, lookback::*, merton_jump_diffusion::*, multi_asset::*, option::*,
+        power::*, quanto::*, scripted::*, spread::*, strategy::*, variance_swap::*, warrant::*,
     };
 
     /// American option pricers.
     pub mod american;
+    /// Analytical approximations of American option prices.
+    pub mod american_approximation;
     /// Asian option pricers.
     pub mod asian;
+    /// Autocallable and Phoenix note pricers.
+    pub mod autocallable;
     /// Bachelier option pricer.
     pub mod bachelier;
     /// Barrier option pricers.
     pub mod barrier;
+    /// Basket option pricers.
+    pub mod basket;
     /// Binary option pricers.
     pub mod binary;
     /// Binomial option pricers.
     pub mod binomial;
     /// Generalised Black-Scholes-Merton option pricer.
     pub mod black_scholes_merton;
+    /// Chooser option pricers.
+    pub mod chooser;
+    /// Cliquet option pricers.
+    pub mod cliquet;
+    /// Compound option pricers.
+    pub mod compound;
+    /// Fourier-cosine (COS) pricing engine for exponential Lévy models.
+    pub mod cos;
     /// European option pricers.
     pub mod european;
+    /// Finite-difference (PDE) option pricing engine.
+    pub mod finite_difference;
     /// Forward start options pricers.
     pub mod forward_start;
+    /// FX option pricer with the market's delta conventions.
+    pub mod fx;
     /// European option Greeks/sensitivities.
     pub mod greeks;
     /// Heston model option pricer.
     pub mod heston;
+    /// Implied volatility solver.
+    pub mod implied_volatility;
+    /// Kou (2002) double exponential jump diffusion model.
+    pub mod kou;
+    /// Ladder option pricers.
+    pub mod ladder;
+    /// Lattice (binomial and trinomial tree) option pricing engine.
+    pub mod lattice;
+    /// Variance gamma, normal inverse Gaussian and CGMY Lévy models.
+    pub mod levy;
+    /// Local volatility option pricers.
+    pub mod local_volatility;
     /// Lookback option pricers.
     pub mod lookback;
     /// Merton (1976) jump diffusion model.
     pub mod merton_jump_diffusion;
+    /// Multi-asset market data.
+    pub mod multi_asset;
     /// Base option traits.
     pub mod option;
     /// Power option pricers.
     pub mod power;
+    /// Quanto and composite option pricers.
+    pub mod quanto;
+    /// Scripted path-dependent payoffs priced by Monte Carlo.
+    pub mod scripted;
+    /// Spread option pricers.
+    pub mod spread;
+    /// Option strategies (spreads, straddles, collars).
+    pub mod strategy;
+    /// Variance and volatility swap pricers.
+    pub mod variance_swap;
+    /// Warrant and employee stock option pricers.
+    pub mod warrant;
 }
 pub use options::*;
+
+/// Interest rate derivatives.
+pub mod rates {
+    pub use crate::instruments::rates::{cap_floor::*, frn::*, swap::*, swaption::*};
+
+    /// Caps and floors.
+    pub mod cap_floor;
+    /// Floating rate notes with compounded overnight rate coupons.
+    pub mod frn;
+    /// Vanilla interest rate swaps.
+    pub mod swap;
+    /// European and Bermudan swaptions.
+    pub mod swaption;
+}
+pub use rates::*;
@@ -7,14 +7,364 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! American option pricing via the Longstaff-Schwartz (2001) least-squares
+//! Monte Carlo (LSM) method.
+//!
+//! Working backwards from expiry, the continuation value of the in-the-money
+//! paths is estimated by regressing the discounted future cash flows on a
+//! set of basis functions of the underlying price. A path is exercised when
+//! the immediate payoff exceeds the estimated continuation value.
+//!
+//! Bermudan options are priced the same way, with exercise restricted to a
+//! set of exercise dates.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::TypeFlag;
+use crate::stochastics::Trajectories;
+use nalgebra::{DMatrix, DVector};
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+/// Basis functions for the regression of the continuation value.
+///
+/// The underlying price is divided by the strike before the basis is
+/// evaluated, to keep the regression well conditioned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegressionBasis {
+    /// Monomials $1, x, x^2, \ldots, x^n$.
+    Polynomial(usize),
+    /// Laguerre polynomials $L_0(x), L_1(x), \ldots, L_n(x)$.
+    Laguerre(usize),
+}
+
+/// Longstaff-Schwartz least-squares Monte Carlo pricer for American options.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy)]
+pub struct LongstaffSchwartz {
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// Call or put flag.
+    pub option_type: TypeFlag,
+    /// Basis functions for the regression.
+    pub basis: RegressionBasis,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl RegressionBasis {
+    /// Number of basis functions (including the constant).
+    #[must_use]
+    pub const fn dimension(&self) -> usize {
+        match self {
+            Self::Polynomial(n) | Self::Laguerre(n) => *n + 1,
+        }
+    }
+
+    /// Evaluate the basis functions at `x`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn evaluate(&self, x: f64) -> Vec<f64> {
+        let mut values = Vec::with_capacity(self.dimension());
+
+        match self {
+            Self::Polynomial(n) => {
+                let mut power = 1.0;
+
+                for _ in 0..=*n {
+                    values.push(power);
+                    power *= x;
+                }
+            }
+            Self::Laguerre(n) => {
+                // (k + 1) L_{k+1}(x) = (2k + 1 - x) L_k(x) - k L_{k-1}(x)
+                values.push(1.0);
+
+                if *n > 0 {
+                    values.push(1.0 - x);
+                }
+
+                for k in 1..*n {
+                    let k_f = k as f64;
+                    let next =
+                        ((2.0 * k_f + 1.0 - x) * values[k] - k_f * values[k - 1]) / (k_f + 1.0);
+                    values.push(next);
+                }
+            }
+        }
+
+        values
+    }
+}
+
+impl LongstaffSchwartz {
+    /// New Longstaff-Schwartz pricer.
+    #[must_use]
+    pub const fn new(
+        strike_price: f64,
+        risk_free_rate: f64,
+        option_type: TypeFlag,
+        basis: RegressionBasis,
+    ) -> Self {
+        Self {
+            strike_price,
+            risk_free_rate,
+            option_type,
+            basis,
+        }
+    }
+
+    /// Option payoff for an underlying price `x`.
+    #[must_use]
+    pub fn payoff(&self, x: f64) -> f64 {
+        match self.option_type {
+            TypeFlag::Call => f64::max(x - self.strike_price, 0.0),
+            TypeFlag::Put => f64::max(self.strike_price - x, 0.0),
+        }
+    }
+
+    /// Price an American option on the simulated paths of the underlying
+    /// (e.g. from `GeometricBrownianMotion::euler_maruyama`, simulated
+    /// under the risk-neutral measure).
+    ///
+    /// Exercise is allowed at every time point after the first.
+    /// Returns a tuple: `(price, standard_error)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no paths, or fewer than two time points.
+    #[must_use]
+    pub fn price(&self, trajectories: &Trajectories) -> (f64, f64) {
+        let exercisable = vec![true; trajectories.times.len()];
+
+        self.price_exercisable(trajectories, &exercisable)
+    }
+
+    /// Price a Bermudan option on the simulated paths of the underlying.
+    ///
+    /// Exercise is allowed at expiry and at the time points nearest to the
+    /// `exercise_dates` (in the same units as the paths' times), so the
+    /// paths should be simulated on a grid containing the exercise dates.
+    /// Returns a tuple: `(price, standard_error)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no paths, or fewer than two time points.
+    #[must_use]
+    pub fn price_bermudan(
+        &self,
+        trajectories: &Trajectories,
+        exercise_dates: &[f64],
+    ) -> (f64, f64) {
+        let times = &trajectories.times;
+        let mut exercisable = vec![false; times.len()];
+
+        for date in exercise_dates {
+            let nearest = (0..times.len())
+                .min_by(|&i, &j| (times[i] - date).abs().total_cmp(&(times[j] - date).abs()));
+
+            if let Some(i) = nearest {
+                exercisable[i] = true;
+            }
+        }
+
+        self.price_exercisable(trajectories, &exercisable)
+    }
+
+    // Price with early exercise allowed at the flagged time points after the
+    // first (exercise at expiry is always allowed).
+    #[allow(clippy::cast_precision_loss)]
+    fn price_exercisable(&self, trajectories: &Trajectories, exercisable: &[bool]) -> (f64, f64) {
+        let times = &trajectories.times;
+        let paths = &trajectories.paths;
+
+        assert!(!paths.is_empty(), "No paths to price on.");
+        assert!(times.len() > 1, "At least two time points are required.");
+
+        let n_steps = times.len() - 1;
+
+        // Cash flow of each path, discounted to the current time point.
+        let mut cash_flows: Vec<f64> = paths
+            .iter()
+            .map(|path| self.payoff(path[n_steps]))
+            .collect();
+
+        for t in (1..n_steps).rev() {
+            let df = (-self.risk_free_rate * (times[t + 1] - times[t])).exp();
+
+            for c in &mut cash_flows {
+                *c *= df;
+            }
+
+            if !exercisable[t] {
+                continue;
+            }
+
+            let in_the_money: Vec<usize> = (0..paths.len())
+                .filter(|&i| self.payoff(paths[i][t]) > 0.0)
+                .collect();
+
+            if in_the_money.len() <= self.basis.dimension() {
+                continue;
+            }
+
+            let Some(coefficients) = self.regress(paths, &in_the_money, t, &cash_flows) else {
+                continue;
+            };
+
+            for &i in &in_the_money {
+                let x = paths[i][t];
+                let exercise = self.payoff(x);
+
+                let continuation: f64 = self
+                    .basis
+                    .evaluate(x / self.strike_price)
+                    .iter()
+                    .zip(coefficients.iter())
+                    .map(|(b, c)| b * c)
+                    .sum();
+
+                if exercise > continuation {
+                    cash_flows[i] = exercise;
+                }
+            }
+        }
+
+        // Discount from the first exercise date to the initial time.
+        let df = (-self.risk_free_rate * (times[1] - times[0])).exp();
+
+        let n = cash_flows.len() as f64;
+        let mean = cash_flows.iter().sum::<f64>() * df / n;
+        let variance = cash_flows
+            .iter()
+            .map(|c| (c * df - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1.0).max(1.0);
+
+        (mean, (variance / n).sqrt())
+    }
+
+    // Least-squares regression of the cash flows of the in-the-money paths
+    // on the basis functions, at time point `t`.
+    fn regress(
+        &self,
+        paths: &[Vec<f64>],
+        in_the_money: &[usize],
+        t: usize,
+        cash_flows: &[f64],
+    ) -> Option<DVector<f64>> {
+        let x = DMatrix::from_row_iterator(
+            in_the_money.len(),
+            self.basis.dimension(),
+            in_the_money
+                .iter()
+                .flat_map(|&i| self.basis.evaluate(paths[i][t] / self.strike_price)),
+        );
+        let y = DVector::from_iterator(
+            in_the_money.len(),
+            in_the_money.iter().map(|&i| cash_flows[i]),
+        );
+
+        x.svd(true, true).solve(&y, f64::EPSILON).ok()
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_american {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::stochastics::{GeometricBrownianMotion, StochasticProcess};
+
+    #[test]
+    fn test_laguerre_basis() {
+        let x: f64 = 0.7;
+        let values = RegressionBasis::Laguerre(3).evaluate(x);
+
+        assert_eq!(values.len(), 4);
+        assert_approx_equal!(values[0], 1.0, 1e-15);
+        assert_approx_equal!(values[1], 1.0 - x, 1e-15);
+        assert_approx_equal!(values[2], 1.0 - 2.0 * x + x * x / 2.0, 1e-15);
+        assert_approx_equal!(
+            values[3],
+            1.0 - 3.0 * x + 1.5 * x * x - x.powi(3) / 6.0,
+            1e-15
+        );
+
+        assert_eq!(
+            RegressionBasis::Polynomial(2).evaluate(3.0),
+            vec![1.0, 3.0, 9.0]
+        );
+    }
+
+    #[test]
+    fn test_american_put() {
+        // Longstaff and Schwartz (2001), Table 1: S = 36, K = 40, r = 0.06,
+        // sigma = 0.2, T = 1, 50 exercise dates per year: 4.472.
+        let gbm = GeometricBrownianMotion::new(0.06, 0.2);
+        let paths = gbm.euler_maruyama(36.0, 0.0, 1.0, 50, 20_000, true);
+
+        for basis in [RegressionBasis::Laguerre(3), RegressionBasis::Polynomial(3)] {
+            let lsm = LongstaffSchwartz::new(40.0, 0.06, TypeFlag::Put, basis);
+            let (price, std_err) = lsm.price(&paths);
+
+            assert!(std_err > 0.0 && std_err < 0.05);
+            assert_approx_equal!(price, 4.472, 0.1);
+        }
+    }
+
+    #[test]
+    fn test_american_call_no_dividends() {
+        // Early exercise of a call on a non-dividend paying asset is never
+        // optimal, so the price is close to the European price (Black-Scholes: 10.45).
+        let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+        let paths = gbm.euler_maruyama(100.0, 0.0, 1.0, 50, 20_000, true);
+
+        let lsm = LongstaffSchwartz::new(100.0, 0.05, TypeFlag::Call, RegressionBasis::Laguerre(2));
+        let (price, _) = lsm.price(&paths);
+
+        assert_approx_equal!(price, 10.45, 0.5);
+    }
+
+    #[test]
+    fn test_bermudan_put() {
+        use crate::instruments::{BinomialTree, ExerciseFlag, LatticeModel, LatticeOption};
+
+        let dates = [0.25, 0.5, 0.75];
+
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 500).price(
+            &LatticeOption::new(
+                36.0,
+                40.0,
+                1.0,
+                0.06,
+                0.0,
+                0.2,
+                TypeFlag::Put,
+                ExerciseFlag::Bermudan,
+            )
+            .with_exercise_dates(&dates),
+        );
+
+        let gbm = GeometricBrownianMotion::new(0.06, 0.2);
+        let paths = gbm.euler_maruyama(36.0, 0.0, 1.0, 20, 20_000, true);
+
+        let lsm = LongstaffSchwartz::new(40.0, 0.06, TypeFlag::Put, RegressionBasis::Laguerre(3));
+        let (bermudan, _) = lsm.price_bermudan(&paths, &dates);
+        let (american, _) = lsm.price(&paths);
+
+        assert_approx_equal!(bermudan, tree, 0.1);
+        assert!(bermudan < american);
+    }
+}
@@ -0,0 +1,455 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Analytical approximations of American option prices:
+//! Barone-Adesi and Whaley (1987) and Bjerksund and Stensland (2002).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{generalised_greeks, TypeFlag};
+use crate::math::{Newton, RootFinder, RootFindingError};
+use crate::statistics::distributions::{bivariate_normal_cdf, Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// American option priced by analytical approximation, without a lattice
+/// or a Monte Carlo simulation.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Call on a futures contract (b = 0), Haug (2007).
+/// let option = AmericanOption::new(100.0, 100.0, 0.1, 0.1, 0.1, 0.15);
+///
+/// let baw = option.price_barone_adesi_whaley(TypeFlag::Call).unwrap();
+/// let bs = option.price_bjerksund_stensland(TypeFlag::Call);
+///
+/// assert!((bs - 1.8757).abs() < 1e-4);
+/// assert!((baw - bs).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AmericanOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl AmericanOption {
+    /// New American option.
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+        }
+    }
+
+    /// Whether early exercise is never optimal, in which case the option
+    /// is worth the European option: calls with `q <= 0` and puts
+    /// with `r <= 0`.
+    #[must_use]
+    pub fn is_european(&self, option_type: TypeFlag) -> bool {
+        match option_type {
+            TypeFlag::Call => self.dividend_yield <= 0.0,
+            TypeFlag::Put => self.risk_free_rate <= 0.0,
+        }
+    }
+
+    /// Price of the European option with the same terms.
+    #[must_use]
+    pub fn european_price(&self, option_type: TypeFlag) -> f64 {
+        let (r, v) = (self.risk_free_rate, self.volatility);
+        let b = r - self.dividend_yield;
+
+        generalised_greeks(
+            self.initial_price,
+            self.strike_price,
+            self.time_to_expiry,
+            r,
+            b,
+            v,
+            option_type,
+        )
+        .price
+    }
+
+    /// Critical price of the Barone-Adesi and Whaley (1987) approximation,
+    /// above which the call (below which the put) is exercised.
+    /// Infinite (zero) if early exercise is never optimal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if Newton's method does not converge.
+    pub fn barone_adesi_whaley_critical_price(
+        &self,
+        option_type: TypeFlag,
+    ) -> Result<f64, RootFindingError> {
+        if self.is_european(option_type) {
+            return Ok(match option_type {
+                TypeFlag::Call => f64::INFINITY,
+                TypeFlag::Put => 0.0,
+            });
+        }
+
+        let (K, T, v) = (self.strike_price, self.time_to_expiry, self.volatility);
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+        let q = self.barone_adesi_whaley_exponent(option_type);
+
+        // Seed from the perpetual option (Barone-Adesi and Whaley, 1987),
+        // whose critical price bounds the root on the other side of `K`.
+        let n = 2.0 * b / (v * v);
+        let m = 2.0 * r / (v * v);
+        let root = ((n - 1.0).powi(2) + 4.0 * m).sqrt();
+        let (lower, upper, seed) = match option_type {
+            TypeFlag::Call => {
+                let infinity = K / (1.0 - 2.0 / (-(n - 1.0) + root));
+                let h = -(b * T + 2.0 * v * T.sqrt()) * K / (infinity - K);
+                (K, infinity, K + (infinity - K) * (1.0 - h.exp()))
+            }
+            TypeFlag::Put => {
+                let infinity = K / (1.0 - 2.0 / (-(n - 1.0) - root));
+                let h = (b * T - 2.0 * v * T.sqrt()) * K / (K - infinity);
+                (infinity, K, infinity + (K - infinity) * h.exp())
+            }
+        };
+
+        // Solve `V(S) - (S - K) = A(S)` (calls) or `V(S) - (K - S) = A(S)` (puts),
+        // where `A(S) = +/- S (1 - exp((b - r) T) N(+/- d_1)) / q`.
+        let sign = match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+        let f = |s: f64| {
+            let european = generalised_greeks(s, K, T, r, b, v, option_type);
+            let exercise = 1.0 - sign * european.delta;
+
+            european.price + sign * (s * exercise / q - (s - K))
+        };
+
+        Newton::new(1e-12 * K, 100).solve(&f, lower, upper, seed)
+    }
+
+    /// Barone-Adesi and Whaley (1987) quadratic approximation.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if the critical price is not found
+    /// (see [`AmericanOption::barone_adesi_whaley_critical_price`]).
+    pub fn price_barone_adesi_whaley(
+        &self,
+        option_type: TypeFlag,
+    ) -> Result<f64, RootFindingError> {
+        let european = self.european_price(option_type);
+
+        if self.is_european(option_type) {
+            return Ok(european);
+        }
+
+        let (S, K, T, v) = (
+            self.initial_price,
+            self.strike_price,
+            self.time_to_expiry,
+            self.volatility,
+        );
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+
+        let critical = self.barone_adesi_whaley_critical_price(option_type)?;
+        let q = self.barone_adesi_whaley_exponent(option_type);
+        let delta = generalised_greeks(critical, K, T, r, b, v, option_type).delta;
+
+        Ok(match option_type {
+            TypeFlag::Call if S < critical => {
+                european + critical / q * (1.0 - delta) * (S / critical).powf(q)
+            }
+            TypeFlag::Put if S > critical => {
+                european - critical / q * (1.0 + delta) * (S / critical).powf(q)
+            }
+            TypeFlag::Call => S - K,
+            TypeFlag::Put => K - S,
+        })
+    }
+
+    /// Bjerksund and Stensland (2002) approximation, with a two-step flat
+    /// exercise boundary. Puts are priced as calls by the put-call
+    /// transformation `P(S, K, r, q) = C(K, S, q, r)`.
+    #[must_use]
+    pub fn price_bjerksund_stensland(&self, option_type: TypeFlag) -> f64 {
+        if self.is_european(option_type) {
+            return self.european_price(option_type);
+        }
+
+        let (S, K, T, v) = (
+            self.initial_price,
+            self.strike_price,
+            self.time_to_expiry,
+            self.volatility,
+        );
+        let (r, q) = (self.risk_free_rate, self.dividend_yield);
+
+        match option_type {
+            TypeFlag::Call => bjerksund_stensland_call(S, K, T, r, r - q, v),
+            TypeFlag::Put => bjerksund_stensland_call(K, S, T, q, q - r, v),
+        }
+    }
+
+    // Exponent `q_2` (calls) or `q_1` (puts) of the Barone-Adesi and
+    // Whaley approximation.
+    fn barone_adesi_whaley_exponent(&self, option_type: TypeFlag) -> f64 {
+        let (T, v) = (self.time_to_expiry, self.volatility);
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+
+        let n = 2.0 * b / (v * v);
+        let m = 2.0 * r / (v * v);
+        let root = ((n - 1.0).powi(2) + 4.0 * m / (1.0 - (-r * T).exp())).sqrt();
+
+        match option_type {
+            TypeFlag::Call => 0.5 * (-(n - 1.0) + root),
+            TypeFlag::Put => 0.5 * (-(n - 1.0) - root),
+        }
+    }
+}
+
+// Bjerksund and Stensland (2002) American call, for `b < r`.
+fn bjerksund_stensland_call(S: f64, K: f64, T: f64, r: f64, b: f64, v: f64) -> f64 {
+    let t1 = 0.5 * (5.0_f64.sqrt() - 1.0) * T;
+
+    let beta = (0.5 - b / (v * v)) + ((b / (v * v) - 0.5).powi(2) + 2.0 * r / (v * v)).sqrt();
+    let infinity = beta / (beta - 1.0) * K;
+    let zero = K.max(r / (r - b) * K);
+
+    let trigger = |t: f64| {
+        let h = -(b * t + 2.0 * v * t.sqrt()) * K * K / ((infinity - zero) * zero);
+        zero + (infinity - zero) * (1.0 - h.exp())
+    };
+    let (I1, I2) = (trigger(t1), trigger(T));
+
+    if S >= I2 {
+        return S - K;
+    }
+
+    let alpha1 = (I1 - K) * I1.powf(-beta);
+    let alpha2 = (I2 - K) * I2.powf(-beta);
+
+    let phi = |gamma, H, I| phi(S, t1, gamma, H, I, r, b, v);
+    let psi = |gamma, H| psi(S, T, gamma, H, I2, I1, t1, r, b, v);
+
+    alpha2 * S.powf(beta) - alpha2 * phi(beta, I2, I2) + phi(1.0, I2, I2)
+        - phi(1.0, I1, I2)
+        - K * phi(0.0, I2, I2)
+        + K * phi(0.0, I1, I2)
+        + alpha1 * phi(beta, I1, I2)
+        - alpha1 * psi(beta, I1)
+        + psi(1.0, I1)
+        - psi(1.0, K)
+        - K * psi(0.0, I1)
+        + K * psi(0.0, K)
+}
+
+// Value of `S^gamma` paid at `T` if the underlying stays below the trigger
+// `I` and ends below `H`.
+#[allow(clippy::too_many_arguments)]
+fn phi(S: f64, T: f64, gamma: f64, H: f64, I: f64, r: f64, b: f64, v: f64) -> f64 {
+    let N = |x: f64| Gaussian::default().cdf(x);
+
+    let lambda = (-r + gamma * b + 0.5 * gamma * (gamma - 1.0) * v * v) * T;
+    let kappa = 2.0 * b / (v * v) + 2.0 * gamma - 1.0;
+    let d = -((S / H).ln() + (b + (gamma - 0.5) * v * v) * T) / (v * T.sqrt());
+
+    lambda.exp()
+        * S.powf(gamma)
+        * (N(d) - (I / S).powf(kappa) * N(d - 2.0 * (I / S).ln() / (v * T.sqrt())))
+}
+
+// Two-period counterpart of `phi`, with the trigger `I_1` up to `t_1` and
+// `I_2` from `t_1` to `T`.
+#[allow(clippy::too_many_arguments)]
+fn psi(
+    S: f64,
+    T: f64,
+    gamma: f64,
+    H: f64,
+    I2: f64,
+    I1: f64,
+    t1: f64,
+    r: f64,
+    b: f64,
+    v: f64,
+) -> f64 {
+    let M = bivariate_normal_cdf;
+    let drift = b + (gamma - 0.5) * v * v;
+
+    let e = |x: f64, sign: f64| (x.ln() + sign * drift * t1) / (v * t1.sqrt());
+    let f = |x: f64| (x.ln() + drift * T) / (v * T.sqrt());
+
+    let e1 = e(S / I1, 1.0);
+    let e2 = e(I2 * I2 / (S * I1), 1.0);
+    let e3 = e(S / I1, -1.0);
+    let e4 = e(I2 * I2 / (S * I1), -1.0);
+
+    let f1 = f(S / H);
+    let f2 = f(I2 * I2 / (S * H));
+    let f3 = f(I1 * I1 / (S * H));
+    let f4 = f(S * I1 * I1 / (H * I2 * I2));
+
+    let rho = (t1 / T).sqrt();
+    let lambda = -r + gamma * b + 0.5 * gamma * (gamma - 1.0) * v * v;
+    let kappa = 2.0 * b / (v * v) + 2.0 * gamma - 1.0;
+
+    (lambda * T).exp()
+        * S.powf(gamma)
+        * (M(-e1, -f1, rho)
+            - (I2 / S).powf(kappa) * M(-e2, -f2, rho)
+            - (I1 / S).powf(kappa) * M(-e3, -f3, -rho)
+            + (I1 / I2).powf(kappa) * M(-e4, -f4, -rho))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_american_approximation {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::{BinomialTree, ExerciseFlag, LatticeModel, LatticeOption};
+
+    #[test]
+    fn test_barone_adesi_whaley() {
+        // Futures options (b = 0), r = 0.1, T = 0.1, v = 0.15.
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 2000);
+
+        for S in [90.0, 100.0, 110.0] {
+            for flag in [TypeFlag::Call, TypeFlag::Put] {
+                let option = AmericanOption::new(S, 100.0, 0.1, 0.1, 0.1, 0.15);
+                let lattice =
+                    LatticeOption::new(S, 100.0, 0.1, 0.1, 0.1, 0.15, flag, ExerciseFlag::American);
+
+                assert_approx_equal!(
+                    option.price_barone_adesi_whaley(flag).unwrap(),
+                    tree.price(&lattice),
+                    0.01
+                );
+            }
+        }
+
+        // With b = 0, at the money calls and puts are worth the same.
+        let option = AmericanOption::new(100.0, 100.0, 0.1, 0.1, 0.1, 0.15);
+        assert_approx_equal!(
+            option.price_barone_adesi_whaley(TypeFlag::Call).unwrap(),
+            option.price_barone_adesi_whaley(TypeFlag::Put).unwrap(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_bjerksund_stensland() {
+        // Haug (2007): futures options (b = 0), r = 0.1, T = 0.1, v = 0.15.
+        let price = |S, flag| {
+            AmericanOption::new(S, 100.0, 0.1, 0.1, 0.1, 0.15).price_bjerksund_stensland(flag)
+        };
+
+        assert_approx_equal!(price(90.0, TypeFlag::Call), 0.0205, 1e-4);
+        assert_approx_equal!(price(100.0, TypeFlag::Call), 1.8757, 1e-4);
+        assert_approx_equal!(price(110.0, TypeFlag::Call), 10.0000, 1e-4);
+    }
+
+    #[test]
+    fn test_american_approximations_against_tree() {
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 2000);
+
+        for (S, q, flag) in [
+            (36.0, 0.0, TypeFlag::Put),
+            (40.0, 0.02, TypeFlag::Put),
+            (44.0, 0.04, TypeFlag::Put),
+            (40.0, 0.08, TypeFlag::Call),
+            (44.0, 0.1, TypeFlag::Call),
+        ] {
+            let option = AmericanOption::new(S, 40.0, 1.0, 0.06, q, 0.2);
+            let lattice =
+                LatticeOption::new(S, 40.0, 1.0, 0.06, q, 0.2, flag, ExerciseFlag::American);
+            let exact = tree.price(&lattice);
+
+            assert_approx_equal!(option.price_barone_adesi_whaley(flag).unwrap(), exact, 0.05);
+            assert_approx_equal!(option.price_bjerksund_stensland(flag), exact, 0.05);
+
+            // Early exercise is worth something.
+            assert!(option.price_bjerksund_stensland(flag) > option.european_price(flag));
+        }
+    }
+
+    #[test]
+    fn test_american_approximations_without_early_exercise() {
+        // Without dividends, an American call is a European call.
+        let option = AmericanOption::new(42.0, 40.0, 0.5, 0.05, 0.0, 0.3);
+        let european = option.european_price(TypeFlag::Call);
+
+        assert!(option.is_european(TypeFlag::Call));
+        assert!(!option.is_european(TypeFlag::Put));
+        assert_approx_equal!(
+            option.price_barone_adesi_whaley(TypeFlag::Call).unwrap(),
+            european,
+            1e-12
+        );
+        assert_approx_equal!(
+            option.price_bjerksund_stensland(TypeFlag::Call),
+            european,
+            1e-12
+        );
+
+        // The put's critical price is below the strike, and exercising
+        // there is worth the same as holding.
+        let critical = option
+            .barone_adesi_whaley_critical_price(TypeFlag::Put)
+            .unwrap();
+        let at_critical = AmericanOption {
+            initial_price: critical,
+            ..option
+        };
+
+        assert!(critical < 40.0);
+        assert_approx_equal!(
+            at_critical
+                .price_barone_adesi_whaley(TypeFlag::Put)
+                .unwrap(),
+            40.0 - critical,
+            1e-8
+        );
+    }
+}
@@ -7,10 +7,12 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
 use time::OffsetDateTime;
 
 use crate::{
-    statistics::distributions::{gaussian::Gaussian, Distribution},
+    autodiff::Real,
     time::{DayCountConvention, DayCounter},
 };
 
@@ -50,36 +52,49 @@ pub enum AveragingMethod {
 /// Asian Option struct.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
-pub struct AsianOption {
+pub struct AsianOption<R: Real = f64> {
     /// `S` - Initial price of the underlying.
-    pub initial_price: f64,
+    pub initial_price: R,
     /// `K` - Strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// `r` - Risk-free rate parameter.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// `v` - Volatility parameter.
-    pub volatility: f64,
+    pub volatility: R,
     /// `q` - Dividend rate.
-    pub dividend_rate: f64,
+    pub dividend_rate: R,
     /// `valuation_date` - Valuation date.
     pub valuation_date: Option<OffsetDateTime>,
     /// `expiry_date` - Expiry date.
     pub expiry_date: OffsetDateTime,
 }
 
+/// Monte Carlo prices of an arithmetic average Asian option.
+#[derive(Debug, Clone, Copy)]
+pub struct AsianMonteCarloResult {
+    /// Call price.
+    pub call: f64,
+    /// Put price.
+    pub put: f64,
+    /// Standard error of the call price.
+    pub call_std_error: f64,
+    /// Standard error of the put price.
+    pub put_std_error: f64,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl AsianOption {
+impl<R: Real> AsianOption<R> {
     /// New Asian Option
     #[must_use]
     pub const fn new(
-        initial_price: f64,
-        strike_price: f64,
-        risk_free_rate: f64,
-        volatility: f64,
-        dividend_rate: f64,
+        initial_price: R,
+        strike_price: R,
+        risk_free_rate: R,
+        volatility: R,
+        dividend_rate: R,
         valuation_date: Option<OffsetDateTime>,
         expiry_date: OffsetDateTime,
     ) -> Self {
@@ -96,7 +111,7 @@ impl AsianOption {
 
     /// Geometric Continuous Average-Rate Price
     #[must_use]
-    pub fn price_geometric_average(&self) -> (f64, f64) {
+    pub fn price_geometric_average(&self) -> (R, R) {
         let S = self.initial_price;
         let K = self.strike_price;
         // let T = self.time_to_maturity;
@@ -120,18 +135,323 @@ impl AsianOption {
 
         let v_a = v / 3_f64.sqrt();
         let b = r - q;
-        let b_a = 0.5 * (b - v * v / 6.0);
+        let b_a = (b - v * v / 6.0) * 0.5;
 
-        let d1 = ((S / K).ln() + (b_a + 0.5 * v_a * v_a) * T) / (v_a * (T).sqrt());
+        let d1 = ((S / K).ln() + (b_a + v_a * v_a * 0.5) * T) / (v_a * (T).sqrt());
         let d2 = d1 - v_a * (T).sqrt();
 
-        let N = Gaussian::default();
+        let c = S * ((b_a - r) * T).exp() * d1.norm_cdf() - K * (-r * T).exp() * d2.norm_cdf();
+        let p =
+            -S * ((b_a - r) * T).exp() * (-d1).norm_cdf() + K * (-r * T).exp() * (-d2).norm_cdf();
+
+        (c, p)
+    }
+
+    /// Geometric Continuous Average-Strike Price
+    /// Returns a tuple: `(call_price, put_price)`
+    #[must_use]
+    pub fn price_geometric_floating(&self) -> (R, R) {
+        self.price_geometric(AsianStrike::Floating, 0.5, 1.0 / 3.0)
+    }
+
+    /// Geometric Discrete Average Price, for `fixings` equally spaced
+    /// fixing dates, the last one at expiry.
+    /// Returns a tuple: `(call_price, put_price)`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fixings` is zero.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_geometric_discrete(&self, strike: AsianStrike, fixings: usize) -> (R, R) {
+        assert!(fixings > 0, "At least one fixing is required.");
+
+        let n = fixings as f64;
+
+        self.price_geometric(
+            strike,
+            (n + 1.0) / (2.0 * n),
+            (n + 1.0) * (2.0 * n + 1.0) / (6.0 * n * n),
+        )
+    }
+
+    /// Turnbull-Wakeman (1991) approximation for a fixed strike,
+    /// continuous arithmetic average.
+    ///
+    /// The arithmetic average is approximated by a lognormal variable with
+    /// the same first two moments.
+    /// Returns a tuple: `(call_price, put_price)`
+    #[must_use]
+    pub fn price_turnbull_wakeman(&self) -> (R, R) {
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let T = self.year_fraction();
+
+        let b = r - self.dividend_rate;
+        let v2 = v * v;
 
-        let c = S * ((b_a - r) * T).exp() * N.cdf(d1) - K * (-r * T).exp() * N.cdf(d2);
-        let p = -S * ((b_a - r) * T).exp() * N.cdf(-d1) + K * (-r * T).exp() * N.cdf(-d2);
+        // First two moments of the average, divided by S and S^2.
+        let (M1, M2) = if b.value().abs() < 1e-10 {
+            let M1 = v2 * 0.0 + 1.0;
+            let M2 = ((v2 * T).exp() - 1.0 - v2 * T) * 2.0 / (v2 * v2 * (T * T));
+            (M1, M2)
+        } else {
+            let M1 = ((b * T).exp() - 1.0) / (b * T);
+            let M2 = ((b * 2.0 + v2) * T).exp() * 2.0 / ((b + v2) * (b * 2.0 + v2) * (T * T))
+                + ((b * 2.0 + v2).recip() - (b * T).exp() / (b + v2)) * 2.0 / (b * (T * T));
+            (M1, M2)
+        };
+
+        let b_A = M1.ln() / T;
+        let v_A = (M2.ln() / T - b_A * 2.0).sqrt();
+
+        let d1 = ((S / K).ln() + (b_A + v_A * v_A * 0.5) * T) / (v_A * T.sqrt());
+        let d2 = d1 - v_A * T.sqrt();
+
+        let c = S * ((b_A - r) * T).exp() * d1.norm_cdf() - K * (-r * T).exp() * d2.norm_cdf();
+        let p =
+            -S * ((b_A - r) * T).exp() * (-d1).norm_cdf() + K * (-r * T).exp() * (-d2).norm_cdf();
 
         (c, p)
     }
+
+    /// Curran (1994) approximation for a fixed strike, discrete arithmetic
+    /// average over `fixings` equally spaced fixing dates, the last one at
+    /// expiry.
+    ///
+    /// The price is computed conditionally on the geometric average.
+    /// Returns a tuple: `(call_price, put_price)`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fixings` is zero.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::similar_names)]
+    pub fn price_curran(&self, fixings: usize) -> (R, R) {
+        assert!(fixings > 0, "At least one fixing is required.");
+
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let T = self.year_fraction();
+
+        let n = fixings as f64;
+        let b = r - self.dividend_rate;
+        let v2 = v * v;
+        let drift = b - v2 * 0.5;
+        let df = (-r * T).exp();
+
+        // Mean and variance of the log of the geometric average.
+        let mu = S.ln() + drift * (T * (n + 1.0) / (2.0 * n));
+        let v2_x = v2 * (T * (n + 1.0) * (2.0 * n + 1.0) / (6.0 * n * n));
+        let v_x = v2_x.sqrt();
+
+        // For each fixing: mean and variance of the log price, and the
+        // covariance with the log of the geometric average.
+        let moments = (1..=fixings).map(|i| {
+            let i = i as f64;
+            let t_i = T * i / n;
+            let cov = T / (n * n) * (i * (i + 1.0) * 0.5 + (n - i) * i);
+            (S.ln() + drift * t_i, v2 * t_i, v2 * cov)
+        });
+
+        let sum = |terms: &mut dyn Iterator<Item = R>| terms.reduce(|a, b| a + b).unwrap();
+
+        let expected_average = sum(&mut moments
+            .clone()
+            .map(|(mu_i, v2_i, _)| (mu_i + v2_i * 0.5).exp()))
+            / n;
+
+        let K_hat = K * 2.0
+            - sum(&mut moments.clone().map(|(mu_i, v2_i, v_xi)| {
+                (mu_i + v_xi / v2_x * (K.ln() - mu) + (v2_i - v_xi * v_xi / v2_x) * 0.5).exp()
+            })) / n;
+
+        let forward_value = df * (expected_average - K);
+
+        let c = if K_hat.value() <= 0.0 {
+            // Exercised with certainty.
+            forward_value
+        } else {
+            let d = (mu - K_hat.ln()) / v_x;
+
+            df * (sum(&mut moments
+                .map(|(mu_i, v2_i, v_xi)| (mu_i + v2_i * 0.5).exp() * (d + v_xi / v_x).norm_cdf()))
+                / n
+                - K * d.norm_cdf())
+        };
+
+        (c, c - forward_value)
+    }
+
+    // Closed form for a geometric average, given the mean time of the
+    // fixings and the variance of the log average, as fractions of the
+    // time to expiry and of `v^2 T`.
+    fn price_geometric(&self, strike: AsianStrike, mean_time: f64, variance: f64) -> (R, R) {
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let T = self.year_fraction();
+
+        let b = r - self.dividend_rate;
+        let df = (-r * T).exp();
+
+        // Expected geometric average, and variance of its log.
+        let v2_G = v * v * (variance * T);
+        let G = S * ((b - v * v * 0.5) * (mean_time * T) + v2_G * 0.5).exp();
+
+        let (F_1, F_2, v_d) = match strike {
+            AsianStrike::Fixed => (G, K, v2_G.sqrt()),
+            // Exchange of the average for the terminal price: the log of
+            // their ratio has variance `v^2 T + v2_G - 2 v^2 mean_time T`.
+            AsianStrike::Floating => (
+                S * (b * T).exp(),
+                G,
+                (v * v * T + v2_G - v * v * (2.0 * mean_time * T)).sqrt(),
+            ),
+        };
+
+        let d1 = (F_1 / F_2).ln() / v_d + v_d * 0.5;
+        let d2 = d1 - v_d;
+
+        let c = df * (F_1 * d1.norm_cdf() - F_2 * d2.norm_cdf());
+        let p = df * (F_2 * (-d2).norm_cdf() - F_1 * (-d1).norm_cdf());
+
+        (c, p)
+    }
+
+    // Time to expiry, in years.
+    fn year_fraction(&self) -> f64 {
+        DayCounter::day_count_factor(
+            self.valuation_date.unwrap_or(OffsetDateTime::now_utc()),
+            self.expiry_date,
+            &DayCountConvention::Actual365,
+        )
+    }
+}
+
+impl AsianOption<f64> {
+    /// Monte Carlo price of an arithmetic average Asian option, over
+    /// `fixings` equally spaced fixing dates (the last one at expiry).
+    ///
+    /// The geometric average option (with the same strike type and
+    /// fixings), which has a closed form, is used as a control variate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fixings` is zero or there are fewer than 2 paths.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_monte_carlo(
+        &self,
+        strike: AsianStrike,
+        fixings: usize,
+        paths: usize,
+        seed: u64,
+    ) -> AsianMonteCarloResult {
+        assert!(fixings > 0, "At least one fixing is required.");
+        assert!(paths > 1, "At least two paths are required.");
+
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let T = self.year_fraction();
+
+        let n = fixings as f64;
+        let dt = T / n;
+        let drift = (r - self.dividend_rate - 0.5 * v * v) * dt;
+        let diffusion = v * dt.sqrt();
+        let df = (-r * T).exp();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // Discounted (call, put) payoffs of the arithmetic and geometric options.
+        let mut arithmetic = Vec::with_capacity(paths);
+        let mut geometric = Vec::with_capacity(paths);
+
+        for _ in 0..paths {
+            let mut log_s = S.ln();
+            let mut sum = 0.0;
+            let mut sum_log = 0.0;
+
+            for _ in 0..fixings {
+                let z: f64 = StandardNormal.sample(&mut rng);
+                log_s += drift + diffusion * z;
+                sum += log_s.exp();
+                sum_log += log_s;
+            }
+
+            let A = sum / n;
+            let G = (sum_log / n).exp();
+
+            let (strike_A, strike_G, underlying) = match strike {
+                AsianStrike::Fixed => (K, K, None),
+                AsianStrike::Floating => (A, G, Some(log_s.exp())),
+            };
+            let payoffs = |average: f64, k: f64| {
+                let x = underlying.unwrap_or(average);
+                (df * f64::max(x - k, 0.0), df * f64::max(k - x, 0.0))
+            };
+
+            arithmetic.push(payoffs(A, strike_A));
+            geometric.push(payoffs(G, strike_G));
+        }
+
+        let (exact_call, exact_put) = self.price_geometric_discrete(strike, fixings);
+
+        let call = control_variate(
+            arithmetic.iter().map(|x| x.0),
+            geometric.iter().map(|x| x.0),
+            exact_call,
+        );
+        let put = control_variate(
+            arithmetic.iter().map(|x| x.1),
+            geometric.iter().map(|x| x.1),
+            exact_put,
+        );
+
+        AsianMonteCarloResult {
+            call: call.0,
+            put: put.0,
+            call_std_error: call.1,
+            put_std_error: put.1,
+        }
+    }
+}
+
+// Control variate estimate of E[X], given samples of X and of a control Y
+// with known mean. Returns a tuple: `(estimate, standard_error)`.
+#[allow(clippy::cast_precision_loss)]
+fn control_variate(
+    x: impl Iterator<Item = f64> + Clone,
+    y: impl Iterator<Item = f64> + Clone,
+    y_mean: f64,
+) -> (f64, f64) {
+    let n = x.clone().count() as f64;
+    let x_bar = x.clone().sum::<f64>() / n;
+    let y_bar = y.clone().sum::<f64>() / n;
+
+    let (cov, var) = x
+        .clone()
+        .zip(y.clone())
+        .fold((0.0, 0.0), |(cov, var), (x, y)| {
+            (cov + (x - x_bar) * (y - y_bar), var + (y - y_bar).powi(2))
+        });
+    let beta = if var > 0.0 { cov / var } else { 0.0 };
+
+    let estimate = x_bar - beta * (y_bar - y_mean);
+    let variance = x
+        .zip(y)
+        .map(|(x, y)| (x - beta * (y - y_bar) - x_bar).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+
+    (estimate, (variance / n).sqrt())
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -164,4 +484,78 @@ mod tests {
         // Value from Haug's book.
         assert_approx_equal!(prices.1, 4.6922, 0.0001);
     }
+
+    fn option(strike: f64) -> AsianOption {
+        let valuation_date = OffsetDateTime::now_utc();
+
+        AsianOption::new(
+            100.0,
+            strike,
+            0.05,
+            0.2,
+            0.0,
+            Some(valuation_date),
+            valuation_date + Duration::days(365),
+        )
+    }
+
+    #[test]
+    fn test_geometric_discrete_converges() {
+        let option = option(100.0);
+
+        let (c, p) = option.price_geometric_average();
+        let (c_n, p_n) = option.price_geometric_discrete(AsianStrike::Fixed, 10_000);
+        assert_approx_equal!(c_n, c, 1e-3);
+        assert_approx_equal!(p_n, p, 1e-3);
+
+        let (c, p) = option.price_geometric_floating();
+        let (c_n, p_n) = option.price_geometric_discrete(AsianStrike::Floating, 10_000);
+        assert_approx_equal!(c_n, c, 1e-3);
+        assert_approx_equal!(p_n, p, 1e-3);
+
+        // A single fixing at expiry is a European option (Black-Scholes).
+        let (c_1, _) = option.price_geometric_discrete(AsianStrike::Fixed, 1);
+        assert_approx_equal!(c_1, 10.450_583_572_185_565, 1e-10);
+    }
+
+    #[test]
+    fn test_arithmetic_approximations() {
+        for strike in [90.0, 100.0, 110.0] {
+            let option = option(strike);
+
+            let mc = option.price_monte_carlo(AsianStrike::Fixed, 12, 20_000, 42);
+            let (curran_call, curran_put) = option.price_curran(12);
+
+            assert!(mc.call_std_error < 0.005);
+            assert_approx_equal!(curran_call, mc.call, 0.02);
+            assert_approx_equal!(curran_put, mc.put, 0.02);
+
+            // Many fixings: continuous averaging. Turnbull-Wakeman is the
+            // less accurate of the two approximations.
+            let (tw_call, tw_put) = option.price_turnbull_wakeman();
+            let (curran_call, curran_put) = option.price_curran(1_000);
+
+            assert_approx_equal!(tw_call, curran_call, 0.05);
+            assert_approx_equal!(tw_put, curran_put, 0.05);
+
+            // The arithmetic average is above the geometric average.
+            assert!(tw_call > option.price_geometric_average().0);
+        }
+    }
+
+    #[test]
+    fn test_floating_strike_monte_carlo() {
+        let option = option(100.0);
+        let mc = option.price_monte_carlo(AsianStrike::Floating, 12, 20_000, 7);
+
+        // Put-call parity: C - P = exp(-rT) (E[S_T] - E[A]).
+        let expected_average = (1..=12)
+            .map(|i| 100.0 * (0.05 * f64::from(i) / 12.0).exp())
+            .sum::<f64>()
+            / 12.0;
+        let parity = (-0.05_f64).exp() * (100.0 * 0.05_f64.exp() - expected_average);
+
+        assert!(mc.call > 0.0 && mc.put > 0.0);
+        assert_approx_equal!(mc.call - mc.put, parity, 0.05);
+    }
 }
@@ -0,0 +1,579 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Autocallable and Phoenix notes, priced by Monte Carlo.
+//!
+//! On each observation date `t_i`, with the underlying at `S_i` and the
+//! levels expressed as fractions of the initial fixing `S_0`:
+//!
+//! - a coupon `c N` is paid if `S_i >= B_c S_0` (with memory, the coupons
+//!   missed on earlier dates are paid as well),
+//! - the note redeems early at `N` if `S_i >= B_a S_0`.
+//!
+//! If the note survives to maturity it redeems at `N (1 - max(K - S_n / S_0, 0))`
+//! if the knock-in barrier `B_k S_0` has been breached, and at `N` otherwise.
+//!
+//! The underlying can follow Black-Scholes, Dupire local volatility or
+//! Heston dynamics (see [`AutocallableModel`]).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::Heston;
+use crate::models::LocalVolatility;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Dynamics of the underlying of an autocallable note.
+#[derive(Debug, Clone, Copy)]
+pub enum AutocallableModel<'a> {
+    /// Geometric Brownian motion, simulated exactly.
+    BlackScholes {
+        /// `S` - Initial price of the underlying.
+        spot: f64,
+        /// `r` - Risk-free rate.
+        risk_free_rate: f64,
+        /// `q` - Dividend yield.
+        dividend_yield: f64,
+        /// `v` - Volatility.
+        volatility: f64,
+    },
+    /// Dupire local volatility, with an Euler scheme in `ln S`.
+    LocalVolatility(&'a LocalVolatility),
+    /// Heston stochastic volatility, with a full truncation Euler scheme.
+    Heston(&'a Heston),
+}
+
+/// Monitoring of the knock-in barrier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnockInMonitoring {
+    /// At maturity only (European barrier).
+    AtMaturity,
+    /// On the observation dates.
+    ObservationDates,
+    /// On every simulation time step.
+    Path,
+}
+
+/// Autocallable (Phoenix) note.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Two year note, quarterly observations, 2% coupon below a 70% barrier,
+/// // autocall at 100%, and a 60% knock-in put at maturity.
+/// let times: Vec<f64> = (1..=8).map(|i| f64::from(i) / 4.0).collect();
+/// let note = AutocallableNote::new(100.0, 100.0, times, 0.02, 0.7, 1.0, 0.6).with_memory(true);
+///
+/// let model = AutocallableModel::BlackScholes {
+///     spot: 100.0,
+///     risk_free_rate: 0.03,
+///     dividend_yield: 0.01,
+///     volatility: 0.25,
+/// };
+/// let result = note.price_monte_carlo(&model, 20_000, 42);
+///
+/// assert!(result.price > 80.0 && result.price < 110.0);
+/// assert_eq!(result.cashflows.len(), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutocallableNote {
+    /// `N` - Notional.
+    pub notional: f64,
+    /// `S_0` - Initial fixing of the underlying, which the levels refer to.
+    pub initial_fixing: f64,
+    /// Observation times (in years), increasing. The last one is maturity.
+    pub observation_times: Vec<f64>,
+    /// `c` - Coupon per observation date, as a fraction of the notional.
+    pub coupon_rate: f64,
+    /// `B_c` - Coupon barrier, as a fraction of the initial fixing.
+    pub coupon_barrier: f64,
+    /// `B_a` - Autocall barrier, as a fraction of the initial fixing.
+    pub autocall_barrier: f64,
+    /// `B_k` - Knock-in barrier, as a fraction of the initial fixing.
+    pub knock_in_barrier: f64,
+    /// `K` - Strike of the knock-in put, as a fraction of the initial fixing.
+    /// Default: 1.
+    pub put_strike: f64,
+    /// Whether missed coupons are paid on the next coupon date. Default: false.
+    pub memory: bool,
+    /// Monitoring of the knock-in barrier. Default: at maturity.
+    pub knock_in_monitoring: KnockInMonitoring,
+    /// Largest simulation time step (in years). Default: 1/252.
+    pub time_step: f64,
+}
+
+/// Expected cashflows of an autocallable note on one observation date.
+#[derive(Debug, Clone, Copy)]
+pub struct AutocallableCashflow {
+    /// Observation time (in years).
+    pub time: f64,
+    /// Probability that the note is still alive on this date.
+    pub survival_probability: f64,
+    /// Probability that the note redeems early on this date.
+    pub autocall_probability: f64,
+    /// Probability that a coupon is paid on this date.
+    pub coupon_probability: f64,
+    /// Expected coupon paid on this date (undiscounted).
+    pub expected_coupon: f64,
+    /// Expected redemption paid on this date (undiscounted).
+    pub expected_redemption: f64,
+    /// Present value of the coupon and redemption paid on this date.
+    pub present_value: f64,
+}
+
+/// Monte Carlo price of an autocallable note.
+#[derive(Debug, Clone)]
+pub struct AutocallableMonteCarloResult {
+    /// Price.
+    pub price: f64,
+    /// Standard error of the price.
+    pub std_error: f64,
+    /// Expected life of the note (in years).
+    pub expected_life: f64,
+    /// Probability that the note reaches maturity with the barrier breached.
+    pub knock_in_probability: f64,
+    /// Expected cashflows, one per observation date.
+    pub cashflows: Vec<AutocallableCashflow>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl AutocallableModel<'_> {
+    /// Initial price of the underlying.
+    #[must_use]
+    pub const fn spot(&self) -> f64 {
+        match self {
+            Self::BlackScholes { spot, .. } => *spot,
+            Self::LocalVolatility(model) => model.spot,
+            Self::Heston(model) => model.initial_price,
+        }
+    }
+
+    /// Risk-free rate, used to discount the cashflows.
+    #[must_use]
+    pub const fn risk_free_rate(&self) -> f64 {
+        match self {
+            Self::BlackScholes { risk_free_rate, .. } => *risk_free_rate,
+            Self::LocalVolatility(model) => model.risk_free_rate,
+            Self::Heston(model) => model.risk_free_rate,
+        }
+    }
+
+    // Initial (ln S, variance) state.
+    fn initial_state(&self) -> (f64, f64) {
+        match self {
+            Self::Heston(model) => (model.initial_price.ln(), model.v0),
+            _ => (self.spot().ln(), 0.0),
+        }
+    }
+
+    // Advance the (ln S, variance) state from `t` to `t + dt`.
+    fn step(&self, state: &mut (f64, f64), t: f64, dt: f64, rng: &mut StdRng) {
+        let z: f64 = StandardNormal.sample(rng);
+
+        match self {
+            Self::BlackScholes {
+                risk_free_rate,
+                dividend_yield,
+                volatility: v,
+                ..
+            } => {
+                state.0 += (risk_free_rate - dividend_yield - 0.5 * v * v) * dt + v * dt.sqrt() * z;
+            }
+            Self::LocalVolatility(model) => {
+                let v = model.local_volatility(state.0.exp(), t);
+                let carry = model.risk_free_rate - model.dividend_yield;
+                state.0 += (carry - 0.5 * v * v) * dt + v * dt.sqrt() * z;
+            }
+            Self::Heston(model) => {
+                let w: f64 = StandardNormal.sample(rng);
+                let w = model.rho * z + (1.0 - model.rho * model.rho).sqrt() * w;
+
+                let v = state.1.max(0.0);
+                let carry = model.risk_free_rate - model.dividend_yield;
+
+                state.0 += (carry - 0.5 * v) * dt + (v * dt).sqrt() * z;
+                state.1 += model.kappa * (model.theta - v) * dt + model.sigma * (v * dt).sqrt() * w;
+            }
+        }
+    }
+}
+
+impl AutocallableNote {
+    /// New autocallable note, without coupon memory, with a knock-in put
+    /// struck at the initial fixing and observed at maturity.
+    #[must_use]
+    pub fn new(
+        notional: f64,
+        initial_fixing: f64,
+        observation_times: Vec<f64>,
+        coupon_rate: f64,
+        coupon_barrier: f64,
+        autocall_barrier: f64,
+        knock_in_barrier: f64,
+    ) -> Self {
+        Self {
+            notional,
+            initial_fixing,
+            observation_times,
+            coupon_rate,
+            coupon_barrier,
+            autocall_barrier,
+            knock_in_barrier,
+            put_strike: 1.0,
+            memory: false,
+            knock_in_monitoring: KnockInMonitoring::AtMaturity,
+            time_step: 1.0 / 252.0,
+        }
+    }
+
+    /// Set whether missed coupons are paid on the next coupon date.
+    #[must_use]
+    pub fn with_memory(self, memory: bool) -> Self {
+        Self { memory, ..self }
+    }
+
+    /// Set the strike of the knock-in put.
+    #[must_use]
+    pub fn with_put_strike(self, put_strike: f64) -> Self {
+        Self { put_strike, ..self }
+    }
+
+    /// Set the monitoring of the knock-in barrier.
+    #[must_use]
+    pub fn with_knock_in_monitoring(self, knock_in_monitoring: KnockInMonitoring) -> Self {
+        Self {
+            knock_in_monitoring,
+            ..self
+        }
+    }
+
+    /// Set the largest simulation time step.
+    #[must_use]
+    pub fn with_time_step(self, time_step: f64) -> Self {
+        Self { time_step, ..self }
+    }
+
+    /// Monte Carlo price and expected cashflows.
+    ///
+    /// Under Black-Scholes the steps between observation dates are exact, so
+    /// the time step only matters for knock-in monitoring along the path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the observation times are empty, not positive or not
+    /// increasing, if the time step is not positive, or if there are fewer
+    /// than 2 paths.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn price_monte_carlo(
+        &self,
+        model: &AutocallableModel,
+        paths: usize,
+        seed: u64,
+    ) -> AutocallableMonteCarloResult {
+        assert!(paths >= 2, "Monte Carlo needs at least 2 paths.");
+
+        let times = &self.observation_times;
+        let steps = self.simulation_steps(model);
+
+        let n_dates = times.len();
+        let r = model.risk_free_rate();
+        let discount: Vec<f64> = times.iter().map(|t| (-r * t).exp()).collect();
+
+        let (s_0, n_0) = (self.initial_fixing, self.notional);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // Per date: (alive, autocalled, coupon paid, coupon, redemption).
+        let mut totals = vec![[0.0; 5]; n_dates];
+        let mut knocked_in_at_maturity = 0.0;
+        let mut life = 0.0;
+        let mut values = Vec::with_capacity(paths);
+
+        for _ in 0..paths {
+            let mut state = model.initial_state();
+            let mut t = 0.0;
+            let mut knocked_in = false;
+            let mut missed = 0.0;
+            let mut value = 0.0;
+
+            for (i, &(n, dt)) in steps.iter().enumerate() {
+                for _ in 0..n {
+                    model.step(&mut state, t, dt, &mut rng);
+                    t += dt;
+
+                    if self.knock_in_monitoring == KnockInMonitoring::Path {
+                        knocked_in |= state.0.exp() < self.knock_in_barrier * s_0;
+                    }
+                }
+                t = times[i];
+
+                let level = state.0.exp() / s_0;
+                let maturity = i == n_dates - 1;
+
+                if self.knock_in_monitoring == KnockInMonitoring::ObservationDates
+                    || (maturity && self.knock_in_monitoring == KnockInMonitoring::AtMaturity)
+                {
+                    knocked_in |= level < self.knock_in_barrier;
+                }
+
+                let totals = &mut totals[i];
+                totals[0] += 1.0;
+
+                let mut coupon = 0.0;
+                if level >= self.coupon_barrier {
+                    coupon = n_0 * self.coupon_rate + missed;
+                    missed = 0.0;
+                    totals[2] += 1.0;
+                } else if self.memory {
+                    missed += n_0 * self.coupon_rate;
+                }
+
+                let autocall = !maturity && level >= self.autocall_barrier;
+                let redemption = if autocall {
+                    n_0
+                } else if maturity {
+                    knocked_in_at_maturity += f64::from(u8::from(knocked_in));
+                    if knocked_in {
+                        n_0 * (1.0 - f64::max(self.put_strike - level, 0.0))
+                    } else {
+                        n_0
+                    }
+                } else {
+                    0.0
+                };
+
+                totals[1] += f64::from(u8::from(autocall));
+                totals[3] += coupon;
+                totals[4] += redemption;
+                value += discount[i] * (coupon + redemption);
+
+                if autocall || maturity {
+                    life += times[i];
+                    break;
+                }
+            }
+
+            values.push(value);
+        }
+
+        let n = paths as f64;
+        let price = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|x| (x - price).powi(2)).sum::<f64>() / (n - 1.0);
+
+        let cashflows = totals
+            .iter()
+            .zip(times)
+            .zip(&discount)
+            .map(|((x, &time), df)| AutocallableCashflow {
+                time,
+                survival_probability: x[0] / n,
+                autocall_probability: x[1] / n,
+                coupon_probability: x[2] / n,
+                expected_coupon: x[3] / n,
+                expected_redemption: x[4] / n,
+                present_value: df * (x[3] + x[4]) / n,
+            })
+            .collect();
+
+        AutocallableMonteCarloResult {
+            price,
+            std_error: (variance / n).sqrt(),
+            expected_life: life / n,
+            knock_in_probability: knocked_in_at_maturity / n,
+            cashflows,
+        }
+    }
+
+    // (number of steps, step size) of the simulation in each observation period.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn simulation_steps(&self, model: &AutocallableModel) -> Vec<(usize, f64)> {
+        let times = &self.observation_times;
+        assert!(
+            !times.is_empty(),
+            "A note needs at least one observation time."
+        );
+        assert!(
+            times[0] > 0.0 && times.windows(2).all(|w| w[0] < w[1]),
+            "Observation times must be positive and increasing."
+        );
+        assert!(self.time_step > 0.0, "The time step must be positive.");
+
+        let exact = matches!(model, AutocallableModel::BlackScholes { .. })
+            && self.knock_in_monitoring != KnockInMonitoring::Path;
+
+        let mut start = 0.0;
+        times
+            .iter()
+            .map(|&end| {
+                let tau: f64 = end - start;
+                start = end;
+                let n = if exact {
+                    1
+                } else {
+                    ((tau / self.time_step).ceil() as usize).max(1)
+                };
+                (n, tau / n as f64)
+            })
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_autocallable {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::{SmileInterpolation, SmileQuotes, VolatilitySurface};
+    use crate::instruments::{black_scholes_greeks, TypeFlag};
+
+    const MODEL: AutocallableModel = AutocallableModel::BlackScholes {
+        spot: 100.0,
+        risk_free_rate: 0.03,
+        dividend_yield: 0.01,
+        volatility: 0.25,
+    };
+
+    fn quarterly(years: u32) -> Vec<f64> {
+        (1..=4 * years).map(|i| f64::from(i) / 4.0).collect()
+    }
+
+    #[test]
+    fn test_fixed_coupon_note() {
+        // Coupons always paid, never called, never knocked in: a bond.
+        let note = AutocallableNote::new(100.0, 100.0, quarterly(2), 0.02, 0.0, f64::INFINITY, 0.0);
+        let result = note.price_monte_carlo(&MODEL, 100, 1);
+
+        let bond = quarterly(2)
+            .iter()
+            .map(|t| 2.0 * (-0.03 * t).exp())
+            .sum::<f64>()
+            + 100.0 * (-0.06_f64).exp();
+
+        assert_approx_equal!(result.price, bond, 1e-10);
+        assert_approx_equal!(result.std_error, 0.0, 1e-10);
+        assert_approx_equal!(result.expected_life, 2.0, 1e-12);
+    }
+
+    #[test]
+    fn test_knock_in_put() {
+        // No coupons and no autocall: the note is a bond minus a put when
+        // the knock-in barrier is the put strike.
+        let note = AutocallableNote::new(100.0, 100.0, vec![1.0], 0.0, f64::INFINITY, 1.0, 1.0);
+        let result = note.price_monte_carlo(&MODEL, 200_000, 3);
+
+        let put = black_scholes_greeks(100.0, 100.0, 1.0, 0.03, 0.01, 0.25, TypeFlag::Put).price;
+        let expected = 100.0 * (-0.03_f64).exp() - put;
+
+        assert_approx_equal!(result.price, expected, 4.0 * result.std_error);
+    }
+
+    #[test]
+    fn test_cashflow_report() {
+        let note = AutocallableNote::new(100.0, 100.0, quarterly(3), 0.025, 0.7, 1.0, 0.6)
+            .with_knock_in_monitoring(KnockInMonitoring::Path)
+            .with_time_step(1.0 / 52.0);
+
+        let plain = note.price_monte_carlo(&MODEL, 20_000, 5);
+        let memory = note
+            .clone()
+            .with_memory(true)
+            .price_monte_carlo(&MODEL, 20_000, 5);
+
+        // Same paths: memory only adds coupons.
+        assert!(memory.price > plain.price);
+
+        for result in [plain, memory] {
+            let cashflows = &result.cashflows;
+            let total = cashflows.iter().map(|c| c.present_value).sum::<f64>();
+            assert_approx_equal!(total, result.price, 1e-9);
+
+            // Every path either autocalls or reaches maturity.
+            let called = cashflows
+                .iter()
+                .map(|c| c.autocall_probability)
+                .sum::<f64>();
+            let last = cashflows.last().unwrap();
+            assert_approx_equal!(called + last.survival_probability, 1.0, 1e-12);
+
+            for pair in cashflows.windows(2) {
+                let survival = pair[0].survival_probability - pair[0].autocall_probability;
+                assert_approx_equal!(pair[1].survival_probability, survival, 1e-12);
+            }
+
+            assert!(result.knock_in_probability <= last.survival_probability);
+            assert!(result.expected_life > 0.25 && result.expected_life < 3.0);
+        }
+    }
+
+    #[test]
+    fn test_heston_model() {
+        // With no volatility of variance, Heston is Black-Scholes.
+        let heston = Heston::new(100.0, 0.03, 0.01, 2.0, 1.0, 0.0625, 0.0, 0.0, 0.0625);
+        let note = AutocallableNote::new(100.0, 100.0, quarterly(2), 0.02, 0.75, 1.0, 0.65)
+            .with_time_step(1.0 / 52.0);
+
+        let black_scholes = note.price_monte_carlo(&MODEL, 50_000, 11);
+        let heston = note.price_monte_carlo(&AutocallableModel::Heston(&heston), 50_000, 11);
+
+        let tolerance = 4.0 * black_scholes.std_error.hypot(heston.std_error);
+        assert_approx_equal!(black_scholes.price, heston.price, tolerance);
+    }
+
+    #[test]
+    fn test_local_volatility_model() {
+        // A flat implied volatility surface has a flat local volatility.
+        let strikes = vec![60.0, 80.0, 100.0, 120.0, 140.0];
+        let quotes = [0.5_f64, 1.0]
+            .iter()
+            .map(|&t| {
+                let forward = 100.0 * (0.02 * t).exp();
+                SmileQuotes::new(t, forward, strikes.clone(), vec![0.25; 5])
+            })
+            .collect();
+        let surface = VolatilitySurface::new(
+            quotes,
+            SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+        )
+        .unwrap();
+        let local_volatility = LocalVolatility::new(surface, 100.0, 0.03, 0.01);
+
+        let note = AutocallableNote::new(100.0, 100.0, quarterly(1), 0.02, 0.75, 1.0, 0.65)
+            .with_time_step(1.0 / 52.0);
+
+        let black_scholes = note.price_monte_carlo(&MODEL, 20_000, 13);
+        let local = note.price_monte_carlo(
+            &AutocallableModel::LocalVolatility(&local_volatility),
+            20_000,
+            13,
+        );
+
+        let tolerance = 4.0 * black_scholes.std_error.hypot(local.std_error);
+        assert_approx_equal!(black_scholes.price, local.price, tolerance);
+    }
+}
@@ -11,8 +11,8 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::autodiff::Real;
 use crate::instruments::options::TypeFlag;
-use crate::statistics::distributions::{Distribution, Gaussian};
 use crate::time::{DayCountConvention, DayCounter};
 
 use time::OffsetDateTime;
@@ -22,13 +22,13 @@ use time::OffsetDateTime;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Bachelier European Option pricing model.
-pub struct Bachelier {
+pub struct Bachelier<R: Real = f64> {
     /// The underlying asset price.
-    pub underlying_price: f64,
+    pub underlying_price: R,
     /// The options strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// The underlying asset's volatility.
-    pub volatility: f64,
+    pub volatility: R,
 
     /// Evaluation date (optional, defaults to today t = 0).
     pub evaluation_date: Option<OffsetDateTime>,
@@ -41,17 +41,17 @@ pub struct Bachelier {
 
 /// Bachelier European Option pricing model.
 #[allow(clippy::module_name_repetitions)]
-pub struct ModifiedBachelier {
+pub struct ModifiedBachelier<R: Real = f64> {
     /// The underlying asset price.
-    pub underlying_price: f64,
+    pub underlying_price: R,
     /// The options strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// The underlying asset's volatility.
-    pub volatility: f64,
+    pub volatility: R,
     /// Risk-free interest rate.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// Dividend yield.
-    pub dividend_yield: f64,
+    pub dividend_yield: R,
 
     /// Evaluation date (optional, defaults to today t = 0).
     pub evaluation_date: Option<OffsetDateTime>,
@@ -66,13 +66,13 @@ pub struct ModifiedBachelier {
 // IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl Bachelier {
+impl<R: Real> Bachelier<R> {
     /// New Bachelier European Option
     #[must_use]
     pub fn new(
-        underlying_price: f64,
-        strike_price: f64,
-        volatility: f64,
+        underlying_price: R,
+        strike_price: R,
+        volatility: R,
         evaluation_date: Option<OffsetDateTime>,
         expiration_date: OffsetDateTime,
         option_type: TypeFlag,
@@ -89,7 +89,7 @@ impl Bachelier {
 
     /// Bachelier European Option price.
     #[must_use]
-    pub fn price(&self) -> f64 {
+    pub fn price(&self) -> R {
         let S = self.underlying_price;
         let K = self.strike_price;
         let v = self.volatility;
@@ -103,25 +103,23 @@ impl Bachelier {
 
         let d1 = (S - K) / (v * T.sqrt());
 
-        let n = Gaussian::default();
-
         match self.option_type {
-            TypeFlag::Call => (S - K) * n.cdf(d1) + v * T.sqrt() * n.pdf(d1),
-            TypeFlag::Put => (K - S) * n.cdf(-d1) + v * T.sqrt() * n.pdf(-d1),
+            TypeFlag::Call => (S - K) * d1.norm_cdf() + v * T.sqrt() * d1.norm_pdf(),
+            TypeFlag::Put => (K - S) * (-d1).norm_cdf() + v * T.sqrt() * (-d1).norm_pdf(),
         }
     }
 }
 
-impl ModifiedBachelier {
+impl<R: Real> ModifiedBachelier<R> {
     /// New Modified Bachelier European Option
     #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub const fn new(
-        underlying_price: f64,
-        strike_price: f64,
-        volatility: f64,
-        risk_free_rate: f64,
-        dividend_yield: f64,
+        underlying_price: R,
+        strike_price: R,
+        volatility: R,
+        risk_free_rate: R,
+        dividend_yield: R,
         evaluation_date: Option<OffsetDateTime>,
         expiration_date: OffsetDateTime,
         option_type: TypeFlag,
@@ -140,7 +138,7 @@ impl ModifiedBachelier {
 
     /// Modified Bachelier European Option price.
     #[must_use]
-    pub fn price(&self) -> f64 {
+    pub fn price(&self) -> R {
         let S = self.underlying_price;
         let K = self.strike_price;
         let v = self.volatility;
@@ -155,11 +153,13 @@ impl ModifiedBachelier {
 
         let d1 = (S - K) / (v * T.sqrt());
 
-        let n = Gaussian::default();
-
         match self.option_type {
-            TypeFlag::Call => (S - K * (-r * T).exp()) * n.cdf(d1) + v * T.sqrt() * n.pdf(d1),
-            TypeFlag::Put => (K * (-r * T).exp() - S) * n.cdf(-d1) + v * T.sqrt() * n.pdf(-d1),
+            TypeFlag::Call => {
+                (S - K * (-r * T).exp()) * d1.norm_cdf() + v * T.sqrt() * d1.norm_pdf()
+            }
+            TypeFlag::Put => {
+                (K * (-r * T).exp() - S) * (-d1).norm_cdf() + v * T.sqrt() * (-d1).norm_pdf()
+            }
         }
     }
 }
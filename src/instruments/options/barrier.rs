@@ -7,7 +7,12 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use super::{
+    ExerciseFlag, FiniteDifferenceOption, FiniteDifferencePricer, KnockOutBarrier, TypeFlag,
+};
 use crate::statistics::distributions::{gaussian::Gaussian, Distribution};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // BARRIER OPTION STRUCT
@@ -30,6 +35,9 @@ pub struct BarrierOption {
     /// * `v` - Volatility.
     pub volatility: f64,
     /// * `K` - Rebate (paid if the option is not able to be exercised).
+    ///
+    /// The rebate is paid when the barrier is touched for knock-out options,
+    /// and at expiry for knock-in options.
     pub rebate: f64,
     /// * `q` - Dividend yield.
     pub dividend_yield: f64,
@@ -196,6 +204,198 @@ impl BarrierOption {
             }
         }
     }
+
+    /// Price of a discretely monitored barrier option, with `monitoring_points`
+    /// equally spaced monitoring dates (the last one at expiry).
+    ///
+    /// Uses the continuity correction of Broadie, Glasserman and Kou (1997):
+    /// the closed-form price with the barrier shifted away from the
+    /// underlying by a factor `exp(0.5826 v sqrt(t / m))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `monitoring_points` is zero, or if the barrier has been touched.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_discrete(&self, type_flag: BarrierType, monitoring_points: usize) -> f64 {
+        // E[max of Brownian motion overshoot] / sqrt(dt) = -zeta(1/2) / sqrt(2 pi).
+        const BETA: f64 = 0.582_597_157_939_010_7;
+
+        assert!(
+            monitoring_points > 0,
+            "At least one monitoring date is required."
+        );
+
+        let shift =
+            (BETA * self.volatility * (self.time_to_expiry / monitoring_points as f64).sqrt())
+                .exp();
+
+        let barrier = if type_flag.is_up() {
+            self.barrier * shift
+        } else {
+            self.barrier / shift
+        };
+
+        Self { barrier, ..*self }.price(type_flag)
+    }
+
+    /// Monte Carlo price of a discretely monitored barrier option, with
+    /// `monitoring_points` equally spaced monitoring dates (the last one at
+    /// expiry), simulated exactly under geometric Brownian motion.
+    ///
+    /// Returns a tuple: `(price, standard_error)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `monitoring_points` is zero or there are fewer than 2 paths.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_monte_carlo(
+        &self,
+        type_flag: BarrierType,
+        monitoring_points: usize,
+        paths: usize,
+        seed: u64,
+    ) -> (f64, f64) {
+        assert!(
+            monitoring_points > 0,
+            "At least one monitoring date is required."
+        );
+        assert!(paths > 1, "At least two paths are required.");
+
+        let S = self.initial_price;
+        let X = self.strike_price;
+        let H = self.barrier;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+
+        let dt = self.time_to_expiry / monitoring_points as f64;
+        let drift = (r - self.dividend_yield - 0.5 * v * v) * dt;
+        let diffusion = v * dt.sqrt();
+
+        let touched = |s: f64| if type_flag.is_up() { s >= H } else { s <= H };
+        let payoff = |s: f64| match type_flag.option_type() {
+            TypeFlag::Call => f64::max(s - X, 0.0),
+            TypeFlag::Put => f64::max(X - s, 0.0),
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let values: Vec<f64> = (0..paths)
+            .map(|_| {
+                let mut log_s = S.ln();
+                // Monitoring date at which the barrier was first touched.
+                let mut hit = touched(S).then_some(0);
+
+                for j in 1..=monitoring_points {
+                    let z: f64 = rng.sample(StandardNormal);
+                    log_s += drift + diffusion * z;
+
+                    if hit.is_none() && touched(log_s.exp()) {
+                        hit = Some(j);
+                    }
+                }
+
+                let df = (-r * self.time_to_expiry).exp();
+
+                match (type_flag.is_knock_in(), hit) {
+                    (true, Some(_)) | (false, None) => df * payoff(log_s.exp()),
+                    (true, None) => df * self.rebate,
+                    (false, Some(j)) => self.rebate * (-r * j as f64 * dt).exp(),
+                }
+            })
+            .collect();
+
+        let n = paths as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        (mean, (variance / n).sqrt())
+    }
+
+    /// Price of a continuously monitored barrier option on the
+    /// finite-difference (PDE) engine.
+    ///
+    /// Knock-out options are solved with the barrier on the edge of the
+    /// grid. Knock-in options use the in-out parity: the vanilla option,
+    /// less the knock-out option without rebate, plus the value of the
+    /// rebate paid at expiry if the barrier is not touched.
+    ///
+    /// # Panics
+    ///
+    /// See [`FiniteDifferencePricer::price`].
+    #[must_use]
+    pub fn price_finite_difference(
+        &self,
+        type_flag: BarrierType,
+        pricer: &FiniteDifferencePricer,
+    ) -> f64 {
+        let vanilla = FiniteDifferenceOption::new(
+            self.initial_price,
+            self.strike_price,
+            self.time_to_expiry,
+            self.risk_free_rate,
+            self.dividend_yield,
+            self.volatility,
+            type_flag.option_type(),
+            ExerciseFlag::European,
+        );
+
+        let barrier = if type_flag.is_up() {
+            KnockOutBarrier::UpAndOut(self.barrier)
+        } else {
+            KnockOutBarrier::DownAndOut(self.barrier)
+        };
+        let knock_out = vanilla.with_barrier(barrier);
+
+        if type_flag.is_knock_in() {
+            pricer.price(&vanilla).price - pricer.price(&knock_out).price
+                + self.rebate
+                    * self.no_touch_probability(type_flag.is_up())
+                    * (-self.risk_free_rate * self.time_to_expiry).exp()
+        } else {
+            pricer.price(&knock_out.with_rebate(self.rebate)).price
+        }
+    }
+
+    // Risk-neutral probability that a continuously monitored barrier is not
+    // touched before expiry.
+    fn no_touch_probability(&self, up: bool) -> f64 {
+        let S = self.initial_price;
+        let H = self.barrier;
+        let t = self.time_to_expiry;
+        let v = self.volatility;
+
+        let mu = (self.risk_free_rate - self.dividend_yield - v * v / 2.) / (v * v);
+        let eta = if up { -1. } else { 1. };
+
+        let x2 = (S / H).ln() / (v * t.sqrt()) + mu * v * t.sqrt();
+        let y2 = (H / S).ln() / (v * t.sqrt()) + mu * v * t.sqrt();
+
+        let norm = Gaussian::default();
+
+        norm.cdf(eta * x2) - (H / S).powf(2. * mu) * norm.cdf(eta * y2)
+    }
+}
+
+impl BarrierType {
+    // Up (barrier above the underlying) or down.
+    const fn is_up(self) -> bool {
+        matches!(self, Self::CUI | Self::CUO | Self::PUI | Self::PUO)
+    }
+
+    // Knock-in or knock-out.
+    const fn is_knock_in(self) -> bool {
+        matches!(self, Self::CUI | Self::CDI | Self::PUI | Self::PDI)
+    }
+
+    // Call or put.
+    const fn option_type(self) -> TypeFlag {
+        match self {
+            Self::CUI | Self::CDI | Self::CUO | Self::CDO => TypeFlag::Call,
+            Self::PUI | Self::PDI | Self::PUO | Self::PDO => TypeFlag::Put,
+        }
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -316,4 +516,70 @@ mod tests {
     fn pdo_panic() {
         let _ = S_BELOW_H.price(BarrierType::PDO);
     }
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Discrete monitoring and numerical engines.
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+    const ALL_TYPES: [BarrierType; 8] = [
+        BarrierType::CUI,
+        BarrierType::CDI,
+        BarrierType::CUO,
+        BarrierType::CDO,
+        BarrierType::PUI,
+        BarrierType::PDI,
+        BarrierType::PUO,
+        BarrierType::PDO,
+    ];
+
+    // Barrier at 90 for down options and at 120 for up options, with a rebate.
+    fn with_rebate(type_flag: BarrierType) -> BarrierOption {
+        BarrierOption {
+            initial_price: 100.0,
+            strike_price: 100.0,
+            barrier: if type_flag.is_up() { 120.0 } else { 90.0 },
+            time_to_expiry: 1.0,
+            risk_free_rate: 0.05,
+            volatility: 0.2,
+            rebate: 3.0,
+            dividend_yield: 0.01,
+        }
+    }
+
+    #[test]
+    fn test_finite_difference() {
+        use crate::instruments::FiniteDifferenceScheme;
+
+        let pricer = FiniteDifferencePricer::new(FiniteDifferenceScheme::CrankNicolson, 400, 400);
+
+        for type_flag in ALL_TYPES {
+            let option = with_rebate(type_flag);
+
+            assert_approx_equal!(
+                option.price_finite_difference(type_flag, &pricer),
+                option.price(type_flag),
+                0.01
+            );
+        }
+    }
+
+    #[test]
+    fn test_discrete_monitoring() {
+        for type_flag in ALL_TYPES {
+            let option = with_rebate(type_flag);
+
+            // Continuous monitoring in the limit.
+            assert_approx_equal!(
+                option.price_discrete(type_flag, 1_000_000),
+                option.price(type_flag),
+                0.01
+            );
+
+            // Weekly monitoring: continuity correction against Monte Carlo.
+            let (mc, std_err) = option.price_monte_carlo(type_flag, 52, 10_000, 42);
+
+            assert!(std_err < 0.15);
+            assert_approx_equal!(option.price_discrete(type_flag, 52), mc, 4.0 * std_err);
+        }
+    }
 }
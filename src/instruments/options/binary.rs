@@ -9,7 +9,7 @@
 
 //! This module contains various 'binary', or 'digital', option types.
 
-use crate::statistics::distributions::{gaussian::Gaussian, Distribution};
+use crate::autodiff::Real;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
@@ -17,43 +17,92 @@ use crate::statistics::distributions::{gaussian::Gaussian, Distribution};
 
 /// Gap option parameters.
 #[derive(Debug, Clone, Copy)]
-pub struct GapOption {
+pub struct GapOption<R: Real = f64> {
     /// `S` - Initial price of the underlying.
-    pub initial_price: f64,
+    pub initial_price: R,
     /// `K_1` - First strike price (barrier strike).
-    pub strike_1: f64,
+    pub strike_1: R,
     /// `K_2` - Second strike price (payoff strike).
-    pub strike_2: f64,
+    pub strike_2: R,
     /// `r` - Risk-free rate parameter.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// `v` - Volatility parameter.
-    pub volatility: f64,
+    pub volatility: R,
     /// `b` - Cost-of-carry.
-    pub cost_of_carry: f64,
+    pub cost_of_carry: R,
     /// `T` - Time to expiry/maturity.
     pub time_to_maturity: f64,
 }
 
 /// Cash-or-Nothing option parameters.
 #[derive(Debug, Clone, Copy)]
-pub struct CashOrNothingOption {
+pub struct CashOrNothingOption<R: Real = f64> {
     /// `S` - Initial price of the underlying.
-    pub initial_price: f64,
+    pub initial_price: R,
     /// `X` - Strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// `K` - Cash payout amount.
-    pub payout_value: f64,
+    pub payout_value: R,
     /// `r` - Risk-free rate parameter.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// `v` - Volatility parameter.
-    pub volatility: f64,
+    pub volatility: R,
     /// `b` - Cost-of-carry.
-    pub cost_of_carry: f64,
+    pub cost_of_carry: R,
     /// `T` - Time to expiry/maturity.
     pub time_to_maturity: f64,
 }
 
-// pub struct AssetOrNothingOption {}
+/// Asset-or-Nothing option parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct AssetOrNothingOption<R: Real = f64> {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: R,
+    /// `X` - Strike price.
+    pub strike_price: R,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: R,
+    /// `v` - Volatility parameter.
+    pub volatility: R,
+    /// `b` - Cost-of-carry.
+    pub cost_of_carry: R,
+    /// `T` - Time to expiry/maturity.
+    pub time_to_maturity: f64,
+}
+
+/// How the digital payoff is valued and risk-managed.
+#[derive(Debug, Clone, Copy)]
+pub enum DigitalMode {
+    /// Closed-form digital, with the discontinuous payoff at the strike.
+    Exact,
+    /// Replicate the digital with a tight vanilla spread of the given width,
+    /// placed on the side of the strike where the spread dominates the
+    /// digital payoff (overhedge). The Greeks stay bounded close to expiry.
+    CallSpread {
+        /// Width of the spread, in units of the underlying.
+        width: f64,
+    },
+}
+
+/// Price and Greeks of a digital option, as `(call, put)` tuples.
+#[derive(Debug, Clone, Copy)]
+pub struct DigitalGreeks<R: Real = f64> {
+    /// Option price.
+    pub price: (R, R),
+    /// Sensitivity to the underlying price.
+    pub delta: (R, R),
+    /// Second order sensitivity to the underlying price.
+    pub gamma: (R, R),
+    /// Sensitivity to the volatility.
+    pub vega: (R, R),
+    /// Sensitivity to the passage of time (minus the derivative with respect to `T`).
+    pub theta: (R, R),
+    /// Sensitivity to the risk-free rate, holding the cost of carry fixed.
+    pub rho: (R, R),
+    /// Sensitivity to the cost of carry.
+    pub carry_rho: (R, R),
+}
+
 // pub struct SupershareOption {}
 // pub struct BinaryBarrierOption {}
 
@@ -61,12 +110,12 @@ pub struct CashOrNothingOption {
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl GapOption {
+impl<R: Real> GapOption<R> {
     /// Gap option pricer.
     /// The payoff from a call is $0$ if $S < K_1$ and $S — K_2$ if $S > K_1$.
     /// Similarly, the payoff from a put is $0$ if $S > K_1$ and $K_2 — S$ if $S < K_1$.
     #[must_use]
-    pub fn price(&self) -> (f64, f64) {
+    pub fn price(&self) -> (R, R) {
         let S = self.initial_price;
         let K_1 = self.strike_1;
         let K_2 = self.strike_2;
@@ -75,24 +124,23 @@ impl GapOption {
         let v = self.volatility;
         let b = self.cost_of_carry;
 
-        let d1 = ((S / K_1).ln() + (b + 0.5 * v * v) * T) / (v * (T).sqrt());
+        let d1 = ((S / K_1).ln() + (b + v * v * 0.5) * T) / (v * (T).sqrt());
         let d2 = d1 - v * (T).sqrt();
 
-        let N = Gaussian::default();
-
-        let c = S * ((b - r) * T).exp() * N.cdf(d1) - K_2 * (-r * T).exp() * N.cdf(d2);
-        let p = -S * ((b - r) * T).exp() * N.cdf(-d1) + K_2 * (-r * T).exp() * N.cdf(-d2);
+        let c = S * ((b - r) * T).exp() * d1.norm_cdf() - K_2 * (-r * T).exp() * d2.norm_cdf();
+        let p =
+            -S * ((b - r) * T).exp() * (-d1).norm_cdf() + K_2 * (-r * T).exp() * (-d2).norm_cdf();
 
         (c, p)
     }
 }
 
-impl CashOrNothingOption {
+impl<R: Real> CashOrNothingOption<R> {
     /// Cah-or-Nothing option pricer.
     /// The payoff from a call is 0 if S < X and K if S > X.
     /// The payoff from a put is 0 if S > X and K if S < X.
     #[must_use]
-    pub fn price(&self) -> (f64, f64) {
+    pub fn price(&self) -> (R, R) {
         let S = self.initial_price;
         let X = self.strike_price;
         let K = self.payout_value;
@@ -101,15 +149,343 @@ impl CashOrNothingOption {
         let v = self.volatility;
         let b = self.cost_of_carry;
 
-        let d = ((S / X).ln() + (b - 0.5 * v * v) * T) / (v * (T).sqrt());
+        let d = ((S / X).ln() + (b - v * v * 0.5) * T) / (v * (T).sqrt());
 
-        let N = Gaussian::default();
-
-        let c = K * (-r * T).exp() * N.cdf(d);
-        let p = K * (-r * T).exp() * N.cdf(-d);
+        let c = K * (-r * T).exp() * d.norm_cdf();
+        let p = K * (-r * T).exp() * (-d).norm_cdf();
 
         (c, p)
     }
+
+    /// Delta of the Cash-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn delta(&self) -> (R, R) {
+        let (S, v, T) = (self.initial_price, self.volatility, self.time_to_maturity);
+        let delta = self.discounted_payout() * self.d().norm_pdf() / (S * v * T.sqrt());
+
+        (delta, -delta)
+    }
+
+    /// Gamma of the Cash-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn gamma(&self) -> (R, R) {
+        let (S, v, T) = (self.initial_price, self.volatility, self.time_to_maturity);
+        let d = self.d();
+        let d1 = d + v * T.sqrt();
+        let gamma = -self.discounted_payout() * d.norm_pdf() * d1 / (S * S * v * v * T);
+
+        (gamma, -gamma)
+    }
+
+    /// Vega of the Cash-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn vega(&self) -> (R, R) {
+        let (v, T) = (self.volatility, self.time_to_maturity);
+        let d = self.d();
+        let d1 = d + v * T.sqrt();
+        let vega = -self.discounted_payout() * d.norm_pdf() * d1 / v;
+
+        (vega, -vega)
+    }
+
+    /// Theta of the Cash-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn theta(&self) -> (R, R) {
+        let (r, v, b, T) = (
+            self.risk_free_rate,
+            self.volatility,
+            self.cost_of_carry,
+            self.time_to_maturity,
+        );
+        let (call, put) = self.price();
+        let d = self.d();
+
+        // Derivative of `d` with respect to `T`.
+        let dd = ((b - v * v * 0.5) / v * (T.sqrt() * 2.0) - d) / (2.0 * T);
+        let decay = self.discounted_payout() * d.norm_pdf() * dd;
+
+        (r * call - decay, r * put + decay)
+    }
+
+    /// Rho of the Cash-or-Nothing option: `(call, put)`.
+    /// The cost of carry is held fixed, see [`Self::carry_rho`].
+    #[must_use]
+    pub fn rho(&self) -> (R, R) {
+        let T = self.time_to_maturity;
+        let (call, put) = self.price();
+
+        (call * -T, put * -T)
+    }
+
+    /// Sensitivity of the Cash-or-Nothing option to the cost of carry: `(call, put)`.
+    #[must_use]
+    pub fn carry_rho(&self) -> (R, R) {
+        let (v, T) = (self.volatility, self.time_to_maturity);
+        let carry = self.discounted_payout() * self.d().norm_pdf() * T.sqrt() / v;
+
+        (carry, -carry)
+    }
+
+    /// Price and Greeks of the Cash-or-Nothing option.
+    ///
+    /// With [`DigitalMode::CallSpread`] the call is valued as `K / h` vanilla
+    /// call spreads struck at `X - h` and `X`, and the put as `K / h` put
+    /// spreads struck at `X` and `X + h`, where `h` is the spread width.
+    #[must_use]
+    pub fn greeks(&self, mode: DigitalMode) -> DigitalGreeks<R> {
+        match mode {
+            DigitalMode::Exact => DigitalGreeks {
+                price: self.price(),
+                delta: self.delta(),
+                gamma: self.gamma(),
+                vega: self.vega(),
+                theta: self.theta(),
+                rho: self.rho(),
+                carry_rho: self.carry_rho(),
+            },
+            DigitalMode::CallSpread { width } => {
+                let X = self.strike_price;
+                let vanilla = |strike: R| {
+                    vanilla_greeks(
+                        self.initial_price,
+                        strike,
+                        self.risk_free_rate,
+                        self.volatility,
+                        self.cost_of_carry,
+                        self.time_to_maturity,
+                    )
+                };
+                let (lower, middle, upper) = (vanilla(X - width), vanilla(X), vanilla(X + width));
+                let w = self.payout_value / width;
+
+                DigitalGreeks::from_fields(|field| {
+                    (
+                        (field(&lower).0 - field(&middle).0) * w,
+                        (field(&upper).1 - field(&middle).1) * w,
+                    )
+                })
+            }
+        }
+    }
+
+    // Discounted cash payout, `K exp(-rT)`.
+    fn discounted_payout(&self) -> R {
+        self.payout_value * (-self.risk_free_rate * self.time_to_maturity).exp()
+    }
+
+    // Compute `d`, the `d2` of Black-Scholes.
+    fn d(&self) -> R {
+        let (S, X, v, b, T) = (
+            self.initial_price,
+            self.strike_price,
+            self.volatility,
+            self.cost_of_carry,
+            self.time_to_maturity,
+        );
+
+        ((S / X).ln() + (b - v * v * 0.5) * T) / (v * T.sqrt())
+    }
+}
+
+impl<R: Real> AssetOrNothingOption<R> {
+    /// Asset-or-Nothing option pricer.
+    /// The payoff from a call is 0 if S < X and S if S > X.
+    /// The payoff from a put is 0 if S > X and S if S < X.
+    #[must_use]
+    pub fn price(&self) -> (R, R) {
+        let d1 = self.d1();
+        let forward = self.discounted_asset();
+
+        (forward * d1.norm_cdf(), forward * (-d1).norm_cdf())
+    }
+
+    /// Delta of the Asset-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn delta(&self) -> (R, R) {
+        let (S, v, T) = (self.initial_price, self.volatility, self.time_to_maturity);
+        let d1 = self.d1();
+        let carry = self.discounted_asset() / S;
+        let jump = d1.norm_pdf() / (v * T.sqrt());
+
+        (
+            carry * (d1.norm_cdf() + jump),
+            carry * ((-d1).norm_cdf() - jump),
+        )
+    }
+
+    /// Gamma of the Asset-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn gamma(&self) -> (R, R) {
+        let (S, v, T) = (self.initial_price, self.volatility, self.time_to_maturity);
+        let d1 = self.d1();
+        let d2 = d1 - v * T.sqrt();
+        let gamma = -self.discounted_asset() * d1.norm_pdf() * d2 / (S * S * v * v * T);
+
+        (gamma, -gamma)
+    }
+
+    /// Vega of the Asset-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn vega(&self) -> (R, R) {
+        let (v, T) = (self.volatility, self.time_to_maturity);
+        let d1 = self.d1();
+        let d2 = d1 - v * T.sqrt();
+        let vega = -self.discounted_asset() * d1.norm_pdf() * d2 / v;
+
+        (vega, -vega)
+    }
+
+    /// Theta of the Asset-or-Nothing option: `(call, put)`.
+    #[must_use]
+    pub fn theta(&self) -> (R, R) {
+        let (r, v, b, T) = (
+            self.risk_free_rate,
+            self.volatility,
+            self.cost_of_carry,
+            self.time_to_maturity,
+        );
+        let (call, put) = self.price();
+        let d1 = self.d1();
+
+        // Derivative of `d1` with respect to `T`.
+        let dd1 = ((b + v * v * 0.5) / v * (T.sqrt() * 2.0) - d1) / (2.0 * T);
+        let decay = self.discounted_asset() * d1.norm_pdf() * dd1;
+
+        ((r - b) * call - decay, (r - b) * put + decay)
+    }
+
+    /// Rho of the Asset-or-Nothing option: `(call, put)`.
+    /// The cost of carry is held fixed, see [`Self::carry_rho`].
+    #[must_use]
+    pub fn rho(&self) -> (R, R) {
+        let T = self.time_to_maturity;
+        let (call, put) = self.price();
+
+        (call * -T, put * -T)
+    }
+
+    /// Sensitivity of the Asset-or-Nothing option to the cost of carry: `(call, put)`.
+    #[must_use]
+    pub fn carry_rho(&self) -> (R, R) {
+        let (v, T) = (self.volatility, self.time_to_maturity);
+        let (call, put) = self.price();
+        let jump = self.discounted_asset() * self.d1().norm_pdf() * T.sqrt() / v;
+
+        (call * T + jump, put * T - jump)
+    }
+
+    /// Price and Greeks of the Asset-or-Nothing option.
+    ///
+    /// The call pays a vanilla call plus `X` cash-or-nothing calls, and the
+    /// put pays `X` cash-or-nothing puts minus a vanilla put. With
+    /// [`DigitalMode::CallSpread`] the cash digitals are overhedged with
+    /// vanilla spreads, as in [`CashOrNothingOption::greeks`].
+    #[must_use]
+    pub fn greeks(&self, mode: DigitalMode) -> DigitalGreeks<R> {
+        match mode {
+            DigitalMode::Exact => DigitalGreeks {
+                price: self.price(),
+                delta: self.delta(),
+                gamma: self.gamma(),
+                vega: self.vega(),
+                theta: self.theta(),
+                rho: self.rho(),
+                carry_rho: self.carry_rho(),
+            },
+            DigitalMode::CallSpread { width } => {
+                let X = self.strike_price;
+                let vanilla = |strike: R| {
+                    vanilla_greeks(
+                        self.initial_price,
+                        strike,
+                        self.risk_free_rate,
+                        self.volatility,
+                        self.cost_of_carry,
+                        self.time_to_maturity,
+                    )
+                };
+                let (lower, middle, upper) = (vanilla(X - width), vanilla(X), vanilla(X + width));
+                let w = X / width;
+
+                DigitalGreeks::from_fields(|field| {
+                    (
+                        field(&middle).0 + (field(&lower).0 - field(&middle).0) * w,
+                        (field(&upper).1 - field(&middle).1) * w - field(&middle).1,
+                    )
+                })
+            }
+        }
+    }
+
+    // Discounted forward price of the asset, `S exp((b - r)T)`.
+    fn discounted_asset(&self) -> R {
+        self.initial_price
+            * ((self.cost_of_carry - self.risk_free_rate) * self.time_to_maturity).exp()
+    }
+
+    // Compute `d1` of Black-Scholes.
+    fn d1(&self) -> R {
+        let (S, X, v, b, T) = (
+            self.initial_price,
+            self.strike_price,
+            self.volatility,
+            self.cost_of_carry,
+            self.time_to_maturity,
+        );
+
+        ((S / X).ln() + (b + v * v * 0.5) * T) / (v * T.sqrt())
+    }
+}
+
+impl<R: Real> DigitalGreeks<R> {
+    // Build the Greeks field by field, from a function of the field accessor.
+    fn from_fields<F>(f: F) -> Self
+    where
+        F: Fn(fn(&Self) -> (R, R)) -> (R, R),
+    {
+        Self {
+            price: f(|g| g.price),
+            delta: f(|g| g.delta),
+            gamma: f(|g| g.gamma),
+            vega: f(|g| g.vega),
+            theta: f(|g| g.theta),
+            rho: f(|g| g.rho),
+            carry_rho: f(|g| g.carry_rho),
+        }
+    }
+}
+
+// Price and Greeks of vanilla European options in the generalised
+// Black-Scholes-Merton model, used to replicate the digitals.
+fn vanilla_greeks<R: Real>(S: R, X: R, r: R, v: R, b: R, T: f64) -> DigitalGreeks<R> {
+    let d1 = ((S / X).ln() + (b + v * v * 0.5) * T) / (v * T.sqrt());
+    let d2 = d1 - v * T.sqrt();
+
+    let carry = ((b - r) * T).exp();
+    let df = (-r * T).exp();
+
+    let call = S * carry * d1.norm_cdf() - X * df * d2.norm_cdf();
+    let put = -S * carry * (-d1).norm_cdf() + X * df * (-d2).norm_cdf();
+
+    let gamma = carry * d1.norm_pdf() / (S * v * T.sqrt());
+    let vega = S * carry * d1.norm_pdf() * T.sqrt();
+    let decay = -S * carry * d1.norm_pdf() * v / (2.0 * T.sqrt());
+
+    DigitalGreeks {
+        price: (call, put),
+        delta: (carry * d1.norm_cdf(), -carry * (-d1).norm_cdf()),
+        gamma: (gamma, gamma),
+        vega: (vega, vega),
+        theta: (
+            decay - (b - r) * S * carry * d1.norm_cdf() - r * X * df * d2.norm_cdf(),
+            decay + (b - r) * S * carry * (-d1).norm_cdf() + r * X * df * (-d2).norm_cdf(),
+        ),
+        rho: (call * -T, put * -T),
+        carry_rho: (
+            S * carry * d1.norm_cdf() * T,
+            -S * carry * (-d1).norm_cdf() * T,
+        ),
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -157,4 +533,151 @@ mod tests {
         // Value from Haug's book.
         assert_approx_equal!(prices.1, 2.671_045_684_461_347, EPS);
     }
+
+    #[test]
+    fn test_asset_or_nothing_option() {
+        let AON = AssetOrNothingOption {
+            initial_price: 70.0,
+            strike_price: 65.0,
+            risk_free_rate: 0.07,
+            volatility: 0.27,
+            time_to_maturity: 0.5,
+            cost_of_carry: 0.07 - 0.05,
+        };
+
+        let prices = AON.price();
+
+        // Value from Haug's book.
+        assert_approx_equal!(prices.1, 20.2069, 1e-4);
+
+        // Both legs together pay the asset.
+        let forward = 70.0 * (-0.05_f64 * 0.5).exp();
+        assert_approx_equal!(prices.0 + prices.1, forward, 1e-12);
+    }
+
+    #[test]
+    fn test_digital_greeks() {
+        use crate::autodiff::{Accumulate, Gradient, Graph};
+
+        const H: f64 = 1e-4;
+
+        let (S, X, K, r, v, b, T) = (100.0, 95.0, 10.0, 0.05, 0.25, 0.03, 0.75);
+
+        let cash = |S: f64, T: f64| CashOrNothingOption {
+            initial_price: S,
+            strike_price: X,
+            payout_value: K,
+            risk_free_rate: r,
+            volatility: v,
+            cost_of_carry: b,
+            time_to_maturity: T,
+        };
+        let asset = |S: f64, T: f64| AssetOrNothingOption {
+            initial_price: S,
+            strike_price: X,
+            risk_free_rate: r,
+            volatility: v,
+            cost_of_carry: b,
+            time_to_maturity: T,
+        };
+
+        let g = Graph::new();
+        let (S_var, r_var, v_var, b_var) = (g.var(S), g.var(r), g.var(v), g.var(b));
+
+        let cash_var = CashOrNothingOption {
+            initial_price: S_var,
+            strike_price: g.var(X),
+            payout_value: g.var(K),
+            risk_free_rate: r_var,
+            volatility: v_var,
+            cost_of_carry: b_var,
+            time_to_maturity: T,
+        };
+        let asset_var = AssetOrNothingOption {
+            initial_price: S_var,
+            strike_price: g.var(X),
+            risk_free_rate: r_var,
+            volatility: v_var,
+            cost_of_carry: b_var,
+            time_to_maturity: T,
+        };
+
+        let closed_form = [
+            cash(S, T).greeks(DigitalMode::Exact),
+            asset(S, T).greeks(DigitalMode::Exact),
+        ];
+        let adjoint = [cash_var.price(), asset_var.price()];
+        let bumped = |i: usize, S: f64, T: f64| match i {
+            0 => cash(S, T).greeks(DigitalMode::Exact),
+            _ => asset(S, T).greeks(DigitalMode::Exact),
+        };
+
+        for (i, (greeks, prices)) in closed_form.iter().zip(adjoint).enumerate() {
+            for (side, price) in [prices.0, prices.1].into_iter().enumerate() {
+                let pick = |x: (f64, f64)| if side == 0 { x.0 } else { x.1 };
+
+                let gradient = price.accumulate();
+
+                assert_approx_equal!(price.value, pick(greeks.price), 1e-12);
+                assert_approx_equal!(gradient.wrt(&S_var), pick(greeks.delta), 1e-10);
+                assert_approx_equal!(gradient.wrt(&v_var), pick(greeks.vega), 1e-10);
+                assert_approx_equal!(gradient.wrt(&r_var), pick(greeks.rho), 1e-10);
+                assert_approx_equal!(gradient.wrt(&b_var), pick(greeks.carry_rho), 1e-10);
+
+                // Gamma and theta against finite differences.
+                let up = pick(bumped(i, S + H, T).delta);
+                let down = pick(bumped(i, S - H, T).delta);
+                assert_approx_equal!((up - down) / (2.0 * H), pick(greeks.gamma), 1e-6);
+
+                let up = pick(bumped(i, S, T + H).price);
+                let down = pick(bumped(i, S, T - H).price);
+                assert_approx_equal!(-(up - down) / (2.0 * H), pick(greeks.theta), 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_digital_call_spread() {
+        let cash = CashOrNothingOption {
+            initial_price: 100.0,
+            strike_price: 100.0,
+            payout_value: 10.0,
+            risk_free_rate: 0.05,
+            volatility: 0.2,
+            cost_of_carry: 0.05,
+            time_to_maturity: 0.5,
+        };
+        let asset = AssetOrNothingOption {
+            initial_price: 100.0,
+            strike_price: 100.0,
+            risk_free_rate: 0.05,
+            volatility: 0.2,
+            cost_of_carry: 0.05,
+            time_to_maturity: 0.5,
+        };
+
+        for (exact, spread, wide) in [
+            (
+                cash.greeks(DigitalMode::Exact),
+                cash.greeks(DigitalMode::CallSpread { width: 0.001 }),
+                cash.greeks(DigitalMode::CallSpread { width: 2.0 }),
+            ),
+            (
+                asset.greeks(DigitalMode::Exact),
+                asset.greeks(DigitalMode::CallSpread { width: 0.001 }),
+                asset.greeks(DigitalMode::CallSpread { width: 2.0 }),
+            ),
+        ] {
+            // A tight spread converges to the digital.
+            assert_approx_equal!(spread.price.0, exact.price.0, 1e-2);
+            assert_approx_equal!(spread.price.1, exact.price.1, 1e-2);
+            assert_approx_equal!(spread.delta.0, exact.delta.0, 1e-2);
+            assert_approx_equal!(spread.delta.1, exact.delta.1, 1e-2);
+            assert_approx_equal!(spread.vega.0, exact.vega.0, 1e-2);
+            assert_approx_equal!(spread.theta.1, exact.theta.1, 1e-2);
+
+            // The overhedge is worth more than the digital it replicates.
+            assert!(wide.price.0 > exact.price.0 && wide.price.1 > exact.price.1);
+        }
+    }
 }
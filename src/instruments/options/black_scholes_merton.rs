@@ -25,9 +25,9 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::instruments::options::TypeFlag;
+use crate::autodiff::Real;
+use crate::instruments::options::{generalised_greeks, OptionGreeks, TypeFlag};
 use crate::instruments::Instrument;
-use crate::statistics::distributions::{Distribution, Gaussian};
 use crate::time::{DayCountConvention, DayCounter};
 
 use time::OffsetDateTime;
@@ -37,7 +37,7 @@ use time::OffsetDateTime;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Generalised Black-Scholes-Merton European Option pricing model.
-pub struct BlackScholesMerton {
+pub struct BlackScholesMerton<R: Real = f64> {
     /// The cost of carry factor.
     /// For the generalised Black-Scholes-Merton model there are five options:
     /// - b = r
@@ -50,15 +50,15 @@ pub struct BlackScholesMerton {
     ///     - Asay 1982 margined futures option model.
     /// - b = r_d - r_f
     ///     - Garman and Kohlhagen 1983 currency option model.
-    pub cost_of_carry: f64,
+    pub cost_of_carry: R,
     /// S - The underlying asset price.
-    pub underlying_price: f64,
+    pub underlying_price: R,
     /// K - The options strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// sigma - The underlying asset's volatility.
-    pub volatility: f64,
+    pub volatility: R,
     /// r - The risk-free interest rate.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
 
     /// Evaluation date (optional, defaults to today t = 0).
     pub evaluation_date: Option<OffsetDateTime>,
@@ -96,16 +96,16 @@ impl Instrument for BlackScholesMerton {
     }
 }
 
-impl BlackScholesMerton {
+impl<R: Real> BlackScholesMerton<R> {
     /// New European Option
     #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn new(
-        cost_of_carry: f64,
-        underlying_price: f64,
-        strike_price: f64,
-        volatility: f64,
-        risk_free_rate: f64,
+        cost_of_carry: R,
+        underlying_price: R,
+        strike_price: R,
+        volatility: R,
+        risk_free_rate: R,
         evaluation_date: Option<OffsetDateTime>,
         expiration_date: OffsetDateTime,
         option_type: TypeFlag,
@@ -124,16 +124,17 @@ impl BlackScholesMerton {
 
     /// Generalised Black-Scholes European Option Price.
     #[must_use]
-    pub fn price(&self) -> f64 {
+    pub fn price(&self) -> R {
         let (S, K, _, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
 
         match self.option_type {
-            TypeFlag::Call => S * ((b - r) * T).exp() * n.cdf(d1) - K * (-r * T).exp() * n.cdf(d2),
+            TypeFlag::Call => {
+                S * ((b - r) * T).exp() * d1.norm_cdf() - K * (-r * T).exp() * d2.norm_cdf()
+            }
             TypeFlag::Put => {
-                -S * ((b - r) * T).exp() * n.cdf(-d1) + K * (-r * T).exp() * n.cdf(-d2)
+                -S * ((b - r) * T).exp() * (-d1).norm_cdf() + K * (-r * T).exp() * (-d2).norm_cdf()
             }
         }
     }
@@ -150,13 +151,13 @@ impl BlackScholesMerton {
 
     // Compute d1 and d2.
     #[must_use]
-    fn d1_d2(&self) -> (f64, f64) {
+    fn d1_d2(&self) -> (R, R) {
         let (S, K, v, _, b) = self.unpack();
 
         // Compute time to maturity.
         let T = self.year_fraction();
 
-        let d1 = (1.0 / (v * T.sqrt())) * ((S / K).ln() + (b + 0.5 * v.powi(2)) * T);
+        let d1 = (v * T.sqrt()).recip() * ((S / K).ln() + (b + v.powi(2) * 0.5) * T);
         let d2 = d1 - v * T.sqrt();
 
         (d1, d2)
@@ -164,7 +165,7 @@ impl BlackScholesMerton {
 
     // Unpack struct to get option parameters.
     #[must_use]
-    fn unpack(&self) -> (f64, f64, f64, f64, f64) {
+    fn unpack(&self) -> (R, R, R, R, R) {
         (
             self.underlying_price,
             self.strike_price,
@@ -176,47 +177,43 @@ impl BlackScholesMerton {
 
     /// Delta of generalised Black-Scholes European Option.
     #[must_use]
-    pub fn delta(&self) -> f64 {
+    pub fn delta(&self) -> R {
         let (_, _, _, r, b) = self.unpack();
         let T = self.year_fraction();
         let d1 = self.d1_d2().0;
-        let n = Gaussian::default();
-
         match self.option_type {
-            TypeFlag::Call => ((b - r) * T).exp() * n.cdf(d1),
-            TypeFlag::Put => ((b - r) * T).exp() * (n.cdf(d1) - 1.0),
+            TypeFlag::Call => ((b - r) * T).exp() * d1.norm_cdf(),
+            TypeFlag::Put => ((b - r) * T).exp() * (d1.norm_cdf() - 1.0),
         }
     }
 
     /// Vanna of generalised Black-Scholes European Option.
     /// Also known as DdeltaDvol.
     #[must_use]
-    pub fn vanna(&self) -> f64 {
+    pub fn vanna(&self) -> R {
         let (_, _, v, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
-
-        -((b - r) * T).exp() * n.pdf(d1) * d2 / v
+        -((b - r) * T).exp() * d1.norm_pdf() * d2 / v
     }
 
     /// Charm of generalised Black-Scholes European Option.
     /// Also known as DdeltaDtime, delta decay or delta bleed.
     #[must_use]
-    pub fn charm(&self) -> f64 {
+    pub fn charm(&self) -> R {
         let (_, _, v, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
-
         match self.option_type {
             TypeFlag::Call => {
                 ((b - r) * T).exp()
-                    * (n.pdf(d1) * ((b / (v * T.sqrt())) - (d2 / (2.0 * T))) + (b - r) * n.cdf(d1))
+                    * (d1.norm_pdf() * ((b / (v * T.sqrt())) - (d2 / (2.0 * T)))
+                        + (b - r) * d1.norm_cdf())
             }
             TypeFlag::Put => {
                 ((b - r) * T).exp()
-                    * (n.pdf(d1) * ((b / (v * T.sqrt())) - (d2 / (2.0 * T))) - (b - r) * n.cdf(-d1))
+                    * (d1.norm_pdf() * ((b / (v * T.sqrt())) - (d2 / (2.0 * T)))
+                        - (b - r) * (-d1).norm_cdf())
             }
         }
     }
@@ -224,85 +221,82 @@ impl BlackScholesMerton {
     /// Lambda of generalised Black-Scholes European Option.
     /// Also known as elasticity or leverage.
     #[must_use]
-    pub fn lambda(&self) -> f64 {
+    pub fn lambda(&self) -> R {
         self.delta() * self.underlying_price / self.price()
     }
 
     /// Gamma of generalised Black-Scholes European Option.
     /// Also known as convexity.
     #[must_use]
-    pub fn gamma(&self) -> f64 {
-        let n = Gaussian::default();
+    pub fn gamma(&self) -> R {
         let (S, _, v, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, _) = self.d1_d2();
 
-        ((b - r) * T).exp() * n.pdf(d1) / (S * v * T.sqrt())
+        ((b - r) * T).exp() * d1.norm_pdf() / (S * v * T.sqrt())
     }
 
     /// Gamma percent of generalised Black-Scholes European Option.
     #[must_use]
-    pub fn gamma_percent(&self) -> f64 {
+    pub fn gamma_percent(&self) -> R {
         self.gamma() * self.underlying_price / 100.0
     }
 
     /// Zomma of generalised Black-Scholes European Option.
     /// Also known as DgammaDvol.
     #[must_use]
-    pub fn zomma(&self) -> f64 {
+    pub fn zomma(&self) -> R {
         let (d1, d2) = self.d1_d2();
         self.gamma() * ((d1 * d2 - 1.0) / self.volatility)
     }
 
     /// Zomma percent of generalised Black-Scholes European Option.
     #[must_use]
-    pub fn zomma_percent(&self) -> f64 {
+    pub fn zomma_percent(&self) -> R {
         self.zomma() * self.underlying_price / 100.0
     }
 
     /// Speed of generalised Black-Scholes European Option.
     /// Also known as DgammaDspot.
     #[must_use]
-    pub fn speed(&self) -> f64 {
+    pub fn speed(&self) -> R {
         let (S, _, v, _, _) = self.unpack();
         let T = self.year_fraction();
         let (d1, _) = self.d1_d2();
 
         let gamma = self.gamma();
 
-        -gamma * (1.0 + d1 / (v * T.sqrt())) / S
+        -gamma * (d1 / (v * T.sqrt()) + 1.0) / S
     }
 
     /// Colour of generalised Black-Scholes European Option.
     /// Also known as DgammaDtime.
     #[must_use]
-    pub fn colour(&self) -> f64 {
+    pub fn colour(&self) -> R {
         let (_, _, v, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, d2) = self.d1_d2();
 
         let gamma = self.gamma();
 
-        gamma * (r - b + b * d1 / (v * T.sqrt()) + (1.0 - d1 * d2) / (2.0 * T))
+        gamma * (r - b + b * d1 / (v * T.sqrt()) + (-(d1 * d2) + 1.0) / (2.0 * T))
     }
 
     /// Vega of generalised Black-Scholes European Option.
     /// Also known as zeta.
     #[must_use]
-    pub fn vega(&self) -> f64 {
+    pub fn vega(&self) -> R {
         let (S, _, _, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, _) = self.d1_d2();
 
-        let n = Gaussian::default();
-
-        S * ((b - r) * T).exp() * n.pdf(d1) * T.sqrt()
+        S * ((b - r) * T).exp() * d1.norm_pdf() * T.sqrt()
     }
 
     /// Vomma of generalised Black-Scholes European Option.
     /// Also known as DvegaDvol.
     #[must_use]
-    pub fn vomma(&self) -> f64 {
+    pub fn vomma(&self) -> R {
         let (d1, d2) = self.d1_d2();
 
         self.vega() * d1 * d2 / self.volatility
@@ -311,7 +305,7 @@ impl BlackScholesMerton {
     /// Ultima of generalised Black-Scholes European Option.
     /// Also known as DvommaDvol.
     #[must_use]
-    pub fn ultima(&self) -> f64 {
+    pub fn ultima(&self) -> R {
         let (d1, d2) = self.d1_d2();
 
         (self.vomma() / self.volatility) * (d1 * d2 - d1 / d2 + d2 / d1 - 1.0)
@@ -320,7 +314,7 @@ impl BlackScholesMerton {
     /// Vega Bleed of the generalised Black-Scholes European option.
     /// Also known as DvegaDtime.
     #[must_use]
-    pub fn vega_bleed(&self) -> f64 {
+    pub fn vega_bleed(&self) -> R {
         let (_, _, v, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, d2) = self.d1_d2();
@@ -331,44 +325,39 @@ impl BlackScholesMerton {
     /// Theta of the generalised Black-Scholes European option.
     /// Also known as Expected Bleed.
     #[must_use]
-    pub fn theta(&self) -> f64 {
+    pub fn theta(&self) -> R {
         let (S, K, v, r, b) = self.unpack();
         let T = self.year_fraction();
         let (d1, d2) = self.d1_d2();
 
-        let n = Gaussian::default();
-
         match self.option_type {
             TypeFlag::Call => {
-                -S * ((b - r) * T).exp() * n.pdf(d1) * v / (2.0 * T.sqrt())
-                    - (b - r) * S * ((b - r) * T).exp() * n.cdf(d1)
-                    - r * K * (-r * T).exp() * n.cdf(d2)
+                -S * ((b - r) * T).exp() * d1.norm_pdf() * v / (2.0 * T.sqrt())
+                    - (b - r) * S * ((b - r) * T).exp() * d1.norm_cdf()
+                    - r * K * (-r * T).exp() * d2.norm_cdf()
             }
             TypeFlag::Put => {
-                -S * ((b - r) * T).exp() * n.pdf(d1) * v / (2.0 * T.sqrt())
-                    + (b - r) * S * ((b - r) * T).exp() * n.cdf(-d1)
-                    + r * K * (-r * T).exp() * n.cdf(-d2)
+                -S * ((b - r) * T).exp() * d1.norm_pdf() * v / (2.0 * T.sqrt())
+                    + (b - r) * S * ((b - r) * T).exp() * (-d1).norm_cdf()
+                    + r * K * (-r * T).exp() * (-d2).norm_cdf()
             }
         }
     }
 
     /// Rho of the generalised Black-Scholes European option.
     #[must_use]
-    pub fn rho(&self) -> f64 {
+    pub fn rho(&self) -> R {
         let T = self.year_fraction();
 
         match self.option_type {
             TypeFlag::Call => {
-                self.strike_price
-                    * T
-                    * (-self.risk_free_rate * T).exp()
-                    * Gaussian::default().cdf(self.d1_d2().1)
+                self.strike_price * T * (-self.risk_free_rate * T).exp() * self.d1_d2().1.norm_cdf()
             }
             TypeFlag::Put => {
                 -self.strike_price
                     * T
                     * (-self.risk_free_rate * T).exp()
-                    * Gaussian::default().cdf(-self.d1_d2().1)
+                    * (-self.d1_d2().1).norm_cdf()
             }
         }
     }
@@ -376,55 +365,69 @@ impl BlackScholesMerton {
     /// Phi of the generalised Black-Scholes European option.
     /// Also known as Rho-2.
     #[must_use]
-    pub fn phi(&self) -> f64 {
+    pub fn phi(&self) -> R {
         let (S, _, _, r, b) = self.unpack();
         let T = self.year_fraction();
 
         let (d1, _) = self.d1_d2();
 
         match self.option_type {
-            TypeFlag::Call => -T * S * ((b - r) * T).exp() * Gaussian::default().cdf(d1),
-            TypeFlag::Put => T * S * ((b - r) * T).exp() * Gaussian::default().cdf(-d1),
+            TypeFlag::Call => S * -T * ((b - r) * T).exp() * d1.norm_cdf(),
+            TypeFlag::Put => S * T * ((b - r) * T).exp() * (-d1).norm_cdf(),
         }
     }
 
     /// Zeta of the generalised Black-Scholes European option.
     /// Also known as the in-the-money probability.
     #[must_use]
-    pub fn zeta(&self) -> f64 {
-        let n = Gaussian::default();
-
+    pub fn zeta(&self) -> R {
         match self.option_type {
-            TypeFlag::Call => n.cdf(self.d1_d2().1),
-            TypeFlag::Put => n.cdf(-self.d1_d2().1),
+            TypeFlag::Call => self.d1_d2().1.norm_cdf(),
+            TypeFlag::Put => (-self.d1_d2().1).norm_cdf(),
         }
     }
 
     /// Strike Delta of the generalised Black-Scholes European option.
     /// Also known as Dual Delta or Discounted Probability.
     #[must_use]
-    pub fn strike_delta(&self) -> f64 {
-        let n = Gaussian::default();
-
+    pub fn strike_delta(&self) -> R {
         let T = self.year_fraction();
 
         match self.option_type {
-            TypeFlag::Call => -(-self.risk_free_rate * T).exp() * n.cdf(self.d1_d2().1),
-            TypeFlag::Put => (-self.risk_free_rate * T).exp() * n.cdf(-self.d1_d2().1),
+            TypeFlag::Call => -(-self.risk_free_rate * T).exp() * self.d1_d2().1.norm_cdf(),
+            TypeFlag::Put => (-self.risk_free_rate * T).exp() * (-self.d1_d2().1).norm_cdf(),
         }
     }
 
     /// Strike Gamma of the generalised Black-Scholes European option.
     #[must_use]
-    pub fn strike_gamma(&self) -> f64 {
-        let n = Gaussian::default();
+    pub fn strike_gamma(&self) -> R {
         let T = self.year_fraction();
 
-        n.pdf(self.d1_d2().1) * (-self.risk_free_rate * T).exp()
+        self.d1_d2().1.norm_pdf() * (-self.risk_free_rate * T).exp()
             / (self.strike_price * self.volatility * T.sqrt())
     }
 }
 
+impl BlackScholesMerton {
+    /// Price and first and second order Greeks of the option.
+    ///
+    /// Theta and charm are with respect to calendar time, and rho moves the
+    /// cost of carry with the rate, as in [`BlackScholesMerton::rho`].
+    #[must_use]
+    pub fn greeks(&self) -> OptionGreeks {
+        generalised_greeks(
+            self.underlying_price,
+            self.strike_price,
+            self.year_fraction(),
+            self.risk_free_rate,
+            self.cost_of_carry,
+            self.volatility,
+            self.option_type,
+        )
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -467,4 +470,59 @@ mod tests_black_scholes_merton {
         );
         assert_approx_equal!(bsm.price(), 2.452_415_221_397_277_6, EPS);
     }
+
+    #[test]
+    fn adjoint_greeks() {
+        use crate::autodiff::{Accumulate, Gradient, Graph};
+
+        let expiry = OffsetDateTime::now_utc() + Duration::days(182);
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let bsm =
+                BlackScholesMerton::new(0.03, 100.0, 95.0, 0.2, 0.05, None, expiry, option_type);
+
+            // Same pricer, evaluated with reverse mode variables.
+            let g = Graph::new();
+            let (b, S, K, v, r) = (
+                g.var(0.03),
+                g.var(100.0),
+                g.var(95.0),
+                g.var(0.2),
+                g.var(0.05),
+            );
+
+            let price = BlackScholesMerton::new(b, S, K, v, r, None, expiry, option_type).price();
+            let gradient = price.accumulate();
+
+            assert_approx_equal!(price.value, bsm.price(), 1e-12);
+            assert_approx_equal!(gradient.wrt(&S), bsm.delta(), 1e-10);
+            assert_approx_equal!(gradient.wrt(&v), bsm.vega(), 1e-10);
+            assert_approx_equal!(gradient.wrt(&b), -bsm.phi(), 1e-10);
+            // `rho` assumes the cost of carry moves with the rate (b = r).
+            assert_approx_equal!(gradient.wrt(&r) + gradient.wrt(&b), bsm.rho(), 1e-10);
+            assert_approx_equal!(gradient.wrt(&K), bsm.strike_delta(), 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_greeks() {
+        let expiry = OffsetDateTime::now_utc() + Duration::days(182);
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let bsm =
+                BlackScholesMerton::new(0.03, 100.0, 95.0, 0.2, 0.05, None, expiry, option_type);
+            let greeks = bsm.greeks();
+
+            assert_approx_equal!(greeks.price, bsm.price(), 1e-12);
+            assert_approx_equal!(greeks.delta, bsm.delta(), 1e-12);
+            assert_approx_equal!(greeks.gamma, bsm.gamma(), 1e-12);
+            assert_approx_equal!(greeks.vega, bsm.vega(), 1e-12);
+            assert_approx_equal!(greeks.theta, bsm.theta(), 1e-12);
+            assert_approx_equal!(greeks.rho, bsm.rho(), 1e-12);
+            assert_approx_equal!(greeks.vanna.unwrap(), bsm.vanna(), 1e-12);
+            assert_approx_equal!(greeks.volga.unwrap(), bsm.vomma(), 1e-12);
+            // `charm` is the derivative of delta with respect to expiry.
+            assert_approx_equal!(greeks.charm.unwrap(), -bsm.charm(), 1e-12);
+        }
+    }
 }
@@ -7,14 +7,271 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! Chooser options: the holder chooses at time `t` whether the option is a
+//! call or a put.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{generalised_greeks, TypeFlag};
+use crate::math::{Newton, RootFinder, RootFindingError};
+use crate::statistics::distributions::{bivariate_normal_cdf, Distribution, Gaussian};
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+/// Simple chooser option (Rubinstein, 1991): at the choice time the holder
+/// picks a call or a put, with the same strike and expiry.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Haug (2007).
+/// let option = ChooserOption::new(50.0, 50.0, 0.25, 0.5, 0.08, 0.0, 0.25);
+///
+/// assert!((option.price() - 6.1071).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ChooserOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `t` - Time to the choice (in years).
+    pub choice_time: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+}
+
+/// Complex chooser option (Rubinstein, 1991): at the choice time the holder
+/// picks a call or a put, with different strikes and expiries.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Haug (2007).
+/// let option = ComplexChooserOption::new(50.0, 55.0, 48.0, 0.25, 0.5, 0.5833, 0.1, 0.05, 0.35);
+///
+/// assert!((option.price().unwrap() - 6.0508).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ComplexChooserOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K_c` - Strike price of the call.
+    pub call_strike: f64,
+    /// `K_p` - Strike price of the put.
+    pub put_strike: f64,
+    /// `t` - Time to the choice (in years).
+    pub choice_time: f64,
+    /// `T_c` - Time to expiry of the call (in years).
+    pub call_expiry: f64,
+    /// `T_p` - Time to expiry of the put (in years).
+    pub put_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl ChooserOption {
+    /// New simple chooser option.
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        choice_time: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            choice_time,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+        }
+    }
+
+    /// Price of the chooser: by put-call parity at the choice time, a call
+    /// expiring at `T` plus a put struck at `K exp(-(r - q)(T - t))`
+    /// expiring at `t`.
+    #[must_use]
+    pub fn price(&self) -> f64 {
+        let (S, K, v) = (self.initial_price, self.strike_price, self.volatility);
+        let (t, T) = (self.choice_time, self.time_to_expiry);
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+        let N = |x: f64| Gaussian::default().cdf(x);
+
+        let d = ((S / K).ln() + (b + 0.5 * v * v) * T) / (v * T.sqrt());
+        let y = ((S / K).ln() + b * T + 0.5 * v * v * t) / (v * t.sqrt());
+
+        let forward = S * ((b - r) * T).exp();
+        let strike = K * (-r * T).exp();
+
+        forward * N(d) - strike * N(d - v * T.sqrt()) - forward * N(-y)
+            + strike * N(-y + v * t.sqrt())
+    }
+}
+
+impl ComplexChooserOption {
+    /// New complex chooser option.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        call_strike: f64,
+        put_strike: f64,
+        choice_time: f64,
+        call_expiry: f64,
+        put_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            call_strike,
+            put_strike,
+            choice_time,
+            call_expiry,
+            put_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+        }
+    }
+
+    /// Critical price `I` of the underlying at the choice time, at which
+    /// the call and the put are worth the same.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if Newton's method does not converge.
+    pub fn critical_price(&self) -> Result<f64, RootFindingError> {
+        let (r, v) = (self.risk_free_rate, self.volatility);
+        let b = r - self.dividend_yield;
+        let t = self.choice_time;
+        let (Tc, Tp) = (self.call_expiry - t, self.put_expiry - t);
+
+        let f = |s: f64| {
+            let call = generalised_greeks(s, self.call_strike, Tc, r, b, v, TypeFlag::Call);
+            let put = generalised_greeks(s, self.put_strike, Tp, r, b, v, TypeFlag::Put);
+
+            call.price - put.price
+        };
+
+        // The call is worth more than the put where its lower bound
+        // `S exp((b - r) T_c) - K_c exp(-r T_c)` exceeds the bound `K_p exp(-r T_p)`.
+        let upper = (self.call_strike * (-r * Tc).exp() + self.put_strike * (-r * Tp).exp())
+            * ((r - b) * Tc).exp();
+
+        Newton::new(1e-12 * upper, 100).solve(
+            &f,
+            f64::MIN_POSITIVE,
+            upper,
+            self.initial_price.min(upper),
+        )
+    }
+
+    /// Price of the complex chooser (Haug, 2007).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if the critical price is not found
+    /// (see [`ComplexChooserOption::critical_price`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the choice is not before both expiries.
+    pub fn price(&self) -> Result<f64, RootFindingError> {
+        let (S, v) = (self.initial_price, self.volatility);
+        let (Kc, Kp) = (self.call_strike, self.put_strike);
+        let (t, Tc, Tp) = (self.choice_time, self.call_expiry, self.put_expiry);
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+
+        assert!(
+            0.0 < t && t < Tc && t < Tp,
+            "The choice must be made before both expiries."
+        );
+
+        let I = self.critical_price()?;
+        let M = bivariate_normal_cdf;
+
+        let d1 = ((S / I).ln() + (b + 0.5 * v * v) * t) / (v * t.sqrt());
+        let d2 = d1 - v * t.sqrt();
+        let y1 = ((S / Kc).ln() + (b + 0.5 * v * v) * Tc) / (v * Tc.sqrt());
+        let y2 = ((S / Kp).ln() + (b + 0.5 * v * v) * Tp) / (v * Tp.sqrt());
+        let (rho1, rho2) = ((t / Tc).sqrt(), (t / Tp).sqrt());
+
+        Ok(S * ((b - r) * Tc).exp() * M(d1, y1, rho1)
+            - Kc * (-r * Tc).exp() * M(d2, y1 - v * Tc.sqrt(), rho1)
+            - S * ((b - r) * Tp).exp() * M(-d1, -y2, rho2)
+            + Kp * (-r * Tp).exp() * M(-d2, -y2 + v * Tp.sqrt(), rho2))
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_chooser {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::black_scholes_greeks;
+
+    #[test]
+    fn test_simple_chooser() {
+        // Haug (2007).
+        let option = ChooserOption::new(50.0, 50.0, 0.25, 0.5, 0.08, 0.0, 0.25);
+        assert_approx_equal!(option.price(), 6.1071, 1e-4);
+
+        // Choosing at expiry is a straddle; choosing now, the dearer option.
+        let price = |t: f64| {
+            ChooserOption {
+                choice_time: t,
+                ..option
+            }
+            .price()
+        };
+        let bs = |flag| black_scholes_greeks(50.0, 50.0, 0.5, 0.08, 0.0, 0.25, flag).price;
+        let (call, put) = (bs(TypeFlag::Call), bs(TypeFlag::Put));
+
+        assert_approx_equal!(price(0.5 - 1e-12), call + put, 1e-4);
+        assert_approx_equal!(price(1e-12), call.max(put), 1e-4);
+    }
+
+    #[test]
+    fn test_complex_chooser() {
+        // Haug (2007).
+        let option =
+            ComplexChooserOption::new(50.0, 55.0, 48.0, 0.25, 0.5, 0.5833, 0.1, 0.05, 0.35);
+        assert_approx_equal!(option.price().unwrap(), 6.0508, 1e-4);
+
+        // With common strikes and expiries it is a simple chooser.
+        let simple = ChooserOption::new(50.0, 52.0, 0.25, 0.5, 0.1, 0.05, 0.35);
+        let complex = ComplexChooserOption::new(50.0, 52.0, 52.0, 0.25, 0.5, 0.5, 0.1, 0.05, 0.35);
+        assert_approx_equal!(complex.price().unwrap(), simple.price(), 1e-8);
+    }
+}
@@ -0,0 +1,344 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Cliquet (ratchet) options under Black-Scholes dynamics.
+//!
+//! A cliquet pays, at the last reset date, the sum of the periodic returns
+//! of the underlying, each clamped to a local floor and cap, with the sum
+//! itself clamped to a global floor and cap:
+//!
+//! $$
+//! N \cdot \min\left(\max\left(\sum_{i=1}^n \min(\max(R_i, F_l), C_l), F_g\right), C_g\right),
+//! \qquad R_i = \frac{S_{t_i}}{S_{t_{i-1}}} - 1
+//! $$
+//!
+//! Without global bounds each period is a forward-start call spread, priced
+//! in closed form (Rubinstein, 1990); otherwise the cliquet is priced by
+//! Monte Carlo.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::statistics::distributions::{Distribution as _, Gaussian};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Cliquet option on the sum of locally capped and floored periodic returns.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Quarterly resets over a year, each return floored at 0% and capped at 5%.
+/// let cliquet = CliquetOption::new(100.0, 0.03, 0.0, 0.2, vec![0.25, 0.5, 0.75, 1.0])
+///     .with_local_bounds(Some(0.0), Some(0.05));
+///
+/// let closed_form = cliquet.price().unwrap();
+/// let mc = cliquet.price_monte_carlo(100_000, 42);
+///
+/// assert!((closed_form - mc.price).abs() < 4.0 * mc.std_error);
+///
+/// // A global floor needs Monte Carlo.
+/// let cliquet = cliquet.with_global_bounds(Some(0.04), None);
+/// assert!(cliquet.price().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CliquetOption {
+    /// `N` - Notional.
+    pub notional: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+    /// Reset times `t_1 < ... < t_n` (in years), with `t_0 = 0`.
+    /// The payoff is paid at `t_n`.
+    pub reset_times: Vec<f64>,
+    /// `F_l` - Floor on each periodic return.
+    pub local_floor: Option<f64>,
+    /// `C_l` - Cap on each periodic return.
+    pub local_cap: Option<f64>,
+    /// `F_g` - Floor on the sum of the returns.
+    pub global_floor: Option<f64>,
+    /// `C_g` - Cap on the sum of the returns.
+    pub global_cap: Option<f64>,
+}
+
+/// Monte Carlo price of a cliquet option.
+#[derive(Debug, Clone, Copy)]
+pub struct CliquetMonteCarloResult {
+    /// Price.
+    pub price: f64,
+    /// Standard error of the price.
+    pub std_error: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CliquetOption {
+    /// New cliquet option, with no caps or floors.
+    #[must_use]
+    pub fn new(
+        notional: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        reset_times: Vec<f64>,
+    ) -> Self {
+        Self {
+            notional,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            reset_times,
+            local_floor: None,
+            local_cap: None,
+            global_floor: None,
+            global_cap: None,
+        }
+    }
+
+    /// Floor and cap on each periodic return.
+    #[must_use]
+    pub fn with_local_bounds(self, floor: Option<f64>, cap: Option<f64>) -> Self {
+        Self {
+            local_floor: floor,
+            local_cap: cap,
+            ..self
+        }
+    }
+
+    /// Floor and cap on the sum of the periodic returns.
+    #[must_use]
+    pub fn with_global_bounds(self, floor: Option<f64>, cap: Option<f64>) -> Self {
+        Self {
+            global_floor: floor,
+            global_cap: cap,
+            ..self
+        }
+    }
+
+    /// Closed-form price, as a sum of forward-start call spreads, or `None`
+    /// if there is a global floor or cap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reset times are empty, not positive or not increasing.
+    #[must_use]
+    pub fn price(&self) -> Option<f64> {
+        self.check_reset_times();
+
+        if self.global_floor.is_some() || self.global_cap.is_some() {
+            return None;
+        }
+
+        let maturity = self.reset_times[self.reset_times.len() - 1];
+        let df = (-self.risk_free_rate * maturity).exp();
+
+        let mut start = 0.0;
+        let mut expected = 0.0;
+
+        for &end in &self.reset_times {
+            expected += self.expected_local_return(end - start);
+            start = end;
+        }
+
+        Some(self.notional * df * expected)
+    }
+
+    /// Monte Carlo price, from exact log-normal steps between the reset dates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reset times are empty, not positive or not increasing,
+    /// or if there are fewer than 2 paths.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_monte_carlo(&self, paths: usize, seed: u64) -> CliquetMonteCarloResult {
+        self.check_reset_times();
+        assert!(paths >= 2, "Monte Carlo needs at least 2 paths.");
+
+        let v = self.volatility;
+        let carry = self.risk_free_rate - self.dividend_yield;
+        let maturity = self.reset_times[self.reset_times.len() - 1];
+        let df = (-self.risk_free_rate * maturity).exp();
+
+        // (drift, diffusion) of the log-return over each period.
+        let mut start = 0.0;
+        let periods: Vec<(f64, f64)> = self
+            .reset_times
+            .iter()
+            .map(|&end| {
+                let tau: f64 = end - start;
+                start = end;
+                ((carry - 0.5 * v * v) * tau, v * tau.sqrt())
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let payoffs: Vec<f64> = (0..paths)
+            .map(|_| {
+                let sum = periods
+                    .iter()
+                    .map(|(drift, diffusion)| {
+                        let z: f64 = StandardNormal.sample(&mut rng);
+                        clamp(
+                            (drift + diffusion * z).exp() - 1.0,
+                            self.local_floor,
+                            self.local_cap,
+                        )
+                    })
+                    .sum::<f64>();
+
+                df * self.notional * clamp(sum, self.global_floor, self.global_cap)
+            })
+            .collect();
+
+        let n = paths as f64;
+        let price = payoffs.iter().sum::<f64>() / n;
+        let variance = payoffs.iter().map(|x| (x - price).powi(2)).sum::<f64>() / (n - 1.0);
+
+        CliquetMonteCarloResult {
+            price,
+            std_error: (variance / n).sqrt(),
+        }
+    }
+
+    // E[min(max(R, F_l), C_l)] for the return over a period of length `tau`:
+    // F_l + E[(R - F_l)^+] - E[(R - C_l)^+], the undiscounted forward-start
+    // calls struck at (1 + F_l) and (1 + C_l) times the starting spot.
+    fn expected_local_return(&self, tau: f64) -> f64 {
+        let growth = ((self.risk_free_rate - self.dividend_yield) * tau).exp();
+
+        let call = |k: f64| {
+            let strike = 1.0 + k;
+            if strike <= 0.0 {
+                return growth - strike;
+            }
+
+            let stdev = self.volatility * tau.sqrt();
+            let d1 = (growth / strike).ln() / stdev + 0.5 * stdev;
+            let d2 = d1 - stdev;
+            let normal = Gaussian::default();
+
+            growth * normal.cdf(d1) - strike * normal.cdf(d2)
+        };
+
+        let floored = self.local_floor.map_or(growth - 1.0, |f| f + call(f));
+
+        floored - self.local_cap.map_or(0.0, call)
+    }
+
+    fn check_reset_times(&self) {
+        assert!(
+            !self.reset_times.is_empty(),
+            "A cliquet needs at least one reset time."
+        );
+        assert!(
+            self.reset_times[0] > 0.0 && self.reset_times.windows(2).all(|w| w[0] < w[1]),
+            "Reset times must be positive and increasing."
+        );
+    }
+}
+
+fn clamp(x: f64, floor: Option<f64>, cap: Option<f64>) -> f64 {
+    let x = floor.map_or(x, |f| x.max(f));
+    cap.map_or(x, |c| x.min(c))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cliquet {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::ForwardStartOption;
+    use time::{Duration, OffsetDateTime};
+
+    #[test]
+    fn test_forward_start_period() {
+        // A return floored at zero, paid at the end of its period, is an
+        // at-the-money forward-start call per unit of the forward at its start.
+        let today = OffsetDateTime::UNIX_EPOCH;
+        let forward_start = ForwardStartOption {
+            initial_price: 1.0,
+            alpha: 1.0,
+            risk_free_rate: 0.05,
+            volatility: 0.25,
+            dividend_rate: 0.02,
+            valuation_date: Some(today),
+            start: today + Duration::days(146),
+            end: today + Duration::days(365),
+        };
+
+        let first = CliquetOption::new(1.0, 0.05, 0.02, 0.25, vec![0.4])
+            .with_local_bounds(Some(0.0), None)
+            .price()
+            .unwrap();
+        let both = CliquetOption::new(1.0, 0.05, 0.02, 0.25, vec![0.4, 1.0])
+            .with_local_bounds(Some(0.0), None)
+            .price()
+            .unwrap();
+
+        // The first period is a vanilla call, deferred from 0.4 to 1.0.
+        let deferred = first * (-0.05_f64 * 0.6).exp();
+        let forward = (0.03_f64 * 0.4).exp();
+        assert_approx_equal!((both - deferred) * forward, forward_start.price().0, 1e-7);
+    }
+
+    #[test]
+    fn test_cliquet_monte_carlo() {
+        let resets: Vec<f64> = (1..=12).map(|i| f64::from(i) / 12.0).collect();
+        let cliquet = CliquetOption::new(100.0, 0.03, 0.01, 0.2, resets)
+            .with_local_bounds(Some(-0.02), Some(0.03));
+
+        let closed_form = cliquet.price().unwrap();
+        let mc = cliquet.price_monte_carlo(200_000, 7);
+
+        assert!(mc.std_error < 0.05);
+        assert_approx_equal!(closed_form, mc.price, 4.0 * mc.std_error);
+
+        // Without bounds the sum of the returns has a closed-form mean.
+        let unbounded = CliquetOption::new(100.0, 0.03, 0.01, 0.2, vec![0.5, 1.0]);
+        let expected = 100.0 * (-0.03_f64).exp() * 2.0 * ((0.02_f64 * 0.5).exp() - 1.0);
+        assert_approx_equal!(unbounded.price().unwrap(), expected, 1e-12);
+    }
+
+    #[test]
+    fn test_cliquet_global_bounds() {
+        let cliquet = CliquetOption::new(100.0, 0.03, 0.0, 0.25, vec![0.25, 0.5, 0.75, 1.0])
+            .with_local_bounds(Some(-0.05), Some(0.05));
+
+        let local = cliquet.price_monte_carlo(50_000, 1).price;
+        let floored = cliquet.clone().with_global_bounds(Some(0.0), None);
+        let collared = cliquet.clone().with_global_bounds(Some(0.0), Some(0.1));
+
+        assert!(floored.price().is_none());
+
+        let floored = floored.price_monte_carlo(50_000, 1).price;
+        let collared = collared.price_monte_carlo(50_000, 1).price;
+
+        // Same paths: a global floor adds value, a global cap removes it,
+        // and the payoff stays within the global bounds.
+        let df = (-0.03_f64).exp();
+        assert!(floored > local);
+        assert!(collared < floored);
+        assert!(collared > 0.0 && collared < 100.0 * 0.1 * df);
+    }
+}
@@ -0,0 +1,245 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Compound options: options on European options (Geske, 1979).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{generalised_greeks, TypeFlag};
+use crate::math::{Newton, RootFinder, RootFindingError};
+use crate::statistics::distributions::{bivariate_normal_cdf, Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Compound option: the right to buy (call) or sell (put) a European
+/// option for the compound strike `K_2` at time `t_1`.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Put on call from Haug (2007).
+/// let option = CompoundOption::new(500.0, 520.0, 50.0, 0.25, 0.5, 0.08, 0.03, 0.35);
+/// let price = option.price(TypeFlag::Put, TypeFlag::Call).unwrap();
+///
+/// assert!((price - 21.1965).abs() < 2e-4);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CompoundOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K_1` - Strike price of the underlying option.
+    pub underlying_strike: f64,
+    /// `K_2` - Strike price of the compound option (the premium paid for
+    /// the underlying option).
+    pub compound_strike: f64,
+    /// `t_1` - Time to expiry of the compound option (in years).
+    pub compound_expiry: f64,
+    /// `T_2` - Time to expiry of the underlying option (in years).
+    pub underlying_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CompoundOption {
+    /// New compound option.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        underlying_strike: f64,
+        compound_strike: f64,
+        compound_expiry: f64,
+        underlying_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            underlying_strike,
+            compound_strike,
+            compound_expiry,
+            underlying_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+        }
+    }
+
+    /// Critical price `I` of the underlying at `t_1`, at which the
+    /// underlying option is worth the compound strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if Newton's method does not converge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a put underlying is never worth the compound strike, that
+    /// is if `K_2 >= K_1 exp(-r (T_2 - t_1))`.
+    pub fn critical_price(&self, underlying: TypeFlag) -> Result<f64, RootFindingError> {
+        let (r, q, v) = (self.risk_free_rate, self.dividend_yield, self.volatility);
+        let (K1, K2) = (self.underlying_strike, self.compound_strike);
+        let tau = self.underlying_expiry - self.compound_expiry;
+
+        // Bracket the root: a call is worth at least `S exp(-q tau) - K_1
+        // exp(-r tau)`, and a put at most `K_1 exp(-r tau) N(-d_2)`, and these
+        // bounds reach the compound strike at `upper`.
+        let upper = match underlying {
+            TypeFlag::Call => (K2 + K1 * (-r * tau).exp()) * (q * tau).exp(),
+            TypeFlag::Put => {
+                assert!(
+                    K2 < K1 * (-r * tau).exp(),
+                    "The put is never worth the compound strike."
+                );
+
+                let d2 = -Gaussian::default().inv_cdf(K2 / K1 * (r * tau).exp());
+                K1 * (d2 * v * tau.sqrt() - (r - q - 0.5 * v * v) * tau).exp()
+            }
+        };
+
+        let f = |s: f64| generalised_greeks(s, K1, tau, r, r - q, v, underlying).price - K2;
+
+        Newton::new(1e-12 * upper, 100).solve(&f, f64::MIN_POSITIVE, upper, K1.min(upper))
+    }
+
+    /// Price of the compound option (Haug, 2007).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if the critical price is not found
+    /// (see [`CompoundOption::critical_price`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the compound option does not expire before the underlying
+    /// option, or if the critical price does not exist (see
+    /// [`CompoundOption::critical_price`]).
+    pub fn price(&self, compound: TypeFlag, underlying: TypeFlag) -> Result<f64, RootFindingError> {
+        let (S, r, v) = (self.initial_price, self.risk_free_rate, self.volatility);
+        let (K1, K2) = (self.underlying_strike, self.compound_strike);
+        let (t1, T2) = (self.compound_expiry, self.underlying_expiry);
+        let b = r - self.dividend_yield;
+
+        assert!(
+            0.0 < t1 && t1 < T2,
+            "The compound option must expire before the underlying option."
+        );
+
+        let I = self.critical_price(underlying)?;
+        let N = |x: f64| Gaussian::default().cdf(x);
+        let M = bivariate_normal_cdf;
+
+        let y1 = ((S / I).ln() + (b + 0.5 * v * v) * t1) / (v * t1.sqrt());
+        let y2 = y1 - v * t1.sqrt();
+        let z1 = ((S / K1).ln() + (b + 0.5 * v * v) * T2) / (v * T2.sqrt());
+        let z2 = z1 - v * T2.sqrt();
+        let rho = (t1 / T2).sqrt();
+
+        let forward = S * ((b - r) * T2).exp();
+        let strike = K1 * (-r * T2).exp();
+        let premium = K2 * (-r * t1).exp();
+
+        Ok(match (compound, underlying) {
+            (TypeFlag::Call, TypeFlag::Call) => {
+                forward * M(z1, y1, rho) - strike * M(z2, y2, rho) - premium * N(y2)
+            }
+            (TypeFlag::Put, TypeFlag::Call) => {
+                strike * M(z2, -y2, -rho) - forward * M(z1, -y1, -rho) + premium * N(-y2)
+            }
+            (TypeFlag::Call, TypeFlag::Put) => {
+                strike * M(-z2, -y2, rho) - forward * M(-z1, -y1, rho) - premium * N(-y2)
+            }
+            (TypeFlag::Put, TypeFlag::Put) => {
+                forward * M(-z1, y1, -rho) - strike * M(-z2, y2, -rho) + premium * N(y2)
+            }
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_compound {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::black_scholes_greeks;
+
+    const OPTION: CompoundOption =
+        CompoundOption::new(500.0, 520.0, 50.0, 0.25, 0.5, 0.08, 0.03, 0.35);
+
+    #[test]
+    fn test_compound_option() {
+        // Haug (2007), put on call.
+        assert_approx_equal!(
+            OPTION.price(TypeFlag::Put, TypeFlag::Call).unwrap(),
+            21.1965,
+            2e-4
+        );
+
+        // The underlying option is worth the compound strike at the critical price.
+        let I = OPTION.critical_price(TypeFlag::Call).unwrap();
+        let call = black_scholes_greeks(I, 520.0, 0.25, 0.08, 0.03, 0.35, TypeFlag::Call).price;
+        assert_approx_equal!(call, 50.0, 1e-9);
+    }
+
+    #[test]
+    fn test_compound_parity() {
+        // Call on X - put on X = X - K_2 exp(-r t_1).
+        let premium = 50.0 * (-0.08 * 0.25_f64).exp();
+
+        for (underlying, compound_strike) in [(TypeFlag::Call, 50.0), (TypeFlag::Put, 30.0)] {
+            let option = CompoundOption {
+                compound_strike,
+                ..OPTION
+            };
+            let premium = premium * compound_strike / 50.0;
+            let vanilla =
+                black_scholes_greeks(500.0, 520.0, 0.5, 0.08, 0.03, 0.35, underlying).price;
+
+            let call = option.price(TypeFlag::Call, underlying).unwrap();
+            let put = option.price(TypeFlag::Put, underlying).unwrap();
+
+            assert_approx_equal!(call - put, vanilla - premium, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_compound_limits() {
+        // With a zero compound strike, a call on an option is the option.
+        let option = CompoundOption {
+            compound_strike: 1e-10,
+            ..OPTION
+        };
+
+        for underlying in [TypeFlag::Call, TypeFlag::Put] {
+            let vanilla =
+                black_scholes_greeks(500.0, 520.0, 0.5, 0.08, 0.03, 0.35, underlying).price;
+            assert_approx_equal!(
+                option.price(TypeFlag::Call, underlying).unwrap(),
+                vanilla,
+                1e-6
+            );
+        }
+    }
+}
@@ -14,24 +14,24 @@
 use time::OffsetDateTime;
 
 use crate::{
-    statistics::distributions::{Distribution, Gaussian},
+    autodiff::Real,
     time::{DayCountConvention, DayCounter},
 };
 
 /// Black-Scholes Vanilla European Option
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
-pub struct EuropeanOption {
+pub struct EuropeanOption<R: Real = f64> {
     /// `S` - Initial price of the underlying.
-    pub initial_price: f64,
+    pub initial_price: R,
     /// `K` - Strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// `r` - Risk-free rate parameter.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// `v` - Volatility parameter.
-    pub volatility: f64,
+    pub volatility: R,
     /// `q` - Dividend rate.
-    pub dividend_rate: f64,
+    pub dividend_rate: R,
     /// `valuation_date` - Valuation date.
     pub evaluation_date: Option<OffsetDateTime>,
     /// `expiry_date` - Expiry date.
@@ -42,15 +42,15 @@ pub struct EuropeanOption {
 // EUROPEAN OPTION IMPLEMENTATION
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl EuropeanOption {
+impl<R: Real> EuropeanOption<R> {
     /// New European Option
     #[must_use]
     pub fn new(
-        initial_price: f64,
-        strike_price: f64,
-        risk_free_rate: f64,
-        volatility: f64,
-        dividend_rate: f64,
+        initial_price: R,
+        strike_price: R,
+        risk_free_rate: R,
+        volatility: R,
+        dividend_rate: R,
         evaluation_date: Option<OffsetDateTime>,
         expiration_date: OffsetDateTime,
     ) -> Self {
@@ -70,7 +70,7 @@ impl EuropeanOption {
     /// # Note:
     /// * `b = r - q` - The cost of carry.
     #[must_use]
-    pub fn price(&self) -> (f64, f64) {
+    pub fn price(&self) -> (R, R) {
         let S = self.initial_price;
         let K = self.strike_price;
         let r = self.risk_free_rate;
@@ -84,24 +84,22 @@ impl EuropeanOption {
             &DayCountConvention::Actual365,
         );
 
-        let df: f64 = (-r * T).exp();
-        let b: f64 = r - q;
-        let Fp: f64 = S * (b * T).exp();
-        let std: f64 = v * T.sqrt();
-        let d: f64 = (Fp / K).ln() / std;
-        let d1: f64 = d + 0.5 * std;
-        let d2: f64 = d1 - std;
+        let df = (-r * T).exp();
+        let b = r - q;
+        let Fp = S * (b * T).exp();
+        let std = v * T.sqrt();
+        let d = (Fp / K).ln() / std;
+        let d1 = d + std * 0.5;
+        let d2 = d1 - std;
 
-        let norm = Gaussian::default();
+        let Nd1 = d1.norm_cdf();
+        let Nd2 = d2.norm_cdf();
 
-        let Nd1: f64 = norm.cdf(d1);
-        let Nd2: f64 = norm.cdf(d2);
+        let Nd1_ = (-d1).norm_cdf();
+        let Nd2_ = (-d2).norm_cdf();
 
-        let Nd1_: f64 = norm.cdf(-d1);
-        let Nd2_: f64 = norm.cdf(-d2);
-
-        let c: f64 = df * (Fp * Nd1 - K * Nd2);
-        let p: f64 = df * (-Fp * Nd1_ + K * Nd2_);
+        let c = df * (Fp * Nd1 - K * Nd2);
+        let p = df * (-Fp * Nd1_ + K * Nd2_);
 
         (c, p)
     }
@@ -0,0 +1,864 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Finite-difference option pricing engine.
+//!
+//! The Black-Scholes PDE is solved backwards from expiry on a uniform grid
+//! in the log of the underlying price, $x = \ln S$:
+//!
+//! $$
+//! \frac{\partial V}{\partial \tau} = \frac{1}{2} \sigma^2 \frac{\partial^2 V}{\partial x^2}
+//!     + \left(b - \frac{1}{2} \sigma^2\right) \frac{\partial V}{\partial x} - r V
+//! $$
+//!
+//! where $\tau$ is the time to expiry and $b = r - q$ the cost of carry.
+//!
+//! - European exercise: implicit or Crank-Nicolson time stepping.
+//! - American exercise: the linear complementarity problem of each time step
+//!   is solved by projected successive over-relaxation (PSOR) or by the
+//!   penalty method of Forsyth and Vetzal (2002).
+//! - Knock-out barriers: the barrier is placed on the edge of the grid,
+//!   where the option value is the rebate (zero by default).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{lattice::deepest_exercise, ExerciseBoundary, ExerciseFlag, TypeFlag};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Time stepping scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FiniteDifferenceScheme {
+    /// Fully implicit (backward Euler): first order in time, unconditionally stable.
+    Implicit,
+    /// Crank-Nicolson: second order in time.
+    ///
+    /// The first two steps are implicit (Rannacher smoothing), to damp the
+    /// oscillations caused by the kink of the payoff at the strike.
+    CrankNicolson,
+}
+
+/// Boundary condition at the edges of the grid that are not a barrier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryCondition {
+    /// The asymptotic value of the option far in or out of the money,
+    /// e.g. `S exp(-q tau) - K exp(-r tau)` for a deep in the money call.
+    Dirichlet,
+    /// Zero second derivative: the value is linear in `ln S` at the edge.
+    Linear,
+}
+
+/// Method used to impose the early exercise constraint of American options.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EarlyExerciseMethod {
+    /// Projected successive over-relaxation (Cryer, 1971).
+    ProjectedSOR {
+        /// Relaxation parameter, in `(0, 2)`.
+        omega: f64,
+        /// Convergence tolerance on the change of the solution.
+        tolerance: f64,
+        /// Maximum number of iterations per time step.
+        max_iterations: usize,
+    },
+    /// Penalty method (Forsyth and Vetzal, 2002).
+    Penalty {
+        /// Penalty factor, e.g. `1e8`.
+        penalty: f64,
+        /// Maximum number of iterations per time step.
+        max_iterations: usize,
+    },
+}
+
+/// Knock-out barrier: the option becomes worthless when it is touched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KnockOutBarrier {
+    /// The option is knocked out if the underlying falls to the barrier.
+    DownAndOut(f64),
+    /// The option is knocked out if the underlying rises to the barrier.
+    UpAndOut(f64),
+}
+
+/// Option contract priced by the finite-difference engine.
+#[derive(Debug, Clone, Copy)]
+pub struct FiniteDifferenceOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `q` - Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility parameter.
+    pub volatility: f64,
+    /// Call or put flag.
+    pub option_type: TypeFlag,
+    /// European or American exercise.
+    pub exercise: ExerciseFlag,
+    /// Optional knock-out barrier (monitored continuously).
+    pub barrier: Option<KnockOutBarrier>,
+    /// Rebate paid when the barrier is touched.
+    pub rebate: f64,
+}
+
+/// Price and Greeks read off the grid.
+#[derive(Debug, Clone, Copy)]
+pub struct FiniteDifferenceResult {
+    /// Option price.
+    pub price: f64,
+    /// Sensitivity to the underlying price.
+    pub delta: f64,
+    /// Second order sensitivity to the underlying price.
+    pub gamma: f64,
+}
+
+/// Finite-difference pricing engine.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let option = FiniteDifferenceOption::new(
+///     36.0, 40.0, 1.0, 0.06, 0.0, 0.2,
+///     TypeFlag::Put,
+///     ExerciseFlag::American,
+/// );
+///
+/// let pde = FiniteDifferencePricer::new(FiniteDifferenceScheme::CrankNicolson, 400, 400);
+///
+/// // American put (Longstaff and Schwartz, 2001, Table 1).
+/// assert!((pde.price(&option).price - 4.486).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FiniteDifferencePricer {
+    /// Time stepping scheme.
+    pub scheme: FiniteDifferenceScheme,
+    /// Number of intervals of the `ln S` grid.
+    pub space_steps: usize,
+    /// Number of time steps.
+    pub time_steps: usize,
+    /// Half-width of the grid, in standard deviations of `ln S_T`
+    /// (edges that are not a barrier). Default: 5.
+    pub grid_width: f64,
+    /// Boundary condition at the edges that are not a barrier.
+    /// Default: `Dirichlet`.
+    pub boundary: BoundaryCondition,
+    /// Early exercise method for American options.
+    /// Default: PSOR with `omega = 1.2`.
+    pub early_exercise: EarlyExerciseMethod,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FiniteDifferenceOption {
+    /// New option without a barrier.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        option_type: TypeFlag,
+        exercise: ExerciseFlag,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            option_type,
+            exercise,
+            barrier: None,
+            rebate: 0.0,
+        }
+    }
+
+    /// Add a knock-out barrier.
+    #[must_use]
+    pub const fn with_barrier(mut self, barrier: KnockOutBarrier) -> Self {
+        self.barrier = Some(barrier);
+        self
+    }
+
+    /// Set the rebate paid when the barrier is touched.
+    #[must_use]
+    pub const fn with_rebate(mut self, rebate: f64) -> Self {
+        self.rebate = rebate;
+        self
+    }
+
+    /// Option payoff for an underlying price `s`.
+    #[must_use]
+    pub fn payoff(&self, s: f64) -> f64 {
+        match self.option_type {
+            TypeFlag::Call => f64::max(s - self.strike_price, 0.0),
+            TypeFlag::Put => f64::max(self.strike_price - s, 0.0),
+        }
+    }
+
+    // True if the barrier has already been touched at the initial price.
+    fn knocked_out(&self) -> bool {
+        match self.barrier {
+            Some(KnockOutBarrier::DownAndOut(h)) => self.initial_price <= h,
+            Some(KnockOutBarrier::UpAndOut(h)) => self.initial_price >= h,
+            None => false,
+        }
+    }
+
+    // Asymptotic (European) value at time to expiry `tau`, for an
+    // underlying price `s` far in or out of the money.
+    fn asymptotic_value(&self, s: f64, tau: f64) -> f64 {
+        let forward = s * (-self.dividend_yield * tau).exp();
+        let strike = self.strike_price * (-self.risk_free_rate * tau).exp();
+
+        match self.option_type {
+            TypeFlag::Call => f64::max(forward - strike, 0.0),
+            TypeFlag::Put => f64::max(strike - forward, 0.0),
+        }
+    }
+}
+
+impl FiniteDifferencePricer {
+    /// New finite-difference pricing engine, with the default grid width,
+    /// boundary condition and early exercise method.
+    #[must_use]
+    pub const fn new(
+        scheme: FiniteDifferenceScheme,
+        space_steps: usize,
+        time_steps: usize,
+    ) -> Self {
+        Self {
+            scheme,
+            space_steps,
+            time_steps,
+            grid_width: 5.0,
+            boundary: BoundaryCondition::Dirichlet,
+            early_exercise: EarlyExerciseMethod::ProjectedSOR {
+                omega: 1.2,
+                tolerance: 1e-10,
+                max_iterations: 10_000,
+            },
+        }
+    }
+
+    /// Set the half-width of the grid, in standard deviations of `ln S_T`.
+    #[must_use]
+    pub const fn with_grid_width(mut self, grid_width: f64) -> Self {
+        self.grid_width = grid_width;
+        self
+    }
+
+    /// Set the boundary condition at the edges that are not a barrier.
+    #[must_use]
+    pub const fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Set the early exercise method for American options.
+    #[must_use]
+    pub const fn with_early_exercise(mut self, early_exercise: EarlyExerciseMethod) -> Self {
+        self.early_exercise = early_exercise;
+        self
+    }
+
+    /// Price the option, and compute delta and gamma from the grid.
+    ///
+    /// The grid is shifted so that the initial price lies on a node, and
+    /// the Greeks are the central differences at that node. An option
+    /// whose barrier has already been touched is worth its rebate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 3 space steps or no time steps,
+    /// or for Bermudan exercise (which requires exercise dates).
+    #[must_use]
+    pub fn price(&self, option: &FiniteDifferenceOption) -> FiniteDifferenceResult {
+        self.price_with_exercise_boundary(option).0
+    }
+
+    /// Price the option as [`FiniteDifferencePricer::price`], and read the
+    /// early-exercise boundary off the grid at each time step: the nodes
+    /// where the option value is the payoff (empty for European options).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 3 space steps or no time steps,
+    /// or for Bermudan exercise (which requires exercise dates).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
+    pub fn price_with_exercise_boundary(
+        &self,
+        option: &FiniteDifferenceOption,
+    ) -> (FiniteDifferenceResult, ExerciseBoundary) {
+        assert!(
+            self.space_steps >= 3,
+            "The grid needs at least 3 space steps."
+        );
+        assert!(
+            self.time_steps > 0,
+            "The grid needs at least one time step."
+        );
+
+        let american = match option.exercise {
+            ExerciseFlag::American => true,
+            ExerciseFlag::European => false,
+            ExerciseFlag::Bermudan => {
+                panic!("Bermudan exercise is not supported by the finite-difference engine.")
+            }
+        };
+
+        let mut boundary = ExerciseBoundary {
+            times: Vec::new(),
+            critical_prices: Vec::new(),
+        };
+
+        if option.knocked_out() {
+            let result = FiniteDifferenceResult {
+                price: option.rebate,
+                delta: 0.0,
+                gamma: 0.0,
+            };
+            return (result, boundary);
+        }
+
+        let (x_min, h, i_0) = self.grid(option);
+        let n = self.space_steps;
+
+        let s: Vec<f64> = (0..=n).map(|i| (x_min + i as f64 * h).exp()).collect();
+        let payoff: Vec<f64> = s.iter().map(|&s| option.payoff(s)).collect();
+
+        let (lower_barrier, upper_barrier) = match option.barrier {
+            Some(KnockOutBarrier::DownAndOut(_)) => (true, false),
+            Some(KnockOutBarrier::UpAndOut(_)) => (false, true),
+            None => (false, false),
+        };
+
+        // Spatial operator: (L V)_i = a V_{i-1} + b V_i + c V_{i+1}.
+        let sigma2 = option.volatility * option.volatility;
+        let mu = option.risk_free_rate - option.dividend_yield - 0.5 * sigma2;
+        let a = 0.5 * sigma2 / (h * h) - 0.5 * mu / h;
+        let b = -sigma2 / (h * h) - option.risk_free_rate;
+        let c = 0.5 * sigma2 / (h * h) + 0.5 * mu / h;
+
+        let dt = option.time_to_expiry / self.time_steps as f64;
+
+        // Value at the edges at time to expiry `tau`. `None` for the
+        // linear boundary condition (extrapolated from the interior).
+        let edge = |i: usize, barrier: bool, tau: f64| -> Option<f64> {
+            if barrier {
+                return Some(option.rebate);
+            }
+            match self.boundary {
+                BoundaryCondition::Dirichlet => {
+                    let value = option.asymptotic_value(s[i], tau);
+                    Some(if american {
+                        value.max(payoff[i])
+                    } else {
+                        value
+                    })
+                }
+                BoundaryCondition::Linear => None,
+            }
+        };
+
+        let mut values = payoff.clone();
+        if lower_barrier {
+            values[0] = option.rebate;
+        }
+        if upper_barrier {
+            values[n] = option.rebate;
+        }
+
+        let m = n - 1;
+
+        for step in 1..=self.time_steps {
+            let tau = step as f64 * dt;
+
+            let theta = match self.scheme {
+                FiniteDifferenceScheme::Implicit => 1.0,
+                FiniteDifferenceScheme::CrankNicolson if step <= 2 => 1.0,
+                FiniteDifferenceScheme::CrankNicolson => 0.5,
+            };
+
+            // Interior system: (I - theta dt L) V^{new} = (I + (1 - theta) dt L) V^{old}.
+            let mut lower = vec![-theta * dt * a; m];
+            let mut diag = vec![1.0 - theta * dt * b; m];
+            let mut upper = vec![-theta * dt * c; m];
+
+            let mut rhs: Vec<f64> = (1..n)
+                .map(|i| {
+                    values[i]
+                        + (1.0 - theta)
+                            * dt
+                            * (a * values[i - 1] + b * values[i] + c * values[i + 1])
+                })
+                .collect();
+
+            let low = edge(0, lower_barrier, tau);
+            let high = edge(n, upper_barrier, tau);
+
+            if let Some(value) = low {
+                rhs[0] -= lower[0] * value;
+            } else {
+                // V_0 = 2 V_1 - V_2.
+                diag[0] += 2.0 * lower[0];
+                upper[0] -= lower[0];
+            }
+            if let Some(value) = high {
+                rhs[m - 1] -= upper[m - 1] * value;
+            } else {
+                // V_n = 2 V_{n-1} - V_{n-2}.
+                diag[m - 1] += 2.0 * upper[m - 1];
+                lower[m - 1] -= upper[m - 1];
+            }
+
+            let interior = if american {
+                self.solve_american(&lower, &diag, &upper, &rhs, &values[1..n], &payoff[1..n])
+            } else {
+                solve_tridiagonal(&lower, &diag, &upper, &rhs)
+            };
+
+            values[1..n].copy_from_slice(&interior);
+            values[0] = low.unwrap_or(2.0 * values[1] - values[2]);
+            values[n] = high.unwrap_or(2.0 * values[n - 1] - values[n - 2]);
+
+            if american {
+                let critical = (1..n)
+                    .filter(|&i| {
+                        payoff[i] > 0.0 && values[i] - payoff[i] <= 1e-9 * option.strike_price
+                    })
+                    .fold(None, |critical, i| {
+                        Some(deepest_exercise(option.option_type, critical, s[i]))
+                    });
+
+                boundary.times.push(option.time_to_expiry - tau);
+                boundary.critical_prices.push(critical);
+            }
+        }
+
+        boundary.times.reverse();
+        boundary.critical_prices.reverse();
+
+        // Greeks from the central differences in ln S at the initial price.
+        let s_0 = s[i_0];
+        let (v_down, v, v_up) = (values[i_0 - 1], values[i_0], values[i_0 + 1]);
+
+        let v_x = (v_up - v_down) / (2.0 * h);
+        let v_xx = (v_up - 2.0 * v + v_down) / (h * h);
+
+        let result = FiniteDifferenceResult {
+            price: v,
+            delta: v_x / s_0,
+            gamma: (v_xx - v_x) / (s_0 * s_0),
+        };
+
+        (result, boundary)
+    }
+
+    // Lower edge and spacing of the ln S grid, and the index of the node of
+    // the initial price. A barrier is always an edge of the grid.
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn grid(&self, option: &FiniteDifferenceOption) -> (f64, f64, usize) {
+        let n = self.space_steps;
+        let x_0 = option.initial_price.ln();
+        let width = self.grid_width * option.volatility * option.time_to_expiry.sqrt();
+
+        // Number of intervals below the initial price, for a grid from
+        // `x_low` to `x_high`, keeping at least one node on each side.
+        let split = |x_low: f64, x_high: f64| -> usize {
+            let i = ((x_0 - x_low) / (x_high - x_low) * n as f64).round() as usize;
+            i.clamp(1, n - 1)
+        };
+
+        match option.barrier {
+            Some(KnockOutBarrier::DownAndOut(barrier)) => {
+                let x_min = barrier.ln();
+                let i_0 = split(x_min, x_0 + width);
+                (x_min, (x_0 - x_min) / i_0 as f64, i_0)
+            }
+            Some(KnockOutBarrier::UpAndOut(barrier)) => {
+                let x_max = barrier.ln();
+                let i_0 = split(x_0 - width, x_max);
+                let h = (x_max - x_0) / (n - i_0) as f64;
+                (x_max - n as f64 * h, h, i_0)
+            }
+            None => {
+                let i_0 = n / 2;
+                let h = width / i_0 as f64;
+                (x_0 - i_0 as f64 * h, h, i_0)
+            }
+        }
+    }
+
+    // Solve the linear complementarity problem
+    //      A V >= rhs,  V >= payoff,  (A V - rhs) (V - payoff) = 0,
+    // starting from the previous solution `initial`.
+    fn solve_american(
+        &self,
+        lower: &[f64],
+        diag: &[f64],
+        upper: &[f64],
+        rhs: &[f64],
+        initial: &[f64],
+        payoff: &[f64],
+    ) -> Vec<f64> {
+        let m = rhs.len();
+        match self.early_exercise {
+            EarlyExerciseMethod::ProjectedSOR {
+                omega,
+                tolerance,
+                max_iterations,
+            } => {
+                let mut v: Vec<f64> = initial.iter().zip(payoff).map(|(v, p)| v.max(*p)).collect();
+
+                for _ in 0..max_iterations {
+                    let mut error = 0.0_f64;
+
+                    for i in 0..m {
+                        let mut residual = rhs[i];
+                        if i > 0 {
+                            residual -= lower[i] * v[i - 1];
+                        }
+                        if i + 1 < m {
+                            residual -= upper[i] * v[i + 1];
+                        }
+
+                        let gauss_seidel = residual / diag[i];
+                        let new = (v[i] + omega * (gauss_seidel - v[i])).max(payoff[i]);
+
+                        error = error.max((new - v[i]).abs());
+                        v[i] = new;
+                    }
+
+                    if error < tolerance {
+                        break;
+                    }
+                }
+
+                v
+            }
+            EarlyExerciseMethod::Penalty {
+                penalty,
+                max_iterations,
+            } => {
+                // Penalise the nodes below the payoff, and repeat until
+                // the set of penalised nodes no longer changes.
+                let below =
+                    |v: &[f64]| -> Vec<bool> { v.iter().zip(payoff).map(|(v, p)| v < p).collect() };
+
+                let mut active = below(initial);
+                let mut v = initial.to_vec();
+
+                for _ in 0..max_iterations {
+                    let penalised_diag: Vec<f64> = diag
+                        .iter()
+                        .zip(&active)
+                        .map(|(d, &on)| if on { d + penalty } else { *d })
+                        .collect();
+                    let penalised_rhs: Vec<f64> = rhs
+                        .iter()
+                        .zip(&active)
+                        .zip(payoff)
+                        .map(|((r, &on), p)| if on { r + penalty * p } else { *r })
+                        .collect();
+
+                    v = solve_tridiagonal(lower, &penalised_diag, upper, &penalised_rhs);
+
+                    let next_active = below(&v);
+                    if next_active == active {
+                        break;
+                    }
+                    active = next_active;
+                }
+
+                v
+            }
+        }
+    }
+}
+
+// Thomas algorithm for a tridiagonal system. `lower[0]` and
+// `upper[m - 1]` are ignored.
+pub(crate) fn solve_tridiagonal(
+    lower: &[f64],
+    diag: &[f64],
+    upper: &[f64],
+    rhs: &[f64],
+) -> Vec<f64> {
+    let m = rhs.len();
+    let mut c = vec![0.0; m];
+    let mut d = vec![0.0; m];
+
+    c[0] = upper[0] / diag[0];
+    d[0] = rhs[0] / diag[0];
+
+    for i in 1..m {
+        let denominator = diag[i] - lower[i] * c[i - 1];
+        c[i] = upper[i] / denominator;
+        d[i] = (rhs[i] - lower[i] * d[i - 1]) / denominator;
+    }
+
+    for i in (0..m - 1).rev() {
+        d[i] -= c[i] * d[i + 1];
+    }
+
+    d
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_finite_difference {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::{
+        BarrierOption, BarrierType, BinomialTree, LatticeModel, LatticeOption,
+    };
+
+    // Black-Scholes (S = 100, K = 100, T = 1, r = 0.05, q = 0, v = 0.2).
+    const CALL: f64 = 10.450_583_572_185_565;
+    const PUT: f64 = 5.573_526_022_256_971;
+    const CALL_DELTA: f64 = 0.636_830_651_175_619;
+    const GAMMA: f64 = 0.018_762_017_345_846_895;
+
+    fn european(option_type: TypeFlag) -> FiniteDifferenceOption {
+        FiniteDifferenceOption::new(
+            100.0,
+            100.0,
+            1.0,
+            0.05,
+            0.0,
+            0.2,
+            option_type,
+            ExerciseFlag::European,
+        )
+    }
+
+    #[test]
+    fn test_european() {
+        for scheme in [
+            FiniteDifferenceScheme::Implicit,
+            FiniteDifferenceScheme::CrankNicolson,
+        ] {
+            for boundary in [BoundaryCondition::Dirichlet, BoundaryCondition::Linear] {
+                let pde = FiniteDifferencePricer::new(scheme, 400, 400).with_boundary(boundary);
+
+                let call = pde.price(&european(TypeFlag::Call));
+                let put = pde.price(&european(TypeFlag::Put));
+
+                assert_approx_equal!(call.price, CALL, 0.01);
+                assert_approx_equal!(put.price, PUT, 0.01);
+                assert_approx_equal!(call.delta, CALL_DELTA, 1e-3);
+                assert_approx_equal!(put.delta, CALL_DELTA - 1.0, 1e-3);
+                assert_approx_equal!(call.gamma, GAMMA, 1e-4);
+                assert_approx_equal!(put.gamma, GAMMA, 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crank_nicolson_converges_faster() {
+        let call = european(TypeFlag::Call);
+
+        let implicit = FiniteDifferencePricer::new(FiniteDifferenceScheme::Implicit, 400, 50);
+        let crank_nicolson =
+            FiniteDifferencePricer::new(FiniteDifferenceScheme::CrankNicolson, 400, 50);
+
+        let implicit_error = (implicit.price(&call).price - CALL).abs();
+        let crank_nicolson_error = (crank_nicolson.price(&call).price - CALL).abs();
+
+        assert!(crank_nicolson_error < implicit_error);
+    }
+
+    #[test]
+    fn test_american_put() {
+        let option = FiniteDifferenceOption::new(
+            36.0,
+            40.0,
+            1.0,
+            0.06,
+            0.0,
+            0.2,
+            TypeFlag::Put,
+            ExerciseFlag::American,
+        );
+
+        let tree =
+            BinomialTree::new(LatticeModel::CoxRossRubinstein, 2000).price(&LatticeOption::new(
+                36.0,
+                40.0,
+                1.0,
+                0.06,
+                0.0,
+                0.2,
+                TypeFlag::Put,
+                ExerciseFlag::American,
+            ));
+
+        for method in [
+            EarlyExerciseMethod::ProjectedSOR {
+                omega: 1.5,
+                tolerance: 1e-10,
+                max_iterations: 10_000,
+            },
+            EarlyExerciseMethod::Penalty {
+                penalty: 1e8,
+                max_iterations: 100,
+            },
+        ] {
+            let pde = FiniteDifferencePricer::new(FiniteDifferenceScheme::CrankNicolson, 400, 400)
+                .with_early_exercise(method);
+            let result = pde.price(&option);
+
+            assert_approx_equal!(result.price, tree, 5e-3);
+            // Deep in the money the put is exercised: delta = -1.
+            assert!(result.delta < -0.6 && result.delta > -1.0);
+            assert!(result.gamma > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_exercise_boundary() {
+        let option = FiniteDifferenceOption::new(
+            36.0,
+            40.0,
+            1.0,
+            0.06,
+            0.0,
+            0.2,
+            TypeFlag::Put,
+            ExerciseFlag::American,
+        );
+        let pde = FiniteDifferencePricer::new(FiniteDifferenceScheme::CrankNicolson, 400, 200);
+        let (result, boundary) = pde.price_with_exercise_boundary(&option);
+
+        assert_approx_equal!(result.price, pde.price(&option).price, 1e-12);
+        assert_eq!(boundary.times.len(), 200);
+        assert_approx_equal!(boundary.times[0], 0.0, 1e-12);
+
+        // Same boundary as the tree, up to the resolution of the grids.
+        let lattice = LatticeOption::new(
+            36.0,
+            40.0,
+            1.0,
+            0.06,
+            0.0,
+            0.2,
+            TypeFlag::Put,
+            ExerciseFlag::American,
+        );
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 1000)
+            .price_with_exercise_boundary(&lattice)
+            .1;
+
+        for (t, critical) in boundary.times.iter().zip(&boundary.critical_prices) {
+            let j = (t * 1000.0).round() as usize;
+            if let (Some(pde), Some(tree)) = (critical, tree.critical_prices[j]) {
+                assert_approx_equal!(pde, &tree, 0.5);
+            }
+        }
+
+        // A European option has no boundary.
+        let european = FiniteDifferenceOption {
+            exercise: ExerciseFlag::European,
+            ..option
+        };
+        assert!(pde
+            .price_with_exercise_boundary(&european)
+            .1
+            .times
+            .is_empty());
+    }
+
+    #[test]
+    fn test_knock_out_barriers() {
+        let closed_form = |barrier: f64| BarrierOption {
+            initial_price: 100.0,
+            strike_price: 100.0,
+            barrier,
+            time_to_expiry: 1.0,
+            risk_free_rate: 0.05,
+            volatility: 0.2,
+            rebate: 0.0,
+            dividend_yield: 0.01,
+        };
+        let option = |option_type: TypeFlag, barrier: KnockOutBarrier| {
+            FiniteDifferenceOption::new(
+                100.0,
+                100.0,
+                1.0,
+                0.05,
+                0.01,
+                0.2,
+                option_type,
+                ExerciseFlag::European,
+            )
+            .with_barrier(barrier)
+        };
+
+        let pde = FiniteDifferencePricer::new(FiniteDifferenceScheme::CrankNicolson, 400, 400);
+
+        let cases = [
+            (
+                TypeFlag::Call,
+                KnockOutBarrier::DownAndOut(90.0),
+                90.0,
+                BarrierType::CDO,
+            ),
+            (
+                TypeFlag::Put,
+                KnockOutBarrier::DownAndOut(90.0),
+                90.0,
+                BarrierType::PDO,
+            ),
+            (
+                TypeFlag::Call,
+                KnockOutBarrier::UpAndOut(130.0),
+                130.0,
+                BarrierType::CUO,
+            ),
+            (
+                TypeFlag::Put,
+                KnockOutBarrier::UpAndOut(110.0),
+                110.0,
+                BarrierType::PUO,
+            ),
+        ];
+
+        for (option_type, barrier, level, barrier_type) in cases {
+            let expected = closed_form(level).price(barrier_type);
+            let result = pde.price(&option(option_type, barrier));
+
+            assert_approx_equal!(result.price, expected, 0.01);
+        }
+
+        // Already knocked out.
+        let knocked_out = option(TypeFlag::Call, KnockOutBarrier::UpAndOut(95.0));
+        assert_eq!(pde.price(&knocked_out).price, 0.0);
+    }
+}
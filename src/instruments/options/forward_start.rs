@@ -14,28 +14,28 @@
 use time::OffsetDateTime;
 
 use crate::{
-    statistics::distributions::{Distribution, Gaussian},
+    autodiff::Real,
     time::{DayCountConvention, DayCounter},
 };
 
 /// Forward Start Option parameters struct
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug)]
-pub struct ForwardStartOption {
+pub struct ForwardStartOption<R: Real = f64> {
     /// `S` - Initial price of the underlying.
-    pub initial_price: f64,
+    pub initial_price: R,
     /// `alpha` - The proportion of S to set the strike price.
     /// Three possibilities:
     ///     - alpha < 1: call (put) will start (1 - alpha)% in-the-money (out-of-the-money).
     ///     - alpha = 1: the option starts at-the-money.
     ///     - alpha > 1: call (put) will start (alpha - 1)% out-of-the-money (in-the-money).
-    pub alpha: f64,
+    pub alpha: R,
     /// `r` - Risk-free rate parameter.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// `v` - Volatility parameter.
-    pub volatility: f64,
+    pub volatility: R,
     /// `q` - Dividend rate.
-    pub dividend_rate: f64,
+    pub dividend_rate: R,
     /// `valuation_date` - Valuation date.
     pub valuation_date: Option<OffsetDateTime>,
 
@@ -49,13 +49,13 @@ pub struct ForwardStartOption {
 // FORWARD START OPTION IMPLEMENTATION
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl ForwardStartOption {
+impl<R: Real> ForwardStartOption<R> {
     /// Rubinstein (1990) Forward Start Option Price formula.
     /// Returns a tuple: `(call_price, put_price)`
     /// # Note:
     /// * `b = r - q` - The cost of carry.
     #[must_use]
-    pub fn price(&self) -> (f64, f64) {
+    pub fn price(&self) -> (R, R) {
         let S = self.initial_price;
         let a = self.alpha;
 
@@ -77,21 +77,19 @@ impl ForwardStartOption {
 
         let b = r - q;
 
-        let d1 = ((1. / a).ln() + (b + v * v / 2.) * (T - t)) / (v * (T - t).sqrt());
+        let d1 = (a.recip().ln() + (b + v * v / 2.) * (T - t)) / (v * (T - t).sqrt());
         let d2 = d1 - v * (T - t).sqrt();
 
-        let norm = Gaussian::default();
+        let Nd1 = d1.norm_cdf();
+        let Nd2 = d2.norm_cdf();
 
-        let Nd1: f64 = norm.cdf(d1);
-        let Nd2: f64 = norm.cdf(d2);
+        let Nd1_ = (-d1).norm_cdf();
+        let Nd2_ = (-d2).norm_cdf();
 
-        let Nd1_: f64 = norm.cdf(-d1);
-        let Nd2_: f64 = norm.cdf(-d2);
-
-        let c: f64 = S
+        let c = S
             * ((b - r) * t).exp()
             * (((b - r) * (T - t)).exp() * Nd1 - a * (-r * (T - t)).exp() * Nd2);
-        let p: f64 = S
+        let p = S
             * ((b - r) * t).exp()
             * (-((b - r) * (T - t)).exp() * Nd1_ + a * (-r * (T - t)).exp() * Nd2_);
 
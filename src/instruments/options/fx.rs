@@ -0,0 +1,461 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! FX options under Garman and Kohlhagen (1983), with the market's delta
+//! and at-the-money conventions (Reiswich and Wystup, 2010).
+//!
+//! The spot `S` is the price of one unit of foreign currency in domestic
+//! currency, and `F = S exp((r_d - r_f) T)`. With `phi = 1` for calls and
+//! `phi = -1` for puts, the deltas are
+//!
+//! | Convention               | Delta                                      |
+//! |--------------------------|--------------------------------------------|
+//! | Spot                     | `phi e^{-r_f T} N(phi d1)`                 |
+//! | Forward                  | `phi N(phi d1)`                            |
+//! | Premium-adjusted spot    | `phi e^{-r_f T} (K / F) N(phi d2)`         |
+//! | Premium-adjusted forward | `phi (K / F) N(phi d2)`                    |
+//!
+//! Premium-adjusted deltas apply when the premium is paid in foreign
+//! currency, so that the hedge is reduced by the premium received.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::{garman_kohlhagen_greeks, OptionGreeks, TypeFlag};
+use crate::math::{Brent, RootFinder, RootFindingError};
+use crate::statistics::distributions::{Distribution, Gaussian};
+use statrs::function::erf::erfc_inv;
+use std::f64::consts::SQRT_2;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ERRORS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// FX option errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum FxOptionError {
+    /// The volatility or time to expiry is not positive.
+    #[error("Volatility and time to expiry must be positive")]
+    NonPositiveInput,
+
+    /// The delta has the wrong sign for the option type, or exceeds the
+    /// bounds of an unadjusted delta.
+    #[error("Delta is outside of the range of the convention")]
+    InvalidDelta,
+
+    /// The premium-adjusted call delta is above its maximum.
+    #[error("Premium-adjusted call delta is above its maximum")]
+    DeltaNotAttainable,
+
+    /// The strike of a premium-adjusted delta was not found.
+    #[error(transparent)]
+    RootFinding(#[from] RootFindingError),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Delta quotation conventions of FX options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FxDeltaConvention {
+    /// Spot delta, premium in domestic currency.
+    Spot,
+    /// Forward delta, premium in domestic currency.
+    Forward,
+    /// Spot delta, premium in foreign currency.
+    PremiumAdjustedSpot,
+    /// Forward delta, premium in foreign currency.
+    PremiumAdjustedForward,
+}
+
+/// At-the-money conventions of FX options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FxAtmConvention {
+    /// Strike equal to the spot.
+    Spot,
+    /// Strike equal to the forward.
+    Forward,
+    /// Delta-neutral straddle (DNS): the call and put deltas sum to zero.
+    DeltaNeutral,
+}
+
+/// Garman-Kohlhagen (1983) pricer of European FX options.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // EUR/USD: the foreign currency is EUR, the domestic currency is USD.
+/// let fx = GarmanKohlhagen::new(1.3465, 0.0294, 0.0346, 1.0);
+///
+/// // Strike of a 25 delta call, with premium-adjusted spot delta.
+/// let strike = fx
+///     .strike_from_delta(0.25, 0.12, TypeFlag::Call, FxDeltaConvention::PremiumAdjustedSpot)
+///     .unwrap();
+///
+/// let delta = fx.delta(strike, 0.12, TypeFlag::Call, FxDeltaConvention::PremiumAdjustedSpot);
+///
+/// assert!((delta - 0.25).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct GarmanKohlhagen {
+    /// `S` - Spot rate, in units of domestic currency per unit of foreign currency.
+    pub spot: f64,
+    /// `r_d` - Domestic risk-free rate.
+    pub domestic_rate: f64,
+    /// `r_f` - Foreign risk-free rate.
+    pub foreign_rate: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FxDeltaConvention {
+    /// Whether the delta is adjusted for a premium paid in foreign currency.
+    #[must_use]
+    pub const fn is_premium_adjusted(self) -> bool {
+        matches!(
+            self,
+            Self::PremiumAdjustedSpot | Self::PremiumAdjustedForward
+        )
+    }
+}
+
+impl GarmanKohlhagen {
+    /// New Garman-Kohlhagen pricer.
+    #[must_use]
+    pub const fn new(
+        spot: f64,
+        domestic_rate: f64,
+        foreign_rate: f64,
+        time_to_expiry: f64,
+    ) -> Self {
+        Self {
+            spot,
+            domestic_rate,
+            foreign_rate,
+            time_to_expiry,
+        }
+    }
+
+    /// Outright forward rate, `S exp((r_d - r_f) T)`.
+    #[must_use]
+    pub fn forward(&self) -> f64 {
+        self.spot * ((self.domestic_rate - self.foreign_rate) * self.time_to_expiry).exp()
+    }
+
+    /// Price of a European call or put, in domestic currency per unit of
+    /// foreign notional.
+    #[must_use]
+    pub fn price(&self, strike: f64, volatility: f64, option_type: TypeFlag) -> f64 {
+        self.greeks(strike, volatility, option_type).price
+    }
+
+    /// Price and Greeks of a European call or put. Delta is the
+    /// (unadjusted) spot delta.
+    #[must_use]
+    pub fn greeks(&self, strike: f64, volatility: f64, option_type: TypeFlag) -> OptionGreeks {
+        garman_kohlhagen_greeks(
+            self.spot,
+            strike,
+            self.time_to_expiry,
+            self.domestic_rate,
+            self.foreign_rate,
+            volatility,
+            option_type,
+        )
+    }
+
+    /// Delta of a European call or put, in the given convention.
+    #[must_use]
+    pub fn delta(
+        &self,
+        strike: f64,
+        volatility: f64,
+        option_type: TypeFlag,
+        convention: FxDeltaConvention,
+    ) -> f64 {
+        let phi = sign(option_type);
+        let norm = Gaussian::default();
+        let forward = self.forward();
+        let std = volatility * self.time_to_expiry.sqrt();
+        let d1 = (forward / strike).ln() / std + 0.5 * std;
+        let d2 = d1 - std;
+
+        let forward_delta = if convention.is_premium_adjusted() {
+            phi * strike / forward * norm.cdf(phi * d2)
+        } else {
+            phi * norm.cdf(phi * d1)
+        };
+
+        forward_delta * self.spot_factor(convention)
+    }
+
+    /// Strike of a European call or put with the given delta.
+    ///
+    /// Unadjusted deltas are inverted in closed form. Premium-adjusted
+    /// deltas are inverted numerically; the premium-adjusted call delta is
+    /// not monotonic in the strike, and the strike returned is the one
+    /// above the strike of maximum delta.
+    ///
+    /// # Errors
+    ///
+    /// - `FxOptionError::NonPositiveInput` if the volatility or time to expiry is not positive.
+    /// - `FxOptionError::InvalidDelta` if the delta is outside of the range of the convention.
+    /// - `FxOptionError::DeltaNotAttainable` if a premium-adjusted call delta is above its maximum.
+    /// - `FxOptionError::RootFinding` if the strike of a premium-adjusted delta is not found.
+    pub fn strike_from_delta(
+        &self,
+        delta: f64,
+        volatility: f64,
+        option_type: TypeFlag,
+        convention: FxDeltaConvention,
+    ) -> Result<f64, FxOptionError> {
+        if volatility <= 0.0 || self.time_to_expiry <= 0.0 {
+            return Err(FxOptionError::NonPositiveInput);
+        }
+
+        let phi = sign(option_type);
+        let forward = self.forward();
+        let std = volatility * self.time_to_expiry.sqrt();
+
+        // Target forward delta, which must have the sign of the option.
+        let target = delta / self.spot_factor(convention);
+        if phi * target <= 0.0 {
+            return Err(FxOptionError::InvalidDelta);
+        }
+
+        // Strike of an unadjusted forward delta.
+        let unadjusted = |delta: f64| {
+            forward * (-phi * inverse_norm_cdf(phi * delta) * std + 0.5 * std * std).exp()
+        };
+
+        if !convention.is_premium_adjusted() {
+            if phi * target >= 1.0 {
+                return Err(FxOptionError::InvalidDelta);
+            }
+
+            return Ok(unadjusted(target));
+        }
+
+        let brent = Brent::new(1e-15, 200);
+        let norm = Gaussian::default();
+        let adjusted = |k: f64| {
+            let d2 = (forward / k).ln() / std - 0.5 * std;
+            phi * k / forward * norm.cdf(phi * d2) - target
+        };
+
+        match option_type {
+            TypeFlag::Call => {
+                if target >= 1.0 {
+                    return Err(FxOptionError::DeltaNotAttainable);
+                }
+
+                // The adjusted delta is below the unadjusted delta at the same
+                // strike, and is maximal where std N(d2) = n(d2).
+                let upper = unadjusted(target);
+                let d2_max = brent.solve(
+                    &|d: f64| std * norm.cdf(d) - norm.pdf(d),
+                    -std,
+                    10.0 + std,
+                    0.0,
+                )?;
+                let lower = forward * (-d2_max * std - 0.5 * std * std).exp();
+
+                if adjusted(lower) < 0.0 {
+                    return Err(FxOptionError::DeltaNotAttainable);
+                }
+
+                Ok(brent.solve(&adjusted, lower, upper, upper)?)
+            }
+            TypeFlag::Put => {
+                // The adjusted put delta is monotonic, and below the
+                // unadjusted delta at the same strike.
+                let upper = unadjusted(target.max(-1.0 + 1e-12));
+                let mut lower = upper;
+                while adjusted(lower) < 0.0 {
+                    lower *= (-std).exp();
+                }
+
+                Ok(brent.solve(&adjusted, lower, upper, upper)?)
+            }
+        }
+    }
+
+    /// At-the-money strike in the given convention. The delta-neutral
+    /// strike depends on whether the delta is premium-adjusted.
+    #[must_use]
+    pub fn atm_strike(
+        &self,
+        volatility: f64,
+        atm: FxAtmConvention,
+        convention: FxDeltaConvention,
+    ) -> f64 {
+        let variance = volatility * volatility * self.time_to_expiry;
+
+        match atm {
+            FxAtmConvention::Spot => self.spot,
+            FxAtmConvention::Forward => self.forward(),
+            FxAtmConvention::DeltaNeutral if convention.is_premium_adjusted() => {
+                self.forward() * (-0.5 * variance).exp()
+            }
+            FxAtmConvention::DeltaNeutral => self.forward() * (0.5 * variance).exp(),
+        }
+    }
+
+    // Ratio of a spot delta to the corresponding forward delta.
+    fn spot_factor(&self, convention: FxDeltaConvention) -> f64 {
+        match convention {
+            FxDeltaConvention::Spot | FxDeltaConvention::PremiumAdjustedSpot => {
+                (-self.foreign_rate * self.time_to_expiry).exp()
+            }
+            FxDeltaConvention::Forward | FxDeltaConvention::PremiumAdjustedForward => 1.0,
+        }
+    }
+}
+
+const fn sign(option_type: TypeFlag) -> f64 {
+    match option_type {
+        TypeFlag::Call => 1.0,
+        TypeFlag::Put => -1.0,
+    }
+}
+
+fn inverse_norm_cdf(p: f64) -> f64 {
+    -SQRT_2 * erfc_inv(2.0 * p)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_fx {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const FX: GarmanKohlhagen = GarmanKohlhagen::new(1.3465, 0.0294, 0.0346, 1.0);
+
+    const CONVENTIONS: [FxDeltaConvention; 4] = [
+        FxDeltaConvention::Spot,
+        FxDeltaConvention::Forward,
+        FxDeltaConvention::PremiumAdjustedSpot,
+        FxDeltaConvention::PremiumAdjustedForward,
+    ];
+
+    #[test]
+    fn test_strike_from_delta() {
+        for convention in CONVENTIONS {
+            for (delta, option_type) in [
+                (0.25, TypeFlag::Call),
+                (0.1, TypeFlag::Call),
+                (-0.25, TypeFlag::Put),
+                (-0.1, TypeFlag::Put),
+            ] {
+                let strike = FX
+                    .strike_from_delta(delta, 0.12, option_type, convention)
+                    .unwrap();
+
+                assert_approx_equal!(FX.delta(strike, 0.12, option_type, convention), delta, 1e-9);
+            }
+        }
+
+        // The premium reduces the hedge, so adjusted call strikes are lower.
+        let unadjusted = FX
+            .strike_from_delta(0.25, 0.12, TypeFlag::Call, FxDeltaConvention::Spot)
+            .unwrap();
+        let adjusted = FX
+            .strike_from_delta(
+                0.25,
+                0.12,
+                TypeFlag::Call,
+                FxDeltaConvention::PremiumAdjustedSpot,
+            )
+            .unwrap();
+        assert!(adjusted < unadjusted);
+    }
+
+    #[test]
+    fn test_delta_consistency() {
+        let (strike, v) = (1.40, 0.12);
+        let greeks = FX.greeks(strike, v, TypeFlag::Call);
+
+        // Spot delta is the Garman-Kohlhagen delta, and the adjusted delta
+        // subtracts the premium in foreign currency.
+        assert_approx_equal!(
+            FX.delta(strike, v, TypeFlag::Call, FxDeltaConvention::Spot),
+            greeks.delta,
+            1e-12
+        );
+        assert_approx_equal!(
+            FX.delta(
+                strike,
+                v,
+                TypeFlag::Call,
+                FxDeltaConvention::PremiumAdjustedSpot
+            ),
+            greeks.delta - greeks.price / FX.spot,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_atm_delta_neutral() {
+        for convention in CONVENTIONS {
+            let strike = FX.atm_strike(0.12, FxAtmConvention::DeltaNeutral, convention);
+
+            assert_approx_equal!(
+                FX.delta(strike, 0.12, TypeFlag::Call, convention)
+                    + FX.delta(strike, 0.12, TypeFlag::Put, convention),
+                0.0,
+                1e-12
+            );
+        }
+
+        assert_approx_equal!(
+            FX.atm_strike(0.12, FxAtmConvention::Forward, FxDeltaConvention::Spot),
+            FX.forward(),
+            1e-15
+        );
+    }
+
+    #[test]
+    fn test_invalid_deltas() {
+        let strike = |delta, option_type, convention| {
+            FX.strike_from_delta(delta, 0.12, option_type, convention)
+        };
+
+        assert_eq!(
+            strike(-0.25, TypeFlag::Call, FxDeltaConvention::Spot),
+            Err(FxOptionError::InvalidDelta)
+        );
+        assert_eq!(
+            strike(0.99, TypeFlag::Call, FxDeltaConvention::Spot),
+            Err(FxOptionError::InvalidDelta)
+        );
+        assert_eq!(
+            strike(
+                0.99,
+                TypeFlag::Call,
+                FxDeltaConvention::PremiumAdjustedForward
+            ),
+            Err(FxOptionError::DeltaNotAttainable)
+        );
+        assert!(strike(
+            -1.2,
+            TypeFlag::Put,
+            FxDeltaConvention::PremiumAdjustedForward
+        )
+        .is_ok());
+    }
+}
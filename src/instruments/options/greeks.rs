@@ -10,6 +10,7 @@
 use time::OffsetDateTime;
 
 use crate::instruments::options::european::EuropeanOption;
+use crate::instruments::options::TypeFlag;
 use crate::statistics::distributions::{Distribution, Gaussian};
 use crate::time::{DayCountConvention, DayCounter};
 
@@ -41,6 +42,138 @@ pub struct Greeks {
     pub Zeta: (f64, f64),
 }
 
+/// Price and Greeks of a single European option.
+///
+/// Time sensitivities are with respect to calendar time, i.e. `-dV/dT`.
+/// Second order Greeks are `None` where a pricer does not provide them.
+#[derive(Debug, Clone, Copy)]
+pub struct OptionGreeks {
+    /// Option price.
+    pub price: f64,
+    /// Sensitivity to the price of the underlying.
+    pub delta: f64,
+    /// Second order sensitivity to the price of the underlying.
+    pub gamma: f64,
+    /// Sensitivity to the volatility.
+    pub vega: f64,
+    /// Sensitivity to the passage of time, `-dV/dT`.
+    pub theta: f64,
+    /// Sensitivity to the (domestic) risk-free rate.
+    pub rho: f64,
+    /// Sensitivity of delta to the volatility, `d2V/dSdsigma`.
+    pub vanna: Option<f64>,
+    /// Sensitivity of vega to the volatility, `d2V/dsigma2`.
+    pub volga: Option<f64>,
+    /// Sensitivity of delta to the passage of time, `-d2V/dSdT`.
+    pub charm: Option<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// CLOSED-FORM GREEKS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Black-Scholes-Merton price and Greeks of a European option on a stock
+/// with continuous dividend yield `q`.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let greeks = black_scholes_greeks(100.0, 100.0, 1.0, 0.05, 0.0, 0.2, TypeFlag::Call);
+///
+/// assert!((greeks.price - 10.450_583_572).abs() < 1e-8);
+/// ```
+#[must_use]
+pub fn black_scholes_greeks(
+    S: f64,
+    K: f64,
+    T: f64,
+    r: f64,
+    q: f64,
+    v: f64,
+    option_type: TypeFlag,
+) -> OptionGreeks {
+    generalised_greeks(S, K, T, r, r - q, v, option_type)
+}
+
+/// Black (1976) price and Greeks of a European option on a forward `F`.
+///
+/// Delta and gamma are with respect to the forward, and rho holds the
+/// forward fixed.
+#[must_use]
+pub fn black76_greeks(
+    F: f64,
+    K: f64,
+    T: f64,
+    r: f64,
+    v: f64,
+    option_type: TypeFlag,
+) -> OptionGreeks {
+    let greeks = generalised_greeks(F, K, T, r, 0.0, v, option_type);
+
+    OptionGreeks {
+        rho: -T * greeks.price,
+        ..greeks
+    }
+}
+
+/// Garman-Kohlhagen (1983) price and Greeks of a European currency option,
+/// with domestic rate `r_d` and foreign rate `r_f`.
+///
+/// Rho is with respect to the domestic rate.
+#[must_use]
+pub fn garman_kohlhagen_greeks(
+    S: f64,
+    K: f64,
+    T: f64,
+    r_d: f64,
+    r_f: f64,
+    v: f64,
+    option_type: TypeFlag,
+) -> OptionGreeks {
+    generalised_greeks(S, K, T, r_d, r_d - r_f, v, option_type)
+}
+
+// Generalised Black-Scholes price and Greeks with cost of carry `b`.
+// Rho moves the cost of carry with the rate.
+pub(crate) fn generalised_greeks(
+    S: f64,
+    K: f64,
+    T: f64,
+    r: f64,
+    b: f64,
+    v: f64,
+    option_type: TypeFlag,
+) -> OptionGreeks {
+    let theta = match option_type {
+        TypeFlag::Call => 1.0,
+        TypeFlag::Put => -1.0,
+    };
+    let norm = Gaussian::default();
+
+    let std = v * T.sqrt();
+    let d1 = ((S / K).ln() + b * T) / std + 0.5 * std;
+    let d2 = d1 - std;
+    let (carry, df) = (((b - r) * T).exp(), (-r * T).exp());
+
+    let price = theta * (S * carry * norm.cdf(theta * d1) - K * df * norm.cdf(theta * d2));
+    let delta = theta * carry * norm.cdf(theta * d1);
+    let vega = S * carry * norm.pdf(d1) * T.sqrt();
+
+    OptionGreeks {
+        price,
+        delta,
+        gamma: carry * norm.pdf(d1) / (S * std),
+        vega,
+        theta: -S * carry * norm.pdf(d1) * v / (2.0 * T.sqrt())
+            - (b - r) * S * delta
+            - r * theta * K * df * norm.cdf(theta * d2),
+        rho: theta * T * K * df * norm.cdf(theta * d2),
+        vanna: Some(-carry * norm.pdf(d1) * d2 / v),
+        volga: Some(vega * d1 * d2 / v),
+        charm: Some(-(b - r) * delta - carry * norm.pdf(d1) * (b / std - d2 / (2.0 * T))),
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // GREEKS IMPLEMENTATION
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -234,4 +367,56 @@ mod tests_greeks {
             assert!(g.Zeta.1 > 0.0);
         }
     }
+
+    #[test]
+    fn test_black76_and_garman_kohlhagen() {
+        // Values from Haug.
+        let black76 = black76_greeks(19.0, 19.0, 0.75, 0.1, 0.28, TypeFlag::Put);
+        assert_approx_equal!(black76.price, 1.7011, 1e-4);
+
+        let garman_kohlhagen =
+            garman_kohlhagen_greeks(1.56, 1.6, 0.5, 0.06, 0.08, 0.12, TypeFlag::Call);
+        assert_approx_equal!(garman_kohlhagen.price, 0.0291, 1e-4);
+    }
+
+    #[test]
+    fn test_greeks_finite_differences() {
+        const H: f64 = 1e-4;
+
+        type Pricer = fn(f64, f64, f64, f64, f64, f64, TypeFlag) -> OptionGreeks;
+
+        // The fifth argument is the dividend yield or foreign rate, where used.
+        let pricers: [Pricer; 3] = [
+            |S, K, T, r, q, v, o| black_scholes_greeks(S, K, T, r, q, v, o),
+            |S, K, T, r, _, v, o| black76_greeks(S, K, T, r, v, o),
+            |S, K, T, r, r_f, v, o| garman_kohlhagen_greeks(S, K, T, r, r_f, v, o),
+        ];
+
+        for pricer in pricers {
+            for option_type in [TypeFlag::Call, TypeFlag::Put] {
+                let g =
+                    |S: f64, T: f64, r: f64, v: f64| pricer(S, 95.0, T, r, 0.02, v, option_type);
+                let (S, T, r, v) = (100.0, 0.8, 0.05, 0.25);
+                let greeks = g(S, T, r, v);
+
+                let delta = (g(S + H, T, r, v).price - g(S - H, T, r, v).price) / (2.0 * H);
+                let gamma = (g(S + H, T, r, v).delta - g(S - H, T, r, v).delta) / (2.0 * H);
+                let vega = (g(S, T, r, v + H).price - g(S, T, r, v - H).price) / (2.0 * H);
+                let theta = -(g(S, T + H, r, v).price - g(S, T - H, r, v).price) / (2.0 * H);
+                let rho = (g(S, T, r + H, v).price - g(S, T, r - H, v).price) / (2.0 * H);
+                let vanna = (g(S, T, r, v + H).delta - g(S, T, r, v - H).delta) / (2.0 * H);
+                let volga = (g(S, T, r, v + H).vega - g(S, T, r, v - H).vega) / (2.0 * H);
+                let charm = -(g(S, T + H, r, v).delta - g(S, T - H, r, v).delta) / (2.0 * H);
+
+                assert_approx_equal!(greeks.delta, delta, 1e-7);
+                assert_approx_equal!(greeks.gamma, gamma, 1e-7);
+                assert_approx_equal!(greeks.vega, vega, 1e-6);
+                assert_approx_equal!(greeks.theta, theta, 1e-6);
+                assert_approx_equal!(greeks.rho, rho, 1e-6);
+                assert_approx_equal!(greeks.vanna.unwrap(), vanna, 1e-6);
+                assert_approx_equal!(greeks.volga.unwrap(), volga, 1e-5);
+                assert_approx_equal!(greeks.charm.unwrap(), charm, 1e-6);
+            }
+        }
+    }
 }
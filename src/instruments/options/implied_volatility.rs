@@ -0,0 +1,528 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Implied volatility of Black-Scholes and Black-76 prices.
+//!
+//! The inversion follows Jäckel, "Let's Be Rational" (2015) and its
+//! predecessor "By Implication" (2006). Prices are normalised by the
+//! discounted geometric mean of the forward and the strike, and in-the-money
+//! options are reduced to out-of-the-money calls, so that with
+//! `x = ln(F / K) <= 0` and `s = v sqrt(T)` the normalised price is
+//!
+//! $$
+//! b(x, s) = e^{x/2} N\left(\frac{x}{s} + \frac{s}{2}\right) - e^{-x/2} N\left(\frac{x}{s} - \frac{s}{2}\right)
+//! $$
+//!
+//! The volatility axis is split at the inflection point `s_c = sqrt(2|x|)`
+//! and at the two points where the tangent at `s_c` reaches the price
+//! bounds. Each of the three branches has its own initial guess, and the
+//! price is matched with Newton iterations on a transformed objective
+//! (logarithm of the price on the lower branch, logarithm of the distance to
+//! the upper bound on the upper branch), which are kept inside a bracket
+//! of the root so that convergence is guaranteed.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::TypeFlag;
+use crate::math::{Newton, RootFinder, RootFindingError, ScalarFunction};
+use statrs::function::erf::{erfc, erfc_inv};
+use std::f64::consts::{PI, SQRT_2};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ERRORS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Implied volatility errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ImpliedVolatilityError {
+    /// The forward (or spot), strike, time to maturity or discount factor is not positive.
+    #[error("Forward, strike, time to maturity and discount factor must be positive")]
+    NonPositiveInput,
+
+    /// The price is below the (discounted) intrinsic value.
+    #[error("Price is below the intrinsic value")]
+    BelowIntrinsic,
+
+    /// The price is at or above the upper bound (the discounted forward for a
+    /// call, the discounted strike for a put).
+    #[error("Price is above the maximum attainable price")]
+    AboveMaximum,
+
+    /// The volatility matching the price was not found.
+    #[error(transparent)]
+    RootFinding(#[from] RootFindingError),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Black-Scholes implied volatility of a European option on a non-dividend
+/// paying asset.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let v = implied_volatility(10.450_583_572_185_565, 100.0, 100.0, 1.0, 0.05, TypeFlag::Call);
+///
+/// assert!((v.unwrap() - 0.2).abs() < 1e-12);
+/// ```
+///
+/// # Errors
+///
+/// See [`implied_volatility_black`].
+pub fn implied_volatility(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    risk_free_rate: f64,
+    option_type: TypeFlag,
+) -> Result<f64, ImpliedVolatilityError> {
+    let discount_factor = (-risk_free_rate * time_to_maturity).exp();
+
+    implied_volatility_black(
+        price,
+        spot / discount_factor,
+        strike,
+        time_to_maturity,
+        discount_factor,
+        option_type,
+    )
+}
+
+/// Black-76 implied volatility of a European option on a forward.
+///
+/// # Errors
+///
+/// - `ImpliedVolatilityError::NonPositiveInput` if the forward, strike,
+///   time to maturity or discount factor is not positive.
+/// - `ImpliedVolatilityError::BelowIntrinsic` if the price is below the
+///   discounted intrinsic value.
+/// - `ImpliedVolatilityError::AboveMaximum` if the price is not below the
+///   discounted forward (call) or strike (put).
+pub fn implied_volatility_black(
+    price: f64,
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    discount_factor: f64,
+    option_type: TypeFlag,
+) -> Result<f64, ImpliedVolatilityError> {
+    if [forward, strike, time_to_maturity, discount_factor]
+        .iter()
+        .any(|x| x.is_nan() || *x <= 0.0)
+    {
+        return Err(ImpliedVolatilityError::NonPositiveInput);
+    }
+
+    let theta = match option_type {
+        TypeFlag::Call => 1.0,
+        TypeFlag::Put => -1.0,
+    };
+
+    let beta = price / (discount_factor * (forward * strike).sqrt());
+    let s = normalised_implied_volatility(beta, (forward / strike).ln(), theta)?;
+
+    Ok(s / time_to_maturity.sqrt())
+}
+
+/// Bachelier (normal) implied volatility of a European option on a
+/// forward, in absolute units. The forward and strike may be negative.
+///
+/// The price is increasing and convex in `s = v sqrt(T)`, and at least
+/// `s n(0) - |F - K|`, so the root is bracketed in `[0, (price + |F - K|) / n(0)]`
+/// and found by [`Newton`] iterations from the upper end.
+///
+/// # Errors
+///
+/// - `ImpliedVolatilityError::NonPositiveInput` if the time to maturity
+///   or discount factor is not positive.
+/// - `ImpliedVolatilityError::BelowIntrinsic` if the price is below the
+///   discounted intrinsic value.
+/// - `ImpliedVolatilityError::AboveMaximum` if the price is not finite.
+/// - `ImpliedVolatilityError::RootFinding` if the iterations do not converge.
+pub fn implied_volatility_bachelier(
+    price: f64,
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    discount_factor: f64,
+    option_type: TypeFlag,
+) -> Result<f64, ImpliedVolatilityError> {
+    if [forward, strike, time_to_maturity, discount_factor]
+        .iter()
+        .any(|x| x.is_nan())
+        || time_to_maturity <= 0.0
+        || discount_factor <= 0.0
+    {
+        return Err(ImpliedVolatilityError::NonPositiveInput);
+    }
+
+    let theta = match option_type {
+        TypeFlag::Call => 1.0,
+        TypeFlag::Put => -1.0,
+    };
+
+    let m = forward - strike;
+    let target = price / discount_factor;
+    let intrinsic = f64::max(theta * m, 0.0);
+
+    if target < intrinsic {
+        return Err(ImpliedVolatilityError::BelowIntrinsic);
+    }
+    if target <= intrinsic {
+        return Ok(0.0);
+    }
+    if !target.is_finite() {
+        return Err(ImpliedVolatilityError::AboveMaximum);
+    }
+
+    let objective = BachelierObjective {
+        moneyness: theta * m,
+        target,
+    };
+    let upper = (target + m.abs()) / normal_pdf(0.0);
+    let s = Newton::new(4.0 * f64::EPSILON * upper, 100).solve(&objective, 0.0, upper, upper)?;
+
+    Ok(s / time_to_maturity.sqrt())
+}
+
+/// Normalised Black price `b(x, s)` of a call (`theta = 1`) or a put
+/// (`theta = -1`), with `x = ln(F / K)` and `s = v sqrt(T)`: the undiscounted
+/// Black price divided by `sqrt(F K)`.
+#[must_use]
+pub fn normalised_black(x: f64, s: f64, theta: f64) -> f64 {
+    let intrinsic = f64::max(theta * ((0.5 * x).exp() - (-0.5 * x).exp()), 0.0);
+
+    // Put-call parity, and b(x, s, put) = b(-x, s, call).
+    intrinsic + normalised_black_call(-x.abs(), s)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// PRIVATE FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Undiscounted Bachelier price, less the target, as a function of
+// `s = v sqrt(T)`, with `moneyness = theta (F - K)`.
+struct BachelierObjective {
+    moneyness: f64,
+    target: f64,
+}
+
+impl ScalarFunction for BachelierObjective {
+    fn value(&self, s: f64) -> f64 {
+        self.derivatives(s)[0]
+    }
+
+    fn derivatives(&self, s: f64) -> [f64; 3] {
+        let m = self.moneyness;
+
+        if s <= 0.0 {
+            return [m.max(0.0) - self.target, normal_pdf(0.0), 0.0];
+        }
+
+        // Vega n(m / s), and volga n(m / s) m^2 / s^3.
+        let vega = normal_pdf(m / s);
+        let price = m * 0.5 * erfc(-m / (s * SQRT_2)) + s * vega;
+
+        [price - self.target, vega, vega * m * m / (s * s * s)]
+    }
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+// Normalised implied volatility `s = v sqrt(T)` of a normalised price.
+fn normalised_implied_volatility(
+    beta: f64,
+    x: f64,
+    theta: f64,
+) -> Result<f64, ImpliedVolatilityError> {
+    const MAX_ITERATIONS: usize = 100;
+
+    let intrinsic = f64::max(theta * ((0.5 * x).exp() - (-0.5 * x).exp()), 0.0);
+
+    // Reduce to an out-of-the-money call.
+    let x = -x.abs();
+    let beta = beta - intrinsic;
+    let b_max = (0.5 * x).exp();
+
+    if beta.is_nan() || beta < -f64::EPSILON * intrinsic.max(1.0) {
+        return Err(ImpliedVolatilityError::BelowIntrinsic);
+    }
+    if beta <= 0.0 {
+        return Ok(0.0);
+    }
+    if beta >= b_max {
+        return Err(ImpliedVolatilityError::AboveMaximum);
+    }
+
+    // At the money: b(0, s) = 2 N(s / 2) - 1.
+    if x == 0.0 {
+        return Ok(-2.0 * inverse_norm_cdf(0.5 * (1.0 - beta)));
+    }
+
+    // Inflection point, and where its tangent reaches 0 and `b_max`.
+    let s_c = (2.0 * x.abs()).sqrt();
+    let b_c = normalised_black_call(x, s_c);
+    let vega_c = normalised_vega(x, s_c);
+
+    let s_l = s_c - b_c / vega_c;
+    let s_u = s_c + (b_max - b_c) / vega_c;
+    let b_l = normalised_black_call(x, s_l);
+    let b_u = normalised_black_call(x, s_u);
+
+    let mut s = if beta < b_l {
+        // Asymptotics of b for small s: ln b ~ -x^2 / (2 s^2) - s^2 / 8.
+        (2.0 * x * x / (x.abs() - 4.0 * (beta / b_l).ln())).sqrt()
+    } else if beta > b_u {
+        // Asymptotics of b_max - b for large s: ~ 2 N(-s / 2).
+        -2.0 * inverse_norm_cdf((b_max - beta) / (b_max - b_u) * norm_cdf(-0.5 * s_u))
+    } else if beta < b_c {
+        s_l + (s_c - s_l) * (beta - b_l) / (b_c - b_l)
+    } else {
+        s_c + (s_u - s_c) * (beta - b_c) / (b_u - b_c)
+    };
+
+    // Bracket of the root (b is increasing in s).
+    let (mut lower, mut upper) = (0.0, f64::INFINITY);
+
+    for _ in 0..MAX_ITERATIONS {
+        let b = normalised_black_call(x, s);
+        let vega = normalised_vega(x, s);
+
+        if b < beta {
+            lower = s;
+        } else {
+            upper = s;
+        }
+
+        // Newton step on ln(b / beta) below the inflection point, and on
+        // ln((b_max - beta) / (b_max - b)) above it.
+        let step = if beta < b_c {
+            (b / beta).ln() * b / vega
+        } else {
+            ((b_max - beta) / (b_max - b)).ln() * (b_max - b) / vega
+        };
+
+        let mut next = s - step;
+
+        if !next.is_finite() || next <= lower || next >= upper {
+            next = if upper.is_finite() {
+                0.5 * (lower + upper)
+            } else {
+                2.0 * s
+            };
+        }
+
+        let converged = (next - s).abs() <= 4.0 * f64::EPSILON * s;
+        s = next;
+
+        if converged {
+            break;
+        }
+    }
+
+    Ok(s)
+}
+
+// Normalised out-of-the-money call price, for x <= 0.
+fn normalised_black_call(x: f64, s: f64) -> f64 {
+    if s <= 0.0 {
+        return f64::max((0.5 * x).exp() - (-0.5 * x).exp(), 0.0);
+    }
+
+    let h = x / s;
+    let t = 0.5 * s;
+
+    if h + t < 0.0 {
+        // Both terms are small: factor out the common exponential, using
+        // N(-z) = erfcx(z / sqrt(2)) exp(-z^2 / 2) / 2, to avoid underflow.
+        0.5 * (-0.5 * (h * h + t * t)).exp() * (erfcx(-(h + t) / SQRT_2) - erfcx(-(h - t) / SQRT_2))
+    } else {
+        (0.5 * x).exp() * norm_cdf(h + t) - (-0.5 * x).exp() * norm_cdf(h - t)
+    }
+}
+
+// Derivative of the normalised price with respect to s.
+fn normalised_vega(x: f64, s: f64) -> f64 {
+    let h = x / s;
+    let t = 0.5 * s;
+
+    (-0.5 * (h * h + t * t)).exp() / (2.0 * PI).sqrt()
+}
+
+// Scaled complementary error function, erfcx(x) = exp(x^2) erfc(x).
+fn erfcx(x: f64) -> f64 {
+    if x < 0.0 {
+        2.0 * (x * x).exp() - erfcx(-x)
+    } else if x < 26.0 {
+        (x * x).exp() * erfc(x)
+    } else {
+        // Asymptotic expansion.
+        let y = 1.0 / (x * x);
+        (1.0 - 0.5 * y * (1.0 - 1.5 * y * (1.0 - 2.5 * y))) / (x * PI.sqrt())
+    }
+}
+
+fn norm_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / SQRT_2)
+}
+
+// Accurate in the lower tail, unlike inverting through `erf`.
+fn inverse_norm_cdf(p: f64) -> f64 {
+    -SQRT_2 * erfc_inv(2.0 * p)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_implied_volatility {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn black(forward: f64, strike: f64, v: f64, t: f64, df: f64, option_type: TypeFlag) -> f64 {
+        let theta = match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+
+        df * (forward * strike).sqrt()
+            * normalised_black((forward / strike).ln(), v * t.sqrt(), theta)
+    }
+
+    #[test]
+    fn test_black_scholes() {
+        // Call and put on the same strike, with prices from Black-Scholes.
+        let call = implied_volatility(
+            10.450_583_572_185_565,
+            100.0,
+            100.0,
+            1.0,
+            0.05,
+            TypeFlag::Call,
+        );
+        let put = implied_volatility(
+            5.573_526_022_256_971,
+            100.0,
+            100.0,
+            1.0,
+            0.05,
+            TypeFlag::Put,
+        );
+
+        assert_approx_equal!(call.unwrap(), 0.2, 1e-12);
+        assert_approx_equal!(put.unwrap(), 0.2, 1e-12);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            for moneyness in [0.05, 0.5, 0.9, 1.0, 1.1, 2.0, 20.0] {
+                for v in [0.01, 0.1, 0.3, 1.0, 3.0] {
+                    for t in [0.01, 1.0, 10.0] {
+                        let (forward, strike, df) = (100.0, 100.0 * moneyness, 0.9);
+                        let price = black(forward, strike, v, t, df, option_type);
+
+                        // Skip prices which are (numerically) insensitive to
+                        // the volatility, e.g. deep in the money.
+                        let s = v * f64::sqrt(t);
+                        let x = f64::ln(forward / strike);
+                        if normalised_vega(-x.abs(), s) * s
+                            <= 1e-6 * price / df / (forward * strike).sqrt()
+                        {
+                            continue;
+                        }
+
+                        let implied =
+                            implied_volatility_black(price, forward, strike, t, df, option_type)
+                                .unwrap();
+
+                        assert_approx_equal!(implied, v, 1e-9 * v);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deep_out_of_the_money() {
+        // A tiny price far from the money is still resolved accurately.
+        let price = black(100.0, 300.0, 0.2, 1.0, 1.0, TypeFlag::Call);
+        assert!(price > 0.0 && price < 1e-6);
+
+        let implied =
+            implied_volatility_black(price, 100.0, 300.0, 1.0, 1.0, TypeFlag::Call).unwrap();
+        assert_approx_equal!(implied, 0.2, 1e-10);
+    }
+
+    #[test]
+    fn test_errors() {
+        let solve = |price: f64, option_type| {
+            implied_volatility_black(price, 100.0, 90.0, 1.0, 1.0, option_type)
+        };
+
+        assert_eq!(
+            solve(9.0, TypeFlag::Call),
+            Err(ImpliedVolatilityError::BelowIntrinsic)
+        );
+        assert_eq!(
+            solve(100.0, TypeFlag::Call),
+            Err(ImpliedVolatilityError::AboveMaximum)
+        );
+        assert_eq!(
+            solve(90.0, TypeFlag::Put),
+            Err(ImpliedVolatilityError::AboveMaximum)
+        );
+        assert_eq!(solve(10.0, TypeFlag::Call), Ok(0.0));
+        assert_eq!(
+            implied_volatility_black(1.0, 100.0, 90.0, 0.0, 1.0, TypeFlag::Call),
+            Err(ImpliedVolatilityError::NonPositiveInput)
+        );
+    }
+
+    #[test]
+    fn test_bachelier() {
+        let bachelier = |forward: f64, strike: f64, v: f64, t: f64, theta: f64| {
+            let s = v * t.sqrt();
+            let d = (forward - strike) / s;
+            theta * (forward - strike) * 0.5 * erfc(-theta * d / SQRT_2)
+                + s * (-0.5 * d * d).exp() / (2.0 * PI).sqrt()
+        };
+
+        for (option_type, theta) in [(TypeFlag::Call, 1.0), (TypeFlag::Put, -1.0)] {
+            for strike in [-0.02, -0.005, 0.0, 0.01, 0.05] {
+                for v in [0.001, 0.005, 0.01] {
+                    let price = 0.95 * bachelier(-0.003, strike, v, 2.0, theta);
+
+                    // Skip prices that are all intrinsic value.
+                    if price - 0.95 * f64::max(theta * (-0.003 - strike), 0.0) < 1e-12 {
+                        continue;
+                    }
+
+                    let implied =
+                        implied_volatility_bachelier(price, -0.003, strike, 2.0, 0.95, option_type)
+                            .unwrap();
+                    assert_approx_equal!(implied, v, 1e-9 * v);
+                }
+            }
+        }
+
+        assert_eq!(
+            implied_volatility_bachelier(0.001, 0.01, 0.0, 1.0, 1.0, TypeFlag::Call),
+            Err(ImpliedVolatilityError::BelowIntrinsic)
+        );
+    }
+}
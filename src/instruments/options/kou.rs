@@ -0,0 +1,335 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::{CarrMadan, OptionGreeks, TypeFlag};
+use num_complex::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Kou (2002) double exponential jump diffusion model, priced with the
+/// Carr-Madan (1999) FFT of the characteristic function.
+///
+/// $$
+/// \frac{dS_t}{S_{t-}} = (r - q - \lambda \zeta) dt + \sigma dW_t + (e^Y - 1) dN_t
+/// $$
+///
+/// where `N` is a Poisson process with intensity `lambda`, and the log jump
+/// size `Y` is exponential with rate `eta_1` upwards (with probability `p`)
+/// and with rate `eta_2` downwards (with probability `1 - p`).
+///
+/// The transform evaluates the characteristic function at `u - (alpha + 1) i`,
+/// so the up jump rate must exceed `alpha + 1` (2.5 with the default
+/// [`CarrMadan`] discretisation).
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Kou (2002).
+/// let kou = Kou::new(100.0, 0.05, 0.0, 0.5, 0.16, 1.0, 0.4, 10.0, 5.0);
+///
+/// assert!((kou.price(98.0, TypeFlag::Call) - 9.14732).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Kou {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `q` - Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `sigma` - Volatility of the diffusion.
+    pub volatility: f64,
+    /// `lambda` - Expected number of jumps per year.
+    pub jump_intensity: f64,
+    /// `p` - Probability of an up jump.
+    pub up_probability: f64,
+    /// `eta_1` - Rate of the up jumps (greater than 1).
+    pub up_rate: f64,
+    /// `eta_2` - Rate of the down jumps.
+    pub down_rate: f64,
+    /// Discretisation of the Fourier transform.
+    pub fft: CarrMadan,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Kou {
+    /// New Kou model, with the default Fourier transform discretisation.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(
+        initial_price: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        time_to_expiry: f64,
+        volatility: f64,
+        jump_intensity: f64,
+        up_probability: f64,
+        up_rate: f64,
+        down_rate: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            risk_free_rate,
+            dividend_yield,
+            time_to_expiry,
+            volatility,
+            jump_intensity,
+            up_probability,
+            up_rate,
+            down_rate,
+            fft: CarrMadan::default(),
+        }
+    }
+
+    /// Set the discretisation of the Fourier transform.
+    #[must_use]
+    pub const fn with_fft(mut self, fft: CarrMadan) -> Self {
+        self.fft = fft;
+        self
+    }
+
+    /// Mean relative jump size, `zeta = E[e^Y] - 1`.
+    #[must_use]
+    pub fn mean_jump(&self) -> f64 {
+        let (p, eta_1, eta_2) = (self.up_probability, self.up_rate, self.down_rate);
+
+        p * eta_1 / (eta_1 - 1.0) + (1.0 - p) * eta_2 / (eta_2 + 1.0) - 1.0
+    }
+
+    /// Characteristic function of `ln S_T`, `E[exp(i u ln S_T)]`.
+    #[must_use]
+    pub fn characteristic_function(&self, u: Complex<f64>) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+        let (p, eta_1, eta_2) = (self.up_probability, self.up_rate, self.down_rate);
+        let (v, lambda) = (self.volatility, self.jump_intensity);
+        let T = self.time_to_expiry;
+
+        let drift = self.risk_free_rate - self.dividend_yield - lambda * self.mean_jump();
+        let jumps = p * eta_1 / (eta_1 - i * u) + (1.0 - p) * eta_2 / (eta_2 + i * u) - 1.0;
+        let exponent = i * u * (drift - 0.5 * v * v) - 0.5 * v * v * u * u + lambda * jumps;
+
+        (i * u * self.initial_price.ln() + exponent * T).exp()
+    }
+
+    /// Call prices on the log-strike grid of the transform, centred on the
+    /// log-strike `centre`. Returns a tuple: `(log_strikes, call_prices)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of points is zero.
+    #[must_use]
+    pub fn call_grid(&self, centre: f64) -> (Vec<f64>, Vec<f64>) {
+        let df = (-self.risk_free_rate * self.time_to_expiry).exp();
+
+        self.fft
+            .call_grid(|u| self.characteristic_function(u), centre, df)
+    }
+
+    /// Prices of European calls or puts for a grid of strikes, from a
+    /// single transform centred on the initial price.
+    ///
+    /// Prices are interpolated linearly in log-strike between the points
+    /// of the transform. Put prices follow from put-call parity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of points is zero, or if a strike
+    /// is outside the log-strike grid of the transform.
+    #[must_use]
+    pub fn prices(&self, strikes: &[f64], option_type: TypeFlag) -> Vec<f64> {
+        let (log_strikes, calls) = self.call_grid(self.initial_price.ln());
+
+        strikes
+            .iter()
+            .map(|&strike| {
+                let call = CarrMadan::interpolate(&log_strikes, &calls, strike);
+                self.put_call_parity(call, strike, option_type)
+            })
+            .collect()
+    }
+
+    /// Price of a European call or put, from a transform centred on the
+    /// strike, so that no interpolation is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of points is zero.
+    #[must_use]
+    pub fn price(&self, strike: f64, option_type: TypeFlag) -> f64 {
+        let (_, calls) = self.call_grid(strike.ln());
+
+        self.put_call_parity(calls[self.fft.points / 2], strike, option_type)
+    }
+
+    /// Price and Greeks of a European call or put.
+    ///
+    /// Delta and gamma follow from the derivatives of the call price in
+    /// log-strike on the grid of the transform, since the call price is
+    /// homogeneous of degree one in the initial price and the strike.
+    /// Vega, theta and rho are computed by central differences, and the
+    /// second order Greeks are not provided.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of points is zero.
+    #[must_use]
+    pub fn greeks(&self, strike: f64, option_type: TypeFlag) -> OptionGreeks {
+        const BUMP: f64 = 1e-4;
+
+        let S = self.initial_price;
+        let (log_strikes, calls) = self.call_grid(strike.ln());
+        let n = self.fft.points / 2;
+
+        let dk = log_strikes[1] - log_strikes[0];
+        let dc_dk = (calls[n + 1] - calls[n - 1]) / (2.0 * dk);
+        let d2c_dk2 = (calls[n + 1] - 2.0 * calls[n] + calls[n - 1]) / (dk * dk);
+
+        let call_delta = (calls[n] - dc_dk) / S;
+        let delta = match option_type {
+            TypeFlag::Call => call_delta,
+            TypeFlag::Put => call_delta - (-self.dividend_yield * self.time_to_expiry).exp(),
+        };
+
+        let bump = |f: &dyn Fn(f64) -> Self, x: f64| {
+            (f(x + BUMP).price(strike, option_type) - f(x - BUMP).price(strike, option_type))
+                / (2.0 * BUMP)
+        };
+
+        OptionGreeks {
+            price: self.put_call_parity(calls[n], strike, option_type),
+            delta,
+            gamma: (d2c_dk2 - dc_dk) / (S * S),
+            vega: bump(
+                &|x| Self {
+                    volatility: x,
+                    ..*self
+                },
+                self.volatility,
+            ),
+            theta: -bump(
+                &|x| Self {
+                    time_to_expiry: x,
+                    ..*self
+                },
+                self.time_to_expiry,
+            ),
+            rho: bump(
+                &|x| Self {
+                    risk_free_rate: x,
+                    ..*self
+                },
+                self.risk_free_rate,
+            ),
+            vanna: None,
+            volga: None,
+            charm: None,
+        }
+    }
+
+    // Put price from the call price, by put-call parity.
+    fn put_call_parity(&self, call: f64, strike: f64, option_type: TypeFlag) -> f64 {
+        let T = self.time_to_expiry;
+
+        match option_type {
+            TypeFlag::Call => call,
+            TypeFlag::Put => {
+                call - self.initial_price * (-self.dividend_yield * T).exp()
+                    + strike * (-self.risk_free_rate * T).exp()
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_kou {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::MertonJumpDiffusion;
+
+    #[test]
+    fn test_kou_price() {
+        // Kou (2002), Table 1.
+        let kou = Kou::new(100.0, 0.05, 0.0, 0.5, 0.16, 1.0, 0.4, 10.0, 5.0);
+
+        assert_approx_equal!(kou.price(98.0, TypeFlag::Call), 9.14732, 1e-4);
+
+        // Interpolated prices agree with the prices at the centre of the grid.
+        let strikes = [80.0, 98.0, 120.0];
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            for (strike, price) in strikes.iter().zip(kou.prices(&strikes, option_type)) {
+                assert_approx_equal!(price, kou.price(*strike, option_type), 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kou_without_jumps() {
+        // No jumps: the Merton series reduces to Black-Scholes.
+        let kou = Kou::new(100.0, 0.05, 0.02, 1.0, 0.25, 0.0, 0.4, 10.0, 5.0);
+        let bs = MertonJumpDiffusion::new(100.0, 0.05, 0.02, 1.0, 0.25, 0.0, 0.0, 0.0);
+
+        for strike in [70.0, 100.0, 130.0] {
+            for option_type in [TypeFlag::Call, TypeFlag::Put] {
+                assert_approx_equal!(
+                    kou.price(strike, option_type),
+                    bs.price(strike, option_type),
+                    1e-6
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_kou_greeks() {
+        let kou = Kou::new(100.0, 0.05, 0.01, 0.75, 0.2, 2.0, 0.3, 25.0, 10.0);
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            for strike in [85.0, 100.0, 115.0] {
+                let greeks = kou.greeks(strike, option_type);
+                let price = |s: f64| {
+                    Kou {
+                        initial_price: s,
+                        ..kou
+                    }
+                    .price(strike, option_type)
+                };
+
+                let delta = (price(100.01) - price(99.99)) / 0.02;
+                let gamma = (price(100.1) - 2.0 * price(100.0) + price(99.9)) / 0.01;
+
+                assert_approx_equal!(greeks.price, price(100.0), 1e-12);
+                assert_approx_equal!(greeks.delta, delta, 1e-4);
+                assert_approx_equal!(greeks.gamma, gamma, 1e-4);
+            }
+        }
+
+        // Put-call parity of the bumped Greeks.
+        let (call, put) = (
+            kou.greeks(100.0, TypeFlag::Call),
+            kou.greeks(100.0, TypeFlag::Put),
+        );
+        assert_approx_equal!(call.vega, put.vega, 1e-6);
+        assert_approx_equal!(
+            call.rho - put.rho,
+            0.75 * 100.0 * (-0.05 * 0.75_f64).exp(),
+            1e-6
+        );
+    }
+}
@@ -0,0 +1,350 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Ladder options.
+//!
+//! A ladder option locks in the intrinsic value at the highest rung (for a
+//! call) or the lowest rung (for a put) reached by the underlying:
+//!
+//! - Call: `max(S_T - K, L - K, 0)`, where `L` is the highest rung reached.
+//! - Put: `max(K - S_T, K - L, 0)`, where `L` is the lowest rung reached.
+//!
+//! The payoff is a vanilla option plus a strip of knock-in options, one per
+//! rung: with `L_0 = K` and the rungs `L_1, L_2, ...` moving away from the
+//! strike, the call is
+//!
+//! $$
+//! (S_T - K)^+ + \sum_i 1_{\{M_T \geq L_i\}} \left[ (L_i - S_T)^+ - (L_{i-1} - S_T)^+ \right]
+//! $$
+//!
+//! where $M_T$ is the running maximum, and similarly for the put.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{BarrierOption, BarrierType, TypeFlag};
+use crate::statistics::distributions::{Distribution, Gaussian};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Ladder option parameters.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let ladder = LadderOption::new(100.0, 100.0, vec![110.0, 120.0], 0.05, 0.2, 1.0, 0.0, TypeFlag::Call);
+///
+/// // Worth more than the vanilla call (10.45) thanks to the rungs.
+/// assert!(ladder.price_analytic() > 10.45);
+/// ```
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub struct LadderOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// Rungs of the ladder. Only the rungs above the strike (for a call) or
+    /// below the strike (for a put) affect the payoff.
+    pub rungs: Vec<f64>,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `v` - Volatility parameter.
+    pub volatility: f64,
+    /// `T` - Time to expiry/maturity.
+    pub time_to_maturity: f64,
+    /// `q` - dividend yield.
+    pub dividend_yield: f64,
+    /// Call or put flag.
+    pub option_type: TypeFlag,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl LadderOption {
+    /// New ladder option.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        rungs: Vec<f64>,
+        risk_free_rate: f64,
+        volatility: f64,
+        time_to_maturity: f64,
+        dividend_yield: f64,
+        option_type: TypeFlag,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            rungs,
+            risk_free_rate,
+            volatility,
+            time_to_maturity,
+            dividend_yield,
+            option_type,
+        }
+    }
+
+    /// Closed-form price of the continuously monitored ladder option, as a
+    /// vanilla option plus a strip of knock-in barrier options.
+    ///
+    /// Rungs already reached by the initial price count as locked in.
+    #[must_use]
+    pub fn price_analytic(&self) -> f64 {
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let t = self.time_to_maturity;
+
+        // Knock-in puts (up-and-in) for a call, knock-in calls (down-and-in) for a put.
+        let (in_type, in_vanilla_type) = match self.option_type {
+            TypeFlag::Call => (BarrierType::PUI, TypeFlag::Put),
+            TypeFlag::Put => (BarrierType::CDI, TypeFlag::Call),
+        };
+
+        let barrier = |strike: f64, rung: f64| BarrierOption {
+            initial_price: S,
+            strike_price: strike,
+            barrier: rung,
+            time_to_expiry: t,
+            risk_free_rate: self.risk_free_rate,
+            volatility: self.volatility,
+            rebate: 0.0,
+            dividend_yield: self.dividend_yield,
+        };
+
+        // Knock-in option struck at `strike`, triggered by reaching `rung`.
+        let knock_in = |strike: f64, rung: f64| {
+            let reached = match self.option_type {
+                TypeFlag::Call => S >= rung,
+                TypeFlag::Put => S <= rung,
+            };
+
+            if reached {
+                self.vanilla(strike, in_vanilla_type)
+            } else {
+                barrier(strike, rung).price(in_type)
+            }
+        };
+
+        let mut price = self.vanilla(K, self.option_type);
+        let mut previous = K;
+
+        for rung in self.sorted_rungs() {
+            price += knock_in(rung, rung) - knock_in(previous, rung);
+            previous = rung;
+        }
+
+        price
+    }
+
+    /// Monte Carlo price of the ladder option, with the rungs monitored at
+    /// `n_steps` equally spaced dates (the last one at expiry).
+    ///
+    /// Returns a tuple: `(price, standard_error)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_steps` is zero or `n_sims` is less than 2.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_simulated(&self, n_steps: usize, n_sims: usize, seed: u64) -> (f64, f64) {
+        assert!(n_steps > 0, "At least one monitoring date is required.");
+        assert!(n_sims > 1, "At least two paths are required.");
+
+        let v = self.volatility;
+        let dt = self.time_to_maturity / n_steps as f64;
+        let drift = (self.risk_free_rate - self.dividend_yield - 0.5 * v * v) * dt;
+        let diffusion = v * dt.sqrt();
+        let df = (-self.risk_free_rate * self.time_to_maturity).exp();
+
+        let rungs = self.sorted_rungs();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let payoffs: Vec<f64> = (0..n_sims)
+            .map(|_| {
+                let mut log_s = self.initial_price.ln();
+                let mut extremum = self.initial_price;
+
+                for _ in 0..n_steps {
+                    let z: f64 = rng.sample(StandardNormal);
+                    log_s += drift + diffusion * z;
+
+                    extremum = match self.option_type {
+                        TypeFlag::Call => extremum.max(log_s.exp()),
+                        TypeFlag::Put => extremum.min(log_s.exp()),
+                    };
+                }
+
+                df * self.payoff(log_s.exp(), extremum, &rungs)
+            })
+            .collect();
+
+        let n = n_sims as f64;
+        let mean = payoffs.iter().sum::<f64>() / n;
+        let variance = payoffs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        (mean, (variance / n).sqrt())
+    }
+
+    // Payoff given the terminal price and the extremum (maximum for a
+    // call, minimum for a put) of the underlying.
+    fn payoff(&self, s_t: f64, extremum: f64, rungs: &[f64]) -> f64 {
+        let K = self.strike_price;
+
+        match self.option_type {
+            TypeFlag::Call => {
+                let locked = rungs
+                    .iter()
+                    .filter(|&&l| extremum >= l)
+                    .fold(K, |a, &l| a.max(l));
+                f64::max(s_t.max(locked) - K, 0.0)
+            }
+            TypeFlag::Put => {
+                let locked = rungs
+                    .iter()
+                    .filter(|&&l| extremum <= l)
+                    .fold(K, |a, &l| a.min(l));
+                f64::max(K - s_t.min(locked), 0.0)
+            }
+        }
+    }
+
+    // Rungs that affect the payoff, ordered away from the strike.
+    fn sorted_rungs(&self) -> Vec<f64> {
+        let K = self.strike_price;
+
+        let mut rungs: Vec<f64> = match self.option_type {
+            TypeFlag::Call => self.rungs.iter().copied().filter(|&l| l > K).collect(),
+            TypeFlag::Put => self.rungs.iter().copied().filter(|&l| l < K).collect(),
+        };
+
+        rungs.sort_by(f64::total_cmp);
+        if let TypeFlag::Put = self.option_type {
+            rungs.reverse();
+        }
+        rungs.dedup();
+
+        rungs
+    }
+
+    // Black-Scholes-Merton price of a vanilla option.
+    fn vanilla(&self, strike: f64, option_type: TypeFlag) -> f64 {
+        let S = self.initial_price;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let t = self.time_to_maturity;
+        let b = r - self.dividend_yield;
+
+        let norm = Gaussian::default();
+
+        let d1 = ((S / strike).ln() + (b + v * v / 2.0) * t) / (v * t.sqrt());
+        let d2 = d1 - v * t.sqrt();
+
+        match option_type {
+            TypeFlag::Call => {
+                S * ((b - r) * t).exp() * norm.cdf(d1) - strike * (-r * t).exp() * norm.cdf(d2)
+            }
+            TypeFlag::Put => {
+                strike * (-r * t).exp() * norm.cdf(-d2) - S * ((b - r) * t).exp() * norm.cdf(-d1)
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_ladder {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::{LookbackOption, LookbackStrike};
+
+    fn ladder(rungs: Vec<f64>, option_type: TypeFlag) -> LadderOption {
+        LadderOption::new(100.0, 100.0, rungs, 0.05, 0.2, 1.0, 0.01, option_type)
+    }
+
+    #[test]
+    fn test_ladder_payoff() {
+        let call = ladder(vec![110.0, 120.0, 90.0], TypeFlag::Call);
+        let rungs = call.sorted_rungs();
+
+        assert_eq!(rungs, vec![110.0, 120.0]);
+        assert_approx_equal!(call.payoff(105.0, 115.0, &rungs), 10.0, 1e-12);
+        assert_approx_equal!(call.payoff(125.0, 130.0, &rungs), 25.0, 1e-12);
+        assert_approx_equal!(call.payoff(95.0, 105.0, &rungs), 0.0, 1e-12);
+
+        let put = ladder(vec![90.0, 80.0], TypeFlag::Put);
+        let rungs = put.sorted_rungs();
+
+        assert_eq!(rungs, vec![90.0, 80.0]);
+        assert_approx_equal!(put.payoff(105.0, 85.0, &rungs), 10.0, 1e-12);
+    }
+
+    #[test]
+    fn test_ladder_limits() {
+        // No rungs: a vanilla option.
+        let call = ladder(vec![], TypeFlag::Call);
+        assert_approx_equal!(
+            call.price_analytic(),
+            call.vanilla(100.0, TypeFlag::Call),
+            1e-12
+        );
+
+        // Many rungs: the fixed strike lookback option.
+        let lookback = LookbackOption {
+            initial_price: 100.0,
+            s_max: 100.0,
+            s_min: 100.0,
+            time_to_maturity: 1.0,
+            risk_free_rate: 0.05,
+            dividend_yield: 0.01,
+            volatility: 0.2,
+            strike_price: Some(100.0),
+            strike_type: LookbackStrike::Fixed,
+        }
+        .price_analytic();
+
+        // The ladder locks in the rung below the maximum: slightly cheaper.
+        let rungs: Vec<f64> = (1..=1000).map(|i| 100.0 + 0.1 * f64::from(i)).collect();
+        let call = ladder(rungs, TypeFlag::Call).price_analytic();
+        assert!(call < lookback.0);
+        assert_approx_equal!(call, lookback.0, 0.1);
+
+        let rungs: Vec<f64> = (1..=999).map(|i| 100.0 - 0.1 * f64::from(i)).collect();
+        let put = ladder(rungs, TypeFlag::Put).price_analytic();
+        assert!(put < lookback.1);
+        assert_approx_equal!(put, lookback.1, 0.1);
+    }
+
+    #[test]
+    fn test_ladder_monte_carlo() {
+        for (rungs, option_type) in [
+            (vec![110.0, 120.0, 130.0], TypeFlag::Call),
+            (vec![95.0, 85.0], TypeFlag::Put),
+        ] {
+            let option = ladder(rungs, option_type);
+            let (mc, std_err) = option.price_simulated(250, 10_000, 42);
+
+            // Discrete monitoring reaches fewer rungs than continuous monitoring.
+            assert!(mc < option.price_analytic() + 2.0 * std_err);
+            assert_approx_equal!(mc, option.price_analytic(), 0.1 + 4.0 * std_err);
+        }
+    }
+}
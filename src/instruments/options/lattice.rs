@@ -0,0 +1,652 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Lattice (tree) option pricing engine.
+//!
+//! Supported lattices:
+//! - Cox-Ross-Rubinstein (1979) binomial tree.
+//! - Jarrow-Rudd (1983) equal-probability binomial tree.
+//! - Tian (1993) moment-matching binomial tree.
+//! - Boyle (1986) trinomial tree.
+//!
+//! Discrete (cash) dividends are handled with the escrowed dividend model:
+//! the tree is built for the underlying price less the present value of the
+//! dividends paid before expiry, which is added back at each node to obtain
+//! the exercise value.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{ExerciseFlag, TypeFlag};
+use crate::time::Schedule;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Lattice used to discretise the underlying price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeModel {
+    /// Cox-Ross-Rubinstein binomial tree (`u = 1 / d`).
+    CoxRossRubinstein,
+    /// Jarrow-Rudd binomial tree (equal up and down probabilities).
+    JarrowRudd,
+    /// Tian binomial tree (matches the first three moments).
+    Tian,
+    /// Boyle trinomial tree.
+    Trinomial,
+}
+
+/// Option contract priced by the lattice engine.
+#[derive(Debug, Clone)]
+pub struct LatticeOption {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `q` - Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility parameter.
+    pub volatility: f64,
+    /// Discrete cash dividends, as `(time, amount)` pairs.
+    pub dividends: Vec<(f64, f64)>,
+    /// Call or put flag.
+    pub option_type: TypeFlag,
+    /// European, American or Bermudan exercise.
+    pub exercise: ExerciseFlag,
+    /// Exercise dates (in years) of a Bermudan option. Exercise is also
+    /// allowed at expiry.
+    pub exercise_dates: Vec<f64>,
+}
+
+/// Price and Greeks computed by bumping the inputs and re-pricing on the tree.
+#[derive(Debug, Clone, Copy)]
+pub struct LatticeGreeks {
+    /// Option price.
+    pub price: f64,
+    /// Sensitivity to the underlying price.
+    pub delta: f64,
+    /// Second order sensitivity to the underlying price.
+    pub gamma: f64,
+    /// Sensitivity to the volatility.
+    pub vega: f64,
+    /// Sensitivity to the passage of time (per year).
+    pub theta: f64,
+    /// Sensitivity to the risk-free rate.
+    pub rho: f64,
+}
+
+/// Early-exercise boundary of an American or Bermudan option: the critical
+/// underlying price at each exercise time, above which a call (below which
+/// a put) is exercised.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseBoundary {
+    /// Exercise times (in years), in increasing order.
+    pub times: Vec<f64>,
+    /// Critical underlying price at each exercise time, or `None` if no
+    /// node is exercised at that time.
+    pub critical_prices: Vec<Option<f64>>,
+}
+
+/// Lattice pricing engine.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let option = LatticeOption::new(
+///     36.0, 40.0, 1.0, 0.06, 0.0, 0.2,
+///     TypeFlag::Put,
+///     ExerciseFlag::American,
+/// );
+///
+/// let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 500);
+///
+/// // American put (Longstaff and Schwartz, 2001, Table 1).
+/// assert!((tree.price(&option) - 4.486).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BinomialTree {
+    /// The lattice model.
+    pub model: LatticeModel,
+    /// Number of time steps.
+    pub steps: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl LatticeOption {
+    /// New option without discrete dividends.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        option_type: TypeFlag,
+        exercise: ExerciseFlag,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            dividends: Vec::new(),
+            option_type,
+            exercise,
+            exercise_dates: Vec::new(),
+        }
+    }
+
+    /// Add discrete cash dividends, as `(time, amount)` pairs.
+    #[must_use]
+    pub fn with_dividends(mut self, dividends: &[(f64, f64)]) -> Self {
+        self.dividends = dividends.to_vec();
+        self
+    }
+
+    /// Add the exercise dates of a Bermudan option, in years.
+    #[must_use]
+    pub fn with_exercise_dates(mut self, exercise_dates: &[f64]) -> Self {
+        self.exercise_dates = exercise_dates.to_vec();
+        self
+    }
+
+    /// Add the exercise dates of a Bermudan option from a schedule, as year
+    /// fractions from the valuation date (using the schedule's day count).
+    /// Dates on or before the valuation date are dropped.
+    #[must_use]
+    pub fn with_exercise_schedule(
+        self,
+        schedule: &Schedule,
+        valuation_date: OffsetDateTime,
+    ) -> Self {
+        let dates = schedule.year_fractions(valuation_date);
+        self.with_exercise_dates(&dates)
+    }
+
+    /// Option payoff for an underlying price `s`.
+    #[must_use]
+    pub fn payoff(&self, s: f64) -> f64 {
+        match self.option_type {
+            TypeFlag::Call => f64::max(s - self.strike_price, 0.0),
+            TypeFlag::Put => f64::max(self.strike_price - s, 0.0),
+        }
+    }
+
+    // Present value at time `t` of the dividends paid in (t, T].
+    fn dividends_after(&self, t: f64) -> f64 {
+        self.dividends
+            .iter()
+            .filter(|(time, _)| *time > t && *time <= self.time_to_expiry)
+            .map(|(time, amount)| amount * (-self.risk_free_rate * (time - t)).exp())
+            .sum()
+    }
+}
+
+impl BinomialTree {
+    /// New lattice pricing engine.
+    #[must_use]
+    pub const fn new(model: LatticeModel, steps: usize) -> Self {
+        Self { model, steps }
+    }
+
+    /// Price the option on the lattice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of steps is zero.
+    #[must_use]
+    pub fn price(&self, option: &LatticeOption) -> f64 {
+        self.price_with_exercise_boundary(option).0
+    }
+
+    /// Price the option on the lattice, and read the early-exercise
+    /// boundary off the exercised nodes (empty for European options).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of steps is zero.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    pub fn price_with_exercise_boundary(&self, option: &LatticeOption) -> (f64, ExerciseBoundary) {
+        assert!(self.steps > 0, "The tree needs at least one step.");
+
+        let n = self.steps;
+        let dt = option.time_to_expiry / n as f64;
+
+        // Whether early exercise is allowed at each step. Bermudan exercise
+        // dates are moved to the nearest step.
+        let mut exercisable = vec![matches!(option.exercise, ExerciseFlag::American); n];
+        if let ExerciseFlag::Bermudan = option.exercise {
+            for t in &option.exercise_dates {
+                let j = (t / dt).round();
+                if j >= 0.0 && j < n as f64 {
+                    exercisable[j as usize] = true;
+                }
+            }
+        }
+        let df = (-option.risk_free_rate * dt).exp();
+
+        // Escrowed dividend model: the tree is built on S less the dividends.
+        let s_0 = option.initial_price - option.dividends_after(0.0);
+
+        // Number of nodes added per step, node price multipliers and
+        // (discounted) transition probabilities from the lowest to the
+        // highest child.
+        let (width, u, d, probabilities) = self.parameters(option, dt);
+        let probabilities: Vec<f64> = probabilities.iter().map(|p| p * df).collect();
+
+        // Underlying price (ex-dividend) of node `i` at step `j`.
+        let node = |i: usize, j: usize| -> f64 {
+            match width {
+                1 => s_0 * u.powi(i as i32) * d.powi((j - i) as i32),
+                _ => s_0 * u.powi(i as i32 - j as i32),
+            }
+        };
+
+        let mut values: Vec<f64> = (0..=width * n).map(|i| option.payoff(node(i, n))).collect();
+        let mut boundary = ExerciseBoundary {
+            times: Vec::new(),
+            critical_prices: Vec::new(),
+        };
+
+        for j in (0..n).rev() {
+            let dividends = option.dividends_after(j as f64 * dt);
+            let mut critical = None;
+
+            for i in 0..=width * j {
+                let continuation: f64 = probabilities
+                    .iter()
+                    .zip(&values[i..])
+                    .map(|(p, v)| p * v)
+                    .sum();
+
+                let s = node(i, j) + dividends;
+                let exercise = option.payoff(s);
+
+                values[i] = if exercisable[j] && exercise > continuation {
+                    critical = Some(deepest_exercise(option.option_type, critical, s));
+                    exercise
+                } else {
+                    continuation
+                };
+            }
+
+            if exercisable[j] {
+                boundary.times.push(j as f64 * dt);
+                boundary.critical_prices.push(critical);
+            }
+        }
+
+        boundary.times.reverse();
+        boundary.critical_prices.reverse();
+
+        (values[0], boundary)
+    }
+
+    /// Price and Greeks by bumping the inputs and re-pricing on the tree.
+    ///
+    /// Delta and gamma use central differences with a 5% bump of the
+    /// underlying price, wide enough to smooth out the oscillation of tree
+    /// prices with the position of the strike between the nodes.
+    #[must_use]
+    pub fn greeks(&self, option: &LatticeOption) -> LatticeGreeks {
+        let price = self.price(option);
+
+        let bumped = |f: &dyn Fn(&mut LatticeOption)| {
+            let mut bumped = option.clone();
+            f(&mut bumped);
+            self.price(&bumped)
+        };
+
+        let dS = option.initial_price * 0.05;
+        let up = bumped(&|o| o.initial_price += dS);
+        let down = bumped(&|o| o.initial_price -= dS);
+
+        let dv = 0.001;
+        let vega = (bumped(&|o| o.volatility += dv) - bumped(&|o| o.volatility -= dv)) / (2.0 * dv);
+
+        let dr = 0.0001;
+        let rho = (bumped(&|o| o.risk_free_rate += dr) - bumped(&|o| o.risk_free_rate -= dr))
+            / (2.0 * dr);
+
+        // One day closer to expiry (dividend dates are kept in calendar time).
+        let dt = (1.0 / 365.0_f64).min(option.time_to_expiry / 2.0);
+        let theta = (bumped(&|o| {
+            o.time_to_expiry -= dt;
+            o.dividends = o.dividends.iter().map(|(t, a)| (t - dt, *a)).collect();
+            o.exercise_dates = o.exercise_dates.iter().map(|t| t - dt).collect();
+        }) - price)
+            / dt;
+
+        LatticeGreeks {
+            price,
+            delta: (up - down) / (2.0 * dS),
+            gamma: (up - 2.0 * price + down) / (dS * dS),
+            vega,
+            theta,
+            rho,
+        }
+    }
+
+    // Returns (nodes added per step, up and down factors, probabilities).
+    fn parameters(&self, option: &LatticeOption, dt: f64) -> (usize, f64, f64, Vec<f64>) {
+        let v = option.volatility;
+        let b = option.risk_free_rate - option.dividend_yield;
+        let growth = (b * dt).exp();
+
+        match self.model {
+            LatticeModel::CoxRossRubinstein => {
+                let u = (v * dt.sqrt()).exp();
+                let d = 1.0 / u;
+                let p = (growth - d) / (u - d);
+
+                (1, u, d, vec![1.0 - p, p])
+            }
+            LatticeModel::JarrowRudd => {
+                let drift = (b - 0.5 * v * v) * dt;
+                let u = (drift + v * dt.sqrt()).exp();
+                let d = (drift - v * dt.sqrt()).exp();
+
+                (1, u, d, vec![0.5, 0.5])
+            }
+            LatticeModel::Tian => {
+                let w = (v * v * dt).exp();
+                let root = (w * w + 2.0 * w - 3.0).sqrt();
+                let u = 0.5 * growth * w * (w + 1.0 + root);
+                let d = 0.5 * growth * w * (w + 1.0 - root);
+                let p = (growth - d) / (u - d);
+
+                (1, u, d, vec![1.0 - p, p])
+            }
+            LatticeModel::Trinomial => {
+                let u = (v * (2.0 * dt).sqrt()).exp();
+                let a = (b * dt / 2.0).exp();
+                let e_up = (v * (dt / 2.0).sqrt()).exp();
+                let e_down = 1.0 / e_up;
+
+                let p_u = ((a - e_down) / (e_up - e_down)).powi(2);
+                let p_d = ((e_up - a) / (e_up - e_down)).powi(2);
+
+                (2, u, 1.0 / u, vec![p_d, 1.0 - p_u - p_d, p_u])
+            }
+        }
+    }
+}
+
+// Critical price of the exercise region after adding the exercised price
+// `s`: the lowest exercised price for calls, the highest for puts.
+pub(crate) fn deepest_exercise(option_type: TypeFlag, critical: Option<f64>, s: f64) -> f64 {
+    match (option_type, critical) {
+        (_, None) => s,
+        (TypeFlag::Call, Some(critical)) => critical.min(s),
+        (TypeFlag::Put, Some(critical)) => critical.max(s),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_lattice {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::autodiff::Real;
+
+    const MODELS: [LatticeModel; 4] = [
+        LatticeModel::CoxRossRubinstein,
+        LatticeModel::JarrowRudd,
+        LatticeModel::Tian,
+        LatticeModel::Trinomial,
+    ];
+
+    // Black-Scholes price with continuous dividend yield.
+    fn black_scholes(S: f64, K: f64, T: f64, r: f64, q: f64, v: f64, call: bool) -> f64 {
+        let d1 = ((S / K).ln() + (r - q + 0.5 * v * v) * T) / (v * T.sqrt());
+        let d2 = d1 - v * T.sqrt();
+
+        if call {
+            S * (-q * T).exp() * d1.norm_cdf() - K * (-r * T).exp() * d2.norm_cdf()
+        } else {
+            K * (-r * T).exp() * (-d2).norm_cdf() - S * (-q * T).exp() * (-d1).norm_cdf()
+        }
+    }
+
+    #[test]
+    fn test_european_convergence() {
+        for (option_type, call) in [(TypeFlag::Call, true), (TypeFlag::Put, false)] {
+            let option = LatticeOption::new(
+                100.0,
+                95.0,
+                0.5,
+                0.08,
+                0.02,
+                0.3,
+                option_type,
+                ExerciseFlag::European,
+            );
+            let expected = black_scholes(100.0, 95.0, 0.5, 0.08, 0.02, 0.3, call);
+
+            for model in MODELS {
+                let price = BinomialTree::new(model, 500).price(&option);
+                assert_approx_equal!(price, expected, 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn test_american() {
+        // American put, Longstaff and Schwartz (2001), Table 1.
+        let put = LatticeOption::new(
+            36.0,
+            40.0,
+            1.0,
+            0.06,
+            0.0,
+            0.2,
+            TypeFlag::Put,
+            ExerciseFlag::American,
+        );
+
+        for model in MODELS {
+            let price = BinomialTree::new(model, 500).price(&put);
+            assert_approx_equal!(price, 4.486, 0.01);
+        }
+
+        // Early exercise of a call without dividends is never optimal.
+        let call = LatticeOption::new(
+            100.0,
+            100.0,
+            1.0,
+            0.05,
+            0.0,
+            0.2,
+            TypeFlag::Call,
+            ExerciseFlag::American,
+        );
+        let european = LatticeOption {
+            exercise: ExerciseFlag::European,
+            ..call.clone()
+        };
+
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 200);
+        assert_approx_equal!(tree.price(&call), tree.price(&european), 1e-10);
+    }
+
+    #[test]
+    fn test_discrete_dividends() {
+        let dividends = [(0.25, 2.0), (0.75, 2.0)];
+        let call = LatticeOption::new(
+            100.0,
+            100.0,
+            1.0,
+            0.05,
+            0.0,
+            0.2,
+            TypeFlag::Call,
+            ExerciseFlag::European,
+        )
+        .with_dividends(&dividends);
+
+        // Escrowed dividend model: European price is Black-Scholes on S - PV(D).
+        let pv = 2.0 * (-0.05_f64 * 0.25).exp() + 2.0 * (-0.05_f64 * 0.75).exp();
+        let expected = black_scholes(100.0 - pv, 100.0, 1.0, 0.05, 0.0, 0.2, true);
+
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 500);
+        assert_approx_equal!(tree.price(&call), expected, 0.02);
+
+        // Early exercise before a dividend is now valuable.
+        let american = LatticeOption {
+            exercise: ExerciseFlag::American,
+            ..call.clone()
+        };
+        assert!(tree.price(&american) > tree.price(&call) + 1e-3);
+    }
+
+    #[test]
+    fn test_exercise_boundary() {
+        let put = LatticeOption::new(
+            36.0,
+            40.0,
+            1.0,
+            0.06,
+            0.0,
+            0.2,
+            TypeFlag::Put,
+            ExerciseFlag::American,
+        );
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 500);
+
+        let (price, boundary) = tree.price_with_exercise_boundary(&put);
+        assert_approx_equal!(price, tree.price(&put), 1e-12);
+        assert_eq!(boundary.times.len(), 500);
+
+        // The put is exercised below a critical price that rises to the
+        // strike at expiry (up to the tree's resolution).
+        let critical: Vec<f64> = boundary.critical_prices[1..]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        assert!(critical.len() > 480);
+        assert!(critical.iter().all(|s| *s < 40.0));
+        assert!(critical.windows(2).all(|w| w[1] >= w[0] * 0.99));
+        assert!(critical[0] > 32.0 && critical[0] < 34.0);
+        assert!(critical[critical.len() - 1] > 39.0);
+
+        // No boundary for European options; Bermudan exercise dates only.
+        let european = LatticeOption {
+            exercise: ExerciseFlag::European,
+            ..put.clone()
+        };
+        assert!(tree
+            .price_with_exercise_boundary(&european)
+            .1
+            .times
+            .is_empty());
+
+        let bermudan = LatticeOption {
+            exercise: ExerciseFlag::Bermudan,
+            ..put.with_exercise_dates(&[0.25, 0.5, 0.75])
+        };
+        let times = tree.price_with_exercise_boundary(&bermudan).1.times;
+        assert_eq!(times.len(), 3);
+        assert_approx_equal!(times[1], 0.5, 1e-12);
+    }
+
+    #[test]
+    fn test_bermudan() {
+        let put = LatticeOption::new(
+            36.0,
+            40.0,
+            1.0,
+            0.06,
+            0.0,
+            0.2,
+            TypeFlag::Put,
+            ExerciseFlag::Bermudan,
+        );
+        let tree = BinomialTree::new(LatticeModel::CoxRossRubinstein, 500);
+
+        let price = |exercise: ExerciseFlag, dates: &[f64]| {
+            tree.price(&LatticeOption {
+                exercise,
+                ..put.clone().with_exercise_dates(dates)
+            })
+        };
+
+        let european = price(ExerciseFlag::European, &[]);
+        let american = price(ExerciseFlag::American, &[]);
+        let quarterly = price(ExerciseFlag::Bermudan, &[0.25, 0.5, 0.75]);
+
+        // Without exercise dates only expiry remains; with all of them it is American.
+        let every_step: Vec<f64> = (0..500).map(|j| f64::from(j) / 500.0).collect();
+        assert_approx_equal!(price(ExerciseFlag::Bermudan, &[]), european, 1e-12);
+        assert_approx_equal!(price(ExerciseFlag::Bermudan, &every_step), american, 1e-12);
+
+        assert!(european < quarterly && quarterly < american);
+
+        // Exercise dates from a schedule: quarterly, from the valuation date.
+        let start = time::macros::datetime!(2023-01-01 0:00 UTC);
+        let schedule = Schedule::new_from_start(start, time::Duration::days(91), 3);
+        let from_schedule = tree.price(&put.clone().with_exercise_schedule(&schedule, start));
+
+        assert_approx_equal!(from_schedule, quarterly, 0.01);
+    }
+
+    #[test]
+    fn test_greeks() {
+        let option = LatticeOption::new(
+            100.0,
+            100.0,
+            1.0,
+            0.05,
+            0.0,
+            0.2,
+            TypeFlag::Call,
+            ExerciseFlag::European,
+        );
+
+        let greeks = BinomialTree::new(LatticeModel::Trinomial, 500).greeks(&option);
+
+        // Black-Scholes: delta = N(d1), gamma = n(d1) / (S v sqrt(T)), etc.
+        let d1: f64 = (0.05 + 0.5 * 0.2 * 0.2) / 0.2;
+        let d2 = d1 - 0.2;
+
+        assert_approx_equal!(greeks.price, 10.450_583_572_185_565, 0.01);
+        assert_approx_equal!(greeks.delta, d1.norm_cdf(), 0.005);
+        assert_approx_equal!(greeks.gamma, d1.norm_pdf() / 20.0, 0.001);
+        assert_approx_equal!(greeks.vega, 100.0 * d1.norm_pdf(), 0.1);
+        assert_approx_equal!(greeks.rho, 100.0 * (-0.05_f64).exp() * d2.norm_cdf(), 0.1);
+        assert_approx_equal!(
+            greeks.theta,
+            -100.0 * d1.norm_pdf() * 0.2 / 2.0 - 0.05 * 100.0 * (-0.05_f64).exp() * d2.norm_cdf(),
+            0.1
+        );
+    }
+}
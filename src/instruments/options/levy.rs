@@ -0,0 +1,250 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pure jump Lévy models: variance gamma, normal inverse Gaussian and CGMY.
+//!
+//! Each model implements [`CharacteristicFunction`], so European options
+//! can be priced with the [`CosPricer`](crate::instruments::CosPricer).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::CharacteristicFunction;
+use num_complex::Complex;
+use statrs::function::gamma::gamma;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Variance gamma process (Madan, Carr and Chang, 1998): a Brownian motion
+/// with drift `theta` and volatility `sigma`, time-changed by a gamma
+/// process with unit mean rate and variance rate `nu`.
+///
+/// $$
+/// \psi(u) = -\frac{1}{\nu} \ln \left( 1 - i u \theta \nu + \frac{1}{2} \sigma^2 \nu u^2 \right)
+/// $$
+#[derive(Debug, Clone, Copy)]
+pub struct VarianceGamma {
+    /// `sigma` - Volatility of the subordinated Brownian motion.
+    pub volatility: f64,
+    /// `theta` - Drift of the subordinated Brownian motion.
+    pub drift: f64,
+    /// `nu` - Variance rate of the gamma time change.
+    pub variance_rate: f64,
+}
+
+/// Normal inverse Gaussian process (Barndorff-Nielsen, 1997).
+///
+/// $$
+/// \psi(u) = -\delta \left( \sqrt{\alpha^2 - (\beta + i u)^2} - \sqrt{\alpha^2 - \beta^2} \right)
+/// $$
+///
+/// The martingale correction requires `alpha > |beta + 1|`.
+#[derive(Debug, Clone, Copy)]
+pub struct NormalInverseGaussian {
+    /// `alpha` - Tail heaviness.
+    pub alpha: f64,
+    /// `beta` - Asymmetry, with `|beta| < alpha`.
+    pub beta: f64,
+    /// `delta` - Scale.
+    pub delta: f64,
+}
+
+/// CGMY process (Carr, Geman, Madan and Yor, 2002).
+///
+/// $$
+/// \psi(u) = C \Gamma(-Y) \left( (M - i u)^Y - M^Y + (G + i u)^Y - G^Y \right)
+/// $$
+///
+/// `Y < 2`, and `Y` must not be 0 or 1, where `Gamma(-Y)` is singular.
+/// The martingale correction requires `M > 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct Cgmy {
+    /// `C` - Overall activity of the jumps.
+    pub c: f64,
+    /// `G` - Rate of exponential decay of the down jumps.
+    pub g: f64,
+    /// `M` - Rate of exponential decay of the up jumps.
+    pub m: f64,
+    /// `Y` - Fine structure of the jumps near zero.
+    pub y: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl VarianceGamma {
+    /// New variance gamma process.
+    #[must_use]
+    pub const fn new(volatility: f64, drift: f64, variance_rate: f64) -> Self {
+        Self {
+            volatility,
+            drift,
+            variance_rate,
+        }
+    }
+}
+
+impl NormalInverseGaussian {
+    /// New normal inverse Gaussian process.
+    #[must_use]
+    pub const fn new(alpha: f64, beta: f64, delta: f64) -> Self {
+        Self { alpha, beta, delta }
+    }
+}
+
+impl Cgmy {
+    /// New CGMY process.
+    #[must_use]
+    pub const fn new(c: f64, g: f64, m: f64, y: f64) -> Self {
+        Self { c, g, m, y }
+    }
+}
+
+impl CharacteristicFunction for VarianceGamma {
+    fn characteristic_exponent(&self, u: Complex<f64>) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+        let (sigma, theta, nu) = (self.volatility, self.drift, self.variance_rate);
+
+        -(1.0 - i * u * theta * nu + 0.5 * sigma * sigma * nu * u * u).ln() / nu
+    }
+
+    fn cumulants(&self, t: f64) -> [f64; 3] {
+        let (sigma, theta, nu) = (self.volatility, self.drift, self.variance_rate);
+        let (s2, t2) = (sigma * sigma, theta * theta);
+
+        [
+            theta * t,
+            (s2 + nu * t2) * t,
+            3.0 * (s2 * s2 * nu + 2.0 * t2 * t2 * nu.powi(3) + 4.0 * s2 * t2 * nu * nu) * t,
+        ]
+    }
+}
+
+impl CharacteristicFunction for NormalInverseGaussian {
+    fn characteristic_exponent(&self, u: Complex<f64>) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+        let (alpha, beta) = (self.alpha, self.beta);
+        let b = beta + i * u;
+
+        -self.delta * ((alpha * alpha - b * b).sqrt() - (alpha * alpha - beta * beta).sqrt())
+    }
+}
+
+impl CharacteristicFunction for Cgmy {
+    fn characteristic_exponent(&self, u: Complex<f64>) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+        let (g, m, y) = (self.g, self.m, self.y);
+
+        self.c * gamma(-y) * ((m - i * u).powf(y) - m.powf(y) + (g + i * u).powf(y) - g.powf(y))
+    }
+
+    fn cumulants(&self, t: f64) -> [f64; 3] {
+        let (c, g, m, y) = (self.c, self.g, self.m, self.y);
+        let gamma_y = c * t * gamma(-y);
+
+        [
+            gamma_y * y * (g.powf(y - 1.0) - m.powf(y - 1.0)),
+            gamma_y * y * (y - 1.0) * (m.powf(y - 2.0) + g.powf(y - 2.0)),
+            gamma_y * y * (y - 1.0) * (y - 2.0) * (y - 3.0) * (m.powf(y - 4.0) + g.powf(y - 4.0)),
+        ]
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_levy {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::{CarrMadan, CosPricer, TypeFlag};
+
+    // Default cumulants, by numerical differentiation.
+    struct Numerical<'a>(&'a dyn CharacteristicFunction);
+
+    impl CharacteristicFunction for Numerical<'_> {
+        fn characteristic_exponent(&self, u: Complex<f64>) -> Complex<f64> {
+            self.0.characteristic_exponent(u)
+        }
+    }
+
+    #[test]
+    fn test_variance_gamma() {
+        // Fang and Oosterlee (2008).
+        let vg = VarianceGamma::new(0.12, -0.14, 0.2);
+        let cos = CosPricer::new(100.0, 0.1, 0.0, 1.0);
+
+        assert_approx_equal!(cos.price(&vg, 90.0, TypeFlag::Call), 19.099_354_724, 1e-6);
+
+        let cos = CosPricer {
+            time_to_expiry: 0.1,
+            ..cos
+        }
+        .with_terms(4096, 10.0);
+        assert_approx_equal!(cos.price(&vg, 90.0, TypeFlag::Call), 10.993_703_187, 1e-4);
+    }
+
+    #[test]
+    fn test_cgmy() {
+        // Fang and Oosterlee (2008).
+        let cos = CosPricer::new(100.0, 0.1, 0.0, 1.0);
+
+        for (y, expected) in [
+            (0.5, 19.812_948_843),
+            (1.5, 49.790_905_469),
+            (1.98, 99.999_905_510),
+        ] {
+            let cgmy = Cgmy::new(1.0, 5.0, 5.0, y);
+            assert_approx_equal!(cos.price(&cgmy, 100.0, TypeFlag::Call), expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_normal_inverse_gaussian() {
+        let nig = NormalInverseGaussian::new(15.0, -5.0, 0.5);
+        let cos = CosPricer::new(100.0, 0.03, 0.01, 0.5);
+
+        let df = (-0.03 * 0.5_f64).exp();
+        let strikes = [80.0, 90.0, 100.0, 110.0, 120.0];
+        let cos_calls = cos.prices(&nig, &strikes, TypeFlag::Call);
+        let cos_puts = cos.prices(&nig, &strikes, TypeFlag::Put);
+
+        for ((&strike, call), put) in strikes.iter().zip(cos_calls).zip(cos_puts) {
+            // Carr-Madan FFT of the same characteristic function, centred on the strike.
+            let fft = CarrMadan::default();
+            let (_, calls) =
+                fft.call_grid(|u| cos.characteristic_function(&nig, u), strike.ln(), df);
+
+            assert_approx_equal!(call, calls[fft.points / 2], 1e-6);
+            assert_approx_equal!(call - put, 100.0 * (-0.005_f64).exp() - strike * df, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_cumulants() {
+        let models: [&dyn CharacteristicFunction; 2] = [
+            &VarianceGamma::new(0.2, -0.1, 0.3),
+            &Cgmy::new(0.5, 4.0, 8.0, 0.7),
+        ];
+
+        for model in models {
+            let expected = model.cumulants(0.5);
+            let numerical = Numerical(model).cumulants(0.5);
+
+            for (c, n) in expected.iter().zip(numerical) {
+                assert_approx_equal!(*c, n, 1e-4 * c.abs().max(1.0));
+            }
+        }
+    }
+}
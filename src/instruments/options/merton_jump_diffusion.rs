@@ -11,9 +11,11 @@
 // Merton (1976) jump diffusion model
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use super::TypeFlag;
+use super::{OptionGreeks, TypeFlag};
 use crate::instruments::BlackScholesMerton;
+use crate::statistics::distributions::{Distribution, Gaussian};
 use crate::time::{DayCountConvention, DayCounter};
+use num_complex::Complex;
 use time::OffsetDateTime;
 
 /// Merton (1976) jump diffusion model parameters.
@@ -48,6 +50,48 @@ pub struct Merton1976 {
     pub expiration_date: OffsetDateTime,
 }
 
+/// Merton (1976) jump diffusion model, with lognormal jumps:
+///
+/// $$
+/// \frac{dS_t}{S_{t-}} = (r - q - \lambda \kappa) dt + \sigma dW_t + (J - 1) dN_t,
+/// \qquad \ln J \sim N(\mu, \delta^2), \quad \kappa = e^{\mu + \delta^2 / 2} - 1
+/// $$
+///
+/// where `N` is a Poisson process with intensity `lambda`.
+/// Conditional on the number of jumps the underlying is lognormal, so
+/// European options are a Poisson-weighted series of Black prices.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let merton = MertonJumpDiffusion::new(100.0, 0.05, 0.0, 1.0, 0.2, 0.5, -0.1, 0.15);
+///
+/// let call = merton.price(100.0, TypeFlag::Call);
+/// let put = merton.price(100.0, TypeFlag::Put);
+///
+/// // Put-call parity.
+/// assert!((call - put - (100.0 - 100.0 * (-0.05_f64).exp())).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MertonJumpDiffusion {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `q` - Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `sigma` - Volatility of the diffusion.
+    pub volatility: f64,
+    /// `lambda` - Expected number of jumps per year.
+    pub jump_intensity: f64,
+    /// `mu` - Mean of the logarithm of the jump sizes.
+    pub jump_mean: f64,
+    /// `delta` - Standard deviation of the logarithm of the jump sizes.
+    pub jump_volatility: f64,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // MERTON (1976) JUMP DIFFUSION OPTION PRICING
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -100,6 +144,150 @@ impl Merton1976 {
     }
 }
 
+impl MertonJumpDiffusion {
+    /// New Merton jump diffusion model.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        time_to_expiry: f64,
+        volatility: f64,
+        jump_intensity: f64,
+        jump_mean: f64,
+        jump_volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            risk_free_rate,
+            dividend_yield,
+            time_to_expiry,
+            volatility,
+            jump_intensity,
+            jump_mean,
+            jump_volatility,
+        }
+    }
+
+    /// Mean relative jump size, `kappa = E[J] - 1`.
+    #[must_use]
+    pub fn mean_jump(&self) -> f64 {
+        (self.jump_mean + 0.5 * self.jump_volatility * self.jump_volatility).exp() - 1.0
+    }
+
+    /// Characteristic function of `ln S_T`, `E[exp(i u ln S_T)]`.
+    #[must_use]
+    pub fn characteristic_function(&self, u: Complex<f64>) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+        let T = self.time_to_expiry;
+        let (v, delta) = (self.volatility, self.jump_volatility);
+
+        let drift = self.initial_price.ln()
+            + (self.risk_free_rate
+                - self.dividend_yield
+                - self.jump_intensity * self.mean_jump()
+                - 0.5 * v * v)
+                * T;
+        let jumps = (i * u * self.jump_mean - 0.5 * delta * delta * u * u).exp() - 1.0;
+
+        (i * u * drift - 0.5 * v * v * u * u * T + self.jump_intensity * T * jumps).exp()
+    }
+
+    /// Price of a European call or put, from Merton's series.
+    #[must_use]
+    pub fn price(&self, strike: f64, option_type: TypeFlag) -> f64 {
+        self.greeks(strike, option_type).price
+    }
+
+    /// Price and Greeks of a European call or put, from Merton's series.
+    ///
+    /// The series is summed past the mode of the Poisson distribution
+    /// until the weights are negligible.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn greeks(&self, strike: f64, option_type: TypeFlag) -> OptionGreeks {
+        const MAX_TERMS: usize = 1000;
+
+        let S = self.initial_price;
+        let K = strike;
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let (v, lambda, delta) = (self.volatility, self.jump_intensity, self.jump_volatility);
+        let kappa = self.mean_jump();
+
+        let theta = match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+        let norm = Gaussian::default();
+        let df = (-r * T).exp();
+        let drift = r - self.dividend_yield - lambda * kappa;
+
+        let (mut price, mut delta_sum, mut gamma, mut vega, mut time_decay) =
+            (0.0, 0.0, 0.0, 0.0, 0.0);
+        let (mut vanna, mut volga, mut charm) = (0.0, 0.0, 0.0);
+
+        // Poisson weight of n jumps.
+        let mut weight = (-lambda * T).exp();
+
+        for n in 0..MAX_TERMS {
+            let n_f = n as f64;
+
+            if n > 0 {
+                weight *= lambda * T / n_f;
+            }
+
+            // Black price given n jumps.
+            let forward = S * (drift * T).exp() * (1.0 + kappa).powf(n_f);
+            let s = (v * v * T + n_f * delta * delta).sqrt();
+            let d1 = (forward / K).ln() / s + 0.5 * s;
+            let d2 = d1 - s;
+            let black = df * theta * (forward * norm.cdf(theta * d1) - K * norm.cdf(theta * d2));
+
+            let dblack_dforward = df * theta * norm.cdf(theta * d1);
+            let dblack_dstdev = df * forward * norm.pdf(d1);
+            let dblack_dt =
+                -r * black + dblack_dforward * drift * forward + dblack_dstdev * v * v / (2.0 * s);
+
+            // Delta given n jumps, and its sensitivity to time.
+            let scale = df * forward / S;
+            let delta_n = scale * theta * norm.cdf(theta * d1);
+            let ddelta_dt = scale
+                * ((drift - r) * theta * norm.cdf(theta * d1)
+                    + norm.pdf(d1) * (drift / s - d2 * v * v / (2.0 * s * s)));
+            let ds_dvol = v * T / s;
+
+            price += weight * black;
+            delta_sum += weight * delta_n;
+            gamma += weight * df * norm.pdf(d1) * forward / (S * S * s);
+            vega += weight * dblack_dstdev * ds_dvol;
+            time_decay -= weight * ((n_f / T - lambda) * black + dblack_dt);
+            vanna -= weight * scale * norm.pdf(d1) * d2 / s * ds_dvol;
+            volga += weight
+                * dblack_dstdev
+                * (d1 * d2 * ds_dvol * ds_dvol / s + T / s * (1.0 - v * v * T / (s * s)));
+            charm -= weight * ((n_f / T - lambda) * delta_n + ddelta_dt);
+
+            if n_f > lambda * T && weight < 1e-16 {
+                break;
+            }
+        }
+
+        OptionGreeks {
+            price,
+            delta: delta_sum,
+            gamma,
+            vega,
+            theta: time_decay,
+            rho: T * (S * delta_sum - price),
+            vanna: Some(vanna),
+            volga: Some(volga),
+            charm: Some(charm),
+        }
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -128,4 +316,168 @@ mod tests_merton_1976 {
         // Result is slightly off due to using Dates instead of floats for T.
         assert_approx_equal!(merton76.price(), 20.67, 0.1);
     }
+
+    const MERTON: MertonJumpDiffusion =
+        MertonJumpDiffusion::new(100.0, 0.05, 0.02, 0.75, 0.2, 0.8, -0.1, 0.2);
+
+    #[test]
+    fn test_merton_series() {
+        // Haug's example, with the jumps' mean chosen so that kappa = 0, and
+        // the diffusion volatility z = sqrt(v^2 - lambda delta^2).
+        let (v, lambda, gamma): (f64, f64, f64) = (0.25, 1.0, 0.25);
+        let delta = (v * v * gamma / lambda).sqrt();
+        let z = (v * v - lambda * delta * delta).sqrt();
+
+        let merton = MertonJumpDiffusion::new(
+            100.0,
+            0.08,
+            0.0,
+            0.1,
+            z,
+            lambda,
+            -0.5 * delta * delta,
+            delta,
+        );
+
+        assert_approx_equal!(merton.price(80.0, TypeFlag::Call), 20.67, 0.01);
+
+        // No jumps: Black-Scholes.
+        let bs = MertonJumpDiffusion {
+            jump_intensity: 0.0,
+            ..MERTON
+        };
+        let d1 = ((100.0_f64 / 105.0).ln() + (0.05 - 0.02 + 0.02) * 0.75) / (0.2 * 0.75_f64.sqrt());
+        let d2 = d1 - 0.2 * 0.75_f64.sqrt();
+        let norm = Gaussian::default();
+        let call = 100.0 * (-0.02 * 0.75_f64).exp() * norm.cdf(d1)
+            - 105.0 * (-0.05 * 0.75_f64).exp() * norm.cdf(d2);
+
+        assert_approx_equal!(bs.price(105.0, TypeFlag::Call), call, 1e-12);
+    }
+
+    #[test]
+    fn test_merton_fft() {
+        use crate::instruments::CarrMadan;
+
+        let df = (-MERTON.risk_free_rate * MERTON.time_to_expiry).exp();
+        let (log_strikes, calls) = CarrMadan::default().call_grid(
+            |u| MERTON.characteristic_function(u),
+            MERTON.initial_price.ln(),
+            df,
+        );
+
+        for strike in [70.0, 90.0, 100.0, 110.0, 140.0] {
+            let fft = CarrMadan::interpolate(&log_strikes, &calls, strike);
+            assert_approx_equal!(fft, MERTON.price(strike, TypeFlag::Call), 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_merton_greeks() {
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            for strike in [80.0, 100.0, 120.0] {
+                let greeks = MERTON.greeks(strike, option_type);
+                let bump = |f: &dyn Fn(f64) -> MertonJumpDiffusion, x: f64, h: f64| {
+                    (f(x + h).price(strike, option_type) - f(x - h).price(strike, option_type))
+                        / (2.0 * h)
+                };
+                let h = 1e-4;
+
+                let delta = bump(
+                    &|x| MertonJumpDiffusion {
+                        initial_price: x,
+                        ..MERTON
+                    },
+                    MERTON.initial_price,
+                    1e-2,
+                );
+                let gamma = (MertonJumpDiffusion {
+                    initial_price: 100.01,
+                    ..MERTON
+                }
+                .price(strike, option_type)
+                    - 2.0 * greeks.price
+                    + MertonJumpDiffusion {
+                        initial_price: 99.99,
+                        ..MERTON
+                    }
+                    .price(strike, option_type))
+                    / 1e-4;
+                let vega = bump(
+                    &|x| MertonJumpDiffusion {
+                        volatility: x,
+                        ..MERTON
+                    },
+                    MERTON.volatility,
+                    h,
+                );
+                let theta = -bump(
+                    &|x| MertonJumpDiffusion {
+                        time_to_expiry: x,
+                        ..MERTON
+                    },
+                    MERTON.time_to_expiry,
+                    h,
+                );
+                let rho = bump(
+                    &|x| MertonJumpDiffusion {
+                        risk_free_rate: x,
+                        ..MERTON
+                    },
+                    MERTON.risk_free_rate,
+                    h,
+                );
+
+                assert_approx_equal!(greeks.delta, delta, 1e-6);
+                assert_approx_equal!(greeks.gamma, gamma, 1e-5);
+                assert_approx_equal!(greeks.vega, vega, 1e-6);
+                assert_approx_equal!(greeks.theta, theta, 1e-6);
+                assert_approx_equal!(greeks.rho, rho, 1e-6);
+
+                let vanna = (MertonJumpDiffusion {
+                    volatility: 0.2 + h,
+                    ..MERTON
+                }
+                .greeks(strike, option_type)
+                .delta
+                    - MertonJumpDiffusion {
+                        volatility: 0.2 - h,
+                        ..MERTON
+                    }
+                    .greeks(strike, option_type)
+                    .delta)
+                    / (2.0 * h);
+                let volga = (MertonJumpDiffusion {
+                    volatility: 0.2 + h,
+                    ..MERTON
+                }
+                .greeks(strike, option_type)
+                .vega
+                    - MertonJumpDiffusion {
+                        volatility: 0.2 - h,
+                        ..MERTON
+                    }
+                    .greeks(strike, option_type)
+                    .vega)
+                    / (2.0 * h);
+                let charm = -(MertonJumpDiffusion {
+                    time_to_expiry: 0.75 + h,
+                    ..MERTON
+                }
+                .greeks(strike, option_type)
+                .delta
+                    - MertonJumpDiffusion {
+                        time_to_expiry: 0.75 - h,
+                        ..MERTON
+                    }
+                    .greeks(strike, option_type)
+                    .delta)
+                    / (2.0 * h);
+
+                assert_approx_equal!(greeks.vanna.unwrap(), vanna, 1e-6);
+                assert_approx_equal!(greeks.volga.unwrap(), volga, 1e-6 * volga.abs());
+                assert_approx_equal!(greeks.charm.unwrap(), charm, 1e-6);
+            }
+        }
+    }
 }
@@ -0,0 +1,354 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Market data for options on several underlyings.
+//!
+//! Each asset follows a geometric Brownian motion under the risk-neutral
+//! measure, and the Brownian motions are correlated:
+//!
+//! $$
+//! dS_i = (r - q_i) S_i dt + \sigma_i S_i dW_i, \qquad d\langle W_i, W_j \rangle = \rho_{ij} dt
+//! $$
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Spot prices, volatilities, dividend yields and correlations of a set of
+/// underlyings, with a common risk-free rate.
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use nalgebra::DMatrix;
+///
+/// let market = MultiAssetMarket::new(
+///     vec![100.0, 90.0],
+///     vec![0.2, 0.3],
+///     vec![0.0, 0.01],
+///     DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]),
+///     0.05,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(market.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiAssetMarket {
+    /// `S_i` - Spot prices of the underlyings.
+    pub spots: Vec<f64>,
+    /// `v_i` - Volatilities of the underlyings.
+    pub volatilities: Vec<f64>,
+    /// `q_i` - Continuous dividend yields of the underlyings.
+    pub dividend_yields: Vec<f64>,
+    /// `rho` - Correlation matrix of the underlyings' Brownian motions.
+    pub correlation: DMatrix<f64>,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+}
+
+/// Monte Carlo price of a multi-asset option.
+#[derive(Debug, Clone, Copy)]
+pub struct MultiAssetMonteCarloResult {
+    /// Call price.
+    pub call: f64,
+    /// Put price.
+    pub put: f64,
+    /// Standard error of the call price.
+    pub call_std_error: f64,
+    /// Standard error of the put price.
+    pub put_std_error: f64,
+}
+
+/// Multi-asset market data and option errors.
+#[derive(Debug, thiserror::Error)]
+pub enum MultiAssetError {
+    /// The inputs do not have one entry per underlying.
+    #[error("Inputs must have one entry per underlying")]
+    LengthMismatch,
+
+    /// There are no underlyings, or not the number the option needs.
+    #[error("Wrong number of underlyings")]
+    WrongNumberOfAssets,
+
+    /// A spot price or volatility is not positive.
+    #[error("Spot prices and volatilities must be positive")]
+    NonPositiveInput,
+
+    /// The correlation matrix is not a valid correlation matrix.
+    #[error("Correlation matrix must be symmetric positive definite with a unit diagonal")]
+    InvalidCorrelation,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MultiAssetMarket {
+    /// New multi-asset market.
+    ///
+    /// # Errors
+    ///
+    /// - `MultiAssetError::WrongNumberOfAssets` if there are no spot prices.
+    /// - `MultiAssetError::LengthMismatch` if the volatilities, dividend yields
+    ///   or correlation matrix do not match the number of spot prices.
+    /// - `MultiAssetError::NonPositiveInput` for a non-positive spot price or volatility.
+    /// - `MultiAssetError::InvalidCorrelation` if the correlation matrix is not
+    ///   symmetric positive definite with a unit diagonal.
+    pub fn new(
+        spots: Vec<f64>,
+        volatilities: Vec<f64>,
+        dividend_yields: Vec<f64>,
+        correlation: DMatrix<f64>,
+        risk_free_rate: f64,
+    ) -> Result<Self, MultiAssetError> {
+        const TOLERANCE: f64 = 1e-12;
+
+        let n = spots.len();
+
+        if n == 0 {
+            return Err(MultiAssetError::WrongNumberOfAssets);
+        }
+        if volatilities.len() != n || dividend_yields.len() != n || correlation.shape() != (n, n) {
+            return Err(MultiAssetError::LengthMismatch);
+        }
+        if spots.iter().chain(&volatilities).any(|&x| x <= 0.0) {
+            return Err(MultiAssetError::NonPositiveInput);
+        }
+
+        let symmetric = (0..n).all(|i| {
+            (correlation[(i, i)] - 1.0).abs() < TOLERANCE
+                && (0..i).all(|j| (correlation[(i, j)] - correlation[(j, i)]).abs() < TOLERANCE)
+        });
+        if !symmetric || correlation.clone().cholesky().is_none() {
+            return Err(MultiAssetError::InvalidCorrelation);
+        }
+
+        Ok(Self {
+            spots,
+            volatilities,
+            dividend_yields,
+            correlation,
+            risk_free_rate,
+        })
+    }
+
+    /// Number of underlyings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.spots.len()
+    }
+
+    /// Whether there are no underlyings.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spots.is_empty()
+    }
+
+    /// Forward price of the `i`-th underlying at time `t`.
+    #[must_use]
+    pub fn forward(&self, i: usize, t: f64) -> f64 {
+        self.spots[i] * ((self.risk_free_rate - self.dividend_yields[i]) * t).exp()
+    }
+
+    /// Covariance of the log-prices of the `i`-th and `j`-th underlyings at time `t`.
+    #[must_use]
+    pub fn covariance(&self, i: usize, j: usize, t: f64) -> f64 {
+        self.correlation[(i, j)] * self.volatilities[i] * self.volatilities[j] * t
+    }
+
+    /// Simulate `paths` draws of the underlyings' prices at time `t`.
+    /// Each draw has one price per underlying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the correlation matrix is not positive definite.
+    #[must_use]
+    pub fn simulate_terminal(&self, t: f64, paths: usize, seed: u64) -> Vec<Vec<f64>> {
+        self.simulate_paths(&[t], paths, seed)
+            .into_iter()
+            .map(|mut path| path.remove(0))
+            .collect()
+    }
+
+    /// Simulate `paths` paths of the underlyings' prices on the increasing
+    /// `times`, stepping exactly between them. Each path has one row per
+    /// time, with one price per underlying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the correlation matrix is not positive definite, or if the
+    /// times are not positive and increasing.
+    #[must_use]
+    pub fn simulate_paths(&self, times: &[f64], paths: usize, seed: u64) -> Vec<Vec<Vec<f64>>> {
+        assert!(
+            times.first().is_none_or(|&t| t > 0.0) && times.windows(2).all(|w| w[0] < w[1]),
+            "Times must be positive and increasing."
+        );
+
+        let n = self.len();
+
+        let cholesky = self
+            .correlation
+            .clone()
+            .cholesky()
+            .expect("Correlation matrix is not positive definite.")
+            .l();
+
+        // (drift, diffusion) of each underlying's log-price over each step.
+        let mut start = 0.0;
+        let steps: Vec<Vec<(f64, f64)>> = times
+            .iter()
+            .map(|&end| {
+                let dt: f64 = end - start;
+                start = end;
+
+                (0..n)
+                    .map(|i| {
+                        let v = self.volatilities[i];
+                        let drift =
+                            (self.risk_free_rate - self.dividend_yields[i] - 0.5 * v * v) * dt;
+                        (drift, v * dt.sqrt())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..paths)
+            .map(|_| {
+                let mut log_prices: Vec<f64> = self.spots.iter().map(|s| s.ln()).collect();
+
+                steps
+                    .iter()
+                    .map(|step| {
+                        let z = DVector::from_fn(n, |_, _| rng.sample::<f64, _>(StandardNormal));
+                        let w = &cholesky * z;
+
+                        (0..n)
+                            .map(|i| {
+                                log_prices[i] += step[i].0 + step[i].1 * w[i];
+                                log_prices[i].exp()
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl MultiAssetMonteCarloResult {
+    // Monte Carlo estimate from the discounted (call, put) payoffs.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn from_payoffs(payoffs: &[(f64, f64)]) -> Self {
+        let n = payoffs.len() as f64;
+
+        let estimate = |payoff: fn(&(f64, f64)) -> f64| {
+            let mean = payoffs.iter().map(payoff).sum::<f64>() / n;
+            let variance = payoffs
+                .iter()
+                .map(|x| (payoff(x) - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1.0);
+
+            (mean, (variance / n).sqrt())
+        };
+
+        let (call, call_std_error) = estimate(|x| x.0);
+        let (put, put_std_error) = estimate(|x| x.1);
+
+        Self {
+            call,
+            put,
+            call_std_error,
+            put_std_error,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_multi_asset {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn correlation(rho: f64) -> DMatrix<f64> {
+        DMatrix::from_row_slice(2, 2, &[1.0, rho, rho, 1.0])
+    }
+
+    #[test]
+    fn test_market_validation() {
+        let new = |spots: Vec<f64>, correlation: DMatrix<f64>| {
+            MultiAssetMarket::new(spots, vec![0.2, 0.3], vec![0.0, 0.0], correlation, 0.05)
+        };
+
+        assert!(new(vec![100.0, 90.0], correlation(0.5)).is_ok());
+        assert!(matches!(
+            new(vec![100.0], correlation(0.5)),
+            Err(MultiAssetError::LengthMismatch)
+        ));
+        assert!(matches!(
+            new(vec![100.0, -90.0], correlation(0.5)),
+            Err(MultiAssetError::NonPositiveInput)
+        ));
+        assert!(matches!(
+            new(vec![100.0, 90.0], correlation(1.5)),
+            Err(MultiAssetError::InvalidCorrelation)
+        ));
+        assert!(matches!(
+            new(
+                vec![100.0, 90.0],
+                DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.4, 1.0])
+            ),
+            Err(MultiAssetError::InvalidCorrelation)
+        ));
+    }
+
+    #[test]
+    fn test_simulate_terminal() {
+        let market = MultiAssetMarket::new(
+            vec![100.0, 90.0],
+            vec![0.2, 0.3],
+            vec![0.0, 0.02],
+            correlation(-0.6),
+            0.05,
+        )
+        .unwrap();
+
+        let draws = market.simulate_terminal(1.0, 50_000, 42);
+        let n = draws.len() as f64;
+
+        // Risk-neutral forwards.
+        for i in 0..2 {
+            let mean = draws.iter().map(|x| x[i]).sum::<f64>() / n;
+            assert_approx_equal!(mean, market.forward(i, 1.0), 0.5);
+        }
+
+        // Correlation of the log-returns.
+        let logs: Vec<(f64, f64)> = draws.iter().map(|x| (x[0].ln(), x[1].ln())).collect();
+        let (m0, m1) = (
+            logs.iter().map(|x| x.0).sum::<f64>() / n,
+            logs.iter().map(|x| x.1).sum::<f64>() / n,
+        );
+        let covariance = logs.iter().map(|x| (x.0 - m0) * (x.1 - m1)).sum::<f64>() / n;
+        assert_approx_equal!(covariance, market.covariance(0, 1, 1.0), 2e-3);
+    }
+}
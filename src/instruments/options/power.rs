@@ -12,6 +12,7 @@
 //! Power contracts are options with the payoff: (S/K)^i
 //! where i is the (fixed) power of the contract.
 
+use crate::autodiff::Real;
 use crate::time::{DayCountConvention, DayCounter};
 use time::OffsetDateTime;
 
@@ -22,20 +23,20 @@ use time::OffsetDateTime;
 /// Power Option contract.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
-pub struct PowerOption {
+pub struct PowerOption<R: Real = f64> {
     /// `S` - Initial price of the underlying.
-    pub initial_price: f64,
+    pub initial_price: R,
     /// `K` - Strike price.
-    pub strike_price: f64,
+    pub strike_price: R,
     /// `i` - Power of the contract.
     pub power: f64,
 
     /// `r` - Risk-free rate parameter.
-    pub risk_free_rate: f64,
+    pub risk_free_rate: R,
     /// `b` - Cost of carry.
-    pub cost_of_carry: f64,
+    pub cost_of_carry: R,
     /// `v` - Volatility parameter.
-    pub volatility: f64,
+    pub volatility: R,
 
     /// `valuation_date` - Valuation date.
     pub evaluation_date: Option<OffsetDateTime>,
@@ -47,17 +48,17 @@ pub struct PowerOption {
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl PowerOption {
+impl<R: Real> PowerOption<R> {
     /// New Power Option contract.
     #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn new(
-        initial_price: f64,
-        strike_price: f64,
+        initial_price: R,
+        strike_price: R,
         power: f64,
-        risk_free_rate: f64,
-        cost_of_carry: f64,
-        volatility: f64,
+        risk_free_rate: R,
+        cost_of_carry: R,
+        volatility: R,
         evaluation_date: Option<OffsetDateTime>,
         expiration_date: OffsetDateTime,
     ) -> Self {
@@ -75,7 +76,7 @@ impl PowerOption {
 
     /// Power Option price.
     #[must_use]
-    pub fn price(&self) -> f64 {
+    pub fn price(&self) -> R {
         let S = self.initial_price;
         let K = self.strike_price;
         let r = self.risk_free_rate;
@@ -90,7 +91,7 @@ impl PowerOption {
             &DayCountConvention::Actual365,
         );
 
-        (S / K).powf(i) * (((b - 0.5 * v.powi(2)) * i - r + 0.5 * (i * v).powi(2)) * T).exp()
+        (S / K).powf(i) * (((b - v.powi(2) * 0.5) * i - r + (v * i).powi(2) * 0.5) * T).exp()
     }
 }
 
@@ -0,0 +1,345 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Quanto and composite options on an asset quoted in a foreign currency.
+//!
+//! The asset `S` (in foreign currency) and the exchange rate `X` (domestic
+//! currency per unit of foreign currency) are correlated geometric Brownian
+//! motions. Under the domestic risk-neutral measure
+//!
+//! $$
+//! \frac{dS}{S} = (r_f - q - \rho \sigma_S \sigma_X) dt + \sigma_S dW^S, \qquad
+//! \frac{dX}{X} = (r_d - r_f) dt + \sigma_X dW^X
+//! $$
+//!
+//! - A quanto option pays `X_0 max(phi (S_T - K), 0)` in domestic currency,
+//!   at a fixed exchange rate `X_0`: the asset's drift carries the quanto
+//!   adjustment `-rho sigma_S sigma_X`.
+//! - A composite option pays `max(phi (X_T S_T - K), 0)` in domestic
+//!   currency, with the strike in domestic currency: the asset's domestic
+//!   value `X S` has volatility `sqrt(sigma_S^2 + sigma_X^2 + 2 rho sigma_S sigma_X)`.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::{generalised_greeks, OptionGreeks, TypeFlag};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market data of an asset quoted in a foreign currency, and of the
+/// exchange rate to the domestic (payoff) currency.
+#[derive(Debug, Clone, Copy)]
+pub struct CrossCurrencyMarket {
+    /// `S` - Spot price of the asset, in foreign currency.
+    pub spot: f64,
+    /// `sigma_S` - Volatility of the asset.
+    pub asset_volatility: f64,
+    /// `q` - Continuous dividend yield of the asset.
+    pub dividend_yield: f64,
+    /// `X` - Spot exchange rate, in domestic currency per unit of foreign currency.
+    pub fx_spot: f64,
+    /// `sigma_X` - Volatility of the exchange rate.
+    pub fx_volatility: f64,
+    /// `rho` - Correlation between the asset and the exchange rate.
+    pub correlation: f64,
+    /// `r_d` - Domestic risk-free rate.
+    pub domestic_rate: f64,
+    /// `r_f` - Foreign risk-free rate.
+    pub foreign_rate: f64,
+}
+
+/// Quanto option: the payoff on the foreign asset is converted to domestic
+/// currency at a fixed exchange rate.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let market = CrossCurrencyMarket::new(100.0, 0.2, 0.04, 1.5, 0.1, 0.3, 0.08, 0.05).unwrap();
+/// let quanto = QuantoOption::new(105.0, 0.5, 1.5);
+///
+/// // Haug, "The Complete Guide to Option Pricing Formulas".
+/// assert!((quanto.price(&market, TypeFlag::Call) - 5.3280).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct QuantoOption {
+    /// `K` - Strike price, in foreign currency.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `X_0` - Fixed exchange rate of the payoff, in domestic currency per
+    /// unit of foreign currency.
+    pub fixed_rate: f64,
+}
+
+/// Composite option: an option on the domestic value of the foreign asset,
+/// with the strike in domestic currency.
+#[derive(Debug, Clone, Copy)]
+pub struct CompositeOption {
+    /// `K` - Strike price, in domestic currency.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+}
+
+/// Cross-currency market data errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CrossCurrencyError {
+    /// A spot price or volatility is not positive.
+    #[error("Spot prices and volatilities must be positive")]
+    NonPositiveInput,
+
+    /// The correlation is not in `[-1, 1]`.
+    #[error("Correlation must be in [-1, 1]")]
+    InvalidCorrelation,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CrossCurrencyMarket {
+    /// New cross-currency market.
+    ///
+    /// # Errors
+    ///
+    /// - `CrossCurrencyError::NonPositiveInput` for a non-positive spot price or volatility.
+    /// - `CrossCurrencyError::InvalidCorrelation` if the correlation is not in `[-1, 1]`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spot: f64,
+        asset_volatility: f64,
+        dividend_yield: f64,
+        fx_spot: f64,
+        fx_volatility: f64,
+        correlation: f64,
+        domestic_rate: f64,
+        foreign_rate: f64,
+    ) -> Result<Self, CrossCurrencyError> {
+        if [spot, asset_volatility, fx_spot, fx_volatility]
+            .iter()
+            .any(|&x| x <= 0.0)
+        {
+            return Err(CrossCurrencyError::NonPositiveInput);
+        }
+        if !(-1.0..=1.0).contains(&correlation) {
+            return Err(CrossCurrencyError::InvalidCorrelation);
+        }
+
+        Ok(Self {
+            spot,
+            asset_volatility,
+            dividend_yield,
+            fx_spot,
+            fx_volatility,
+            correlation,
+            domestic_rate,
+            foreign_rate,
+        })
+    }
+
+    /// Quanto drift adjustment of the asset, `-rho sigma_S sigma_X`.
+    #[must_use]
+    pub fn quanto_adjustment(&self) -> f64 {
+        -self.correlation * self.asset_volatility * self.fx_volatility
+    }
+
+    /// Forward price of the asset at time `t` under the domestic measure,
+    /// in foreign currency.
+    #[must_use]
+    pub fn quanto_forward(&self, t: f64) -> f64 {
+        self.spot * ((self.foreign_rate - self.dividend_yield + self.quanto_adjustment()) * t).exp()
+    }
+
+    /// Volatility of the asset's value in domestic currency, `X S`.
+    #[must_use]
+    pub fn composite_volatility(&self) -> f64 {
+        let (v_s, v_x) = (self.asset_volatility, self.fx_volatility);
+
+        (v_s * v_s + v_x * v_x + 2.0 * self.correlation * v_s * v_x).sqrt()
+    }
+}
+
+impl QuantoOption {
+    /// New quanto option.
+    #[must_use]
+    pub const fn new(strike_price: f64, time_to_expiry: f64, fixed_rate: f64) -> Self {
+        Self {
+            strike_price,
+            time_to_expiry,
+            fixed_rate,
+        }
+    }
+
+    /// Price of the quanto option, in domestic currency.
+    #[must_use]
+    pub fn price(&self, market: &CrossCurrencyMarket, option_type: TypeFlag) -> f64 {
+        self.greeks(market, option_type).price
+    }
+
+    /// Price and Greeks of the quanto option, in domestic currency.
+    ///
+    /// Delta and gamma are with respect to the asset price (in foreign
+    /// currency), vega to the asset's volatility with the quanto adjustment
+    /// held fixed, and rho to the domestic rate.
+    #[must_use]
+    pub fn greeks(&self, market: &CrossCurrencyMarket, option_type: TypeFlag) -> OptionGreeks {
+        let T = self.time_to_expiry;
+        let carry = market.foreign_rate - market.dividend_yield + market.quanto_adjustment();
+
+        let greeks = generalised_greeks(
+            market.spot,
+            self.strike_price,
+            T,
+            market.domestic_rate,
+            carry,
+            market.asset_volatility,
+            option_type,
+        );
+        let scale = |x: f64| self.fixed_rate * x;
+
+        OptionGreeks {
+            price: scale(greeks.price),
+            delta: scale(greeks.delta),
+            gamma: scale(greeks.gamma),
+            vega: scale(greeks.vega),
+            theta: scale(greeks.theta),
+            // The cost of carry does not depend on the domestic rate.
+            rho: -T * scale(greeks.price),
+            vanna: greeks.vanna.map(scale),
+            volga: greeks.volga.map(scale),
+            charm: greeks.charm.map(scale),
+        }
+    }
+}
+
+impl CompositeOption {
+    /// New composite option.
+    #[must_use]
+    pub const fn new(strike_price: f64, time_to_expiry: f64) -> Self {
+        Self {
+            strike_price,
+            time_to_expiry,
+        }
+    }
+
+    /// Price of the composite option, in domestic currency.
+    #[must_use]
+    pub fn price(&self, market: &CrossCurrencyMarket, option_type: TypeFlag) -> f64 {
+        self.greeks(market, option_type).price
+    }
+
+    /// Price and Greeks of the composite option, in domestic currency.
+    ///
+    /// Delta and gamma are with respect to the asset's value in domestic
+    /// currency, `X S`, and vega to the composite volatility.
+    #[must_use]
+    pub fn greeks(&self, market: &CrossCurrencyMarket, option_type: TypeFlag) -> OptionGreeks {
+        generalised_greeks(
+            market.fx_spot * market.spot,
+            self.strike_price,
+            self.time_to_expiry,
+            market.domestic_rate,
+            market.domestic_rate - market.dividend_yield,
+            market.composite_volatility(),
+            option_type,
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_quanto {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    #[test]
+    fn test_quanto_haug() {
+        let market = CrossCurrencyMarket::new(100.0, 0.2, 0.04, 1.5, 0.1, 0.3, 0.08, 0.05).unwrap();
+        let quanto = QuantoOption::new(105.0, 0.5, 1.5);
+
+        assert_approx_equal!(quanto.price(&market, TypeFlag::Call), 5.3280, 1e-4);
+
+        // Rho holds the foreign rates fixed.
+        let h = 1e-5;
+        let bumped = |r_d: f64| {
+            quanto.price(
+                &CrossCurrencyMarket {
+                    domestic_rate: r_d,
+                    ..market
+                },
+                TypeFlag::Call,
+            )
+        };
+        let rho = (bumped(0.08 + h) - bumped(0.08 - h)) / (2.0 * h);
+
+        assert_approx_equal!(quanto.greeks(&market, TypeFlag::Call).rho, rho, 1e-6);
+    }
+
+    #[test]
+    fn test_monte_carlo() {
+        let market =
+            CrossCurrencyMarket::new(100.0, 0.25, 0.01, 1.2, 0.12, -0.4, 0.04, 0.02).unwrap();
+        let (T, paths) = (1.0, 200_000);
+        let quanto = QuantoOption::new(95.0, T, 1.1);
+        let composite = CompositeOption::new(120.0, T);
+
+        // Joint simulation of the asset and the exchange rate under the
+        // domestic measure.
+        let mut rng = StdRng::seed_from_u64(42);
+        let (v_s, v_x, rho) = (0.25, 0.12, -0.4);
+        let (mut quanto_sum, mut composite_sum) = (0.0, 0.0);
+
+        for _ in 0..paths {
+            let z_1: f64 = rng.sample(StandardNormal);
+            let z_2: f64 = rng.sample(StandardNormal);
+            let w_x = rho * z_1 + (1.0_f64 - rho * rho).sqrt() * z_2;
+
+            let s =
+                100.0 * ((0.02 - 0.01 - rho * v_s * v_x - 0.5 * v_s * v_s) * T + v_s * z_1).exp();
+            let x = 1.2 * ((0.04 - 0.02 - 0.5 * v_x * v_x) * T + v_x * w_x).exp();
+
+            quanto_sum += 1.1 * (s - 95.0_f64).max(0.0);
+            composite_sum += (x * s - 120.0_f64).max(0.0);
+        }
+
+        let df = (-0.04 * T).exp();
+        let n = f64::from(paths);
+
+        assert_approx_equal!(
+            quanto.price(&market, TypeFlag::Call),
+            df * quanto_sum / n,
+            0.1
+        );
+        assert_approx_equal!(
+            composite.price(&market, TypeFlag::Call),
+            df * composite_sum / n,
+            0.1
+        );
+    }
+
+    #[test]
+    fn test_invalid_market() {
+        assert_eq!(
+            CrossCurrencyMarket::new(100.0, 0.2, 0.0, 1.5, 0.1, 1.3, 0.08, 0.05).unwrap_err(),
+            CrossCurrencyError::InvalidCorrelation
+        );
+        assert_eq!(
+            CrossCurrencyMarket::new(100.0, 0.2, 0.0, -1.5, 0.1, 0.3, 0.08, 0.05).unwrap_err(),
+            CrossCurrencyError::NonPositiveInput
+        );
+    }
+}
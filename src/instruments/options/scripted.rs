@@ -0,0 +1,470 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Scripted payoffs: bespoke path-dependent payoffs, built from
+//! [`PathObservable`] combinators and priced by Monte Carlo on a
+//! [`MultiAssetMarket`], without a new instrument type for each.
+//!
+//! A payoff is evaluated on the fixings of the underlyings on the
+//! observation dates. Observables reduce a series of fixings (an underlying
+//! or a weighted basket, see [`PathSeries`]) to a number, and combine with
+//! `+`, `-`, `*`, [`max`](PathObservable::max) and [`min`](PathObservable::min).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::MultiAssetMarket;
+use crate::instruments::PathDependentPayoff;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Series of fixings that an observable reduces.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSeries {
+    /// A single underlying, by index in the market.
+    Asset(usize),
+    /// A basket of the underlyings, `sum_i w_i S_i`.
+    Basket(Vec<f64>),
+}
+
+/// Direction of a barrier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierDirection {
+    /// Hit when a fixing is at or above the level.
+    Up,
+    /// Hit when a fixing is at or below the level.
+    Down,
+}
+
+/// Payoff expression on the fixings of the underlyings.
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use nalgebra::DMatrix;
+///
+/// let market = MultiAssetMarket::new(
+///     vec![100.0, 100.0],
+///     vec![0.2, 0.3],
+///     vec![0.0, 0.0],
+///     DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]),
+///     0.05,
+/// )
+/// .unwrap();
+///
+/// // Call on the average of an equally weighted basket, knocked out if
+/// // the second underlying ever fixes below 70.
+/// let basket = PathSeries::Basket(vec![0.5, 0.5]);
+/// let payoff = PathObservable::average(basket).call(100.0)
+///     * (1.0 - PathObservable::barrier_hit(1, 70.0, BarrierDirection::Down));
+///
+/// let times: Vec<f64> = (1..=12).map(|i| f64::from(i) / 12.0).collect();
+/// let mc = ScriptedPayoff::new(payoff, times).price_monte_carlo(&market, 20_000, 42);
+///
+/// assert!(mc.price > 0.0 && mc.std_error < 0.1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathObservable {
+    /// A constant.
+    Constant(f64),
+    /// The fixing on the last observation date.
+    Terminal(PathSeries),
+    /// The fixing on an observation date, by index.
+    Fixing(PathSeries, usize),
+    /// The arithmetic average of the fixings.
+    Average(PathSeries),
+    /// The largest fixing.
+    PathMaximum(PathSeries),
+    /// The smallest fixing.
+    PathMinimum(PathSeries),
+    /// 1 if any fixing hits the barrier level, and 0 otherwise.
+    BarrierHit(PathSeries, f64, BarrierDirection),
+    /// Sum of two observables.
+    Sum(Box<PathObservable>, Box<PathObservable>),
+    /// Difference of two observables.
+    Difference(Box<PathObservable>, Box<PathObservable>),
+    /// Product of two observables.
+    Product(Box<PathObservable>, Box<PathObservable>),
+    /// Larger of two observables.
+    Max(Box<PathObservable>, Box<PathObservable>),
+    /// Smaller of two observables.
+    Min(Box<PathObservable>, Box<PathObservable>),
+}
+
+/// Payoff paid on the last observation date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedPayoff {
+    /// Payoff expression.
+    pub payoff: PathObservable,
+    /// Observation times (in years), increasing. The payoff is paid at the last one.
+    pub observation_times: Vec<f64>,
+}
+
+/// Monte Carlo price of a scripted payoff.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptedPayoffMonteCarloResult {
+    /// Price.
+    pub price: f64,
+    /// Standard error of the price.
+    pub std_error: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl From<usize> for PathSeries {
+    fn from(asset: usize) -> Self {
+        Self::Asset(asset)
+    }
+}
+
+impl PathSeries {
+    // Value of the series on one observation date, from the fixings of
+    // every underlying on that date.
+    fn value(&self, fixings: &[f64]) -> f64 {
+        match self {
+            Self::Asset(i) => fixings[*i],
+            Self::Basket(weights) => weights.iter().zip(fixings).map(|(w, s)| w * s).sum(),
+        }
+    }
+
+    // Values of the series on every observation date.
+    fn values<'a>(&'a self, fixings: &'a [Vec<f64>]) -> impl Iterator<Item = f64> + 'a {
+        fixings.iter().map(|f| self.value(f))
+    }
+}
+
+impl From<f64> for PathObservable {
+    fn from(value: f64) -> Self {
+        Self::Constant(value)
+    }
+}
+
+impl PathObservable {
+    /// The fixing on the last observation date.
+    pub fn terminal(series: impl Into<PathSeries>) -> Self {
+        Self::Terminal(series.into())
+    }
+
+    /// The fixing on the observation date with index `date`.
+    pub fn fixing(series: impl Into<PathSeries>, date: usize) -> Self {
+        Self::Fixing(series.into(), date)
+    }
+
+    /// The arithmetic average of the fixings.
+    pub fn average(series: impl Into<PathSeries>) -> Self {
+        Self::Average(series.into())
+    }
+
+    /// The largest fixing.
+    pub fn maximum(series: impl Into<PathSeries>) -> Self {
+        Self::PathMaximum(series.into())
+    }
+
+    /// The smallest fixing.
+    pub fn minimum(series: impl Into<PathSeries>) -> Self {
+        Self::PathMinimum(series.into())
+    }
+
+    /// 1 if any fixing hits the barrier `level` from the given direction,
+    /// and 0 otherwise.
+    pub fn barrier_hit(
+        series: impl Into<PathSeries>,
+        level: f64,
+        direction: BarrierDirection,
+    ) -> Self {
+        Self::BarrierHit(series.into(), level, direction)
+    }
+
+    /// Larger of this and another observable.
+    #[must_use]
+    pub fn max(self, other: impl Into<Self>) -> Self {
+        Self::Max(Box::new(self), Box::new(other.into()))
+    }
+
+    /// Smaller of this and another observable.
+    #[must_use]
+    pub fn min(self, other: impl Into<Self>) -> Self {
+        Self::Min(Box::new(self), Box::new(other.into()))
+    }
+
+    /// Call payoff on this observable, `max(x - K, 0)`.
+    #[must_use]
+    pub fn call(self, strike: f64) -> Self {
+        (self - strike).max(0.0)
+    }
+
+    /// Put payoff on this observable, `max(K - x, 0)`.
+    #[must_use]
+    pub fn put(self, strike: f64) -> Self {
+        (Self::Constant(strike) - self).max(0.0)
+    }
+
+    /// Value of the observable on the fixings, one row per observation date
+    /// with one price per underlying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no fixings, or if an underlying or observation
+    /// date is out of range.
+    #[must_use]
+    pub fn evaluate(&self, fixings: &[Vec<f64>]) -> f64 {
+        assert!(!fixings.is_empty(), "A payoff needs at least one fixing.");
+
+        match self {
+            Self::Constant(value) => *value,
+            Self::Terminal(s) => s.value(&fixings[fixings.len() - 1]),
+            Self::Fixing(s, date) => s.value(&fixings[*date]),
+            #[allow(clippy::cast_precision_loss)]
+            Self::Average(s) => s.values(fixings).sum::<f64>() / fixings.len() as f64,
+            Self::PathMaximum(s) => s.values(fixings).fold(f64::NEG_INFINITY, f64::max),
+            Self::PathMinimum(s) => s.values(fixings).fold(f64::INFINITY, f64::min),
+            Self::BarrierHit(s, level, direction) => {
+                let hit = match direction {
+                    BarrierDirection::Up => s.values(fixings).any(|x| x >= *level),
+                    BarrierDirection::Down => s.values(fixings).any(|x| x <= *level),
+                };
+                f64::from(u8::from(hit))
+            }
+            Self::Sum(a, b) => a.evaluate(fixings) + b.evaluate(fixings),
+            Self::Difference(a, b) => a.evaluate(fixings) - b.evaluate(fixings),
+            Self::Product(a, b) => a.evaluate(fixings) * b.evaluate(fixings),
+            Self::Max(a, b) => a.evaluate(fixings).max(b.evaluate(fixings)),
+            Self::Min(a, b) => a.evaluate(fixings).min(b.evaluate(fixings)),
+        }
+    }
+}
+
+impl<T: Into<PathObservable>> Add<T> for PathObservable {
+    type Output = Self;
+
+    fn add(self, rhs: T) -> Self {
+        Self::Sum(Box::new(self), Box::new(rhs.into()))
+    }
+}
+
+impl<T: Into<PathObservable>> Sub<T> for PathObservable {
+    type Output = Self;
+
+    fn sub(self, rhs: T) -> Self {
+        Self::Difference(Box::new(self), Box::new(rhs.into()))
+    }
+}
+
+impl<T: Into<PathObservable>> Mul<T> for PathObservable {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::Product(Box::new(self), Box::new(rhs.into()))
+    }
+}
+
+impl Neg for PathObservable {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::Constant(0.0) - self
+    }
+}
+
+impl Add<PathObservable> for f64 {
+    type Output = PathObservable;
+
+    fn add(self, rhs: PathObservable) -> PathObservable {
+        PathObservable::Constant(self) + rhs
+    }
+}
+
+impl Sub<PathObservable> for f64 {
+    type Output = PathObservable;
+
+    fn sub(self, rhs: PathObservable) -> PathObservable {
+        PathObservable::Constant(self) - rhs
+    }
+}
+
+impl Mul<PathObservable> for f64 {
+    type Output = PathObservable;
+
+    fn mul(self, rhs: PathObservable) -> PathObservable {
+        PathObservable::Constant(self) * rhs
+    }
+}
+
+/// A single underlying's path, as the fixings of underlying 0.
+impl PathDependentPayoff for PathObservable {
+    fn payoff(&self, path: &[f64]) -> f64 {
+        let fixings: Vec<Vec<f64>> = path.iter().map(|&s| vec![s]).collect();
+
+        self.evaluate(&fixings)
+    }
+}
+
+impl ScriptedPayoff {
+    /// New scripted payoff.
+    #[must_use]
+    pub fn new(payoff: PathObservable, observation_times: Vec<f64>) -> Self {
+        Self {
+            payoff,
+            observation_times,
+        }
+    }
+
+    /// Monte Carlo price, from exact correlated log-normal steps between the
+    /// observation dates (see [`MultiAssetMarket::simulate_paths`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no observation times, if they are not positive
+    /// and increasing, or if there are fewer than 2 paths.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn price_monte_carlo(
+        &self,
+        market: &MultiAssetMarket,
+        paths: usize,
+        seed: u64,
+    ) -> ScriptedPayoffMonteCarloResult {
+        let times = &self.observation_times;
+        assert!(
+            !times.is_empty(),
+            "A payoff needs at least one observation time."
+        );
+        assert!(paths >= 2, "Monte Carlo needs at least 2 paths.");
+
+        let df = (-market.risk_free_rate * times[times.len() - 1]).exp();
+
+        let payoffs: Vec<f64> = market
+            .simulate_paths(times, paths, seed)
+            .iter()
+            .map(|fixings| df * self.payoff.evaluate(fixings))
+            .collect();
+
+        let n = paths as f64;
+        let price = payoffs.iter().sum::<f64>() / n;
+        let variance = payoffs.iter().map(|x| (x - price).powi(2)).sum::<f64>() / (n - 1.0);
+
+        ScriptedPayoffMonteCarloResult {
+            price,
+            std_error: (variance / n).sqrt(),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_scripted {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::{black_scholes_greeks, BasketOption, TypeFlag};
+    use nalgebra::DMatrix;
+
+    fn market() -> MultiAssetMarket {
+        MultiAssetMarket::new(
+            vec![100.0, 90.0],
+            vec![0.2, 0.3],
+            vec![0.01, 0.0],
+            DMatrix::from_row_slice(2, 2, &[1.0, 0.4, 0.4, 1.0]),
+            0.05,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let fixings = vec![vec![100.0, 80.0], vec![110.0, 70.0], vec![105.0, 90.0]];
+        let basket = PathSeries::Basket(vec![0.5, 0.5]);
+        let eval = |x: &PathObservable| x.evaluate(&fixings);
+
+        assert_approx_equal!(eval(&PathObservable::terminal(0)), 105.0, 1e-12);
+        assert_approx_equal!(eval(&PathObservable::fixing(1, 1)), 70.0, 1e-12);
+        assert_approx_equal!(eval(&PathObservable::average(0)), 105.0, 1e-12);
+        assert_approx_equal!(eval(&PathObservable::maximum(0)), 110.0, 1e-12);
+        assert_approx_equal!(eval(&PathObservable::minimum(1)), 70.0, 1e-12);
+        assert_approx_equal!(eval(&PathObservable::average(basket.clone())), 92.5, 1e-12);
+        assert_approx_equal!(eval(&PathObservable::terminal(basket.clone())), 97.5, 1e-12);
+
+        let down = |level| PathObservable::barrier_hit(1, level, BarrierDirection::Down);
+        assert_approx_equal!(eval(&down(70.0)), 1.0, 1e-12);
+        assert_approx_equal!(eval(&down(69.0)), 0.0, 1e-12);
+        assert_approx_equal!(
+            eval(&PathObservable::barrier_hit(0, 110.0, BarrierDirection::Up)),
+            1.0,
+            1e-12
+        );
+
+        // Best-of call, and a put spread, from the combinators.
+        let best_of = PathObservable::terminal(0)
+            .max(PathObservable::terminal(1))
+            .call(95.0);
+        assert_approx_equal!(eval(&best_of), 10.0, 1e-12);
+
+        let spread = PathObservable::minimum(1).put(85.0) - PathObservable::minimum(1).put(75.0);
+        assert_approx_equal!(eval(&spread), 10.0, 1e-12);
+        assert_approx_equal!(
+            eval(&(2.0 * -PathObservable::terminal(1) + 1.0)),
+            -179.0,
+            1e-12
+        );
+
+        // The single underlying payoff trait.
+        let lookback = PathObservable::maximum(0) - PathObservable::terminal(0);
+        assert_approx_equal!(lookback.payoff(&[100.0, 120.0, 90.0]), 30.0, 1e-12);
+    }
+
+    #[test]
+    fn test_vanilla_and_basket() {
+        let market = market();
+
+        // European call on the first underlying.
+        let call = ScriptedPayoff::new(PathObservable::terminal(0).call(100.0), vec![1.0]);
+        let mc = call.price_monte_carlo(&market, 200_000, 1);
+        let bs = black_scholes_greeks(100.0, 100.0, 1.0, 0.05, 0.01, 0.2, TypeFlag::Call).price;
+        assert_approx_equal!(mc.price, bs, 4.0 * mc.std_error);
+
+        // Same paths as the basket option pricer.
+        let weights = vec![0.6, 0.4];
+        let basket = PathObservable::terminal(PathSeries::Basket(weights.clone())).call(95.0);
+        let mc = ScriptedPayoff::new(basket, vec![1.0]).price_monte_carlo(&market, 10_000, 7);
+        let expected = BasketOption::new(market, weights, 95.0, 1.0)
+            .unwrap()
+            .price_monte_carlo(10_000, 7);
+        assert_approx_equal!(mc.price, expected.call, 1e-10);
+    }
+
+    #[test]
+    fn test_barrier_parity() {
+        let market = market();
+        let times: Vec<f64> = (1..=52).map(|i| f64::from(i) / 52.0).collect();
+        let price = |payoff: &PathObservable| {
+            ScriptedPayoff::new(payoff.clone(), times.clone())
+                .price_monte_carlo(&market, 20_000, 3)
+                .price
+        };
+
+        let vanilla = PathObservable::terminal(0).call(100.0);
+        let hit = PathObservable::barrier_hit(0, 85.0, BarrierDirection::Down);
+        let knock_in = vanilla.clone() * hit.clone();
+        let knock_out = vanilla.clone() * (1.0 - hit);
+
+        // In + out = vanilla, path by path.
+        assert_approx_equal!(price(&knock_in) + price(&knock_out), price(&vanilla), 1e-9);
+
+        // A floating strike lookback is worth more than the vanilla call.
+        let lookback = PathObservable::terminal(0) - PathObservable::minimum(0);
+        assert!(price(&lookback) > price(&vanilla));
+    }
+}
@@ -0,0 +1,240 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Spread options.
+//!
+//! A spread option pays on the difference between two underlyings:
+//!
+//! - Call: `max(S_1(T) - S_2(T) - K, 0)`
+//! - Put: `max(K - S_1(T) + S_2(T), 0)`
+//!
+//! With `K = 0` this is an exchange option, priced exactly by Margrabe's
+//! formula, to which both approximations below reduce.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{BasketOption, MultiAssetError, MultiAssetMarket, MultiAssetMonteCarloResult};
+use crate::statistics::distributions::{Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Spread option parameters.
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use nalgebra::DMatrix;
+///
+/// let market = MultiAssetMarket::new(
+///     vec![110.0, 100.0],
+///     vec![0.25, 0.2],
+///     vec![0.0, 0.0],
+///     DMatrix::from_row_slice(2, 2, &[1.0, 0.6, 0.6, 1.0]),
+///     0.05,
+/// )
+/// .unwrap();
+///
+/// let spread = SpreadOption::new(market, 5.0, 1.0).unwrap();
+///
+/// let (kirk, _) = spread.price_kirk();
+/// let (bs, _) = spread.price_bjerksund_stensland();
+///
+/// assert!((kirk - bs).abs() < 0.05);
+/// ```
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub struct SpreadOption {
+    /// Market data of the two underlyings, `S_1` first.
+    pub market: MultiAssetMarket,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `T` - Time to expiry/maturity.
+    pub time_to_maturity: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SpreadOption {
+    /// New spread option.
+    ///
+    /// # Errors
+    ///
+    /// `MultiAssetError::WrongNumberOfAssets` if the market does not have
+    /// exactly two underlyings.
+    pub fn new(
+        market: MultiAssetMarket,
+        strike_price: f64,
+        time_to_maturity: f64,
+    ) -> Result<Self, MultiAssetError> {
+        if market.len() != 2 {
+            return Err(MultiAssetError::WrongNumberOfAssets);
+        }
+
+        Ok(Self {
+            market,
+            strike_price,
+            time_to_maturity,
+        })
+    }
+
+    /// Spread option price using Kirk's (1995) approximation: `S_2 + K` is
+    /// treated as a lognormal asset, and the option is priced as an exchange
+    /// option.
+    ///
+    /// Returns a tuple: `(call_price, put_price)`.
+    #[must_use]
+    pub fn price_kirk(&self) -> (f64, f64) {
+        let (F1, F2, v1, v2, rho, T, df) = self.unpack();
+        let K = self.strike_price;
+
+        let z = F2 / (F2 + K);
+        let v = (v1 * v1 - 2.0 * rho * v1 * v2 * z + v2 * v2 * z * z).sqrt();
+
+        let d1 = ((F1 / (F2 + K)).ln() + 0.5 * v * v * T) / (v * T.sqrt());
+        let d2 = d1 - v * T.sqrt();
+
+        let norm = Gaussian::default();
+
+        let call = df * (F1 * norm.cdf(d1) - (F2 + K) * norm.cdf(d2));
+        let put = df * ((F2 + K) * norm.cdf(-d2) - F1 * norm.cdf(-d1));
+
+        (call, put)
+    }
+
+    /// Spread option price using the Bjerksund and Stensland (2011)
+    /// approximation, a lower bound which is typically closer to the exact
+    /// price than Kirk's approximation.
+    ///
+    /// Returns a tuple: `(call_price, put_price)`.
+    /// The put is obtained by put-call parity.
+    #[must_use]
+    pub fn price_bjerksund_stensland(&self) -> (f64, f64) {
+        let (F1, F2, v1, v2, rho, T, df) = self.unpack();
+        let K = self.strike_price;
+
+        let a = F2 + K;
+        let b = F2 / a;
+        let v = (v1 * v1 - 2.0 * b * rho * v1 * v2 + b * b * v2 * v2).sqrt();
+
+        let moneyness = (F1 / a).ln();
+        let denominator = v * T.sqrt();
+
+        let d1 = (moneyness + (0.5 * v1 * v1 - b * rho * v1 * v2 + 0.5 * b * b * v2 * v2) * T)
+            / denominator;
+        let d2 = (moneyness + (-0.5 * v1 * v1 + rho * v1 * v2 + (0.5 * b * b - b) * v2 * v2) * T)
+            / denominator;
+        let d3 = (moneyness + (-0.5 * v1 * v1 + 0.5 * b * b * v2 * v2) * T) / denominator;
+
+        let norm = Gaussian::default();
+
+        let call = df * (F1 * norm.cdf(d1) - F2 * norm.cdf(d2) - K * norm.cdf(d3));
+        let put = call - df * (F1 - F2 - K);
+
+        (call, put)
+    }
+
+    /// Monte Carlo price of the spread option, simulating the correlated
+    /// underlyings at expiry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 2 paths.
+    #[must_use]
+    pub fn price_monte_carlo(&self, paths: usize, seed: u64) -> MultiAssetMonteCarloResult {
+        BasketOption {
+            market: self.market.clone(),
+            weights: vec![1.0, -1.0],
+            strike_price: self.strike_price,
+            time_to_maturity: self.time_to_maturity,
+        }
+        .price_monte_carlo(paths, seed)
+    }
+
+    // Forwards, volatilities, correlation, time to maturity and discount factor.
+    fn unpack(&self) -> (f64, f64, f64, f64, f64, f64, f64) {
+        let T = self.time_to_maturity;
+        let m = &self.market;
+
+        (
+            m.forward(0, T),
+            m.forward(1, T),
+            m.volatilities[0],
+            m.volatilities[1],
+            m.correlation[(0, 1)],
+            T,
+            (-m.risk_free_rate * T).exp(),
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_spread {
+    use super::*;
+    use crate::assert_approx_equal;
+    use nalgebra::DMatrix;
+
+    fn spread(strike: f64, rho: f64) -> SpreadOption {
+        let market = MultiAssetMarket::new(
+            vec![122.0, 120.0],
+            vec![0.2, 0.25],
+            vec![0.0, 0.02],
+            DMatrix::from_row_slice(2, 2, &[1.0, rho, rho, 1.0]),
+            0.1,
+        )
+        .unwrap();
+
+        SpreadOption::new(market, strike, 0.5).unwrap()
+    }
+
+    #[test]
+    fn test_exchange_option() {
+        let option = spread(0.0, 0.3);
+
+        // Margrabe's formula.
+        let (F1, F2, v1, v2, rho, T, df) = option.unpack();
+        let v = (v1 * v1 + v2 * v2 - 2.0 * rho * v1 * v2).sqrt();
+        let d1 = ((F1 / F2).ln() + 0.5 * v * v * T) / (v * T.sqrt());
+        let norm = Gaussian::default();
+        let margrabe = df * (F1 * norm.cdf(d1) - F2 * norm.cdf(d1 - v * T.sqrt()));
+
+        assert_approx_equal!(option.price_kirk().0, margrabe, 1e-10);
+        assert_approx_equal!(option.price_bjerksund_stensland().0, margrabe, 1e-10);
+    }
+
+    #[test]
+    fn test_spread_approximations() {
+        for (strike, rho) in [(3.0, -0.5), (5.0, 0.5), (10.0, 0.9)] {
+            let option = spread(strike, rho);
+
+            let kirk = option.price_kirk();
+            let bs = option.price_bjerksund_stensland();
+            let mc = option.price_monte_carlo(200_000, 1);
+
+            assert!(mc.call_std_error < 0.05);
+            assert_approx_equal!(kirk.0, mc.call, 0.1);
+            assert_approx_equal!(bs.0, mc.call, 0.1);
+            assert_approx_equal!(kirk.1, mc.put, 0.1);
+            assert_approx_equal!(bs.1, mc.put, 0.1);
+
+            // Put-call parity.
+            let (F1, F2, _, _, _, _, df) = option.unpack();
+            assert_approx_equal!(kirk.0 - kirk.1, df * (F1 - F2 - strike), 1e-10);
+            assert_approx_equal!(bs.0 - bs.1, df * (F1 - F2 - strike), 1e-10);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Option strategies: portfolios of long and short vanilla options (and the
+//! underlying) on a single asset, priced with Black-Scholes-Merton.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{black_scholes_greeks, OptionGreeks, TypeFlag};
+use crate::math::{Brent, RootFinder};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Leg of an option strategy. Negative quantities are short positions.
+#[derive(Debug, Clone, Copy)]
+pub enum StrategyLeg {
+    /// European option.
+    Option {
+        /// Call or put flag.
+        option_type: TypeFlag,
+        /// `K` - Strike price.
+        strike_price: f64,
+        /// `T` - Time to expiry (in years).
+        time_to_expiry: f64,
+        /// Number of options.
+        quantity: f64,
+    },
+    /// Position in the underlying.
+    Underlying {
+        /// Number of units.
+        quantity: f64,
+    },
+}
+
+/// Option strategy: a portfolio of legs on the same underlying.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // Long straddle: a call and a put with the same strike.
+/// let straddle = Strategy::new(100.0, 0.05, 0.0, 0.2).straddle(100.0, 1.0);
+///
+/// let call = black_scholes_greeks(100.0, 100.0, 1.0, 0.05, 0.0, 0.2, TypeFlag::Call);
+/// let put = black_scholes_greeks(100.0, 100.0, 1.0, 0.05, 0.0, 0.2, TypeFlag::Put);
+///
+/// assert!((straddle.price() - call.price - put.price).abs() < 1e-12);
+/// assert_eq!(straddle.break_even_points().len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Strategy {
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+    /// Legs of the strategy.
+    pub legs: Vec<StrategyLeg>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Strategy {
+    /// New strategy without any legs.
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            legs: Vec::new(),
+        }
+    }
+
+    /// Add a leg.
+    #[must_use]
+    pub fn with_leg(mut self, leg: StrategyLeg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Add `quantity` European options (negative for a short position).
+    #[must_use]
+    pub fn with_option(
+        self,
+        option_type: TypeFlag,
+        strike_price: f64,
+        time_to_expiry: f64,
+        quantity: f64,
+    ) -> Self {
+        self.with_leg(StrategyLeg::Option {
+            option_type,
+            strike_price,
+            time_to_expiry,
+            quantity,
+        })
+    }
+
+    /// Add `quantity` units of the underlying (negative for a short position).
+    #[must_use]
+    pub fn with_underlying(self, quantity: f64) -> Self {
+        self.with_leg(StrategyLeg::Underlying { quantity })
+    }
+
+    /// Long straddle: long a call and a put struck at `K`.
+    #[must_use]
+    pub fn straddle(self, strike_price: f64, time_to_expiry: f64) -> Self {
+        self.with_option(TypeFlag::Call, strike_price, time_to_expiry, 1.0)
+            .with_option(TypeFlag::Put, strike_price, time_to_expiry, 1.0)
+    }
+
+    /// Long strangle: long a put struck at `K_p` and a call struck at `K_c`.
+    #[must_use]
+    pub fn strangle(self, put_strike: f64, call_strike: f64, time_to_expiry: f64) -> Self {
+        self.with_option(TypeFlag::Put, put_strike, time_to_expiry, 1.0)
+            .with_option(TypeFlag::Call, call_strike, time_to_expiry, 1.0)
+    }
+
+    /// Bull call spread: long a call struck at `K_1`, short a call struck at
+    /// `K_2 > K_1`.
+    #[must_use]
+    pub fn bull_call_spread(
+        self,
+        lower_strike: f64,
+        upper_strike: f64,
+        time_to_expiry: f64,
+    ) -> Self {
+        self.with_option(TypeFlag::Call, lower_strike, time_to_expiry, 1.0)
+            .with_option(TypeFlag::Call, upper_strike, time_to_expiry, -1.0)
+    }
+
+    /// Bear put spread: long a put struck at `K_2`, short a put struck at
+    /// `K_1 < K_2`.
+    #[must_use]
+    pub fn bear_put_spread(
+        self,
+        lower_strike: f64,
+        upper_strike: f64,
+        time_to_expiry: f64,
+    ) -> Self {
+        self.with_option(TypeFlag::Put, upper_strike, time_to_expiry, 1.0)
+            .with_option(TypeFlag::Put, lower_strike, time_to_expiry, -1.0)
+    }
+
+    /// Long call butterfly: long calls struck at `K_1` and `K_3`, short two
+    /// calls struck at `K_2`.
+    #[must_use]
+    pub fn butterfly(
+        self,
+        lower_strike: f64,
+        middle_strike: f64,
+        upper_strike: f64,
+        time_to_expiry: f64,
+    ) -> Self {
+        self.with_option(TypeFlag::Call, lower_strike, time_to_expiry, 1.0)
+            .with_option(TypeFlag::Call, middle_strike, time_to_expiry, -2.0)
+            .with_option(TypeFlag::Call, upper_strike, time_to_expiry, 1.0)
+    }
+
+    /// Collar: long the underlying, long a put struck at `K_p` and short a
+    /// call struck at `K_c`.
+    #[must_use]
+    pub fn collar(self, put_strike: f64, call_strike: f64, time_to_expiry: f64) -> Self {
+        self.with_underlying(1.0)
+            .with_option(TypeFlag::Put, put_strike, time_to_expiry, 1.0)
+            .with_option(TypeFlag::Call, call_strike, time_to_expiry, -1.0)
+    }
+
+    /// Calendar spread: short a call expiring at `T_1`, long a call expiring
+    /// at `T_2 > T_1`, both struck at `K`.
+    #[must_use]
+    pub fn calendar_spread(self, strike_price: f64, near_expiry: f64, far_expiry: f64) -> Self {
+        self.with_option(TypeFlag::Call, strike_price, near_expiry, -1.0)
+            .with_option(TypeFlag::Call, strike_price, far_expiry, 1.0)
+    }
+
+    /// Cost of the strategy (the sum of the leg prices).
+    #[must_use]
+    pub fn price(&self) -> f64 {
+        self.greeks().price
+    }
+
+    /// Price and Greeks of the strategy (the sums over the legs).
+    #[must_use]
+    pub fn greeks(&self) -> OptionGreeks {
+        let mut total = OptionGreeks {
+            price: 0.0,
+            delta: 0.0,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: 0.0,
+            rho: 0.0,
+            vanna: Some(0.0),
+            volga: Some(0.0),
+            charm: Some(0.0),
+        };
+        let add =
+            |total: Option<f64>, leg: Option<f64>, quantity: f64| Some(total? + quantity * leg?);
+
+        for leg in &self.legs {
+            match *leg {
+                StrategyLeg::Option {
+                    option_type,
+                    strike_price,
+                    time_to_expiry,
+                    quantity,
+                } => {
+                    let greeks = black_scholes_greeks(
+                        self.initial_price,
+                        strike_price,
+                        time_to_expiry,
+                        self.risk_free_rate,
+                        self.dividend_yield,
+                        self.volatility,
+                        option_type,
+                    );
+
+                    total.price += quantity * greeks.price;
+                    total.delta += quantity * greeks.delta;
+                    total.gamma += quantity * greeks.gamma;
+                    total.vega += quantity * greeks.vega;
+                    total.theta += quantity * greeks.theta;
+                    total.rho += quantity * greeks.rho;
+                    total.vanna = add(total.vanna, greeks.vanna, quantity);
+                    total.volga = add(total.volga, greeks.volga, quantity);
+                    total.charm = add(total.charm, greeks.charm, quantity);
+                }
+                StrategyLeg::Underlying { quantity } => {
+                    total.price += quantity * self.initial_price;
+                    total.delta += quantity;
+                }
+            }
+        }
+
+        total
+    }
+
+    /// Horizon of the payoff: the earliest expiry of the options
+    /// (zero if there are none).
+    #[must_use]
+    pub fn horizon(&self) -> f64 {
+        self.legs
+            .iter()
+            .filter_map(|leg| match leg {
+                StrategyLeg::Option { time_to_expiry, .. } => Some(*time_to_expiry),
+                StrategyLeg::Underlying { .. } => None,
+            })
+            .reduce(f64::min)
+            .unwrap_or(0.0)
+    }
+
+    /// Value of the strategy at the horizon for an underlying price `s`.
+    /// Options expiring at the horizon pay off; later expiries are
+    /// valued with Black-Scholes-Merton.
+    #[must_use]
+    pub fn payoff(&self, s: f64) -> f64 {
+        let horizon = self.horizon();
+
+        self.legs
+            .iter()
+            .map(|leg| match *leg {
+                StrategyLeg::Option {
+                    option_type,
+                    strike_price,
+                    time_to_expiry,
+                    quantity,
+                } => {
+                    let remaining = time_to_expiry - horizon;
+                    let value = if remaining > 0.0 {
+                        black_scholes_greeks(
+                            s,
+                            strike_price,
+                            remaining,
+                            self.risk_free_rate,
+                            self.dividend_yield,
+                            self.volatility,
+                            option_type,
+                        )
+                        .price
+                    } else {
+                        match option_type {
+                            TypeFlag::Call => (s - strike_price).max(0.0),
+                            TypeFlag::Put => (strike_price - s).max(0.0),
+                        }
+                    };
+
+                    quantity * value
+                }
+                StrategyLeg::Underlying { quantity } => quantity * s,
+            })
+            .sum()
+    }
+
+    /// Profit at the horizon: the payoff less the initial cost of the
+    /// strategy, carried forward at the risk-free rate.
+    #[must_use]
+    pub fn profit(&self, s: f64) -> f64 {
+        self.payoff(s) - self.price() * (self.risk_free_rate * self.horizon()).exp()
+    }
+
+    /// Payoff diagram: `(s, payoff, profit)` at `points` evenly spaced
+    /// underlying prices from `lower` to `upper`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 2 points.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn payoff_diagram(&self, lower: f64, upper: f64, points: usize) -> Vec<(f64, f64, f64)> {
+        assert!(points >= 2, "The diagram needs at least 2 points.");
+
+        let cost = self.price() * (self.risk_free_rate * self.horizon()).exp();
+        let step = (upper - lower) / (points - 1) as f64;
+
+        (0..points)
+            .map(|i| {
+                let s = lower + i as f64 * step;
+                let payoff = self.payoff(s);
+                (s, payoff, payoff - cost)
+            })
+            .collect()
+    }
+
+    /// Break-even points: the underlying prices at the horizon where the
+    /// profit is zero, in increasing order.
+    ///
+    /// The profit is searched for sign changes between the strikes and up
+    /// to ten times the largest of the strikes and the initial price, and
+    /// the roots are refined by Brent's method.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn break_even_points(&self) -> Vec<f64> {
+        const SUBINTERVALS: usize = 64;
+
+        let cost = self.price() * (self.risk_free_rate * self.horizon()).exp();
+        let profit = |s: f64| self.payoff(s) - cost;
+
+        // The payoff at expiry is linear between the strikes.
+        let mut knots: Vec<f64> = self
+            .legs
+            .iter()
+            .filter_map(|leg| match leg {
+                StrategyLeg::Option { strike_price, .. } => Some(*strike_price),
+                StrategyLeg::Underlying { .. } => None,
+            })
+            .collect();
+        let upper = 10.0 * knots.iter().copied().fold(self.initial_price, f64::max);
+        knots.extend([0.0, upper]);
+        knots.sort_by(f64::total_cmp);
+        knots.dedup();
+
+        let brent = Brent::new(1e-12 * upper, 100);
+        let mut points: Vec<f64> = Vec::new();
+
+        for window in knots.windows(2) {
+            let step = (window[1] - window[0]) / SUBINTERVALS as f64;
+
+            for i in 0..SUBINTERVALS {
+                let (a, b) = (
+                    window[0] + i as f64 * step,
+                    window[0] + (i + 1) as f64 * step,
+                );
+                let Ok(root) = brent.solve(&profit, a, b, a) else {
+                    continue;
+                };
+
+                if points.last().is_none_or(|last| root - last > 1e-9 * upper) {
+                    points.push(root);
+                }
+            }
+        }
+
+        points
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_strategy {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const MARKET: Strategy = Strategy::new(100.0, 0.05, 0.02, 0.25);
+
+    fn bs(K: f64, T: f64, flag: TypeFlag) -> OptionGreeks {
+        black_scholes_greeks(100.0, K, T, 0.05, 0.02, 0.25, flag)
+    }
+
+    #[test]
+    fn test_straddle() {
+        let straddle = MARKET.straddle(100.0, 0.5);
+        let (call, put) = (
+            bs(100.0, 0.5, TypeFlag::Call),
+            bs(100.0, 0.5, TypeFlag::Put),
+        );
+        let greeks = straddle.greeks();
+
+        assert_approx_equal!(greeks.price, call.price + put.price, 1e-12);
+        assert_approx_equal!(greeks.delta, call.delta + put.delta, 1e-12);
+        assert_approx_equal!(greeks.gamma, 2.0 * call.gamma, 1e-12);
+        assert_approx_equal!(greeks.vega, 2.0 * call.vega, 1e-12);
+
+        // Break even when the move pays back the premium (carried forward).
+        let cost = greeks.price * (0.05_f64 * 0.5).exp();
+        let points = straddle.break_even_points();
+
+        assert_eq!(points.len(), 2);
+        assert_approx_equal!(points[0], 100.0 - cost, 1e-8);
+        assert_approx_equal!(points[1], 100.0 + cost, 1e-8);
+    }
+
+    #[test]
+    fn test_spreads_and_collar() {
+        // A bull call spread pays between zero and the strike difference.
+        let spread = MARKET.bull_call_spread(95.0, 105.0, 1.0);
+        for (s, payoff, _) in spread.payoff_diagram(50.0, 150.0, 101) {
+            assert!((0.0..=10.0).contains(&payoff));
+            if s >= 105.0 {
+                assert_approx_equal!(payoff, 10.0, 1e-12);
+            }
+        }
+        assert_eq!(spread.break_even_points().len(), 1);
+
+        // A butterfly has two break-even points around the middle strike.
+        let butterfly = MARKET.butterfly(90.0, 100.0, 110.0, 1.0);
+        let points = butterfly.break_even_points();
+        assert_eq!(points.len(), 2);
+        assert!(points[0] > 90.0 && points[0] < 100.0 && points[1] > 100.0 && points[1] < 110.0);
+
+        // Collar: the payoff is the underlying, floored and capped.
+        let collar = MARKET.collar(90.0, 110.0, 1.0);
+        let (put, call) = (bs(90.0, 1.0, TypeFlag::Put), bs(110.0, 1.0, TypeFlag::Call));
+
+        assert_approx_equal!(collar.price(), 100.0 + put.price - call.price, 1e-12);
+        assert_approx_equal!(collar.greeks().delta, 1.0 + put.delta - call.delta, 1e-12);
+        assert_approx_equal!(collar.payoff(50.0), 90.0, 1e-12);
+        assert_approx_equal!(collar.payoff(150.0), 110.0, 1e-12);
+    }
+
+    #[test]
+    fn test_calendar_spread() {
+        let calendar = MARKET.calendar_spread(100.0, 0.25, 1.0);
+        let (near, far) = (
+            bs(100.0, 0.25, TypeFlag::Call),
+            bs(100.0, 1.0, TypeFlag::Call),
+        );
+
+        assert_approx_equal!(calendar.horizon(), 0.25, 1e-12);
+        assert_approx_equal!(calendar.price(), far.price - near.price, 1e-12);
+
+        // At the near expiry, the far call is still worth its time value.
+        let far_at_strike =
+            black_scholes_greeks(100.0, 100.0, 0.75, 0.05, 0.02, 0.25, TypeFlag::Call);
+        assert_approx_equal!(calendar.payoff(100.0), far_at_strike.price, 1e-12);
+
+        // Profitable near the strike only.
+        let points = calendar.break_even_points();
+        assert_eq!(points.len(), 2);
+        assert!(calendar.profit(100.0) > 0.0);
+        assert!(calendar.profit(0.5 * (points[0] + points[1])) > 0.0);
+        assert!(points.iter().all(|s| calendar.profit(*s).abs() < 1e-8));
+    }
+}
@@ -0,0 +1,429 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Variance and volatility swaps.
+//!
+//! The fair variance strike is replicated by the log contract, a static
+//! position in out-of-the-money options (Demeterfi, Derman, Kamal and Zou,
+//! 1999):
+//!
+//! $$
+//! K_{var} = \frac{2 e^{rT}}{T} \left( \int_0^F \frac{P(K)}{K^2} dK + \int_F^\infty \frac{C(K)}{K^2} dK \right)
+//! $$
+//!
+//! The volatility swap strike is not replicable; it is approximated by the
+//! square root of the variance strike less a convexity adjustment
+//! (Brockhaus and Long, 2000).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::VolatilitySurface;
+use crate::statistics::distributions::{Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Variance swap: pays `N_var (sigma_realised^2 - K)` at expiry.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+///
+/// let strikes = vec![60.0, 80.0, 100.0, 120.0, 140.0];
+/// let surface = VolatilitySurface::new(
+///     vec![
+///         SmileQuotes::new(0.5, 101.0, strikes.clone(), vec![0.2; 5]),
+///         SmileQuotes::new(1.0, 102.0, strikes, vec![0.2; 5]),
+///     ],
+///     SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+/// )
+/// .unwrap();
+///
+/// let swap = VarianceSwap::new(10_000.0, 0.04, 1.0, 0.02);
+///
+/// // A flat surface replicates its own variance.
+/// let fair = swap.fair_strike_from_surface(&surface);
+/// assert!((fair - 0.04).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct VarianceSwap {
+    /// `N_var` - Variance notional.
+    pub variance_notional: f64,
+    /// `K` - Strike, in variance units (`sigma^2`).
+    pub strike: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+}
+
+/// Volatility swap: pays `N_vol (sigma_realised - K)` at expiry.
+#[derive(Debug, Clone, Copy)]
+pub struct VolatilitySwap {
+    /// `N_vol` - Volatility notional.
+    pub volatility_notional: f64,
+    /// `K` - Strike, in volatility units (`sigma`).
+    pub strike: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+}
+
+/// Strip of European call and put prices on the same expiry.
+#[derive(Debug, Clone)]
+pub struct OptionStrip {
+    /// Strikes, increasing.
+    pub strikes: Vec<f64>,
+    /// Call prices, one per strike.
+    pub calls: Vec<f64>,
+    /// Put prices, one per strike.
+    pub puts: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl OptionStrip {
+    /// New option strip.
+    #[must_use]
+    pub fn new(strikes: Vec<f64>, calls: Vec<f64>, puts: Vec<f64>) -> Self {
+        Self {
+            strikes,
+            calls,
+            puts,
+        }
+    }
+
+    /// Forward implied by put-call parity at the strike where the call and
+    /// put prices are closest, `F = K + e^{rT} (C - P)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strip is empty.
+    #[must_use]
+    pub fn implied_forward(&self, discount_factor: f64) -> f64 {
+        let i = (0..self.strikes.len())
+            .min_by(|&a, &b| {
+                let gap = |i: usize| (self.calls[i] - self.puts[i]).abs();
+                gap(a).total_cmp(&gap(b))
+            })
+            .expect("The option strip is empty.");
+
+        self.strikes[i] + (self.calls[i] - self.puts[i]) / discount_factor
+    }
+}
+
+impl VarianceSwap {
+    /// New variance swap.
+    #[must_use]
+    pub const fn new(
+        variance_notional: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+    ) -> Self {
+        Self {
+            variance_notional,
+            strike,
+            time_to_expiry,
+            risk_free_rate,
+        }
+    }
+
+    /// New variance swap, with the notional given in volatility units
+    /// (`N_var = N_vega / (2 K_vol)`) and the strike as a volatility.
+    #[must_use]
+    pub fn from_vega_notional(
+        vega_notional: f64,
+        volatility_strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+    ) -> Self {
+        Self::new(
+            vega_notional / (2.0 * volatility_strike),
+            volatility_strike * volatility_strike,
+            time_to_expiry,
+            risk_free_rate,
+        )
+    }
+
+    /// Fair variance strike replicated from a discrete strip of options
+    /// (the CBOE VIX methodology):
+    ///
+    /// $$
+    /// K_{var} = \frac{2 e^{rT}}{T} \sum_i \frac{\Delta K_i}{K_i^2} Q(K_i) - \frac{1}{T} \left( \frac{F}{K_0} - 1 \right)^2
+    /// $$
+    ///
+    /// where `K_0` is the first strike at or below the forward implied by
+    /// put-call parity, `Q` is the put price below `K_0`, the call price above
+    /// it, and their average at `K_0`.
+    ///
+    /// The strip truncates the log contract, so it should extend into the
+    /// wings until the option prices are negligible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strip has fewer than 2 strikes, or if the prices do not
+    /// have one entry per strike.
+    #[must_use]
+    pub fn fair_strike_from_strip(&self, strip: &OptionStrip) -> f64 {
+        let strikes = &strip.strikes;
+        let n = strikes.len();
+        assert!(n >= 2, "The option strip needs at least 2 strikes.");
+        assert!(
+            strip.calls.len() == n && strip.puts.len() == n,
+            "The option strip needs one call and one put price per strike."
+        );
+
+        let T = self.time_to_expiry;
+        let growth = (self.risk_free_rate * T).exp();
+        let forward = strip.implied_forward(growth.recip());
+
+        let k0 = strikes.iter().rposition(|&k| k <= forward).unwrap_or(0);
+
+        let sum = (0..n)
+            .map(|i| {
+                let dk = match i {
+                    0 => strikes[1] - strikes[0],
+                    _ if i == n - 1 => strikes[n - 1] - strikes[n - 2],
+                    _ => 0.5 * (strikes[i + 1] - strikes[i - 1]),
+                };
+
+                let q = match i.cmp(&k0) {
+                    std::cmp::Ordering::Less => strip.puts[i],
+                    std::cmp::Ordering::Equal => 0.5 * (strip.puts[i] + strip.calls[i]),
+                    std::cmp::Ordering::Greater => strip.calls[i],
+                };
+
+                dk / (strikes[i] * strikes[i]) * q
+            })
+            .sum::<f64>();
+
+        (2.0 * growth * sum - (forward / strikes[k0] - 1.0).powi(2)) / T
+    }
+
+    /// Fair variance strike replicated from an implied volatility surface,
+    /// by integrating the log contract over log-moneyness `k = ln(K / F)`:
+    ///
+    /// $$
+    /// K_{var} = \frac{2}{T} \int_{-\infty}^{\infty} e^{-k} q(k) dk
+    /// $$
+    ///
+    /// with `q` the undiscounted out-of-the-money Black price per unit of
+    /// forward. The integral is truncated at 12 at-the-money standard
+    /// deviations either side of the forward.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fair_strike_from_surface(&self, surface: &VolatilitySurface) -> f64 {
+        const INTERVALS: usize = 2000;
+
+        let T = self.time_to_expiry;
+        let width = 12.0 * surface.total_variance(0.0, T).sqrt().max(1e-4);
+        let h = 2.0 * width / INTERVALS as f64;
+        let normal = Gaussian::default();
+
+        let integrand = |k: f64| {
+            let stdev = surface.total_variance(k, T).sqrt();
+            if stdev <= 0.0 {
+                return 0.0;
+            }
+
+            let d1 = -k / stdev + 0.5 * stdev;
+            let d2 = d1 - stdev;
+
+            let q = if k < 0.0 {
+                k.exp() * normal.cdf(-d2) - normal.cdf(-d1)
+            } else {
+                normal.cdf(d1) - k.exp() * normal.cdf(d2)
+            };
+
+            (-k).exp() * q
+        };
+
+        // Composite Simpson's rule.
+        let sum = (0..=INTERVALS)
+            .map(|i| {
+                let weight = match i {
+                    0 | INTERVALS => 1.0,
+                    _ if i % 2 == 1 => 4.0,
+                    _ => 2.0,
+                };
+                weight * integrand(-width + h * i as f64)
+            })
+            .sum::<f64>();
+
+        2.0 / T * sum * h / 3.0
+    }
+
+    /// Value of the swap, given the fair variance strike.
+    #[must_use]
+    pub fn value(&self, fair_strike: f64) -> f64 {
+        self.seasoned_value(0.0, 0.0, fair_strike)
+    }
+
+    /// Value of the swap after `elapsed` years, given the variance realised
+    /// so far (annualised) and the fair variance strike for the remaining
+    /// `T - elapsed` years:
+    ///
+    /// $$
+    /// N_{var} e^{-r (T - t)} \left( \frac{t}{T} \sigma_{realised}^2 + \frac{T - t}{T} K_{var} - K \right)
+    /// $$
+    #[must_use]
+    pub fn seasoned_value(&self, elapsed: f64, realised_variance: f64, fair_strike: f64) -> f64 {
+        let T = self.time_to_expiry;
+        let remaining = T - elapsed;
+        let expected = (elapsed * realised_variance + remaining * fair_strike) / T;
+
+        self.variance_notional * (-self.risk_free_rate * remaining).exp() * (expected - self.strike)
+    }
+}
+
+impl VolatilitySwap {
+    /// New volatility swap.
+    #[must_use]
+    pub const fn new(
+        volatility_notional: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+    ) -> Self {
+        Self {
+            volatility_notional,
+            strike,
+            time_to_expiry,
+            risk_free_rate,
+        }
+    }
+
+    /// Convexity adjustment `Var[V] / (8 E[V]^{3/2})`, from the second order
+    /// expansion of the square root of the realised variance `V` around its
+    /// mean.
+    #[must_use]
+    pub fn convexity_adjustment(variance_strike: f64, variance_of_variance: f64) -> f64 {
+        variance_of_variance / (8.0 * variance_strike.powf(1.5))
+    }
+
+    /// Approximate fair volatility strike, from the fair variance strike
+    /// `E[V]` and the variance of the realised variance `Var[V]`:
+    ///
+    /// $$
+    /// K_{vol} \approx \sqrt{E[V]} - \frac{Var[V]}{8 E[V]^{3/2}}
+    /// $$
+    #[must_use]
+    pub fn fair_strike(variance_strike: f64, variance_of_variance: f64) -> f64 {
+        variance_strike.sqrt() - Self::convexity_adjustment(variance_strike, variance_of_variance)
+    }
+
+    /// Value of the swap, given the fair volatility strike.
+    #[must_use]
+    pub fn value(&self, fair_strike: f64) -> f64 {
+        let df = (-self.risk_free_rate * self.time_to_expiry).exp();
+
+        self.volatility_notional * df * (fair_strike - self.strike)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_variance_swap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::{SmileInterpolation, SmileQuotes};
+    use crate::instruments::{black_scholes_greeks, TypeFlag};
+
+    fn flat_surface(vol: f64, forward: f64) -> VolatilitySurface {
+        let strikes = vec![60.0, 80.0, 100.0, 120.0, 140.0];
+
+        VolatilitySurface::new(
+            vec![
+                SmileQuotes::new(0.5, forward, strikes.clone(), vec![vol; 5]),
+                SmileQuotes::new(1.0, forward, strikes, vec![vol; 5]),
+            ],
+            SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fair_strike_from_surface() {
+        let swap = VarianceSwap::new(1.0, 0.0, 0.75, 0.03);
+
+        for vol in [0.1, 0.25, 0.6] {
+            let fair = swap.fair_strike_from_surface(&flat_surface(vol, 100.0));
+            assert_approx_equal!(fair, vol * vol, 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fair_strike_from_strip() {
+        // A fine strip of Black-Scholes prices replicates the flat variance.
+        let (spot, r, q, vol, T) = (100.0, 0.03, 0.01, 0.3, 0.5);
+        let strikes: Vec<f64> = (1..=600).map(|i| 0.5 * f64::from(i)).collect();
+        let price = |k: f64, flag| black_scholes_greeks(spot, k, T, r, q, vol, flag).price;
+
+        let strip = OptionStrip::new(
+            strikes.clone(),
+            strikes.iter().map(|&k| price(k, TypeFlag::Call)).collect(),
+            strikes.iter().map(|&k| price(k, TypeFlag::Put)).collect(),
+        );
+
+        let forward = spot * ((r - q) * T).exp();
+        assert_approx_equal!(strip.implied_forward((-r * T).exp()), forward, 1e-8);
+
+        let swap = VarianceSwap::new(1.0, 0.0, T, r);
+        assert_approx_equal!(swap.fair_strike_from_strip(&strip), vol * vol, 1e-4);
+
+        // A truncated strip misses the wings.
+        let narrow = OptionStrip::new(
+            strikes[160..240].to_vec(),
+            strip.calls[160..240].to_vec(),
+            strip.puts[160..240].to_vec(),
+        );
+        assert!(swap.fair_strike_from_strip(&narrow) < vol * vol - 1e-3);
+    }
+
+    #[test]
+    fn test_swap_values() {
+        let swap = VarianceSwap::from_vega_notional(100_000.0, 0.2, 1.0, 0.05);
+        assert_approx_equal!(swap.variance_notional, 250_000.0, 1e-9);
+        assert_approx_equal!(swap.strike, 0.04, 1e-15);
+        assert_approx_equal!(swap.value(0.04), 0.0, 1e-9);
+
+        // Half way through, realised at 25% and the remaining fair strike at 20%.
+        let value = swap.seasoned_value(0.5, 0.0625, 0.04);
+        let expected = 250_000.0 * (-0.025_f64).exp() * (0.5 * 0.0625 + 0.5 * 0.04 - 0.04);
+        assert_approx_equal!(value, expected, 1e-8);
+
+        // No uncertainty in the realised variance: no convexity adjustment.
+        assert_approx_equal!(VolatilitySwap::fair_strike(0.04, 0.0), 0.2, 1e-15);
+
+        // Lognormal realised variance with mean m and variance s^2, for which
+        // E[sqrt(V)] = sqrt(m) exp(-ln(1 + s^2 / m^2) / 8).
+        let (m, s2): (f64, f64) = (0.04, 1e-5);
+        let sigma2 = (1.0 + s2 / (m * m)).ln();
+        let exact = m.sqrt() * (-sigma2 / 8.0).exp();
+        assert_approx_equal!(VolatilitySwap::fair_strike(m, s2), exact, 1e-6);
+
+        let vol_swap = VolatilitySwap::new(100_000.0, 0.19, 1.0, 0.05);
+        let fair = VolatilitySwap::fair_strike(m, s2);
+        assert_approx_equal!(
+            vol_swap.value(fair),
+            100_000.0 * (-0.05_f64).exp() * (fair - 0.19),
+            1e-9
+        );
+    }
+}
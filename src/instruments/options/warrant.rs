@@ -0,0 +1,338 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Warrants (with dilution) and employee stock options
+//! (Hull and White, 2004).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{generalised_greeks, TypeFlag};
+use crate::math::{Newton, RootFinder, RootFindingError};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// European warrant: a call written by the company, which issues new shares
+/// when the warrants are exercised.
+///
+/// With `N` shares and `M` warrants outstanding, the warrant is worth
+/// `N / (N + M)` calls on the equity per share `S + (M / N) W`, where `S`
+/// is the observed share price (Galai and Schneller, 1978), so the price
+/// solves
+///
+/// $$
+/// W = \frac{N}{N + M} c\left(S + \frac{M}{N} W, K, T\right).
+/// $$
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// // 1 warrant for every 10 shares.
+/// let warrant = Warrant::new(50.0, 55.0, 2.0, 0.05, 0.0, 0.3, 1_000_000.0, 100_000.0);
+/// let call = black_scholes_greeks(50.0, 55.0, 2.0, 0.05, 0.0, 0.3, TypeFlag::Call);
+///
+/// // Dilution makes the warrant cheaper than the call.
+/// assert!(warrant.price().unwrap() < call.price);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Warrant {
+    /// `S` - Share price.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility of the equity per share.
+    pub volatility: f64,
+    /// `N` - Number of shares outstanding.
+    pub shares_outstanding: f64,
+    /// `M` - Number of warrants issued (one new share each).
+    pub warrants_issued: f64,
+}
+
+/// Employee stock option (Hull and White, 2004), priced on a
+/// Cox-Ross-Rubinstein tree.
+///
+/// - The option cannot be exercised during the vesting period.
+/// - Employees leave at the exit rate `e` (per year): unvested options are
+///   forfeited and vested options are exercised if in the money.
+/// - Vested options are exercised as soon as the share price reaches the
+///   exercise multiple `M` times the strike.
+///
+/// ```
+/// use RustQuant::instruments::*;
+///
+/// let option = EmployeeStockOption::new(50.0, 50.0, 10.0, 0.05, 0.025, 0.3)
+///     .with_vesting_period(3.0)
+///     .with_exit_rate(0.03)
+///     .with_exercise_multiple(2.0);
+///
+/// let call = black_scholes_greeks(50.0, 50.0, 10.0, 0.05, 0.025, 0.3, TypeFlag::Call);
+///
+/// // Forfeiture and early exercise make the option cheaper than a call.
+/// assert!(option.price(500) < call.price);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EmployeeStockOption {
+    /// `S` - Share price.
+    pub initial_price: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `T` - Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `v` - Volatility.
+    pub volatility: f64,
+    /// Vesting period (in years). Default: 0.
+    pub vesting_period: f64,
+    /// `e` - Employee exit rate (per year). Default: 0.
+    pub exit_rate: f64,
+    /// `M` - Exercise multiple: vested options are exercised when the
+    /// share price reaches `M K`. Default: infinite (no early exercise
+    /// other than on exit).
+    pub exercise_multiple: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Warrant {
+    /// New warrant.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        shares_outstanding: f64,
+        warrants_issued: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            shares_outstanding,
+            warrants_issued,
+        }
+    }
+
+    /// Dilution factor `N / (N + M)`.
+    #[must_use]
+    pub fn dilution_factor(&self) -> f64 {
+        self.shares_outstanding / (self.shares_outstanding + self.warrants_issued)
+    }
+
+    /// Price of the warrant, solving the dilution equation by Newton's
+    /// method from the undiluted call price.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`RootFindingError`] if Newton's method does not converge.
+    pub fn price(&self) -> Result<f64, RootFindingError> {
+        let (S, K, T) = (self.initial_price, self.strike_price, self.time_to_expiry);
+        let (r, v) = (self.risk_free_rate, self.volatility);
+        let b = r - self.dividend_yield;
+        let ratio = self.warrants_issued / self.shares_outstanding;
+        let dilution = self.dilution_factor();
+
+        let call = |s: f64| generalised_greeks(s, K, T, r, b, v, TypeFlag::Call).price;
+
+        // The root is at most `S`, as `c(S + (M / N) S) <= S (N + M) / N`.
+        let f = |w: f64| w - dilution * call(S + ratio * w);
+
+        Newton::new(1e-12 * S, 100).solve(&f, 0.0, S, call(S))
+    }
+}
+
+impl EmployeeStockOption {
+    /// New employee stock option, vested and without exits or early
+    /// exercise.
+    #[must_use]
+    pub const fn new(
+        initial_price: f64,
+        strike_price: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            vesting_period: 0.0,
+            exit_rate: 0.0,
+            exercise_multiple: f64::INFINITY,
+        }
+    }
+
+    /// Set the vesting period (in years).
+    #[must_use]
+    pub const fn with_vesting_period(mut self, vesting_period: f64) -> Self {
+        self.vesting_period = vesting_period;
+        self
+    }
+
+    /// Set the employee exit rate (per year).
+    #[must_use]
+    pub const fn with_exit_rate(mut self, exit_rate: f64) -> Self {
+        self.exit_rate = exit_rate;
+        self
+    }
+
+    /// Set the exercise multiple.
+    #[must_use]
+    pub const fn with_exercise_multiple(mut self, exercise_multiple: f64) -> Self {
+        self.exercise_multiple = exercise_multiple;
+        self
+    }
+
+    /// Price on a Cox-Ross-Rubinstein tree with `steps` time steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of steps is zero.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap
+    )]
+    pub fn price(&self, steps: usize) -> f64 {
+        assert!(steps > 0, "The tree needs at least one step.");
+
+        let (S, K, v) = (self.initial_price, self.strike_price, self.volatility);
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+        let dt = self.time_to_expiry / steps as f64;
+
+        let u = (v * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = ((b * dt).exp() - d) / (u - d);
+        let df = (-r * dt).exp();
+
+        // Probability that the employee leaves during a step.
+        let exit = 1.0 - (-self.exit_rate * dt).exp();
+        let node = |i: usize, j: usize| S * u.powi(2 * i as i32 - j as i32);
+
+        let mut values: Vec<f64> = (0..=steps).map(|i| (node(i, steps) - K).max(0.0)).collect();
+
+        for j in (0..steps).rev() {
+            let vested = j as f64 * dt >= self.vesting_period;
+
+            for i in 0..=j {
+                let continuation = df * (p * values[i + 1] + (1.0 - p) * values[i]);
+                let intrinsic = (node(i, j) - K).max(0.0);
+
+                values[i] = if !vested {
+                    (1.0 - exit) * continuation
+                } else if node(i, j) >= self.exercise_multiple * K {
+                    intrinsic
+                } else {
+                    (1.0 - exit) * continuation + exit * intrinsic
+                };
+            }
+        }
+
+        values[0]
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_warrant {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::black_scholes_greeks;
+
+    #[test]
+    fn test_warrant() {
+        let warrant = Warrant::new(50.0, 55.0, 2.0, 0.05, 0.01, 0.3, 1_000_000.0, 250_000.0);
+        let W = warrant.price().unwrap();
+
+        // The price solves the dilution equation.
+        let call =
+            black_scholes_greeks(50.0 + 0.25 * W, 55.0, 2.0, 0.05, 0.01, 0.3, TypeFlag::Call);
+        assert_approx_equal!(W, 0.8 * call.price, 1e-10);
+
+        // Without dilution it is a call.
+        let undiluted = Warrant {
+            warrants_issued: 0.0,
+            ..warrant
+        };
+        let call = black_scholes_greeks(50.0, 55.0, 2.0, 0.05, 0.01, 0.3, TypeFlag::Call);
+        assert_approx_equal!(undiluted.price().unwrap(), call.price, 1e-12);
+
+        // More warrants, more dilution.
+        let diluted = Warrant {
+            warrants_issued: 1_000_000.0,
+            ..warrant
+        };
+        assert!(diluted.price().unwrap() < W && W < call.price);
+    }
+
+    #[test]
+    fn test_employee_stock_option() {
+        // Vested, no exits and no early exercise: a European call (an
+        // American call without dividends).
+        let option = EmployeeStockOption::new(50.0, 50.0, 5.0, 0.05, 0.0, 0.3);
+        let call = black_scholes_greeks(50.0, 50.0, 5.0, 0.05, 0.0, 0.3, TypeFlag::Call);
+        assert_approx_equal!(option.price(1000), call.price, 0.02);
+
+        // Each feature lowers the value.
+        let option = EmployeeStockOption {
+            dividend_yield: 0.025,
+            time_to_expiry: 10.0,
+            ..option
+        };
+        let base = option.price(500);
+        let multiple = option.with_exercise_multiple(2.0).price(500);
+        let exit = option.with_exit_rate(0.05).price(500);
+        let vesting = option
+            .with_exit_rate(0.05)
+            .with_vesting_period(3.0)
+            .price(500);
+
+        assert!(multiple < base);
+        assert!(exit < base);
+        assert!(vesting < exit);
+
+        // With an exercise multiple of 1, vested options in the money are
+        // exercised immediately.
+        let immediate = EmployeeStockOption {
+            initial_price: 60.0,
+            ..option
+        }
+        .with_exercise_multiple(1.0);
+        assert_approx_equal!(immediate.price(500), 10.0, 1e-12);
+    }
+}
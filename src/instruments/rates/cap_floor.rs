@@ -0,0 +1,461 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Caps and floors: strips of caplets (calls on a floating rate) and
+//! floorlets (puts), priced with Black-76, shifted Black-76 or Bachelier
+//! on the forward rates projected from a [`CurveContext`].
+//!
+//! A caplet on the period $[t_s, t_e]$ pays $N \tau (L - K)^+$ at $t_e$,
+//! where the rate $L$ fixes at $t_s$, so its value is
+//! $N \tau P(t_e) \, C(F, K, t_s)$ with $C$ the (undiscounted) price of a
+//! call on the forward rate $F$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::CurveContext;
+use crate::instruments::options::{
+    implied_volatility_bachelier, implied_volatility_black, ImpliedVolatilityError, TypeFlag,
+};
+use crate::instruments::rates::SwapLeg;
+use crate::statistics::distributions::{Distribution, Gaussian};
+use crate::time::{DayCountConvention, DayCounter, Schedule};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Volatility of a forward rate, and the model it is quoted in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateVolatility {
+    /// Lognormal (Black-76) volatility. The forward and the strike must be
+    /// positive.
+    Lognormal(f64),
+
+    /// Shifted lognormal volatility: Black-76 on the rates plus the shift,
+    /// for rates above minus the shift.
+    ShiftedLognormal {
+        /// Lognormal volatility of the shifted rate.
+        volatility: f64,
+        /// Shift of the rates.
+        shift: f64,
+    },
+
+    /// Normal (Bachelier) volatility, in absolute rate units. Allows
+    /// negative forwards and strikes.
+    Normal(f64),
+}
+
+/// Cap or floor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapFloorType {
+    /// Cap: a strip of caplets (calls on the rate).
+    Cap,
+    /// Floor: a strip of floorlets (puts on the rate).
+    Floor,
+}
+
+/// Caplet or floorlet on one accrual period of a floating rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caplet {
+    /// Caplet or floorlet.
+    pub cap_floor_type: CapFloorType,
+    /// Notional.
+    pub notional: f64,
+    /// Strike rate.
+    pub strike: f64,
+    /// Start of the accrual period: the fixing date.
+    pub accrual_start: OffsetDateTime,
+    /// End of the accrual period: the payment date.
+    pub accrual_end: OffsetDateTime,
+    /// Day count convention of the accrual period.
+    pub day_count_convention: DayCountConvention,
+    /// Floating rate index, the key of its projection curve in the
+    /// [`CurveContext`].
+    pub index: String,
+}
+
+/// Cap or floor: caplets or floorlets on the accrual periods of a
+/// schedule.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::Schedule;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+///
+/// // Negative rates: Black-76 does not apply, Bachelier and shifted Black do.
+/// let curve = YieldCurve::new(BTreeMap::from([
+///     (today, -0.005),
+///     (today + Duration::days(3650), 0.002),
+/// ]));
+/// let context = CurveContext::new(curve);
+///
+/// let schedule = Schedule::new_from_start(today, Duration::days(91), 20);
+/// let floor = CapFloor::new(CapFloorType::Floor, 1_000_000.0, 0.0, &schedule, "IBOR3M");
+///
+/// let normal = floor.price(&context, &RateVolatility::Normal(0.006));
+/// let shifted = floor.price(
+///     &context,
+///     &RateVolatility::ShiftedLognormal { volatility: 0.2, shift: 0.03 },
+/// );
+///
+/// assert!(normal > 0.0 && shifted > 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapFloor {
+    /// Cap or floor.
+    pub cap_floor_type: CapFloorType,
+    /// Notional.
+    pub notional: f64,
+    /// Strike rate of all the caplets.
+    pub strike: f64,
+    /// Accrual periods of the caplets.
+    pub leg: SwapLeg,
+    /// Floating rate index, the key of its projection curve in the
+    /// [`CurveContext`].
+    pub index: String,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RateVolatility {
+    /// Undiscounted price of a call or put on a forward rate `F` with
+    /// strike `K`, expiring in `T` years. Intrinsic value if `T <= 0`.
+    #[must_use]
+    #[allow(non_snake_case)]
+    pub fn forward_price(&self, F: f64, K: f64, T: f64, option_type: TypeFlag) -> f64 {
+        let omega = match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+
+        if T <= 0.0 {
+            return (omega * (F - K)).max(0.0);
+        }
+
+        let N = |x: f64| Gaussian::default().cdf(x);
+
+        match *self {
+            Self::Lognormal(volatility) => black76(F, K, T, volatility, omega),
+            Self::ShiftedLognormal { volatility, shift } => {
+                black76(F + shift, K + shift, T, volatility, omega)
+            }
+            Self::Normal(volatility) => {
+                let stdev = volatility * T.sqrt();
+                let d = (F - K) / stdev;
+
+                omega * (F - K) * N(omega * d) + stdev * Gaussian::default().pdf(d)
+            }
+        }
+    }
+
+    /// Implied volatility of the same kind as `self` (and with the same
+    /// shift) from the price of an option on a forward rate, discounted
+    /// with `discount_factor`.
+    ///
+    /// # Errors
+    ///
+    /// See [`implied_volatility_black`] and [`implied_volatility_bachelier`].
+    #[allow(non_snake_case)]
+    pub fn implied_from_price(
+        &self,
+        price: f64,
+        F: f64,
+        K: f64,
+        T: f64,
+        discount_factor: f64,
+        option_type: TypeFlag,
+    ) -> Result<Self, ImpliedVolatilityError> {
+        match *self {
+            Self::Lognormal(_) => {
+                implied_volatility_black(price, F, K, T, discount_factor, option_type)
+                    .map(Self::Lognormal)
+            }
+            Self::ShiftedLognormal { shift, .. } => implied_volatility_black(
+                price,
+                F + shift,
+                K + shift,
+                T,
+                discount_factor,
+                option_type,
+            )
+            .map(|volatility| Self::ShiftedLognormal { volatility, shift }),
+            Self::Normal(_) => {
+                implied_volatility_bachelier(price, F, K, T, discount_factor, option_type)
+                    .map(Self::Normal)
+            }
+        }
+    }
+}
+
+// Undiscounted Black-76 price, with omega = 1 for calls and -1 for puts.
+#[allow(non_snake_case)]
+fn black76(F: f64, K: f64, T: f64, volatility: f64, omega: f64) -> f64 {
+    let N = |x: f64| Gaussian::default().cdf(x);
+
+    let stdev = volatility * T.sqrt();
+    let d1 = (F / K).ln() / stdev + 0.5 * stdev;
+    let d2 = d1 - stdev;
+
+    omega * (F * N(omega * d1) - K * N(omega * d2))
+}
+
+impl CapFloorType {
+    // Caplets are calls on the rate, floorlets puts.
+    const fn type_flag(self) -> TypeFlag {
+        match self {
+            Self::Cap => TypeFlag::Call,
+            Self::Floor => TypeFlag::Put,
+        }
+    }
+}
+
+impl Caplet {
+    /// Year fraction of the accrual period.
+    #[must_use]
+    pub fn year_fraction(&self) -> f64 {
+        DayCounter::day_count_factor(
+            self.accrual_start,
+            self.accrual_end,
+            &self.day_count_convention,
+        )
+    }
+
+    /// Forward rate of the period, from the projection curve of the index.
+    #[must_use]
+    pub fn forward_rate(&self, context: &CurveContext) -> f64 {
+        context.forward_rate(
+            &self.index,
+            self.accrual_start,
+            self.accrual_end,
+            &self.day_count_convention,
+        )
+    }
+
+    /// Time to the fixing date (Actual/365) from the valuation date.
+    #[must_use]
+    pub fn expiry(&self, context: &CurveContext) -> f64 {
+        DayCounter::day_count_factor(
+            context.valuation_date(),
+            self.accrual_start,
+            &DayCountConvention::Actual365,
+        )
+    }
+
+    /// Price of the caplet or floorlet.
+    #[must_use]
+    pub fn price(&self, context: &CurveContext, volatility: &RateVolatility) -> f64 {
+        let option = volatility.forward_price(
+            self.forward_rate(context),
+            self.strike,
+            self.expiry(context),
+            self.cap_floor_type.type_flag(),
+        );
+
+        self.notional * self.year_fraction() * context.discount_factor(self.accrual_end) * option
+    }
+}
+
+impl CapFloor {
+    /// New cap or floor on the accrual periods of a schedule, with its day
+    /// count convention.
+    #[must_use]
+    pub fn new(
+        cap_floor_type: CapFloorType,
+        notional: f64,
+        strike: f64,
+        schedule: &Schedule,
+        index: &str,
+    ) -> Self {
+        Self {
+            cap_floor_type,
+            notional,
+            strike,
+            leg: SwapLeg::from(schedule),
+            index: index.to_string(),
+        }
+    }
+
+    /// Caplets (or floorlets) that fix after the valuation date. The first
+    /// period of a spot-starting cap fixes on the valuation date and is not
+    /// an option, so it is excluded.
+    #[must_use]
+    pub fn caplets(&self, context: &CurveContext) -> Vec<Caplet> {
+        let valuation_date = context.valuation_date();
+
+        self.leg
+            .periods_after(valuation_date)
+            .into_iter()
+            .filter(|(start, _, _)| *start > valuation_date)
+            .map(|(start, end, _)| Caplet {
+                cap_floor_type: self.cap_floor_type,
+                notional: self.notional,
+                strike: self.strike,
+                accrual_start: start,
+                accrual_end: end,
+                day_count_convention: self.leg.day_count_convention,
+                index: self.index.clone(),
+            })
+            .collect()
+    }
+
+    /// Price of the cap or floor: the sum of its caplets, all priced with
+    /// the same (flat) volatility.
+    #[must_use]
+    pub fn price(&self, context: &CurveContext, volatility: &RateVolatility) -> f64 {
+        self.caplets(context)
+            .iter()
+            .map(|caplet| caplet.price(context, volatility))
+            .sum()
+    }
+
+    /// Price of the cap or floor from a volatility per caplet (e.g.
+    /// stripped caplet volatilities), in the order of
+    /// [`CapFloor::caplets`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer volatilities than caplets.
+    #[must_use]
+    pub fn price_with_caplet_volatilities(
+        &self,
+        context: &CurveContext,
+        volatilities: &[RateVolatility],
+    ) -> f64 {
+        let caplets = self.caplets(context);
+        assert!(
+            volatilities.len() >= caplets.len(),
+            "One volatility is needed per caplet."
+        );
+
+        caplets
+            .iter()
+            .zip(volatilities)
+            .map(|(caplet, volatility)| caplet.price(context, volatility))
+            .sum()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cap_floor {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{context, days};
+    use crate::instruments::options::generalised_greeks;
+    use time::Duration;
+
+    fn cap_floor(cap_floor_type: CapFloorType, strike: f64) -> CapFloor {
+        let schedule = Schedule::new_from_start(days(0), Duration::days(91), 20);
+        CapFloor::new(cap_floor_type, 100.0, strike, &schedule, "IBOR3M")
+    }
+
+    #[test]
+    fn test_black76_caplet() {
+        let context = context(0.03, 0.04);
+        let cap = cap_floor(CapFloorType::Cap, 0.035);
+        let caplets = cap.caplets(&context);
+
+        assert_eq!(caplets.len(), 19);
+
+        let caplet = &caplets[7];
+        let (F, T, tau) = (
+            caplet.forward_rate(&context),
+            caplet.expiry(&context),
+            caplet.year_fraction(),
+        );
+        let black = generalised_greeks(F, 0.035, T, 0.0, 0.0, 0.2, TypeFlag::Call).price;
+
+        assert_approx_equal!(
+            caplet.price(&context, &RateVolatility::Lognormal(0.2)),
+            100.0 * tau * context.discount_factor(caplet.accrual_end) * black,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_cap_floor_parity() {
+        // Cap - floor = the value of the floating leg less the fixed leg.
+        for (context, strike) in [
+            (context(0.03, 0.04), 0.035),
+            (context(-0.01, 0.005), -0.002),
+        ] {
+            let cap = cap_floor(CapFloorType::Cap, strike);
+            let floor = cap_floor(CapFloorType::Floor, strike);
+
+            let swap: f64 = cap
+                .caplets(&context)
+                .iter()
+                .map(|caplet| {
+                    100.0
+                        * caplet.year_fraction()
+                        * context.discount_factor(caplet.accrual_end)
+                        * (caplet.forward_rate(&context) - strike)
+                })
+                .sum();
+
+            let mut volatilities = vec![
+                RateVolatility::Normal(0.008),
+                RateVolatility::ShiftedLognormal {
+                    volatility: 0.25,
+                    shift: 0.02,
+                },
+            ];
+            if strike > 0.0 {
+                volatilities.push(RateVolatility::Lognormal(0.3));
+            }
+
+            for volatility in volatilities {
+                assert_approx_equal!(
+                    cap.price(&context, &volatility) - floor.price(&context, &volatility),
+                    swap,
+                    1e-12
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_normal_and_shifted_lognormal() {
+        // Near the money, a shifted lognormal volatility sigma is close to
+        // a normal volatility of sigma (F + shift).
+        let (F, K, T, shift) = (-0.002, -0.002, 2.0, 0.03);
+        let sigma = 0.2;
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let shifted = RateVolatility::ShiftedLognormal {
+                volatility: sigma,
+                shift,
+            }
+            .forward_price(F, K, T, option_type);
+            let normal =
+                RateVolatility::Normal(sigma * (F + shift)).forward_price(F, K, T, option_type);
+
+            assert_approx_equal!(shifted, normal, 1e-2 * normal);
+        }
+
+        // Intrinsic value at expiry.
+        assert_approx_equal!(
+            RateVolatility::Normal(0.01).forward_price(0.02, 0.015, 0.0, TypeFlag::Call),
+            0.005,
+            1e-15
+        );
+    }
+}
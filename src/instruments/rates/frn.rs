@@ -0,0 +1,488 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Floating rate notes on overnight risk-free rates (SOFR, SONIA, €STR).
+//!
+//! The coupon rate of a period is the overnight rate compounded in arrears
+//! over the business days $d_i$ of the period:
+//!
+//! $$
+//! R = \left( \prod_i \left( 1 + r_i \tau_i \right) - 1 \right) \frac{1}{\tau}
+//! $$
+//!
+//! where $\tau_i$ is the year fraction from $d_i$ to the next business day
+//! and $\tau$ the year fraction of the period. As the rates are only known
+//! at the end of the period, the market conventions observe them earlier:
+//!
+//! - lookback: the rate of each day is observed some business days earlier,
+//!   with the weights $\tau_i$ of the accrual period;
+//! - observation shift: the rates and the weights are both those of an
+//!   observation period shifted some business days earlier;
+//! - lockout: the rate of the last days of the period is frozen at the rate
+//!   of the rate cut-off date, some business days before the end.
+//!
+//! Overnight rates before the valuation date come from the published
+//! fixings, and after it from the projection curve of the index. The
+//! period dates are assumed to be business days.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, CurveContext, YieldCurve};
+use crate::math::solve_decreasing;
+use crate::math::RootFindingError;
+use crate::time::{Calendar, DayCountConvention, DayCounter, Schedule};
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Compounding convention of an overnight rate over a coupon period, with
+/// shifts in business days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RfrCompounding {
+    /// Compounded in arrears over the accrual period.
+    InArrears,
+    /// Rates observed a number of business days earlier, weighted by the
+    /// days of the accrual period.
+    Lookback(usize),
+    /// Rates and weights of an observation period shifted a number of
+    /// business days earlier.
+    ObservationShift(usize),
+    /// Rates frozen over a number of business days before the end of the
+    /// period.
+    Lockout(usize),
+}
+
+/// Floating rate note paying a compounded overnight rate plus a spread.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::{DayCountConvention, Schedule, UnitedStates};
+/// use std::collections::BTreeMap;
+/// use time::{macros::datetime, Duration};
+///
+/// let today = datetime!(2024-03-04 0:00 UTC);
+/// let curve = YieldCurve::new(BTreeMap::from([
+///     (today, 0.053),
+///     (datetime!(2030-01-01 0:00 UTC), 0.042),
+/// ]));
+/// let context = CurveContext::new(curve);
+///
+/// // Quarterly SOFR FRN, with a 2 business days lockout.
+/// let mut schedule = Schedule::new_from_start(today, Duration::days(91), 8);
+/// schedule.day_count_convention = DayCountConvention::Actual360;
+/// let frn = FloatingRateNote::new(
+///     100.0, 0.0, &schedule, "SOFR", RfrCompounding::Lockout(2),
+/// );
+///
+/// // Without spread and discounted on the projection curve, close to par.
+/// let price = frn.npv(&context, &UnitedStates);
+/// assert!((price - 100.0).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatingRateNote {
+    /// Face value, repaid at maturity.
+    pub face_value: f64,
+    /// Spread over the compounded rate.
+    pub spread: f64,
+    /// Accrual dates: the start of the first coupon period, then the
+    /// coupon dates, the last being the maturity.
+    pub dates: Vec<OffsetDateTime>,
+    /// Day count convention of the coupons and of the overnight rate.
+    pub day_count_convention: DayCountConvention,
+    /// Overnight index, the key of its projection curve in the
+    /// [`CurveContext`].
+    pub index: String,
+    /// Compounding convention of the overnight rate.
+    pub compounding: RfrCompounding,
+    /// Published fixings of the overnight rate, by date.
+    pub fixings: BTreeMap<OffsetDateTime, f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RfrCompounding {
+    /// Compounded overnight rate over the period from `start` to `end`,
+    /// from the fixings before the initial date of the projection curve and
+    /// the curve after.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate observed before the initial date of the curve has
+    /// no fixing.
+    #[must_use]
+    pub fn compounded_rate<C: Calendar>(
+        &self,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        projection_curve: &YieldCurve,
+        fixings: &BTreeMap<OffsetDateTime, f64>,
+        calendar: &C,
+        day_count_convention: &DayCountConvention,
+    ) -> f64 {
+        let (start, end) = match *self {
+            Self::ObservationShift(days) => (
+                shift_business_days(start, days, calendar),
+                shift_business_days(end, days, calendar),
+            ),
+            _ => (start, end),
+        };
+
+        // Business days of the period, and the end.
+        let mut dates = vec![start];
+        let mut date = next_business_day(start, calendar);
+        while date < end {
+            dates.push(date);
+            date = next_business_day(date, calendar);
+        }
+        dates.push(end);
+
+        let n = dates.len() - 1;
+        let observed = |i: usize| match *self {
+            Self::Lookback(days) => shift_business_days(dates[i], days, calendar),
+            Self::Lockout(days) => dates[i.min(n.saturating_sub(days))],
+            Self::InArrears | Self::ObservationShift(_) => dates[i],
+        };
+        let year_fraction = |from, to| DayCounter::day_count_factor(from, to, day_count_convention);
+
+        let growth: f64 = (0..n)
+            .map(|i| {
+                let rate = overnight_rate(
+                    observed(i),
+                    projection_curve,
+                    fixings,
+                    calendar,
+                    *day_count_convention,
+                );
+                1.0 + rate * year_fraction(dates[i], dates[i + 1])
+            })
+            .product();
+
+        (growth - 1.0) / year_fraction(start, end)
+    }
+}
+
+impl FloatingRateNote {
+    /// New note without fixings, on the dates and day count convention of
+    /// a schedule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule has less than two dates.
+    #[must_use]
+    pub fn new(
+        face_value: f64,
+        spread: f64,
+        schedule: &Schedule,
+        index: &str,
+        compounding: RfrCompounding,
+    ) -> Self {
+        assert!(
+            schedule.dates.len() > 1,
+            "The schedule must have at least two dates."
+        );
+
+        Self {
+            face_value,
+            spread,
+            dates: schedule.dates.clone(),
+            day_count_convention: schedule.day_count_convention,
+            index: index.to_string(),
+            compounding,
+            fixings: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the published fixing of the overnight rate on `date`.
+    #[must_use]
+    pub fn with_fixing(mut self, date: OffsetDateTime, rate: f64) -> Self {
+        self.fixings.insert(date, rate);
+        self
+    }
+
+    /// Coupon rate of the period from `start` to `end`: the compounded
+    /// overnight rate plus the spread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate observed before the valuation date has no fixing.
+    #[must_use]
+    pub fn coupon_rate<C: Calendar>(
+        &self,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        context: &CurveContext,
+        calendar: &C,
+    ) -> f64 {
+        let rate = self.compounding.compounded_rate(
+            start,
+            end,
+            context.projection_curve(&self.index),
+            &self.fixings,
+            calendar,
+            &self.day_count_convention,
+        );
+
+        rate + self.spread
+    }
+
+    /// Cash flows paid after the valuation date: the coupons, and the face
+    /// value at maturity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate observed before the valuation date has no fixing.
+    #[must_use]
+    pub fn cashflows<C: Calendar>(
+        &self,
+        context: &CurveContext,
+        calendar: &C,
+    ) -> Vec<(OffsetDateTime, f64)> {
+        let valuation_date = context.valuation_date();
+        let maturity = self.dates[self.dates.len() - 1];
+
+        self.dates
+            .windows(2)
+            .filter(|w| w[1] > valuation_date)
+            .map(|w| {
+                let tau = DayCounter::day_count_factor(w[0], w[1], &self.day_count_convention);
+                let coupon =
+                    self.face_value * self.coupon_rate(w[0], w[1], context, calendar) * tau;
+                let redemption = if w[1] == maturity {
+                    self.face_value
+                } else {
+                    0.0
+                };
+                (w[1], coupon + redemption)
+            })
+            .collect()
+    }
+
+    /// Dirty price: the cash flows discounted on the discount curve.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate observed before the valuation date has no fixing.
+    #[must_use]
+    pub fn npv<C: Calendar>(&self, context: &CurveContext, calendar: &C) -> f64 {
+        context.present_value(&self.cashflows(context, calendar))
+    }
+
+    /// Discount margin: the parallel shift of the zero rates of the
+    /// discount curve (the projection of the coupons unchanged) at which
+    /// the note is worth its dirty price.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RootFindingError` if no margin reprices the note.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate observed before the valuation date has no fixing.
+    pub fn discount_margin<C: Calendar>(
+        &self,
+        context: &CurveContext,
+        calendar: &C,
+        dirty_price: f64,
+    ) -> Result<f64, RootFindingError> {
+        let cashflows = self.cashflows(context, calendar);
+
+        solve_decreasing(
+            |margin| {
+                let discount_curve = context.discount_curve.shifted(margin);
+                let value: f64 = cashflows
+                    .iter()
+                    .map(|(date, amount)| amount * discount_curve.discount_factor(*date))
+                    .sum();
+                value - dirty_price
+            },
+            0.0,
+        )
+    }
+}
+
+// Overnight rate on a date: its fixing before the initial date of the
+// curve, and the projected rate to the next business day after.
+fn overnight_rate<C: Calendar>(
+    date: OffsetDateTime,
+    curve: &YieldCurve,
+    fixings: &BTreeMap<OffsetDateTime, f64>,
+    calendar: &C,
+    day_count_convention: DayCountConvention,
+) -> f64 {
+    if date < curve.initial_date() {
+        return *fixings
+            .get(&date)
+            .expect("No fixing for an overnight rate before the valuation date.");
+    }
+
+    let next = next_business_day(date, calendar);
+    let tau = DayCounter::day_count_factor(date, next, &day_count_convention);
+
+    (curve.discount_factor(date) / curve.discount_factor(next) - 1.0) / tau
+}
+
+fn next_business_day<C: Calendar>(date: OffsetDateTime, calendar: &C) -> OffsetDateTime {
+    let mut next = date + Duration::days(1);
+    while !calendar.is_business_day(next) {
+        next += Duration::days(1);
+    }
+    next
+}
+
+// The date `days` business days before `date`.
+fn shift_business_days<C: Calendar>(
+    date: OffsetDateTime,
+    days: usize,
+    calendar: &C,
+) -> OffsetDateTime {
+    let mut shifted = date;
+    for _ in 0..days {
+        shifted -= Duration::days(1);
+        while !calendar.is_business_day(shifted) {
+            shifted -= Duration::days(1);
+        }
+    }
+    shifted
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_frn {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::time::UnitedStates;
+    use time::macros::datetime;
+
+    const ACT360: DayCountConvention = DayCountConvention::Actual360;
+
+    fn curve() -> YieldCurve {
+        YieldCurve::new(BTreeMap::from([
+            (datetime!(2024-03-05 0:00 UTC), 0.053),
+            (datetime!(2026-03-03 0:00 UTC), 0.045),
+            (datetime!(2030-01-01 0:00 UTC), 0.041),
+        ]))
+    }
+
+    #[test]
+    fn test_projected_compounding() {
+        let curve = curve();
+        let (start, end) = (
+            datetime!(2024-06-03 0:00 UTC),
+            datetime!(2024-09-03 0:00 UTC),
+        );
+        let forward = |start, end| {
+            (curve.discount_factor(start) / curve.discount_factor(end) - 1.0)
+                / DayCounter::day_count_factor(start, end, &ACT360)
+        };
+        let rate = |compounding: RfrCompounding| {
+            compounding.compounded_rate(
+                start,
+                end,
+                &curve,
+                &BTreeMap::new(),
+                &UnitedStates,
+                &ACT360,
+            )
+        };
+
+        // Projected from a curve, the compounded rate is the simple forward
+        // rate of the (observation) period.
+        assert_approx_equal!(rate(RfrCompounding::InArrears), forward(start, end), 1e-14);
+        assert_approx_equal!(
+            rate(RfrCompounding::ObservationShift(2)),
+            forward(
+                datetime!(2024-05-30 0:00 UTC),
+                datetime!(2024-08-29 0:00 UTC)
+            ),
+            1e-14
+        );
+
+        // A lookback observes the rates of the shifted period, weighted by
+        // the days of the accrual period (within a basis point here); a
+        // lockout only freezes the last days.
+        assert_approx_equal!(
+            rate(RfrCompounding::Lookback(5)),
+            rate(RfrCompounding::ObservationShift(5)),
+            1e-4
+        );
+        assert_approx_equal!(
+            rate(RfrCompounding::Lockout(2)),
+            rate(RfrCompounding::InArrears),
+            1e-5
+        );
+    }
+
+    #[test]
+    fn test_fixings() {
+        let curve = curve();
+        let r = 0.05;
+
+        // One week of fixings, Monday to Friday, the Friday rate applying
+        // over the weekend.
+        let (start, end) = (
+            datetime!(2024-02-05 0:00 UTC),
+            datetime!(2024-02-12 0:00 UTC),
+        );
+        let fixings: BTreeMap<OffsetDateTime, f64> =
+            (0..5).map(|day| (start + Duration::days(day), r)).collect();
+        let expected = ((1.0 + r / 360.0).powi(4) * (1.0 + 3.0 * r / 360.0) - 1.0) * 360.0 / 7.0;
+
+        for compounding in [RfrCompounding::InArrears, RfrCompounding::Lockout(2)] {
+            assert_approx_equal!(
+                compounding.compounded_rate(start, end, &curve, &fixings, &UnitedStates, &ACT360),
+                expected,
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_note() {
+        let context = CurveContext::new(curve());
+        let mut schedule =
+            Schedule::new_from_start(datetime!(2024-03-05 0:00 UTC), Duration::days(91), 8);
+        schedule.day_count_convention = ACT360;
+        let frn = FloatingRateNote::new(100.0, 0.0, &schedule, "SOFR", RfrCompounding::InArrears);
+
+        // On a reset date, compounded in arrears without spread, and
+        // discounted on the projection curve: par. The dates, on Tuesdays,
+        // are all business days.
+        assert_approx_equal!(frn.npv(&context, &UnitedStates), 100.0, 1e-10);
+
+        // A spread adds its annuity, and the discount margin recovers it.
+        let with_spread = FloatingRateNote {
+            spread: 0.0075,
+            ..frn.clone()
+        };
+        let price = with_spread.npv(&context, &UnitedStates);
+        assert!(price > 101.0);
+        assert_approx_equal!(
+            frn.discount_margin(&context, &UnitedStates, 100.0).unwrap(),
+            0.0,
+            1e-10
+        );
+        assert!(
+            with_spread
+                .discount_margin(&context, &UnitedStates, 100.0)
+                .unwrap()
+                > 0.0074
+        );
+    }
+}
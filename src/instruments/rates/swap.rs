@@ -0,0 +1,428 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Vanilla interest rate swaps: a fixed leg against a floating leg.
+//!
+//! Both legs are built from a [`Schedule`]: its dates are the accrual
+//! period boundaries (the first being the effective date) and coupons are
+//! paid at the end of each period, accrued with the schedule's day count
+//! convention. The swap is valued against a [`CurveContext`]: the floating
+//! rates are projected on the curve of the floating index and the cash
+//! flows discounted on the discount curve.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{CurveContext, KeyRateLadder};
+use crate::time::{DayCountConvention, DayCounter, Schedule};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Direction of a swap, from the point of view of the holder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapDirection {
+    /// Pays the fixed leg and receives the floating leg.
+    Payer,
+    /// Receives the fixed leg and pays the floating leg.
+    Receiver,
+}
+
+/// Leg of a swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapLegType {
+    /// Fixed rate leg.
+    Fixed,
+    /// Floating rate leg.
+    Floating,
+}
+
+/// Accrual periods of a swap leg.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapLeg {
+    /// Accrual period boundaries: the start date followed by the payment
+    /// dates.
+    pub dates: Vec<OffsetDateTime>,
+    /// Day count convention of the accrual periods.
+    pub day_count_convention: DayCountConvention,
+}
+
+/// Cash flow of a swap, as one row of its cash flow table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapCashflow {
+    /// Leg paying the cash flow.
+    pub leg: SwapLegType,
+    /// Start of the accrual period.
+    pub accrual_start: OffsetDateTime,
+    /// End of the accrual period, and payment date.
+    pub accrual_end: OffsetDateTime,
+    /// Accrual period year fraction.
+    pub year_fraction: f64,
+    /// Rate of the period: the fixed rate, or the floating rate (fixing or
+    /// projected forward) plus the spread.
+    pub rate: f64,
+    /// Amount paid, signed from the point of view of the holder.
+    pub amount: f64,
+    /// Discount factor to the payment date.
+    pub discount_factor: f64,
+    /// Present value of the amount.
+    pub present_value: f64,
+}
+
+/// Vanilla interest rate swap.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::Schedule;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let curve = YieldCurve::new(BTreeMap::from([
+///     (today, 0.03),
+///     (today + Duration::days(3650), 0.04),
+/// ]));
+/// let context = CurveContext::new(curve);
+///
+/// // 5 year swap: semi-annual fixed leg, quarterly floating leg.
+/// let fixed = Schedule::new_from_start(today, Duration::days(182), 10);
+/// let floating = Schedule::new_from_start(today, Duration::days(91), 20);
+///
+/// let swap = InterestRateSwap::new(
+///     1_000_000.0, 0.035, SwapDirection::Payer, &fixed, &floating, "IBOR3M",
+/// );
+///
+/// let par = swap.par_rate(&context);
+/// let at_par = InterestRateSwap { fixed_rate: par, ..swap.clone() };
+///
+/// assert!(at_par.npv(&context).abs() < 1e-6);
+/// assert!(swap.dv01(&context) > 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterestRateSwap {
+    /// Notional of both legs.
+    pub notional: f64,
+    /// Rate of the fixed leg.
+    pub fixed_rate: f64,
+    /// Payer or receiver of the fixed leg.
+    pub direction: SwapDirection,
+    /// Fixed leg accrual periods.
+    pub fixed_leg: SwapLeg,
+    /// Floating leg accrual periods.
+    pub floating_leg: SwapLeg,
+    /// Floating rate index, the key of its projection curve in the
+    /// [`CurveContext`].
+    pub floating_index: String,
+    /// Spread over the floating rate.
+    pub floating_spread: f64,
+    /// Fixings of the floating rate for the periods that started before
+    /// the valuation date, keyed by the start of the period.
+    pub fixings: BTreeMap<OffsetDateTime, f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl From<&Schedule> for SwapLeg {
+    fn from(schedule: &Schedule) -> Self {
+        Self {
+            dates: schedule.dates.clone(),
+            day_count_convention: schedule.day_count_convention,
+        }
+    }
+}
+
+impl SwapLeg {
+    /// Accrual periods `(start, end, year fraction)` paid after `date`.
+    #[must_use]
+    pub fn periods_after(
+        &self,
+        date: OffsetDateTime,
+    ) -> Vec<(OffsetDateTime, OffsetDateTime, f64)> {
+        self.dates
+            .windows(2)
+            .filter(|w| w[1] > date)
+            .map(|w| {
+                let tau = DayCounter::day_count_factor(w[0], w[1], &self.day_count_convention);
+                (w[0], w[1], tau)
+            })
+            .collect()
+    }
+}
+
+impl InterestRateSwap {
+    /// New swap without spread, with the legs on the dates and day count
+    /// conventions of the schedules.
+    #[must_use]
+    pub fn new(
+        notional: f64,
+        fixed_rate: f64,
+        direction: SwapDirection,
+        fixed_schedule: &Schedule,
+        floating_schedule: &Schedule,
+        floating_index: &str,
+    ) -> Self {
+        Self {
+            notional,
+            fixed_rate,
+            direction,
+            fixed_leg: SwapLeg::from(fixed_schedule),
+            floating_leg: SwapLeg::from(floating_schedule),
+            floating_index: floating_index.to_string(),
+            floating_spread: 0.0,
+            fixings: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the spread over the floating rate.
+    #[must_use]
+    pub fn with_floating_spread(mut self, spread: f64) -> Self {
+        self.floating_spread = spread;
+        self
+    }
+
+    /// Sets the fixing of the floating rate for the period starting on
+    /// `date`.
+    #[must_use]
+    pub fn with_fixing(mut self, date: OffsetDateTime, rate: f64) -> Self {
+        self.fixings.insert(date, rate);
+        self
+    }
+
+    /// Cash flow table of the payments after the valuation date (the
+    /// initial date of the discount curve), fixed leg first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a floating period started before the valuation date and
+    /// has no fixing.
+    #[must_use]
+    pub fn cashflows(&self, context: &CurveContext) -> Vec<SwapCashflow> {
+        let valuation_date = context.valuation_date();
+
+        // Sign of the fixed leg amounts for the holder.
+        let sign = match self.direction {
+            SwapDirection::Payer => -1.0,
+            SwapDirection::Receiver => 1.0,
+        };
+
+        let cashflow =
+            |leg, (start, end, tau): (OffsetDateTime, OffsetDateTime, f64), rate, sign| {
+                let amount = sign * self.notional * rate * tau;
+                let discount_factor = context.discount_factor(end);
+
+                SwapCashflow {
+                    leg,
+                    accrual_start: start,
+                    accrual_end: end,
+                    year_fraction: tau,
+                    rate,
+                    amount,
+                    discount_factor,
+                    present_value: amount * discount_factor,
+                }
+            };
+
+        let fixed = self
+            .fixed_leg
+            .periods_after(valuation_date)
+            .into_iter()
+            .map(|period| cashflow(SwapLegType::Fixed, period, self.fixed_rate, sign));
+
+        let floating = self
+            .floating_leg
+            .periods_after(valuation_date)
+            .into_iter()
+            .map(|period| {
+                let (start, end, _) = period;
+                let index_rate = if start < valuation_date {
+                    *self
+                        .fixings
+                        .get(&start)
+                        .expect("No fixing for a floating period that has started.")
+                } else {
+                    context.forward_rate(
+                        &self.floating_index,
+                        start,
+                        end,
+                        &self.floating_leg.day_count_convention,
+                    )
+                };
+                let rate = index_rate + self.floating_spread;
+
+                cashflow(SwapLegType::Floating, period, rate, -sign)
+            });
+
+        fixed.chain(floating).collect()
+    }
+
+    /// Present value of a leg, signed from the point of view of the holder.
+    #[must_use]
+    pub fn leg_npv(&self, leg: SwapLegType, context: &CurveContext) -> f64 {
+        self.cashflows(context)
+            .iter()
+            .filter(|cashflow| cashflow.leg == leg)
+            .map(|cashflow| cashflow.present_value)
+            .sum()
+    }
+
+    /// Net present value of the swap for the holder.
+    #[must_use]
+    pub fn npv(&self, context: &CurveContext) -> f64 {
+        self.cashflows(context)
+            .iter()
+            .map(|cashflow| cashflow.present_value)
+            .sum()
+    }
+
+    /// Annuity of the fixed leg: the present value of a unit rate paid on
+    /// a unit notional, $\sum_i \tau_i P(t_i)$.
+    #[must_use]
+    pub fn annuity(&self, context: &CurveContext) -> f64 {
+        self.fixed_leg
+            .periods_after(context.valuation_date())
+            .iter()
+            .map(|(_, end, tau)| tau * context.discount_factor(*end))
+            .sum()
+    }
+
+    /// Par rate: the fixed rate for which the swap is worth zero.
+    #[must_use]
+    pub fn par_rate(&self, context: &CurveContext) -> f64 {
+        // Value of the floating leg to the floating rate receiver.
+        let floating = match self.direction {
+            SwapDirection::Payer => self.leg_npv(SwapLegType::Floating, context),
+            SwapDirection::Receiver => -self.leg_npv(SwapLegType::Floating, context),
+        };
+
+        floating / (self.notional * self.annuity(context))
+    }
+
+    /// DV01: the change in the net present value for a one basis point
+    /// parallel rise of all the curves (central difference). Positive for
+    /// payer swaps.
+    #[must_use]
+    pub fn dv01(&self, context: &CurveContext) -> f64 {
+        const BASIS_POINT: f64 = 1e-4;
+
+        (self.npv(&context.shifted(BASIS_POINT)) - self.npv(&context.shifted(-BASIS_POINT))) / 2.0
+    }
+
+    /// Key-rate DV01s: the change in the net present value for a one basis
+    /// point rise of the zero rate of each pillar of the curves, keyed by
+    /// tenor. They add up to the [`InterestRateSwap::dv01`] when the curves
+    /// are interpolated linearly.
+    #[must_use]
+    pub fn key_rate_dv01s(&self, context: &CurveContext) -> KeyRateLadder {
+        context.key_rate_dv01s(|context| self.npv(context))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_swap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{curve, days};
+    use crate::curves::{Curve, YieldCurve};
+    use time::Duration;
+
+    fn swap(direction: SwapDirection) -> InterestRateSwap {
+        let fixed = Schedule::new_from_start(days(0), Duration::days(365), 5);
+        let floating = Schedule::new_from_start(days(0), Duration::days(91), 20);
+
+        InterestRateSwap::new(100.0, 0.04, direction, &fixed, &floating, "IBOR3M")
+    }
+
+    #[test]
+    fn test_single_curve() {
+        let context = CurveContext::new(curve(0.03, 0.045));
+        let payer = swap(SwapDirection::Payer);
+        let receiver = swap(SwapDirection::Receiver);
+
+        // The floating leg telescopes: its value is N (1 - P(T_n)) per unit
+        // notional when it is projected and discounted on one curve.
+        let end = *payer.floating_leg.dates.last().unwrap();
+        assert_approx_equal!(
+            payer.leg_npv(SwapLegType::Floating, &context),
+            100.0 * (1.0 - context.discount_curve.discount_factor(end)),
+            1e-10
+        );
+
+        assert_approx_equal!(payer.npv(&context), -receiver.npv(&context), 1e-12);
+        assert_eq!(payer.cashflows(&context).len(), 25);
+
+        let par = InterestRateSwap {
+            fixed_rate: payer.par_rate(&context),
+            ..payer.clone()
+        };
+        assert_approx_equal!(par.npv(&context), 0.0, 1e-12);
+
+        // DV01 of the single-curve swap: N (T_n P(T_n) + c sum tau_i t_i P(t_i))
+        // per unit shift of the (continuously compounded) zero rates.
+        let df = |date| context.discount_curve.discount_factor(date);
+        let t = |date| DayCounter::day_count_factor(days(0), date, &DayCountConvention::Actual365);
+        let fixed_leg: f64 = par
+            .fixed_leg
+            .periods_after(days(0))
+            .iter()
+            .map(|(_, end, tau)| tau * t(*end) * df(*end))
+            .sum();
+        let dv01 = 100.0 * (t(end) * df(end) + par.fixed_rate * fixed_leg) * 1e-4;
+
+        assert!(par.dv01(&context) > 0.0);
+        assert_approx_equal!(par.dv01(&context), dv01, 1e-7);
+        assert_approx_equal!(receiver.dv01(&context), -payer.dv01(&context), 1e-12);
+    }
+
+    #[test]
+    fn test_dual_curve_and_fixings() {
+        let context = CurveContext::new(curve(0.03, 0.03))
+            .with_projection_curve("IBOR3M", curve(0.035, 0.035));
+        let payer = swap(SwapDirection::Payer);
+
+        // The par rate is the annuity-weighted average of the forwards,
+        // above the OIS rate.
+        assert!(payer.par_rate(&context) > 0.035);
+        assert!(payer.par_rate(&context) > payer.par_rate(&CurveContext::new(curve(0.03, 0.03))));
+
+        // A spread moves the par rate by the ratio of the legs' annuities.
+        let spread = payer.clone().with_floating_spread(0.001);
+        assert_approx_equal!(
+            spread.par_rate(&context) - payer.par_rate(&context),
+            0.001,
+            2e-5
+        );
+
+        // Seasoned swap: the current floating period has fixed.
+        let seasoned = CurveContext::new(YieldCurve::new(BTreeMap::from([
+            (days(30), 0.03),
+            (days(4000), 0.03),
+        ])));
+        let fixed = payer.clone().with_fixing(days(0), 0.05);
+        let first = fixed
+            .cashflows(&seasoned)
+            .into_iter()
+            .find(|cashflow| cashflow.leg == SwapLegType::Floating)
+            .unwrap();
+
+        assert_eq!(first.accrual_start, days(0));
+        assert_approx_equal!(first.rate, 0.05, 1e-15);
+        assert_approx_equal!(first.amount, 100.0 * 0.05 * 91.0 / 365.0, 1e-12);
+    }
+}
@@ -0,0 +1,400 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! European swaptions: options to enter an interest rate swap at the
+//! fixed rate (the strike) on the expiry date.
+//!
+//! Under the annuity measure the forward swap rate $S$ is a martingale, so
+//! a physically settled payer swaption is worth $N A \, C(S, K, T)$, with
+//! $A$ the annuity of the underlying swap from the discount curve and $C$
+//! the (undiscounted) Black-76 or Bachelier call price. A put gives the
+//! receiver swaption.
+//!
+//! Cash settled (par yield) swaptions pay $N a(S_T) (S_T - K)^+$ at the
+//! start of the swap, where the cash annuity $a(S)$ discounts the fixed
+//! leg at the swap rate itself. The market approximation values them as
+//! $N P(t_0) a(S) \, C(S, K, T)$.
+//!
+//! Bermudan swaptions can be exercised on several dates into the remaining
+//! part of the swap, and are priced on a short-rate lattice, see
+//! [`HullWhiteModel::bermudan_swaption_price`](crate::models::HullWhiteModel::bermudan_swaption_price).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::CurveContext;
+use crate::instruments::options::{ImpliedVolatilityError, TypeFlag};
+use crate::instruments::rates::{InterestRateSwap, RateVolatility, SwapDirection};
+use crate::time::{DayCountConvention, DayCounter};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Settlement of a swaption on exercise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwaptionSettlement {
+    /// The holder enters the underlying swap.
+    Physical,
+    /// The holder receives the value of the swap in cash, computed with
+    /// the cash (par yield) annuity at the swap rate fixed on expiry.
+    CashParYield,
+}
+
+/// European swaption.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::instruments::*;
+/// use RustQuant::time::Schedule;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let expiry = today + Duration::days(365);
+///
+/// let context = CurveContext::new(YieldCurve::new(BTreeMap::from([
+///     (today, 0.03),
+///     (today + Duration::days(3650), 0.04),
+/// ])));
+///
+/// // 1y x 5y payer swaption, struck at the money.
+/// let fixed = Schedule::new_from_start(expiry, Duration::days(365), 5);
+/// let floating = Schedule::new_from_start(expiry, Duration::days(182), 10);
+/// let swap = InterestRateSwap::new(1e6, 0.0, SwapDirection::Payer, &fixed, &floating, "IBOR6M");
+/// let atm = swap.par_rate(&context);
+///
+/// let swaption = Swaption::new(
+///     InterestRateSwap { fixed_rate: atm, ..swap },
+///     expiry,
+///     SwaptionSettlement::Physical,
+/// );
+///
+/// let price = swaption.price(&context, &RateVolatility::Normal(0.01));
+/// let implied = swaption.implied_volatility(price, &context, &RateVolatility::Lognormal(0.0));
+///
+/// assert!(price > 0.0);
+/// assert!(implied.is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Swaption {
+    /// Underlying swap, starting on or after the expiry. Its fixed rate is
+    /// the strike, and its direction makes a payer or receiver swaption.
+    pub swap: InterestRateSwap,
+    /// Expiry date.
+    pub expiry: OffsetDateTime,
+    /// Settlement on exercise.
+    pub settlement: SwaptionSettlement,
+}
+
+/// Bermudan swaption: the option to enter, on any of the exercise dates,
+/// the periods of the underlying swap that start on or after that date.
+/// Settlement is physical.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BermudanSwaption {
+    /// Underlying swap. Its fixed rate is the strike, and its direction
+    /// makes a payer or receiver swaption.
+    pub swap: InterestRateSwap,
+    /// Exercise dates, in increasing order.
+    pub exercise_dates: Vec<OffsetDateTime>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Swaption {
+    /// New swaption on a swap.
+    #[must_use]
+    pub const fn new(
+        swap: InterestRateSwap,
+        expiry: OffsetDateTime,
+        settlement: SwaptionSettlement,
+    ) -> Self {
+        Self {
+            swap,
+            expiry,
+            settlement,
+        }
+    }
+
+    /// Strike: the fixed rate of the underlying swap.
+    #[must_use]
+    pub const fn strike(&self) -> f64 {
+        self.swap.fixed_rate
+    }
+
+    /// Time to expiry (Actual/365) from the valuation date.
+    #[must_use]
+    pub fn time_to_expiry(&self, context: &CurveContext) -> f64 {
+        DayCounter::day_count_factor(
+            context.valuation_date(),
+            self.expiry,
+            &DayCountConvention::Actual365,
+        )
+    }
+
+    /// Forward swap rate of the underlying swap.
+    #[must_use]
+    pub fn forward_swap_rate(&self, context: &CurveContext) -> f64 {
+        self.swap.par_rate(context)
+    }
+
+    /// Cash (par yield) annuity of the fixed leg at a swap rate, per unit
+    /// notional and undiscounted: the fixed leg accruals discounted at the
+    /// swap rate, $\sum_i \tau_i \prod_{j \le i} (1 + \tau_j S)^{-1}$.
+    #[must_use]
+    pub fn cash_annuity(&self, swap_rate: f64) -> f64 {
+        let leg = &self.swap.fixed_leg;
+
+        leg.dates
+            .windows(2)
+            .scan(1.0, |discount, w| {
+                let tau = DayCounter::day_count_factor(w[0], w[1], &leg.day_count_convention);
+                *discount /= 1.0 + tau * swap_rate;
+                Some(tau * *discount)
+            })
+            .sum()
+    }
+
+    /// Annuity of the swaption per unit notional, discounted to the
+    /// valuation date: the annuity of the swap from the discount curve for
+    /// physical settlement, and the cash annuity at the forward swap rate
+    /// discounted from the start of the swap for cash settlement.
+    #[must_use]
+    pub fn annuity(&self, context: &CurveContext) -> f64 {
+        match self.settlement {
+            SwaptionSettlement::Physical => self.swap.annuity(context),
+            SwaptionSettlement::CashParYield => {
+                context.discount_factor(self.swap.fixed_leg.dates[0])
+                    * self.cash_annuity(self.forward_swap_rate(context))
+            }
+        }
+    }
+
+    /// Amount paid on exercise of a cash settled swaption when the swap
+    /// rate fixes at `swap_rate`, paid at the start of the swap.
+    #[must_use]
+    pub fn cash_settlement_amount(&self, swap_rate: f64) -> f64 {
+        let intrinsic = match self.swap.direction {
+            SwapDirection::Payer => swap_rate - self.strike(),
+            SwapDirection::Receiver => self.strike() - swap_rate,
+        };
+
+        self.swap.notional * self.cash_annuity(swap_rate) * intrinsic.max(0.0)
+    }
+
+    /// Price of the swaption with a Black-76, shifted Black-76 or
+    /// Bachelier volatility of the swap rate.
+    #[must_use]
+    pub fn price(&self, context: &CurveContext, volatility: &RateVolatility) -> f64 {
+        let option = volatility.forward_price(
+            self.forward_swap_rate(context),
+            self.strike(),
+            self.time_to_expiry(context),
+            self.type_flag(),
+        );
+
+        self.swap.notional * self.annuity(context) * option
+    }
+
+    /// Implied volatility of a swaption price, of the same kind as
+    /// `volatility` (whose value is ignored, but whose shift is kept).
+    ///
+    /// # Errors
+    ///
+    /// See [`RateVolatility::implied_from_price`].
+    pub fn implied_volatility(
+        &self,
+        price: f64,
+        context: &CurveContext,
+        volatility: &RateVolatility,
+    ) -> Result<RateVolatility, ImpliedVolatilityError> {
+        volatility.implied_from_price(
+            price,
+            self.forward_swap_rate(context),
+            self.strike(),
+            self.time_to_expiry(context),
+            self.swap.notional * self.annuity(context),
+            self.type_flag(),
+        )
+    }
+
+    // Payer swaptions are calls on the swap rate, receivers puts.
+    fn type_flag(&self) -> TypeFlag {
+        match self.swap.direction {
+            SwapDirection::Payer => TypeFlag::Call,
+            SwapDirection::Receiver => TypeFlag::Put,
+        }
+    }
+}
+
+impl BermudanSwaption {
+    /// New Bermudan swaption on a swap, exercisable on the given dates.
+    #[must_use]
+    pub fn new(swap: InterestRateSwap, exercise_dates: &[OffsetDateTime]) -> Self {
+        let mut exercise_dates = exercise_dates.to_vec();
+        exercise_dates.sort();
+
+        Self {
+            swap,
+            exercise_dates,
+        }
+    }
+
+    /// Strike: the fixed rate of the underlying swap.
+    #[must_use]
+    pub const fn strike(&self) -> f64 {
+        self.swap.fixed_rate
+    }
+
+    /// Fixed leg periods `(start, end, year fraction)` entered on exercise
+    /// on `date`.
+    #[must_use]
+    pub fn periods_from(&self, date: OffsetDateTime) -> Vec<(OffsetDateTime, OffsetDateTime, f64)> {
+        self.swap
+            .fixed_leg
+            .periods_after(date)
+            .into_iter()
+            .filter(|(start, _, _)| *start >= date)
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_swaption {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{context, days};
+    use crate::time::Schedule;
+    use std::f64::consts::PI;
+    use time::Duration;
+
+    // 2y x 5y swaption with annual fixed and floating legs.
+    fn swaption(direction: SwapDirection, strike: f64, settlement: SwaptionSettlement) -> Swaption {
+        let fixed = Schedule::new_from_start(days(730), Duration::days(365), 5);
+        let swap = InterestRateSwap::new(100.0, strike, direction, &fixed, &fixed, "IBOR12M");
+
+        Swaption::new(swap, days(730), settlement)
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        let context = context(0.02, 0.035);
+        let forward = swaption(SwapDirection::Payer, 0.0, SwaptionSettlement::Physical)
+            .forward_swap_rate(&context);
+
+        for strike in [0.01, forward, 0.05] {
+            let payer = swaption(SwapDirection::Payer, strike, SwaptionSettlement::Physical);
+            let receiver = swaption(
+                SwapDirection::Receiver,
+                strike,
+                SwaptionSettlement::Physical,
+            );
+
+            for volatility in [
+                RateVolatility::Lognormal(0.25),
+                RateVolatility::Normal(0.008),
+            ] {
+                // Payer - receiver = the forward starting payer swap.
+                assert_approx_equal!(
+                    payer.price(&context, &volatility) - receiver.price(&context, &volatility),
+                    payer.swap.npv(&context),
+                    1e-12
+                );
+            }
+        }
+
+        // At the money, the Bachelier price is N A sigma sqrt(T / 2 pi).
+        let atm = swaption(SwapDirection::Payer, forward, SwaptionSettlement::Physical);
+        assert_approx_equal!(
+            atm.price(&context, &RateVolatility::Normal(0.008)),
+            100.0 * atm.annuity(&context) * 0.008 * (2.0 / (2.0 * PI)).sqrt(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_cash_settlement() {
+        // On a flat curve with annual periods of 365 days, the forward swap
+        // rate is the annually compounded rate, and the cash annuity
+        // discounts like the curve: both settlements have the same price.
+        let flat = context(0.03, 0.03);
+        let physical = swaption(SwapDirection::Payer, 0.03, SwaptionSettlement::Physical);
+        let cash = swaption(SwapDirection::Payer, 0.03, SwaptionSettlement::CashParYield);
+
+        assert_approx_equal!(physical.forward_swap_rate(&flat), 0.03_f64.exp_m1(), 1e-12);
+        assert_approx_equal!(cash.annuity(&flat), physical.annuity(&flat), 1e-12);
+
+        let volatility = RateVolatility::Lognormal(0.2);
+        assert_approx_equal!(
+            cash.price(&flat, &volatility),
+            physical.price(&flat, &volatility),
+            1e-12
+        );
+
+        // The cash settlement amount is the intrinsic value times the cash
+        // annuity, and zero out of the money.
+        assert_approx_equal!(
+            cash.cash_settlement_amount(0.04),
+            100.0 * 0.01 * cash.cash_annuity(0.04),
+            1e-12
+        );
+        assert_approx_equal!(cash.cash_settlement_amount(0.02), 0.0, 1e-15);
+
+        // On a sloped curve the annuities differ.
+        let sloped = context(0.01, 0.05);
+        assert!((cash.annuity(&sloped) - physical.annuity(&sloped)).abs() > 1e-4);
+    }
+
+    #[test]
+    fn test_implied_volatility() {
+        let context = context(-0.004, 0.002);
+
+        for settlement in [
+            SwaptionSettlement::Physical,
+            SwaptionSettlement::CashParYield,
+        ] {
+            for direction in [SwapDirection::Payer, SwapDirection::Receiver] {
+                let swaption = swaption(direction, 0.0005, settlement);
+
+                for volatility in [
+                    RateVolatility::Normal(0.005),
+                    RateVolatility::ShiftedLognormal {
+                        volatility: 0.3,
+                        shift: 0.02,
+                    },
+                ] {
+                    let price = swaption.price(&context, &volatility);
+                    let implied = swaption
+                        .implied_volatility(price, &context, &volatility)
+                        .unwrap();
+
+                    match (implied, volatility) {
+                        (RateVolatility::Normal(implied), RateVolatility::Normal(v))
+                        | (
+                            RateVolatility::ShiftedLognormal {
+                                volatility: implied,
+                                ..
+                            },
+                            RateVolatility::ShiftedLognormal { volatility: v, .. },
+                        ) => assert_approx_equal!(implied, v, 1e-9 * v),
+                        _ => panic!("The implied volatility is of another kind."),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -7,11 +7,377 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! Fast Fourier transforms, and the grids of the Fourier option pricing
+//! methods.
+//!
+//! - [`FourierTransform`]: planned transform of complex sequences of any
+//!   length, by the iterative radix-2 algorithm for powers of 2, and by
+//!   Bluestein's algorithm (a convolution of radix-2 transforms) otherwise.
+//! - [`RealFourierTransform`]: transform of real sequences of even length,
+//!   from a complex transform of half the length.
+//! - [`convolve`]: linear convolution of real sequences.
+//! - [`FourierGrid`]: reciprocal grids of frequencies and log-strikes (or
+//!   log-prices), for the Carr-Madan (1999) method, and [`conv_expectation`]
+//!   for the CONV method (Lord et al., 2008).
+
 use num_complex::Complex;
 use std::f64::consts::PI;
 
 // pub const i: Complex<f64> = Complex { re: 0.0, im: 1.0 };
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Discrete Fourier transform of complex sequences of a given length,
+///
+/// $$
+/// X_k = \sum_{j=0}^{n-1} x_j e^{-2 \pi i j k / n},
+/// $$
+///
+/// with the twiddle factors computed once for repeated transforms.
+///
+/// ```
+/// use RustQuant::math::*;
+/// use num_complex::Complex;
+///
+/// let transform = FourierTransform::new(3);
+///
+/// let mut x = vec![Complex::new(1.0, 0.0); 3];
+/// transform.forward(&mut x);
+/// assert!((x[0] - 3.0).norm() < 1e-15 && x[1].norm() < 1e-15);
+///
+/// transform.inverse(&mut x);
+/// assert!((x[2] - 1.0).norm() < 1e-15);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FourierTransform {
+    size: usize,
+    algorithm: Algorithm,
+}
+
+/// Discrete Fourier transform of real sequences of a given even length.
+///
+/// The transform of a real sequence is conjugate symmetric,
+/// $X_{n-k} = \overline{X_k}$: only $X_0, \ldots, X_{n/2}$ are computed,
+/// from a complex transform of length `n / 2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealFourierTransform {
+    size: usize,
+    half: FourierTransform,
+    twiddles: Vec<Complex<f64>>,
+}
+
+/// Reciprocal grids of a discrete Fourier transform of `size` points:
+/// the frequencies $u_j = j \Delta u$ and the points
+/// $x_k = x_0 + k \Delta x$ (log-strikes for Carr-Madan, log-prices for
+/// CONV), with
+///
+/// $$
+/// \Delta u \Delta x = \frac{2 \pi}{\text{size}}.
+/// $$
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // Log-strike grid centred on ln(100).
+/// let grid = FourierGrid::new(4096, 0.25, 100_f64.ln());
+///
+/// assert!((grid.strikes()[2048] - 100.0).abs() < 1e-12);
+/// assert!((grid.spacing - 2.0 * std::f64::consts::PI / 1024.0).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FourierGrid {
+    /// Number of points.
+    pub size: usize,
+    /// Spacing of the frequencies, `du`.
+    pub frequency_spacing: f64,
+    /// Spacing of the points, `dx`.
+    pub spacing: f64,
+    /// First point, `x_0`.
+    pub start: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Algorithm {
+    // Twiddle factors e^{-2 pi i k / n}, for k < n / 2.
+    Radix2(Vec<Complex<f64>>),
+    // Chirp e^{-pi i k^2 / n}, and the transform of the convolution kernel
+    // (the conjugate chirp) by the power of 2 transform.
+    Bluestein {
+        chirp: Vec<Complex<f64>>,
+        kernel: Vec<Complex<f64>>,
+        inner: Box<FourierTransform>,
+    },
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FourierTransform {
+    /// New transform of sequences of length `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "A Fourier transform needs at least one point.");
+
+        let algorithm = if size.is_power_of_two() {
+            Algorithm::Radix2(
+                (0..size / 2)
+                    .map(|k| Complex::from_polar(1.0, -2.0 * PI * k as f64 / size as f64))
+                    .collect(),
+            )
+        } else {
+            // k^2 mod 2n keeps the phase accurate for large k.
+            let chirp: Vec<Complex<f64>> = (0..size)
+                .map(|k| {
+                    let phase = (k * k) % (2 * size);
+                    Complex::from_polar(1.0, -PI * phase as f64 / size as f64)
+                })
+                .collect();
+
+            let inner = Self::new((2 * size - 1).next_power_of_two());
+            let mut kernel = vec![Complex::new(0.0, 0.0); inner.size];
+            kernel[0] = chirp[0].conj();
+            for k in 1..size {
+                kernel[k] = chirp[k].conj();
+                kernel[inner.size - k] = chirp[k].conj();
+            }
+            inner.forward(&mut kernel);
+
+            Algorithm::Bluestein {
+                chirp,
+                kernel,
+                inner: Box::new(inner),
+            }
+        };
+
+        Self { size, algorithm }
+    }
+
+    /// Length of the sequences.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Forward transform of `x`, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `x` is not the size of the transform.
+    pub fn forward(&self, x: &mut [Complex<f64>]) {
+        assert_eq!(x.len(), self.size, "Length of the sequence to transform.");
+
+        match &self.algorithm {
+            Algorithm::Radix2(twiddles) => radix2(x, twiddles),
+            Algorithm::Bluestein {
+                chirp,
+                kernel,
+                inner,
+            } => {
+                let mut a = vec![Complex::new(0.0, 0.0); inner.size];
+                for ((a, x), w) in a.iter_mut().zip(x.iter()).zip(chirp) {
+                    *a = x * w;
+                }
+
+                inner.forward(&mut a);
+                a.iter_mut().zip(kernel).for_each(|(a, b)| *a *= b);
+                inner.inverse(&mut a);
+
+                for ((x, a), w) in x.iter_mut().zip(a).zip(chirp) {
+                    *x = a * w;
+                }
+            }
+        }
+    }
+
+    /// Inverse transform of `x`, in place, including the factor `1 / n`:
+    ///
+    /// $$
+    /// x_j = \frac{1}{n} \sum_{k=0}^{n-1} X_k e^{2 \pi i j k / n}.
+    /// $$
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `x` is not the size of the transform.
+    pub fn inverse(&self, x: &mut [Complex<f64>]) {
+        // The inverse transform is the conjugate of the forward transform of
+        // the conjugate.
+        for x in x.iter_mut() {
+            *x = x.conj();
+        }
+        self.forward(x);
+
+        let scale = 1.0 / self.size as f64;
+        for x in x.iter_mut() {
+            *x = x.conj() * scale;
+        }
+    }
+}
+
+impl RealFourierTransform {
+    /// New transform of real sequences of length `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero or odd.
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        assert!(
+            size > 0 && size.is_multiple_of(2),
+            "A real Fourier transform needs an even number of points."
+        );
+
+        Self {
+            size,
+            half: FourierTransform::new(size / 2),
+            twiddles: (0..=size / 2)
+                .map(|k| Complex::from_polar(1.0, -2.0 * PI * k as f64 / size as f64))
+                .collect(),
+        }
+    }
+
+    /// Length of the sequences.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Forward transform of `x`: the terms $X_0, \ldots, X_{n/2}$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `x` is not the size of the transform.
+    #[must_use]
+    pub fn forward(&self, x: &[f64]) -> Vec<Complex<f64>> {
+        assert_eq!(x.len(), self.size, "Length of the sequence to transform.");
+        let m = self.size / 2;
+
+        // The even and odd terms as the real and imaginary parts: z = e + i o,
+        // and Z = E + i O, with E and O conjugate symmetric.
+        let mut z: Vec<Complex<f64>> = x
+            .chunks_exact(2)
+            .map(|pair| Complex::new(pair[0], pair[1]))
+            .collect();
+        self.half.forward(&mut z);
+
+        (0..=m)
+            .map(|k| {
+                let (z_k, z_conj) = (z[k % m], z[(m - k) % m].conj());
+                let even = 0.5 * (z_k + z_conj);
+                let odd = Complex::new(0.0, -0.5) * (z_k - z_conj);
+
+                even + self.twiddles[k] * odd
+            })
+            .collect()
+    }
+
+    /// Inverse transform of the terms $X_0, \ldots, X_{n/2}$ of a
+    /// conjugate symmetric sequence, including the factor `1 / n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `spectrum` is not `n / 2 + 1`.
+    #[must_use]
+    pub fn inverse(&self, spectrum: &[Complex<f64>]) -> Vec<f64> {
+        let m = self.size / 2;
+        assert_eq!(
+            spectrum.len(),
+            m + 1,
+            "Length of the sequence to transform."
+        );
+
+        let mut z: Vec<Complex<f64>> = (0..m)
+            .map(|k| {
+                let (x_k, x_conj) = (spectrum[k], spectrum[m - k].conj());
+                let even = 0.5 * (x_k + x_conj);
+                let odd = 0.5 * (x_k - x_conj) * self.twiddles[k].conj();
+
+                even + Complex::<f64>::i() * odd
+            })
+            .collect();
+        self.half.inverse(&mut z);
+
+        z.iter().flat_map(|z| [z.re, z.im]).collect()
+    }
+}
+
+impl FourierGrid {
+    /// Grid of `size` points, with frequency spacing `frequency_spacing`,
+    /// and the point `size / 2` at `centre`.
+    #[must_use]
+    pub fn new(size: usize, frequency_spacing: f64, centre: f64) -> Self {
+        let spacing = 2.0 * PI / (size as f64 * frequency_spacing);
+
+        Self {
+            size,
+            frequency_spacing,
+            spacing,
+            start: centre - (size / 2) as f64 * spacing,
+        }
+    }
+
+    /// Grid of `size` points, with spacing `spacing`, and the point
+    /// `size / 2` at `centre`.
+    #[must_use]
+    pub fn with_spacing(size: usize, spacing: f64, centre: f64) -> Self {
+        Self::new(size, 2.0 * PI / (size as f64 * spacing), centre)
+    }
+
+    /// Frequencies $u_j = j \Delta u$.
+    #[must_use]
+    pub fn frequencies(&self) -> Vec<f64> {
+        (0..self.size)
+            .map(|j| j as f64 * self.frequency_spacing)
+            .collect()
+    }
+
+    /// Points $x_k = x_0 + k \Delta x$.
+    #[must_use]
+    pub fn points(&self) -> Vec<f64> {
+        (0..self.size)
+            .map(|k| self.start + k as f64 * self.spacing)
+            .collect()
+    }
+
+    /// Strikes (or prices) $e^{x_k}$ of the log-strike (or log-price)
+    /// points.
+    #[must_use]
+    pub fn strikes(&self) -> Vec<f64> {
+        self.points().into_iter().map(f64::exp).collect()
+    }
+
+    /// Value at the log-strike `x` of the values at the points,
+    /// interpolated linearly, or `None` outside the grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `values` is not the size of the grid.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn interpolate(&self, values: &[f64], x: f64) -> Option<f64> {
+        assert_eq!(values.len(), self.size, "One value per point of the grid.");
+
+        let position = (x - self.start) / self.spacing;
+        if !(0.0..=(self.size - 1) as f64).contains(&position) {
+            return None;
+        }
+
+        let k = (position.floor() as usize).min(self.size.saturating_sub(2));
+        let w = position - k as f64;
+
+        Some(match values.get(k + 1) {
+            Some(next) => (1.0 - w) * values[k] + w * next,
+            None => values[k],
+        })
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -116,6 +482,143 @@ fn fft_complex_calculation(x: &mut Vec<Complex<f64>>) {
     }
 }
 
+/// Linear convolution of `a` and `b`, of length `a.len() + b.len() - 1`:
+///
+/// $$
+/// (a * b)_k = \sum_j a_j b_{k-j}.
+/// $$
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // (1 + 2x)(3 + x + x^2) = 3 + 7x + 3x^2 + 2x^3.
+/// let product = convolve(&[1.0, 2.0], &[3.0, 1.0, 1.0]);
+///
+/// for (c, expected) in product.iter().zip([3.0, 7.0, 3.0, 2.0]) {
+///     assert!((c - expected).abs() < 1e-14);
+/// }
+/// ```
+#[must_use]
+pub fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let length = a.len() + b.len() - 1;
+    let transform = RealFourierTransform::new(length.next_power_of_two().max(2));
+
+    let padded = |x: &[f64]| {
+        let mut padded = x.to_vec();
+        padded.resize(transform.size(), 0.0);
+        transform.forward(&padded)
+    };
+    let spectrum: Vec<Complex<f64>> = padded(a)
+        .into_iter()
+        .zip(padded(b))
+        .map(|(a, b)| a * b)
+        .collect();
+
+    let mut convolution = transform.inverse(&spectrum);
+    convolution.truncate(length);
+    convolution
+}
+
+/// Expectations $E[V(x_k + Z)]$ at the points of the grid, given the values
+/// $V(x_k)$ at the points, by the CONV method (Lord et al., 2008): one step
+/// of the backward induction, without discounting.
+///
+/// `characteristic_function` is $\phi(u) = E[e^{i u Z}]$ of the increment,
+/// evaluated at $u + i \alpha$: the values are damped by $e^{\alpha x}$,
+/// e.g. $\alpha < -1$ for calls on the price $e^x$ if the grid is narrow.
+/// The convolution is circular: the values near the ends of the grid are
+/// not accurate.
+///
+/// # Panics
+///
+/// Panics if the size of the grid is odd, or the length of `values` is not
+/// the size of the grid.
+#[must_use]
+pub fn conv_expectation<F>(
+    grid: &FourierGrid,
+    values: &[f64],
+    characteristic_function: F,
+    alpha: f64,
+) -> Vec<f64>
+where
+    F: Fn(Complex<f64>) -> Complex<f64>,
+{
+    assert_eq!(values.len(), grid.size, "One value per point of the grid.");
+    let transform = RealFourierTransform::new(grid.size);
+    let points = grid.points();
+
+    // Damped values, with the trapezoidal rule weights.
+    let damped: Vec<f64> = points
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(k, (x, v))| {
+            let weight = if k == 0 || k == grid.size - 1 {
+                0.5
+            } else {
+                1.0
+            };
+            weight * (alpha * x).exp() * v
+        })
+        .collect();
+
+    // The expectation is the correlation of the damped values with the
+    // damped density of the increment: its transform is the product of the
+    // transform of the values with phi(u + i alpha).
+    let spectrum: Vec<Complex<f64>> = transform
+        .forward(&damped)
+        .into_iter()
+        .zip(grid.frequencies())
+        .map(|(c, u)| c * characteristic_function(Complex::new(u, alpha)))
+        .collect();
+
+    transform
+        .inverse(&spectrum)
+        .into_iter()
+        .zip(points)
+        .map(|(c, x)| (-alpha * x).exp() * c)
+        .collect()
+}
+
+// Iterative radix-2 transform, in place, given the twiddle factors.
+fn radix2(x: &mut [Complex<f64>], twiddles: &[Complex<f64>]) {
+    let n = x.len();
+    if n < 2 {
+        return;
+    }
+
+    // Bit reversal permutation.
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            x.swap(i, j);
+        }
+    }
+
+    // Butterflies of blocks of length 2, 4, ..., n.
+    let mut length = 2;
+    while length <= n {
+        let stride = n / length;
+
+        for block in x.chunks_exact_mut(length) {
+            let (low, high) = block.split_at_mut(length / 2);
+
+            for (k, (a, b)) in low.iter_mut().zip(high).enumerate() {
+                let t = twiddles[k * stride] * *b;
+                *b = *a - t;
+                *a += t;
+            }
+        }
+
+        length *= 2;
+    }
+}
+
 fn split_array<T: Copy>(x: &Vec<T>) -> (Vec<T>, Vec<T>) {
     let n = x.len();
 
@@ -208,6 +711,128 @@ mod test {
         assert_real_vecs_almost_equal(&test_vec, &REAL_TEST_SEQUENCE.to_vec());
     }
 
+    // Discrete Fourier transform, by its definition.
+    fn dft(x: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let n = x.len() as f64;
+
+        (0..x.len())
+            .map(|k| {
+                x.iter()
+                    .enumerate()
+                    .map(|(j, x)| x * Complex::from_polar(1.0, -2.0 * PI * (j * k) as f64 / n))
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn sequence(n: usize) -> Vec<Complex<f64>> {
+        (0..n)
+            .map(|j| Complex::new((j as f64).sin() + 0.5, (0.3 * j as f64).cos()))
+            .collect()
+    }
+
+    #[test]
+    fn test_fourier_transform() {
+        // Radix-2 and Bluestein lengths.
+        for n in [1, 2, 8, 64, 3, 7, 12, 100] {
+            let transform = FourierTransform::new(n);
+            let x = sequence(n);
+
+            let mut y = x.clone();
+            transform.forward(&mut y);
+            assert_complex_vecs_almost_equal(&y, &dft(&x));
+
+            transform.inverse(&mut y);
+            assert_complex_vecs_almost_equal(&y, &x);
+        }
+
+        assert_complex_vecs_almost_equal(
+            &fft_complex(&COMPLEX_TEST_SEQUENCE.to_vec()),
+            &COMPLEX_TEST_RESULT.to_vec(),
+        );
+    }
+
+    #[test]
+    fn test_real_fourier_transform() {
+        for n in [2, 6, 16, 30] {
+            let transform = RealFourierTransform::new(n);
+            let x: Vec<f64> = sequence(n).iter().map(|z| z.re).collect();
+
+            let spectrum = transform.forward(&x);
+            let complex: Vec<Complex<f64>> = x.iter().map(|x| Complex::new(*x, 0.0)).collect();
+            assert_complex_vecs_almost_equal(&spectrum, &dft(&complex)[..=n / 2].to_vec());
+
+            for (y, x) in transform.inverse(&spectrum).iter().zip(&x) {
+                assert!((y - x).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "even number of points")]
+    fn test_real_fourier_transform_odd_length() {
+        let _ = RealFourierTransform::new(5);
+    }
+
+    #[test]
+    fn test_convolve() {
+        let a = [1.0, -2.0, 0.5, 3.0, 1.5];
+        let b = [2.0, 0.0, -1.0];
+
+        let convolution = convolve(&a, &b);
+        assert_eq!(convolution.len(), 7);
+
+        for (k, c) in convolution.iter().enumerate() {
+            let expected: f64 = (0..a.len())
+                .filter(|j| k >= *j && k - j < b.len())
+                .map(|j| a[j] * b[k - j])
+                .sum();
+            assert!((c - expected).abs() < 1e-12);
+        }
+
+        assert!(convolve(&[], &b).is_empty());
+    }
+
+    #[test]
+    fn test_fourier_grid() {
+        let grid = FourierGrid::with_spacing(8, 0.5, 1.0);
+        assert!((grid.frequency_spacing * grid.spacing - 2.0 * PI / 8.0).abs() < 1e-15);
+        assert_eq!(grid.points(), [-1.0, -0.5, 0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+        assert_eq!(grid, FourierGrid::new(8, grid.frequency_spacing, 1.0));
+
+        let values: Vec<f64> = grid.points().iter().map(|x| 2.0 * x).collect();
+        assert!((grid.interpolate(&values, 0.2).unwrap() - 0.4).abs() < 1e-15);
+        assert!((grid.interpolate(&values, 2.5).unwrap() - 5.0).abs() < 1e-15);
+        assert_eq!(grid.interpolate(&values, 2.6), None);
+        assert_eq!(grid.interpolate(&values, -1.1), None);
+    }
+
+    #[test]
+    fn test_conv_expectation() {
+        use statrs::distribution::{ContinuousCDF, Normal};
+
+        // European call in the Black-Scholes model, one step of CONV over
+        // the log-price.
+        let (s, k, r, sigma, t) = (100.0_f64, 110.0, 0.05, 0.2, 1.0);
+        let mean = (r - 0.5 * sigma * sigma) * t;
+        let variance = sigma * sigma * t;
+        let phi = |u: Complex<f64>| (Complex::<f64>::i() * u * mean - 0.5 * variance * u * u).exp();
+
+        let d_1 = ((s / k).ln() + (r + 0.5 * variance) * t) / variance.sqrt();
+        let n = |x: f64| Normal::new(0.0, 1.0).unwrap().cdf(x);
+        let call = s * n(d_1) - k * (-r * t).exp() * n(d_1 - variance.sqrt());
+
+        let grid = FourierGrid::with_spacing(1024, 0.01, s.ln());
+        let payoff: Vec<f64> = grid.strikes().iter().map(|s| (s - k).max(0.0)).collect();
+
+        for alpha in [0.0, -2.0] {
+            let values = conv_expectation(&grid, &payoff, phi, alpha);
+            let price = (-r * t).exp() * values[512];
+
+            assert!((price - call).abs() < 1e-3, "{price} {call}");
+        }
+    }
+
     #[test]
     #[should_panic(expected = "FFT can only handle vectors which length is a power of 2.")]
     fn test_invalid_vec_length() {
@@ -0,0 +1,551 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Globally adaptive quadrature, with error estimates.
+//!
+//! Every method implements [`Integrator`]: the interval is split into
+//! segments, each integrated by a rule with an error estimate, and the
+//! segment with the largest error is bisected until the total error is
+//! within the tolerance:
+//!
+//! - [`AdaptiveSimpson`]: Simpson's rule on the segment and its halves,
+//!   with Richardson extrapolation.
+//! - [`GaussKronrod`]: the 7 point Gauss rule and its 15 point Kronrod
+//!   extension (as in QUADPACK), for smooth integrands.
+//!
+//! Either bound may be infinite: the integral is then over a finite
+//! interval after a change of variables, e.g. $x = a + (1 - t) / t$ for
+//! $[a, +\infty)$.
+
+use std::cell::Cell;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Integration error type.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum IntegrationError {
+    /// A bound of the interval is NaN.
+    #[error("Invalid interval of integration")]
+    InvalidInterval,
+
+    /// The integrand is not finite at a point of the interval.
+    #[error("Non-finite value of the integrand")]
+    NonFinite,
+
+    /// The tolerance was not reached in the maximum number of segments.
+    /// The best estimate is attached.
+    #[error("No convergence in the maximum number of subdivisions")]
+    MaxSubdivisions(Integral),
+}
+
+/// Estimate of an integral.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Integral {
+    /// Value of the integral.
+    pub value: f64,
+    /// Estimate of the absolute error.
+    pub error: f64,
+    /// Number of evaluations of the integrand.
+    pub evaluations: usize,
+}
+
+/// Adaptive quadrature method.
+pub trait Integrator {
+    /// Integral of `f` over `[a, b]`, where either bound may be infinite
+    /// (and `b < a` reverses the sign).
+    ///
+    /// # Errors
+    ///
+    /// See [`IntegrationError`].
+    fn integrate<F>(&self, f: F, a: f64, b: f64) -> Result<Integral, IntegrationError>
+    where
+        F: Fn(f64) -> f64;
+}
+
+/// Adaptive Simpson quadrature.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let integral = AdaptiveSimpson::default()
+///     .integrate(|x| x.sin().exp(), 0.0, 5.0)
+///     .unwrap();
+///
+/// assert!((integral.value - 7.189_119_253_631_281).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveSimpson {
+    /// Absolute tolerance on the integral.
+    pub absolute_tolerance: f64,
+    /// Relative tolerance on the integral.
+    pub relative_tolerance: f64,
+    /// Maximum number of segments.
+    pub max_subdivisions: usize,
+}
+
+/// Adaptive Gauss-Kronrod (7-15 point) quadrature.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // The Gaussian integral.
+/// let integral = GaussKronrod::default()
+///     .integrate(|x| (-x * x).exp(), f64::NEG_INFINITY, f64::INFINITY)
+///     .unwrap();
+///
+/// assert!((integral.value - std::f64::consts::PI.sqrt()).abs() < 1e-12);
+/// assert!(integral.error < 1e-10 * integral.value);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaussKronrod {
+    /// Absolute tolerance on the integral.
+    pub absolute_tolerance: f64,
+    /// Relative tolerance on the integral.
+    pub relative_tolerance: f64,
+    /// Maximum number of segments.
+    pub max_subdivisions: usize,
+}
+
+// Change of variables to a finite interval.
+#[derive(Debug, Clone, Copy)]
+enum Domain {
+    // [a, b]: no change.
+    Finite,
+    // [a, +inf): x = a + (1 - t) / t, for t in (0, 1].
+    LowerBounded(f64),
+    // (-inf, b]: x = b - (1 - t) / t, for t in (0, 1].
+    UpperBounded(f64),
+    // (-inf, +inf): x = t / (1 - t^2), for t in (-1, 1).
+    Real,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+macro_rules! impl_integrator_new {
+    ($($method:ident),*) => {
+        $(
+            impl $method {
+                /// New integrator, converged when the error estimate is
+                /// within `absolute_tolerance` or `relative_tolerance`
+                /// times the integral, with at most `max_subdivisions`
+                /// segments.
+                #[must_use]
+                pub fn new(
+                    absolute_tolerance: f64,
+                    relative_tolerance: f64,
+                    max_subdivisions: usize,
+                ) -> Self {
+                    Self {
+                        absolute_tolerance,
+                        relative_tolerance,
+                        max_subdivisions,
+                    }
+                }
+            }
+
+            impl Default for $method {
+                /// Tolerances `1e-10`, and at most 1000 segments.
+                fn default() -> Self {
+                    Self::new(1e-10, 1e-10, 1000)
+                }
+            }
+        )*
+    };
+}
+
+impl_integrator_new!(AdaptiveSimpson, GaussKronrod);
+
+impl Integrator for AdaptiveSimpson {
+    fn integrate<F>(&self, f: F, a: f64, b: f64) -> Result<Integral, IntegrationError>
+    where
+        F: Fn(f64) -> f64,
+    {
+        adaptive(
+            &f,
+            a,
+            b,
+            (
+                self.absolute_tolerance,
+                self.relative_tolerance,
+                self.max_subdivisions,
+            ),
+            simpson,
+        )
+    }
+}
+
+impl Integrator for GaussKronrod {
+    fn integrate<F>(&self, f: F, a: f64, b: f64) -> Result<Integral, IntegrationError>
+    where
+        F: Fn(f64) -> f64,
+    {
+        adaptive(
+            &f,
+            a,
+            b,
+            (
+                self.absolute_tolerance,
+                self.relative_tolerance,
+                self.max_subdivisions,
+            ),
+            kronrod,
+        )
+    }
+}
+
+impl Domain {
+    // Domain of `[a, b]`, with `a <= b`, and the finite interval of `t`.
+    fn new(a: f64, b: f64) -> (Self, f64, f64) {
+        match (a.is_finite(), b.is_finite()) {
+            (true, true) => (Self::Finite, a, b),
+            (true, false) => (Self::LowerBounded(a), 0.0, 1.0),
+            (false, true) => (Self::UpperBounded(b), 0.0, 1.0),
+            (false, false) => (Self::Real, -1.0, 1.0),
+        }
+    }
+
+    // Integrand in `t`, with the Jacobian of the change of variables. The
+    // ends mapped to infinity (only evaluated by Simpson's rule) are zero:
+    // the integrand must decay there.
+    fn integrand(self, f: impl Fn(f64) -> f64) -> impl Fn(f64) -> f64 {
+        move |t| match self {
+            Self::Finite => f(t),
+            Self::LowerBounded(_) | Self::UpperBounded(_) if t == 0.0 => 0.0,
+            Self::LowerBounded(a) => f(a + (1.0 - t) / t) / (t * t),
+            Self::UpperBounded(b) => f(b - (1.0 - t) / t) / (t * t),
+            Self::Real => {
+                let s = 1.0 - t * t;
+
+                if s == 0.0 {
+                    0.0
+                } else {
+                    f(t / s) * (1.0 + t * t) / (s * s)
+                }
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Globally adaptive integration of `f` over `[a, b]`, with `rule` giving the
+// integral over a segment and its error estimate.
+fn adaptive<F, R>(
+    f: &F,
+    a: f64,
+    b: f64,
+    (absolute_tolerance, relative_tolerance, max_subdivisions): (f64, f64, usize),
+    rule: R,
+) -> Result<Integral, IntegrationError>
+where
+    F: Fn(f64) -> f64,
+    R: Fn(&dyn Fn(f64) -> f64, f64, f64) -> (f64, f64),
+{
+    if a.is_nan() || b.is_nan() {
+        return Err(IntegrationError::InvalidInterval);
+    }
+    if b < a {
+        return adaptive(
+            f,
+            b,
+            a,
+            (absolute_tolerance, relative_tolerance, max_subdivisions),
+            rule,
+        )
+        .map(|integral| Integral {
+            value: -integral.value,
+            ..integral
+        })
+        .map_err(|error| match error {
+            IntegrationError::MaxSubdivisions(integral) => {
+                IntegrationError::MaxSubdivisions(Integral {
+                    value: -integral.value,
+                    ..integral
+                })
+            }
+            error => error,
+        });
+    }
+
+    let evaluations = Cell::new(0);
+    let (domain, lower, upper) = Domain::new(a, b);
+    let integrand = domain.integrand(|x| {
+        evaluations.set(evaluations.get() + 1);
+        f(x)
+    });
+
+    // Segments (lower, upper, integral, error).
+    let mut segments = Vec::with_capacity(max_subdivisions.max(1));
+    let push = |segments: &mut Vec<_>, lower: f64, upper: f64| {
+        let (value, error): (f64, f64) = rule(&integrand, lower, upper);
+
+        if value.is_finite() && error.is_finite() {
+            segments.push((lower, upper, value, error));
+            Ok(())
+        } else {
+            Err(IntegrationError::NonFinite)
+        }
+    };
+    if lower < upper {
+        push(&mut segments, lower, upper)?;
+    }
+
+    loop {
+        let value = segments.iter().map(|s| s.2).sum::<f64>();
+        let error = segments.iter().map(|s| s.3).sum::<f64>();
+        let integral = Integral {
+            value,
+            error,
+            evaluations: evaluations.get(),
+        };
+
+        if error <= absolute_tolerance.max(relative_tolerance * value.abs()) {
+            return Ok(integral);
+        }
+        if segments.len() >= max_subdivisions {
+            return Err(IntegrationError::MaxSubdivisions(integral));
+        }
+
+        let worst = (0..segments.len())
+            .max_by(|i, j| segments[*i].3.total_cmp(&segments[*j].3))
+            .unwrap_or_default();
+        let (lower, upper, _, _) = segments.swap_remove(worst);
+        let middle = 0.5 * (lower + upper);
+
+        push(&mut segments, lower, middle)?;
+        push(&mut segments, middle, upper)?;
+    }
+}
+
+// Simpson's rule on [a, b] and on its halves, extrapolated, with the error
+// estimate of the halves.
+fn simpson(f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let h = b - a;
+    let values = [0.0, 0.25, 0.5, 0.75, 1.0].map(|s| f(a + s * h));
+
+    let whole = h / 6.0 * (values[0] + 4.0 * values[2] + values[4]);
+    let halves =
+        h / 12.0 * (values[0] + 4.0 * values[1] + 2.0 * values[2] + 4.0 * values[3] + values[4]);
+
+    (
+        halves + (halves - whole) / 15.0,
+        (halves - whole).abs() / 15.0,
+    )
+}
+
+// Kronrod 15 point rule on [a, b], with the error estimate of QUADPACK from
+// the embedded 7 point Gauss rule.
+fn kronrod(f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let (centre, half) = (0.5 * (a + b), 0.5 * (b - a));
+
+    let f_centre = f(centre);
+    let values: Vec<(f64, f64)> = KRONROD_NODES[..7]
+        .iter()
+        .map(|x| (f(centre - half * x), f(centre + half * x)))
+        .collect();
+
+    let kronrod = KRONROD_WEIGHTS[7] * f_centre
+        + values
+            .iter()
+            .zip(KRONROD_WEIGHTS)
+            .map(|((down, up), w)| w * (down + up))
+            .sum::<f64>();
+    let gauss = GAUSS_WEIGHTS[3] * f_centre
+        + values
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .zip(GAUSS_WEIGHTS)
+            .map(|((down, up), w)| w * (down + up))
+            .sum::<f64>();
+
+    // Integrals of |f| and of |f - mean|, which scale the error estimate.
+    let mean = 0.5 * kronrod;
+    let absolute = KRONROD_WEIGHTS[7] * f_centre.abs()
+        + values
+            .iter()
+            .zip(KRONROD_WEIGHTS)
+            .map(|((down, up), w)| w * (down.abs() + up.abs()))
+            .sum::<f64>();
+    let deviation = KRONROD_WEIGHTS[7] * (f_centre - mean).abs()
+        + values
+            .iter()
+            .zip(KRONROD_WEIGHTS)
+            .map(|((down, up), w)| w * ((down - mean).abs() + (up - mean).abs()))
+            .sum::<f64>();
+
+    let mut error = ((kronrod - gauss) * half).abs();
+    if deviation != 0.0 && error != 0.0 {
+        error = deviation
+            * half.abs()
+            * (200.0 * error / (deviation * half.abs()))
+                .powf(1.5)
+                .min(1.0);
+    }
+    if absolute * half.abs() > f64::MIN_POSITIVE / (50.0 * f64::EPSILON) {
+        error = error.max(50.0 * f64::EPSILON * absolute * half.abs());
+    }
+
+    (kronrod * half, error)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NODES & WEIGHTS
+// These are for the Gauss-Kronrod quadrature, from QUADPACK.
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Kronrod nodes in (0, 1], in decreasing order: the odd ones are the Gauss
+// nodes. The centre is the last node, 0.
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_5,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_48,
+    0.0,
+];
+
+// Kronrod weights, at the nodes.
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_224,
+    0.063_092_092_629_978_56,
+    0.104_790_010_322_250_19,
+    0.140_653_259_715_525_92,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_42,
+    0.204_432_940_075_298_89,
+    0.209_482_141_084_727_82,
+];
+
+// Gauss weights, at the odd Kronrod nodes.
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_64,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_adaptive_quadrature {
+    use super::*;
+    use crate::assert_approx_equal;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_kronrod_rule() {
+        // The Kronrod rule is exact for polynomials of degree up to 22, and
+        // the Gauss rule up to 13.
+        for degree in 0..23 {
+            let (value, _) = kronrod(&|x: f64| x.powi(degree), 0.0, 1.0);
+            assert_approx_equal!(value, 1.0 / f64::from(degree + 1), 1e-15);
+        }
+        assert_approx_equal!(
+            GAUSS_WEIGHTS[3] + 2.0 * GAUSS_WEIGHTS[..3].iter().sum::<f64>(),
+            2.0,
+            1e-15
+        );
+    }
+
+    #[test]
+    fn test_integrators() {
+        let f = |x: f64| x.sin().exp();
+        let exact = 7.189_119_253_631_281;
+
+        for integral in [
+            AdaptiveSimpson::default().integrate(f, 0.0, 5.0).unwrap(),
+            GaussKronrod::default().integrate(f, 0.0, 5.0).unwrap(),
+        ] {
+            assert_approx_equal!(integral.value, exact, 1e-10);
+            assert!(integral.error <= 1e-10 * exact);
+        }
+
+        // Reversed and empty intervals.
+        let integral = GaussKronrod::default().integrate(f, 5.0, 0.0).unwrap();
+        assert_approx_equal!(integral.value, -exact, 1e-10);
+        let integral = AdaptiveSimpson::default().integrate(f, 1.0, 1.0).unwrap();
+        assert_eq!((integral.value, integral.evaluations), (0.0, 0));
+    }
+
+    #[test]
+    fn test_singular_integrands() {
+        // Integrable singularities at an end point.
+        let integral = GaussKronrod::default()
+            .integrate(f64::ln, 0.0, 1.0)
+            .unwrap();
+        assert_approx_equal!(integral.value, -1.0, 1e-10);
+
+        let integral = GaussKronrod::default()
+            .integrate(|x| 1.0 / x.sqrt(), 0.0, 1.0)
+            .unwrap();
+        assert_approx_equal!(integral.value, 2.0, 1e-9);
+
+        // A kink, where Simpson's rule adapts.
+        let integral = AdaptiveSimpson::default()
+            .integrate(|x: f64| (x - 1.0 / 3.0).abs(), 0.0, 1.0)
+            .unwrap();
+        assert_approx_equal!(integral.value, 5.0 / 18.0, 1e-10);
+    }
+
+    #[test]
+    fn test_infinite_intervals() {
+        let gauss_kronrod = GaussKronrod::default();
+
+        // Integral of e^{-x} cos(x) over [0, inf): 1 / 2.
+        let integral = gauss_kronrod
+            .integrate(|x| (-x).exp() * x.cos(), 0.0, f64::INFINITY)
+            .unwrap();
+        assert_approx_equal!(integral.value, 0.5, 1e-10);
+
+        // Integral of 1 / (1 + x^2) over (-inf, 1]: 3 pi / 4.
+        let integral = gauss_kronrod
+            .integrate(|x| 1.0 / (1.0 + x * x), f64::NEG_INFINITY, 1.0)
+            .unwrap();
+        assert_approx_equal!(integral.value, 0.75 * PI, 1e-10);
+
+        let integral = AdaptiveSimpson::default()
+            .integrate(|x| (-x * x).exp(), f64::NEG_INFINITY, f64::INFINITY)
+            .unwrap();
+        assert_approx_equal!(integral.value, PI.sqrt(), 1e-9);
+    }
+
+    #[test]
+    fn test_integration_errors() {
+        let gauss_kronrod = GaussKronrod::default();
+
+        assert_eq!(
+            gauss_kronrod.integrate(f64::exp, f64::NAN, 1.0),
+            Err(IntegrationError::InvalidInterval)
+        );
+        assert_eq!(
+            gauss_kronrod.integrate(|x| 1.0 / x, -1.0, 1.0),
+            Err(IntegrationError::NonFinite)
+        );
+
+        // A tolerance too tight for the segments.
+        match GaussKronrod::new(0.0, 0.0, 10).integrate(|x| x.sin().abs(), 0.0, 10.0) {
+            Err(IntegrationError::MaxSubdivisions(integral)) => {
+                assert_approx_equal!(integral.value, 7.0 + 10_f64.cos(), 1e-4);
+                assert!(integral.error > 0.0);
+            }
+            result => panic!("{result:?}"),
+        }
+    }
+}
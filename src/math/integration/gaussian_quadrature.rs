@@ -0,0 +1,378 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Gaussian quadrature: the `n` point rule
+//!
+//! $$
+//! \int f(x) w(x) dx \approx \sum_{i=1}^n w_i f(x_i)
+//! $$
+//!
+//! exact for polynomials `f` of degree up to `2n - 1`, with the nodes `x_i`
+//! the roots of the degree `n` orthogonal polynomial of the weight function
+//! `w`:
+//!
+//! - [`GaussLegendre`]: $w(x) = 1$ on $[-1, 1]$, mapped to any finite
+//!   interval.
+//! - [`GaussHermite`]: $w(x) = e^{-x^2}$ on $\mathbb{R}$, e.g. for
+//!   expectations of functions of normal variables.
+//! - [`GaussLaguerre`]: $w(x) = x^\alpha e^{-x}$ on $[0, +\infty)$, e.g.
+//!   for Fourier pricing integrals.
+//!
+//! The nodes are the eigenvalues of the Jacobi matrix of the three term
+//! recurrence of the orthonormal polynomials (Golub and Welsch, 1969),
+//! refined by Newton's method on the recurrence, which also gives the
+//! weights.
+
+use nalgebra::DMatrix;
+use statrs::function::gamma::gamma;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Gauss-Legendre quadrature over a finite interval.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let rule = GaussLegendre::new(10);
+///
+/// let integral = rule.integrate(|x| x.sin().exp(), 0.0, 5.0);
+/// assert!((integral - 7.189_119_253_631_281).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussLegendre {
+    nodes: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+/// Gauss-Hermite quadrature over the real line, with the weight function
+/// $e^{-x^2}$.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let rule = GaussHermite::new(20);
+///
+/// // E[exp(X)] = exp(mu + sigma^2 / 2) for X ~ N(mu, sigma^2).
+/// let expectation = rule.normal_expectation(f64::exp, 0.1, 0.2);
+/// assert!((expectation - 0.12_f64.exp()).abs() < 1e-14);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussHermite {
+    nodes: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+/// Gauss-Laguerre quadrature over $[0, +\infty)$, with the weight function
+/// $x^\alpha e^{-x}$.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let rule = GaussLaguerre::new(32);
+///
+/// // Laplace transform of cos at 1: 1 / 2.
+/// assert!((rule.integrate(f64::cos) - 0.5).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussLaguerre {
+    nodes: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+macro_rules! impl_gauss_rule {
+    ($($rule:ident),*) => {
+        $(
+            impl $rule {
+                /// Nodes of the rule, in increasing order.
+                #[must_use]
+                pub fn nodes(&self) -> &[f64] {
+                    &self.nodes
+                }
+
+                /// Weights of the rule, at the nodes.
+                #[must_use]
+                pub fn weights(&self) -> &[f64] {
+                    &self.weights
+                }
+
+                // Sum of the weighted values of `f` at the nodes.
+                fn sum<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+                    self.nodes
+                        .iter()
+                        .zip(&self.weights)
+                        .map(|(x, w)| w * f(*x))
+                        .sum()
+                }
+            }
+        )*
+    };
+}
+
+impl_gauss_rule!(GaussLegendre, GaussHermite, GaussLaguerre);
+
+impl GaussLegendre {
+    /// New `n` point Gauss-Legendre rule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        let (nodes, weights) = gauss_rule(n, |_| 0.0, |k| k / (4.0 * k * k - 1.0).sqrt(), 2.0);
+
+        Self { nodes, weights }
+    }
+
+    /// Integral of `f` over `[a, b]`.
+    pub fn integrate<F: Fn(f64) -> f64>(&self, f: F, a: f64, b: f64) -> f64 {
+        let (c, d) = (0.5 * (b - a), 0.5 * (a + b));
+
+        c * self.sum(|t| f(c * t + d))
+    }
+}
+
+impl GaussHermite {
+    /// New `n` point Gauss-Hermite rule.
+    ///
+    /// The weights underflow for `n` beyond a few hundred points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        let sqrt_pi = std::f64::consts::PI.sqrt();
+        let (nodes, weights) = gauss_rule(n, |_| 0.0, |k| (0.5 * k).sqrt(), sqrt_pi);
+
+        Self { nodes, weights }
+    }
+
+    /// Integral of $f(x) e^{-x^2}$ over the real line.
+    pub fn integrate<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        self.sum(f)
+    }
+
+    /// Expectation of `f(X)`, for `X` normal with mean `mean` and standard
+    /// deviation `std_dev`.
+    pub fn normal_expectation<F: Fn(f64) -> f64>(&self, f: F, mean: f64, std_dev: f64) -> f64 {
+        let scale = std::f64::consts::SQRT_2 * std_dev;
+
+        self.sum(|x| f(mean + scale * x)) / std::f64::consts::PI.sqrt()
+    }
+}
+
+impl GaussLaguerre {
+    /// New `n` point Gauss-Laguerre rule, with the weight function $e^{-x}$.
+    ///
+    /// The weights underflow for `n` beyond about a hundred points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        Self::generalized(n, 0.0)
+    }
+
+    /// New `n` point generalized Gauss-Laguerre rule, with the weight
+    /// function $x^\alpha e^{-x}$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero, or `alpha <= -1`.
+    #[must_use]
+    pub fn generalized(n: usize, alpha: f64) -> Self {
+        assert!(alpha > -1.0, "alpha must be greater than -1");
+
+        let (nodes, weights) = gauss_rule(
+            n,
+            |k| 2.0 * k + alpha + 1.0,
+            |k| (k * (k + alpha)).sqrt(),
+            gamma(alpha + 1.0),
+        );
+
+        Self { nodes, weights }
+    }
+
+    /// Integral of $f(x) x^\alpha e^{-x}$ over $[0, +\infty)$.
+    pub fn integrate<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        self.sum(f)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Nodes and weights of the `n` point rule of the orthonormal polynomials of
+// the recurrence
+//
+//     x p_k(x) = b(k + 1) p_{k+1}(x) + a(k) p_k(x) + b(k) p_{k-1}(x),
+//
+// with `p_0 = 1 / sqrt(mu_0)` and `mu_0` the integral of the weight
+// function.
+fn gauss_rule<A, B>(n: usize, a: A, b: B, mu_0: f64) -> (Vec<f64>, Vec<f64>)
+where
+    A: Fn(f64) -> f64,
+    B: Fn(f64) -> f64,
+{
+    assert!(n > 0, "a Gaussian quadrature needs at least one node");
+
+    // b(0) multiplies p_{-1} = 0.
+    #[allow(clippy::cast_precision_loss)]
+    let (a, b) = (
+        |k: usize| a(k as f64),
+        |k: usize| if k == 0 { 0.0 } else { b(k as f64) },
+    );
+
+    let jacobi = DMatrix::from_fn(n, n, |i, j| match i.abs_diff(j) {
+        0 => a(i),
+        1 => b(i.max(j)),
+        _ => 0.0,
+    });
+    let mut nodes: Vec<f64> = jacobi.symmetric_eigenvalues().iter().copied().collect();
+    nodes.sort_by(f64::total_cmp);
+
+    // p_n(x) and its derivative, and the sum of the p_k(x)^2 for k < n.
+    let recurrence = |x: f64| {
+        let (mut p, mut p_previous) = (1.0 / mu_0.sqrt(), 0.0);
+        let (mut dp, mut dp_previous) = (0.0, 0.0);
+        let mut squares = 0.0;
+
+        for k in 0..n {
+            squares += p * p;
+            let next = ((x - a(k)) * p - b(k) * p_previous) / b(k + 1);
+            let d_next = (p + (x - a(k)) * dp - b(k) * dp_previous) / b(k + 1);
+            (p_previous, p) = (p, next);
+            (dp_previous, dp) = (dp, d_next);
+        }
+
+        (p, dp, squares)
+    };
+
+    let weights = nodes
+        .iter_mut()
+        .map(|x| {
+            for _ in 0..10 {
+                let (p, dp, _) = recurrence(*x);
+                let step = p / dp;
+                *x -= step;
+
+                if step.abs() <= 2.0 * f64::EPSILON * x.abs().max(1.0) {
+                    break;
+                }
+            }
+
+            // Christoffel numbers: w_i = 1 / sum_k p_k(x_i)^2.
+            1.0 / recurrence(*x).2
+        })
+        .collect();
+
+    (nodes, weights)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_gaussian_quadrature {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_gauss_legendre() {
+        // Nodes and weights of the 3 point rule.
+        let rule = GaussLegendre::new(3);
+        let x = 0.6_f64.sqrt();
+        for (node, expected) in rule.nodes().iter().zip([-x, 0.0, x]) {
+            assert_approx_equal!(*node, expected, 1e-15);
+        }
+        for (weight, expected) in rule.weights().iter().zip([5.0, 8.0, 5.0]) {
+            assert_approx_equal!(*weight, expected / 9.0, 1e-15);
+        }
+
+        // Exact for polynomials of degree up to 2n - 1.
+        let rule = GaussLegendre::new(8);
+        for degree in 0..16 {
+            let integral = rule.integrate(|x| x.powi(degree), 0.0, 2.0);
+            let exact = 2_f64.powi(degree + 1) / f64::from(degree + 1);
+            assert_approx_equal!(integral, exact, 1e-12 * exact);
+        }
+
+        let rule = GaussLegendre::new(40);
+        assert_approx_equal!(rule.weights().iter().sum::<f64>(), 2.0, 1e-13);
+        assert_approx_equal!(
+            rule.integrate(|x| x.sin().exp(), 0.0, 5.0),
+            7.189_119_253_631_281,
+            1e-13
+        );
+    }
+
+    #[test]
+    fn test_gauss_hermite() {
+        // Nodes and weights of the 2 point rule.
+        let rule = GaussHermite::new(2);
+        let sqrt_pi = std::f64::consts::PI.sqrt();
+        assert_approx_equal!(rule.nodes()[1], 0.5_f64.sqrt(), 1e-15);
+        assert_approx_equal!(rule.weights()[0], 0.5 * sqrt_pi, 1e-15);
+
+        // Moments of the standard normal distribution.
+        let rule = GaussHermite::new(12);
+        let moments = [1.0, 0.0, 1.0, 0.0, 3.0, 0.0, 15.0, 0.0, 105.0];
+        for (k, moment) in (0..).zip(moments) {
+            assert_approx_equal!(
+                rule.normal_expectation(|x| x.powi(k), 0.0, 1.0),
+                moment,
+                1e-11
+            );
+        }
+
+        // E[max(exp(X) - 1, 0)] for X ~ N(0, 0.04): the Black-Scholes call.
+        let rule = GaussHermite::new(100);
+        let call = rule.normal_expectation(|x| (x.exp() - 1.0).max(0.0), -0.02, 0.2);
+        assert_approx_equal!(call, 0.079_655_674_554_057_82, 1e-4);
+    }
+
+    #[test]
+    fn test_gauss_laguerre() {
+        // Nodes and weights of the 2 point rule.
+        let rule = GaussLaguerre::new(2);
+        let sqrt_2 = std::f64::consts::SQRT_2;
+        assert_approx_equal!(rule.nodes()[0], 2.0 - sqrt_2, 1e-14);
+        assert_approx_equal!(rule.weights()[0], (2.0 + sqrt_2) / 4.0, 1e-14);
+
+        // Integral of x^k e^{-x} is k!.
+        let rule = GaussLaguerre::new(10);
+        let mut factorial = 1.0;
+        for k in 0..20 {
+            assert_approx_equal!(rule.integrate(|x| x.powi(k)), factorial, 1e-12 * factorial);
+            factorial *= f64::from(k + 1);
+        }
+
+        // Integral of x^k x^{-1/2} e^{-x} is Gamma(k + 1/2).
+        let rule = GaussLaguerre::generalized(16, -0.5);
+        for k in 0..10 {
+            let exact = gamma(f64::from(k) + 0.5);
+            assert_approx_equal!(rule.integrate(|x| x.powi(k)), exact, 1e-12 * exact);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one node")]
+    fn test_empty_rule() {
+        let _ = GaussLegendre::new(0);
+    }
+}
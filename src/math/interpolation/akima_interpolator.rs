@@ -0,0 +1,171 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Module containing functionality for Akima interpolation.
+
+use crate::math::interpolation::{
+    impl_cubic_interpolator, secants, sorted_knots, validate_knots, CubicHermite, Extrapolation,
+    InterpolationError,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Akima (1970) interpolator: a cubic Hermite spline whose slope at each
+/// point weighs the secants either side by how much the secants vary on the
+/// other side. An outlier only moves the curve near it, and the curve
+/// wiggles less than a cubic spline.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let xs = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+/// let ys = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+///
+/// let mut akima = AkimaInterpolator::new(xs, ys).unwrap();
+/// akima.fit().unwrap();
+///
+/// // Flat where the points are.
+/// assert!(akima.interpolate(1.5).unwrap().abs() < 1e-12);
+/// assert!((akima.interpolate(4.5).unwrap() - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AkimaInterpolator {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    extrapolation: Extrapolation,
+    hermite: Option<CubicHermite>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl AkimaInterpolator {
+    /// Create a new Akima interpolator.
+    ///
+    /// # Errors
+    /// - `InterpolationError::UnequalLength` if ```xs.length() != ys.length()```.
+    ///
+    /// # Panics
+    /// Panics if NaN is in the index.
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>) -> Result<Self, InterpolationError> {
+        let (xs, ys) = sorted_knots(xs, ys)?;
+
+        Ok(Self {
+            xs,
+            ys,
+            extrapolation: Extrapolation::default(),
+            hermite: None,
+        })
+    }
+
+    fn slopes(&self) -> Vec<f64> {
+        akima_slopes(&self.xs, &self.ys)
+    }
+}
+
+impl_cubic_interpolator!(AkimaInterpolator);
+
+/// Akima slopes at sorted and distinct knots (at least two).
+pub(crate) fn akima_slopes(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    let (_, delta) = secants(xs, ys);
+
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    // Secants m_{-2}, ..., m_n: two more at each end, extrapolated
+    // linearly.
+    let mut m = Vec::with_capacity(n + 3);
+    m.push(3.0 * delta[0] - 2.0 * delta[1]);
+    m.push(2.0 * delta[0] - delta[1]);
+    m.extend_from_slice(&delta);
+    m.push(2.0 * delta[n - 2] - delta[n - 3]);
+    m.push(3.0 * delta[n - 2] - 2.0 * delta[n - 3]);
+
+    m.windows(4)
+        .map(|m| {
+            let (w_left, w_right) = ((m[3] - m[2]).abs(), (m[1] - m[0]).abs());
+
+            if w_left + w_right == 0.0 {
+                0.5 * (m[1] + m[2])
+            } else {
+                (w_left * m[1] + w_right * m[2]) / (w_left + w_right)
+            }
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_akima_interpolator {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::Interpolator;
+
+    #[test]
+    fn test_akima() {
+        let xs = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let ys = vec![1.0, 4.0, 9.0, 16.0, 25.0, 36.0];
+
+        let mut akima = AkimaInterpolator::new(xs.clone(), ys.clone()).unwrap();
+        akima.fit().unwrap();
+
+        for (x, y) in xs.iter().zip(&ys) {
+            assert_approx_equal!(akima.interpolate(*x).unwrap(), *y, 1e-12);
+        }
+
+        // Exact for a parabola at the interior points, where the secants
+        // vary evenly.
+        for x in [3.0, 4.0] {
+            assert_approx_equal!(akima.derivative(x).unwrap(), 2.0 * x, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_outlier_is_local() {
+        // An outlier at x = 5 leaves the curve below x = 3 untouched.
+        let xs: Vec<f64> = (0..10).map(f64::from).collect();
+        let mut ys = vec![0.0; 10];
+        let mut flat = AkimaInterpolator::new(xs.clone(), ys.clone()).unwrap();
+        ys[5] = 1.0;
+        let mut bumped = AkimaInterpolator::new(xs, ys).unwrap();
+        flat.fit().unwrap();
+        bumped.fit().unwrap();
+
+        for i in 0..=30 {
+            let x = 0.1 * f64::from(i);
+            assert_approx_equal!(
+                bumped.interpolate(x).unwrap(),
+                flat.interpolate(x).unwrap(),
+                1e-15
+            );
+        }
+        assert!(bumped.interpolate(4.5).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_two_points() {
+        let mut akima = AkimaInterpolator::new(vec![2.0, 0.0], vec![1.0, 0.0])
+            .unwrap()
+            .with_extrapolation(Extrapolation::Flat);
+        akima.fit().unwrap();
+
+        assert_approx_equal!(akima.interpolate(0.5).unwrap(), 0.25, 1e-15);
+        assert_approx_equal!(akima.derivative(0.5).unwrap(), 0.5, 1e-15);
+        assert_approx_equal!(akima.interpolate(-1.0).unwrap(), 0.0, 1e-15);
+        assert_approx_equal!(akima.interpolate(3.0).unwrap(), 1.0, 1e-15);
+    }
+}
@@ -0,0 +1,192 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Module containing functionality for bicubic spline interpolation on a
+//! grid.
+
+use crate::math::interpolation::{
+    locate, sorted_grid, spline_slopes, CubicHermite, Extrapolation, InterpolationError,
+    Interpolator2D, SplineBoundary,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bicubic spline interpolator of the values `z[i][j] = f(x[i], y[j])` on a
+/// grid: the tensor product of natural cubic splines, twice continuously
+/// differentiable in each variable.
+///
+/// Each row `x[i]` is interpolated by a cubic spline in `y`, and the values
+/// of the rows at `y` by a cubic spline in `x`. With
+/// [`Extrapolation::Linear`], the splines are extended linearly.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // f(x, y) = sin(x + y) on a grid of spacing 0.25.
+/// let xs: Vec<f64> = (0..=12).map(|i| 0.25 * f64::from(i)).collect();
+/// let ys: Vec<f64> = (0..=4).map(|j| 0.25 * f64::from(j)).collect();
+/// let zs = xs
+///     .iter()
+///     .map(|x| ys.iter().map(|y| (x + y).sin()).collect())
+///     .collect();
+///
+/// let interpolator = BicubicInterpolator::new(xs, ys, zs).unwrap();
+///
+/// assert!((interpolator.interpolate(1.6, 0.3).unwrap() - 1.9_f64.sin()).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BicubicInterpolator {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    rows: Vec<CubicHermite>,
+    extrapolation: Extrapolation,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BicubicInterpolator {
+    /// Create a new bicubic spline interpolator from the grid `xs` by `ys`
+    /// and the values `zs`, with `zs[i][j]` the value at `(xs[i], ys[j])`.
+    ///
+    /// # Errors
+    /// - `InterpolationError::UnequalLength` if `zs` is not `xs.len()` by
+    ///   `ys.len()`.
+    /// - `InterpolationError::NotEnoughPoints` if `xs` or `ys` has fewer than
+    ///   two points.
+    /// - `InterpolationError::DuplicateIndex` for a repeated `x` or `y`.
+    ///
+    /// # Panics
+    /// Panics if NaN is in the grid.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>, zs: Vec<Vec<f64>>) -> Result<Self, InterpolationError> {
+        let (xs, ys, zs) = sorted_grid(&xs, &ys, &zs)?;
+
+        let rows = zs
+            .into_iter()
+            .map(|row| {
+                let slopes = spline_slopes(&ys, &row, SplineBoundary::Natural);
+                CubicHermite::new(ys.clone(), row, slopes)
+            })
+            .collect();
+
+        Ok(Self {
+            xs,
+            ys,
+            rows,
+            extrapolation: Extrapolation::default(),
+        })
+    }
+}
+
+impl Interpolator2D for BicubicInterpolator {
+    fn interpolate(&self, x: f64, y: f64) -> Result<f64, InterpolationError> {
+        let x = locate(x, &self.xs, self.extrapolation)?;
+        let y = locate(y, &self.ys, self.extrapolation)?;
+
+        // The points are inside the grid, but for linear extrapolation.
+        let zs: Vec<f64> = self
+            .rows
+            .iter()
+            .map(|row| row.evaluate(y, Extrapolation::Linear).map(|z| z[0]))
+            .collect::<Result<_, _>>()?;
+        let slopes = spline_slopes(&self.xs, &zs, SplineBoundary::Natural);
+
+        Ok(CubicHermite::new(self.xs.clone(), zs, slopes).evaluate(x, Extrapolation::Linear)?[0])
+    }
+
+    fn set_extrapolation(&mut self, extrapolation: Extrapolation) {
+        self.extrapolation = extrapolation;
+    }
+
+    fn range(&self) -> ((f64, f64), (f64, f64)) {
+        (
+            (self.xs[0], self.xs[self.xs.len() - 1]),
+            (self.ys[0], self.ys[self.ys.len() - 1]),
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bicubic_interpolator {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::BilinearInterpolator;
+
+    #[test]
+    fn test_bicubic() {
+        let f = |x: f64, y: f64| (-x * x).exp() * (2.0 * y).cos();
+        let grid = |n: u32| -> Vec<f64> {
+            (0..=n)
+                .map(|i| -2.0 + 4.0 * f64::from(i) / f64::from(n))
+                .collect()
+        };
+        let (xs, ys) = (grid(16), grid(12));
+        let zs: Vec<Vec<f64>> = xs
+            .iter()
+            .map(|x| ys.iter().map(|y| f(*x, *y)).collect())
+            .collect();
+
+        let bicubic = BicubicInterpolator::new(xs.clone(), ys.clone(), zs.clone()).unwrap();
+        let bilinear = BilinearInterpolator::new(xs.clone(), ys.clone(), zs).unwrap();
+
+        // Through the grid points.
+        for x in &xs {
+            for y in &ys {
+                assert_approx_equal!(bicubic.interpolate(*x, *y).unwrap(), f(*x, *y), 1e-12);
+            }
+        }
+
+        // More accurate than bilinear between them.
+        let (mut cubic_error, mut linear_error) = (0.0_f64, 0.0_f64);
+        for (x, y) in [(-0.9, 0.4), (0.1, -1.3), (0.6, 0.7), (1.3, 1.1)] {
+            cubic_error = cubic_error.max((bicubic.interpolate(x, y).unwrap() - f(x, y)).abs());
+            linear_error = linear_error.max((bilinear.interpolate(x, y).unwrap() - f(x, y)).abs());
+        }
+        assert!(cubic_error < 2e-3, "{cubic_error}");
+        assert!(cubic_error < 0.1 * linear_error);
+
+        assert_eq!(
+            bicubic.interpolate(0.0, 2.5),
+            Err(InterpolationError::OutsideOfRange)
+        );
+        let bicubic = bicubic.with_extrapolation(Extrapolation::Flat);
+        assert_approx_equal!(
+            bicubic.interpolate(0.0, 2.5).unwrap(),
+            bicubic.interpolate(0.0, 2.0).unwrap(),
+            1e-15
+        );
+    }
+
+    #[test]
+    fn test_bicubic_linear_extrapolation() {
+        // Linear functions are reproduced, inside and outside the grid.
+        let f = |x: f64, y: f64| 0.5 + x - 2.0 * y;
+        let (xs, ys) = (vec![0.0, 1.0, 3.0], vec![0.0, 2.0]);
+        let zs = xs
+            .iter()
+            .map(|x| ys.iter().map(|y| f(*x, *y)).collect())
+            .collect();
+
+        let bicubic = BicubicInterpolator::new(xs, ys, zs)
+            .unwrap()
+            .with_extrapolation(Extrapolation::Linear);
+
+        for (x, y) in [(0.5, 1.0), (-1.0, 3.0), (4.0, -1.0)] {
+            assert_approx_equal!(bicubic.interpolate(x, y).unwrap(), f(x, y), 1e-12);
+        }
+    }
+}
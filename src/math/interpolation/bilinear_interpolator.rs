@@ -0,0 +1,240 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Module containing functionality for bilinear interpolation on a grid.
+
+use crate::math::interpolation::{
+    validate_knots, Extrapolation, InterpolationError, Interpolator2D,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bilinear interpolator of the values `z[i][j] = f(x[i], y[j])` on a grid:
+/// linear in each variable on each cell of the grid.
+///
+/// With [`Extrapolation::Linear`], the nearest cell is extended.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let interpolator = BilinearInterpolator::new(
+///     vec![0.0, 1.0],
+///     vec![0.0, 2.0],
+///     vec![vec![0.0, 2.0], vec![1.0, 5.0]],
+/// )
+/// .unwrap();
+///
+/// assert!((interpolator.interpolate(0.5, 1.0).unwrap() - 2.0).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BilinearInterpolator {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    zs: Vec<Vec<f64>>,
+    extrapolation: Extrapolation,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BilinearInterpolator {
+    /// Create a new bilinear interpolator from the grid `xs` by `ys` and
+    /// the values `zs`, with `zs[i][j]` the value at `(xs[i], ys[j])`.
+    ///
+    /// # Errors
+    /// - `InterpolationError::UnequalLength` if `zs` is not `xs.len()` by
+    ///   `ys.len()`.
+    /// - `InterpolationError::NotEnoughPoints` if `xs` or `ys` has fewer than
+    ///   two points.
+    /// - `InterpolationError::DuplicateIndex` for a repeated `x` or `y`.
+    ///
+    /// # Panics
+    /// Panics if NaN is in the grid.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>, zs: Vec<Vec<f64>>) -> Result<Self, InterpolationError> {
+        let (xs, ys, zs) = sorted_grid(&xs, &ys, &zs)?;
+
+        Ok(Self {
+            xs,
+            ys,
+            zs,
+            extrapolation: Extrapolation::default(),
+        })
+    }
+}
+
+impl Interpolator2D for BilinearInterpolator {
+    fn interpolate(&self, x: f64, y: f64) -> Result<f64, InterpolationError> {
+        let x = locate(x, &self.xs, self.extrapolation)?;
+        let y = locate(y, &self.ys, self.extrapolation)?;
+
+        let (i, tx) = cell(&self.xs, x);
+        let (j, ty) = cell(&self.ys, y);
+        let z = |i: usize, j: usize| self.zs[i][j];
+
+        Ok((1.0 - tx) * ((1.0 - ty) * z(i, j) + ty * z(i, j + 1))
+            + tx * ((1.0 - ty) * z(i + 1, j) + ty * z(i + 1, j + 1)))
+    }
+
+    fn set_extrapolation(&mut self, extrapolation: Extrapolation) {
+        self.extrapolation = extrapolation;
+    }
+
+    fn range(&self) -> ((f64, f64), (f64, f64)) {
+        (
+            (self.xs[0], self.xs[self.xs.len() - 1]),
+            (self.ys[0], self.ys[self.ys.len() - 1]),
+        )
+    }
+}
+
+/// Grid `xs` by `ys` and values `zs`, with `zs[i][j]` at `(xs[i], ys[j])`.
+pub(crate) type Grid = (Vec<f64>, Vec<f64>, Vec<Vec<f64>>);
+
+/// Grid sorted by `x` and `y`, with `zs` permuted accordingly.
+///
+/// # Errors
+/// See [`BilinearInterpolator::new`].
+///
+/// # Panics
+/// Panics if NaN is in the grid.
+pub(crate) fn sorted_grid(
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[Vec<f64>],
+) -> Result<Grid, InterpolationError> {
+    if zs.len() != xs.len() || zs.iter().any(|row| row.len() != ys.len()) {
+        return Err(InterpolationError::UnequalLength);
+    }
+
+    let order = |v: &[f64]| {
+        let mut order: Vec<usize> = (0..v.len()).collect();
+        order.sort_by(|a, b| v[*a].partial_cmp(&v[*b]).unwrap());
+        order
+    };
+    let (rows, columns) = (order(xs), order(ys));
+
+    let xs: Vec<f64> = rows.iter().map(|i| xs[*i]).collect();
+    let ys: Vec<f64> = columns.iter().map(|j| ys[*j]).collect();
+    validate_knots(&xs)?;
+    validate_knots(&ys)?;
+
+    let zs = rows
+        .iter()
+        .map(|i| columns.iter().map(|j| zs[*i][*j]).collect())
+        .collect();
+
+    Ok((xs, ys, zs))
+}
+
+/// Coordinate at which to evaluate the interpolant for `point`, given the
+/// sorted grid `knots`: clamped to the grid for flat extrapolation.
+///
+/// # Errors
+/// - `InterpolationError::OutsideOfRange` when `point` is outside the grid,
+///   and the extrapolation is [`Extrapolation::Error`].
+pub(crate) fn locate(
+    point: f64,
+    knots: &[f64],
+    extrapolation: Extrapolation,
+) -> Result<f64, InterpolationError> {
+    let (first, last) = (knots[0], knots[knots.len() - 1]);
+
+    if point >= first && point <= last {
+        return Ok(point);
+    }
+
+    match extrapolation {
+        Extrapolation::Error => Err(InterpolationError::OutsideOfRange),
+        Extrapolation::Flat => Ok(point.clamp(first, last)),
+        Extrapolation::Linear => Ok(point),
+    }
+}
+
+// Index of the cell of the grid containing `point` (the nearest cell
+// outside the grid), and the position of `point` in it.
+fn cell(knots: &[f64], point: f64) -> (usize, f64) {
+    let i = knots
+        .partition_point(|&x| x <= point)
+        .clamp(1, knots.len() - 1)
+        - 1;
+
+    (i, (point - knots[i]) / (knots[i + 1] - knots[i]))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bilinear_interpolator {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    // f(x, y) = 1 + 2x - y + 3xy, reproduced by bilinear interpolation.
+    fn f(x: f64, y: f64) -> f64 {
+        1.0 + 2.0 * x - y + 3.0 * x * y
+    }
+
+    #[test]
+    fn test_bilinear() {
+        // Unsorted grid.
+        let xs = vec![1.0, -1.0, 0.5];
+        let ys = vec![0.0, 2.0, 1.0, 4.0];
+        let zs = xs
+            .iter()
+            .map(|x| ys.iter().map(|y| f(*x, *y)).collect())
+            .collect();
+
+        let interpolator = BilinearInterpolator::new(xs, ys, zs).unwrap();
+        assert_eq!(interpolator.range(), ((-1.0, 1.0), (0.0, 4.0)));
+
+        for (x, y) in [(-1.0, 0.0), (0.2, 1.5), (0.75, 3.9), (1.0, 4.0)] {
+            assert_approx_equal!(interpolator.interpolate(x, y).unwrap(), f(x, y), 1e-12);
+        }
+
+        assert_eq!(
+            interpolator.interpolate(2.0, 1.0),
+            Err(InterpolationError::OutsideOfRange)
+        );
+
+        let interpolator = interpolator.with_extrapolation(Extrapolation::Flat);
+        assert_approx_equal!(
+            interpolator.interpolate(2.0, 5.0).unwrap(),
+            f(1.0, 4.0),
+            1e-12
+        );
+
+        let interpolator = interpolator.with_extrapolation(Extrapolation::Linear);
+        assert_approx_equal!(
+            interpolator.interpolate(2.0, 5.0).unwrap(),
+            f(2.0, 5.0),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_grid_errors() {
+        assert_eq!(
+            BilinearInterpolator::new(vec![0.0, 1.0], vec![0.0, 1.0], vec![vec![0.0; 2]]),
+            Err(InterpolationError::UnequalLength)
+        );
+        assert_eq!(
+            BilinearInterpolator::new(vec![0.0, 1.0], vec![0.0], vec![vec![0.0]; 2]),
+            Err(InterpolationError::NotEnoughPoints)
+        );
+        assert_eq!(
+            BilinearInterpolator::new(vec![0.0, 0.0], vec![0.0, 1.0], vec![vec![0.0; 2]; 2]),
+            Err(InterpolationError::DuplicateIndex)
+        );
+    }
+}
@@ -0,0 +1,260 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Piecewise cubic Hermite interpolation, given the slopes at the knots.
+//!
+//! The cubic interpolators only differ in how they choose the slopes: this
+//! module evaluates the interpolant and implements [`Interpolator`] for
+//! them.
+//!
+//! [`Interpolator`]: crate::math::interpolation::Interpolator
+
+use crate::math::interpolation::{Extrapolation, InterpolationError};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Cubic Hermite interpolant through sorted and distinct knots.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CubicHermite {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    slopes: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CubicHermite {
+    /// Interpolant with the given slopes at the knots (at least two).
+    pub(crate) fn new(xs: Vec<f64>, ys: Vec<f64>, slopes: Vec<f64>) -> Self {
+        debug_assert!(xs.len() >= 2 && xs.len() == ys.len() && xs.len() == slopes.len());
+
+        Self { xs, ys, slopes }
+    }
+
+    /// Value and first two derivatives of the cubic of the segment of `x`,
+    /// or of the nearest end segment outside the knots.
+    pub(crate) fn derivatives(&self, x: f64) -> [f64; 3] {
+        let (xs, ys, m) = (&self.xs, &self.ys, &self.slopes);
+        let n = xs.len();
+
+        let k = xs.partition_point(|&x_i| x_i <= x).clamp(1, n - 1) - 1;
+        let h = xs[k + 1] - xs[k];
+        let s = (x - xs[k]) / h;
+
+        let h00 = (1.0 + 2.0 * s) * (1.0 - s) * (1.0 - s);
+        let h10 = s * (1.0 - s) * (1.0 - s);
+        let h01 = s * s * (3.0 - 2.0 * s);
+        let h11 = s * s * (s - 1.0);
+
+        let delta = (ys[k + 1] - ys[k]) / h;
+
+        [
+            h00 * ys[k] + h10 * h * m[k] + h01 * ys[k + 1] + h11 * h * m[k + 1],
+            6.0 * s * (1.0 - s) * delta
+                + (1.0 - s) * (1.0 - 3.0 * s) * m[k]
+                + s * (3.0 * s - 2.0) * m[k + 1],
+            ((6.0 - 12.0 * s) * delta + (6.0 * s - 4.0) * m[k] + (6.0 * s - 2.0) * m[k + 1]) / h,
+        ]
+    }
+
+    /// Value and first two derivatives at `x`, extrapolated outside the
+    /// knots.
+    pub(crate) fn evaluate(
+        &self,
+        x: f64,
+        extrapolation: Extrapolation,
+    ) -> Result<[f64; 3], InterpolationError> {
+        let (first, last) = (self.xs[0], self.xs[self.xs.len() - 1]);
+
+        let edge = if x < first {
+            first
+        } else if x > last {
+            last
+        } else {
+            return Ok(self.derivatives(x));
+        };
+
+        match extrapolation {
+            Extrapolation::Error => Err(InterpolationError::OutsideOfRange),
+            Extrapolation::Flat => Ok([self.derivatives(edge)[0], 0.0, 0.0]),
+            Extrapolation::Linear => {
+                let [y, dy, _] = self.derivatives(edge);
+
+                Ok([y + dy * (x - edge), dy, 0.0])
+            }
+        }
+    }
+}
+
+/// Knots sorted by index.
+///
+/// # Errors
+/// - `InterpolationError::UnequalLength` when the length of `xs` != `ys`.
+///
+/// # Panics
+/// Panics if NaN is in the index.
+pub(crate) fn sorted_knots(
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+) -> Result<(Vec<f64>, Vec<f64>), InterpolationError> {
+    if xs.len() != ys.len() {
+        return Err(InterpolationError::UnequalLength);
+    }
+
+    let mut knots: Vec<_> = xs.into_iter().zip(ys).collect();
+    knots.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    Ok(knots.into_iter().unzip())
+}
+
+/// Checks that there are at least two knots, with distinct (sorted) indices.
+///
+/// # Errors
+/// - `InterpolationError::NotEnoughPoints` for fewer than two knots.
+/// - `InterpolationError::DuplicateIndex` for repeated indices.
+pub(crate) fn validate_knots(xs: &[f64]) -> Result<(), InterpolationError> {
+    if xs.len() < 2 {
+        return Err(InterpolationError::NotEnoughPoints);
+    }
+    if xs.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(InterpolationError::DuplicateIndex);
+    }
+
+    Ok(())
+}
+
+/// Widths `h` and slopes `delta` of the segments between the knots.
+pub(crate) fn secants(xs: &[f64], ys: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let h: Vec<f64> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+    let delta = ys
+        .windows(2)
+        .zip(&h)
+        .map(|(y, h)| (y[1] - y[0]) / h)
+        .collect();
+
+    (h, delta)
+}
+
+/// Implements [`Interpolator<f64, f64>`] for a cubic interpolator with
+/// fields `xs`, `ys`, `extrapolation` and `hermite: Option<CubicHermite>`,
+/// and a method `slopes(&self) -> Vec<f64>` giving the slopes at its knots.
+///
+/// [`Interpolator<f64, f64>`]: crate::math::interpolation::Interpolator
+macro_rules! impl_cubic_interpolator {
+    ($interpolator:ty) => {
+        impl $crate::math::interpolation::Interpolator<f64, f64> for $interpolator {
+            fn fit(&mut self) -> Result<(), InterpolationError> {
+                validate_knots(&self.xs)?;
+                self.hermite = Some(CubicHermite::new(
+                    self.xs.clone(),
+                    self.ys.clone(),
+                    self.slopes(),
+                ));
+
+                Ok(())
+            }
+
+            fn interpolate(&self, point: f64) -> Result<f64, InterpolationError> {
+                let hermite = self.hermite.as_ref().ok_or(InterpolationError::Unfitted)?;
+
+                Ok(hermite.evaluate(point, self.extrapolation)?[0])
+            }
+
+            fn derivative(&self, point: f64) -> Result<f64, InterpolationError> {
+                let hermite = self.hermite.as_ref().ok_or(InterpolationError::Unfitted)?;
+
+                Ok(hermite.evaluate(point, self.extrapolation)?[1])
+            }
+
+            fn set_extrapolation(&mut self, extrapolation: Extrapolation) {
+                self.extrapolation = extrapolation;
+            }
+
+            fn range(&self) -> (f64, f64) {
+                (*self.xs.first().unwrap(), *self.xs.last().unwrap())
+            }
+
+            // The slopes depend on all the knots: the interpolator needs
+            // fitting again.
+            fn add_point(&mut self, point: (f64, f64)) {
+                let idx = self.xs.partition_point(|&x| x < point.0);
+                self.xs.insert(idx, point.0);
+                self.ys.insert(idx, point.1);
+                self.hermite = None;
+            }
+        }
+    };
+}
+
+pub(crate) use impl_cubic_interpolator;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cubic_hermite {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_cubic_hermite_derivatives() {
+        // f(x) = x^3 is reproduced from its values and slopes.
+        let xs = vec![-1.0, 0.5, 2.0];
+        let ys = xs.iter().map(|x: &f64| x.powi(3)).collect();
+        let slopes = xs.iter().map(|x| 3.0 * x * x).collect();
+        let hermite = CubicHermite::new(xs, ys, slopes);
+
+        for x in [-1.0, -0.3, 0.5, 1.2, 2.0] {
+            let [y, dy, d2y] = hermite.derivatives(x);
+
+            assert_approx_equal!(y, x * x * x, 1e-12);
+            assert_approx_equal!(dy, 3.0 * x * x, 1e-12);
+            assert_approx_equal!(d2y, 6.0 * x, 1e-12);
+        }
+
+        assert_eq!(
+            hermite.evaluate(2.5, Extrapolation::Error),
+            Err(InterpolationError::OutsideOfRange)
+        );
+        assert_eq!(
+            hermite.evaluate(2.5, Extrapolation::Flat),
+            Ok([8.0, 0.0, 0.0])
+        );
+
+        let [y, dy, d2y] = hermite.evaluate(-2.0, Extrapolation::Linear).unwrap();
+        assert_approx_equal!(y, -4.0, 1e-12);
+        assert_approx_equal!(dy, 3.0, 1e-12);
+        assert_approx_equal!(d2y, 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_knots() {
+        assert_eq!(
+            sorted_knots(vec![2.0, 1.0], vec![4.0, 3.0]),
+            Ok((vec![1.0, 2.0], vec![3.0, 4.0]))
+        );
+        assert_eq!(
+            sorted_knots(vec![1.0], vec![]),
+            Err(InterpolationError::UnequalLength)
+        );
+        assert_eq!(
+            validate_knots(&[1.0]),
+            Err(InterpolationError::NotEnoughPoints)
+        );
+        assert_eq!(
+            validate_knots(&[1.0, 2.0, 2.0]),
+            Err(InterpolationError::DuplicateIndex)
+        );
+    }
+}
@@ -0,0 +1,224 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Module containing functionality for cubic spline interpolation.
+
+use crate::math::interpolation::{
+    impl_cubic_interpolator, secants, sorted_knots, validate_knots, CubicHermite, Extrapolation,
+    InterpolationError,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Cubic spline interpolator: the twice continuously differentiable
+/// piecewise cubic through the points.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let xs = vec![0.0, 1.0, 2.0, 3.0];
+/// let ys = vec![0.0, 1.0, 0.0, 1.0];
+///
+/// let mut spline = CubicSplineInterpolator::new(xs, ys).unwrap();
+/// spline.fit().unwrap();
+///
+/// assert!((spline.interpolate(1.0).unwrap() - 1.0).abs() < 1e-12);
+/// assert!(spline.interpolate(1.5).unwrap() < 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubicSplineInterpolator {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    boundary: SplineBoundary,
+    extrapolation: Extrapolation,
+    hermite: Option<CubicHermite>,
+}
+
+/// End conditions of a cubic spline.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SplineBoundary {
+    /// Zero second derivative at both ends (the default).
+    #[default]
+    Natural,
+    /// Given first derivatives at the first and last points.
+    Clamped(f64, f64),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CubicSplineInterpolator {
+    /// Create a new natural cubic spline interpolator.
+    ///
+    /// # Errors
+    /// - `InterpolationError::UnequalLength` if ```xs.length() != ys.length()```.
+    ///
+    /// # Panics
+    /// Panics if NaN is in the index.
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>) -> Result<Self, InterpolationError> {
+        let (xs, ys) = sorted_knots(xs, ys)?;
+
+        Ok(Self {
+            xs,
+            ys,
+            boundary: SplineBoundary::default(),
+            extrapolation: Extrapolation::default(),
+            hermite: None,
+        })
+    }
+
+    /// The interpolator, with the given end conditions.
+    #[must_use]
+    pub fn with_boundary(mut self, boundary: SplineBoundary) -> Self {
+        self.boundary = boundary;
+        self.hermite = None;
+        self
+    }
+
+    fn slopes(&self) -> Vec<f64> {
+        spline_slopes(&self.xs, &self.ys, self.boundary)
+    }
+}
+
+impl_cubic_interpolator!(CubicSplineInterpolator);
+
+/// Slopes of the cubic spline at sorted and distinct knots (at least two).
+///
+/// Continuity of the second derivative at the interior knots, and the end
+/// conditions, give a tridiagonal system for the slopes.
+pub(crate) fn spline_slopes(xs: &[f64], ys: &[f64], boundary: SplineBoundary) -> Vec<f64> {
+    let n = xs.len();
+    let (h, delta) = secants(xs, ys);
+
+    // Sub-diagonal, diagonal, super-diagonal and right-hand side.
+    let (mut a, mut b, mut c, mut d) = (vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+
+    for i in 1..n - 1 {
+        a[i] = h[i];
+        b[i] = 2.0 * (h[i - 1] + h[i]);
+        c[i] = h[i - 1];
+        d[i] = 3.0 * (h[i] * delta[i - 1] + h[i - 1] * delta[i]);
+    }
+
+    match boundary {
+        SplineBoundary::Natural => {
+            (b[0], c[0], d[0]) = (2.0, 1.0, 3.0 * delta[0]);
+            (a[n - 1], b[n - 1], d[n - 1]) = (1.0, 2.0, 3.0 * delta[n - 2]);
+        }
+        SplineBoundary::Clamped(first, last) => {
+            (b[0], d[0]) = (1.0, first);
+            (b[n - 1], d[n - 1]) = (1.0, last);
+        }
+    }
+
+    // Thomas algorithm.
+    for i in 1..n {
+        let w = a[i] / b[i - 1];
+        b[i] -= w * c[i - 1];
+        d[i] -= w * d[i - 1];
+    }
+
+    let mut slopes = vec![0.0; n];
+    slopes[n - 1] = d[n - 1] / b[n - 1];
+    for i in (0..n - 1).rev() {
+        slopes[i] = (d[i] - c[i] * slopes[i + 1]) / b[i];
+    }
+
+    slopes
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cubic_spline_interpolator {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::Interpolator;
+
+    #[test]
+    fn test_natural_spline() {
+        let xs = vec![0.0, 1.0, 2.5, 3.0, 4.0];
+        let ys: Vec<f64> = xs.iter().map(|x: &f64| x.sin()).collect();
+
+        let mut spline = CubicSplineInterpolator::new(xs.clone(), ys.clone()).unwrap();
+        assert_eq!(spline.interpolate(1.0), Err(InterpolationError::Unfitted));
+        spline.fit().unwrap();
+
+        for (x, y) in xs.iter().zip(&ys) {
+            assert_approx_equal!(spline.interpolate(*x).unwrap(), *y, 1e-12);
+        }
+
+        // Continuous second derivative at the interior knots, and zero at
+        // the ends.
+        let hermite = spline.hermite.as_ref().unwrap();
+        for x in &xs[1..4] {
+            assert_approx_equal!(
+                hermite.derivatives(x - 1e-9)[2],
+                hermite.derivatives(x + 1e-9)[2],
+                1e-6
+            );
+        }
+        assert_approx_equal!(hermite.derivatives(0.0)[2], 0.0, 1e-12);
+        assert_approx_equal!(hermite.derivatives(4.0)[2], 0.0, 1e-12);
+
+        assert_eq!(
+            spline.interpolate(4.5),
+            Err(InterpolationError::OutsideOfRange)
+        );
+    }
+
+    #[test]
+    fn test_clamped_spline() {
+        // A clamped spline reproduces cubics.
+        let cubic = |x: f64| x * x * x - 2.0 * x;
+        let xs = vec![-1.0, 0.0, 0.5, 2.0];
+        let ys = xs.iter().map(|x| cubic(*x)).collect();
+
+        let mut spline = CubicSplineInterpolator::new(xs, ys)
+            .unwrap()
+            .with_boundary(SplineBoundary::Clamped(1.0, 10.0))
+            .with_extrapolation(Extrapolation::Linear);
+        spline.fit().unwrap();
+
+        for x in [-1.0, -0.4, 0.3, 1.1, 2.0] {
+            assert_approx_equal!(spline.interpolate(x).unwrap(), cubic(x), 1e-12);
+            assert_approx_equal!(spline.derivative(x).unwrap(), 3.0 * x * x - 2.0, 1e-12);
+        }
+
+        assert_approx_equal!(spline.interpolate(3.0).unwrap(), 14.0, 1e-12);
+        assert_approx_equal!(spline.derivative(3.0).unwrap(), 10.0, 1e-12);
+    }
+
+    #[test]
+    fn test_add_point() {
+        let mut spline = CubicSplineInterpolator::new(vec![0.0, 2.0], vec![0.0, 2.0]).unwrap();
+        spline.fit().unwrap();
+        assert_approx_equal!(spline.interpolate(1.0).unwrap(), 1.0, 1e-15);
+
+        spline.add_point((1.0, 0.0));
+        assert_eq!(spline.interpolate(1.0), Err(InterpolationError::Unfitted));
+        spline.fit().unwrap();
+        assert_approx_equal!(spline.interpolate(1.0).unwrap(), 0.0, 1e-15);
+        assert_eq!(spline.range(), (0.0, 2.0));
+
+        spline.add_point((1.0, 1.0));
+        assert_eq!(spline.fit(), Err(InterpolationError::DuplicateIndex));
+        assert_eq!(
+            CubicSplineInterpolator::new(vec![1.0], vec![1.0])
+                .unwrap()
+                .fit(),
+            Err(InterpolationError::NotEnoughPoints)
+        );
+    }
+}
@@ -48,14 +48,45 @@ where
     ///
     /// # Errors
     /// - `InterpolationError::UnequalLength` when the length of `xs` != `ys`.
+    /// - `InterpolationError::NotEnoughPoints` when there are too few points
+    ///   for the method.
+    /// - `InterpolationError::DuplicateIndex` when two points have the same
+    ///   index.
     fn fit(&mut self) -> Result<(), InterpolationError>;
 
     /// Interpolate at value `point`.
     ///
     /// # Errors
     /// - `InterpolationError::Unfitted` when the interpolator has not been fitted.
+    /// - `InterpolationError::OutsideOfRange` when `point` is outside the
+    ///   range, and the extrapolation is [`Extrapolation::Error`].
     fn interpolate(&self, point: IndexType) -> Result<ValueType, InterpolationError>;
 
+    /// Derivative of the interpolant at value `point` (the right derivative
+    /// at a knot where the interpolant has a kink).
+    ///
+    /// # Errors
+    /// - `InterpolationError::Unfitted` when the interpolator has not been fitted.
+    /// - `InterpolationError::OutsideOfRange` when `point` is outside the
+    ///   range, and the extrapolation is [`Extrapolation::Error`].
+    fn derivative(&self, point: IndexType) -> Result<ValueType, InterpolationError>
+    where
+        IndexType: InterpolationIndex<Delta = ValueType>;
+
+    /// Set the extrapolation outside the range of interpolation.
+    fn set_extrapolation(&mut self, extrapolation: Extrapolation);
+
+    /// The interpolator, with the extrapolation outside the range of
+    /// interpolation.
+    #[must_use]
+    fn with_extrapolation(mut self, extrapolation: Extrapolation) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_extrapolation(extrapolation);
+        self
+    }
+
     /// Return range of interpolation.
     fn range(&self) -> (IndexType, IndexType);
 
@@ -63,6 +94,48 @@ where
     fn add_point(&mut self, point: (IndexType, ValueType));
 }
 
+/// Interpolator of a function of two variables, `z = f(x, y)`.
+/// This trait is implemented by the two dimensional interpolation models,
+/// which are fitted when they are built.
+pub trait Interpolator2D {
+    /// Interpolate at `(x, y)`.
+    ///
+    /// # Errors
+    /// - `InterpolationError::OutsideOfRange` when `(x, y)` is outside the
+    ///   range, and the extrapolation is [`Extrapolation::Error`].
+    fn interpolate(&self, x: f64, y: f64) -> Result<f64, InterpolationError>;
+
+    /// Set the extrapolation outside the range of interpolation.
+    fn set_extrapolation(&mut self, extrapolation: Extrapolation);
+
+    /// The interpolator, with the extrapolation outside the range of
+    /// interpolation.
+    #[must_use]
+    fn with_extrapolation(mut self, extrapolation: Extrapolation) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_extrapolation(extrapolation);
+        self
+    }
+
+    /// Return range of interpolation: the ranges of `x` and of `y`.
+    fn range(&self) -> ((f64, f64), (f64, f64));
+}
+
+/// Extrapolation of an interpolator outside its range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Extrapolation {
+    /// No extrapolation: `InterpolationError::OutsideOfRange` (the default).
+    #[default]
+    Error,
+    /// The value at the nearest end of the range.
+    Flat,
+    /// Linear, with the value and the derivative at the nearest end of the
+    /// range.
+    Linear,
+}
+
 /// Error for `interpolator`s.
 #[derive(Debug, PartialEq)]
 pub enum InterpolationError {
@@ -72,6 +145,10 @@ pub enum InterpolationError {
     Unfitted,
     /// Outside of interpolation range.
     OutsideOfRange,
+    /// Fewer points than the interpolation method needs.
+    NotEnoughPoints,
+    /// Two points have the same index.
+    DuplicateIndex,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -10,7 +10,7 @@
 //! Module containing functionality for linear interpolation.
 
 use crate::math::interpolation::{
-    InterpolationError, InterpolationIndex, InterpolationValue, Interpolator,
+    Extrapolation, InterpolationError, InterpolationIndex, InterpolationValue, Interpolator,
 };
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -26,6 +26,7 @@ where
     xs: Vec<IndexType>,
     ys: Vec<ValueType>,
     fitted: bool,
+    extrapolation: Extrapolation,
 }
 
 // // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -60,8 +61,18 @@ where
             xs,
             ys,
             fitted: false,
+            extrapolation: Extrapolation::default(),
         })
     }
+
+    // Indices of the ends of the segment used at `point`: the segment
+    // containing it, or the nearest end segment outside the range.
+    fn segment(&self, point: IndexType) -> (usize, usize) {
+        let n = self.xs.len();
+        let idx_r = self.xs.partition_point(|&x| x <= point).clamp(1, n - 1);
+
+        (idx_r - 1, idx_r)
+    }
 }
 
 impl<IndexType, ValueType> Interpolator<IndexType, ValueType>
@@ -78,10 +89,26 @@ where
     fn interpolate(&self, point: IndexType) -> Result<ValueType, InterpolationError>
 where {
         let range = self.range();
-        if point.partial_cmp(&range.0).unwrap() == std::cmp::Ordering::Less
-            || point.partial_cmp(&range.1).unwrap() == std::cmp::Ordering::Greater
-        {
-            return Err(InterpolationError::OutsideOfRange);
+        let below = point.partial_cmp(&range.0).unwrap() == std::cmp::Ordering::Less;
+        if below || point.partial_cmp(&range.1).unwrap() == std::cmp::Ordering::Greater {
+            match self.extrapolation {
+                Extrapolation::Error => return Err(InterpolationError::OutsideOfRange),
+                Extrapolation::Flat => {
+                    return Ok(if below {
+                        self.ys[0]
+                    } else {
+                        self.ys[self.ys.len() - 1]
+                    })
+                }
+                Extrapolation::Linear if self.xs.len() > 1 => {
+                    let (idx_l, idx_r) = self.segment(point);
+
+                    return Ok(self.ys[idx_l]
+                        + (self.ys[idx_r] - self.ys[idx_l])
+                            * ((point - self.xs[idx_l]) / (self.xs[idx_r] - self.xs[idx_l])));
+                }
+                Extrapolation::Linear => return Ok(self.ys[0]),
+            }
         }
         if let Ok(idx) = self
             .xs
@@ -97,6 +124,30 @@ where {
                 * ((point - self.xs[idx_l]) / (self.xs[idx_r] - self.xs[idx_l])))
     }
 
+    fn derivative(&self, point: IndexType) -> Result<ValueType, InterpolationError>
+    where
+        IndexType: InterpolationIndex<Delta = ValueType>,
+    {
+        let range = self.range();
+        let outside = point.partial_cmp(&range.0).unwrap() == std::cmp::Ordering::Less
+            || point.partial_cmp(&range.1).unwrap() == std::cmp::Ordering::Greater;
+
+        match self.extrapolation {
+            Extrapolation::Error if outside => Err(InterpolationError::OutsideOfRange),
+            Extrapolation::Flat if outside => Ok(ValueType::zero()),
+            _ if self.xs.len() < 2 => Ok(ValueType::zero()),
+            _ => {
+                let (idx_l, idx_r) = self.segment(point);
+
+                Ok((self.ys[idx_r] - self.ys[idx_l]) / (self.xs[idx_r] - self.xs[idx_l]))
+            }
+        }
+    }
+
+    fn set_extrapolation(&mut self, extrapolation: Extrapolation) {
+        self.extrapolation = extrapolation;
+    }
+
     fn range(&self) -> (IndexType, IndexType) {
         (*self.xs.first().unwrap(), *self.xs.last().unwrap())
     }
@@ -141,4 +192,27 @@ mod tests {
 
         assert!(InterpolationError::OutsideOfRange == interpolator.interpolate(6.).err().unwrap());
     }
+
+    #[test]
+    fn test_linear_extrapolation_and_derivative() {
+        let xs = vec![1., 2., 4.];
+        let ys = vec![1., 3., 4.];
+
+        let mut interpolator = LinearInterpolator::new(xs, ys).unwrap();
+        let _ = interpolator.fit();
+
+        assert_approx_equal!(2.0, interpolator.derivative(1.5).unwrap(), EPS);
+        assert_approx_equal!(0.5, interpolator.derivative(2.0).unwrap(), EPS);
+        assert!(interpolator.derivative(0.0).is_err());
+
+        interpolator.set_extrapolation(Extrapolation::Flat);
+        assert_approx_equal!(1.0, interpolator.interpolate(0.0).unwrap(), EPS);
+        assert_approx_equal!(4.0, interpolator.interpolate(6.0).unwrap(), EPS);
+        assert_approx_equal!(0.0, interpolator.derivative(6.0).unwrap(), EPS);
+
+        let interpolator = interpolator.with_extrapolation(Extrapolation::Linear);
+        assert_approx_equal!(-1.0, interpolator.interpolate(0.0).unwrap(), EPS);
+        assert_approx_equal!(5.0, interpolator.interpolate(6.0).unwrap(), EPS);
+        assert_approx_equal!(0.5, interpolator.derivative(6.0).unwrap(), EPS);
+    }
 }
@@ -11,6 +11,11 @@
 //! This module will be used to construct term structures
 //! like yield curves and volatility surfaces from market data.
 //!
+//! Every interpolator implements [`Interpolator`]: it is built from the
+//! points, fitted, then evaluated (value and derivative), with an
+//! [`Extrapolation`] outside the range of the points. The two dimensional
+//! interpolators implement [`Interpolator2D`].
+//!
 //! "Interpolatable"
 //! - ADJECTIVE:
 //!     - *Able to be interpolated, or suited to interpolation.*
@@ -19,9 +24,38 @@
 pub mod interpolator;
 pub use interpolator::*;
 
+/// Bicubic spline interpolation on a grid.
+pub mod bicubic_interpolator;
+pub use bicubic_interpolator::*;
+
+/// Bilinear interpolation on a grid.
+pub mod bilinear_interpolator;
+pub use bilinear_interpolator::*;
+
+/// Cubic Hermite interpolation, shared by the cubic interpolators.
+pub(crate) mod cubic_hermite;
+pub(crate) use cubic_hermite::*;
+
+/// Akima interpolation.
+pub mod akima_interpolator;
+pub use akima_interpolator::*;
+
+/// Cubic spline interpolation.
+pub mod cubic_spline_interpolator;
+pub use cubic_spline_interpolator::*;
+
+/// Linear interpolation.
 pub mod linear_interpolator;
 pub use linear_interpolator::*;
 
+/// Monotone cubic interpolation (Fritsch-Butland, Steffen and Hyman).
+pub mod monotone_interpolator;
+pub use monotone_interpolator::*;
+
+/// Thin plate spline interpolation of scattered points.
+pub mod thin_plate_spline;
+pub use thin_plate_spline::*;
+
 // // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // // STRUCTS, ENUMS, AND TRAITS
 // // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -0,0 +1,262 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Module containing functionality for monotone cubic interpolation.
+//!
+//! The interpolant is a cubic Hermite spline whose slopes are limited so
+//! that it is monotone wherever the points are: it does not overshoot, and
+//! has no extrema other than at the points.
+
+use crate::math::interpolation::{
+    impl_cubic_interpolator, secants, sorted_knots, spline_slopes, validate_knots, CubicHermite,
+    Extrapolation, InterpolationError, SplineBoundary,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Monotone cubic interpolator.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // A step: a cubic spline would overshoot it.
+/// let xs = vec![0.0, 1.0, 2.0, 3.0, 4.0];
+/// let ys = vec![0.0, 0.0, 1.0, 1.0, 1.0];
+///
+/// let mut interpolator = MonotoneCubicInterpolator::new(xs, ys).unwrap();
+/// interpolator.fit().unwrap();
+///
+/// for i in 0..=40 {
+///     let y = interpolator.interpolate(0.1 * f64::from(i)).unwrap();
+///     assert!((0.0..=1.0).contains(&y));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonotoneCubicInterpolator {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    method: MonotoneMethod,
+    extrapolation: Extrapolation,
+    hermite: Option<CubicHermite>,
+}
+
+/// Choice of the slopes of a [`MonotoneCubicInterpolator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MonotoneMethod {
+    /// Weighted harmonic mean of the adjacent secants (Fritsch and Butland,
+    /// 1984).
+    FritschButland,
+    /// Steffen (1990): the slope of the parabola through the point and its
+    /// neighbours, limited by the adjacent secants (the default).
+    #[default]
+    Steffen,
+    /// Natural cubic spline slopes, filtered to keep monotonicity (Hyman,
+    /// 1983): the smoothest of the three where the points allow it.
+    Hyman,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MonotoneCubicInterpolator {
+    /// Create a new monotone cubic interpolator, with Steffen's slopes.
+    ///
+    /// # Errors
+    /// - `InterpolationError::UnequalLength` if ```xs.length() != ys.length()```.
+    ///
+    /// # Panics
+    /// Panics if NaN is in the index.
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>) -> Result<Self, InterpolationError> {
+        let (xs, ys) = sorted_knots(xs, ys)?;
+
+        Ok(Self {
+            xs,
+            ys,
+            method: MonotoneMethod::default(),
+            extrapolation: Extrapolation::default(),
+            hermite: None,
+        })
+    }
+
+    /// The interpolator, with the given choice of slopes.
+    #[must_use]
+    pub fn with_method(mut self, method: MonotoneMethod) -> Self {
+        self.method = method;
+        self.hermite = None;
+        self
+    }
+
+    fn slopes(&self) -> Vec<f64> {
+        monotone_slopes(&self.xs, &self.ys, self.method)
+    }
+}
+
+impl_cubic_interpolator!(MonotoneCubicInterpolator);
+
+/// Monotone slopes at sorted and distinct knots (at least two).
+pub(crate) fn monotone_slopes(xs: &[f64], ys: &[f64], method: MonotoneMethod) -> Vec<f64> {
+    let n = xs.len();
+    let (h, delta) = secants(xs, ys);
+
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    match method {
+        MonotoneMethod::FritschButland => (0..n)
+            .map(|i| {
+                if i == 0 {
+                    return delta[0];
+                }
+                if i == n - 1 {
+                    return delta[n - 2];
+                }
+                if delta[i - 1] * delta[i] <= 0.0 {
+                    return 0.0;
+                }
+                let (w1, w2) = (2.0 * h[i] + h[i - 1], h[i] + 2.0 * h[i - 1]);
+                (w1 + w2) / (w1 / delta[i - 1] + w2 / delta[i])
+            })
+            .collect(),
+        MonotoneMethod::Steffen => {
+            // One-sided parabolas at the ends.
+            let end = |h0: f64, h1: f64, d0: f64, d1: f64| {
+                let p = d0 * (1.0 + h0 / (h0 + h1)) - d1 * h0 / (h0 + h1);
+
+                if p * d0 <= 0.0 {
+                    0.0
+                } else if p.abs() > 2.0 * d0.abs() {
+                    2.0 * d0
+                } else {
+                    p
+                }
+            };
+
+            let mut slopes = vec![end(h[0], h[1], delta[0], delta[1])];
+            slopes.extend((1..n - 1).map(|i| {
+                let p = (delta[i - 1] * h[i] + delta[i] * h[i - 1]) / (h[i - 1] + h[i]);
+
+                (sign(delta[i - 1]) + sign(delta[i]))
+                    * delta[i - 1].abs().min(delta[i].abs()).min(0.5 * p.abs())
+            }));
+            slopes.push(end(h[n - 2], h[n - 3], delta[n - 2], delta[n - 3]));
+
+            slopes
+        }
+        MonotoneMethod::Hyman => {
+            let mut slopes = spline_slopes(xs, ys, SplineBoundary::Natural);
+
+            for (i, slope) in slopes.iter_mut().enumerate() {
+                // Secants either side of the knot (the same one at the ends).
+                let (left, right) = (delta[i.max(1) - 1], delta[i.min(n - 2)]);
+
+                *slope = if left * right <= 0.0 {
+                    0.0
+                } else if right > 0.0 {
+                    slope.max(0.0).min(3.0 * left.min(right))
+                } else {
+                    slope.min(0.0).max(3.0 * left.max(right))
+                };
+            }
+
+            slopes
+        }
+    }
+}
+
+// Sign of `x`, zero for zero.
+fn sign(x: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x.signum()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_monotone_interpolator {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::Interpolator;
+
+    const METHODS: [MonotoneMethod; 3] = [
+        MonotoneMethod::FritschButland,
+        MonotoneMethod::Steffen,
+        MonotoneMethod::Hyman,
+    ];
+
+    #[test]
+    fn test_monotonicity() {
+        // Increasing data with a sharp rise, on an uneven grid.
+        let xs = vec![0.0, 0.5, 2.0, 2.2, 3.0, 5.0, 9.0];
+        let ys = vec![0.0, 0.1, 0.2, 3.0, 3.1, 3.15, 3.2];
+
+        for method in METHODS {
+            let mut interpolator = MonotoneCubicInterpolator::new(xs.clone(), ys.clone())
+                .unwrap()
+                .with_method(method);
+            interpolator.fit().unwrap();
+
+            for (x, y) in xs.iter().zip(&ys) {
+                assert_approx_equal!(interpolator.interpolate(*x).unwrap(), *y, 1e-12);
+            }
+
+            let mut previous = 0.0;
+            for i in 0..=900 {
+                let x = 0.01 * f64::from(i);
+                let y = interpolator.interpolate(x).unwrap();
+
+                assert!(y >= previous - 1e-12, "{method:?} at {x}");
+                assert!(interpolator.derivative(x).unwrap() >= -1e-12);
+                previous = y;
+            }
+        }
+    }
+
+    #[test]
+    fn test_extrema_are_flat() {
+        // A local maximum and minimum at the points.
+        let xs = vec![0.0, 1.0, 2.0, 3.0];
+        let ys = vec![0.0, 1.0, -1.0, 0.0];
+
+        for method in METHODS {
+            let mut interpolator = MonotoneCubicInterpolator::new(xs.clone(), ys.clone())
+                .unwrap()
+                .with_method(method);
+            interpolator.fit().unwrap();
+
+            assert_approx_equal!(interpolator.derivative(1.0).unwrap(), 0.0, 1e-15);
+            assert_approx_equal!(interpolator.derivative(2.0).unwrap(), 0.0, 1e-15);
+            for i in 0..=30 {
+                let y = interpolator.interpolate(0.1 * f64::from(i)).unwrap();
+                assert!((-1.0..=1.0).contains(&y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_data() {
+        // Each method reproduces a straight line.
+        let xs = vec![0.0, 0.3, 1.0, 2.5];
+        let ys: Vec<f64> = xs.iter().map(|x| 1.0 - 2.0 * x).collect();
+
+        for method in METHODS {
+            assert!(monotone_slopes(&xs, &ys, method)
+                .iter()
+                .all(|s| (s + 2.0).abs() < 1e-12));
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Module containing functionality for thin plate spline interpolation of
+//! scattered points.
+
+use crate::math::interpolation::{Extrapolation, InterpolationError, Interpolator2D};
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Structs, enums, and traits
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Thin plate spline (Duchon, 1976): the interpolant of scattered points
+/// `z_i = f(x_i, y_i)` of least bending energy,
+///
+/// $$
+/// f(x, y) = a_0 + a_1 x + a_2 y + \sum_i w_i \phi(\lVert (x, y) - (x_i, y_i) \rVert),
+/// \quad \phi(r) = r^2 \ln r.
+/// $$
+///
+/// The points need not lie on a grid. The distance is Euclidean, so the
+/// variables should be on comparable scales. The spline is defined
+/// everywhere: [`Extrapolation::Linear`] evaluates it outside the bounding
+/// box of the points, where it is asymptotically linear.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let spline = ThinPlateSpline::new(
+///     vec![0.0, 1.0, 0.0, 1.0, 0.4],
+///     vec![0.0, 0.0, 1.0, 1.0, 0.7],
+///     vec![0.0, 1.0, 1.0, 0.0, 0.5],
+/// )
+/// .unwrap();
+///
+/// assert!((spline.interpolate(0.4, 0.7).unwrap() - 0.5).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThinPlateSpline {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    weights: Vec<f64>,
+    affine: [f64; 3],
+    extrapolation: Extrapolation,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Implementations, functions, and macros
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ThinPlateSpline {
+    /// Create a new thin plate spline through the points `(xs[i], ys[i])`
+    /// with values `zs[i]`.
+    ///
+    /// # Errors
+    /// - `InterpolationError::UnequalLength` if `xs`, `ys` and `zs` differ
+    ///   in length.
+    /// - `InterpolationError::DuplicateIndex` for a repeated point.
+    /// - `InterpolationError::NotEnoughPoints` for fewer than three points,
+    ///   or points all on a line.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn new(xs: Vec<f64>, ys: Vec<f64>, zs: Vec<f64>) -> Result<Self, InterpolationError> {
+        let n = xs.len();
+
+        if ys.len() != n || zs.len() != n {
+            return Err(InterpolationError::UnequalLength);
+        }
+        if n < 3 {
+            return Err(InterpolationError::NotEnoughPoints);
+        }
+        // Points closer than the kernel resolves are duplicates too.
+        let coincide = |i: usize, j: usize| {
+            let (dx, dy) = (xs[i] - xs[j], ys[i] - ys[j]);
+            dx * dx + dy * dy == 0.0
+        };
+        if (0..n).any(|i| (i + 1..n).any(|j| coincide(i, j))) {
+            return Err(InterpolationError::DuplicateIndex);
+        }
+
+        // [K P; P^T 0] [w; a] = [z; 0], with K_ij = phi(|p_i - p_j|) and
+        // P the rows (1, x_i, y_i).
+        let mut system = DMatrix::zeros(n + 3, n + 3);
+        for i in 0..n {
+            for j in 0..i {
+                let phi = kernel(xs[i] - xs[j], ys[i] - ys[j]);
+                system[(i, j)] = phi;
+                system[(j, i)] = phi;
+            }
+            for (k, p) in [1.0, xs[i], ys[i]].into_iter().enumerate() {
+                system[(i, n + k)] = p;
+                system[(n + k, i)] = p;
+            }
+        }
+        let mut rhs = DVector::zeros(n + 3);
+        rhs.rows_mut(0, n).copy_from_slice(&zs);
+
+        let solution = system
+            .lu()
+            .solve(&rhs)
+            .filter(|solution| solution.iter().all(|c| c.is_finite()))
+            .ok_or(InterpolationError::NotEnoughPoints)?;
+
+        Ok(Self {
+            xs,
+            ys,
+            weights: solution.rows(0, n).iter().copied().collect(),
+            affine: [solution[n], solution[n + 1], solution[n + 2]],
+            extrapolation: Extrapolation::default(),
+        })
+    }
+}
+
+impl Interpolator2D for ThinPlateSpline {
+    fn interpolate(&self, x: f64, y: f64) -> Result<f64, InterpolationError> {
+        let ((x_min, x_max), (y_min, y_max)) = self.range();
+        let inside = (x_min..=x_max).contains(&x) && (y_min..=y_max).contains(&y);
+
+        let (x, y) = match self.extrapolation {
+            _ if inside => (x, y),
+            Extrapolation::Error => return Err(InterpolationError::OutsideOfRange),
+            Extrapolation::Flat => (x.clamp(x_min, x_max), y.clamp(y_min, y_max)),
+            Extrapolation::Linear => (x, y),
+        };
+
+        let [a_0, a_1, a_2] = self.affine;
+        let bending: f64 = self
+            .xs
+            .iter()
+            .zip(&self.ys)
+            .zip(&self.weights)
+            .map(|((x_i, y_i), w_i)| w_i * kernel(x - x_i, y - y_i))
+            .sum();
+
+        Ok(a_0 + a_1 * x + a_2 * y + bending)
+    }
+
+    fn set_extrapolation(&mut self, extrapolation: Extrapolation) {
+        self.extrapolation = extrapolation;
+    }
+
+    fn range(&self) -> ((f64, f64), (f64, f64)) {
+        let bounds = |v: &[f64]| {
+            v.iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| {
+                    (lo.min(*x), hi.max(*x))
+                })
+        };
+
+        (bounds(&self.xs), bounds(&self.ys))
+    }
+}
+
+// phi(r) = r^2 ln(r) = r^2 ln(r^2) / 2, from the offsets between two points.
+fn kernel(dx: f64, dy: f64) -> f64 {
+    let r2 = dx * dx + dy * dy;
+
+    if r2 == 0.0 {
+        0.0
+    } else {
+        0.5 * r2 * r2.ln()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Unit tests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_thin_plate_spline {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_thin_plate_spline() {
+        // Scattered points of a smooth function.
+        let f = |x: f64, y: f64| (x + 0.5 * y).sin() + 0.2 * y * y;
+        let (mut xs, mut ys) = (vec![], vec![]);
+        for i in 0..7 {
+            for j in 0..7 {
+                // A sheared, uneven layout.
+                xs.push(f64::from(i) / 6.0 + 0.05 * f64::from(j % 3));
+                ys.push(f64::from(j) / 6.0 + 0.03 * f64::from(i % 2));
+            }
+        }
+        let zs: Vec<f64> = xs.iter().zip(&ys).map(|(x, y)| f(*x, *y)).collect();
+
+        let spline = ThinPlateSpline::new(xs.clone(), ys.clone(), zs.clone()).unwrap();
+
+        for ((x, y), z) in xs.iter().zip(&ys).zip(&zs) {
+            assert_approx_equal!(spline.interpolate(*x, *y).unwrap(), *z, 1e-10);
+        }
+        for (x, y) in [(0.31, 0.52), (0.7, 0.2), (0.5, 0.5)] {
+            assert_approx_equal!(spline.interpolate(x, y).unwrap(), f(x, y), 1e-3);
+        }
+
+        assert_eq!(
+            spline.interpolate(2.0, 0.5),
+            Err(InterpolationError::OutsideOfRange)
+        );
+        let spline = spline.with_extrapolation(Extrapolation::Linear);
+        assert!(spline.interpolate(2.0, 0.5).is_ok());
+    }
+
+    #[test]
+    fn test_affine_functions_are_exact() {
+        let (xs, ys) = (vec![0.0, 1.0, 0.0, 2.0], vec![0.0, 0.0, 1.0, 3.0]);
+        let zs = xs.iter().zip(&ys).map(|(x, y)| 1.0 + 2.0 * x - y).collect();
+
+        let spline = ThinPlateSpline::new(xs, ys, zs).unwrap();
+
+        assert!(spline.weights.iter().all(|w| w.abs() < 1e-12));
+        assert_approx_equal!(spline.interpolate(1.5, 2.0).unwrap(), 2.0, 1e-12);
+    }
+
+    #[test]
+    fn test_thin_plate_spline_errors() {
+        assert_eq!(
+            ThinPlateSpline::new(vec![0.0; 3], vec![0.0; 2], vec![0.0; 3]),
+            Err(InterpolationError::UnequalLength)
+        );
+        assert_eq!(
+            ThinPlateSpline::new(vec![0.0, 1.0], vec![0.0, 1.0], vec![0.0, 1.0]),
+            Err(InterpolationError::NotEnoughPoints)
+        );
+        assert_eq!(
+            ThinPlateSpline::new(vec![0.0, 1.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0; 3]),
+            Err(InterpolationError::DuplicateIndex)
+        );
+        assert_eq!(
+            ThinPlateSpline::new(vec![0.0, 1.0, 2.0], vec![0.0, 1.0, 2.0], vec![0.0; 3]),
+            Err(InterpolationError::NotEnoughPoints)
+        );
+    }
+}
@@ -7,7 +7,13 @@
 //! ### Optimization and Root Finding
 //!
 //! - [x] Gradient Descent
+//! - [x] L-BFGS, with box constraints (see [`Lbfgs`])
+//! - [x] Levenberg-Marquardt (nonlinear least squares), with box constraints
+//! - [x] Nelder-Mead, with box constraints (see [`NelderMead`])
 //! - [x] Newton-Raphson
+//! - [x] Global optimisation: differential evolution and simulated
+//!   annealing, refined by a local optimiser with [`Minimizer::then`]
+//! - [x] Root finding: Brent, Ridders, Newton and Halley (see [`RootFinder`])
 //!
 //! Note: the reason you need to specify the lifetimes and use the type `Variable` is because the gradient descent optimiser uses the `RustQuant::autodiff` module to compute the gradients. This is a slight inconvenience, but the speed-up is enormous when working with functions with many inputs (when compared with using finite-difference quotients).
 //!
@@ -42,6 +48,10 @@
 //!
 //! - Numerical Integration (needed for Heston model, for example):
 //!   - [x] Tanh-Sinh (double exponential) quadrature
+//!   - [x] Gaussian quadrature: Gauss-Legendre, Gauss-Hermite and
+//!     Gauss-Laguerre
+//!   - [x] Adaptive Simpson and Gauss-Kronrod quadrature, with error
+//!     estimates, over finite or infinite intervals (see [`Integrator`])
 //!
 //! ```rust
 //! use RustQuant::math::*;
@@ -58,28 +68,86 @@
 //! println!("Integral = {}", integral);
 //! ```
 //!
+//! ### Fourier Transforms
+//!
+//! - [x] FFT of any length: radix-2, and Bluestein's algorithm otherwise
+//! - [x] FFT of real sequences, and linear convolution
+//! - [x] Frequency and log-strike grids of the Carr-Madan and CONV methods
+//!   (see [`FourierGrid`])
+//!
+//! ### Interpolation
+//!
+//! - [x] Linear
+//! - [x] Cubic spline (natural or clamped)
+//! - [x] Monotone cubic: Fritsch-Butland, Steffen and Hyman
+//! - [x] Akima
+//! - [x] Two dimensional: bilinear, bicubic spline and thin plate spline
+//!
 //! ### Risk-Reward Metrics
 //!
 //! - [x] Risk-Reward Measures (Sharpe, Treynor, Sortino, etc)
 
 /// Numerical integration routines.
-/// The primary (useful) integrator is the Tanh-Sinh (double exponential) implementation.
-pub mod integration;
+pub mod integration {
+    /// Adaptive Simpson and Gauss-Kronrod quadrature, with error estimates.
+    pub mod adaptive_quadrature;
+    pub use adaptive_quadrature::*;
+
+    /// Gauss-Legendre, Gauss-Hermite and Gauss-Laguerre quadrature.
+    pub mod gaussian_quadrature;
+    pub use gaussian_quadrature::*;
+
+    /// Tanh-Sinh (double exponential) quadrature.
+    pub mod tanh_sinh;
+    pub use tanh_sinh::*;
+}
 pub use integration::*;
 
 /// Numerical optimization and root-finding routines.
 pub mod optimization {
+    /// Differential evolution global optimisation.
+    pub mod differential_evolution;
+    pub use differential_evolution::*;
+
+    /// Golden-section search.
+    pub(crate) mod golden_section;
+    pub(crate) use golden_section::*;
+
     /// Gradient descent optimization.
     pub mod gradient_descent;
     pub use gradient_descent::*;
 
+    /// Limited memory BFGS, with box constraints.
+    pub mod lbfgs;
+    pub use lbfgs::*;
+
+    /// Levenberg-Marquardt nonlinear least squares.
+    pub mod levenberg_marquardt;
+    pub use levenberg_marquardt::*;
+
+    /// Nelder-Mead simplex minimisation.
+    pub mod nelder_mead;
+    pub use nelder_mead::*;
+
     /// Newton-Raphson method.
     pub mod newton_raphson;
     pub use newton_raphson::*;
+
+    /// Objective functions and box constraints of the optimisers.
+    pub mod objective;
+    pub use objective::*;
+
+    /// Root finding: Brent, Ridders, Newton and Halley.
+    pub mod root_finding;
+    pub use root_finding::*;
+
+    /// Simulated annealing global optimisation.
+    pub mod simulated_annealing;
+    pub use simulated_annealing::*;
 }
 pub use optimization::*;
 
-/// Fast fourier transform.
+/// Fast fourier transform, and the grids of the Fourier pricing methods.
 pub mod fft;
 pub use fft::*;
 
@@ -0,0 +1,324 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Global minimisation by differential evolution (Storn and Price, 1997).
+//!
+//! A population of points in the box evolves by generations: each member
+//! is challenged by a trial point, made by adding a scaled difference of
+//! two other members to a base member (the mutation) and then mixing its
+//! parameters with those of the member (the crossover). The trial point
+//! replaces the member if it is at least as good.
+//!
+//! The search is seeded, so that results are reproducible, and needs finite
+//! bounds. The starting point is one of the members of the initial
+//! population, the others being uniform in the box.
+
+use super::{value_or_infinity, Bounds, Minimizer, Minimum, ObjectiveFunction, OptimizationError};
+use rand::{rngs::StdRng, seq::index, Rng, SeedableRng};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Base member of the mutation of differential evolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationStrategy {
+    /// `DE/rand/1/bin`: a random member, for a broad search.
+    Rand,
+    /// `DE/best/1/bin`: the best member, for faster convergence.
+    Best,
+    /// `DE/current-to-best/1/bin`: the member itself, moved towards the
+    /// best member.
+    CurrentToBest,
+}
+
+/// Differential evolution optimiser.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // Ackley function, with its global minimum at the origin.
+/// let ackley = |x: &[f64]| {
+///     let (a, b) = (x[0], x[1]);
+///     let tau = 2.0 * std::f64::consts::PI;
+///
+///     -20.0 * (-0.2 * (0.5 * (a * a + b * b)).sqrt()).exp()
+///         - (0.5 * ((tau * a).cos() + (tau * b).cos())).exp()
+///         + 20.0
+///         + std::f64::consts::E
+/// };
+/// let bounds = Bounds::new(vec![-5.0; 2], vec![5.0; 2]).unwrap();
+///
+/// let result = DifferentialEvolution::default()
+///     .minimize(&ackley, &[3.0, 3.0], Some(&bounds))
+///     .unwrap();
+///
+/// assert!(result.minimizer.iter().all(|x| x.abs() < 1e-6));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifferentialEvolution {
+    /// Number of members of the population (at least 4).
+    pub population_size: usize,
+    /// Scale of the difference vector, usually in `[0.5, 1]`.
+    pub mutation: f64,
+    /// Probability that a parameter of the trial point comes from the
+    /// mutant rather than the member.
+    pub crossover: f64,
+    /// Base member of the mutation.
+    pub strategy: MutationStrategy,
+    /// Maximum number of generations.
+    pub max_generations: usize,
+    /// The search stops when the values over the population differ by no
+    /// more than this (relative to the best value).
+    pub tolerance: f64,
+    /// Seed of the random number generator.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for DifferentialEvolution {
+    /// 40 members, mutation `0.7`, crossover `0.9`, [`MutationStrategy::Rand`],
+    /// at most 1000 generations, tolerance `1e-12` and seed 42.
+    fn default() -> Self {
+        Self {
+            population_size: 40,
+            mutation: 0.7,
+            crossover: 0.9,
+            strategy: MutationStrategy::Rand,
+            max_generations: 1_000,
+            tolerance: 1e-12,
+            seed: 42,
+        }
+    }
+}
+
+impl DifferentialEvolution {
+    /// New differential evolution optimiser, with the `DE/rand/1/bin`
+    /// strategy, tolerance `1e-12` and seed 42 (see the fields to change
+    /// them).
+    #[must_use]
+    pub fn new(
+        population_size: usize,
+        mutation: f64,
+        crossover: f64,
+        max_generations: usize,
+    ) -> Self {
+        Self {
+            population_size,
+            mutation,
+            crossover,
+            max_generations,
+            ..Self::default()
+        }
+    }
+}
+
+impl Minimizer for DifferentialEvolution {
+    /// # Errors
+    ///
+    /// - [`OptimizationError::UnboundedSearch`] without finite bounds.
+    /// - [`OptimizationError::DimensionMismatch`] if the bounds and `x0`
+    ///   have different dimensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the population has fewer than 4 members.
+    fn minimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<Minimum, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        let bounds = Bounds::check_finite(bounds, x0)?;
+        assert!(self.population_size >= 4);
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let n = x0.len();
+        let (lower, upper) = (bounds.lower(), bounds.upper());
+
+        // The starting point (projected onto the box), and uniform points.
+        let mut population: Vec<Vec<f64>> = (0..self.population_size)
+            .map(|k| {
+                let mut x = x0.to_vec();
+                if k > 0 {
+                    for (x, (l, u)) in x.iter_mut().zip(lower.iter().zip(upper)) {
+                        *x = l + rng.gen::<f64>() * (u - l);
+                    }
+                }
+                bounds.project(&mut x);
+                x
+            })
+            .collect();
+        let mut values: Vec<f64> = population.iter().map(|x| value_or_infinity(f, x)).collect();
+        let mut generations = 0;
+
+        while generations < self.max_generations {
+            let best = (0..self.population_size)
+                .min_by(|a, b| values[*a].total_cmp(&values[*b]))
+                .unwrap_or(0);
+            let worst = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            if worst - values[best] <= self.tolerance * (1.0 + values[best].abs()) {
+                break;
+            }
+            generations += 1;
+
+            for member in 0..self.population_size {
+                // Three distinct other members.
+                let others: Vec<usize> = index::sample(&mut rng, self.population_size - 1, 3)
+                    .into_iter()
+                    .map(|k| if k >= member { k + 1 } else { k })
+                    .collect();
+                let (r0, r1, r2) = (
+                    &population[others[0]],
+                    &population[others[1]],
+                    &population[others[2]],
+                );
+                let current = &population[member];
+
+                // Binomial crossover, with at least one parameter from the
+                // mutant.
+                let forced = rng.gen_range(0..n);
+                let trial: Vec<f64> = (0..n)
+                    .map(|j| {
+                        if j != forced && rng.gen::<f64>() >= self.crossover {
+                            return current[j];
+                        }
+
+                        let difference = self.mutation * (r1[j] - r2[j]);
+                        let mutant = match self.strategy {
+                            MutationStrategy::Rand => r0[j] + difference,
+                            MutationStrategy::Best => population[best][j] + difference,
+                            MutationStrategy::CurrentToBest => {
+                                current[j]
+                                    + self.mutation * (population[best][j] - current[j])
+                                    + difference
+                            }
+                        };
+
+                        // Back into the box, half way from the member to
+                        // the bound it crossed.
+                        if mutant < lower[j] {
+                            0.5 * (lower[j] + current[j])
+                        } else if mutant > upper[j] {
+                            0.5 * (upper[j] + current[j])
+                        } else {
+                            mutant
+                        }
+                    })
+                    .collect();
+
+                let trial_value = value_or_infinity(f, &trial);
+                if trial_value <= values[member] {
+                    population[member] = trial;
+                    values[member] = trial_value;
+                }
+            }
+        }
+
+        let best = (0..self.population_size)
+            .min_by(|a, b| values[*a].total_cmp(&values[*b]))
+            .unwrap_or(0);
+
+        Ok(Minimum {
+            minimizer: population.swap_remove(best),
+            minimum: values[best],
+            iterations: generations,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_differential_evolution {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::Lbfgs;
+
+    // Rastrigin function in 4 dimensions: 10^4 local minima in the box.
+    fn rastrigin(x: &[f64]) -> f64 {
+        x.iter()
+            .map(|x| x * x - 10.0 * (2.0 * std::f64::consts::PI * x).cos() + 10.0)
+            .sum()
+    }
+
+    #[test]
+    fn test_differential_evolution() {
+        let bounds = Bounds::new(vec![-5.12; 4], vec![5.12; 4]).unwrap();
+        let x0 = [4.0, -3.0, 2.0, 1.0];
+
+        for strategy in [
+            MutationStrategy::Rand,
+            MutationStrategy::Best,
+            MutationStrategy::CurrentToBest,
+        ] {
+            let de = DifferentialEvolution {
+                strategy,
+                ..DifferentialEvolution::new(60, 0.8, 0.5, 3_000)
+            };
+            let result = de.minimize(&rastrigin, &x0, Some(&bounds)).unwrap();
+
+            assert!(result.minimum < 1e-9, "{strategy:?}: {result:?}");
+            assert!(bounds.contains(&result.minimizer));
+
+            // Reproducible given the seed.
+            assert_eq!(de.minimize(&rastrigin, &x0, Some(&bounds)).unwrap(), result);
+        }
+    }
+
+    #[test]
+    fn test_refinement() {
+        let bounds = Bounds::new(vec![-5.12; 4], vec![5.12; 4]).unwrap();
+        let x0 = [4.0, -3.0, 2.0, 1.0];
+
+        // A few generations find the basin, and L-BFGS the minimum in it.
+        let de = DifferentialEvolution::new(60, 0.6, 0.5, 200);
+        let rough = de.minimize(&rastrigin, &x0, Some(&bounds)).unwrap();
+        let refined = de
+            .then(Lbfgs::default())
+            .minimize(&rastrigin, &x0, Some(&bounds))
+            .unwrap();
+
+        assert!(refined.minimum < rough.minimum);
+        assert!(refined.iterations > rough.iterations);
+        for x in &refined.minimizer {
+            assert_approx_equal!(*x, 0.0, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let de = DifferentialEvolution::default();
+
+        assert_eq!(
+            de.minimize(&rastrigin, &[0.0], None),
+            Err(OptimizationError::UnboundedSearch)
+        );
+
+        let bounds = Bounds::new(vec![0.0], vec![f64::INFINITY]).unwrap();
+        assert_eq!(
+            de.minimize(&rastrigin, &[0.0], Some(&bounds)),
+            Err(OptimizationError::UnboundedSearch)
+        );
+
+        let bounds = Bounds::new(vec![0.0; 2], vec![1.0; 2]).unwrap();
+        assert_eq!(
+            de.minimize(&rastrigin, &[0.0], Some(&bounds)),
+            Err(OptimizationError::DimensionMismatch)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! One dimensional maximisation by golden-section search.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Maximum of a unimodal function on `[low, high]`, by golden-section search.
+pub(crate) fn golden_section_maximum<F: Fn(f64) -> f64>(f: F, low: f64, high: f64) -> f64 {
+    const TOLERANCE: f64 = 1e-6;
+    let ratio = 0.5 * (5_f64.sqrt() - 1.0);
+
+    let (mut a, mut b) = (low, high);
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let (mut f_c, mut f_d) = (f(c), f(d));
+
+    while b - a > TOLERANCE {
+        if f_c > f_d {
+            b = d;
+            (d, f_d) = (c, f_c);
+            c = b - ratio * (b - a);
+            f_c = f(c);
+        } else {
+            a = c;
+            (c, f_c) = (d, f_d);
+            d = a + ratio * (b - a);
+            f_d = f(d);
+        }
+    }
+
+    0.5 * (a + b)
+}
@@ -0,0 +1,401 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Limited memory BFGS (L-BFGS) minimisation, with optional box
+//! constraints.
+//!
+//! The inverse Hessian is approximated from the last `m` steps and gradient
+//! changes, by the two-loop recursion (Nocedal and Wright, 2006, Algorithm
+//! 7.4), and the step length is found by backtracking until the Armijo
+//! condition holds.
+//!
+//! Box constraints are handled by projection: the parameters at a bound,
+//! with the gradient pointing out of the box, are held fixed for the
+//! iteration, and the line search follows the projection of the search
+//! direction onto the box. The optimiser stops when the projected gradient
+//! `P(x - ∇f(x)) - x` vanishes, which is the first order optimality
+//! condition on the box, or when the function stops decreasing.
+//!
+//! The gradient comes from the [`ObjectiveFunction`]: exact, from the
+//! reverse mode tape, for a [`ReverseMode`](super::ReverseMode) closure,
+//! and by finite differences for a plain closure.
+
+use super::{Bounds, Minimizer, Minimum, ObjectiveFunction, OptimizationError};
+use std::collections::VecDeque;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// L-BFGS optimiser.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+/// use RustQuant::math::*;
+///
+/// // Rosenbrock function, with the gradient from the tape.
+/// let rosenbrock = ReverseMode::new(|v: &[Variable]| {
+///     (1.0 - v[0]).powf(2.0) + 100.0 * (v[1] - v[0] * v[0]).powf(2.0)
+/// });
+///
+/// let result = Lbfgs::default().optimize(&rosenbrock, &[-1.2, 1.0], None).unwrap();
+/// assert!((result.minimizer[0] - 1.0).abs() < 1e-8);
+/// assert!((result.minimizer[1] - 1.0).abs() < 1e-8);
+///
+/// // With x <= 0.5, the minimum is on the parabola y = x^2 at x = 0.5.
+/// let bounds = Bounds::new(vec![-2.0, -2.0], vec![0.5, 2.0]).unwrap();
+///
+/// let result = Lbfgs::default().optimize(&rosenbrock, &[-1.2, 1.0], Some(&bounds)).unwrap();
+/// assert_eq!(result.minimizer[0], 0.5);
+/// assert!((result.minimizer[1] - 0.25).abs() < 1e-8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lbfgs {
+    /// Number of past steps kept to approximate the inverse Hessian.
+    pub memory: usize,
+    /// The optimiser stops when no component of the projected gradient
+    /// exceeds this in absolute value.
+    pub tolerance: f64,
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+}
+
+/// Result of the L-BFGS optimiser.
+#[derive(Debug, Clone)]
+pub struct LbfgsResult {
+    /// Minimizer of the function.
+    pub minimizer: Vec<f64>,
+    /// Value of the function at the minimizer.
+    pub minimum: f64,
+    /// Gradient of the function at the minimizer.
+    pub gradient: Vec<f64>,
+    /// Number of iterations performed.
+    pub iterations: usize,
+    /// Whether the projected gradient vanished (to the tolerance) or the
+    /// function stopped decreasing (to machine precision), rather than the
+    /// iterations running out or the line search stalling.
+    pub converged: bool,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for Lbfgs {
+    /// Memory 10, tolerance `1e-8`, and at most 1000 iterations.
+    fn default() -> Self {
+        Self::new(10, 1e-8, 1_000)
+    }
+}
+
+impl Lbfgs {
+    /// New L-BFGS optimiser.
+    #[must_use]
+    pub const fn new(memory: usize, tolerance: f64, max_iterations: usize) -> Self {
+        Self {
+            memory,
+            tolerance,
+            max_iterations,
+        }
+    }
+
+    /// Minimise `f` from `x0`, subject to the `bounds` if any (the starting
+    /// point is projected onto them).
+    ///
+    /// # Errors
+    ///
+    /// [`OptimizationError::DimensionMismatch`] if the bounds and `x0` have
+    /// different dimensions.
+    pub fn optimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<LbfgsResult, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        const ARMIJO: f64 = 1e-4;
+        const MAX_BACKTRACKS: usize = 50;
+
+        Bounds::check(bounds, x0)?;
+
+        let project = |x: &mut [f64]| {
+            if let Some(bounds) = bounds {
+                bounds.project(x);
+            }
+        };
+
+        let mut x = x0.to_vec();
+        project(&mut x);
+        let (mut value, mut gradient) = f.gradient(&x);
+
+        // Pairs (s, y, 1 / s'y) of steps and gradient changes.
+        let mut history: VecDeque<(Vec<f64>, Vec<f64>, f64)> = VecDeque::new();
+        let mut iterations = 0;
+        let mut converged = false;
+
+        while iterations < self.max_iterations {
+            if is_stationary(&x, &gradient, bounds, self.tolerance) {
+                converged = true;
+                break;
+            }
+            iterations += 1;
+
+            let free = free_parameters(&x, &gradient, bounds);
+            let masked: Vec<f64> = gradient
+                .iter()
+                .zip(&free)
+                .map(|(g, free)| if *free { *g } else { 0.0 })
+                .collect();
+
+            let mut direction = two_loop(&masked, &history);
+            for (d, free) in direction.iter_mut().zip(&free) {
+                *d = if *free { -*d } else { 0.0 };
+            }
+            if dot(&direction, &gradient) >= 0.0 {
+                history.clear();
+                direction = masked.iter().map(|g| -g).collect();
+            }
+
+            // Backtracking along the projected path.
+            let mut step = if history.is_empty() {
+                1_f64.min(1.0 / direction.iter().map(|d| d.abs()).fold(0.0, f64::max))
+            } else {
+                1.0
+            };
+            let mut accepted = None;
+
+            for _ in 0..MAX_BACKTRACKS {
+                let mut candidate: Vec<f64> = x
+                    .iter()
+                    .zip(&direction)
+                    .map(|(x, d)| x + step * d)
+                    .collect();
+                project(&mut candidate);
+
+                let s: Vec<f64> = candidate.iter().zip(&x).map(|(c, x)| c - x).collect();
+                let decrease = dot(&gradient, &s);
+                if decrease >= 0.0 {
+                    break;
+                }
+
+                let (candidate_value, candidate_gradient) = f.gradient(&candidate);
+                if candidate_value <= value + ARMIJO * decrease {
+                    accepted = Some((candidate, s, candidate_value, candidate_gradient));
+                    break;
+                }
+                step *= 0.5;
+            }
+
+            let Some((candidate, s, candidate_value, candidate_gradient)) = accepted else {
+                // Restart from steepest descent, unless that stalled too.
+                if history.is_empty() {
+                    break;
+                }
+                history.clear();
+                continue;
+            };
+
+            let y: Vec<f64> = candidate_gradient
+                .iter()
+                .zip(&gradient)
+                .map(|(new, old)| new - old)
+                .collect();
+            let curvature = dot(&s, &y);
+            if curvature > f64::EPSILON * dot(&y, &y) {
+                if history.len() == self.memory {
+                    history.pop_front();
+                }
+                history.push_back((s, y, 1.0 / curvature));
+            }
+
+            let improvement = value - candidate_value;
+            (x, value, gradient) = (candidate, candidate_value, candidate_gradient);
+
+            if improvement <= f64::EPSILON * value.abs() {
+                converged = true;
+                break;
+            }
+        }
+
+        Ok(LbfgsResult {
+            minimizer: x,
+            minimum: value,
+            gradient,
+            iterations,
+            converged,
+        })
+    }
+}
+
+impl Minimizer for Lbfgs {
+    fn minimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<Minimum, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        let result = self.optimize(f, x0, bounds)?;
+
+        Ok(Minimum {
+            minimizer: result.minimizer,
+            minimum: result.minimum,
+            iterations: result.iterations,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+// Whether no component of the projected gradient P(x - g) - x exceeds the
+// tolerance.
+fn is_stationary(x: &[f64], gradient: &[f64], bounds: Option<&Bounds>, tolerance: f64) -> bool {
+    let mut projected: Vec<f64> = x.iter().zip(gradient).map(|(x, g)| x - g).collect();
+    if let Some(bounds) = bounds {
+        bounds.project(&mut projected);
+    }
+
+    projected
+        .iter()
+        .zip(x)
+        .all(|(p, x)| (p - x).abs() <= tolerance)
+}
+
+// Parameters which are not held at a bound, i.e. not at a bound which the
+// gradient pushes them out of.
+fn free_parameters(x: &[f64], gradient: &[f64], bounds: Option<&Bounds>) -> Vec<bool> {
+    match bounds {
+        Some(bounds) => (0..x.len())
+            .map(|j| {
+                !(x[j] <= bounds.lower()[j] && gradient[j] > 0.0
+                    || x[j] >= bounds.upper()[j] && gradient[j] < 0.0)
+            })
+            .collect(),
+        None => vec![true; x.len()],
+    }
+}
+
+// Product of the L-BFGS inverse Hessian approximation with `gradient`, by
+// the two-loop recursion, scaled by s'y / y'y of the latest pair.
+fn two_loop(gradient: &[f64], history: &VecDeque<(Vec<f64>, Vec<f64>, f64)>) -> Vec<f64> {
+    let mut q = gradient.to_vec();
+    let mut alphas = Vec::with_capacity(history.len());
+
+    for (s, y, rho) in history.iter().rev() {
+        let alpha = rho * dot(s, &q);
+        q.iter_mut().zip(y).for_each(|(q, y)| *q -= alpha * y);
+        alphas.push(alpha);
+    }
+
+    if let Some((_, y, rho)) = history.back() {
+        let gamma = 1.0 / (rho * dot(y, y));
+        for q in &mut q {
+            *q *= gamma;
+        }
+    }
+
+    for ((s, y, rho), alpha) in history.iter().zip(alphas.iter().rev()) {
+        let beta = rho * dot(y, &q);
+        q.iter_mut()
+            .zip(s)
+            .for_each(|(q, s)| *q += (alpha - beta) * s);
+    }
+
+    q
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_lbfgs {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::autodiff::Variable;
+    use crate::math::ReverseMode;
+
+    // Extended Rosenbrock function in 10 dimensions.
+    fn rosenbrock<'v>(v: &[Variable<'v>]) -> Variable<'v> {
+        v.windows(2)
+            .map(|w| {
+                (1.0 - w[0]) * (1.0 - w[0]) + 100.0 * (w[1] - w[0] * w[0]) * (w[1] - w[0] * w[0])
+            })
+            .reduce(|a, b| a + b)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unconstrained() {
+        let x0: Vec<f64> = (0..10)
+            .map(|k| if k % 2 == 0 { -1.2 } else { 1.0 })
+            .collect();
+
+        let result = Lbfgs::default()
+            .optimize(&ReverseMode(rosenbrock), &x0, None)
+            .unwrap();
+
+        assert!(result.converged);
+        assert!(result.minimum < 1e-16);
+        for x in &result.minimizer {
+            assert_approx_equal!(*x, 1.0, 1e-8);
+        }
+
+        // Finite difference gradients of a plain closure.
+        let quadratic = |x: &[f64]| (x[0] - 3.0).powi(2) + 10.0 * (x[1] + x[0]).powi(2);
+        let result = Lbfgs::default()
+            .optimize(&quadratic, &[0.0, 0.0], None)
+            .unwrap();
+        assert_approx_equal!(result.minimizer[0], 3.0, 1e-7);
+        assert_approx_equal!(result.minimizer[1], -3.0, 1e-7);
+    }
+
+    #[test]
+    fn test_bounds() {
+        // Minimum of the quadratic at (3, -3), outside the box [0, 2] x [-1, 1]:
+        // the constrained minimum is on the bound y = -1.
+        let quadratic = ReverseMode::new(|v: &[Variable]| {
+            (v[0] - 3.0) * (v[0] - 3.0) + 10.0 * (v[1] + v[0]) * (v[1] + v[0])
+        });
+        let bounds = Bounds::new(vec![0.0, -1.0], vec![2.0, 1.0]).unwrap();
+
+        let result = Lbfgs::default()
+            .optimize(&quadratic, &[1.0, 0.0], Some(&bounds))
+            .unwrap();
+
+        // At y = -1, d/dx = 2 (x - 3) + 20 (x - 1) = 0 at x = 13 / 11.
+        assert!(result.converged);
+        assert_approx_equal!(result.minimizer[0], 13.0 / 11.0, 1e-8);
+        assert_approx_equal!(result.minimizer[1], -1.0, 1e-15);
+        assert!(bounds.contains(&result.minimizer));
+
+        // A start outside the box is projected onto it.
+        let result = Lbfgs::default()
+            .optimize(&quadratic, &[10.0, 10.0], Some(&bounds))
+            .unwrap();
+        assert_approx_equal!(result.minimizer[0], 13.0 / 11.0, 1e-8);
+
+        assert_eq!(
+            Lbfgs::default()
+                .optimize(&quadratic, &[1.0], Some(&bounds))
+                .unwrap_err(),
+            OptimizationError::DimensionMismatch
+        );
+    }
+}
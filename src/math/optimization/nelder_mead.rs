@@ -0,0 +1,257 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Derivative-free minimisation by the Nelder-Mead simplex algorithm, with
+//! optional box constraints (each trial point is projected onto the box).
+//!
+//! Used internally by the maximum likelihood fits, whose objectives are
+//! cheap to evaluate but awkward to differentiate.
+
+use super::{Bounds, Minimizer, Minimum, ObjectiveFunction, OptimizationError};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Nelder-Mead simplex optimiser.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // Minimum of (x - 2)^2 + (y + 1)^2 subject to 0 <= x <= 1.
+/// let f = |x: &[f64]| (x[0] - 2.0).powi(2) + (x[1] + 1.0).powi(2);
+/// let bounds = Bounds::new(vec![0.0, f64::NEG_INFINITY], vec![1.0, f64::INFINITY]).unwrap();
+///
+/// let result = NelderMead::default().optimize(&f, &[0.5, 0.0], Some(&bounds)).unwrap();
+///
+/// assert!((result.minimizer[0] - 1.0).abs() < 1e-6);
+/// assert!((result.minimizer[1] + 1.0).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NelderMead {
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+    /// The optimiser stops when the values at the vertices of the simplex
+    /// differ by no more than this (relative to the best value).
+    pub tolerance: f64,
+    /// Offset of each parameter from the starting point, in the initial
+    /// simplex.
+    pub step: f64,
+}
+
+/// Result of the Nelder-Mead optimiser.
+#[derive(Debug, Clone)]
+pub struct NelderMeadResult {
+    /// Best vertex of the final simplex.
+    pub minimizer: Vec<f64>,
+    /// Value of the function at the minimizer.
+    pub minimum: f64,
+    /// Number of iterations performed.
+    pub iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for NelderMead {
+    /// At most 5000 iterations, tolerance `1e-10` and step `0.5`.
+    fn default() -> Self {
+        Self::new(5_000, 1e-10, 0.5)
+    }
+}
+
+impl NelderMead {
+    /// New Nelder-Mead optimiser.
+    #[must_use]
+    pub const fn new(max_iterations: usize, tolerance: f64, step: f64) -> Self {
+        Self {
+            max_iterations,
+            tolerance,
+            step,
+        }
+    }
+
+    /// Minimise `f` from `x0`, subject to the `bounds` if any.
+    ///
+    /// # Errors
+    ///
+    /// [`OptimizationError::DimensionMismatch`] if the bounds and `x0` have
+    /// different dimensions.
+    pub fn optimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<NelderMeadResult, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        Bounds::check(bounds, x0)?;
+
+        Ok(self.simplex(f, x0, bounds))
+    }
+
+    fn simplex<F>(&self, f: &F, start: &[f64], bounds: Option<&Bounds>) -> NelderMeadResult
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        let n = start.len();
+        let point = |mut x: Vec<f64>| {
+            if let Some(bounds) = bounds {
+                bounds.project(&mut x);
+            }
+            let value = f.value(&x);
+            (x, value)
+        };
+
+        let mut simplex: Vec<(Vec<f64>, f64)> = (0..=n)
+            .map(|k| {
+                let mut x = start.to_vec();
+                if k > 0 {
+                    x[k - 1] += self.step;
+                    // Step the other way from an upper bound.
+                    if bounds.is_some_and(|bounds| x[k - 1] > bounds.upper()[k - 1]) {
+                        x[k - 1] -= 2.0 * self.step;
+                    }
+                }
+                point(x)
+            })
+            .collect();
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+            if (simplex[n].1 - simplex[0].1).abs() <= self.tolerance * (1.0 + simplex[0].1.abs()) {
+                break;
+            }
+            iterations += 1;
+
+            let centroid: Vec<f64> = (0..n)
+                .map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<f64>() / n as f64)
+                .collect();
+            let towards = |coefficient: f64| -> Vec<f64> {
+                centroid
+                    .iter()
+                    .zip(&simplex[n].0)
+                    .map(|(c, worst)| c + coefficient * (worst - c))
+                    .collect()
+            };
+
+            let reflected = point(towards(-1.0));
+            if reflected.1 < simplex[0].1 {
+                let expanded = point(towards(-2.0));
+                simplex[n] = if expanded.1 < reflected.1 {
+                    expanded
+                } else {
+                    reflected
+                };
+            } else if reflected.1 < simplex[n - 1].1 {
+                simplex[n] = reflected;
+            } else {
+                let contracted = if reflected.1 < simplex[n].1 {
+                    point(towards(-0.5))
+                } else {
+                    point(towards(0.5))
+                };
+
+                if contracted.1 < simplex[n].1.min(reflected.1) {
+                    simplex[n] = contracted;
+                } else {
+                    // Shrink towards the best point.
+                    let best = simplex[0].0.clone();
+                    for vertex in simplex.iter_mut().skip(1) {
+                        let x = best
+                            .iter()
+                            .zip(&vertex.0)
+                            .map(|(b, x)| b + 0.5 * (x - b))
+                            .collect();
+                        *vertex = point(x);
+                    }
+                }
+            }
+        }
+
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (minimizer, minimum) = simplex.swap_remove(0);
+
+        NelderMeadResult {
+            minimizer,
+            minimum,
+            iterations,
+        }
+    }
+}
+
+impl Minimizer for NelderMead {
+    fn minimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<Minimum, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        let result = self.optimize(f, x0, bounds)?;
+
+        Ok(Minimum {
+            minimizer: result.minimizer,
+            minimum: result.minimum,
+            iterations: result.iterations,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Minimum of `f`, by the Nelder-Mead simplex algorithm from `start`.
+pub(crate) fn nelder_mead<F: Fn(&[f64]) -> f64>(f: F, start: &[f64]) -> Vec<f64> {
+    NelderMead::default().simplex(&f, start, None).minimizer
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_nelder_mead {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_nelder_mead() {
+        let rosenbrock = |x: &[f64]| (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0] * x[0]).powi(2);
+
+        let result = NelderMead::default()
+            .optimize(&rosenbrock, &[-1.2, 1.0], None)
+            .unwrap();
+        assert_approx_equal!(result.minimizer[0], 1.0, 1e-4);
+        assert_approx_equal!(result.minimizer[1], 1.0, 1e-4);
+        assert!(result.minimum < 1e-9);
+
+        // With x <= 0.5, the minimum is on the parabola y = x^2 at x = 0.5.
+        let bounds = Bounds::new(vec![-2.0, -2.0], vec![0.5, 2.0]).unwrap();
+        let result = NelderMead::default()
+            .optimize(&rosenbrock, &[-1.2, 1.0], Some(&bounds))
+            .unwrap();
+        assert_approx_equal!(result.minimizer[0], 0.5, 1e-4);
+        assert_approx_equal!(result.minimizer[1], 0.25, 1e-4);
+        assert!(bounds.contains(&result.minimizer));
+
+        assert_eq!(
+            NelderMead::default()
+                .optimize(&rosenbrock, &[0.0], Some(&bounds))
+                .unwrap_err(),
+            OptimizationError::DimensionMismatch
+        );
+    }
+}
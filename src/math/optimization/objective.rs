@@ -0,0 +1,404 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Objective functions and box constraints of the multidimensional
+//! optimisers: [`Lbfgs`](super::Lbfgs), [`NelderMead`](super::NelderMead)
+//! and [`LevenbergMarquardt`](super::LevenbergMarquardt), and the global
+//! optimisers [`DifferentialEvolution`](super::DifferentialEvolution) and
+//! [`SimulatedAnnealing`](super::SimulatedAnnealing).
+//!
+//! An [`ObjectiveFunction`] is any closure `Fn(&[f64]) -> f64`, with its
+//! gradient by finite differences, or a [`ReverseMode`] closure of
+//! [`Variable`]s, with its exact gradient from the reverse mode tape of
+//! [`crate::autodiff`], in a single backward pass whatever the number of
+//! parameters.
+//!
+//! The optimisers of a function (all but the least squares optimiser)
+//! implement [`Minimizer`], so that a global search can be refined by a
+//! local optimiser with [`Minimizer::then`]:
+//!
+//! ```
+//! use RustQuant::math::*;
+//!
+//! // Rastrigin function: a local minimum at every integer point.
+//! let rastrigin = |x: &[f64]| {
+//!     x.iter()
+//!         .map(|x| x * x - 10.0 * (2.0 * std::f64::consts::PI * x).cos() + 10.0)
+//!         .sum::<f64>()
+//! };
+//! let bounds = Bounds::new(vec![-5.12; 2], vec![5.12; 2]).unwrap();
+//!
+//! let local = Lbfgs::default().minimize(&rastrigin, &[3.0, -2.0], Some(&bounds)).unwrap();
+//! assert!(local.minimum > 10.0);
+//!
+//! let global = DifferentialEvolution::default()
+//!     .then(Lbfgs::default())
+//!     .minimize(&rastrigin, &[3.0, -2.0], Some(&bounds))
+//!     .unwrap();
+//! assert!(global.minimum < 1e-12);
+//! ```
+
+use crate::autodiff::{Accumulate, Gradient, Graph, Variable};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Optimisation error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum OptimizationError {
+    /// A lower bound is above its upper bound, or a bound is NaN.
+    #[error("Invalid bounds")]
+    InvalidBounds,
+
+    /// The starting point and the bounds have different dimensions.
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+
+    /// The damped normal equations of the Levenberg-Marquardt algorithm
+    /// cannot be solved.
+    #[error("Singular linear system")]
+    SingularSystem,
+
+    /// A global optimiser was not given finite bounds to search.
+    #[error("Finite bounds are required")]
+    UnboundedSearch,
+}
+
+/// Real function of several variables, with its gradient.
+pub trait ObjectiveFunction {
+    /// Value of the function at `x`.
+    fn value(&self, x: &[f64]) -> f64;
+
+    /// Value and gradient of the function at `x`.
+    ///
+    /// The default implementation uses central finite differences.
+    fn gradient(&self, x: &[f64]) -> (f64, Vec<f64>) {
+        let mut point = x.to_vec();
+        let gradient = (0..x.len())
+            .map(|j| {
+                let h = f64::EPSILON.cbrt() * x[j].abs().max(1.0);
+
+                point[j] = x[j] + h;
+                let up = self.value(&point);
+                point[j] = x[j] - h;
+                let down = self.value(&point);
+                point[j] = x[j];
+
+                (up - down) / (2.0 * h)
+            })
+            .collect();
+
+        (self.value(x), gradient)
+    }
+}
+
+/// Function of [`Variable`]s, with its exact gradient by reverse mode
+/// automatic differentiation.
+///
+/// Closures should be wrapped with [`ReverseMode::new`], which lets the
+/// compiler infer their signature for every lifetime of the tape.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+/// use RustQuant::math::*;
+///
+/// let f = ReverseMode::new(|x: &[Variable]| x[0] * x[1].exp());
+///
+/// assert_eq!(f.gradient(&[2.0, 0.0]), (2.0, vec![1.0, 2.0]));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ReverseMode<F>(pub F);
+
+/// Minimisation method of an [`ObjectiveFunction`], local or global.
+pub trait Minimizer {
+    /// Minimise `f` from `x0`, subject to the `bounds` if any.
+    ///
+    /// # Errors
+    ///
+    /// See [`OptimizationError`].
+    fn minimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<Minimum, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized;
+
+    /// This method, followed by `next` from the point it found.
+    #[must_use]
+    fn then<M: Minimizer>(self, next: M) -> Chain<Self, M>
+    where
+        Self: Sized,
+    {
+        Chain { first: self, next }
+    }
+}
+
+/// Result of a [`Minimizer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimum {
+    /// Minimizer of the function.
+    pub minimizer: Vec<f64>,
+    /// Value of the function at the minimizer.
+    pub minimum: f64,
+    /// Number of iterations (or generations) performed.
+    pub iterations: usize,
+}
+
+/// Two minimisation methods run one after the other, e.g. a global search
+/// refined by a local optimiser (see [`Minimizer::then`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chain<A, B> {
+    /// The method run first, from the starting point.
+    pub first: A,
+    /// The method run next, from the point found by the first.
+    pub next: B,
+}
+
+/// Box constraints `lower <= x <= upper`, element-wise.
+///
+/// Infinite bounds leave a parameter unconstrained on that side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds {
+    lower: Vec<f64>,
+    upper: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<F: Fn(&[f64]) -> f64> ObjectiveFunction for F {
+    fn value(&self, x: &[f64]) -> f64 {
+        self(x)
+    }
+}
+
+impl<F> ReverseMode<F>
+where
+    F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+{
+    /// Wraps the function `f` of [`Variable`]s.
+    pub fn new(f: F) -> Self {
+        Self(f)
+    }
+}
+
+impl<F> ObjectiveFunction for ReverseMode<F>
+where
+    F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+{
+    fn value(&self, x: &[f64]) -> f64 {
+        let graph = Graph::new();
+
+        (self.0)(&graph.vars(x)).value
+    }
+
+    fn gradient(&self, x: &[f64]) -> (f64, Vec<f64>) {
+        let graph = Graph::new();
+        let variables = graph.vars(x);
+        let output = (self.0)(&variables);
+
+        (output.value, output.accumulate().wrt(&variables))
+    }
+}
+
+impl<A: Minimizer, B: Minimizer> Minimizer for Chain<A, B> {
+    fn minimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<Minimum, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        let first = self.first.minimize(f, x0, bounds)?;
+        let next = self.next.minimize(f, &first.minimizer, bounds)?;
+        let iterations = first.iterations + next.iterations;
+
+        // The next method may be a stochastic one, which need not improve.
+        let best = if next.minimum <= first.minimum {
+            next
+        } else {
+            first
+        };
+
+        Ok(Minimum { iterations, ..best })
+    }
+}
+
+impl Bounds {
+    /// New box constraints.
+    ///
+    /// # Errors
+    ///
+    /// - [`OptimizationError::DimensionMismatch`] if `lower` and `upper`
+    ///   have different lengths.
+    /// - [`OptimizationError::InvalidBounds`] if a bound is NaN, or a
+    ///   lower bound is above its upper bound.
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Result<Self, OptimizationError> {
+        if lower.len() != upper.len() {
+            return Err(OptimizationError::DimensionMismatch);
+        }
+        if lower
+            .iter()
+            .zip(&upper)
+            .any(|(l, u)| l.is_nan() || u.is_nan() || l > u)
+        {
+            return Err(OptimizationError::InvalidBounds);
+        }
+
+        Ok(Self { lower, upper })
+    }
+
+    /// Number of parameters.
+    #[must_use]
+    pub fn dimension(&self) -> usize {
+        self.lower.len()
+    }
+
+    /// Lower bounds.
+    #[must_use]
+    pub fn lower(&self) -> &[f64] {
+        &self.lower
+    }
+
+    /// Upper bounds.
+    #[must_use]
+    pub fn upper(&self) -> &[f64] {
+        &self.upper
+    }
+
+    /// Whether `x` satisfies the constraints.
+    #[must_use]
+    pub fn contains(&self, x: &[f64]) -> bool {
+        x.len() == self.dimension()
+            && x.iter()
+                .zip(self.lower.iter().zip(&self.upper))
+                .all(|(x, (l, u))| (l..=u).contains(&x))
+    }
+
+    /// Projects `x` onto the box, i.e. clamps each parameter to its bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have the dimension of the bounds.
+    pub fn project(&self, x: &mut [f64]) {
+        assert_eq!(x.len(), self.dimension());
+
+        for (x, (l, u)) in x.iter_mut().zip(self.lower.iter().zip(&self.upper)) {
+            *x = x.clamp(*l, *u);
+        }
+    }
+
+    // Checks that there are finite bounds, of the dimension of the starting
+    // point, for a global search.
+    pub(crate) fn check_finite<'a>(
+        bounds: Option<&'a Self>,
+        x0: &[f64],
+    ) -> Result<&'a Self, OptimizationError> {
+        let bounds = bounds.ok_or(OptimizationError::UnboundedSearch)?;
+        Self::check(Some(bounds), x0)?;
+
+        if bounds
+            .lower
+            .iter()
+            .chain(&bounds.upper)
+            .all(|b| b.is_finite())
+        {
+            Ok(bounds)
+        } else {
+            Err(OptimizationError::UnboundedSearch)
+        }
+    }
+
+    // Checks the dimension of the bounds, if any, against the starting point.
+    pub(crate) fn check(bounds: Option<&Self>, x0: &[f64]) -> Result<(), OptimizationError> {
+        match bounds {
+            Some(bounds) if bounds.dimension() != x0.len() => {
+                Err(OptimizationError::DimensionMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Value of `f` at `x` for the global optimisers, with NaN (e.g. outside the
+// domain of a model) as infinity, so that such points are never kept.
+pub(crate) fn value_or_infinity<F: ObjectiveFunction + ?Sized>(f: &F, x: &[f64]) -> f64 {
+    let value = f.value(x);
+
+    if value.is_nan() {
+        f64::INFINITY
+    } else {
+        value
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_objective {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_gradients() {
+        let closure = |x: &[f64]| x[0] * x[0] * x[1].sin() + x[2].exp();
+        let tape = ReverseMode::new(|x: &[Variable]| x[0] * x[0] * x[1].sin() + x[2].exp());
+
+        let x = [1.5, 0.3, -0.7];
+        let (value, gradient) = tape.gradient(&x);
+        let (value_fd, gradient_fd) = closure.gradient(&x);
+
+        assert_approx_equal!(value, closure(&x), 1e-15);
+        assert_approx_equal!(value_fd, value, 1e-15);
+        assert_approx_equal!(gradient[0], 2.0 * 1.5 * 0.3_f64.sin(), 1e-15);
+        assert_approx_equal!(gradient[1], 1.5 * 1.5 * 0.3_f64.cos(), 1e-15);
+        assert_approx_equal!(gradient[2], (-0.7_f64).exp(), 1e-15);
+
+        for (exact, approximate) in gradient.iter().zip(&gradient_fd) {
+            assert_approx_equal!(approximate, exact, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::new(vec![0.0, f64::NEG_INFINITY], vec![1.0, 2.0]).unwrap();
+
+        let mut x = [1.5, -10.0];
+        assert!(!bounds.contains(&x));
+        bounds.project(&mut x);
+        assert_approx_equal!(x[0], 1.0, 1e-15);
+        assert_approx_equal!(x[1], -10.0, 1e-15);
+        assert!(bounds.contains(&x));
+
+        assert_eq!(
+            Bounds::new(vec![0.0], vec![1.0, 2.0]),
+            Err(OptimizationError::DimensionMismatch)
+        );
+        assert_eq!(
+            Bounds::new(vec![1.0], vec![0.0]),
+            Err(OptimizationError::InvalidBounds)
+        );
+        assert_eq!(
+            Bounds::check(Some(&bounds), &[0.0]),
+            Err(OptimizationError::DimensionMismatch)
+        );
+    }
+}
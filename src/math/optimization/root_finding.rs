@@ -0,0 +1,611 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Roots of functions of one variable, e.g. implied volatilities, yields to
+//! maturity and par rates.
+//!
+//! Every method implements [`RootFinder`], and looks for the root in an
+//! interval `[lower, upper]`, from a `guess`:
+//!
+//! - Bracketing methods, which need a sign change over the interval and
+//!   then always converge:
+//!   - [`Brent`]: inverse quadratic interpolation and secant steps,
+//!     falling back to bisection (Brent, 1973).
+//!   - [`Ridders`]: exponential fitting of the function at the mid point
+//!     (Ridders, 1979), with quadratic convergence.
+//! - Derivative based methods, from the guess:
+//!   - [`Newton`]: quadratic convergence, with the first derivative.
+//!   - [`Halley`]: cubic convergence, with the first two derivatives.
+//!
+//!   Given a sign change over the interval, they are safeguarded: the
+//!   root stays bracketed, and a step leaving the bracket is replaced by
+//!   bisection. Otherwise the iterates are kept in the interval.
+//!
+//! The function is a [`ScalarFunction`]: any closure `Fn(f64) -> f64`, with
+//! derivatives by finite differences, or an [`AutoDiff`] closure of
+//! hyper-dual numbers, with exact derivatives by forward mode automatic
+//! differentiation.
+
+use crate::autodiff::{second_derivative, HyperDual};
+use std::cell::RefCell;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Root finding error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RootFindingError {
+    /// The interval is empty, or does not contain the guess, or (for the
+    /// bracketing methods) the function does not change sign over it.
+    #[error("Invalid bracket of the root")]
+    InvalidBracket,
+
+    /// The derivative vanished (or is not finite), with no bracket to fall
+    /// back on.
+    #[error("Zero derivative")]
+    ZeroDerivative,
+
+    /// The tolerance was not reached in the maximum number of iterations.
+    #[error("No convergence in the maximum number of iterations")]
+    MaxIterations,
+}
+
+/// Real function of one variable, with its first two derivatives.
+pub trait ScalarFunction {
+    /// Value of the function at `x`.
+    fn value(&self, x: f64) -> f64;
+
+    /// Value, first and second derivatives of the function at `x`.
+    ///
+    /// The default implementation uses central finite differences.
+    fn derivatives(&self, x: f64) -> [f64; 3] {
+        let h = 1e-4 * x.abs().max(1.0);
+        let (down, middle, up) = (self.value(x - h), self.value(x), self.value(x + h));
+
+        [
+            middle,
+            (up - down) / (2.0 * h),
+            (up - 2.0 * middle + down) / (h * h),
+        ]
+    }
+}
+
+/// Function of hyper-dual numbers, with exact derivatives by forward mode
+/// automatic differentiation (see [`crate::autodiff::HyperDual`]).
+///
+/// ```
+/// use RustQuant::autodiff::*;
+/// use RustQuant::math::*;
+///
+/// // The cube root of 2.
+/// let f = AutoDiff(|x: HyperDual| x * x * x - 2.0);
+///
+/// assert_eq!(f.derivatives(1.0), [-1.0, 3.0, 6.0]);
+///
+/// let root = Halley::default().solve(&f, 0.0, 2.0, 1.0).unwrap();
+/// assert!((root - 2_f64.cbrt()).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AutoDiff<F>(pub F);
+
+/// Root finding method.
+pub trait RootFinder {
+    /// Root of `f` in `[lower, upper]`, from `guess` (which the bracketing
+    /// methods ignore).
+    ///
+    /// # Errors
+    ///
+    /// See [`RootFindingError`].
+    fn solve<F>(&self, f: &F, lower: f64, upper: f64, guess: f64) -> Result<f64, RootFindingError>
+    where
+        F: ScalarFunction + ?Sized;
+}
+
+/// Brent's method.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// let root = Brent::default().solve(&|x: f64| x.cos() - x, 0.0, 1.0, 0.5).unwrap();
+///
+/// assert!((root - 0.739_085_133_215_160_6).abs() < 1e-14);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Brent {
+    /// Absolute tolerance on the root.
+    pub tolerance: f64,
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+}
+
+/// Ridders' method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ridders {
+    /// Absolute tolerance on the root.
+    pub tolerance: f64,
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+}
+
+/// Newton's method (safeguarded, given a bracket).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Newton {
+    /// Absolute tolerance on the root.
+    pub tolerance: f64,
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+}
+
+/// Halley's method (safeguarded, given a bracket).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Halley {
+    /// Absolute tolerance on the root.
+    pub tolerance: f64,
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<F: Fn(f64) -> f64> ScalarFunction for F {
+    fn value(&self, x: f64) -> f64 {
+        self(x)
+    }
+}
+
+impl<F: Fn(HyperDual) -> HyperDual> ScalarFunction for AutoDiff<F> {
+    fn value(&self, x: f64) -> f64 {
+        (self.0)(HyperDual::constant(x)).value
+    }
+
+    fn derivatives(&self, x: f64) -> [f64; 3] {
+        let (value, first, second) = second_derivative(&self.0, x);
+
+        [value, first, second]
+    }
+}
+
+macro_rules! impl_root_finder_new {
+    ($($method:ident),*) => {
+        $(
+            impl $method {
+                /// New root finder with the absolute tolerance `tolerance` on
+                /// the root, and at most `max_iterations` iterations.
+                #[must_use]
+                pub fn new(tolerance: f64, max_iterations: usize) -> Self {
+                    Self {
+                        tolerance,
+                        max_iterations,
+                    }
+                }
+            }
+
+            impl Default for $method {
+                /// Tolerance `1e-12`, and at most 100 iterations.
+                fn default() -> Self {
+                    Self::new(1e-12, 100)
+                }
+            }
+        )*
+    };
+}
+
+impl_root_finder_new!(Brent, Ridders, Newton, Halley);
+
+impl RootFinder for Brent {
+    #[allow(clippy::many_single_char_names)]
+    fn solve<F>(&self, f: &F, lower: f64, upper: f64, _guess: f64) -> Result<f64, RootFindingError>
+    where
+        F: ScalarFunction + ?Sized,
+    {
+        let (mut a, mut b) = (lower, upper);
+        let (mut fa, mut fb) = (f.value(a), f.value(b));
+        if let Some(root) = bracket(lower, upper, fa, fb)? {
+            return Ok(root);
+        }
+
+        // `b` is the best estimate, `c` the other end of the bracket, and
+        // `a` the previous estimate.
+        let (mut c, mut fc) = (b, fb);
+        let (mut d, mut e) = (b - a, b - a);
+        let mut interpolated = false;
+
+        for _ in 0..self.max_iterations {
+            if (fb > 0.0) == (fc > 0.0) {
+                (c, fc) = (a, fa);
+                (d, e) = (b - a, b - a);
+                interpolated = false;
+            }
+            if fc.abs() < fb.abs() {
+                (a, fa) = (b, fb);
+                (b, fb) = (c, fc);
+                (c, fc) = (a, fa);
+                interpolated = false;
+            }
+
+            let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * self.tolerance;
+            let half = 0.5 * (c - b);
+            if half.abs() <= tolerance || fb == 0.0 {
+                return Ok(b);
+            }
+
+            if e.abs() >= tolerance && fa.abs() > fb.abs() {
+                // Secant step if `a` is `c`, inverse quadratic otherwise.
+                let s = fb / fa;
+                let (mut p, mut q) = if interpolated {
+                    let (q, r) = (fa / fc, fb / fc);
+                    (
+                        s * (2.0 * half * q * (q - r) - (b - a) * (r - 1.0)),
+                        (q - 1.0) * (r - 1.0) * (s - 1.0),
+                    )
+                } else {
+                    (2.0 * half * s, 1.0 - s)
+                };
+                if p > 0.0 {
+                    q = -q;
+                }
+                p = p.abs();
+
+                if 2.0 * p < (3.0 * half * q - (tolerance * q).abs()).min((e * q).abs()) {
+                    e = d;
+                    d = p / q;
+                } else {
+                    d = half;
+                    e = d;
+                }
+            } else {
+                d = half;
+                e = d;
+            }
+
+            (a, fa) = (b, fb);
+            b += if d.abs() > tolerance {
+                d
+            } else {
+                tolerance.copysign(half)
+            };
+            fb = f.value(b);
+            interpolated = true;
+        }
+
+        Err(RootFindingError::MaxIterations)
+    }
+}
+
+impl RootFinder for Ridders {
+    fn solve<F>(&self, f: &F, lower: f64, upper: f64, _guess: f64) -> Result<f64, RootFindingError>
+    where
+        F: ScalarFunction + ?Sized,
+    {
+        let (mut low, mut high) = (lower, upper);
+        let (mut f_low, mut f_high) = (f.value(low), f.value(high));
+        if let Some(root) = bracket(lower, upper, f_low, f_high)? {
+            return Ok(root);
+        }
+
+        let mut root = f64::NAN;
+
+        for _ in 0..self.max_iterations {
+            let middle = 0.5 * (low + high);
+            let f_middle = f.value(middle);
+            let s = (f_middle * f_middle - f_low * f_high).sqrt();
+            if s == 0.0 {
+                return Ok(middle);
+            }
+
+            let next = middle + (middle - low) * (f_low - f_high).signum() * f_middle / s;
+            if (next - root).abs() <= self.tolerance {
+                return Ok(next);
+            }
+            root = next;
+
+            let f_root = f.value(root);
+            if f_root == 0.0 {
+                return Ok(root);
+            }
+
+            if (f_middle > 0.0) != (f_root > 0.0) {
+                (low, f_low) = (middle, f_middle);
+                (high, f_high) = (root, f_root);
+            } else if (f_low > 0.0) != (f_root > 0.0) {
+                (high, f_high) = (root, f_root);
+            } else {
+                (low, f_low) = (root, f_root);
+            }
+
+            if (high - low).abs() <= self.tolerance {
+                return Ok(root);
+            }
+        }
+
+        Err(RootFindingError::MaxIterations)
+    }
+}
+
+impl RootFinder for Newton {
+    fn solve<F>(&self, f: &F, lower: f64, upper: f64, guess: f64) -> Result<f64, RootFindingError>
+    where
+        F: ScalarFunction + ?Sized,
+    {
+        safeguarded(
+            f,
+            lower,
+            upper,
+            guess,
+            (self.tolerance, self.max_iterations),
+            |[value, first, _]| value / first,
+        )
+    }
+}
+
+impl RootFinder for Halley {
+    fn solve<F>(&self, f: &F, lower: f64, upper: f64, guess: f64) -> Result<f64, RootFindingError>
+    where
+        F: ScalarFunction + ?Sized,
+    {
+        safeguarded(
+            f,
+            lower,
+            upper,
+            guess,
+            (self.tolerance, self.max_iterations),
+            |[value, first, second]| 2.0 * value * first / (2.0 * first * first - value * second),
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Root of a decreasing function, bracketed by stepping out from `guess`
+// (tripling the width of the bracket at most 40 times) and found by
+// Brent's method. The function may have side effects, e.g. setting the
+// rate being solved for on a curve, so the caller should evaluate it at
+// the root afterwards.
+pub(crate) fn solve_decreasing<F: FnMut(f64) -> f64>(
+    f: F,
+    guess: f64,
+) -> Result<f64, RootFindingError> {
+    const MAX_EXPANSIONS: usize = 40;
+
+    let f = RefCell::new(f);
+    let f = |x: f64| (*f.borrow_mut())(x);
+
+    let (mut lo, mut hi) = (guess - 0.01, guess + 0.01);
+    let mut expansions = 0..MAX_EXPANSIONS;
+
+    while f(lo) < 0.0 {
+        expansions.next().ok_or(RootFindingError::InvalidBracket)?;
+        lo -= 2.0 * (hi - lo);
+    }
+    while f(hi) > 0.0 {
+        expansions.next().ok_or(RootFindingError::InvalidBracket)?;
+        hi += 2.0 * (hi - lo);
+    }
+
+    Brent::new(1e-15, 200).solve(&f, lo, hi, guess)
+}
+
+// Checks the bracket of a bracketing method, and returns an end point if it
+// is a root.
+fn bracket(
+    lower: f64,
+    upper: f64,
+    f_lower: f64,
+    f_upper: f64,
+) -> Result<Option<f64>, RootFindingError> {
+    if lower.is_nan() || upper.is_nan() || lower >= upper {
+        return Err(RootFindingError::InvalidBracket);
+    }
+
+    if f_lower == 0.0 {
+        Ok(Some(lower))
+    } else if f_upper == 0.0 {
+        Ok(Some(upper))
+    } else if f_lower * f_upper < 0.0 {
+        Ok(None)
+    } else {
+        Err(RootFindingError::InvalidBracket)
+    }
+}
+
+// Iterations x -= step(f(x), f'(x), f''(x)) from the guess, kept in the
+// bracket of the root (bisecting when a step leaves it) if the function
+// changes sign over `[lower, upper]`, and in the interval otherwise.
+fn safeguarded<F, S>(
+    f: &F,
+    lower: f64,
+    upper: f64,
+    guess: f64,
+    (tolerance, max_iterations): (f64, usize),
+    step: S,
+) -> Result<f64, RootFindingError>
+where
+    F: ScalarFunction + ?Sized,
+    S: Fn([f64; 3]) -> f64,
+{
+    if !(lower < upper && (lower..=upper).contains(&guess)) {
+        return Err(RootFindingError::InvalidBracket);
+    }
+
+    // The ends of the bracket where the function is negative and positive.
+    let (f_lower, f_upper) = (f.value(lower), f.value(upper));
+    let mut bracket = if f_lower < 0.0 && f_upper > 0.0 {
+        Some((lower, upper))
+    } else if f_lower > 0.0 && f_upper < 0.0 {
+        Some((upper, lower))
+    } else {
+        None
+    };
+
+    let mut x = guess;
+
+    for _ in 0..max_iterations {
+        let derivatives = f.derivatives(x);
+        if derivatives[0] == 0.0 {
+            return Ok(x);
+        }
+
+        let mut next = x - step(derivatives);
+
+        if let Some((negative, positive)) = bracket.as_mut() {
+            if derivatives[0] < 0.0 {
+                *negative = x;
+            } else {
+                *positive = x;
+            }
+
+            let (low, high) = (negative.min(*positive), negative.max(*positive));
+            if !(next > low && next < high) {
+                next = 0.5 * (low + high);
+            }
+        } else if next.is_finite() {
+            next = next.clamp(lower, upper);
+        } else {
+            return Err(RootFindingError::ZeroDerivative);
+        }
+
+        if (next - x).abs() <= tolerance {
+            return Ok(next);
+        }
+        x = next;
+    }
+
+    Err(RootFindingError::MaxIterations)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_root_finding {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::autodiff::Powi;
+
+    fn check<R: RootFinder>(method: &R) {
+        // Dottie number, and a root of a polynomial with a nearby
+        // stationary point.
+        let cos = |x: f64| x.cos() - x;
+        let polynomial = |x: f64| x.powi(3) - 2.0 * x - 5.0;
+
+        assert_approx_equal!(
+            method.solve(&cos, 0.0, 1.0, 0.5).unwrap(),
+            0.739_085_133_215_160_6,
+            1e-12
+        );
+        assert_approx_equal!(
+            method.solve(&polynomial, 2.0, 3.0, 2.5).unwrap(),
+            2.094_551_481_542_326_5,
+            1e-12
+        );
+
+        // A root at an end point of the interval.
+        assert_approx_equal!(
+            method.solve(&|x: f64| x - 1.0, 1.0, 2.0, 1.0).unwrap(),
+            1.0,
+            1e-12
+        );
+
+        assert_eq!(
+            method.solve(&cos, 1.0, 0.0, 0.5),
+            Err(RootFindingError::InvalidBracket)
+        );
+    }
+
+    #[test]
+    fn test_root_finders() {
+        check(&Brent::default());
+        check(&Ridders::default());
+        check(&Newton::default());
+        check(&Halley::default());
+
+        // The bracketing methods need a sign change.
+        let square = |x: f64| x * x - 4.0;
+        assert_eq!(
+            Brent::default().solve(&square, -3.0, 3.0, 0.0),
+            Err(RootFindingError::InvalidBracket)
+        );
+        assert_eq!(
+            Ridders::default().solve(&square, -3.0, 3.0, 0.0),
+            Err(RootFindingError::InvalidBracket)
+        );
+
+        // Newton's method does not, but fails at a stationary point.
+        assert_approx_equal!(
+            Newton::default().solve(&square, -3.0, 3.0, 1.0).unwrap(),
+            2.0,
+            1e-12
+        );
+        assert_eq!(
+            Newton::default().solve(&AutoDiff(|x: HyperDual| x * x - 4.0), -3.0, 3.0, 0.0),
+            Err(RootFindingError::ZeroDerivative)
+        );
+    }
+
+    #[test]
+    fn test_safeguarding() {
+        // Newton's method alone cycles between 0 and 1 on x^3 - 2 x + 2
+        // from 0: the bracket forces convergence.
+        let f = AutoDiff(|x: HyperDual| x.powi(3) - 2.0 * x + 2.0);
+        let root = Newton::default().solve(&f, -3.0, 1.0, 0.0).unwrap();
+        assert_approx_equal!(root, -1.769_292_354_238_631_4, 1e-12);
+
+        // Without a bracket, it does not converge.
+        assert_eq!(
+            Newton::new(1e-12, 50).solve(&f, -0.5, 1.0, 0.0),
+            Err(RootFindingError::MaxIterations)
+        );
+
+        // Flat tails, where the unguarded steps overshoot.
+        let arctan = AutoDiff(|x: HyperDual| x.atan() - 1.0);
+        let root = Halley::default().solve(&arctan, -10.0, 10.0, 8.0).unwrap();
+        assert_approx_equal!(root, 1_f64.tan(), 1e-12);
+    }
+
+    #[test]
+    fn test_auto_diff() {
+        let f = AutoDiff(|x: HyperDual| x.exp() * x.sin());
+        let [value, first, second] = f.derivatives(0.7);
+
+        assert_approx_equal!(value, 0.7_f64.exp() * 0.7_f64.sin(), 1e-15);
+        assert_approx_equal!(
+            first,
+            0.7_f64.exp() * (0.7_f64.sin() + 0.7_f64.cos()),
+            1e-14
+        );
+        assert_approx_equal!(second, 2.0 * 0.7_f64.exp() * 0.7_f64.cos(), 1e-14);
+
+        // Finite differences of a closure.
+        let [_, first_fd, second_fd] = (|x: f64| x.exp() * x.sin()).derivatives(0.7);
+        assert_approx_equal!(first_fd, first, 1e-7);
+        assert_approx_equal!(second_fd, second, 1e-6);
+    }
+
+    #[test]
+    fn test_solve_decreasing() {
+        let root = solve_decreasing(|x: f64| 2.0 - x.exp(), 100.0).unwrap();
+        assert_approx_equal!(root, 2_f64.ln(), 1e-15);
+
+        // No root, or not a number: the bracket is not found.
+        assert_eq!(
+            solve_decreasing(|x: f64| 1.0 + (-x).exp(), 0.0),
+            Err(RootFindingError::InvalidBracket)
+        );
+        assert_eq!(
+            solve_decreasing(|_| f64::NAN, 0.0),
+            Err(RootFindingError::InvalidBracket)
+        );
+    }
+}
@@ -0,0 +1,245 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Global minimisation by simulated annealing (Kirkpatrick et al., 1983).
+//!
+//! A random walk in the box, from the starting point, accepts every
+//! downhill move and an uphill move of `Δ` with probability `exp(-Δ / T)`,
+//! the Metropolis criterion. The temperature `T` is lowered by a
+//! [`CoolingSchedule`], so that the walk escapes local minima early on and
+//! settles later. The moves are Gaussian, with a standard deviation of
+//! `step` times the width of the box.
+//!
+//! The search is seeded, so that results are reproducible, and needs finite
+//! bounds. The best point visited is returned: its precision is that of the
+//! moves, and a local optimiser should refine it (see [`Minimizer::then`]).
+
+use super::{value_or_infinity, Bounds, Minimizer, Minimum, ObjectiveFunction, OptimizationError};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Temperature `T_k` at iteration `k`, from the initial temperature `T_0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoolingSchedule {
+    /// `T_k = T_0 rate^k`, with `0 < rate < 1`.
+    Exponential(f64),
+    /// `T_k = T_0 / (1 + k)`, as in fast simulated annealing (Szu and
+    /// Hartley, 1987).
+    Fast,
+    /// `T_k = T_0 / ln(e + k)`, the slow schedule of the convergence
+    /// results of Geman and Geman (1984).
+    Logarithmic,
+}
+
+/// Simulated annealing optimiser.
+///
+/// ```
+/// use RustQuant::math::*;
+///
+/// // Himmelblau's function: four global minima, one at (3, 2).
+/// let himmelblau = |x: &[f64]| (x[0] * x[0] + x[1] - 11.0).powi(2) + (x[0] + x[1] * x[1] - 7.0).powi(2);
+/// let bounds = Bounds::new(vec![0.0; 2], vec![5.0; 2]).unwrap();
+///
+/// let result = SimulatedAnnealing::default()
+///     .then(NelderMead::default())
+///     .minimize(&himmelblau, &[0.0, 0.0], Some(&bounds))
+///     .unwrap();
+///
+/// assert!((result.minimizer[0] - 3.0).abs() < 1e-4);
+/// assert!((result.minimizer[1] - 2.0).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulatedAnnealing {
+    /// Initial temperature, on the scale of the differences of the
+    /// function between local minima.
+    pub initial_temperature: f64,
+    /// Cooling schedule.
+    pub cooling: CoolingSchedule,
+    /// Standard deviation of the moves, relative to the width of the box.
+    pub step: f64,
+    /// Number of iterations (moves proposed).
+    pub max_iterations: usize,
+    /// Seed of the random number generator.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CoolingSchedule {
+    /// Temperature at iteration `k`, from the initial temperature `t_0`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn temperature(&self, t_0: f64, k: usize) -> f64 {
+        let k = k as f64;
+
+        match self {
+            Self::Exponential(rate) => t_0 * rate.powf(k),
+            Self::Fast => t_0 / (1.0 + k),
+            Self::Logarithmic => t_0 / (std::f64::consts::E + k).ln(),
+        }
+    }
+}
+
+impl Default for SimulatedAnnealing {
+    /// Initial temperature 10, exponential cooling at rate `0.999`, step
+    /// `0.1`, 20000 iterations and seed 42.
+    fn default() -> Self {
+        Self::new(10.0, CoolingSchedule::Exponential(0.999), 0.1, 20_000)
+    }
+}
+
+impl SimulatedAnnealing {
+    /// New simulated annealing optimiser, with seed 42.
+    #[must_use]
+    pub const fn new(
+        initial_temperature: f64,
+        cooling: CoolingSchedule,
+        step: f64,
+        max_iterations: usize,
+    ) -> Self {
+        Self {
+            initial_temperature,
+            cooling,
+            step,
+            max_iterations,
+            seed: 42,
+        }
+    }
+}
+
+impl Minimizer for SimulatedAnnealing {
+    /// # Errors
+    ///
+    /// - [`OptimizationError::UnboundedSearch`] without finite bounds.
+    /// - [`OptimizationError::DimensionMismatch`] if the bounds and `x0`
+    ///   have different dimensions.
+    fn minimize<F>(
+        &self,
+        f: &F,
+        x0: &[f64],
+        bounds: Option<&Bounds>,
+    ) -> Result<Minimum, OptimizationError>
+    where
+        F: ObjectiveFunction + ?Sized,
+    {
+        let bounds = Bounds::check_finite(bounds, x0)?;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let widths: Vec<f64> = bounds
+            .lower()
+            .iter()
+            .zip(bounds.upper())
+            .map(|(l, u)| u - l)
+            .collect();
+
+        let mut x = x0.to_vec();
+        bounds.project(&mut x);
+        let mut value = value_or_infinity(f, &x);
+        let (mut best, mut best_value) = (x.clone(), value);
+
+        for k in 0..self.max_iterations {
+            let temperature = self.cooling.temperature(self.initial_temperature, k);
+            let mut candidate: Vec<f64> = x
+                .iter()
+                .zip(&widths)
+                .map(|(x, width)| x + self.step * width * rng.sample::<f64, _>(StandardNormal))
+                .collect();
+            bounds.project(&mut candidate);
+            let candidate_value = value_or_infinity(f, &candidate);
+
+            // Metropolis criterion.
+            let difference = candidate_value - value;
+            if difference <= 0.0 || rng.gen::<f64>() < (-difference / temperature).exp() {
+                (x, value) = (candidate, candidate_value);
+
+                if value < best_value {
+                    (best, best_value) = (x.clone(), value);
+                }
+            }
+        }
+
+        Ok(Minimum {
+            minimizer: best,
+            minimum: best_value,
+            iterations: self.max_iterations,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_simulated_annealing {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::Lbfgs;
+
+    #[test]
+    fn test_cooling_schedules() {
+        assert_approx_equal!(
+            CoolingSchedule::Exponential(0.5).temperature(8.0, 3),
+            1.0,
+            1e-15
+        );
+        assert_approx_equal!(CoolingSchedule::Fast.temperature(8.0, 3), 2.0, 1e-15);
+        assert_approx_equal!(CoolingSchedule::Logarithmic.temperature(8.0, 0), 8.0, 1e-15);
+    }
+
+    #[test]
+    fn test_simulated_annealing() {
+        // A double well, with the global minimum near x = -1, and a local
+        // minimum near x = 1, where the walk starts.
+        let double_well = |x: &[f64]| (x[0] * x[0] - 1.0).powi(2) + 0.15 * x[0] + x[1] * x[1];
+        let bounds = Bounds::new(vec![-2.0; 2], vec![2.0; 2]).unwrap();
+
+        // A local optimiser stays in the well of the starting point.
+        let local = Lbfgs::default()
+            .minimize(&double_well, &[1.0, 0.5], Some(&bounds))
+            .unwrap();
+        assert!(local.minimizer[0] > 0.0);
+
+        for cooling in [
+            CoolingSchedule::Exponential(0.999),
+            CoolingSchedule::Fast,
+            CoolingSchedule::Logarithmic,
+        ] {
+            let annealing = SimulatedAnnealing::new(10.0, cooling, 0.2, 20_000);
+            let result = annealing
+                .then(Lbfgs::default())
+                .minimize(&double_well, &[1.0, 0.5], Some(&bounds))
+                .unwrap();
+
+            assert!(result.minimizer[0] < 0.0, "{cooling:?}: {result:?}");
+            assert!(result.minimum < local.minimum);
+            assert_approx_equal!(result.minimizer[1], 0.0, 1e-8);
+
+            // Reproducible given the seed.
+            assert_eq!(
+                annealing
+                    .then(Lbfgs::default())
+                    .minimize(&double_well, &[1.0, 0.5], Some(&bounds))
+                    .unwrap(),
+                result
+            );
+        }
+
+        assert_eq!(
+            SimulatedAnnealing::default().minimize(&double_well, &[0.0; 2], None),
+            Err(OptimizationError::UnboundedSearch)
+        );
+    }
+}
@@ -0,0 +1,626 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Hull-White trinomial tree, for instruments with early exercise.
+//!
+//! The construction follows Brigo and Mercurio (2006, Appendix F). The
+//! tree is first built for the zero-mean process $dx = -a x dt + \sigma dW$
+//! on a time grid with possibly uneven steps: from a node at $x$, the tree
+//! branches to the three nodes of the next time around the conditional
+//! mean $x e^{-a \Delta t}$, spaced $\Delta x = V \sqrt{3}$ apart with $V$
+//! the conditional standard deviation, with probabilities matching the
+//! first two moments. The short rate is then $r = x + \alpha_i$ over the
+//! $i$-th step, with the shifts $\alpha_i$ fitted by forward induction
+//! (Arrow-Debreu prices) so that the tree reprices the initial discount
+//! curve on the time grid.
+//!
+//! Instruments are valued by backward induction, applying the exercise
+//! decisions on the exercise dates: callable and puttable bonds, and
+//! Bermudan swaptions.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::bonds::CallableBond;
+use crate::instruments::rates::{BermudanSwaption, SwapDirection};
+use crate::math::solve_decreasing;
+use crate::math::RootFindingError;
+use crate::models::HullWhiteModel;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Trinomial tree of the Hull-White short rate, fitted to the initial
+/// discount curve of a [`HullWhiteModel`].
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::models::*;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let model = HullWhiteModel::new(
+///     YieldCurve::new(BTreeMap::from([
+///         (today, 0.02),
+///         (today + Duration::days(3650), 0.04),
+///     ])),
+///     0.1,
+///     0.01,
+/// );
+///
+/// let tree = HullWhiteTree::new(&model, &[2.5, 5.0], 100);
+///
+/// // The tree reprices the initial curve on its time grid.
+/// let bond = tree.values_at(0, &[(5.0, 1.0)])[0];
+/// assert!((bond - model.market_discount_factor(5.0)).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HullWhiteTree {
+    // Time grid, starting at 0.
+    times: Vec<f64>,
+    // Node spacing at each time.
+    dx: Vec<f64>,
+    // Index of the lowest node at each time (x = index * dx).
+    lowest: Vec<i64>,
+    // Shift of the short rate over each step.
+    alpha: Vec<f64>,
+    // Branching from each node of each time, but the last.
+    branches: Vec<Vec<Branch>>,
+}
+
+// Branching from a node to three consecutive nodes of the next time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Branch {
+    // Position of the middle node at the next time.
+    middle: usize,
+    // Down, middle and up probabilities.
+    probabilities: [f64; 3],
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HullWhiteTree {
+    /// Builds the tree with about `steps` steps up to the last of the
+    /// `event_times`, all of which (if positive) are on the time grid.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    pub fn new(model: &HullWhiteModel, event_times: &[f64], steps: usize) -> Self {
+        let (a, sigma) = (model.a, model.sigma);
+        let times = time_grid(event_times, steps);
+        let n = times.len() - 1;
+
+        let mut tree = Self {
+            times,
+            dx: vec![0.0],
+            lowest: vec![0],
+            alpha: Vec::with_capacity(n),
+            branches: Vec::with_capacity(n),
+        };
+
+        // Tree of x, the short rate less its shift.
+        for i in 0..n {
+            let dt = tree.times[i + 1] - tree.times[i];
+            let V = sigma * (-(-2.0 * a * dt).exp_m1() / (2.0 * a)).sqrt();
+            let dx = V * 3.0_f64.sqrt();
+
+            let targets: Vec<(i64, [f64; 3])> = tree
+                .x(i)
+                .iter()
+                .map(|x| {
+                    let mean = x * (-a * dt).exp();
+                    let k = (mean / dx).round();
+                    let eta = mean - k * dx;
+                    let (e2, e1) = (eta * eta / (6.0 * V * V), eta / (2.0 * 3.0_f64.sqrt() * V));
+
+                    (
+                        k as i64,
+                        [
+                            1.0 / 6.0 + e2 - e1,
+                            2.0 / 3.0 - 2.0 * e2,
+                            1.0 / 6.0 + e2 + e1,
+                        ],
+                    )
+                })
+                .collect();
+
+            let lowest = targets.iter().map(|(k, _)| k).min().unwrap_or(&0) - 1;
+
+            tree.branches.push(
+                targets
+                    .iter()
+                    .map(|(k, probabilities)| Branch {
+                        middle: usize::try_from(k - lowest).unwrap_or(1),
+                        probabilities: *probabilities,
+                    })
+                    .collect(),
+            );
+            tree.dx.push(dx);
+            tree.lowest.push(lowest);
+        }
+
+        // Shifts fitting the discount curve, by forward induction of the
+        // Arrow-Debreu prices.
+        let mut arrow_debreu = vec![1.0];
+
+        for i in 0..n {
+            let dt = tree.times[i + 1] - tree.times[i];
+            let x = tree.x(i);
+
+            let sum: f64 = arrow_debreu
+                .iter()
+                .zip(&x)
+                .map(|(q, x)| q * (-x * dt).exp())
+                .sum();
+            let alpha = (sum / model.market_discount_factor(tree.times[i + 1])).ln() / dt;
+
+            let mut next = vec![0.0; tree.nodes(i + 1)];
+            for ((q, x), branch) in arrow_debreu.iter().zip(&x).zip(&tree.branches[i]) {
+                let discounted = q * (-(alpha + x) * dt).exp();
+                for (m, p) in branch.probabilities.iter().enumerate() {
+                    next[branch.middle + m - 1] += discounted * p;
+                }
+            }
+
+            tree.alpha.push(alpha);
+            arrow_debreu = next;
+        }
+
+        tree
+    }
+
+    /// Time grid of the tree, starting at 0.
+    #[must_use]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Index of time `t` on the time grid, if it is on the grid.
+    #[must_use]
+    pub fn time_index(&self, t: f64) -> Option<usize> {
+        self.times.iter().position(|s| (s - t).abs() < TOLERANCE)
+    }
+
+    /// Number of nodes at time index `i`.
+    #[must_use]
+    pub fn nodes(&self, i: usize) -> usize {
+        match self.branches.get(i) {
+            Some(branches) => branches.len(),
+            None => self.branches.last().map_or(1, |branches| {
+                branches.iter().map(|b| b.middle).max().unwrap_or(0) + 2
+            }),
+        }
+    }
+
+    /// Short rates at the nodes of time index `i`, over the step to the
+    /// next time (so `i` must be before the last time).
+    #[must_use]
+    pub fn short_rates(&self, i: usize) -> Vec<f64> {
+        self.x(i).iter().map(|x| x + self.alpha[i]).collect()
+    }
+
+    /// Values at the nodes of time index `i` of a claim worth `values` at
+    /// the nodes of the next time: the discounted expectation.
+    #[must_use]
+    pub fn step_back(&self, i: usize, values: &[f64]) -> Vec<f64> {
+        let dt = self.times[i + 1] - self.times[i];
+
+        self.short_rates(i)
+            .iter()
+            .zip(&self.branches[i])
+            .map(|(r, branch)| {
+                let expectation: f64 = branch
+                    .probabilities
+                    .iter()
+                    .enumerate()
+                    .map(|(m, p)| p * values[branch.middle + m - 1])
+                    .sum();
+
+                (-r * dt).exp() * expectation
+            })
+            .collect()
+    }
+
+    /// Values at the nodes of time index `i` of the cash flows
+    /// `(time, amount)` paid at or after the time of `i`, by backward
+    /// induction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a cash flow time is not on the time grid.
+    #[must_use]
+    pub fn values_at(&self, i: usize, cashflows: &[(f64, f64)]) -> Vec<f64> {
+        let n = self.times.len() - 1;
+        let mut amounts = vec![0.0; n + 1];
+        for (t, amount) in cashflows {
+            let j = self
+                .time_index(*t)
+                .expect("The cash flow time is not on the time grid.");
+            amounts[j] += amount;
+        }
+
+        let mut values = vec![amounts[n]; self.nodes(n)];
+        for j in (i..n).rev() {
+            values = self.step_back(j, &values);
+            for v in &mut values {
+                *v += amounts[j];
+            }
+        }
+
+        values
+    }
+
+    // Zero-mean process at the nodes of time index `i`.
+    #[allow(clippy::cast_possible_wrap, clippy::cast_precision_loss)]
+    fn x(&self, i: usize) -> Vec<f64> {
+        (0..self.nodes(i))
+            .map(|node| (self.lowest[i] + node as i64) as f64 * self.dx[i])
+            .collect()
+    }
+}
+
+impl HullWhiteModel {
+    /// Price of a callable or puttable bond, by backward induction on a
+    /// trinomial tree with about `steps` time steps. When a date is on
+    /// both schedules, the holder's put is exercised before the issuer's
+    /// call. Cash flows, calls and puts up to the initial date of the
+    /// curve are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has no cash flows after the initial date.
+    #[must_use]
+    pub fn callable_bond_price(&self, bond: &CallableBond, steps: usize) -> f64 {
+        let after = |schedule: &std::collections::BTreeMap<_, f64>| -> Vec<(f64, f64)> {
+            schedule
+                .iter()
+                .map(|(date, amount)| (self.time(*date), *amount))
+                .filter(|(t, _)| *t > 0.0)
+                .collect()
+        };
+        let (cashflows, calls, puts) = (
+            after(&bond.cashflows),
+            after(&bond.call_schedule),
+            after(&bond.put_schedule),
+        );
+        assert!(!cashflows.is_empty(), "The bond has no cash flows.");
+
+        let events: Vec<f64> = cashflows
+            .iter()
+            .chain(&calls)
+            .chain(&puts)
+            .map(|(t, _)| *t)
+            .collect();
+        let tree = HullWhiteTree::new(self, &events, steps);
+
+        // Amounts by time index.
+        let by_index = |schedule: &[(f64, f64)]| -> Vec<Option<f64>> {
+            let mut amounts = vec![None; tree.times().len()];
+            for (t, amount) in schedule {
+                let i = tree.time_index(*t).unwrap_or(0);
+                amounts[i] = Some(amounts[i].unwrap_or(0.0) + amount);
+            }
+            amounts
+        };
+        let (cashflows, calls, puts) = (by_index(&cashflows), by_index(&calls), by_index(&puts));
+
+        let n = tree.times().len() - 1;
+        let mut values = vec![0.0; tree.nodes(n)];
+
+        for i in (0..=n).rev() {
+            if i < n {
+                values = tree.step_back(i, &values);
+            }
+            for v in &mut values {
+                if let Some(put) = puts[i] {
+                    *v = v.max(put);
+                }
+                if let Some(call) = calls[i] {
+                    *v = v.min(call);
+                }
+                *v += cashflows[i].unwrap_or(0.0);
+            }
+        }
+
+        values[0]
+    }
+
+    /// Option-adjusted spread of a callable or puttable bond from its dirty
+    /// price on the initial date of the curve: the parallel shift of the
+    /// zero rates at which the tree price matches the market price. For a
+    /// bond without calls or puts, this is its Z-spread.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RootFindingError` if no spread reprices the bond.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bond has no cash flows after the initial date.
+    pub fn option_adjusted_spread(
+        &self,
+        bond: &CallableBond,
+        dirty_price: f64,
+        steps: usize,
+    ) -> Result<f64, RootFindingError> {
+        solve_decreasing(
+            |spread| {
+                let shifted = Self::new(self.curve.shifted(spread), self.a, self.sigma);
+                shifted.callable_bond_price(bond, steps) - dirty_price
+            },
+            0.0,
+        )
+    }
+
+    /// Price of a Bermudan swaption, by backward induction on a trinomial
+    /// tree with about `steps` time steps, on the initial curve
+    /// (single-curve: the floating leg is worth par at the start of the
+    /// periods entered, and its spread is ignored). Exercise dates before
+    /// the initial date of the curve are ignored.
+    #[must_use]
+    pub fn bermudan_swaption_price(&self, swaption: &BermudanSwaption, steps: usize) -> f64 {
+        let sign = match swaption.swap.direction {
+            SwapDirection::Payer => 1.0,
+            SwapDirection::Receiver => -1.0,
+        };
+
+        // Cash flows of the swap entered on each exercise date, per unit
+        // notional, for the payer: receives the notional at the start of
+        // the periods, and pays back the fixed coupons and the notional.
+        let exercises: Vec<(f64, Vec<(f64, f64)>)> = swaption
+            .exercise_dates
+            .iter()
+            .filter(|date| self.time(**date) >= 0.0)
+            .filter_map(|date| {
+                let periods = swaption.periods_from(*date);
+                let (start, ..) = periods.first()?;
+                let (_, end, _) = periods.last()?;
+
+                let cashflows = std::iter::once((self.time(*start), 1.0))
+                    .chain(
+                        periods
+                            .iter()
+                            .map(|(_, end, tau)| (self.time(*end), -swaption.strike() * tau)),
+                    )
+                    .chain(std::iter::once((self.time(*end), -1.0)))
+                    .collect();
+
+                Some((self.time(*date), cashflows))
+            })
+            .collect();
+
+        let events: Vec<f64> = exercises
+            .iter()
+            .flat_map(|(t, cashflows)| std::iter::once(*t).chain(cashflows.iter().map(|c| c.0)))
+            .collect();
+        let tree = HullWhiteTree::new(self, &events, steps);
+
+        let mut underlying = vec![None; tree.times().len()];
+        for (t, cashflows) in &exercises {
+            let i = tree.time_index(*t).unwrap_or(0);
+            underlying[i] = Some(tree.values_at(i, cashflows));
+        }
+
+        let n = tree.times().len() - 1;
+        let mut values = vec![0.0; tree.nodes(n)];
+
+        for i in (0..=n).rev() {
+            if i < n {
+                values = tree.step_back(i, &values);
+            }
+            if let Some(swap) = &underlying[i] {
+                for (v, u) in values.iter_mut().zip(swap) {
+                    *v = v.max(sign * u);
+                }
+            }
+        }
+
+        swaption.swap.notional * values[0]
+    }
+}
+
+// Times closer than this are the same point of the grid.
+const TOLERANCE: f64 = 1e-10;
+
+// Time grid from 0 to the last event time, with all the positive event
+// times, and evenly spaced steps of at most the last time over `steps`
+// between them.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn time_grid(event_times: &[f64], steps: usize) -> Vec<f64> {
+    let mut events: Vec<f64> = std::iter::once(0.0)
+        .chain(event_times.iter().copied().filter(|t| *t > TOLERANCE))
+        .collect();
+    events.sort_by(f64::total_cmp);
+    events.dedup_by(|a, b| (*a - *b).abs() < TOLERANCE);
+
+    let dt = events[events.len() - 1] / steps.max(1) as f64;
+    let mut times = vec![0.0];
+
+    for w in events.windows(2) {
+        let m = ((w[1] - w[0]) / dt - TOLERANCE).ceil().max(1.0) as usize;
+        times.extend((1..=m).map(|k| w[0] + (w[1] - w[0]) * k as f64 / m as f64));
+    }
+
+    times
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hull_white_tree {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{days, yield_curve};
+    use crate::curves::Curve;
+    use crate::instruments::bonds::FixedRateBond;
+    use crate::instruments::options::TypeFlag;
+    use crate::instruments::rates::{InterestRateSwap, Swaption, SwaptionSettlement};
+    use crate::models::ShortRateModel;
+    use crate::time::{PaymentFrequency, Schedule};
+    use time::{Duration, OffsetDateTime};
+
+    fn model() -> HullWhiteModel {
+        HullWhiteModel::new(
+            yield_curve(&[(0, 0.02), (730, 0.03), (4000, 0.04)]),
+            0.1,
+            0.01,
+        )
+    }
+
+    #[test]
+    fn test_tree() {
+        let model = model();
+        let tree = HullWhiteTree::new(&model, &[0.75, 3.0, 7.3], 120);
+
+        // Probabilities are valid, and the tree reprices the curve.
+        for branches in &tree.branches {
+            for branch in branches {
+                assert!(branch.probabilities.iter().all(|p| *p > 0.0));
+                assert_approx_equal!(branch.probabilities.iter().sum::<f64>(), 1.0, 1e-14);
+            }
+        }
+        for t in [0.75, 3.0, 7.3] {
+            assert!(tree.time_index(t).is_some());
+            assert_approx_equal!(
+                tree.values_at(0, &[(t, 1.0)])[0],
+                model.market_discount_factor(t),
+                1e-12
+            );
+        }
+
+        // Zero-coupon bond option against the analytic price.
+        let (T, S) = (3.0, 7.3);
+        let K = model.market_discount_factor(S) / model.market_discount_factor(T);
+        let i = tree.time_index(T).unwrap();
+        let bonds = tree.values_at(i, &[(S, 1.0)]);
+        let payoff: Vec<f64> = bonds.iter().map(|p| (p - K).max(0.0)).collect();
+
+        let mut values = payoff;
+        for j in (0..i).rev() {
+            values = tree.step_back(j, &values);
+        }
+        let analytic = model.bond_option_price(K, T, S, TypeFlag::Call);
+        assert_approx_equal!(values[0], analytic, 0.005 * analytic);
+    }
+
+    // Bermudan swaption exercisable on the fixed leg dates from `first`
+    // (in years) on, into a swap ending in 10 years.
+    fn bermudan(direction: SwapDirection, strike: f64, first: i64) -> BermudanSwaption {
+        let schedule = Schedule::new_from_start(days(365), Duration::days(365), 9);
+        let swap = InterestRateSwap::new(100.0, strike, direction, &schedule, &schedule, "");
+        let exercise_dates: Vec<OffsetDateTime> = (first..10).map(|y| days(365 * y)).collect();
+
+        BermudanSwaption::new(swap, &exercise_dates)
+    }
+
+    #[test]
+    fn test_bermudan_swaption() {
+        let model = model();
+
+        for direction in [SwapDirection::Payer, SwapDirection::Receiver] {
+            let swap = bermudan(direction, 0.035, 1).swap;
+
+            // With a single exercise date, Jamshidian's European price.
+            let single = BermudanSwaption::new(swap.clone(), &[days(365 * 3)]);
+            let european = Swaption::new(swap.clone(), days(365 * 3), SwaptionSettlement::Physical);
+
+            let tree = model.bermudan_swaption_price(&single, 200);
+            let analytic = model.swaption_price(&european);
+            assert_approx_equal!(tree, analytic, 0.001 * analytic);
+
+            // The Bermudan is worth more than each of its Europeans.
+            let bermudan = model.bermudan_swaption_price(&bermudan(direction, 0.035, 1), 200);
+            for expiry in 1..10 {
+                let european = BermudanSwaption::new(swap.clone(), &[days(365 * expiry)]);
+                assert!(bermudan >= model.bermudan_swaption_price(&european, 200));
+            }
+        }
+    }
+
+    #[test]
+    fn test_callable_bond() {
+        let model = model();
+        let schedule = Schedule::new_from_start(days(0), Duration::days(365), 10);
+        let straight = CallableBond::new(100.0, 0.035, &schedule);
+
+        // Without calls or puts, the tree reprices the bond on the curve.
+        let discounted: f64 = straight
+            .cashflows
+            .iter()
+            .map(|(date, amount)| amount * model.curve.discount_factor(*date))
+            .sum();
+        assert_approx_equal!(model.callable_bond_price(&straight, 100), discounted, 1e-10);
+
+        // Callable at par = straight bond - Bermudan receiver swaption.
+        let callable = schedule.dates[1..10]
+            .iter()
+            .fold(straight.clone(), |bond, date| bond.with_call(*date, 100.0));
+        let puttable = schedule.dates[1..10]
+            .iter()
+            .fold(straight.clone(), |bond, date| bond.with_put(*date, 100.0));
+
+        let swaption =
+            model.bermudan_swaption_price(&bermudan(SwapDirection::Receiver, 0.035, 1), 100);
+        assert_approx_equal!(
+            model.callable_bond_price(&callable, 100),
+            discounted - swaption,
+            1e-8
+        );
+
+        // Puttable at par = straight bond + Bermudan payer swaption.
+        let swaption =
+            model.bermudan_swaption_price(&bermudan(SwapDirection::Payer, 0.035, 1), 100);
+        assert_approx_equal!(
+            model.callable_bond_price(&puttable, 100),
+            discounted + swaption,
+            1e-8
+        );
+    }
+
+    #[test]
+    fn test_option_adjusted_spread() {
+        let model = model();
+        let schedule = Schedule::new_from_start(days(0), Duration::days(365), 10);
+        let bond = FixedRateBond::new(100.0, 0.035, &schedule, PaymentFrequency::Annually);
+        let straight = CallableBond::from(&bond);
+        let callable = schedule.dates[3..10]
+            .iter()
+            .fold(straight.clone(), |bond, date| bond.with_call(*date, 100.0));
+
+        // Without calls, the OAS is the Z-spread.
+        let price = bond.dirty_price_from_curve(&model.curve, 0.01);
+        assert_approx_equal!(bond.z_spread(&model.curve, price).unwrap(), 0.01, 1e-10);
+        assert_approx_equal!(
+            model.option_adjusted_spread(&straight, price, 100).unwrap(),
+            0.01,
+            1e-8
+        );
+
+        // The Z-spread of a callable bond includes the value of the call
+        // to the issuer, which the OAS removes.
+        let price = HullWhiteModel::new(model.curve.shifted(0.01), model.a, model.sigma)
+            .callable_bond_price(&callable, 100);
+        assert_approx_equal!(
+            model.option_adjusted_spread(&callable, price, 100).unwrap(),
+            0.01,
+            1e-8
+        );
+        assert!(bond.z_spread(&model.curve, price).unwrap() > 0.01);
+    }
+}
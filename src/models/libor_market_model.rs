@@ -0,0 +1,748 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! LIBOR market model (Brace, Gatarek and Musiela, 1997).
+//!
+//! The forward rates $F_i$ of the periods $[T_i, T_{i+1}]$ of a tenor
+//! structure are lognormal, with deterministic volatilities and correlated
+//! Brownian motions:
+//!
+//! $$
+//! \frac{dF_i}{F_i} = \mu_i(t) dt + \sigma_i(t) dW_i, \quad
+//! d\langle W_i, W_j \rangle = \rho_{ij} dt.
+//! $$
+//!
+//! Under the spot LIBOR measure, whose numeraire rolls over the
+//! zero-coupon bond of the next tenor date, the drift of a forward alive
+//! at $t$ is
+//!
+//! $$
+//! \mu_i(t) = \sigma_i(t) \sum_{j = m(t)}^{i}
+//!     \frac{\tau_j \rho_{ij} \sigma_j(t) F_j(t)}{1 + \tau_j F_j(t)},
+//! $$
+//!
+//! with $m(t)$ the index of the first forward not yet fixed. The forwards
+//! are simulated with a log-Euler scheme, the drift being either frozen at
+//! the start of each step or averaged with its value at a predicted end
+//! of step (predictor-corrector).
+//!
+//! Caplets are priced exactly by Black's formula, and swaptions
+//! approximately by Rebonato's formula: the Monte Carlo prices of caps and
+//! swaptions validate the simulation against both.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::Curve;
+use crate::instruments::options::TypeFlag;
+use crate::instruments::rates::{CapFloorType, RateVolatility, SwapDirection};
+use crate::math::integrate;
+use crate::time::{DayCountConvention, DayCounter};
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Rebonato's "abcd" volatility of a forward rate, as a function of its
+/// time to fixing $\tau$: $\sigma(\tau) = (a + b \tau) e^{-c \tau} + d$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbcdVolatility {
+    /// Short end level (with `d`).
+    pub a: f64,
+    /// Slope of the hump.
+    pub b: f64,
+    /// Decay of the hump.
+    pub c: f64,
+    /// Long end level.
+    pub d: f64,
+}
+
+/// Correlation of the forward rates fixing at $T_i$ and $T_j$:
+/// $\rho_{ij} = \rho_\infty + (1 - \rho_\infty) e^{-\beta |T_i - T_j|}$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LmmCorrelation {
+    /// Long-run correlation $\rho_\infty$, between distant forwards.
+    pub long_run: f64,
+    /// Decay $\beta$ of the correlation with the distance in time.
+    pub decay: f64,
+}
+
+/// Approximation of the spot measure drift over a time step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftApproximation {
+    /// Drift frozen at the start of the step (log-Euler).
+    FrozenCurve,
+    /// Average of the drifts at the start of the step and at the end of
+    /// the step predicted with the frozen drift.
+    PredictorCorrector,
+}
+
+/// LIBOR market model on a tenor structure.
+///
+/// ```
+/// use RustQuant::instruments::*;
+/// use RustQuant::models::*;
+///
+/// let model = LiborMarketModel::new(
+///     (0..=10).map(|i| 0.5 * f64::from(i)).collect(),
+///     vec![0.03; 10],
+///     1.0,
+///     AbcdVolatility { a: 0.05, b: 0.1, c: 1.0, d: 0.15 },
+///     LmmCorrelation { long_run: 0.3, decay: 0.2 },
+/// );
+///
+/// // Cap on the forwards 1 to 9, at the money.
+/// let analytic = model.cap_price(1, 10, 0.03, CapFloorType::Cap);
+///
+/// let paths = model.simulate(2_000, 2, DriftApproximation::PredictorCorrector, 42);
+/// let mc = model.monte_carlo_cap_price(&paths, 1, 10, 0.03, CapFloorType::Cap);
+///
+/// assert!((mc.price - analytic).abs() < 4.0 * mc.std_error);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiborMarketModel {
+    /// Tenor dates $T_0 < \dots < T_n$, in years from the valuation date.
+    pub times: Vec<f64>,
+    /// Initial forward rates $F_i(0)$ of the periods $[T_i, T_{i+1}]$.
+    pub forwards: Vec<f64>,
+    /// Discount factor $P(0, T_0)$ to the first tenor date.
+    pub discount_factor: f64,
+    /// Volatility of the forward rates.
+    pub volatility: AbcdVolatility,
+    /// Correlation of the forward rates.
+    pub correlation: LmmCorrelation,
+}
+
+/// Monte Carlo price from simulated forward rates.
+#[derive(Debug, Clone, Copy)]
+pub struct LmmMonteCarloResult {
+    /// Price.
+    pub price: f64,
+    /// Standard error of the price.
+    pub std_error: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl AbcdVolatility {
+    /// Volatility at time to fixing `tau`.
+    #[must_use]
+    pub fn volatility(&self, tau: f64) -> f64 {
+        (self.a + self.b * tau) * (-self.c * tau).exp() + self.d
+    }
+}
+
+impl LmmCorrelation {
+    /// Correlation of the forward rates fixing at `T_i` and `T_j`.
+    #[must_use]
+    pub fn correlation(&self, T_i: f64, T_j: f64) -> f64 {
+        self.long_run + (1.0 - self.long_run) * (-self.decay * (T_i - T_j).abs()).exp()
+    }
+}
+
+impl LiborMarketModel {
+    /// New LIBOR market model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tenor dates are not non-negative and increasing, or if
+    /// there is not one forward rate per period.
+    #[must_use]
+    pub fn new(
+        times: Vec<f64>,
+        forwards: Vec<f64>,
+        discount_factor: f64,
+        volatility: AbcdVolatility,
+        correlation: LmmCorrelation,
+    ) -> Self {
+        assert!(
+            times.first().is_some_and(|t| *t >= 0.0) && times.windows(2).all(|w| w[0] < w[1]),
+            "Tenor dates must be non-negative and increasing."
+        );
+        assert_eq!(
+            forwards.len() + 1,
+            times.len(),
+            "There must be one forward rate per period."
+        );
+
+        Self {
+            times,
+            forwards,
+            discount_factor,
+            volatility,
+            correlation,
+        }
+    }
+
+    /// New LIBOR market model with the forward rates of a discount curve
+    /// between the tenor `dates`, with times in years (Actual/365) from
+    /// the initial date of the curve.
+    #[must_use]
+    pub fn from_curve<C: Curve>(
+        curve: &C,
+        dates: &[OffsetDateTime],
+        volatility: AbcdVolatility,
+        correlation: LmmCorrelation,
+    ) -> Self {
+        let times: Vec<f64> = dates
+            .iter()
+            .map(|date| {
+                DayCounter::day_count_factor(
+                    curve.initial_date(),
+                    *date,
+                    &DayCountConvention::Actual365,
+                )
+            })
+            .collect();
+        let discount_factors = curve.discount_factors(dates);
+
+        let forwards = discount_factors
+            .windows(2)
+            .zip(times.windows(2))
+            .map(|(p, t)| (p[0] / p[1] - 1.0) / (t[1] - t[0]))
+            .collect();
+
+        Self::new(
+            times,
+            forwards,
+            discount_factors[0],
+            volatility,
+            correlation,
+        )
+    }
+
+    /// Number of forward rates.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.forwards.len()
+    }
+
+    /// Whether there are no forward rates.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.forwards.is_empty()
+    }
+
+    /// Accrual $\tau_i = T_{i+1} - T_i$ of the `i`-th period.
+    #[must_use]
+    pub fn accrual(&self, i: usize) -> f64 {
+        self.times[i + 1] - self.times[i]
+    }
+
+    /// Discount factor $P(0, T_k)$ to the `k`-th tenor date.
+    #[must_use]
+    pub fn discount_factor(&self, k: usize) -> f64 {
+        (0..k).fold(self.discount_factor, |p, j| {
+            p / (1.0 + self.accrual(j) * self.forwards[j])
+        })
+    }
+
+    /// Volatility of the `i`-th forward rate at time `t`, zero once fixed.
+    #[must_use]
+    pub fn volatility(&self, i: usize, t: f64) -> f64 {
+        if t < self.times[i] {
+            self.volatility.volatility(self.times[i] - t)
+        } else {
+            0.0
+        }
+    }
+
+    /// Correlation matrix of the forward rates.
+    #[must_use]
+    pub fn correlation_matrix(&self) -> DMatrix<f64> {
+        let n = self.len();
+
+        DMatrix::from_fn(n, n, |i, j| {
+            self.correlation.correlation(self.times[i], self.times[j])
+        })
+    }
+
+    /// Integrated covariance $\rho_{ij} \int_0^T \sigma_i(t) \sigma_j(t) dt$
+    /// of the log forward rates `i` and `j` up to `T`.
+    #[must_use]
+    pub fn covariance(&self, i: usize, j: usize, T: f64) -> f64 {
+        let T = T.min(self.times[i]).min(self.times[j]);
+        if T <= 0.0 {
+            return 0.0;
+        }
+
+        self.correlation.correlation(self.times[i], self.times[j])
+            * integrate(|t| self.volatility(i, t) * self.volatility(j, t), 0.0, T)
+    }
+
+    /// Black volatility of the caplet on the `i`-th forward rate: the
+    /// root-mean-square volatility up to its fixing.
+    #[must_use]
+    pub fn caplet_volatility(&self, i: usize) -> f64 {
+        let T = self.times[i];
+        if T <= 0.0 {
+            return 0.0;
+        }
+
+        (self.covariance(i, i, T) / T).sqrt()
+    }
+
+    /// Price of the caplet (or floorlet) on the `i`-th forward rate, per
+    /// unit notional, by Black's formula.
+    #[must_use]
+    pub fn caplet_price(&self, i: usize, strike: f64, cap_floor_type: CapFloorType) -> f64 {
+        let option = RateVolatility::Lognormal(self.caplet_volatility(i)).forward_price(
+            self.forwards[i],
+            strike,
+            self.times[i],
+            type_flag(cap_floor_type),
+        );
+
+        self.discount_factor(i + 1) * self.accrual(i) * option
+    }
+
+    /// Price of the cap (or floor) on the forward rates `start..end`, per
+    /// unit notional.
+    #[must_use]
+    pub fn cap_price(
+        &self,
+        start: usize,
+        end: usize,
+        strike: f64,
+        cap_floor_type: CapFloorType,
+    ) -> f64 {
+        (start..end)
+            .map(|i| self.caplet_price(i, strike, cap_floor_type))
+            .sum()
+    }
+
+    /// Annuity $\sum_{j} \tau_j P(0, T_{j+1})$ of the swap on the periods
+    /// `start..end`.
+    #[must_use]
+    pub fn annuity(&self, start: usize, end: usize) -> f64 {
+        (start..end)
+            .map(|j| self.accrual(j) * self.discount_factor(j + 1))
+            .sum()
+    }
+
+    /// Forward swap rate of the swap on the periods `start..end`.
+    #[must_use]
+    pub fn swap_rate(&self, start: usize, end: usize) -> f64 {
+        (self.discount_factor(start) - self.discount_factor(end)) / self.annuity(start, end)
+    }
+
+    /// Rebonato's approximation of the Black volatility of the swaption
+    /// expiring at $T_{start}$ on the swap on the periods `start..end`,
+    /// freezing the weights of the forwards in the swap rate.
+    #[must_use]
+    pub fn swaption_volatility(&self, start: usize, end: usize) -> f64 {
+        let T = self.times[start];
+        if T <= 0.0 {
+            return 0.0;
+        }
+
+        let annuity = self.annuity(start, end);
+        let weights: Vec<f64> = (start..end)
+            .map(|j| self.accrual(j) * self.discount_factor(j + 1) / annuity * self.forwards[j])
+            .collect();
+
+        let variance: f64 = (start..end)
+            .flat_map(|i| (start..end).map(move |j| (i, j)))
+            .map(|(i, j)| weights[i - start] * weights[j - start] * self.covariance(i, j, T))
+            .sum();
+
+        let swap_rate = self.swap_rate(start, end);
+
+        (variance / T).sqrt() / swap_rate
+    }
+
+    /// Price of the swaption expiring at $T_{start}$ on the swap on the
+    /// periods `start..end`, per unit notional, by Black's formula with
+    /// Rebonato's volatility.
+    #[must_use]
+    pub fn swaption_price(
+        &self,
+        start: usize,
+        end: usize,
+        strike: f64,
+        direction: SwapDirection,
+    ) -> f64 {
+        let option = RateVolatility::Lognormal(self.swaption_volatility(start, end)).forward_price(
+            self.swap_rate(start, end),
+            strike,
+            self.times[start],
+            swap_type_flag(direction),
+        );
+
+        self.annuity(start, end) * option
+    }
+
+    /// Simulates `paths` paths of the forward rates under the spot measure,
+    /// with `steps_per_period` log-Euler steps per period. Each path has,
+    /// for each tenor date but the last, the forward rates on that date
+    /// (those already fixed keep their fixing).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the correlation matrix is not positive definite.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn simulate(
+        &self,
+        paths: usize,
+        steps_per_period: usize,
+        drift: DriftApproximation,
+        seed: u64,
+    ) -> Vec<Vec<Vec<f64>>> {
+        let n = self.len();
+        let m = steps_per_period.max(1);
+
+        let correlation = self.correlation_matrix();
+        let cholesky = correlation
+            .clone()
+            .cholesky()
+            .expect("Correlation matrix is not positive definite.")
+            .l();
+
+        // Time grid: `m` steps up to the first tenor date, and per period.
+        let mut grid: Vec<(f64, f64)> = Vec::new();
+        let mut start = 0.0;
+        for &end in &self.times[..n] {
+            if end > start {
+                let dt = (end - start) / m as f64;
+                grid.extend((0..m).map(|k| (start + k as f64 * dt, dt)));
+            }
+            start = end;
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..paths)
+            .map(|_| {
+                let mut log_forwards: Vec<f64> = self.forwards.iter().map(|f| f.ln()).collect();
+                let mut states = Vec::with_capacity(n);
+                let mut steps = grid.iter().peekable();
+
+                for k in 0..n {
+                    while let Some(&&(t, dt)) = steps.peek() {
+                        if t >= self.times[k] {
+                            break;
+                        }
+                        steps.next();
+
+                        let z = DVector::from_fn(n, |_, _| rng.sample::<f64, _>(StandardNormal));
+                        let w = &cholesky * z;
+                        self.step(&mut log_forwards, &w, &correlation, (t, dt), drift);
+                    }
+
+                    states.push(log_forwards.iter().map(|x| x.exp()).collect());
+                }
+
+                states
+            })
+            .collect()
+    }
+
+    /// Monte Carlo price of the cap (or floor) on the forward rates
+    /// `start..end`, per unit notional, from simulated paths.
+    #[must_use]
+    pub fn monte_carlo_cap_price(
+        &self,
+        paths: &[Vec<Vec<f64>>],
+        start: usize,
+        end: usize,
+        strike: f64,
+        cap_floor_type: CapFloorType,
+    ) -> LmmMonteCarloResult {
+        let omega = match cap_floor_type {
+            CapFloorType::Cap => 1.0,
+            CapFloorType::Floor => -1.0,
+        };
+
+        let payoffs: Vec<f64> = paths
+            .iter()
+            .map(|path| {
+                let numeraire = self.numeraire(path);
+
+                (start..end)
+                    .map(|i| {
+                        let fixing = path[i][i];
+                        self.accrual(i) * (omega * (fixing - strike)).max(0.0) / numeraire[i + 1]
+                    })
+                    .sum()
+            })
+            .collect();
+
+        monte_carlo_result(&payoffs)
+    }
+
+    /// Monte Carlo price of the swaption expiring at $T_{start}$ on the
+    /// swap on the periods `start..end`, per unit notional, from
+    /// simulated paths.
+    #[must_use]
+    pub fn monte_carlo_swaption_price(
+        &self,
+        paths: &[Vec<Vec<f64>>],
+        start: usize,
+        end: usize,
+        strike: f64,
+        direction: SwapDirection,
+    ) -> LmmMonteCarloResult {
+        let omega = match direction {
+            SwapDirection::Payer => 1.0,
+            SwapDirection::Receiver => -1.0,
+        };
+
+        let payoffs: Vec<f64> = paths
+            .iter()
+            .map(|path| {
+                let forwards = &path[start];
+
+                // Zero-coupon bonds and annuity on the expiry date.
+                let mut bond = 1.0;
+                let mut annuity = 0.0;
+                for (j, forward) in forwards.iter().enumerate().take(end).skip(start) {
+                    bond /= 1.0 + self.accrual(j) * forward;
+                    annuity += self.accrual(j) * bond;
+                }
+                let swap_rate = (1.0 - bond) / annuity;
+
+                annuity * (omega * (swap_rate - strike)).max(0.0) / self.numeraire(path)[start]
+            })
+            .collect();
+
+        monte_carlo_result(&payoffs)
+    }
+
+    // Log-Euler step from `t` to `t + dt` of the log forward rates alive
+    // at `t`, with the correlated Brownian increments `w` (per unit time).
+    fn step(
+        &self,
+        log_forwards: &mut [f64],
+        w: &DVector<f64>,
+        correlation: &DMatrix<f64>,
+        (t, dt): (f64, f64),
+        drift: DriftApproximation,
+    ) {
+        let n = self.len();
+        let alive = self.times[..n].partition_point(|T| *T <= t);
+        let sigma: Vec<f64> = (0..n).map(|i| self.volatility(i, t)).collect();
+
+        // Spot measure drifts of the alive forwards.
+        let drifts = |log_forwards: &[f64]| -> Vec<f64> {
+            let terms: Vec<f64> = (0..n)
+                .map(|j| {
+                    let (tau, f) = (self.accrual(j), log_forwards[j].exp());
+                    tau * sigma[j] * f / (1.0 + tau * f)
+                })
+                .collect();
+
+            (0..n)
+                .map(|i| {
+                    sigma[i]
+                        * (alive..=i)
+                            .map(|j| correlation[(i, j)] * terms[j])
+                            .sum::<f64>()
+                })
+                .collect()
+        };
+
+        let shocks: Vec<f64> = (0..n)
+            .map(|i| -0.5 * sigma[i] * sigma[i] * dt + sigma[i] * dt.sqrt() * w[i])
+            .collect();
+
+        let initial = drifts(log_forwards);
+        let mu = match drift {
+            DriftApproximation::FrozenCurve => initial,
+            DriftApproximation::PredictorCorrector => {
+                let predicted: Vec<f64> = (0..n)
+                    .map(|i| log_forwards[i] + initial[i] * dt + shocks[i])
+                    .collect();
+
+                drifts(&predicted)
+                    .iter()
+                    .zip(&initial)
+                    .map(|(a, b)| 0.5 * (a + b))
+                    .collect()
+            }
+        };
+
+        for i in alive..n {
+            log_forwards[i] += mu[i] * dt + shocks[i];
+        }
+    }
+
+    // Spot numeraire on each tenor date, relative to its value at time 0:
+    // the rolled-over zero-coupon bonds over the discount factor to T_0.
+    fn numeraire(&self, path: &[Vec<f64>]) -> Vec<f64> {
+        let mut numeraire = Vec::with_capacity(self.len() + 1);
+        numeraire.push(1.0 / self.discount_factor);
+
+        for (k, forwards) in path.iter().enumerate() {
+            numeraire.push(numeraire[k] * (1.0 + self.accrual(k) * forwards[k]));
+        }
+
+        numeraire
+    }
+}
+
+// Mean and standard error of the discounted payoffs.
+#[allow(clippy::cast_precision_loss)]
+fn monte_carlo_result(payoffs: &[f64]) -> LmmMonteCarloResult {
+    let n = payoffs.len() as f64;
+    let price = payoffs.iter().sum::<f64>() / n;
+    let variance = payoffs.iter().map(|x| (x - price).powi(2)).sum::<f64>() / (n - 1.0);
+
+    LmmMonteCarloResult {
+        price,
+        std_error: (variance / n).sqrt(),
+    }
+}
+
+// Caps are calls on the forward rates, floors puts.
+fn type_flag(cap_floor_type: CapFloorType) -> TypeFlag {
+    match cap_floor_type {
+        CapFloorType::Cap => TypeFlag::Call,
+        CapFloorType::Floor => TypeFlag::Put,
+    }
+}
+
+// Payer swaptions are calls on the swap rate, receivers puts.
+fn swap_type_flag(direction: SwapDirection) -> TypeFlag {
+    match direction {
+        SwapDirection::Payer => TypeFlag::Call,
+        SwapDirection::Receiver => TypeFlag::Put,
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_libor_market_model {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::YieldCurve;
+    use std::collections::BTreeMap;
+    use time::Duration;
+
+    // Semi-annual tenor structure over 5 years, on an upward sloping curve.
+    fn model() -> LiborMarketModel {
+        let today = OffsetDateTime::UNIX_EPOCH;
+        let curve = YieldCurve::new(BTreeMap::from([
+            (today, 0.03),
+            (today + Duration::days(3650), 0.05),
+        ]));
+        let dates: Vec<OffsetDateTime> =
+            (1..=11).map(|i| today + Duration::days(182 * i)).collect();
+
+        LiborMarketModel::from_curve(
+            &curve,
+            &dates,
+            AbcdVolatility {
+                a: 0.05,
+                b: 0.2,
+                c: 1.2,
+                d: 0.12,
+            },
+            LmmCorrelation {
+                long_run: 0.4,
+                decay: 0.3,
+            },
+        )
+    }
+
+    #[test]
+    fn test_parameterisation() {
+        let model = model();
+
+        assert_eq!(model.len(), 10);
+        assert_approx_equal!(
+            model.discount_factor(10) / model.discount_factor(0),
+            (0..10)
+                .map(|i| 1.0 / (1.0 + model.accrual(i) * model.forwards[i]))
+                .product::<f64>(),
+            1e-14
+        );
+
+        // Humped volatility, decaying to `d`.
+        let abcd = model.volatility;
+        assert!(abcd.volatility(1.0) > abcd.volatility(0.0));
+        assert_approx_equal!(abcd.volatility(100.0), abcd.d, 1e-12);
+        assert_approx_equal!(model.volatility(3, 2.0), 0.0, 1e-15);
+
+        // Positive definite correlation, decaying with distance.
+        let rho = model.correlation_matrix();
+        assert!(rho.clone().cholesky().is_some());
+        assert!(rho[(0, 1)] > rho[(0, 9)] && rho[(0, 9)] > 0.4);
+
+        // Caplet variance is the integrated volatility.
+        let T = model.times[4];
+        let v = model.caplet_volatility(4);
+        assert_approx_equal!(v * v * T, model.covariance(4, 4, T), 1e-14);
+    }
+
+    #[test]
+    fn test_monte_carlo_validation() {
+        let model = model();
+        let paths = model.simulate(10_000, 4, DriftApproximation::PredictorCorrector, 1234);
+
+        // Zero-coupon bonds are martingales under the spot measure: a floor
+        // at zero strike is worthless, and the cap is the forward leg.
+        let floor = model.monte_carlo_cap_price(&paths, 1, 10, 0.0, CapFloorType::Floor);
+        assert_approx_equal!(floor.price, 0.0, 1e-15);
+
+        let floating = model.monte_carlo_cap_price(&paths, 1, 10, 0.0, CapFloorType::Cap);
+        assert_approx_equal!(
+            floating.price,
+            model.discount_factor(1) - model.discount_factor(10),
+            3.0 * floating.std_error
+        );
+
+        // Caplets against Black's formula.
+        for i in [1, 4, 9] {
+            let strike = model.forwards[i];
+            let mc = model.monte_carlo_cap_price(&paths, i, i + 1, strike, CapFloorType::Cap);
+            let analytic = model.caplet_price(i, strike, CapFloorType::Cap);
+            assert_approx_equal!(mc.price, analytic, 3.0 * mc.std_error);
+        }
+
+        // Swaptions against Rebonato's approximation.
+        for (start, direction) in [(2, SwapDirection::Payer), (4, SwapDirection::Receiver)] {
+            let strike = model.swap_rate(start, 10);
+            let mc = model.monte_carlo_swaption_price(&paths, start, 10, strike, direction);
+            let analytic = model.swaption_price(start, 10, strike, direction);
+            assert_approx_equal!(mc.price, analytic, 0.02 * analytic);
+        }
+    }
+
+    #[test]
+    fn test_drift_approximation() {
+        // Both drift approximations price the caplets, even with a single
+        // step per period.
+        let model = model();
+
+        for drift in [
+            DriftApproximation::FrozenCurve,
+            DriftApproximation::PredictorCorrector,
+        ] {
+            let paths = model.simulate(10_000, 1, drift, 42);
+
+            for i in [3, 9] {
+                let strike = model.forwards[i];
+                let mc = model.monte_carlo_cap_price(&paths, i, i + 1, strike, CapFloorType::Cap);
+                let analytic = model.caplet_price(i, strike, CapFloorType::Cap);
+                assert_approx_equal!(mc.price, analytic, 3.0 * mc.std_error);
+            }
+        }
+    }
+}
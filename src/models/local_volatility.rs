@@ -0,0 +1,254 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Dupire local volatility model.
+//!
+//! The underlying follows
+//!
+//! $$
+//! dS_t = (r - q) S_t dt + \sigma_{loc}(S_t, t) S_t dW_t
+//! $$
+//!
+//! where the local volatility is chosen to reprice every European option
+//! of an implied volatility surface (Dupire, 1994). In terms of the total
+//! implied variance `w(k, T)` at log-moneyness `k = ln(K / F(T))` it is
+//! (Gatheral, 2006)
+//!
+//! $$
+//! \sigma_{loc}^2(k, T) = \frac{\partial_T w}{1 - \frac{k}{w} \partial_k w + \frac{1}{4} \left( -\frac{1}{4} - \frac{1}{w} + \frac{k^2}{w^2} \right) (\partial_k w)^2 + \frac{1}{2} \partial_{kk} w}
+//! $$
+//!
+//! The derivatives are taken by finite differences of the surface.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::VolatilitySurface;
+use crate::models::durrleman;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Local volatility extracted from an implied volatility surface.
+///
+/// The surface's forwards are expected to agree with `S exp((r - q) T)`.
+/// Where the surface has calendar arbitrage (`dw/dT < 0`) the local
+/// volatility is floored at `minimum_volatility`, and where it has butterfly
+/// arbitrage (the denominator is not positive) it is capped at
+/// `maximum_volatility`.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::models::*;
+///
+/// let strikes = vec![80.0, 90.0, 100.0, 110.0, 120.0];
+/// let surface = VolatilitySurface::new(
+///     vec![
+///         SmileQuotes::new(0.5, 101.0, strikes.clone(), vec![0.2; 5]),
+///         SmileQuotes::new(1.0, 102.0, strikes, vec![0.2; 5]),
+///     ],
+///     SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+/// )
+/// .unwrap();
+///
+/// let model = LocalVolatility::new(surface, 100.0, 0.03, 0.01);
+///
+/// // A flat surface has a flat local volatility.
+/// assert!((model.local_volatility(90.0, 0.75) - 0.2).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalVolatility {
+    /// Implied volatility surface.
+    pub surface: VolatilitySurface,
+    /// `S` - Initial price of the underlying.
+    pub spot: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `q` - Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// Lower bound of the local volatility. Default: 0.01.
+    pub minimum_volatility: f64,
+    /// Upper bound of the local volatility. Default: 3.
+    pub maximum_volatility: f64,
+    /// Step of the finite differences in time (in years). Default: 1e-3.
+    pub time_step: f64,
+    /// Step of the finite differences in log-moneyness. Default: 1e-3.
+    pub log_moneyness_step: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl LocalVolatility {
+    /// New local volatility model, with the default bounds and steps.
+    #[must_use]
+    pub const fn new(
+        surface: VolatilitySurface,
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+    ) -> Self {
+        Self {
+            surface,
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            minimum_volatility: 0.01,
+            maximum_volatility: 3.0,
+            time_step: 1e-3,
+            log_moneyness_step: 1e-3,
+        }
+    }
+
+    /// Set the bounds of the local volatility.
+    #[must_use]
+    pub const fn with_volatility_bounds(mut self, minimum: f64, maximum: f64) -> Self {
+        self.minimum_volatility = minimum;
+        self.maximum_volatility = maximum;
+        self
+    }
+
+    /// Set the steps of the finite differences in time and log-moneyness.
+    #[must_use]
+    pub const fn with_steps(mut self, time_step: f64, log_moneyness_step: f64) -> Self {
+        self.time_step = time_step;
+        self.log_moneyness_step = log_moneyness_step;
+        self
+    }
+
+    /// Forward price of the underlying at time `t`.
+    #[must_use]
+    pub fn forward(&self, t: f64) -> f64 {
+        self.spot * ((self.risk_free_rate - self.dividend_yield) * t).exp()
+    }
+
+    /// Local variance at log-moneyness `k = ln(S / F(t))` and time `t`.
+    #[must_use]
+    #[allow(clippy::similar_names)]
+    pub fn local_variance(&self, k: f64, t: f64) -> f64 {
+        let (dt, dk) = (self.time_step, self.log_moneyness_step);
+        let (lower, upper) = (
+            self.minimum_volatility * self.minimum_volatility,
+            self.maximum_volatility * self.maximum_volatility,
+        );
+
+        // The surface is not differentiable in time at t = 0.
+        let t = t.max(dt);
+        let w = |k: f64, t: f64| self.surface.total_variance(k, t);
+
+        let dw_dt = if t > dt {
+            (w(k, t + dt) - w(k, t - dt)) / (2.0 * dt)
+        } else {
+            (w(k, t + dt) - w(k, t)) / dt
+        };
+
+        let (w_down, w_0, w_up) = (w(k - dk, t), w(k, t), w(k + dk, t));
+        let dw_dk = (w_up - w_down) / (2.0 * dk);
+        let d2w_dk2 = (w_up - 2.0 * w_0 + w_down) / (dk * dk);
+
+        let denominator = durrleman(k, w_0, dw_dk, d2w_dk2);
+        let variance = dw_dt / denominator;
+
+        if denominator <= 0.0 || variance.is_nan() {
+            return upper;
+        }
+
+        variance.clamp(lower, upper)
+    }
+
+    /// Local volatility for an underlying price `s` at time `t`.
+    #[must_use]
+    pub fn local_volatility(&self, s: f64, t: f64) -> f64 {
+        self.local_variance((s / self.forward(t)).ln(), t).sqrt()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_local_volatility {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::{SmileInterpolation, SmileQuotes};
+    use crate::models::RawSvi;
+
+    const STRIKES: [f64; 9] = [60.0, 70.0, 80.0, 90.0, 100.0, 110.0, 120.0, 135.0, 150.0];
+
+    fn surface(term_structure: &[(f64, f64)]) -> VolatilitySurface {
+        VolatilitySurface::new(
+            term_structure
+                .iter()
+                .map(|&(expiry, v)| {
+                    SmileQuotes::new(expiry, 100.0, STRIKES.to_vec(), vec![v; STRIKES.len()])
+                })
+                .collect(),
+            SmileInterpolation::SmoothingSpline { smoothing: 0.0 },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_term_structure() {
+        // No smile: the local variance is the forward variance.
+        let model = LocalVolatility::new(surface(&[(0.5, 0.2), (1.0, 0.3)]), 100.0, 0.0, 0.0);
+        let forward_variance = (0.3 * 0.3 * 1.0 - 0.2 * 0.2 * 0.5) / 0.5;
+
+        for s in [80.0, 100.0, 125.0] {
+            assert_approx_equal!(model.local_volatility(s, 0.25), 0.2, 1e-8);
+            assert_approx_equal!(
+                model.local_variance((s / 100.0_f64).ln(), 0.75),
+                forward_variance,
+                1e-8
+            );
+        }
+    }
+
+    #[test]
+    fn test_skew() {
+        // Near the money the local volatility skew is about twice the implied skew.
+        let svi = RawSvi::new(0.03, 0.1, -0.7, 0.0, 0.2);
+        let quotes = |expiry: f64| {
+            let scaled = RawSvi::new(svi.a * expiry, svi.b * expiry, svi.rho, svi.m, svi.sigma);
+            SmileQuotes::new(
+                expiry,
+                100.0,
+                STRIKES.to_vec(),
+                scaled.volatilities(100.0, expiry, &STRIKES),
+            )
+        };
+        let surface =
+            VolatilitySurface::new(vec![quotes(1.0), quotes(2.0)], SmileInterpolation::Svi)
+                .unwrap();
+        let model = LocalVolatility::new(surface.clone(), 100.0, 0.0, 0.0);
+
+        let h: f64 = 0.01;
+        let implied_skew =
+            (surface.vol(100.0 * h.exp(), 1.5) - surface.vol(100.0 * (-h).exp(), 1.5)) / (2.0 * h);
+        let local_skew = (model.local_volatility(100.0 * h.exp(), 1.5)
+            - model.local_volatility(100.0 * (-h).exp(), 1.5))
+            / (2.0 * h);
+
+        assert!(implied_skew < 0.0);
+        assert!((1.5..2.5).contains(&(local_skew / implied_skew)));
+    }
+
+    #[test]
+    fn test_arbitrage_safeguards() {
+        // Calendar arbitrage: total variance decreasing in time.
+        let model = LocalVolatility::new(surface(&[(0.5, 0.3), (1.0, 0.1)]), 100.0, 0.0, 0.0)
+            .with_volatility_bounds(0.05, 2.0);
+
+        assert_approx_equal!(model.local_volatility(100.0, 0.75), 0.05, 1e-12);
+    }
+}
@@ -14,6 +14,30 @@
 //! Module containing all models (e.g. Black-Scholes, Heston, etc).
 //! Also a `Model` trait is defined here for all models to implement.
 
+/// Hull-White trinomial tree.
+pub mod hull_white_tree;
+pub use hull_white_tree::*;
+
+/// LIBOR market model.
+pub mod libor_market_model;
+pub use libor_market_model::*;
+
+/// Dupire local volatility model.
+pub mod local_volatility;
+pub use local_volatility::*;
+
 /// Model trait.
 pub mod model;
 pub use model::*;
+
+/// SABR stochastic volatility model.
+pub mod sabr;
+pub use sabr::*;
+
+/// Short-rate models (Vasicek, Cox-Ingersoll-Ross, Hull-White).
+pub mod short_rate;
+pub use short_rate::*;
+
+/// Stochastic volatility inspired (SVI) smile parameterisation.
+pub mod svi;
+pub use svi::*;
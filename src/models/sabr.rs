@@ -0,0 +1,393 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! SABR stochastic volatility model (Hagan et al., 2002):
+//!
+//! $$
+//! dF_t = \alpha_t F_t^\beta dW_t, \qquad
+//! d\alpha_t = \nu \alpha_t dZ_t, \qquad
+//! d\langle W, Z \rangle_t = \rho dt
+//! $$
+//!
+//! The implied volatility of a European option on the forward is given by
+//! Hagan's asymptotic expansion, either as a lognormal (Black) volatility
+//! or as a normal (Bachelier) volatility.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::LevenbergMarquardt;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// SABR model parameters.
+///
+/// ```
+/// use RustQuant::models::*;
+///
+/// let sabr = Sabr::new(0.03, 0.5, -0.3, 0.4);
+///
+/// let strikes = [0.02, 0.025, 0.03, 0.035, 0.04];
+/// let vols: Vec<f64> = strikes
+///     .iter()
+///     .map(|&k| sabr.lognormal_volatility(0.03, k, 2.0))
+///     .collect();
+///
+/// // Fit alpha, rho and nu with beta fixed at 0.5.
+/// let fitted = Sabr::calibrate(0.03, 2.0, &strikes, &vols, SabrVolatility::Lognormal, Some(0.5))
+///     .unwrap();
+///
+/// assert!((fitted.nu - 0.4).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sabr {
+    /// `alpha` - Initial volatility.
+    pub alpha: f64,
+    /// `beta` - CEV exponent, in `[0, 1]`.
+    pub beta: f64,
+    /// `rho` - Correlation between the forward and its volatility.
+    pub rho: f64,
+    /// `nu` - Volatility of the volatility.
+    pub nu: f64,
+}
+
+/// Type of implied volatility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SabrVolatility {
+    /// Lognormal (Black) implied volatility.
+    Lognormal,
+    /// Normal (Bachelier) implied volatility.
+    Normal,
+}
+
+/// SABR calibration errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SabrError {
+    /// The strikes and the volatilities have different lengths.
+    #[error("Number of strikes and volatilities do not match")]
+    LengthMismatch,
+
+    /// Fewer quotes than parameters to fit.
+    #[error("Not enough quotes to fit the parameters")]
+    NotEnoughQuotes,
+
+    /// The forward, a strike, a volatility or the expiry is not positive.
+    #[error("Forward, strikes, volatilities and expiry must be positive")]
+    NonPositiveInput,
+
+    /// The fit did not produce finite parameters.
+    #[error("Calibration failed")]
+    CalibrationFailed,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Sabr {
+    /// New SABR model.
+    #[must_use]
+    pub const fn new(alpha: f64, beta: f64, rho: f64, nu: f64) -> Self {
+        Self {
+            alpha,
+            beta,
+            rho,
+            nu,
+        }
+    }
+
+    /// Implied volatility of the given type, for a `forward`, `strike`
+    /// and time to `expiry` (in years).
+    #[must_use]
+    pub fn volatility(
+        &self,
+        forward: f64,
+        strike: f64,
+        expiry: f64,
+        volatility_type: SabrVolatility,
+    ) -> f64 {
+        match volatility_type {
+            SabrVolatility::Lognormal => self.lognormal_volatility(forward, strike, expiry),
+            SabrVolatility::Normal => self.normal_volatility(forward, strike, expiry),
+        }
+    }
+
+    /// Hagan's lognormal (Black) implied volatility approximation.
+    #[must_use]
+    pub fn lognormal_volatility(&self, forward: f64, strike: f64, expiry: f64) -> f64 {
+        let Self {
+            alpha,
+            beta,
+            rho,
+            nu,
+        } = *self;
+
+        let log_moneyness = (forward / strike).ln();
+        let fk_beta = (forward * strike).powf(0.5 * (1.0 - beta));
+        let b2 = (1.0 - beta).powi(2);
+
+        let denominator = fk_beta
+            * (1.0 + b2 / 24.0 * log_moneyness.powi(2) + b2 * b2 / 1920.0 * log_moneyness.powi(4));
+
+        let z = nu / alpha * fk_beta * log_moneyness;
+
+        let correction = 1.0
+            + (b2 / 24.0 * alpha * alpha / (fk_beta * fk_beta)
+                + 0.25 * rho * beta * nu * alpha / fk_beta
+                + (2.0 - 3.0 * rho * rho) / 24.0 * nu * nu)
+                * expiry;
+
+        alpha / denominator * self.z_over_x(z) * correction
+    }
+
+    /// Hagan's normal (Bachelier) implied volatility approximation.
+    #[must_use]
+    pub fn normal_volatility(&self, forward: f64, strike: f64, expiry: f64) -> f64 {
+        let Self {
+            alpha,
+            beta,
+            rho,
+            nu,
+        } = *self;
+
+        let log_moneyness = (forward / strike).ln();
+        let l2 = log_moneyness.powi(2);
+        let fk_beta = (forward * strike).powf(0.5 * (1.0 - beta));
+        let b2 = (1.0 - beta).powi(2);
+
+        let ratio = (1.0 + l2 / 24.0 + l2 * l2 / 1920.0)
+            / (1.0 + b2 / 24.0 * l2 + b2 * b2 / 1920.0 * l2 * l2);
+
+        let z = nu / alpha * fk_beta * log_moneyness;
+
+        let correction = 1.0
+            + (-beta * (2.0 - beta) / 24.0 * alpha * alpha / (fk_beta * fk_beta)
+                + 0.25 * rho * beta * nu * alpha / fk_beta
+                + (2.0 - 3.0 * rho * rho) / 24.0 * nu * nu)
+                * expiry;
+
+        alpha * (forward * strike).powf(0.5 * beta) * ratio * self.z_over_x(z) * correction
+    }
+
+    /// Fit the parameters to a slice of implied volatilities with the same
+    /// expiry, by Levenberg-Marquardt least squares on the volatilities.
+    ///
+    /// If `beta` is given it is held fixed (the usual market practice, since
+    /// `beta` and `rho` have similar effects on the smile) and only `alpha`,
+    /// `rho` and `nu` are fitted. Otherwise all four parameters are fitted.
+    ///
+    /// # Errors
+    ///
+    /// - `SabrError::LengthMismatch` if the strikes and volatilities differ in length.
+    /// - `SabrError::NotEnoughQuotes` if there are fewer quotes than parameters.
+    /// - `SabrError::NonPositiveInput` for a non-positive forward, strike,
+    ///   volatility or expiry.
+    /// - `SabrError::CalibrationFailed` if the fit breaks down.
+    pub fn calibrate(
+        forward: f64,
+        expiry: f64,
+        strikes: &[f64],
+        volatilities: &[f64],
+        volatility_type: SabrVolatility,
+        beta: Option<f64>,
+    ) -> Result<Self, SabrError> {
+        if strikes.len() != volatilities.len() {
+            return Err(SabrError::LengthMismatch);
+        }
+        if strikes.len() < if beta.is_some() { 3 } else { 4 } {
+            return Err(SabrError::NotEnoughQuotes);
+        }
+        if forward <= 0.0 || expiry <= 0.0 || strikes.iter().chain(volatilities).any(|&x| x <= 0.0)
+        {
+            return Err(SabrError::NonPositiveInput);
+        }
+
+        // Unconstrained parameters: alpha = exp(p0), rho = tanh(p1),
+        // nu = exp(p2) and, if fitted, beta = 1 / (1 + exp(-p3)).
+        let to_model = |p: &[f64]| Self {
+            alpha: p[0].exp(),
+            beta: beta.unwrap_or_else(|| 1.0 / (1.0 + (-p[3]).exp())),
+            rho: p[1].tanh(),
+            nu: p[2].exp(),
+        };
+
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            let model = to_model(p);
+            strikes
+                .iter()
+                .zip(volatilities)
+                .map(|(&k, &v)| model.volatility(forward, k, expiry, volatility_type) - v)
+                .collect()
+        };
+
+        // Initial guess from the volatility closest to the money.
+        let beta_0 = beta.unwrap_or(0.5);
+        let atm = strikes
+            .iter()
+            .zip(volatilities)
+            .min_by(|a, b| (a.0 - forward).abs().total_cmp(&(b.0 - forward).abs()))
+            .map_or(volatilities[0], |(_, &v)| v);
+        let alpha_0 = match volatility_type {
+            SabrVolatility::Lognormal => atm * forward.powf(1.0 - beta_0),
+            SabrVolatility::Normal => atm / forward.powf(beta_0),
+        };
+
+        let mut p0 = vec![alpha_0.ln(), 0.0, 0.5_f64.ln()];
+        if beta.is_none() {
+            p0.push(0.0);
+        }
+
+        let p = LevenbergMarquardt::default()
+            .optimize(residuals, &p0)
+            .ok_or(SabrError::CalibrationFailed)?
+            .minimizer;
+
+        let model = to_model(&p);
+
+        if [model.alpha, model.beta, model.rho, model.nu]
+            .iter()
+            .all(|x| x.is_finite())
+        {
+            Ok(model)
+        } else {
+            Err(SabrError::CalibrationFailed)
+        }
+    }
+
+    // z / x(z), with the limit 1 as z -> 0 (at the money).
+    fn z_over_x(&self, z: f64) -> f64 {
+        if z.abs() < 1e-8 {
+            return 1.0 - 0.5 * self.rho * z;
+        }
+
+        let x =
+            (((1.0 - 2.0 * self.rho * z + z * z).sqrt() + z - self.rho) / (1.0 - self.rho)).ln();
+
+        z / x
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_sabr {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const FORWARD: f64 = 0.03;
+    const EXPIRY: f64 = 2.0;
+    const STRIKES: [f64; 7] = [0.015, 0.02, 0.025, 0.03, 0.035, 0.04, 0.05];
+
+    #[test]
+    fn test_limits() {
+        // No vol of vol: beta = 1 is Black, beta = 0 is Bachelier.
+        let black = Sabr::new(0.2, 1.0, 0.0, 0.0);
+        let bachelier = Sabr::new(0.01, 0.0, 0.0, 0.0);
+
+        for k in STRIKES {
+            assert_approx_equal!(black.lognormal_volatility(FORWARD, k, EXPIRY), 0.2, 1e-15);
+            assert_approx_equal!(bachelier.normal_volatility(FORWARD, k, EXPIRY), 0.01, 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_at_the_money_continuity() {
+        let sabr = Sabr::new(0.03, 0.5, -0.3, 0.4);
+
+        for volatility_type in [SabrVolatility::Lognormal, SabrVolatility::Normal] {
+            let atm = sabr.volatility(FORWARD, FORWARD, EXPIRY, volatility_type);
+            let near = sabr.volatility(FORWARD, FORWARD * (1.0 + 1e-9), EXPIRY, volatility_type);
+
+            assert_approx_equal!(atm, near, 1e-9);
+        }
+
+        // Negative correlation: downward sloping smile.
+        assert!(
+            sabr.lognormal_volatility(FORWARD, 0.02, EXPIRY)
+                > sabr.lognormal_volatility(FORWARD, 0.04, EXPIRY)
+        );
+    }
+
+    #[test]
+    fn test_calibration() {
+        let sabr = Sabr::new(0.03, 0.5, -0.3, 0.4);
+
+        for volatility_type in [SabrVolatility::Lognormal, SabrVolatility::Normal] {
+            let vols: Vec<f64> = STRIKES
+                .iter()
+                .map(|&k| sabr.volatility(FORWARD, k, EXPIRY, volatility_type))
+                .collect();
+
+            // Fixed beta: the parameters are recovered.
+            let fitted =
+                Sabr::calibrate(FORWARD, EXPIRY, &STRIKES, &vols, volatility_type, Some(0.5))
+                    .unwrap();
+
+            assert_approx_equal!(fitted.alpha, sabr.alpha, 1e-6);
+            assert_approx_equal!(fitted.rho, sabr.rho, 1e-6);
+            assert_approx_equal!(fitted.nu, sabr.nu, 1e-6);
+
+            // Free beta: the smile is recovered.
+            let fitted =
+                Sabr::calibrate(FORWARD, EXPIRY, &STRIKES, &vols, volatility_type, None).unwrap();
+
+            for (&k, &v) in STRIKES.iter().zip(&vols) {
+                assert_approx_equal!(
+                    fitted.volatility(FORWARD, k, EXPIRY, volatility_type),
+                    v,
+                    1e-5 * v
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_calibration_errors() {
+        let vols = [0.2; 7];
+
+        assert!(matches!(
+            Sabr::calibrate(
+                FORWARD,
+                EXPIRY,
+                &STRIKES,
+                &vols[..6],
+                SabrVolatility::Lognormal,
+                None
+            ),
+            Err(SabrError::LengthMismatch)
+        ));
+        assert!(matches!(
+            Sabr::calibrate(
+                FORWARD,
+                EXPIRY,
+                &STRIKES[..3],
+                &vols[..3],
+                SabrVolatility::Lognormal,
+                None
+            ),
+            Err(SabrError::NotEnoughQuotes)
+        ));
+        assert!(matches!(
+            Sabr::calibrate(
+                -FORWARD,
+                EXPIRY,
+                &STRIKES,
+                &vols,
+                SabrVolatility::Lognormal,
+                None
+            ),
+            Err(SabrError::NonPositiveInput)
+        ));
+    }
+}
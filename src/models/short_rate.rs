@@ -0,0 +1,733 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! One-factor short-rate models with analytic zero-coupon bond prices:
+//!
+//! - Vasicek (1977): $dr = \kappa (\theta - r) dt + \sigma dW$.
+//! - Cox-Ingersoll-Ross (1985): $dr = \kappa (\theta - r) dt + \sigma \sqrt{r} dW$.
+//! - Hull-White (1990): $dr = (\theta(t) - a r) dt + \sigma dW$, with
+//!   $\theta(t)$ fitted to an initial discount curve.
+//!
+//! All three are affine: $P(t, T) = A(t, T) e^{-B(t, T) r(t)}$. Times are in
+//! years from the valuation date (time 0). Each model also exposes its
+//! short-rate process from the [`stochastics`](crate::stochastics) module,
+//! for Monte Carlo simulation.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::curves::{Curve, YieldCurve};
+use crate::instruments::options::TypeFlag;
+use crate::instruments::rates::{CapFloorType, Caplet, SwapDirection, Swaption};
+use crate::math::{solve_decreasing, LevenbergMarquardt};
+use crate::statistics::distributions::{Distribution, Gaussian};
+use crate::stochastics::{
+    CoxIngersollRoss, HullWhite, OrnsteinUhlenbeck, StochasticProcess, Trajectories,
+};
+use crate::time::{DayCountConvention, DayCounter};
+use statrs::function::gamma::{gamma_lr, ln_gamma};
+use time::{Duration, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// One-factor short-rate model with analytic bond prices.
+pub trait ShortRateModel {
+    /// Short-rate process of the model.
+    type Process: StochasticProcess;
+
+    /// Short rate at time 0.
+    fn initial_short_rate(&self) -> f64;
+
+    /// Price at time `t` of the zero-coupon bond maturing at `T`, given the
+    /// short rate `r` at `t`.
+    fn bond_price(&self, r: f64, t: f64, T: f64) -> f64;
+
+    /// Price at time 0 of a European option expiring at `T`, with strike
+    /// `K`, on the zero-coupon bond maturing at `S` (`S > T`).
+    fn bond_option_price(&self, K: f64, T: f64, S: f64, option_type: TypeFlag) -> f64;
+
+    /// Short-rate process, for simulation.
+    fn process(&self) -> Self::Process;
+
+    /// Discount factor to `T`: the price at time 0 of the zero-coupon bond.
+    fn discount_factor(&self, T: f64) -> f64 {
+        self.bond_price(self.initial_short_rate(), 0.0, T)
+    }
+
+    /// Simulates short-rate paths from time 0 to `T` (Euler-Maruyama).
+    fn simulate(&self, T: f64, n_steps: usize, n_paths: usize, parallel: bool) -> Trajectories {
+        self.process().euler_maruyama(
+            self.initial_short_rate(),
+            0.0,
+            T,
+            n_steps,
+            n_paths,
+            parallel,
+        )
+    }
+}
+
+/// Vasicek (1977) short-rate model.
+///
+/// ```
+/// use RustQuant::instruments::TypeFlag;
+/// use RustQuant::models::*;
+///
+/// let model = VasicekModel::new(0.03, 0.3, 0.1, 0.03);
+///
+/// let call = model.bond_option_price(0.9, 1.0, 3.0, TypeFlag::Call);
+/// let put = model.bond_option_price(0.9, 1.0, 3.0, TypeFlag::Put);
+///
+/// // Put-call parity.
+/// let forward = model.discount_factor(3.0) - 0.9 * model.discount_factor(1.0);
+/// assert!((call - put - forward).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VasicekModel {
+    /// Initial short rate.
+    pub r0: f64,
+    /// Speed of mean reversion.
+    pub kappa: f64,
+    /// Long-run mean of the short rate.
+    pub theta: f64,
+    /// Volatility of the short rate.
+    pub sigma: f64,
+}
+
+/// Cox-Ingersoll-Ross (1985) short-rate model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoxIngersollRossModel {
+    /// Initial short rate.
+    pub r0: f64,
+    /// Speed of mean reversion.
+    pub kappa: f64,
+    /// Long-run mean of the short rate.
+    pub theta: f64,
+    /// Volatility of the short rate (times its square root).
+    pub sigma: f64,
+}
+
+/// Hull-White (1990) one-factor model, fitted to an initial discount curve.
+///
+/// ```
+/// use RustQuant::curves::*;
+/// use RustQuant::models::*;
+/// use std::collections::BTreeMap;
+/// use time::{Duration, OffsetDateTime};
+///
+/// let today = OffsetDateTime::UNIX_EPOCH;
+/// let curve = YieldCurve::new(BTreeMap::from([
+///     (today, 0.02),
+///     (today + Duration::days(3650), 0.04),
+/// ]));
+///
+/// let model = HullWhiteModel::new(curve.clone(), 0.1, 0.01);
+///
+/// // The model reprices the initial curve.
+/// let date = today + Duration::days(1000);
+/// let t = 1000.0 / 365.0;
+/// assert!((model.discount_factor(t) - curve.discount_factor(date)).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HullWhiteModel {
+    /// Initial discount curve; its initial date is time 0.
+    pub curve: YieldCurve,
+    /// Speed of mean reversion.
+    pub a: f64,
+    /// Volatility of the short rate.
+    pub sigma: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl VasicekModel {
+    /// New Vasicek model.
+    #[must_use]
+    pub const fn new(r0: f64, kappa: f64, theta: f64, sigma: f64) -> Self {
+        Self {
+            r0,
+            kappa,
+            theta,
+            sigma,
+        }
+    }
+}
+
+impl ShortRateModel for VasicekModel {
+    type Process = OrnsteinUhlenbeck;
+
+    fn initial_short_rate(&self) -> f64 {
+        self.r0
+    }
+
+    fn bond_price(&self, r: f64, t: f64, T: f64) -> f64 {
+        let (kappa, sigma) = (self.kappa, self.sigma);
+        let tau = T - t;
+        let B = -(-kappa * tau).exp_m1() / kappa;
+        let ln_A = (self.theta - sigma * sigma / (2.0 * kappa * kappa)) * (B - tau)
+            - sigma * sigma * B * B / (4.0 * kappa);
+
+        (ln_A - B * r).exp()
+    }
+
+    fn bond_option_price(&self, K: f64, T: f64, S: f64, option_type: TypeFlag) -> f64 {
+        let sigma_p = gaussian_bond_volatility(self.kappa, self.sigma, T, S);
+
+        gaussian_bond_option(
+            self.discount_factor(T),
+            self.discount_factor(S),
+            K,
+            sigma_p,
+            option_type,
+        )
+    }
+
+    fn process(&self) -> OrnsteinUhlenbeck {
+        OrnsteinUhlenbeck::new(self.theta, self.sigma, self.kappa)
+    }
+}
+
+impl CoxIngersollRossModel {
+    /// New Cox-Ingersoll-Ross model. The short rate stays positive if
+    /// `2 kappa theta >= sigma^2` (the Feller condition).
+    #[must_use]
+    pub const fn new(r0: f64, kappa: f64, theta: f64, sigma: f64) -> Self {
+        Self {
+            r0,
+            kappa,
+            theta,
+            sigma,
+        }
+    }
+
+    fn gamma(&self) -> f64 {
+        (self.kappa * self.kappa + 2.0 * self.sigma * self.sigma).sqrt()
+    }
+
+    // A(tau) and B(tau) of the bond price.
+    fn affine(&self, tau: f64) -> (f64, f64) {
+        let (kappa, gamma) = (self.kappa, self.gamma());
+        let growth = (gamma * tau).exp_m1();
+        let denominator = (gamma + kappa) * growth + 2.0 * gamma;
+
+        let A = (2.0 * gamma * ((kappa + gamma) * tau / 2.0).exp() / denominator)
+            .powf(2.0 * kappa * self.theta / (self.sigma * self.sigma));
+        let B = 2.0 * growth / denominator;
+
+        (A, B)
+    }
+}
+
+impl ShortRateModel for CoxIngersollRossModel {
+    type Process = CoxIngersollRoss;
+
+    fn initial_short_rate(&self) -> f64 {
+        self.r0
+    }
+
+    fn bond_price(&self, r: f64, t: f64, T: f64) -> f64 {
+        let (A, B) = self.affine(T - t);
+
+        A * (-B * r).exp()
+    }
+
+    /// Cox, Ingersoll and Ross (1985) formula, with non-central chi-squared
+    /// distribution functions.
+    fn bond_option_price(&self, K: f64, T: f64, S: f64, option_type: TypeFlag) -> f64 {
+        let (kappa, sigma, gamma) = (self.kappa, self.sigma, self.gamma());
+        let (A, B) = self.affine(S - T);
+
+        let rho = 2.0 * gamma / (sigma * sigma * (gamma * T).exp_m1());
+        let psi = (kappa + gamma) / (sigma * sigma);
+        let r_star = (A / K).ln() / B;
+
+        let dof = 4.0 * kappa * self.theta / (sigma * sigma);
+        let noncentrality = |x: f64| 2.0 * rho * rho * self.r0 * (gamma * T).exp() / x;
+
+        let (P_T, P_S) = (self.discount_factor(T), self.discount_factor(S));
+
+        let call = P_S
+            * noncentral_chi_squared_cdf(
+                2.0 * r_star * (rho + psi + B),
+                dof,
+                noncentrality(rho + psi + B),
+            )
+            - K * P_T
+                * noncentral_chi_squared_cdf(
+                    2.0 * r_star * (rho + psi),
+                    dof,
+                    noncentrality(rho + psi),
+                );
+
+        match option_type {
+            TypeFlag::Call => call,
+            TypeFlag::Put => call - P_S + K * P_T,
+        }
+    }
+
+    fn process(&self) -> CoxIngersollRoss {
+        CoxIngersollRoss::new(self.theta, self.sigma, self.kappa)
+    }
+}
+
+impl HullWhiteModel {
+    /// New Hull-White model on an initial discount curve.
+    #[must_use]
+    pub const fn new(curve: YieldCurve, a: f64, sigma: f64) -> Self {
+        Self { curve, a, sigma }
+    }
+
+    /// Time (Actual/365, in years) from the initial date of the curve.
+    #[must_use]
+    pub fn time(&self, date: OffsetDateTime) -> f64 {
+        DayCounter::day_count_factor(
+            self.curve.initial_date(),
+            date,
+            &DayCountConvention::Actual365,
+        )
+    }
+
+    /// Discount factor of the initial curve at time `t`. Unlike
+    /// [`Curve::discount_factor`], it does not round `t` to whole days, so
+    /// that forward rates can be taken by finite differences.
+    #[must_use]
+    pub fn market_discount_factor(&self, t: f64) -> f64 {
+        (-self.curve.rate(self.date(t)) * t).exp()
+    }
+
+    /// Instantaneous forward rate of the initial curve at time `t`
+    /// (finite difference of the log discount factors).
+    #[must_use]
+    pub fn market_forward_rate(&self, t: f64) -> f64 {
+        const H: f64 = 1e-4;
+
+        let (t0, t1) = ((t - H).max(0.0), t + H);
+        (self.market_discount_factor(t0) / self.market_discount_factor(t1)).ln() / (t1 - t0)
+    }
+
+    /// Drift term `theta(t)` that fits the model to the initial curve:
+    /// $\theta(t) = f'(0, t) + a f(0, t) + \frac{\sigma^2}{2a}(1 - e^{-2at})$.
+    #[must_use]
+    pub fn theta(&self, t: f64) -> f64 {
+        theta(&self.curve, self.a, self.sigma, t)
+    }
+
+    /// Price of a caplet (a put on a zero-coupon bond) or floorlet (a call),
+    /// on the initial curve (single-curve).
+    #[must_use]
+    pub fn caplet_price(&self, caplet: &Caplet) -> f64 {
+        let tau = caplet.year_fraction();
+        let (T, S) = (
+            self.time(caplet.accrual_start),
+            self.time(caplet.accrual_end),
+        );
+        let strike = 1.0 / (1.0 + caplet.strike * tau);
+
+        let option_type = match caplet.cap_floor_type {
+            CapFloorType::Cap => TypeFlag::Put,
+            CapFloorType::Floor => TypeFlag::Call,
+        };
+
+        caplet.notional / strike * self.bond_option_price(strike, T, S, option_type)
+    }
+
+    /// Price of a European swaption by Jamshidian's (1989) decomposition of
+    /// the option on the fixed leg coupon bond into options on zero-coupon
+    /// bonds, on the initial curve (single-curve). The swap is taken to
+    /// start on the expiry date, and the settlement to be physical.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fixed leg has no dates, or if the short rate at which
+    /// the coupon bond is at par is not found.
+    #[must_use]
+    pub fn swaption_price(&self, swaption: &Swaption) -> f64 {
+        let leg = &swaption.swap.fixed_leg;
+        let T = self.time(swaption.expiry);
+
+        // Coupon bond paying c_i at t_i: the fixed coupons, and the notional.
+        let mut coupons: Vec<(f64, f64)> = leg
+            .periods_after(swaption.expiry)
+            .iter()
+            .map(|(_, end, tau)| (self.time(*end), swaption.strike() * tau))
+            .collect();
+        coupons.last_mut().expect("The fixed leg has no dates.").1 += 1.0;
+
+        // Short rate at expiry for which the coupon bond is worth 1.
+        let value = |r: f64| -> f64 {
+            coupons
+                .iter()
+                .map(|(t, c)| c * self.bond_price(r, T, *t))
+                .sum()
+        };
+        let r_star = solve_decreasing(|r| value(r) - 1.0, self.market_forward_rate(T))
+            .expect("The coupon bond is at par for some short rate.");
+
+        // A payer swaption is a put on the coupon bond, struck at 1.
+        let option_type = match swaption.swap.direction {
+            SwapDirection::Payer => TypeFlag::Put,
+            SwapDirection::Receiver => TypeFlag::Call,
+        };
+
+        swaption.swap.notional
+            * coupons
+                .iter()
+                .map(|(t, c)| {
+                    c * self.bond_option_price(self.bond_price(r_star, T, *t), T, *t, option_type)
+                })
+                .sum::<f64>()
+    }
+
+    /// Calibrates `a` and `sigma` to market prices of caplets and swaptions
+    /// by least squares on the relative price errors, starting from the
+    /// model's parameters. Returns `None` if the optimiser fails.
+    #[must_use]
+    pub fn calibrate(
+        &self,
+        caplets: &[(Caplet, f64)],
+        swaptions: &[(Swaption, f64)],
+    ) -> Option<Self> {
+        let model = |p: &[f64]| Self::new(self.curve.clone(), p[0].exp(), p[1].exp());
+
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            let model = model(p);
+
+            caplets
+                .iter()
+                .map(|(caplet, price)| model.caplet_price(caplet) / price - 1.0)
+                .chain(
+                    swaptions
+                        .iter()
+                        .map(|(swaption, price)| model.swaption_price(swaption) / price - 1.0),
+                )
+                .collect()
+        };
+
+        let result =
+            LevenbergMarquardt::default().optimize(residuals, &[self.a.ln(), self.sigma.ln()])?;
+
+        Some(model(&result.minimizer))
+    }
+
+    // Date at time `t` from the initial date of the curve.
+    fn date(&self, t: f64) -> OffsetDateTime {
+        self.curve.initial_date() + Duration::seconds_f64(t * 365.0 * 86_400.0)
+    }
+}
+
+impl ShortRateModel for HullWhiteModel {
+    type Process = HullWhite;
+
+    fn initial_short_rate(&self) -> f64 {
+        self.market_forward_rate(0.0)
+    }
+
+    fn bond_price(&self, r: f64, t: f64, T: f64) -> f64 {
+        let a = self.a;
+        let B = -(-a * (T - t)).exp_m1() / a;
+        let A = self.market_discount_factor(T) / self.market_discount_factor(t)
+            * (B * self.market_forward_rate(t)
+                - self.sigma * self.sigma / (4.0 * a) * -(-2.0 * a * t).exp_m1() * B * B)
+                .exp();
+
+        A * (-B * r).exp()
+    }
+
+    fn bond_option_price(&self, K: f64, T: f64, S: f64, option_type: TypeFlag) -> f64 {
+        let sigma_p = gaussian_bond_volatility(self.a, self.sigma, T, S);
+
+        gaussian_bond_option(
+            self.market_discount_factor(T),
+            self.market_discount_factor(S),
+            K,
+            sigma_p,
+            option_type,
+        )
+    }
+
+    fn process(&self) -> HullWhite {
+        let (curve, a, sigma) = (self.curve.clone(), self.a, self.sigma);
+
+        HullWhite::new(a, sigma, move |t| theta(&curve, a, sigma, t))
+    }
+}
+
+// Hull-White drift fitting the initial curve.
+fn theta(curve: &YieldCurve, a: f64, sigma: f64, t: f64) -> f64 {
+    const H: f64 = 1e-4;
+
+    let model = HullWhiteModel::new(curve.clone(), a, sigma);
+    let f = |t: f64| model.market_forward_rate(t);
+    let slope = (f(t + H) - f((t - H).max(0.0))) / (t + H - (t - H).max(0.0));
+
+    slope + a * f(t) - sigma * sigma / (2.0 * a) * (-2.0 * a * t).exp_m1()
+}
+
+// Volatility of the log price at T of the bond maturing at S, for
+// Gaussian (Vasicek and Hull-White) models.
+fn gaussian_bond_volatility(a: f64, sigma: f64, T: f64, S: f64) -> f64 {
+    sigma / a * -(-a * (S - T)).exp_m1() * (-(-2.0 * a * T).exp_m1() / (2.0 * a)).sqrt()
+}
+
+// Option on a zero-coupon bond with lognormal price: Black's formula on
+// the forward bond price P(0, S) / P(0, T).
+fn gaussian_bond_option(P_T: f64, P_S: f64, K: f64, sigma_p: f64, option_type: TypeFlag) -> f64 {
+    let N = |x: f64| Gaussian::default().cdf(x);
+    let h = (P_S / (K * P_T)).ln() / sigma_p + 0.5 * sigma_p;
+
+    match option_type {
+        TypeFlag::Call => P_S * N(h) - K * P_T * N(h - sigma_p),
+        TypeFlag::Put => K * P_T * N(sigma_p - h) - P_S * N(-h),
+    }
+}
+
+// Non-central chi-squared distribution function, as a Poisson mixture of
+// central chi-squared distribution functions.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn noncentral_chi_squared_cdf(x: f64, dof: f64, noncentrality: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+
+    let half = 0.5 * noncentrality;
+    let terms = (half + 12.0 * half.sqrt() + 50.0).ceil() as usize;
+
+    (0..terms)
+        .map(|j| {
+            let j = j as f64;
+            let weight = (-half + j * half.ln() - ln_gamma(j + 1.0)).exp();
+            let weight = if half > 0.0 || j == 0.0 { weight } else { 0.0 };
+
+            weight * gamma_lr(0.5 * dof + j, 0.5 * x)
+        })
+        .sum()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_short_rate {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::curves::test_utils::{days, yield_curve};
+    use crate::curves::CurveContext;
+    use crate::instruments::rates::{CapFloor, InterestRateSwap, SwaptionSettlement};
+    use crate::time::Schedule;
+
+    // Monte Carlo price of an option on a zero-coupon bond, from simulated
+    // short-rate paths (trapezoidal discounting).
+    fn monte_carlo_bond_option<M: ShortRateModel>(
+        model: &M,
+        K: f64,
+        T: f64,
+        S: f64,
+        option_type: TypeFlag,
+    ) -> f64 {
+        let steps = 200;
+        let paths = model.simulate(T, steps, 20_000, true);
+        let dt = T / 200.0;
+
+        #[allow(clippy::cast_precision_loss)]
+        let n = paths.paths.len() as f64;
+
+        paths
+            .paths
+            .iter()
+            .map(|path| {
+                let integral: f64 = path.windows(2).map(|w| 0.5 * (w[0] + w[1]) * dt).sum();
+                let bond = model.bond_price(path[steps], T, S);
+                let payoff = match option_type {
+                    TypeFlag::Call => (bond - K).max(0.0),
+                    TypeFlag::Put => (K - bond).max(0.0),
+                };
+                (-integral).exp() * payoff
+            })
+            .sum::<f64>()
+            / n
+    }
+
+    #[test]
+    fn test_bond_prices() {
+        // Same values as the Vasicek and CIR bond pricers.
+        let vasicek = VasicekModel::new(0.03, 0.3, 0.1, 0.03);
+        let cir = CoxIngersollRossModel::new(0.03, 0.3, 0.1, 0.03);
+
+        assert_approx_equal!(vasicek.discount_factor(1.0), 0.9614, 1e-4);
+        assert_approx_equal!(cir.discount_factor(1.0), 0.9613, 1e-4);
+
+        // With no volatility, CIR and Vasicek agree.
+        let deterministic = |sigma| {
+            (
+                VasicekModel::new(0.03, 0.3, 0.1, sigma).discount_factor(5.0),
+                CoxIngersollRossModel::new(0.03, 0.3, 0.1, sigma).discount_factor(5.0),
+            )
+        };
+        let (v, c) = deterministic(1e-4);
+        assert_approx_equal!(v, c, 1e-6);
+    }
+
+    #[test]
+    fn test_bond_options() {
+        let vasicek = VasicekModel::new(0.03, 0.3, 0.06, 0.02);
+        let cir = CoxIngersollRossModel::new(0.03, 0.3, 0.06, 0.1);
+        let (T, S) = (1.0, 4.0);
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let K = vasicek.bond_price(0.045, T, S);
+            let analytic = vasicek.bond_option_price(K, T, S, option_type);
+            let mc = monte_carlo_bond_option(&vasicek, K, T, S, option_type);
+            assert_approx_equal!(mc, analytic, 0.05 * analytic);
+
+            let K = cir.bond_price(0.045, T, S);
+            let analytic = cir.bond_option_price(K, T, S, option_type);
+            let mc = monte_carlo_bond_option(&cir, K, T, S, option_type);
+            assert_approx_equal!(mc, analytic, 0.05 * analytic);
+        }
+
+        // CIR put-call parity.
+        let K = 0.85;
+        assert_approx_equal!(
+            cir.bond_option_price(K, T, S, TypeFlag::Call)
+                - cir.bond_option_price(K, T, S, TypeFlag::Put),
+            cir.discount_factor(S) - K * cir.discount_factor(T),
+            1e-12
+        );
+    }
+
+    fn curve() -> YieldCurve {
+        yield_curve(&[(0, 0.02), (730, 0.03), (4000, 0.04)])
+    }
+
+    #[test]
+    fn test_hull_white() {
+        let model = HullWhiteModel::new(curve(), 0.1, 0.01);
+
+        // The model reprices the curve, also from a later date through the
+        // bond price at the expected short rate.
+        for t in [0.5, 2.0, 7.0] {
+            assert_approx_equal!(
+                model.discount_factor(t),
+                model.market_discount_factor(t),
+                1e-12
+            );
+        }
+
+        // Bond option by Monte Carlo with the fitted drift.
+        let (T, S) = (1.0, 3.0);
+        let K = model.market_discount_factor(S) / model.market_discount_factor(T);
+        let analytic = model.bond_option_price(K, T, S, TypeFlag::Call);
+        let mc = monte_carlo_bond_option(&model, K, T, S, TypeFlag::Call);
+        assert_approx_equal!(mc, analytic, 0.05 * analytic);
+
+        // A one-period swaption is a caplet on the swap rate.
+        let schedule = Schedule::new_from_start(days(365), Duration::days(365), 1);
+        let swap =
+            InterestRateSwap::new(100.0, 0.035, SwapDirection::Payer, &schedule, &schedule, "");
+        let swaption = Swaption::new(swap, days(365), SwaptionSettlement::Physical);
+        let caplet = &CapFloor::new(CapFloorType::Cap, 100.0, 0.035, &schedule, "")
+            .caplets(&CurveContext::new(curve()))[0];
+
+        assert_approx_equal!(
+            model.swaption_price(&swaption),
+            model.caplet_price(caplet),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_hull_white_swaptions_and_calibration() {
+        let model = HullWhiteModel::new(curve(), 0.08, 0.012);
+        let context = CurveContext::new(curve());
+
+        let swaption = |expiry: i64, years: i64, direction| {
+            let schedule = Schedule::new_from_start(days(365 * expiry), Duration::days(365), years);
+            let swap = InterestRateSwap::new(100.0, 0.0, direction, &schedule, &schedule, "");
+            let atm = swap.par_rate(&context);
+            Swaption::new(
+                InterestRateSwap {
+                    fixed_rate: atm,
+                    ..swap
+                },
+                days(365 * expiry),
+                SwaptionSettlement::Physical,
+            )
+        };
+
+        // Payer - receiver = the forward swap.
+        let payer = swaption(2, 5, SwapDirection::Payer);
+        let receiver = swaption(2, 5, SwapDirection::Receiver);
+        let strike = InterestRateSwap {
+            fixed_rate: 0.04,
+            ..payer.swap.clone()
+        };
+        let (payer_otm, receiver_itm) = (
+            Swaption::new(strike.clone(), payer.expiry, payer.settlement),
+            Swaption::new(
+                InterestRateSwap {
+                    direction: SwapDirection::Receiver,
+                    ..strike.clone()
+                },
+                payer.expiry,
+                payer.settlement,
+            ),
+        );
+        assert_approx_equal!(
+            model.swaption_price(&payer_otm) - model.swaption_price(&receiver_itm),
+            strike.npv(&context),
+            1e-10
+        );
+        assert_approx_equal!(
+            model.swaption_price(&payer),
+            model.swaption_price(&receiver),
+            1e-10
+        );
+
+        // Calibration recovers the parameters from model prices.
+        let caplets: Vec<(Caplet, f64)> = CapFloor::new(
+            CapFloorType::Cap,
+            100.0,
+            0.03,
+            &Schedule::new_from_start(days(0), Duration::days(182), 10),
+            "",
+        )
+        .caplets(&context)
+        .into_iter()
+        .map(|caplet| {
+            let price = model.caplet_price(&caplet);
+            (caplet, price)
+        })
+        .collect();
+        let swaptions: Vec<(Swaption, f64)> = [(1, 5), (2, 5), (5, 5)]
+            .iter()
+            .map(|(expiry, years)| {
+                let swaption = swaption(*expiry, *years, SwapDirection::Payer);
+                let price = model.swaption_price(&swaption);
+                (swaption, price)
+            })
+            .collect();
+
+        let calibrated = HullWhiteModel::new(curve(), 0.03, 0.02)
+            .calibrate(&caplets, &swaptions)
+            .unwrap();
+
+        assert_approx_equal!(calibrated.a, 0.08, 1e-6);
+        assert_approx_equal!(calibrated.sigma, 0.012, 1e-8);
+    }
+}
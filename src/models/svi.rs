@@ -0,0 +1,711 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Stochastic volatility inspired (SVI) parameterisation of a volatility smile.
+//!
+//! The raw SVI parameterisation (Gatheral, 2004) gives the total implied
+//! variance `w = v^2 T` of a single expiry as a function of the
+//! log-moneyness `k = ln(K / F)`:
+//!
+//! $$
+//! w(k) = a + b \left( \rho (k - m) + \sqrt{(k - m)^2 + \sigma^2} \right)
+//! $$
+//!
+//! The natural parameterisation (Gatheral and Jacquier, 2014) describes the
+//! same smiles as
+//!
+//! $$
+//! w(k) = \Delta + \frac{\omega}{2} \left( 1 + \zeta \rho (k - \mu) + \sqrt{(\zeta (k - \mu) + \rho)^2 + 1 - \rho^2} \right)
+//! $$
+//!
+//! Slices can be fitted either by plain least squares on all five
+//! parameters, or with the quasi-explicit method of Zeliade Systems (2009),
+//! which solves a constrained linear least squares problem in `(a, b, rho)`
+//! for each `(m, sigma)` and only searches over the latter two.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::LevenbergMarquardt;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Raw SVI parameters of a single expiry.
+///
+/// ```
+/// use RustQuant::models::*;
+///
+/// let svi = RawSvi::new(0.02, 0.1, -0.4, 0.05, 0.2);
+///
+/// let k = [-0.4, -0.2, -0.1, 0.0, 0.1, 0.2, 0.4];
+/// let w: Vec<f64> = k.iter().map(|&k| svi.total_variance(k)).collect();
+///
+/// let fitted = RawSvi::fit(&k, &w).unwrap();
+///
+/// assert!((fitted.rho + 0.4).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawSvi {
+    /// `a` - Overall level of the total variance.
+    pub a: f64,
+    /// `b` - Slope of the wings, non-negative.
+    pub b: f64,
+    /// `rho` - Asymmetry of the smile, in `(-1, 1)`.
+    pub rho: f64,
+    /// `m` - Horizontal translation of the smile.
+    pub m: f64,
+    /// `sigma` - Curvature of the smile at the minimum, positive.
+    pub sigma: f64,
+}
+
+/// Natural SVI parameters of a single expiry.
+///
+/// ```
+/// use RustQuant::models::*;
+///
+/// let raw = RawSvi::new(0.02, 0.1, -0.4, 0.05, 0.2);
+/// let natural = NaturalSvi::from(raw);
+///
+/// assert!((natural.total_variance(0.3) - raw.total_variance(0.3)).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NaturalSvi {
+    /// `delta` - Level shift of the total variance.
+    pub delta: f64,
+    /// `mu` - Horizontal translation of the smile.
+    pub mu: f64,
+    /// `rho` - Asymmetry of the smile, in `(-1, 1)`.
+    pub rho: f64,
+    /// `omega` - At-the-money total variance scale, non-negative.
+    pub omega: f64,
+    /// `zeta` - Curvature of the smile, positive.
+    pub zeta: f64,
+}
+
+/// SVI calibration and arbitrage errors.
+#[derive(Debug, thiserror::Error)]
+pub enum SviError {
+    /// The log-moneyness and the total variances have different lengths.
+    #[error("Number of log-moneyness points and total variances do not match")]
+    LengthMismatch,
+
+    /// Fewer quotes than parameters to fit.
+    #[error("Not enough quotes to fit the parameters")]
+    NotEnoughQuotes,
+
+    /// The forward, a strike, a volatility or the expiry is not positive.
+    #[error("Forward, strikes, volatilities and expiry must be positive")]
+    NonPositiveInput,
+
+    /// The fit did not produce finite parameters.
+    #[error("Calibration failed")]
+    CalibrationFailed,
+
+    /// `b < 0`, `|rho| > 1` or `sigma <= 0`.
+    #[error("SVI parameters out of range")]
+    InvalidParameters,
+
+    /// The minimum total variance `a + b sigma sqrt(1 - rho^2)` is negative.
+    #[error("Total variance is negative")]
+    NegativeVariance,
+
+    /// The wings are steeper than Lee's moment formula allows: `b (1 + |rho|) > 4`.
+    #[error("Wing slopes exceed Lee's bound")]
+    WingSlope,
+
+    /// Durrleman's condition fails, so the implied density is negative somewhere.
+    #[error("Smile has butterfly arbitrage")]
+    ButterflyArbitrage,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RawSvi {
+    /// New raw SVI slice.
+    #[must_use]
+    pub const fn new(a: f64, b: f64, rho: f64, m: f64, sigma: f64) -> Self {
+        Self {
+            a,
+            b,
+            rho,
+            m,
+            sigma,
+        }
+    }
+
+    /// Total implied variance `w(k)` at log-moneyness `k`.
+    #[must_use]
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = k - self.m;
+
+        self.a + self.b * (self.rho * x + x.hypot(self.sigma))
+    }
+
+    /// First derivative `w'(k)` of the total variance.
+    #[must_use]
+    pub fn total_variance_first_derivative(&self, k: f64) -> f64 {
+        let x = k - self.m;
+
+        self.b * (self.rho + x / x.hypot(self.sigma))
+    }
+
+    /// Second derivative `w''(k)` of the total variance.
+    #[must_use]
+    pub fn total_variance_second_derivative(&self, k: f64) -> f64 {
+        let r = (k - self.m).hypot(self.sigma);
+
+        self.b * self.sigma * self.sigma / (r * r * r)
+    }
+
+    /// Implied volatility at log-moneyness `k` for an expiry (in years).
+    #[must_use]
+    pub fn implied_volatility(&self, k: f64, expiry: f64) -> f64 {
+        (self.total_variance(k) / expiry).sqrt()
+    }
+
+    /// Implied volatilities for strikes, given the forward and the expiry (in years).
+    #[must_use]
+    pub fn volatilities(&self, forward: f64, expiry: f64, strikes: &[f64]) -> Vec<f64> {
+        strikes
+            .iter()
+            .map(|&strike| self.implied_volatility((strike / forward).ln(), expiry))
+            .collect()
+    }
+
+    /// Minimum of the total variance, `a + b sigma sqrt(1 - rho^2)`.
+    #[must_use]
+    pub fn minimum_total_variance(&self) -> f64 {
+        self.a + self.b * self.sigma * (1.0 - self.rho * self.rho).sqrt()
+    }
+
+    /// Durrleman's function `g(k)`, which is non-negative where the
+    /// risk-neutral density implied by the smile is non-negative.
+    #[must_use]
+    pub fn durrleman(&self, k: f64) -> f64 {
+        durrleman(
+            k,
+            self.total_variance(k),
+            self.total_variance_first_derivative(k),
+            self.total_variance_second_derivative(k),
+        )
+    }
+
+    /// Check the slice for static arbitrage.
+    ///
+    /// The parameters must be in range, the total variance non-negative, the
+    /// wing slopes within Lee's bound `b (1 + |rho|) <= 4`, and Durrleman's
+    /// condition `g(k) >= 0` must hold on a grid of log-moneyness around `m`.
+    ///
+    /// # Errors
+    ///
+    /// The first failed condition, as one of `SviError::InvalidParameters`,
+    /// `SviError::NegativeVariance`, `SviError::WingSlope` or
+    /// `SviError::ButterflyArbitrage`.
+    pub fn check_arbitrage(&self) -> Result<(), SviError> {
+        const TOLERANCE: f64 = 1e-10;
+
+        if self.b.is_nan()
+            || self.b < 0.0
+            || self.rho.abs() > 1.0
+            || self.sigma.is_nan()
+            || self.sigma <= 0.0
+        {
+            return Err(SviError::InvalidParameters);
+        }
+        if self.minimum_total_variance() < -TOLERANCE {
+            return Err(SviError::NegativeVariance);
+        }
+        if self.b * (1.0 + self.rho.abs()) > 4.0 + TOLERANCE {
+            return Err(SviError::WingSlope);
+        }
+
+        // Negative density only appears near the minimum of the smile.
+        let width = 5.0 + 20.0 * self.sigma;
+        if (0..=1000)
+            .map(|i| self.m - width + 2.0 * width * f64::from(i) / 1000.0)
+            .any(|k| self.durrleman(k) < -TOLERANCE)
+        {
+            return Err(SviError::ButterflyArbitrage);
+        }
+
+        Ok(())
+    }
+
+    /// Fit the parameters to implied volatilities of the same expiry, with
+    /// the quasi-explicit method (see [`RawSvi::fit_quasi_explicit`]).
+    ///
+    /// # Errors
+    ///
+    /// - `SviError::LengthMismatch` if the strikes and volatilities differ in length.
+    /// - `SviError::NonPositiveInput` for a non-positive forward, strike,
+    ///   volatility or expiry.
+    /// - `SviError::NotEnoughQuotes` if there are fewer than five quotes.
+    /// - `SviError::CalibrationFailed` if the fit breaks down.
+    pub fn calibrate(
+        forward: f64,
+        expiry: f64,
+        strikes: &[f64],
+        volatilities: &[f64],
+    ) -> Result<Self, SviError> {
+        if strikes.len() != volatilities.len() {
+            return Err(SviError::LengthMismatch);
+        }
+        if forward <= 0.0 || expiry <= 0.0 || strikes.iter().chain(volatilities).any(|&x| x <= 0.0)
+        {
+            return Err(SviError::NonPositiveInput);
+        }
+
+        let k: Vec<f64> = strikes.iter().map(|&x| (x / forward).ln()).collect();
+        let w: Vec<f64> = volatilities.iter().map(|&v| v * v * expiry).collect();
+
+        Self::fit_quasi_explicit(&k, &w)
+    }
+
+    /// Fit the parameters to total variances `w` at log-moneyness `k` with
+    /// the quasi-explicit method of Zeliade Systems (2009).
+    ///
+    /// With `y = (k - m) / sigma` the smile is linear in `(a, d, c)`:
+    ///
+    /// $$
+    /// w = a + d y + c \sqrt{y^2 + 1}, \qquad d = \rho b \sigma, \quad c = b \sigma
+    /// $$
+    ///
+    /// For given `(m, sigma)` these are found by linear least squares subject
+    /// to `0 <= a <= max(w)`, `|d| <= c` and `|d| <= 4 sigma - c`, which keep
+    /// the total variance non-negative and the wings within Lee's bound.
+    /// The remaining two parameters are fitted by Levenberg-Marquardt,
+    /// started from several values of `m` across the quotes.
+    /// The result is not guaranteed to be free of butterfly arbitrage, which
+    /// can be checked with [`RawSvi::check_arbitrage`].
+    ///
+    /// # Errors
+    ///
+    /// - `SviError::LengthMismatch` if `k` and `w` differ in length.
+    /// - `SviError::NotEnoughQuotes` if there are fewer than five quotes.
+    /// - `SviError::CalibrationFailed` if the fit breaks down.
+    pub fn fit_quasi_explicit(k: &[f64], w: &[f64]) -> Result<Self, SviError> {
+        if k.len() != w.len() {
+            return Err(SviError::LengthMismatch);
+        }
+        if k.len() < 5 {
+            return Err(SviError::NotEnoughQuotes);
+        }
+
+        // Outer parameters: m = p0 and sigma = exp(p1).
+        let residuals = |p: &[f64]| Self::quasi_explicit_slice(k, w, p[0], p[1].exp()).1;
+
+        let k_min = k.iter().copied().fold(f64::INFINITY, f64::min);
+        let k_max = k.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let spread = (k_max - k_min).max(1e-4);
+
+        let best = (0..5)
+            .filter_map(|i| {
+                let m_0 = k_min + spread * f64::from(i) / 4.0;
+                LevenbergMarquardt::default().optimize(residuals, &[m_0, (0.1 * spread).ln()])
+            })
+            .filter(|result| result.cost.is_finite())
+            .min_by(|x, y| x.cost.total_cmp(&y.cost))
+            .ok_or(SviError::CalibrationFailed)?;
+
+        let (m, sigma) = (best.minimizer[0], best.minimizer[1].exp());
+        let model = Self::quasi_explicit_slice(k, w, m, sigma).0;
+
+        if [model.a, model.b, model.rho, model.m, model.sigma]
+            .iter()
+            .all(|x| x.is_finite())
+        {
+            Ok(model)
+        } else {
+            Err(SviError::CalibrationFailed)
+        }
+    }
+
+    // Best slice and its residuals for given (m, sigma): constrained linear
+    // least squares in x = (a, d, c), solved by enumerating the active sets
+    // of the six linear constraints and keeping the best feasible solution.
+    fn quasi_explicit_slice(k: &[f64], w: &[f64], m: f64, sigma: f64) -> (Self, Vec<f64>) {
+        let n = k.len();
+        let w_max = w.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let design = DMatrix::from_fn(n, 3, |i, j| {
+            let y = (k[i] - m) / sigma;
+            match j {
+                0 => 1.0,
+                1 => y,
+                _ => y.hypot(1.0),
+            }
+        });
+        let target = DVector::from_column_slice(w);
+
+        // Constraints A x <= bound.
+        let constraints = DMatrix::from_row_slice(
+            6,
+            3,
+            &[
+                -1.0, 0.0, 0.0, //
+                1.0, 0.0, 0.0, //
+                0.0, 1.0, -1.0, //
+                0.0, -1.0, -1.0, //
+                0.0, 1.0, 1.0, //
+                0.0, -1.0, 1.0, //
+            ],
+        );
+        let bound = DVector::from_column_slice(&[0.0, w_max, 0.0, 0.0, 4.0 * sigma, 4.0 * sigma]);
+
+        let hessian = design.transpose() * &design;
+        let gradient = design.transpose() * &target;
+        let tolerance = 1e-12 * (1.0 + w_max.abs() + sigma);
+
+        let best = (0_u32..64)
+            .filter(|mask| mask.count_ones() <= 3)
+            .filter_map(|mask| {
+                let active: Vec<usize> = (0..6).filter(|i| mask & (1 << i) != 0).collect();
+                let size = 3 + active.len();
+
+                // KKT system of the equality constrained problem.
+                let mut kkt = DMatrix::zeros(size, size);
+                let mut rhs = DVector::zeros(size);
+                kkt.view_mut((0, 0), (3, 3)).copy_from(&hessian);
+                rhs.rows_mut(0, 3).copy_from(&gradient);
+                for (row, &i) in active.iter().enumerate() {
+                    for j in 0..3 {
+                        kkt[(3 + row, j)] = constraints[(i, j)];
+                        kkt[(j, 3 + row)] = constraints[(i, j)];
+                    }
+                    rhs[3 + row] = bound[i];
+                }
+
+                let x = kkt.lu().solve(&rhs)?.rows(0, 3).into_owned();
+
+                let feasible = (&constraints * &x - &bound).iter().all(|&e| e <= tolerance);
+                let cost = (&design * &x - &target).norm_squared();
+
+                (feasible && cost.is_finite()).then_some((x, cost))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        let Some((x, _)) = best else {
+            return (
+                Self::new(f64::NAN, f64::NAN, f64::NAN, m, sigma),
+                vec![f64::NAN; n],
+            );
+        };
+
+        let (a, d, c) = (x[0], x[1], x[2]);
+        let model = Self::new(a, c / sigma, if c > 0.0 { d / c } else { 0.0 }, m, sigma);
+        let residuals = (&design * &x - &target).as_slice().to_vec();
+
+        (model, residuals)
+    }
+
+    /// Fit the parameters to total variances `w` at log-moneyness `k`, by
+    /// Levenberg-Marquardt least squares on the total variances.
+    ///
+    /// The fit is started from several values of `m` across the quotes and
+    /// the best fit is kept, since the SVI least squares problem has local minima.
+    ///
+    /// # Errors
+    ///
+    /// - `SviError::LengthMismatch` if `k` and `w` differ in length.
+    /// - `SviError::NotEnoughQuotes` if there are fewer than five quotes.
+    /// - `SviError::CalibrationFailed` if the fit breaks down.
+    pub fn fit(k: &[f64], w: &[f64]) -> Result<Self, SviError> {
+        if k.len() != w.len() {
+            return Err(SviError::LengthMismatch);
+        }
+        if k.len() < 5 {
+            return Err(SviError::NotEnoughQuotes);
+        }
+
+        // Unconstrained parameters: a = p0, b = exp(p1), rho = tanh(p2),
+        // m = p3 and sigma = exp(p4).
+        let to_model = |p: &[f64]| Self::new(p[0], p[1].exp(), p[2].tanh(), p[3], p[4].exp());
+
+        let residuals = |p: &[f64]| -> Vec<f64> {
+            let model = to_model(p);
+            k.iter()
+                .zip(w)
+                .map(|(&k, &w)| model.total_variance(k) - w)
+                .collect()
+        };
+
+        let k_min = k.iter().copied().fold(f64::INFINITY, f64::min);
+        let k_max = k.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let w_min = w.iter().copied().fold(f64::INFINITY, f64::min);
+        let spread = (k_max - k_min).max(1e-4);
+
+        let best = (0..5)
+            .filter_map(|i| {
+                let m_0 = k_min + spread * f64::from(i) / 4.0;
+                let p0 = [w_min, 0.1_f64.ln(), 0.0, m_0, (0.1 * spread).ln()];
+                LevenbergMarquardt::default().optimize(residuals, &p0)
+            })
+            .filter(|result| result.cost.is_finite())
+            .min_by(|x, y| x.cost.total_cmp(&y.cost))
+            .ok_or(SviError::CalibrationFailed)?;
+
+        let model = to_model(&best.minimizer);
+
+        if [model.a, model.b, model.rho, model.m, model.sigma]
+            .iter()
+            .all(|x| x.is_finite())
+        {
+            Ok(model)
+        } else {
+            Err(SviError::CalibrationFailed)
+        }
+    }
+}
+
+impl NaturalSvi {
+    /// New natural SVI slice.
+    #[must_use]
+    pub const fn new(delta: f64, mu: f64, rho: f64, omega: f64, zeta: f64) -> Self {
+        Self {
+            delta,
+            mu,
+            rho,
+            omega,
+            zeta,
+        }
+    }
+
+    /// Total implied variance `w(k)` at log-moneyness `k`.
+    #[must_use]
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = self.zeta * (k - self.mu);
+
+        self.delta
+            + 0.5
+                * self.omega
+                * (1.0 + self.rho * x + (x + self.rho).hypot((1.0 - self.rho * self.rho).sqrt()))
+    }
+
+    /// Implied volatility at log-moneyness `k` for an expiry (in years).
+    #[must_use]
+    pub fn implied_volatility(&self, k: f64, expiry: f64) -> f64 {
+        (self.total_variance(k) / expiry).sqrt()
+    }
+}
+
+impl From<NaturalSvi> for RawSvi {
+    fn from(svi: NaturalSvi) -> Self {
+        let NaturalSvi {
+            delta,
+            mu,
+            rho,
+            omega,
+            zeta,
+        } = svi;
+
+        Self::new(
+            delta + 0.5 * omega * (1.0 - rho * rho),
+            0.5 * omega * zeta,
+            rho,
+            mu - rho / zeta,
+            (1.0 - rho * rho).sqrt() / zeta,
+        )
+    }
+}
+
+/// Requires `|rho| < 1`, otherwise `zeta` is zero and `omega` infinite.
+impl From<RawSvi> for NaturalSvi {
+    fn from(svi: RawSvi) -> Self {
+        let RawSvi {
+            a,
+            b,
+            rho,
+            m,
+            sigma,
+        } = svi;
+        let zeta = (1.0 - rho * rho).sqrt() / sigma;
+        let omega = 2.0 * b / zeta;
+
+        Self::new(
+            a - 0.5 * omega * (1.0 - rho * rho),
+            m + rho / zeta,
+            rho,
+            omega,
+            zeta,
+        )
+    }
+}
+
+/// Durrleman's function
+///
+/// $$
+/// g(k) = \left(1 - \frac{k w'}{2 w}\right)^2 - \frac{w'^2}{4}\left(\frac{1}{w} + \frac{1}{4}\right) + \frac{w''}{2}
+/// $$
+///
+/// from the total variance `w` and its first two derivatives at `k`.
+/// A smile is free of butterfly arbitrage where `g(k) >= 0`.
+#[must_use]
+pub fn durrleman(k: f64, w: f64, dw: f64, d2w: f64) -> f64 {
+    if w <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+
+    (1.0 - 0.5 * k * dw / w).powi(2) - 0.25 * dw * dw * (1.0 / w + 0.25) + 0.5 * d2w
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_svi {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const SVI: RawSvi = RawSvi::new(0.04, 0.4, -0.6, 0.05, 0.3);
+
+    #[test]
+    fn test_derivatives() {
+        let h = 1e-5;
+
+        for k in [-1.0, -0.3, 0.0, 0.05, 0.4, 1.5] {
+            let up = SVI.total_variance(k + h);
+            let down = SVI.total_variance(k - h);
+
+            assert_approx_equal!(
+                SVI.total_variance_first_derivative(k),
+                (up - down) / (2.0 * h),
+                1e-8
+            );
+            assert_approx_equal!(
+                SVI.total_variance_second_derivative(k),
+                (up - 2.0 * SVI.total_variance(k) + down) / (h * h),
+                1e-4
+            );
+        }
+    }
+
+    #[test]
+    fn test_fit() {
+        let k: Vec<f64> = (-8..=8).map(|i| f64::from(i) * 0.1).collect();
+        let w: Vec<f64> = k.iter().map(|&k| SVI.total_variance(k)).collect();
+
+        let fitted = RawSvi::fit(&k, &w).unwrap();
+
+        assert_approx_equal!(fitted.a, SVI.a, 1e-6);
+        assert_approx_equal!(fitted.b, SVI.b, 1e-6);
+        assert_approx_equal!(fitted.rho, SVI.rho, 1e-6);
+        assert_approx_equal!(fitted.m, SVI.m, 1e-6);
+        assert_approx_equal!(fitted.sigma, SVI.sigma, 1e-6);
+    }
+
+    #[test]
+    fn test_natural_parameterisation() {
+        let natural = NaturalSvi::from(SVI);
+        let raw = RawSvi::from(natural);
+
+        for k in [-1.0, -0.2, 0.0, 0.3, 1.2] {
+            assert_approx_equal!(natural.total_variance(k), SVI.total_variance(k), 1e-14);
+        }
+
+        assert_approx_equal!(raw.a, SVI.a, 1e-14);
+        assert_approx_equal!(raw.b, SVI.b, 1e-14);
+        assert_approx_equal!(raw.rho, SVI.rho, 1e-14);
+        assert_approx_equal!(raw.m, SVI.m, 1e-14);
+        assert_approx_equal!(raw.sigma, SVI.sigma, 1e-14);
+    }
+
+    #[test]
+    fn test_quasi_explicit() {
+        let k: Vec<f64> = (-8..=8).map(|i| f64::from(i) * 0.1).collect();
+        let w: Vec<f64> = k.iter().map(|&k| SVI.total_variance(k)).collect();
+
+        let fitted = RawSvi::fit_quasi_explicit(&k, &w).unwrap();
+
+        assert_approx_equal!(fitted.a, SVI.a, 1e-6);
+        assert_approx_equal!(fitted.b, SVI.b, 1e-6);
+        assert_approx_equal!(fitted.rho, SVI.rho, 1e-6);
+        assert_approx_equal!(fitted.m, SVI.m, 1e-6);
+        assert_approx_equal!(fitted.sigma, SVI.sigma, 1e-6);
+    }
+
+    #[test]
+    fn test_quasi_explicit_constraints() {
+        // Steep wings and a negative level: the fit stays inside the domain.
+        let steep = RawSvi::new(-0.05, 3.5, 0.4, 0.0, 0.2);
+        let k: Vec<f64> = (-10..=10).map(|i| f64::from(i) * 0.1).collect();
+        let w: Vec<f64> = k.iter().map(|&k| steep.total_variance(k)).collect();
+
+        let fitted = RawSvi::fit_quasi_explicit(&k, &w).unwrap();
+
+        assert!(fitted.a >= -1e-12);
+        assert!(fitted.b * (1.0 + fitted.rho.abs()) <= 4.0 + 1e-9);
+        assert!(fitted.minimum_total_variance() >= -1e-12);
+    }
+
+    #[test]
+    fn test_implied_volatility_quotes() {
+        let (forward, expiry) = (100.0, 0.75);
+        let strikes = [60.0, 75.0, 90.0, 100.0, 110.0, 125.0, 150.0];
+
+        let vols = SVI.volatilities(forward, expiry, &strikes);
+        let fitted = RawSvi::calibrate(forward, expiry, &strikes, &vols).unwrap();
+
+        for (v, fitted) in vols
+            .iter()
+            .zip(fitted.volatilities(forward, expiry, &strikes))
+        {
+            assert_approx_equal!(*v, fitted, 1e-8);
+        }
+
+        assert!(matches!(
+            RawSvi::calibrate(-forward, expiry, &strikes, &vols),
+            Err(SviError::NonPositiveInput)
+        ));
+    }
+
+    #[test]
+    fn test_check_arbitrage() {
+        assert!(SVI.check_arbitrage().is_ok());
+
+        // Axel Vogt's smile: valid parameters but a negative density.
+        let vogt = RawSvi::new(-0.0410, 0.1331, 0.3060, 0.3586, 0.4153);
+        assert!(vogt.minimum_total_variance() > 0.0);
+        assert!(matches!(
+            vogt.check_arbitrage(),
+            Err(SviError::ButterflyArbitrage)
+        ));
+
+        assert!(matches!(
+            RawSvi::new(0.04, 0.4, 1.2, 0.0, 0.3).check_arbitrage(),
+            Err(SviError::InvalidParameters)
+        ));
+        assert!(matches!(
+            RawSvi::new(-0.2, 0.4, 0.0, 0.0, 0.3).check_arbitrage(),
+            Err(SviError::NegativeVariance)
+        ));
+        assert!(matches!(
+            RawSvi::new(0.04, 3.0, 0.5, 0.0, 0.3).check_arbitrage(),
+            Err(SviError::WingSlope)
+        ));
+    }
+
+    #[test]
+    fn test_fit_errors() {
+        assert!(matches!(
+            RawSvi::fit(&[0.0, 0.1], &[0.04]),
+            Err(SviError::LengthMismatch)
+        ));
+        assert!(matches!(
+            RawSvi::fit(&[0.0, 0.1], &[0.04, 0.05]),
+            Err(SviError::NotEnoughQuotes)
+        ));
+    }
+}
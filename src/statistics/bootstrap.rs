@@ -0,0 +1,358 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bootstrap resampling of (time series of) observations, for the
+//! sampling distribution and confidence intervals of any statistic, e.g.
+//! a Sharpe ratio or a value at risk.
+//!
+//! Resampling individual observations destroys serial dependence, such as
+//! volatility clustering, and so understates the variability of statistics
+//! of returns. The block bootstraps resample blocks of consecutive
+//! observations instead, which preserves the dependence within each block.
+
+use crate::statistics::distributions::{Distribution, EmpiricalDistribution};
+use rand::Rng;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bootstrap error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BootstrapError {
+    /// A parameter of the bootstrap is out of range.
+    #[error("Bootstrap parameter out of range")]
+    InvalidParameter,
+
+    /// There are too few observations (e.g. fewer than one block).
+    #[error("Not enough observations to bootstrap")]
+    NotEnoughObservations,
+}
+
+/// Resampling scheme of a bootstrap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resampling {
+    /// Independent draws with replacement (Efron, 1979), for serially
+    /// independent observations.
+    Iid,
+
+    /// Moving block bootstrap (Künsch, 1989): blocks of `block_length`
+    /// consecutive observations, starting at uniformly drawn positions.
+    Block {
+        /// Length of each block.
+        block_length: usize,
+    },
+
+    /// Stationary bootstrap (Politis and Romano, 1994): blocks of
+    /// geometrically distributed length, wrapping around the end of the
+    /// series, so that the resampled series is stationary.
+    Stationary {
+        /// Mean length of the blocks.
+        mean_block_length: f64,
+    },
+}
+
+/// Bootstrap of a statistic.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// let returns: Vec<f64> = (1..=500).map(|t| 0.001 + 0.01 * (t as f64 * 12.9898).sin()).collect();
+///
+/// // Confidence interval of the (per period) Sharpe ratio.
+/// let sharpe = |x: &[f64]| {
+///     let mean = x.iter().sum::<f64>() / x.len() as f64;
+///     let variance = x.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (x.len() - 1) as f64;
+///     mean / variance.sqrt()
+/// };
+///
+/// let bootstrap = Bootstrap::new(Resampling::Stationary { mean_block_length: 10.0 }, 1_000).unwrap();
+/// let interval = bootstrap
+///     .confidence_interval(&returns, sharpe, 0.95, &mut rand::thread_rng())
+///     .unwrap();
+///
+/// assert!(interval.lower < interval.estimate && interval.estimate < interval.upper);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bootstrap {
+    /// The resampling scheme.
+    pub resampling: Resampling,
+
+    /// The number of bootstrap replications.
+    pub replications: usize,
+}
+
+/// Bootstrap estimate of a statistic, with its percentile confidence
+/// interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceInterval {
+    /// The statistic of the original observations.
+    pub estimate: f64,
+
+    /// The lower end of the interval.
+    pub lower: f64,
+
+    /// The upper end of the interval.
+    pub upper: f64,
+
+    /// The standard deviation of the bootstrapped statistic.
+    pub standard_error: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Bootstrap {
+    /// New bootstrap with the resampling scheme and number of replications.
+    ///
+    /// # Errors
+    ///
+    /// `BootstrapError::InvalidParameter` if there are no replications, the
+    /// block length is zero, or the mean block length is less than one.
+    pub fn new(resampling: Resampling, replications: usize) -> Result<Self, BootstrapError> {
+        let valid = match resampling {
+            Resampling::Iid => true,
+            Resampling::Block { block_length } => block_length > 0,
+            Resampling::Stationary { mean_block_length } => mean_block_length >= 1.0,
+        };
+
+        if valid && replications > 0 {
+            Ok(Self {
+                resampling,
+                replications,
+            })
+        } else {
+            Err(BootstrapError::InvalidParameter)
+        }
+    }
+
+    /// One resample of `data`, of the same length.
+    ///
+    /// # Errors
+    ///
+    /// `BootstrapError::NotEnoughObservations` if `data` is empty, or
+    /// shorter than a block.
+    pub fn resample<R: Rng + ?Sized>(
+        &self,
+        data: &[f64],
+        rng: &mut R,
+    ) -> Result<Vec<f64>, BootstrapError> {
+        let n = data.len();
+        if n == 0 {
+            return Err(BootstrapError::NotEnoughObservations);
+        }
+
+        let resample = match self.resampling {
+            Resampling::Iid => (0..n).map(|_| data[rng.gen_range(0..n)]).collect(),
+            Resampling::Block { block_length } => {
+                if block_length > n {
+                    return Err(BootstrapError::NotEnoughObservations);
+                }
+
+                let mut resample = Vec::with_capacity(n + block_length);
+                while resample.len() < n {
+                    let start = rng.gen_range(0..=n - block_length);
+                    resample.extend_from_slice(&data[start..start + block_length]);
+                }
+                resample.truncate(n);
+                resample
+            }
+            Resampling::Stationary { mean_block_length } => {
+                let restart = mean_block_length.recip();
+                let mut index = rng.gen_range(0..n);
+
+                (0..n)
+                    .map(|_| {
+                        let x = data[index];
+                        index = if rng.gen::<f64>() < restart {
+                            rng.gen_range(0..n)
+                        } else {
+                            (index + 1) % n
+                        };
+                        x
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(resample)
+    }
+
+    /// The `statistic` of each of the bootstrap resamples of `data`: a
+    /// sample from its bootstrap distribution.
+    ///
+    /// # Errors
+    ///
+    /// `BootstrapError::NotEnoughObservations` if `data` is empty, or
+    /// shorter than a block.
+    pub fn replicate<F, R>(
+        &self,
+        data: &[f64],
+        statistic: F,
+        rng: &mut R,
+    ) -> Result<Vec<f64>, BootstrapError>
+    where
+        F: Fn(&[f64]) -> f64,
+        R: Rng + ?Sized,
+    {
+        (0..self.replications)
+            .map(|_| self.resample(data, rng).map(|x| statistic(&x)))
+            .collect()
+    }
+
+    /// Percentile confidence interval of the `statistic` at confidence
+    /// `level` (e.g. 0.95): the (1 - level) / 2 and (1 + level) / 2
+    /// quantiles of its bootstrap distribution. Non-finite replications
+    /// (e.g. from degenerate resamples) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - `BootstrapError::InvalidParameter` if `level` is not in (0, 1).
+    /// - `BootstrapError::NotEnoughObservations` if `data` is empty, or
+    ///   shorter than a block, or no replication is finite.
+    pub fn confidence_interval<F, R>(
+        &self,
+        data: &[f64],
+        statistic: F,
+        level: f64,
+        rng: &mut R,
+    ) -> Result<ConfidenceInterval, BootstrapError>
+    where
+        F: Fn(&[f64]) -> f64,
+        R: Rng + ?Sized,
+    {
+        if !(level > 0.0 && level < 1.0) {
+            return Err(BootstrapError::InvalidParameter);
+        }
+
+        let replications = self.replicate(data, &statistic, rng)?;
+        let distribution = EmpiricalDistribution::new(&replications)
+            .map_err(|_| BootstrapError::NotEnoughObservations)?;
+
+        let n = distribution.observations().len() as f64;
+        let standard_error = if n > 1.0 {
+            (distribution.variance() * n / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        Ok(ConfidenceInterval {
+            estimate: statistic(data),
+            lower: distribution.inv_cdf(0.5 * (1.0 - level)),
+            upper: distribution.inv_cdf(0.5 * (1.0 + level)),
+            standard_error,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bootstrap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    // Count of the places where the resample does not continue with the
+    // next observation of 0, 1, ..., n - 1 (wrapping around).
+    fn breaks(resample: &[f64], n: f64) -> usize {
+        resample
+            .windows(2)
+            .filter(|w| ((w[0] + 1.0) % n - w[1]).abs() > 0.5)
+            .count()
+    }
+
+    #[test]
+    fn test_resampling() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let data: Vec<f64> = (0..10_000_u32).map(f64::from).collect();
+
+        let iid = Bootstrap::new(Resampling::Iid, 1).unwrap();
+        let resample = iid.resample(&data, &mut rng).unwrap();
+        assert_eq!(resample.len(), data.len());
+        assert!(resample.iter().all(|x| x.fract() == 0.0 && *x < 10_000.0));
+
+        // Blocks of ten consecutive observations.
+        let block = Bootstrap::new(Resampling::Block { block_length: 10 }, 1).unwrap();
+        let resample = block.resample(&data, &mut rng).unwrap();
+        assert_eq!(resample.len(), data.len());
+        assert!(resample
+            .chunks(10)
+            .all(|chunk| chunk.windows(2).all(|w| (w[1] - w[0] - 1.0).abs() < 0.5)));
+
+        // Blocks with a mean length of five.
+        let stationary = Bootstrap::new(
+            Resampling::Stationary {
+                mean_block_length: 5.0,
+            },
+            1,
+        )
+        .unwrap();
+        let resample = stationary.resample(&data, &mut rng).unwrap();
+        assert_eq!(resample.len(), data.len());
+        assert_approx_equal!(breaks(&resample, 10_000.0) as f64, 2_000.0, 150.0);
+
+        assert!(block.resample(&data[..5], &mut rng).is_err());
+        assert!(iid.resample(&[], &mut rng).is_err());
+        assert!(Bootstrap::new(Resampling::Iid, 0).is_err());
+        assert!(Bootstrap::new(Resampling::Block { block_length: 0 }, 10).is_err());
+        assert!(Bootstrap::new(
+            Resampling::Stationary {
+                mean_block_length: 0.5
+            },
+            10
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_confidence_interval() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let data: Vec<f64> = (0..400).map(|_| rng.sample(StandardNormal)).collect();
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+
+        // The standard error of the mean is about 1 / sqrt(400).
+        let bootstrap = Bootstrap::new(Resampling::Iid, 2_000).unwrap();
+        let interval = bootstrap
+            .confidence_interval(&data, mean, 0.95, &mut rng)
+            .unwrap();
+
+        assert_approx_equal!(interval.estimate, mean(&data), 1e-15);
+        assert_approx_equal!(interval.standard_error, 0.05, 0.005);
+        assert_approx_equal!(interval.upper - interval.lower, 2.0 * 1.96 * 0.05, 0.02);
+        assert!(interval.lower < interval.estimate && interval.estimate < interval.upper);
+
+        // Positively autocorrelated observations: the block bootstrap
+        // standard error of the mean is larger than the iid one.
+        let mut ar = vec![0.0];
+        for t in 1..2_000 {
+            let e: f64 = rng.sample(StandardNormal);
+            ar.push(0.8 * ar[t - 1] + e);
+        }
+        let iid = bootstrap
+            .confidence_interval(&ar, mean, 0.9, &mut rng)
+            .unwrap();
+        let block = Bootstrap::new(Resampling::Block { block_length: 50 }, 2_000)
+            .unwrap()
+            .confidence_interval(&ar, mean, 0.9, &mut rng)
+            .unwrap();
+        assert!(block.standard_error > 2.0 * iid.standard_error);
+
+        assert!(bootstrap
+            .confidence_interval(&data, mean, 1.0, &mut rng)
+            .is_err());
+        assert!(bootstrap
+            .confidence_interval(&data, |_| f64::NAN, 0.9, &mut rng)
+            .is_err());
+    }
+}
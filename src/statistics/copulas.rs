@@ -7,38 +7,1220 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! Copulas: the dependence structure of random variables, apart from their
+//! marginal distributions.
+//!
+//! A copula is the joint distribution of uniform variables
+//! $U_j = F_j(X_j)$. Dependent defaults or joint asset scenarios are
+//! simulated by sampling uniforms from a copula, and mapping them through
+//! the inverse marginal distributions, e.g. $\tau_j = F_j^{-1}(U_j)$ for the
+//! default times.
+//!
+//! - Elliptical copulas, with a correlation matrix $R$:
+//!   - Gaussian: no tail dependence.
+//!   - Student's t, with $\nu$ degrees of freedom: symmetric tail
+//!     dependence, joint extremes being more likely the fewer the degrees of
+//!     freedom.
+//! - Archimedean copulas, $C(u) = \psi\left( \sum_j \psi^{-1}(u_j) \right)$,
+//!   with a single parameter $\theta$ (positive dependence only), sampled
+//!   with the frailty construction of Marshall and Olkin (1988):
+//!   - Clayton: $\psi(t) = (1 + t)^{-1/\theta}$, lower tail dependence.
+//!   - Gumbel: $\psi(t) = e^{-t^{1/\theta}}$, upper tail dependence.
+//!   - Frank: $\psi(t) = -\frac{1}{\theta} \ln\left( 1 - (1 - e^{-\theta}) e^{-t} \right)$,
+//!     no tail dependence.
+//!
+//! The copulas are fitted to pseudo-observations (see
+//! [`pseudo_observations`]), by inversion of Kendall's tau or by maximum
+//! likelihood.
+
+use crate::math::golden_section_maximum;
+use crate::stochastics::nearest_correlation_matrix;
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::{ChiSquared, Exp1, Gamma, Open01, StandardNormal};
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+use statrs::function::gamma::ln_gamma;
+use std::f64::consts::PI;
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// IMPORTS
+// STRUCTS, ENUMS, AND TRAITS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use RustQuant::*;
+/// Copula error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CopulaError {
+    /// A parameter of the copula is out of its range.
+    #[error("Copula parameter out of range")]
+    InvalidParameter,
+
+    /// The correlation matrix is not symmetric positive definite with a unit
+    /// diagonal.
+    #[error("Correlation matrix must be symmetric positive definite with a unit diagonal")]
+    InvalidCorrelation,
+
+    /// The observations do not all have the same dimension, of at least two.
+    #[error("Observations must have the same dimension, of at least two")]
+    DimensionMismatch,
+
+    /// An observation is not in the open unit interval.
+    #[error("Observations must lie in (0, 1)")]
+    InvalidObservation,
+
+    /// There are fewer than two observations.
+    #[error("At least two observations are required")]
+    NotEnoughObservations,
+}
+
+/// Method of fitting a copula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopulaFitMethod {
+    /// Inversion of the (pairwise) sample Kendall's tau.
+    KendallsTau,
+
+    /// Maximum likelihood.
+    MaximumLikelihood,
+}
+
+/// Tail dependence coefficients of a pair of variables: the limits of
+/// $P(U_j \le q \mid U_i \le q)$ as $q \to 0$ (lower), and of
+/// $P(U_j > q \mid U_i > q)$ as $q \to 1$ (upper).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TailDependence {
+    /// Lower tail dependence coefficient.
+    pub lower: f64,
+
+    /// Upper tail dependence coefficient.
+    pub upper: f64,
+}
+
+/// Base trait for copulas.
+pub trait Copula {
+    /// Number of variables.
+    fn dimension(&self) -> usize;
+
+    /// Sample of uniform variables from the copula.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64>;
+
+    /// Density of the copula at `u`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` does not have one value per variable.
+    fn density(&self, u: &[f64]) -> f64;
+
+    /// Kendall's tau of the variables `i` and `j`.
+    fn kendalls_tau(&self, i: usize, j: usize) -> f64;
+
+    /// Tail dependence coefficients of the variables `i` and `j`.
+    fn tail_dependence(&self, i: usize, j: usize) -> TailDependence;
+
+    /// `n` samples of uniform variables from the copula.
+    fn samples<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<Vec<f64>> {
+        (0..n).map(|_| self.sample(rng)).collect()
+    }
+
+    /// Log-likelihood of the observations `data`.
+    fn log_likelihood(&self, data: &[Vec<f64>]) -> f64 {
+        data.iter().map(|u| self.density(u).ln()).sum()
+    }
+}
+
+/// Gaussian copula.
+///
+/// ```
+/// use RustQuant::statistics::*;
+/// use nalgebra::DMatrix;
+///
+/// let correlation = DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]);
+/// let copula = GaussianCopula::new(correlation).unwrap();
+///
+/// // Kendall's tau: 2 / pi * asin(0.5).
+/// assert!((copula.kendalls_tau(0, 1) - 1.0 / 3.0).abs() < 1e-12);
+/// assert_eq!(copula.tail_dependence(0, 1).lower, 0.0);
+///
+/// let samples = copula.samples(10, &mut rand::thread_rng());
+/// assert!(samples.iter().flatten().all(|u| *u > 0.0 && *u < 1.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct GaussianCopula {
+    /// Correlation matrix ($R$).
+    pub correlation: DMatrix<f64>,
+
+    // Cholesky factor, inverse and log-determinant of the correlation.
+    cholesky: DMatrix<f64>,
+    inverse: DMatrix<f64>,
+    log_determinant: f64,
+}
+
+/// Student's t copula.
+///
+/// ```
+/// use RustQuant::statistics::*;
+/// use nalgebra::DMatrix;
+///
+/// let correlation = DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]);
+/// let copula = StudentTCopula::new(correlation, 4.0).unwrap();
+///
+/// // Joint extremes, in both tails.
+/// let tails = copula.tail_dependence(0, 1);
+/// assert!(tails.lower > 0.2);
+/// assert_eq!(tails.lower, tails.upper);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StudentTCopula {
+    /// Correlation matrix ($R$).
+    pub correlation: DMatrix<f64>,
+
+    /// Degrees of freedom ($\nu$).
+    pub degrees_of_freedom: f64,
+
+    // Cholesky factor, inverse and log-determinant of the correlation.
+    cholesky: DMatrix<f64>,
+    inverse: DMatrix<f64>,
+    log_determinant: f64,
+}
+
+/// Generator of an Archimedean copula, with a single parameter $\theta$.
+pub trait ArchimedeanGenerator: Sized {
+    /// Infimum of the parameter (excluded, unless the copula is then the
+    /// independence copula).
+    const LOWER_BOUND: f64;
+
+    /// Generator with the parameter `theta`.
+    ///
+    /// # Errors
+    ///
+    /// `CopulaError::InvalidParameter` if `theta` is out of range.
+    fn new(theta: f64) -> Result<Self, CopulaError>;
+
+    /// The parameter ($\theta$).
+    fn theta(&self) -> f64;
+
+    /// The generator $\psi(t)$.
+    fn psi(&self, t: f64) -> f64;
+
+    /// The inverse generator $\psi^{-1}(u)$.
+    fn psi_inverse(&self, u: f64) -> f64;
+
+    /// The derivative of order `k` of the generator, with sign $(-1)^k$
+    /// (positive, as the generator is completely monotone).
+    fn psi_derivative(&self, t: f64, k: usize) -> f64;
+
+    /// Sample of the frailty: the positive variable with Laplace transform
+    /// $\psi$.
+    fn frailty<R: Rng + ?Sized>(&self, rng: &mut R) -> f64;
+
+    /// Kendall's tau of each pair of variables.
+    fn kendalls_tau(&self) -> f64;
+
+    /// Tail dependence coefficients of each pair of variables.
+    fn tail_dependence(&self) -> TailDependence;
+
+    /// Generator with Kendall's tau `tau`.
+    ///
+    /// # Errors
+    ///
+    /// `CopulaError::InvalidParameter` if no parameter gives `tau`.
+    fn from_kendalls_tau(tau: f64) -> Result<Self, CopulaError>;
+}
+
+/// Archimedean copula of `dimension` variables.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// // Defaults clustering in bad times: Clayton copula, lower tail dependent.
+/// let copula = ArchimedeanCopula::new(Clayton::new(2.0).unwrap(), 5).unwrap();
+///
+/// assert!((copula.kendalls_tau(0, 1) - 0.5).abs() < 1e-12);
+/// assert!((copula.tail_dependence(0, 1).lower - 0.5_f64.sqrt()).abs() < 1e-12);
+///
+/// // Default times of five names, exponential with a 2% intensity.
+/// let default_times: Vec<f64> = copula
+///     .sample(&mut rand::thread_rng())
+///     .iter()
+///     .map(|u| -(1.0 - u).ln() / 0.02)
+///     .collect();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchimedeanCopula<G: ArchimedeanGenerator> {
+    /// The generator.
+    pub generator: G,
+
+    /// Number of variables.
+    pub dimension: usize,
+}
+
+/// Clayton generator: $\psi(t) = (1 + t)^{-1/\theta}$, $\theta > 0$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clayton {
+    theta: f64,
+}
+
+/// Gumbel generator: $\psi(t) = e^{-t^{1/\theta}}$, $\theta \ge 1$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gumbel {
+    theta: f64,
+}
+
+/// Frank generator:
+/// $\psi(t) = -\frac{1}{\theta} \ln\left( 1 - (1 - e^{-\theta}) e^{-t} \right)$,
+/// $\theta > 0$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frank {
+    theta: f64,
+}
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// Structs, enums, and traits
+// IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-enum Enum {}
+impl GaussianCopula {
+    /// New Gaussian copula with the correlation matrix `correlation`.
+    ///
+    /// # Errors
+    ///
+    /// `CopulaError::InvalidCorrelation` if `correlation` is not a symmetric
+    /// positive definite matrix with a unit diagonal, of dimension at least
+    /// two.
+    pub fn new(correlation: DMatrix<f64>) -> Result<Self, CopulaError> {
+        let (cholesky, inverse, log_determinant) = decompose(&correlation)?;
+
+        Ok(Self {
+            correlation,
+            cholesky,
+            inverse,
+            log_determinant,
+        })
+    }
+
+    /// Gaussian copula fitted to the pseudo-observations `data`: with the
+    /// correlations $\sin(\pi \tau / 2)$ from the sample Kendall's taus (the
+    /// matrix repaired if it is not positive definite), or by maximum
+    /// likelihood, with the correlation of the normal scores.
+    ///
+    /// # Errors
+    ///
+    /// If the observations are invalid (see [`CopulaError`]).
+    pub fn fit(data: &[Vec<f64>], method: CopulaFitMethod) -> Result<Self, CopulaError> {
+        let dimension = validate(data)?;
+
+        let correlation = match method {
+            CopulaFitMethod::KendallsTau => tau_correlation(data, dimension),
+            CopulaFitMethod::MaximumLikelihood => {
+                let normal = standard_normal();
+                let scores: Vec<DVector<f64>> = data
+                    .iter()
+                    .map(|u| {
+                        DVector::from_iterator(dimension, u.iter().map(|u| normal.inverse_cdf(*u)))
+                    })
+                    .collect();
+                let covariance = scores
+                    .iter()
+                    .fold(DMatrix::zeros(dimension, dimension), |sum, z| {
+                        sum + z * z.transpose()
+                    });
+
+                DMatrix::from_fn(dimension, dimension, |i, j| {
+                    covariance[(i, j)] / (covariance[(i, i)] * covariance[(j, j)]).sqrt()
+                })
+            }
+        };
+
+        Self::new(correlation)
+    }
+}
+
+impl Copula for GaussianCopula {
+    fn dimension(&self) -> usize {
+        self.correlation.nrows()
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let normal = standard_normal();
+        let z = DVector::from_fn(self.dimension(), |_, _| rng.sample(StandardNormal));
+
+        (&self.cholesky * z)
+            .iter()
+            .map(|x| normal.cdf(*x))
+            .collect()
+    }
+
+    fn density(&self, u: &[f64]) -> f64 {
+        assert_eq!(u.len(), self.dimension(), "One value per variable.");
+
+        let normal = standard_normal();
+        let z = DVector::from_iterator(u.len(), u.iter().map(|u| normal.inverse_cdf(*u)));
+        let quadratic = (z.transpose() * &self.inverse * &z)[0] - z.norm_squared();
+
+        (-0.5 * (quadratic + self.log_determinant)).exp()
+    }
+
+    fn kendalls_tau(&self, i: usize, j: usize) -> f64 {
+        2.0 / PI * self.correlation[(i, j)].asin()
+    }
+
+    fn tail_dependence(&self, i: usize, j: usize) -> TailDependence {
+        let dependence = if i != j && self.correlation[(i, j)] < 1.0 {
+            0.0
+        } else {
+            1.0
+        };
+
+        TailDependence {
+            lower: dependence,
+            upper: dependence,
+        }
+    }
+}
+
+impl StudentTCopula {
+    /// New Student's t copula with the correlation matrix `correlation` and
+    /// `degrees_of_freedom` degrees of freedom.
+    ///
+    /// # Errors
+    ///
+    /// - `CopulaError::InvalidCorrelation` if `correlation` is not a
+    ///   symmetric positive definite matrix with a unit diagonal, of
+    ///   dimension at least two.
+    /// - `CopulaError::InvalidParameter` if the degrees of freedom are not
+    ///   positive.
+    pub fn new(correlation: DMatrix<f64>, degrees_of_freedom: f64) -> Result<Self, CopulaError> {
+        if !(degrees_of_freedom > 0.0 && degrees_of_freedom.is_finite()) {
+            return Err(CopulaError::InvalidParameter);
+        }
+        let (cholesky, inverse, log_determinant) = decompose(&correlation)?;
+
+        Ok(Self {
+            correlation,
+            degrees_of_freedom,
+            cholesky,
+            inverse,
+            log_determinant,
+        })
+    }
+
+    /// Student's t copula fitted to the pseudo-observations `data`: the
+    /// correlations $\sin(\pi \tau / 2)$ from the sample Kendall's taus (the
+    /// matrix repaired if it is not positive definite), then the degrees of
+    /// freedom, between 1 and 200, by maximum likelihood.
+    ///
+    /// # Errors
+    ///
+    /// If the observations are invalid (see [`CopulaError`]).
+    pub fn fit(data: &[Vec<f64>]) -> Result<Self, CopulaError> {
+        let dimension = validate(data)?;
+        let correlation = tau_correlation(data, dimension);
+
+        let log_likelihood = |log_nu: f64| {
+            Self::new(correlation.clone(), log_nu.exp())
+                .map_or(f64::NEG_INFINITY, |copula| copula.log_likelihood(data))
+        };
+        let log_nu = golden_section_maximum(log_likelihood, 0.0, 200_f64.ln());
+
+        Self::new(correlation, log_nu.exp())
+    }
+
+    fn students_t(&self) -> StudentsT {
+        StudentsT::new(0.0, 1.0, self.degrees_of_freedom).unwrap()
+    }
+}
+
+impl Copula for StudentTCopula {
+    fn dimension(&self) -> usize {
+        self.correlation.nrows()
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let nu = self.degrees_of_freedom;
+        let z = DVector::from_fn(self.dimension(), |_, _| rng.sample(StandardNormal));
+        let w: f64 = rng.sample(ChiSquared::new(nu).unwrap()) / nu;
+
+        let t = self.students_t();
+        (&self.cholesky * z)
+            .iter()
+            .map(|x| t.cdf(x / w.sqrt()))
+            .collect()
+    }
+
+    fn density(&self, u: &[f64]) -> f64 {
+        assert_eq!(u.len(), self.dimension(), "One value per variable.");
+
+        let (nu, d) = (self.degrees_of_freedom, u.len() as f64);
+        let t = self.students_t();
+        let x = DVector::from_iterator(u.len(), u.iter().map(|u| t.inverse_cdf(*u)));
+        let quadratic = (x.transpose() * &self.inverse * &x)[0];
+
+        let log_density = ln_gamma(0.5 * (nu + d)) + (d - 1.0) * ln_gamma(0.5 * nu)
+            - d * ln_gamma(0.5 * (nu + 1.0))
+            - 0.5 * self.log_determinant
+            - 0.5 * (nu + d) * (quadratic / nu).ln_1p()
+            + 0.5 * (nu + 1.0) * x.iter().map(|x| (x * x / nu).ln_1p()).sum::<f64>();
+
+        log_density.exp()
+    }
+
+    fn kendalls_tau(&self, i: usize, j: usize) -> f64 {
+        2.0 / PI * self.correlation[(i, j)].asin()
+    }
+
+    fn tail_dependence(&self, i: usize, j: usize) -> TailDependence {
+        let (nu, rho) = (self.degrees_of_freedom, self.correlation[(i, j)]);
+        let t = StudentsT::new(0.0, 1.0, nu + 1.0).unwrap();
+        let dependence = 2.0 * t.cdf(-((nu + 1.0) * (1.0 - rho) / (1.0 + rho)).sqrt());
+
+        TailDependence {
+            lower: dependence,
+            upper: dependence,
+        }
+    }
+}
+
+impl<G: ArchimedeanGenerator> ArchimedeanCopula<G> {
+    /// New Archimedean copula of `dimension` variables with the generator
+    /// `generator`.
+    ///
+    /// # Errors
+    ///
+    /// `CopulaError::DimensionMismatch` if there are fewer than two
+    /// variables.
+    pub fn new(generator: G, dimension: usize) -> Result<Self, CopulaError> {
+        if dimension < 2 {
+            return Err(CopulaError::DimensionMismatch);
+        }
+
+        Ok(Self {
+            generator,
+            dimension,
+        })
+    }
+
+    /// Distribution function of the copula at `u`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `u` does not have one value per variable.
+    #[must_use]
+    pub fn cdf(&self, u: &[f64]) -> f64 {
+        assert_eq!(u.len(), self.dimension, "One value per variable.");
+
+        self.generator
+            .psi(u.iter().map(|u| self.generator.psi_inverse(*u)).sum())
+    }
+
+    /// Archimedean copula fitted to the pseudo-observations `data`: by
+    /// inversion of the average of the pairwise sample Kendall's taus, or
+    /// by maximum likelihood.
+    ///
+    /// # Errors
+    ///
+    /// - If the observations are invalid (see [`CopulaError`]).
+    /// - `CopulaError::InvalidParameter` if the sample Kendall's tau is out
+    ///   of the range of the copula (e.g. negative), with
+    ///   `CopulaFitMethod::KendallsTau`.
+    pub fn fit(data: &[Vec<f64>], method: CopulaFitMethod) -> Result<Self, CopulaError> {
+        let dimension = validate(data)?;
+
+        let pairs = dimension * (dimension - 1) / 2;
+        let tau = (0..dimension)
+            .flat_map(|i| (0..i).map(move |j| (i, j)))
+            .map(|(i, j)| pairwise_kendalls_tau(data, i, j))
+            .sum::<f64>()
+            / pairs as f64;
+
+        let generator = match method {
+            CopulaFitMethod::KendallsTau => G::from_kendalls_tau(tau)?,
+            CopulaFitMethod::MaximumLikelihood => {
+                // Search around the moment estimate, on the log-distance of
+                // the parameter from its lower bound.
+                let start = G::from_kendalls_tau(tau)
+                    .map_or(1.0, |generator| generator.theta() - G::LOWER_BOUND)
+                    .ln();
+                let log_likelihood = |x: f64| {
+                    G::new(G::LOWER_BOUND + x.exp()).map_or(f64::NEG_INFINITY, |generator| {
+                        Self {
+                            generator,
+                            dimension,
+                        }
+                        .log_likelihood(data)
+                    })
+                };
+                let x = golden_section_maximum(log_likelihood, start - 4.0, start + 4.0);
+
+                G::new(G::LOWER_BOUND + x.exp())?
+            }
+        };
+
+        Self::new(generator, dimension)
+    }
+}
+
+impl<G: ArchimedeanGenerator> Copula for ArchimedeanCopula<G> {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Marshall-Olkin sampling: $U_j = \psi(E_j / V)$, with the frailty $V$
+    /// and independent standard exponential $E_j$.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let frailty = self.generator.frailty(rng);
+
+        (0..self.dimension)
+            .map(|_| {
+                let e: f64 = rng.sample(Exp1);
+                self.generator.psi(e / frailty)
+            })
+            .collect()
+    }
+
+    fn density(&self, u: &[f64]) -> f64 {
+        assert_eq!(u.len(), self.dimension, "One value per variable.");
+
+        let t: Vec<f64> = u.iter().map(|u| self.generator.psi_inverse(*u)).collect();
+
+        self.generator
+            .psi_derivative(t.iter().sum(), self.dimension)
+            / t.iter()
+                .map(|t| self.generator.psi_derivative(*t, 1))
+                .product::<f64>()
+    }
+
+    fn kendalls_tau(&self, _i: usize, _j: usize) -> f64 {
+        self.generator.kendalls_tau()
+    }
+
+    fn tail_dependence(&self, _i: usize, _j: usize) -> TailDependence {
+        self.generator.tail_dependence()
+    }
+}
+
+impl ArchimedeanGenerator for Clayton {
+    const LOWER_BOUND: f64 = 0.0;
+
+    fn new(theta: f64) -> Result<Self, CopulaError> {
+        if theta > 0.0 && theta.is_finite() {
+            Ok(Self { theta })
+        } else {
+            Err(CopulaError::InvalidParameter)
+        }
+    }
+
+    fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    fn psi(&self, t: f64) -> f64 {
+        (1.0 + t).powf(-1.0 / self.theta)
+    }
 
-struct Struct {}
+    fn psi_inverse(&self, u: f64) -> f64 {
+        u.powf(-self.theta) - 1.0
+    }
 
-trait Trait {}
+    fn psi_derivative(&self, t: f64, k: usize) -> f64 {
+        let alpha = 1.0 / self.theta;
+
+        (0..k).map(|j| alpha + j as f64).product::<f64>() * (1.0 + t).powf(-alpha - k as f64)
+    }
+
+    /// Gamma distributed, with shape $1 / \theta$ and unit scale.
+    fn frailty<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        rng.sample(Gamma::new(1.0 / self.theta, 1.0).unwrap())
+    }
+
+    fn kendalls_tau(&self) -> f64 {
+        self.theta / (self.theta + 2.0)
+    }
+
+    fn tail_dependence(&self) -> TailDependence {
+        TailDependence {
+            lower: 2_f64.powf(-1.0 / self.theta),
+            upper: 0.0,
+        }
+    }
+
+    fn from_kendalls_tau(tau: f64) -> Result<Self, CopulaError> {
+        Self::new(2.0 * tau / (1.0 - tau))
+    }
+}
+
+impl ArchimedeanGenerator for Gumbel {
+    const LOWER_BOUND: f64 = 1.0;
+
+    fn new(theta: f64) -> Result<Self, CopulaError> {
+        if theta >= 1.0 && theta.is_finite() {
+            Ok(Self { theta })
+        } else {
+            Err(CopulaError::InvalidParameter)
+        }
+    }
+
+    fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    fn psi(&self, t: f64) -> f64 {
+        (-t.powf(1.0 / self.theta)).exp()
+    }
+
+    fn psi_inverse(&self, u: f64) -> f64 {
+        (-u.ln()).powf(self.theta)
+    }
+
+    /// $\psi(t) t^{-k} \sum_{m=1}^k a_{km} t^{m / \theta}$, with the
+    /// coefficients of Hofert, Mächler and McNeil (2012), from the Stirling
+    /// numbers of both kinds:
+    /// $a_{km} = (-1)^{k-m} \sum_{j=m}^k \theta^{-j} s(k, j) S(j, m)$.
+    fn psi_derivative(&self, t: f64, k: usize) -> f64 {
+        let alpha = 1.0 / self.theta;
+        let (first, second) = (stirling_first(k), stirling_second(k));
+
+        let sum = (1..=k)
+            .map(|m| {
+                let coefficient = (m..=k)
+                    .map(|j| alpha.powf(j as f64) * first[k][j] * second[j][m])
+                    .sum::<f64>();
+                let sign = (-1_f64).powf((k - m) as f64);
+
+                sign * coefficient * t.powf(alpha * m as f64)
+            })
+            .sum::<f64>();
+
+        self.psi(t) * t.powf(-(k as f64)) * sum
+    }
+
+    /// Positive stable, with index $1 / \theta$, by Kanter's (1975)
+    /// representation.
+    fn frailty<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let alpha = 1.0 / self.theta;
+        if alpha >= 1.0 {
+            return 1.0;
+        }
+
+        let angle = PI * rng.sample::<f64, _>(Open01);
+        let w: f64 = rng.sample(Exp1);
+
+        (alpha * angle).sin() / angle.sin().powf(1.0 / alpha)
+            * (((1.0 - alpha) * angle).sin() / w).powf((1.0 - alpha) / alpha)
+    }
+
+    fn kendalls_tau(&self) -> f64 {
+        1.0 - 1.0 / self.theta
+    }
+
+    fn tail_dependence(&self) -> TailDependence {
+        TailDependence {
+            lower: 0.0,
+            upper: 2.0 - 2_f64.powf(1.0 / self.theta),
+        }
+    }
+
+    fn from_kendalls_tau(tau: f64) -> Result<Self, CopulaError> {
+        Self::new(1.0 / (1.0 - tau))
+    }
+}
+
+impl ArchimedeanGenerator for Frank {
+    const LOWER_BOUND: f64 = 0.0;
+
+    fn new(theta: f64) -> Result<Self, CopulaError> {
+        if theta > 0.0 && theta.is_finite() {
+            Ok(Self { theta })
+        } else {
+            Err(CopulaError::InvalidParameter)
+        }
+    }
+
+    fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    fn psi(&self, t: f64) -> f64 {
+        -((-self.theta).exp_m1() * (-t).exp()).ln_1p() / self.theta
+    }
+
+    fn psi_inverse(&self, u: f64) -> f64 {
+        -((-self.theta * u).exp_m1() / (-self.theta).exp_m1()).ln()
+    }
+
+    /// $\mathrm{Li}_{1-k}\left( (1 - e^{-\theta}) e^{-t} \right) / \theta$,
+    /// with the polylogarithms of negative order
+    /// $\mathrm{Li}_{-n}(z) = \sum_{m=0}^n m! S(n+1, m+1) \left( \frac{z}{1-z} \right)^{m+1}$.
+    fn psi_derivative(&self, t: f64, k: usize) -> f64 {
+        if k == 0 {
+            return self.psi(t);
+        }
+
+        let z = -(-self.theta).exp_m1() * (-t).exp();
+        let ratio = z / (1.0 - z);
+        let second = stirling_second(k);
+
+        let mut factorial = 1.0;
+        let mut sum = 0.0;
+        for m in 0..k {
+            if m > 0 {
+                factorial *= m as f64;
+            }
+            sum += factorial * second[k][m + 1] * ratio.powf((m + 1) as f64);
+        }
+
+        sum / self.theta
+    }
+
+    /// Logarithmic, with parameter $1 - e^{-\theta}$, by Kemp's (1981)
+    /// algorithm.
+    fn frailty<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let p = -(-self.theta).exp_m1();
+        let v: f64 = rng.sample(Open01);
+        if v > p {
+            return 1.0;
+        }
+
+        let q = -(-self.theta * rng.sample::<f64, _>(Open01)).exp_m1();
+        if v < q * q {
+            (1.0 + v.ln() / q.ln()).floor()
+        } else if v < q {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    /// $1 - \frac{4}{\theta} \left( 1 - D_1(\theta) \right)$, with the Debye
+    /// function $D_1(\theta) = \frac{1}{\theta} \int_0^\theta \frac{t}{e^t - 1} dt$.
+    fn kendalls_tau(&self) -> f64 {
+        const INTERVALS: usize = 256;
+
+        let theta = self.theta;
+        let integrand = |t: f64| if t == 0.0 { 1.0 } else { t / t.exp_m1() };
+
+        // Simpson's rule.
+        let h = theta / INTERVALS as f64;
+        let integral = (0..=INTERVALS)
+            .map(|n| {
+                let weight = if n == 0 || n == INTERVALS {
+                    1.0
+                } else if n % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                weight * integrand(h * n as f64)
+            })
+            .sum::<f64>()
+            * h
+            / 3.0;
+
+        1.0 - 4.0 / theta * (1.0 - integral / theta)
+    }
+
+    fn tail_dependence(&self) -> TailDependence {
+        TailDependence {
+            lower: 0.0,
+            upper: 0.0,
+        }
+    }
+
+    /// By bisection, with $\theta$ between $10^{-6}$ and $10^4$.
+    fn from_kendalls_tau(tau: f64) -> Result<Self, CopulaError> {
+        let tau_of = |log_theta: f64| {
+            Self {
+                theta: log_theta.exp(),
+            }
+            .kendalls_tau()
+        };
+
+        let (mut low, mut high) = (1e-6_f64.ln(), 1e4_f64.ln());
+        if !(tau_of(low)..tau_of(high)).contains(&tau) {
+            return Err(CopulaError::InvalidParameter);
+        }
+
+        while high - low > 1e-12 {
+            let middle = 0.5 * (low + high);
+            if tau_of(middle) < tau {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        Self::new((0.5 * (low + high)).exp())
+    }
+}
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// Implementations, functions, and macros
+// FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-impl Struct {}
+/// Pseudo-observations of the rows of `data` (one row per observation, one
+/// column per variable): the ranks of the values of each variable, divided
+/// by the number of observations plus one.
+///
+/// ```
+/// use RustQuant::statistics::pseudo_observations;
+///
+/// let data = vec![vec![0.3, -1.0], vec![0.1, 2.0], vec![0.2, 0.5]];
+///
+/// assert_eq!(
+///     pseudo_observations(&data),
+///     vec![vec![0.75, 0.25], vec![0.25, 0.75], vec![0.5, 0.5]]
+/// );
+/// ```
+#[must_use]
+pub fn pseudo_observations(data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = data.len();
+    let dimension = data.first().map_or(0, Vec::len);
+    let mut pseudo = vec![vec![0.0; dimension]; n];
+
+    for j in 0..dimension {
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| data[*a][j].total_cmp(&data[*b][j]));
+
+        for (rank, row) in order.iter().enumerate() {
+            pseudo[*row][j] = (rank + 1) as f64 / (n + 1) as f64;
+        }
+    }
+
+    pseudo
+}
 
-impl Trait for Struct {}
+/// Sample Kendall's tau of `x` and `y`: the difference between the
+/// proportions of concordant and discordant pairs.
+///
+/// # Panics
+///
+/// Panics if `x` and `y` have different lengths, or fewer than two values.
+#[must_use]
+pub fn sample_kendalls_tau(x: &[f64], y: &[f64]) -> f64 {
+    assert_eq!(x.len(), y.len(), "The samples must have the same length.");
+    assert!(x.len() >= 2, "At least two values are required.");
+
+    let n = x.len();
+    let concordance = (0..n)
+        .flat_map(|a| (0..a).map(move |b| (a, b)))
+        .map(|(a, b)| ((x[a] - x[b]) * (y[a] - y[b])).signum())
+        .sum::<f64>();
+
+    concordance / (n * (n - 1) / 2) as f64
+}
+
+// Checks the observations, and returns their dimension.
+fn validate(data: &[Vec<f64>]) -> Result<usize, CopulaError> {
+    if data.len() < 2 {
+        return Err(CopulaError::NotEnoughObservations);
+    }
+
+    let dimension = data[0].len();
+    if dimension < 2 || data.iter().any(|u| u.len() != dimension) {
+        return Err(CopulaError::DimensionMismatch);
+    }
+    if data.iter().flatten().any(|u| !(*u > 0.0 && *u < 1.0)) {
+        return Err(CopulaError::InvalidObservation);
+    }
+
+    Ok(dimension)
+}
+
+fn pairwise_kendalls_tau(data: &[Vec<f64>], i: usize, j: usize) -> f64 {
+    let x: Vec<f64> = data.iter().map(|u| u[i]).collect();
+    let y: Vec<f64> = data.iter().map(|u| u[j]).collect();
+
+    sample_kendalls_tau(&x, &y)
+}
+
+fn standard_normal() -> Normal {
+    Normal::new(0.0, 1.0).unwrap()
+}
+
+// Correlation matrix of an elliptical copula from the pairwise sample
+// Kendall's taus, repaired if it is not positive definite.
+fn tau_correlation(data: &[Vec<f64>], dimension: usize) -> DMatrix<f64> {
+    let mut correlation = DMatrix::identity(dimension, dimension);
+    for i in 0..dimension {
+        for j in 0..i {
+            let rho = (0.5 * PI * pairwise_kendalls_tau(data, i, j)).sin();
+            correlation[(i, j)] = rho;
+            correlation[(j, i)] = rho;
+        }
+    }
+
+    match correlation.clone().cholesky() {
+        Some(_) => correlation,
+        None => nearest_correlation_matrix(&correlation),
+    }
+}
+
+// Cholesky factor, inverse and log-determinant of a correlation matrix.
+fn decompose(correlation: &DMatrix<f64>) -> Result<(DMatrix<f64>, DMatrix<f64>, f64), CopulaError> {
+    const TOLERANCE: f64 = 1e-12;
+
+    let n = correlation.nrows();
+    let valid = n >= 2
+        && correlation.is_square()
+        && (0..n).all(|i| {
+            (correlation[(i, i)] - 1.0).abs() < TOLERANCE
+                && (0..i).all(|j| (correlation[(i, j)] - correlation[(j, i)]).abs() < TOLERANCE)
+        });
+    if !valid {
+        return Err(CopulaError::InvalidCorrelation);
+    }
+
+    let cholesky = correlation
+        .clone()
+        .cholesky()
+        .ok_or(CopulaError::InvalidCorrelation)?;
+    let log_determinant = 2.0 * cholesky.l().diagonal().iter().map(|x| x.ln()).sum::<f64>();
+
+    Ok((cholesky.l(), cholesky.inverse(), log_determinant))
+}
+
+// Signed Stirling numbers of the first kind, `s(n, k)` for `n, k <= order`.
+fn stirling_first(order: usize) -> Vec<Vec<f64>> {
+    let mut s = vec![vec![0.0; order + 1]; order + 1];
+    s[0][0] = 1.0;
+    for n in 0..order {
+        for k in 1..=n + 1 {
+            s[n + 1][k] = s[n][k - 1] - n as f64 * s[n][k];
+        }
+    }
+
+    s
+}
+
+// Stirling numbers of the second kind, `S(n, k)` for `n, k <= order`.
+fn stirling_second(order: usize) -> Vec<Vec<f64>> {
+    let mut s = vec![vec![0.0; order + 1]; order + 1];
+    s[0][0] = 1.0;
+    for n in 0..order {
+        for k in 1..=n + 1 {
+            s[n + 1][k] = k as f64 * s[n][k] + s[n][k - 1];
+        }
+    }
+
+    s
+}
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-// Unit tests
+// UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 #[cfg(test)]
-mod tests {
+mod tests_copulas {
     use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn correlation(rho: f64) -> DMatrix<f64> {
+        DMatrix::from_row_slice(2, 2, &[1.0, rho, rho, 1.0])
+    }
+
+    // Mixed second derivative of a bivariate distribution function.
+    fn numerical_density(cdf: impl Fn(f64, f64) -> f64, u: f64, v: f64) -> f64 {
+        let h = 1e-4;
+        (cdf(u + h, v + h) - cdf(u + h, v - h) - cdf(u - h, v + h) + cdf(u - h, v - h))
+            / (4.0 * h * h)
+    }
+
+    #[test]
+    fn test_densities() {
+        let points: [(f64, f64); 4] = [(0.2, 0.7), (0.5, 0.5), (0.9, 0.85), (0.05, 0.1)];
+
+        // Closed form bivariate densities.
+        let theta = 2.5;
+        let clayton = ArchimedeanCopula::new(Clayton::new(theta).unwrap(), 2).unwrap();
+        let frank = ArchimedeanCopula::new(Frank::new(theta).unwrap(), 2).unwrap();
+        for (u, v) in points {
+            let expected = (1.0 + theta)
+                * (u * v).powf(-1.0 - theta)
+                * (u.powf(-theta) + v.powf(-theta) - 1.0).powf(-2.0 - 1.0 / theta);
+            assert_approx_equal!(clayton.density(&[u, v]), expected, 1e-10);
+
+            let a = -(-theta).exp_m1();
+            let expected = theta * a * (-theta * (u + v)).exp()
+                / (a - (-theta * u).exp_m1() * (-theta * v).exp_m1()).powi(2);
+            assert_approx_equal!(frank.density(&[u, v]), expected, 1e-10);
+        }
+
+        // Densities from the distribution functions, in three dimensions
+        // with the third variable at one.
+        let gumbel = ArchimedeanCopula::new(Gumbel::new(1.8).unwrap(), 2).unwrap();
+        let trivariate = ArchimedeanCopula::new(Clayton::new(theta).unwrap(), 3).unwrap();
+        for (u, v) in points {
+            let numerical = numerical_density(|u, v| gumbel.cdf(&[u, v]), u, v);
+            assert_approx_equal!(gumbel.density(&[u, v]), numerical, 1e-4 * numerical);
+
+            // c(u, v, w) = d^3 C / du dv dw, against the closed form.
+            let w: f64 = 0.4;
+            let sum = u.powf(-theta) + v.powf(-theta) + w.powf(-theta) - 2.0;
+            let expected = (1.0 + theta)
+                * (1.0 + 2.0 * theta)
+                * (u * v * w).powf(-1.0 - theta)
+                * sum.powf(-3.0 - 1.0 / theta);
+            assert_approx_equal!(trivariate.density(&[u, v, w]), expected, 1e-9 * expected);
+        }
+
+        // Gaussian, against the bivariate normal density; Student's t with
+        // many degrees of freedom, close to the Gaussian.
+        let rho = 0.6;
+        let gaussian = GaussianCopula::new(correlation(rho)).unwrap();
+        let student = StudentTCopula::new(correlation(rho), 1e4).unwrap();
+        let normal = standard_normal();
+        for (u, v) in points {
+            let (x, y) = (normal.inverse_cdf(u), normal.inverse_cdf(v));
+            let expected = (-(rho * rho * (x * x + y * y) - 2.0 * rho * x * y)
+                / (2.0 * (1.0 - rho * rho)))
+                .exp()
+                / (1.0 - rho * rho).sqrt();
+
+            assert_approx_equal!(gaussian.density(&[u, v]), expected, 1e-10);
+            assert_approx_equal!(student.density(&[u, v]), expected, 1e-3);
+        }
+
+        // Student's t at the medians.
+        let nu = 4.0;
+        let student = StudentTCopula::new(correlation(rho), nu).unwrap();
+        let expected = (ln_gamma(0.5 * nu + 1.0) + ln_gamma(0.5 * nu)
+            - 2.0 * ln_gamma(0.5 * (nu + 1.0)))
+        .exp()
+            / (1.0 - rho * rho).sqrt();
+        assert_approx_equal!(student.density(&[0.5, 0.5]), expected, 1e-10);
+    }
+
+    #[test]
+    fn test_sampling() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let check = |copula: &dyn Fn(&mut StdRng) -> Vec<f64>, tau: f64, rng: &mut StdRng| {
+            let samples: Vec<Vec<f64>> = (0..2_000).map(|_| copula(rng)).collect();
+
+            let mean = samples.iter().map(|u| u[0]).sum::<f64>() / 2_000.0;
+            assert_approx_equal!(mean, 0.5, 0.02);
+            assert_approx_equal!(pairwise_kendalls_tau(&samples, 1, 0), tau, 0.03);
+        };
+
+        let gaussian = GaussianCopula::new(correlation(0.5)).unwrap();
+        check(
+            &|rng| gaussian.sample(rng),
+            gaussian.kendalls_tau(0, 1),
+            &mut rng,
+        );
+
+        let student = StudentTCopula::new(correlation(-0.4), 3.0).unwrap();
+        check(
+            &|rng| student.sample(rng),
+            student.kendalls_tau(0, 1),
+            &mut rng,
+        );
+
+        let clayton = ArchimedeanCopula::new(Clayton::new(2.0).unwrap(), 3).unwrap();
+        check(&|rng| clayton.sample(rng), 0.5, &mut rng);
+
+        let gumbel = ArchimedeanCopula::new(Gumbel::new(2.5).unwrap(), 3).unwrap();
+        check(&|rng| gumbel.sample(rng), 0.6, &mut rng);
+
+        let frank = ArchimedeanCopula::new(Frank::new(5.0).unwrap(), 3).unwrap();
+        check(&|rng| frank.sample(rng), frank.kendalls_tau(0, 1), &mut rng);
+        // Tabulated Kendall's tau of the Frank copula.
+        assert_approx_equal!(frank.kendalls_tau(0, 1), 0.4567, 1e-4);
+    }
 
     #[test]
-    fn very_thorough_test() {}
+    fn test_fitting() {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        let clayton = ArchimedeanCopula::new(Clayton::new(3.0).unwrap(), 2).unwrap();
+        let data = pseudo_observations(&clayton.samples(1_000, &mut rng));
+        for method in [
+            CopulaFitMethod::KendallsTau,
+            CopulaFitMethod::MaximumLikelihood,
+        ] {
+            let fitted = ArchimedeanCopula::<Clayton>::fit(&data, method).unwrap();
+            assert_approx_equal!(fitted.generator.theta(), 3.0, 0.4);
+        }
+
+        let gumbel = ArchimedeanCopula::new(Gumbel::new(1.5).unwrap(), 3).unwrap();
+        let data = pseudo_observations(&gumbel.samples(1_000, &mut rng));
+        for method in [
+            CopulaFitMethod::KendallsTau,
+            CopulaFitMethod::MaximumLikelihood,
+        ] {
+            let fitted = ArchimedeanCopula::<Gumbel>::fit(&data, method).unwrap();
+            assert_approx_equal!(fitted.generator.theta(), 1.5, 0.1);
+        }
+
+        let frank = ArchimedeanCopula::new(Frank::new(4.0).unwrap(), 2).unwrap();
+        let data = pseudo_observations(&frank.samples(1_000, &mut rng));
+        for method in [
+            CopulaFitMethod::KendallsTau,
+            CopulaFitMethod::MaximumLikelihood,
+        ] {
+            let fitted = ArchimedeanCopula::<Frank>::fit(&data, method).unwrap();
+            assert_approx_equal!(fitted.generator.theta(), 4.0, 0.6);
+        }
+
+        let gaussian = GaussianCopula::new(correlation(0.7)).unwrap();
+        let data = pseudo_observations(&gaussian.samples(1_000, &mut rng));
+        for method in [
+            CopulaFitMethod::KendallsTau,
+            CopulaFitMethod::MaximumLikelihood,
+        ] {
+            let fitted = GaussianCopula::fit(&data, method).unwrap();
+            assert_approx_equal!(fitted.correlation[(0, 1)], 0.7, 0.05);
+        }
+
+        let student = StudentTCopula::new(correlation(0.5), 4.0).unwrap();
+        let data = pseudo_observations(&student.samples(2_000, &mut rng));
+        let fitted = StudentTCopula::fit(&data).unwrap();
+        assert_approx_equal!(fitted.correlation[(0, 1)], 0.5, 0.05);
+        assert_approx_equal!(fitted.degrees_of_freedom, 4.0, 2.0);
+    }
+
+    #[test]
+    fn test_tail_dependence() {
+        let clayton = Clayton::new(2.0).unwrap().tail_dependence();
+        assert_approx_equal!(clayton.lower, 0.5_f64.sqrt(), 1e-12);
+
+        let gumbel = Gumbel::new(2.0).unwrap().tail_dependence();
+        assert_approx_equal!(gumbel.upper, 2.0 - 2_f64.sqrt(), 1e-12);
+
+        // Student's t: fewer degrees of freedom, more tail dependence.
+        let heavy = StudentTCopula::new(correlation(0.5), 2.0).unwrap();
+        let light = StudentTCopula::new(correlation(0.5), 20.0).unwrap();
+        assert!(heavy.tail_dependence(0, 1).upper > light.tail_dependence(0, 1).upper);
+        assert_approx_equal!(heavy.tail_dependence(0, 0).lower, 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(Clayton::new(0.0), Err(CopulaError::InvalidParameter));
+        assert_eq!(Gumbel::new(0.9), Err(CopulaError::InvalidParameter));
+        assert_eq!(
+            Frank::from_kendalls_tau(-0.2),
+            Err(CopulaError::InvalidParameter)
+        );
+
+        assert!(matches!(
+            GaussianCopula::new(correlation(1.2)),
+            Err(CopulaError::InvalidCorrelation)
+        ));
+        assert!(matches!(
+            StudentTCopula::new(correlation(0.2), 0.0),
+            Err(CopulaError::InvalidParameter)
+        ));
+
+        let fit = |data: &[Vec<f64>]| {
+            ArchimedeanCopula::<Clayton>::fit(data, CopulaFitMethod::KendallsTau)
+        };
+        assert_eq!(
+            fit(&[vec![0.5, 0.5]]),
+            Err(CopulaError::NotEnoughObservations)
+        );
+        assert_eq!(
+            fit(&[vec![0.5, 0.5], vec![0.5]]),
+            Err(CopulaError::DimensionMismatch)
+        );
+        assert_eq!(
+            fit(&[vec![0.5, 0.5], vec![1.0, 0.2]]),
+            Err(CopulaError::InvalidObservation)
+        );
+    }
 }
@@ -0,0 +1,376 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Covariance matrix estimators, for portfolio optimisation and correlated
+//! simulation.
+//!
+//! The sample covariance of $p$ variables from $n$ observations is noisy
+//! when $p$ is not small relative to $n$ (and singular for $p \ge n$). The
+//! alternatives are:
+//!
+//! - Ledoit-Wolf (2004) shrinkage of the sample covariance $S$ towards a
+//!   multiple of the identity, $(1 - \delta) S + \delta \mu I$ with
+//!   $\mu = \operatorname{tr}(S) / p$, with the intensity $\delta$
+//!   minimising the expected squared (Frobenius) error. The result is
+//!   positive definite, and better conditioned.
+//! - Exponential weighting (RiskMetrics, 1996) of the (de-meaned) returns,
+//!   the weight of the return `k` periods ago being proportional to
+//!   $\lambda^k$: the multivariate [`crate::statistics::Ewma`].
+//!
+//! A matrix estimated otherwise (e.g. from pairwise or stressed
+//! correlations) may not be positive semi-definite: the nearest covariance
+//! matrix with the same variances repairs it, with the nearest correlation
+//! matrix of Higham (2002).
+
+use crate::stochastics::nearest_correlation_matrix;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Covariance estimation error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CovarianceError {
+    /// A parameter of the estimator is out of its range.
+    #[error("Covariance estimator parameter out of range")]
+    InvalidParameter,
+
+    /// The observations do not all have the same dimension, of at least one.
+    #[error("Observations must have the same dimension, of at least one")]
+    DimensionMismatch,
+
+    /// There are fewer than two observations.
+    #[error("At least two observations are required")]
+    NotEnoughObservations,
+
+    /// The matrix is not symmetric with a positive diagonal.
+    #[error("Matrix must be symmetric with a positive diagonal")]
+    InvalidMatrix,
+}
+
+/// Covariance matrix shrunk towards a multiple of the identity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShrunkCovariance {
+    /// The shrunk covariance matrix.
+    pub covariance: DMatrix<f64>,
+
+    /// The shrinkage intensity, in `[0, 1]`.
+    pub shrinkage: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Unbiased sample covariance matrix of the observations `data` (one
+/// vector of values per observation).
+///
+/// # Errors
+///
+/// If the observations are invalid (see [`CovarianceError`]).
+pub fn sample_covariance(data: &[Vec<f64>]) -> Result<DMatrix<f64>, CovarianceError> {
+    let deviations = deviations(data)?;
+    let n = deviations.len() as f64;
+
+    Ok(scatter(&deviations) / (n - 1.0))
+}
+
+/// Ledoit-Wolf shrinkage estimate of the covariance matrix of the
+/// observations `data` (one vector of values per observation), towards
+/// the identity times the average variance.
+///
+/// The sample covariance $S$ (dividing by $n$) is shrunk with the
+/// intensity $\min(\bar{b}^2, d^2) / d^2$, where
+/// $d^2 = \lVert S - \mu I \rVert^2$ and
+/// $\bar{b}^2 = \frac{1}{n^2} \sum_k \lVert x_k x_k^\top - S \rVert^2$, for
+/// the de-meaned observations $x_k$.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// // Fewer observations than variables: the sample covariance is singular.
+/// let returns = vec![
+///     vec![0.010, -0.004, 0.021, 0.003],
+///     vec![-0.012, 0.008, -0.015, 0.001],
+///     vec![0.004, 0.002, 0.009, -0.006],
+/// ];
+/// let estimate = ledoit_wolf(&returns).unwrap();
+///
+/// assert!(estimate.shrinkage > 0.0 && estimate.shrinkage <= 1.0);
+/// assert!(estimate.covariance.cholesky().is_some());
+/// ```
+///
+/// # Errors
+///
+/// If the observations are invalid (see [`CovarianceError`]).
+pub fn ledoit_wolf(data: &[Vec<f64>]) -> Result<ShrunkCovariance, CovarianceError> {
+    let deviations = deviations(data)?;
+    let (n, p) = (deviations.len() as f64, deviations[0].len());
+
+    let sample = scatter(&deviations) / n;
+    let mu = sample.trace() / p as f64;
+    let target = DMatrix::from_diagonal_element(p, p, mu);
+
+    let distance = (&sample - &target).norm_squared();
+    let dispersion = deviations
+        .iter()
+        .map(|x| (x * x.transpose() - &sample).norm_squared())
+        .sum::<f64>()
+        / (n * n);
+
+    let shrinkage = if distance > 0.0 {
+        dispersion.min(distance) / distance
+    } else {
+        0.0
+    };
+
+    Ok(ShrunkCovariance {
+        covariance: sample * (1.0 - shrinkage) + target * shrinkage,
+        shrinkage,
+    })
+}
+
+/// Exponentially weighted covariance matrix of the returns `data` (one
+/// vector of returns per period, the latest last), with the decay factor
+/// `lambda` (0.94 for daily returns in RiskMetrics).
+///
+/// The returns are taken to have zero mean, as in the univariate
+/// [`crate::statistics::Ewma`], and the weights
+/// $\lambda^k (1 - \lambda) / (1 - \lambda^n)$ of the return `k` periods
+/// ago sum to one.
+///
+/// # Errors
+///
+/// - `CovarianceError::InvalidParameter` if `lambda` is not in `[0, 1)`.
+/// - If the observations are invalid (see [`CovarianceError`]).
+pub fn ewma_covariance(data: &[Vec<f64>], lambda: f64) -> Result<DMatrix<f64>, CovarianceError> {
+    if !(0.0..1.0).contains(&lambda) {
+        return Err(CovarianceError::InvalidParameter);
+    }
+    let p = validate(data)?;
+
+    let mut covariance = DMatrix::zeros(p, p);
+    let mut weight = 1.0;
+    let mut total = 0.0;
+
+    for returns in data.iter().rev() {
+        let r = DVector::from_column_slice(returns);
+        covariance += &r * r.transpose() * weight;
+        total += weight;
+        weight *= lambda;
+    }
+
+    Ok(covariance / total)
+}
+
+/// Nearest covariance matrix to `matrix` with the same variances, from the
+/// nearest correlation matrix of its correlations (see
+/// [`crate::stochastics::nearest_correlation_matrix`]). The result is
+/// positive definite.
+///
+/// ```
+/// use RustQuant::statistics::nearest_covariance_matrix;
+/// use nalgebra::DMatrix;
+///
+/// // Volatilities 20%, 30% and 10%, with inconsistent correlations.
+/// let invalid = DMatrix::from_row_slice(3, 3, &[
+///     0.04, 0.054, 0.014,
+///     0.054, 0.09, -0.012,
+///     0.014, -0.012, 0.01,
+/// ]);
+/// assert!(invalid.clone().cholesky().is_none());
+///
+/// let repaired = nearest_covariance_matrix(&invalid).unwrap();
+/// assert!(repaired.clone().cholesky().is_some());
+/// assert!((repaired[(1, 1)] - 0.09).abs() < 1e-12);
+/// ```
+///
+/// # Errors
+///
+/// - `CovarianceError::InvalidMatrix` if `matrix` is not square and
+///   symmetric, with a positive diagonal.
+pub fn nearest_covariance_matrix(matrix: &DMatrix<f64>) -> Result<DMatrix<f64>, CovarianceError> {
+    const TOLERANCE: f64 = 1e-12;
+
+    let n = matrix.nrows();
+    let valid = n >= 1
+        && matrix.is_square()
+        && (0..n).all(|i| {
+            matrix[(i, i)] > 0.0
+                && (0..i).all(|j| {
+                    let (a, b) = (matrix[(i, j)], matrix[(j, i)]);
+                    (a - b).abs() <= TOLERANCE * a.abs().max(b.abs())
+                })
+        });
+    if !valid {
+        return Err(CovarianceError::InvalidMatrix);
+    }
+
+    let volatilities = matrix.diagonal().map(f64::sqrt);
+    let correlation = DMatrix::from_fn(n, n, |i, j| {
+        matrix[(i, j)] / (volatilities[i] * volatilities[j])
+    });
+    let correlation = nearest_correlation_matrix(&correlation);
+
+    Ok(DMatrix::from_fn(n, n, |i, j| {
+        correlation[(i, j)] * volatilities[i] * volatilities[j]
+    }))
+}
+
+// Checks the observations, and returns their dimension.
+fn validate(data: &[Vec<f64>]) -> Result<usize, CovarianceError> {
+    if data.len() < 2 {
+        return Err(CovarianceError::NotEnoughObservations);
+    }
+
+    let dimension = data[0].len();
+    if dimension == 0 || data.iter().any(|x| x.len() != dimension) {
+        return Err(CovarianceError::DimensionMismatch);
+    }
+
+    Ok(dimension)
+}
+
+// The observations minus their sample mean.
+fn deviations(data: &[Vec<f64>]) -> Result<Vec<DVector<f64>>, CovarianceError> {
+    let p = validate(data)?;
+
+    let observations: Vec<DVector<f64>> =
+        data.iter().map(|x| DVector::from_column_slice(x)).collect();
+    let mean = observations
+        .iter()
+        .fold(DVector::zeros(p), |sum, x| sum + x)
+        / observations.len() as f64;
+
+    Ok(observations.into_iter().map(|x| x - &mean).collect())
+}
+
+// Sum of the outer products of the deviations.
+fn scatter(deviations: &[DVector<f64>]) -> DMatrix<f64> {
+    let p = deviations[0].len();
+
+    deviations
+        .iter()
+        .fold(DMatrix::zeros(p, p), |sum, x| sum + x * x.transpose())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_covariance {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn data() -> Vec<Vec<f64>> {
+        vec![
+            vec![1.0, 2.0, -1.0],
+            vec![2.0, 1.5, 0.5],
+            vec![0.5, 3.0, -2.0],
+            vec![1.5, 2.5, 1.0],
+            vec![3.0, 0.5, 0.0],
+        ]
+    }
+
+    #[test]
+    fn test_sample_covariance() {
+        let covariance = sample_covariance(&data()).unwrap();
+
+        assert_approx_equal!(covariance[(0, 0)], 0.925, 1e-12);
+        assert_approx_equal!(covariance[(0, 1)], -0.8625, 1e-12);
+        assert_approx_equal!(covariance[(2, 1)], -0.475, 1e-12);
+        assert_approx_equal!(covariance[(1, 2)], covariance[(2, 1)], 1e-15);
+
+        assert_eq!(
+            sample_covariance(&data()[..1]),
+            Err(CovarianceError::NotEnoughObservations)
+        );
+        assert_eq!(
+            sample_covariance(&[vec![1.0, 2.0], vec![1.0]]),
+            Err(CovarianceError::DimensionMismatch)
+        );
+    }
+
+    #[test]
+    fn test_ledoit_wolf() {
+        // Reference values computed independently, from the formulae of
+        // Ledoit and Wolf (2004).
+        let estimate = ledoit_wolf(&data()).unwrap();
+
+        assert_approx_equal!(estimate.shrinkage, 0.559_377_769_026_287_3, 1e-12);
+        assert_approx_equal!(estimate.covariance[(0, 0)], 0.818_312_887_663_680_3, 1e-12);
+        assert_approx_equal!(estimate.covariance[(0, 1)], -0.304_029_339_371_861_8, 1e-12);
+
+        // The trace is that of the sample covariance (dividing by n).
+        let sample = sample_covariance(&data()).unwrap() * 0.8;
+        assert_approx_equal!(estimate.covariance.trace(), sample.trace(), 1e-12);
+
+        // Uncorrelated variables with equal variances need no shrinkage.
+        let isotropic = vec![
+            vec![1.0, 0.0],
+            vec![-1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.0, -1.0],
+        ];
+        assert_approx_equal!(ledoit_wolf(&isotropic).unwrap().shrinkage, 0.0, 1e-15);
+    }
+
+    #[test]
+    fn test_ewma_covariance() {
+        let returns = vec![vec![0.01, -0.02], vec![0.03, 0.01], vec![-0.02, 0.02]];
+        let covariance = ewma_covariance(&returns, 0.5).unwrap();
+
+        // Weights 1, 0.5 and 0.25 of the last, middle and first returns.
+        let expected =
+            (-0.02 * 0.02 + 0.5 * 0.03 * 0.01 + 0.25 * 0.01 * -0.02) / (1.0 + 0.5 + 0.25);
+        assert_approx_equal!(covariance[(0, 1)], expected, 1e-15);
+        assert_approx_equal!(covariance[(1, 0)], expected, 1e-15);
+
+        // No decay: the last return only.
+        let covariance = ewma_covariance(&returns, 0.0).unwrap();
+        assert_approx_equal!(covariance[(1, 1)], 0.02 * 0.02, 1e-15);
+
+        assert_eq!(
+            ewma_covariance(&returns, 1.0),
+            Err(CovarianceError::InvalidParameter)
+        );
+    }
+
+    #[test]
+    fn test_nearest_covariance_matrix() {
+        let volatilities = [0.2, 0.3, 0.1];
+        let correlation = [1.0, 0.9, 0.7, 0.9, 1.0, -0.4, 0.7, -0.4, 1.0];
+        let invalid = DMatrix::from_fn(3, 3, |i, j| {
+            correlation[3 * i + j] * volatilities[i] * volatilities[j]
+        });
+
+        let repaired = nearest_covariance_matrix(&invalid).unwrap();
+        assert!(repaired.clone().cholesky().is_some());
+        for i in 0..3 {
+            assert_approx_equal!(repaired[(i, i)], invalid[(i, i)], 1e-12);
+        }
+
+        // A valid covariance matrix is (nearly) unchanged.
+        let valid = sample_covariance(&data()).unwrap();
+        let repaired = nearest_covariance_matrix(&valid).unwrap();
+        assert!((repaired - &valid).norm() < 1e-6);
+
+        let asymmetric = DMatrix::from_row_slice(2, 2, &[1.0, 0.2, 0.3, 1.0]);
+        assert_eq!(
+            nearest_covariance_matrix(&asymmetric),
+            Err(CovarianceError::InvalidMatrix)
+        );
+        assert_eq!(
+            nearest_covariance_matrix(&DMatrix::zeros(2, 2)),
+            Err(CovarianceError::InvalidMatrix)
+        );
+    }
+}
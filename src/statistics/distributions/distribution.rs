@@ -54,6 +54,14 @@ pub enum DistributionError {
     /// Error variant from constructing Poisson distribution.
     #[error("{0}")]
     Poisson(#[from] rand_distr::PoissonError),
+
+    /// A parameter of the distribution is out of its range.
+    #[error("Distribution parameter out of range")]
+    InvalidParameter,
+
+    /// There are too few (valid) observations to fit the distribution.
+    #[error("Not enough observations to fit the distribution")]
+    NotEnoughObservations,
 }
 
 /// Base trait for all distributions.
@@ -126,3 +134,104 @@ pub trait Distribution {
     /// Generates a random sample from the distribution.
     fn sample(&self, n: usize) -> Result<Vec<f64>, DistributionError>;
 }
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NUMERICAL HELPERS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Integral of `f` over [a, b], as the sum of the tanh-sinh quadratures over
+// `panels` equal sub-intervals, for integrands too peaked for one rule.
+pub(crate) fn composite_integral<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, panels: usize) -> f64 {
+    let width = (b - a) / panels as f64;
+
+    (0..panels)
+        .map(|k| {
+            let low = a + k as f64 * width;
+            crate::math::integrate(&f, low, low + width)
+        })
+        .sum()
+}
+
+// Integral of `f` over (-inf, x], for distributions without a closed form
+// distribution function. The half line is mapped onto [0, 1) by
+// y = x - scale * s / (1 - s), so `scale` should be the width of the density.
+pub(crate) fn lower_tail_integral<F: Fn(f64) -> f64>(f: F, x: f64, scale: f64) -> f64 {
+    composite_integral(
+        |s| f(x - scale * s / (1.0 - s)) * scale / (1.0 - s).powi(2),
+        0.0,
+        1.0,
+        8,
+    )
+}
+
+// Integral of `f` over [x, +inf).
+pub(crate) fn upper_tail_integral<F: Fn(f64) -> f64>(f: F, x: f64, scale: f64) -> f64 {
+    lower_tail_integral(|y| f(-y), -x, scale)
+}
+
+// Integral of `f` over the real line, split at `centre`.
+pub(crate) fn real_line_integral<F: Fn(f64) -> f64>(f: F, centre: f64, scale: f64) -> f64 {
+    lower_tail_integral(&f, centre, scale) + upper_tail_integral(&f, centre, scale)
+}
+
+// Solves cdf(x) = p by bracketing outwards from `centre` in steps of `scale`,
+// then bisecting.
+pub(crate) fn invert_cdf<F: Fn(f64) -> f64>(cdf: F, p: f64, centre: f64, scale: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let (mut low, mut high) = (centre - scale, centre + scale);
+    let mut step = scale;
+    while cdf(low) > p && low.is_finite() {
+        step *= 2.0;
+        low -= step;
+    }
+    step = scale;
+    while cdf(high) < p && high.is_finite() {
+        step *= 2.0;
+        high += step;
+    }
+
+    for _ in 0..200 {
+        let middle = 0.5 * (low + high);
+        if middle <= low || middle >= high {
+            break;
+        }
+        if cdf(middle) < p {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    0.5 * (low + high)
+}
+
+// Centre and scale (the median, and the interquartile range scaled to be
+// the standard deviation for normal data) of the finite observations, and
+// the observations standardised by them. Quantile based, so that fits are
+// well started for heavy tailed data too.
+pub(crate) fn standardise(
+    data: &[f64],
+    minimum: usize,
+) -> Result<(f64, f64, Vec<f64>), DistributionError> {
+    let mut finite: Vec<f64> = data.iter().copied().filter(|x| x.is_finite()).collect();
+    if finite.len() < minimum {
+        return Err(DistributionError::NotEnoughObservations);
+    }
+
+    finite.sort_by(f64::total_cmp);
+    let n = finite.len();
+    let median = finite[n / 2];
+    let scale = (finite[3 * n / 4] - finite[n / 4]) / 1.349;
+    if scale <= 0.0 {
+        return Err(DistributionError::NotEnoughObservations);
+    }
+
+    let z = finite.iter().map(|x| (x - median) / scale).collect();
+    Ok((median, scale, z))
+}
@@ -0,0 +1,245 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::statistics::distributions::Distribution;
+use crate::statistics::DistributionError;
+use num_complex::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Empirical distribution of a sample: mass 1/n at each observation.
+///
+/// The distribution function is the empirical distribution function (ECDF)
+/// and the moments are those of the sample (so the variance divides by n).
+/// The quantile function linearly interpolates between the order
+/// statistics, as type 7 of Hyndman and Fan (the default of R's `quantile`),
+/// rather than being the step function inverse of the ECDF.
+/// Sampling is bootstrap resampling of the observations.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::statistics::distributions::*;
+///
+/// let returns = [0.01, -0.02, 0.03, 0.0, -0.01];
+/// let empirical = EmpiricalDistribution::new(&returns).unwrap();
+///
+/// assert_approx_equal!(empirical.cdf(0.0), 0.6, 1e-12);
+/// assert_approx_equal!(empirical.inv_cdf(0.1), -0.016, 1e-12);
+/// assert_approx_equal!(empirical.mean(), 0.002, 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EmpiricalDistribution {
+    /// The finite observations, in increasing order.
+    observations: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl EmpiricalDistribution {
+    /// New empirical distribution of the finite observations in `data`
+    /// (NaN and infinite values are ignored).
+    ///
+    /// # Errors
+    ///
+    /// - `DistributionError::NotEnoughObservations` if there are no finite
+    ///   observations.
+    pub fn new(data: &[f64]) -> Result<Self, DistributionError> {
+        let mut observations: Vec<f64> = data.iter().copied().filter(|x| x.is_finite()).collect();
+        if observations.is_empty() {
+            return Err(DistributionError::NotEnoughObservations);
+        }
+
+        observations.sort_by(f64::total_cmp);
+
+        Ok(Self { observations })
+    }
+
+    /// The observations, in increasing order.
+    #[must_use]
+    pub fn observations(&self) -> &[f64] {
+        &self.observations
+    }
+
+    /// The `k`-th central moment of the sample, E[(X - mean)^k].
+    #[must_use]
+    pub fn central_moment(&self, k: i32) -> f64 {
+        let mean = self.mean();
+
+        self.expectation(|x| (x - mean).powi(k))
+    }
+
+    // Sample average of f(X).
+    fn expectation<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        self.observations.iter().map(|x| f(*x)).sum::<f64>() / self.observations.len() as f64
+    }
+
+    // Lengths of the runs of equal observations, with their values.
+    fn runs(&self) -> Vec<(f64, usize)> {
+        let mut runs: Vec<(f64, usize)> = Vec::new();
+
+        for x in &self.observations {
+            match runs.last_mut() {
+                Some((value, count)) if value.total_cmp(x).is_eq() => *count += 1,
+                _ => runs.push((*x, 1)),
+            }
+        }
+
+        runs
+    }
+}
+
+impl Distribution for EmpiricalDistribution {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        self.observations
+            .iter()
+            .map(|x| Complex::new(0.0, t * x).exp())
+            .sum::<Complex<f64>>()
+            / self.observations.len() as f64
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        self.pmf(x)
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        let below = self.observations.partition_point(|y| *y < x);
+        let at_or_below = self.observations.partition_point(|y| *y <= x);
+
+        (at_or_below - below) as f64 / self.observations.len() as f64
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        self.observations.partition_point(|y| *y <= x) as f64 / self.observations.len() as f64
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn inv_cdf(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        let n = self.observations.len();
+        let h = (n - 1) as f64 * p;
+        let k = h.floor() as usize;
+
+        if k + 1 >= n {
+            self.observations[n - 1]
+        } else {
+            let (low, high) = (self.observations[k], self.observations[k + 1]);
+            low + (h - k as f64) * (high - low)
+        }
+    }
+
+    fn mean(&self) -> f64 {
+        self.expectation(|x| x)
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    /// The most frequent observation (the smallest, if there are several).
+    fn mode(&self) -> f64 {
+        self.runs()
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map_or(f64::NAN, |(value, _)| value)
+    }
+
+    fn variance(&self) -> f64 {
+        self.central_moment(2)
+    }
+
+    fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    fn kurtosis(&self) -> f64 {
+        self.central_moment(4) / self.variance().powi(2) - 3.0
+    }
+
+    fn entropy(&self) -> f64 {
+        let n = self.observations.len() as f64;
+
+        -self
+            .runs()
+            .into_iter()
+            .map(|(_, count)| {
+                let p = count as f64 / n;
+                p * p.ln()
+            })
+            .sum::<f64>()
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        self.expectation(|x| (t * x).exp())
+    }
+
+    fn sample(&self, n: usize) -> Result<Vec<f64>, DistributionError> {
+        use rand::{thread_rng, Rng};
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+        let size = self.observations.len();
+
+        Ok((0..n)
+            .map(|_| self.observations[rng.gen_range(0..size)])
+            .collect())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_empirical {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_empirical_distribution() {
+        let empirical = EmpiricalDistribution::new(&[3.0, 1.0, f64::NAN, 2.0, 2.0, 4.0]).unwrap();
+
+        assert_eq!(empirical.observations(), &[1.0, 2.0, 2.0, 3.0, 4.0]);
+
+        assert_approx_equal!(empirical.cdf(0.5), 0.0, 1e-12);
+        assert_approx_equal!(empirical.cdf(2.0), 0.6, 1e-12);
+        assert_approx_equal!(empirical.cdf(4.0), 1.0, 1e-12);
+        assert_approx_equal!(empirical.pmf(2.0), 0.4, 1e-12);
+        assert_approx_equal!(empirical.pmf(2.5), 0.0, 1e-12);
+
+        // Reference values: quantile(c(1, 2, 2, 3, 4), p) in R.
+        assert_approx_equal!(empirical.inv_cdf(0.0), 1.0, 1e-12);
+        assert_approx_equal!(empirical.inv_cdf(0.1), 1.4, 1e-12);
+        assert_approx_equal!(empirical.inv_cdf(0.9), 3.6, 1e-12);
+        assert_approx_equal!(empirical.inv_cdf(1.0), 4.0, 1e-12);
+        assert_approx_equal!(empirical.median(), 2.0, 1e-12);
+
+        assert_approx_equal!(empirical.mean(), 2.4, 1e-12);
+        assert_approx_equal!(empirical.variance(), 1.04, 1e-12);
+        assert_approx_equal!(empirical.mode(), 2.0, 1e-12);
+        assert_approx_equal!(
+            empirical.entropy(),
+            -(3.0 * 0.2 * 0.2_f64.ln() + 0.4 * 0.4_f64.ln()),
+            1e-12
+        );
+        assert_approx_equal!(empirical.cf(0.0).re, 1.0, 1e-12);
+        assert_approx_equal!(empirical.mgf(0.0), 1.0, 1e-12);
+
+        let sample = empirical.sample(100).unwrap();
+        assert!(sample.iter().all(|x| empirical.pmf(*x) > 0.0));
+
+        assert!(EmpiricalDistribution::new(&[f64::NAN]).is_err());
+    }
+}
@@ -0,0 +1,451 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::nelder_mead;
+use crate::statistics::distributions::{
+    invert_cdf, lower_tail_integral, real_line_integral, standardise, upper_tail_integral,
+    Distribution,
+};
+use crate::statistics::DistributionError;
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalized hyperbolic distribution: X ~ GH(lambda, alpha, beta, delta, mu)
+///
+/// A normal variance-mean mixture, X = mu + beta W + sqrt(W) Z, with W
+/// generalized inverse Gaussian. It nests the normal inverse Gaussian
+/// (lambda = -1/2), hyperbolic (lambda = 1) and variance gamma (delta -> 0)
+/// distributions, and is a standard model for semi-heavy tailed returns.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::statistics::distributions::*;
+///
+/// // Normal inverse Gaussian.
+/// let nig = GeneralizedHyperbolic::new(-0.5, 2.0, 0.5, 1.0, 0.0);
+///
+/// assert_approx_equal!(nig.cdf(nig.inv_cdf(0.9)), 0.9, 1e-8);
+/// assert_approx_equal!(nig.mean(), 1.0 / 15_f64.sqrt(), 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct GeneralizedHyperbolic {
+    /// Lambda: the index of the mixing distribution.
+    lambda: f64,
+    /// Alpha: the tail heaviness.
+    alpha: f64,
+    /// Beta: the asymmetry, |beta| < alpha.
+    beta: f64,
+    /// Delta: the scale.
+    delta: f64,
+    /// Mu: the location.
+    mu: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl GeneralizedHyperbolic {
+    /// New instance of a generalized hyperbolic distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if delta is not positive or |beta| is not less than alpha.
+    #[must_use]
+    pub fn new(lambda: f64, alpha: f64, beta: f64, delta: f64, mu: f64) -> Self {
+        assert!(delta > 0.0 && beta.abs() < alpha);
+
+        Self {
+            lambda,
+            alpha,
+            beta,
+            delta,
+            mu,
+        }
+    }
+
+    /// Natural logarithm of the density at `x`.
+    #[must_use]
+    pub fn ln_pdf(&self, x: f64) -> f64 {
+        let (lambda, alpha, delta) = (self.lambda, self.alpha, self.delta);
+        let gamma = self.gamma();
+        let q = delta.hypot(x - self.mu);
+
+        lambda * (gamma / delta).ln() - 0.5 * (2.0 * PI).ln() - ln_bessel_k(lambda, delta * gamma)
+            + self.beta * (x - self.mu)
+            + ln_bessel_k(lambda - 0.5, alpha * q)
+            - (0.5 - lambda) * (q / alpha).ln()
+    }
+
+    /// Maximum likelihood fit to the observations in `data`.
+    ///
+    /// The likelihood is maximised by the Nelder-Mead algorithm, on the data
+    /// standardised by its median and standard deviation.
+    ///
+    /// # Errors
+    ///
+    /// - `DistributionError::NotEnoughObservations` if there are fewer than
+    ///   five finite observations, or they are all equal.
+    pub fn fit(data: &[f64]) -> Result<Self, DistributionError> {
+        let (centre, scale, z) = standardise(data, 5)?;
+
+        let model = |p: &[f64]| {
+            let beta = p[2];
+            Self {
+                lambda: p[0],
+                alpha: beta.abs() + p[1].exp(),
+                beta,
+                delta: p[3].exp(),
+                mu: p[4],
+            }
+        };
+        let objective = |p: &[f64]| {
+            let model = model(p);
+            let value = -z.iter().map(|x| model.ln_pdf(*x)).sum::<f64>();
+            if value.is_finite() {
+                value
+            } else {
+                f64::INFINITY
+            }
+        };
+
+        // Start from a symmetric normal inverse Gaussian.
+        let minimizer = nelder_mead(objective, &[-0.5, 0.0, 0.0, 0.0, 0.0]);
+        let fitted = model(&minimizer);
+
+        Ok(Self {
+            lambda: fitted.lambda,
+            alpha: fitted.alpha / scale,
+            beta: fitted.beta / scale,
+            delta: fitted.delta * scale,
+            mu: centre + fitted.mu * scale,
+        })
+    }
+
+    fn gamma(&self) -> f64 {
+        (self.alpha * self.alpha - self.beta * self.beta).sqrt()
+    }
+
+    // Ratio K_{lambda + k}(zeta) / K_lambda(zeta), where zeta = delta gamma.
+    fn bessel_ratio(&self, k: f64) -> f64 {
+        let zeta = self.delta * self.gamma();
+
+        (ln_bessel_k(self.lambda + k, zeta) - ln_bessel_k(self.lambda, zeta)).exp()
+    }
+
+    fn central_moment(&self, k: i32) -> f64 {
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+
+        real_line_integral(|x| (x - mean).powi(k) * self.pdf(x), mean, sd)
+    }
+}
+
+impl Distribution for GeneralizedHyperbolic {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        let (lambda, delta) = (self.lambda, self.delta);
+        let gamma = self.gamma();
+        let w = (self.alpha * self.alpha - Complex::new(self.beta, t).powi(2)).sqrt();
+
+        (Complex::new(0.0, self.mu * t)
+            + lambda * (gamma.ln() - w.ln())
+            + ln_bessel_k_complex(lambda, delta * w)
+            - ln_bessel_k(lambda, delta * gamma))
+        .exp()
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        self.ln_pdf(x).exp()
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        self.pdf(x)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+
+        if x <= mean {
+            lower_tail_integral(|y| self.pdf(y), x, sd)
+        } else {
+            1.0 - upper_tail_integral(|y| self.pdf(y), x, sd)
+        }
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        invert_cdf(|x| self.cdf(x), p, self.mean(), self.variance().sqrt())
+    }
+
+    fn mean(&self) -> f64 {
+        self.mu + self.beta * self.delta / self.gamma() * self.bessel_ratio(1.0)
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    fn mode(&self) -> f64 {
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+        let minimizer = nelder_mead(|z| -self.ln_pdf(mean + sd * z[0]), &[0.0]);
+
+        mean + sd * minimizer[0]
+    }
+
+    fn variance(&self) -> f64 {
+        let gamma = self.gamma();
+        let r1 = self.bessel_ratio(1.0);
+
+        self.delta / gamma * r1
+            + (self.beta * self.delta / gamma).powi(2) * (self.bessel_ratio(2.0) - r1 * r1)
+    }
+
+    fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    fn kurtosis(&self) -> f64 {
+        self.central_moment(4) / self.variance().powi(2) - 3.0
+    }
+
+    fn entropy(&self) -> f64 {
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+
+        real_line_integral(
+            |x| {
+                let ln_pdf = self.ln_pdf(x);
+                -ln_pdf * ln_pdf.exp()
+            },
+            mean,
+            sd,
+        )
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        assert!((self.beta + t).abs() < self.alpha);
+
+        let (lambda, delta) = (self.lambda, self.delta);
+        let gamma = self.gamma();
+        let w = (self.alpha * self.alpha - (self.beta + t).powi(2)).sqrt();
+
+        (self.mu * t + lambda * (gamma / w).ln() + ln_bessel_k(lambda, delta * w)
+            - ln_bessel_k(lambda, delta * gamma))
+        .exp()
+    }
+
+    fn sample(&self, n: usize) -> Result<Vec<f64>, DistributionError> {
+        use rand::thread_rng;
+        use rand_distr::{Distribution, StandardNormal};
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+        let gamma = self.gamma();
+        let mixing = GeneralizedInverseGaussian::new(self.lambda, self.delta * gamma);
+        let eta = self.delta / gamma;
+
+        let variates = (0..n)
+            .map(|_| {
+                let w = eta * mixing.sample(&mut rng);
+                let z: f64 = StandardNormal.sample(&mut rng);
+
+                self.mu + self.beta * w + w.sqrt() * z
+            })
+            .collect();
+
+        Ok(variates)
+    }
+}
+
+// Generalized inverse Gaussian variates with density proportional to
+// y^(lambda - 1) exp(-omega (y + 1 / y) / 2), by the ratio-of-uniforms
+// method with mode shift (Hörmann and Leydold, 2014).
+struct GeneralizedInverseGaussian {
+    lambda: f64,
+    omega: f64,
+    mode: f64,
+    v_minus: f64,
+    v_plus: f64,
+}
+
+impl GeneralizedInverseGaussian {
+    fn new(lambda: f64, omega: f64) -> Self {
+        // GIG(-lambda) is the distribution of the reciprocal of GIG(lambda).
+        let l = lambda.abs();
+        let mode = ((l - 1.0) + (l - 1.0).hypot(omega)) / omega;
+
+        // The extremes of (y - mode) sqrt(h(y)) are roots of a cubic.
+        let a = -2.0 * (l + 1.0) / omega - mode;
+        let b = 2.0 * (l - 1.0) * mode / omega - 1.0;
+        let p = b - a * a / 3.0;
+        let q = 2.0 * a.powi(3) / 27.0 - a * b / 3.0 + mode;
+        let phi = (-0.5 * q * (-27.0 / p.powi(3)).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos();
+        let radius = (-4.0 * p / 3.0).sqrt();
+        let y_minus = radius * (phi / 3.0 + 4.0 * PI / 3.0).cos() - a / 3.0;
+        let y_plus = radius * (phi / 3.0).cos() - a / 3.0;
+
+        let mut gig = Self {
+            lambda,
+            omega,
+            mode,
+            v_minus: 0.0,
+            v_plus: 0.0,
+        };
+        gig.v_minus = (y_minus - mode) * (0.5 * gig.ln_h(y_minus)).exp();
+        gig.v_plus = (y_plus - mode) * (0.5 * gig.ln_h(y_plus)).exp();
+        gig
+    }
+
+    // Log of the unnormalised density of GIG(|lambda|), relative to the mode.
+    fn ln_h(&self, y: f64) -> f64 {
+        let l = self.lambda.abs();
+        let ln_h = |y: f64| (l - 1.0) * y.ln() - 0.5 * self.omega * (y + y.recip());
+
+        ln_h(y) - ln_h(self.mode)
+    }
+
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        loop {
+            let u: f64 = rng.gen();
+            let v = self.v_minus + (self.v_plus - self.v_minus) * rng.gen::<f64>();
+            let y = v / u + self.mode;
+
+            if y > 0.0 && 2.0 * u.ln() <= self.ln_h(y) {
+                return if self.lambda < 0.0 { y.recip() } else { y };
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Natural logarithm of the modified Bessel function of the second kind,
+// K_nu(x) = 1/2 int exp(-x cosh(u) + nu u) du over the real line, for x > 0.
+pub(crate) fn ln_bessel_k(nu: f64, x: f64) -> f64 {
+    ln_bessel_k_complex(nu, Complex::new(x, 0.0)).re
+}
+
+// Natural logarithm of K_nu(z) for complex z with positive real part.
+// The integrand is analytic and decays doubly exponentially, so the
+// trapezoidal rule over the real line, centred on the peak of the integrand
+// and with a step resolving its width, converges exponentially fast.
+pub(crate) fn ln_bessel_k_complex(nu: f64, z: Complex<f64>) -> Complex<f64> {
+    let nu = nu.abs();
+    let exponent = |u: f64| -z.re * u.cosh() + nu * u;
+    let peak = (nu / z.re).asinh();
+    let top = exponent(peak);
+    let step = (0.5 / (z.norm() * peak.cosh()).sqrt()).min(0.25);
+
+    let term = |u: f64| (-z * u.cosh() + nu * u - top).exp();
+    let mut sum = term(peak);
+    for direction in [-1.0, 1.0] {
+        let mut u = peak + direction * step;
+        while exponent(u) > top - 40.0 {
+            sum += term(u);
+            u += direction * step;
+        }
+    }
+
+    top + (0.5 * step * sum).ln()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_generalized_hyperbolic {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_bessel_k() {
+        // Closed forms for half-integer orders, and tabulated values.
+        let x: f64 = 1.7;
+        let k_half = (PI / (2.0 * x)).sqrt() * (-x).exp();
+        assert_approx_equal!(ln_bessel_k(0.5, x).exp(), k_half, 1e-12);
+        assert_approx_equal!(ln_bessel_k(1.5, x).exp(), k_half * (1.0 + x.recip()), 1e-12);
+        assert_approx_equal!(ln_bessel_k(0.0, 1.0).exp(), 0.421_024_438_240_708_3, 1e-12);
+        assert_approx_equal!(ln_bessel_k(1.0, 2.0).exp(), 0.139_865_881_816_522_4, 1e-12);
+        assert_approx_equal!(
+            ln_bessel_k(0.0, 500.0),
+            -500.0 + (PI / 1000.0).sqrt().ln(),
+            1e-3
+        );
+
+        let z = Complex::new(1.7, 0.0);
+        assert_approx_equal!(ln_bessel_k_complex(0.5, z).exp().re, k_half, 1e-12);
+    }
+
+    #[test]
+    fn test_normal_inverse_gaussian() {
+        let (alpha, beta, delta, mu): (f64, f64, f64, f64) = (2.0, 0.5, 1.0, 0.3);
+        let gamma = (alpha * alpha - beta * beta).sqrt();
+        let nig = GeneralizedHyperbolic::new(-0.5, alpha, beta, delta, mu);
+
+        for x in [-3.0, -0.5, 0.3, 1.0, 4.0] {
+            let q = delta.hypot(x - mu);
+            let pdf = alpha * delta / (PI * q)
+                * ln_bessel_k(1.0, alpha * q).exp()
+                * (delta * gamma + beta * (x - mu)).exp();
+            assert_approx_equal!(nig.pdf(x), pdf, 1e-12);
+        }
+
+        // Closed form moments of the normal inverse Gaussian.
+        assert_approx_equal!(nig.mean(), mu + delta * beta / gamma, 1e-10);
+        assert_approx_equal!(nig.variance(), delta * alpha * alpha / gamma.powi(3), 1e-10);
+        assert_approx_equal!(
+            nig.skewness(),
+            3.0 * beta / (alpha * (delta * gamma).sqrt()),
+            1e-6
+        );
+        assert_approx_equal!(
+            nig.kurtosis(),
+            3.0 * (1.0 + 4.0 * beta * beta / (alpha * alpha)) / (delta * gamma),
+            1e-5
+        );
+
+        assert_approx_equal!(nig.cdf(10.0) - nig.cdf(-10.0), 1.0, 1e-6);
+        assert_approx_equal!(nig.mgf(0.0), 1.0, 1e-12);
+        assert_approx_equal!(nig.cf(0.0).re, 1.0, 1e-12);
+
+        // cf(t) = exp(i mu t + delta (gamma - sqrt(alpha^2 - (beta + i t)^2))).
+        let t = 0.8;
+        let w = (alpha * alpha - Complex::new(beta, t).powi(2)).sqrt();
+        let cf = (Complex::new(0.0, mu * t) + delta * (gamma - w)).exp();
+        assert_approx_equal!(nig.cf(t).re, cf.re, 1e-8);
+        assert_approx_equal!(nig.cf(t).im, cf.im, 1e-8);
+    }
+
+    #[test]
+    fn test_generalized_hyperbolic_sample_and_fit() {
+        let gh = GeneralizedHyperbolic::new(1.0, 3.0, -1.0, 0.5, 0.1);
+        let sample = gh.sample(20_000).unwrap();
+
+        let n = sample.len() as f64;
+        let mean = sample.iter().sum::<f64>() / n;
+        let variance = sample.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        assert_approx_equal!(mean, gh.mean(), 0.03);
+        assert_approx_equal!(variance, gh.variance(), 0.06);
+
+        // Tolerances of about four standard errors of the estimates.
+        let fitted = GeneralizedHyperbolic::fit(&sample[..2_000]).unwrap();
+        assert_approx_equal!(fitted.mean(), gh.mean(), 0.1);
+        assert_approx_equal!(fitted.variance(), gh.variance(), 0.15);
+        assert_approx_equal!(fitted.inv_cdf(0.01), gh.inv_cdf(0.01), 0.3);
+
+        assert!(GeneralizedHyperbolic::fit(&[1.0, 1.0, 1.0, 1.0, 1.0]).is_err());
+    }
+}
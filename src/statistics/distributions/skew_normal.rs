@@ -0,0 +1,310 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::{integrate, nelder_mead};
+use crate::statistics::distributions::{invert_cdf, real_line_integral, standardise, Distribution};
+use crate::statistics::DistributionError;
+use num_complex::Complex;
+use statrs::function::erf::erfc;
+use std::f64::consts::{FRAC_1_SQRT_2, LN_2, PI};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Skew-normal distribution: X ~ SN(xi, omega, alpha)
+///
+/// The density is 2 / omega phi(z) Phi(alpha z), with z = (x - xi) / omega,
+/// so `alpha` skews the normal distribution (alpha = 0) to either side.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::statistics::distributions::*;
+///
+/// let sn = SkewNormal::new(0.0, 1.0, 3.0);
+///
+/// assert!(sn.skewness() > 0.0);
+/// assert_approx_equal!(sn.cdf(sn.inv_cdf(0.25)), 0.25, 1e-10);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SkewNormal {
+    /// Xi: the location.
+    xi: f64,
+    /// Omega: the scale.
+    omega: f64,
+    /// Alpha: the shape (skewness).
+    alpha: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SkewNormal {
+    /// New instance of a skew-normal distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if omega is not positive.
+    #[must_use]
+    pub fn new(xi: f64, omega: f64, alpha: f64) -> Self {
+        assert!(omega > 0.0);
+
+        Self { xi, omega, alpha }
+    }
+
+    /// Natural logarithm of the density at `x`.
+    #[must_use]
+    pub fn ln_pdf(&self, x: f64) -> f64 {
+        let z = (x - self.xi) / self.omega;
+
+        LN_2 - self.omega.ln() - 0.5 * (2.0 * PI).ln() - 0.5 * z * z
+            + normal_cdf(self.alpha * z).ln()
+    }
+
+    /// Maximum likelihood fit to the observations in `data`, by the
+    /// Nelder-Mead algorithm started from the method of moments estimates.
+    ///
+    /// # Errors
+    ///
+    /// - `DistributionError::NotEnoughObservations` if there are fewer than
+    ///   three finite observations, or they are all equal.
+    pub fn fit(data: &[f64]) -> Result<Self, DistributionError> {
+        let (centre, scale, z) = standardise(data, 3)?;
+
+        let n = z.len() as f64;
+        let mean = z.iter().sum::<f64>() / n;
+        let variance = z.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let skewness = z.iter().map(|x| (x - mean).powi(3)).sum::<f64>() / n / variance.powf(1.5);
+
+        // Method of moments, with the skewness capped inside the attainable
+        // range (|skewness| < 0.995).
+        let r = (2.0 * skewness.abs().min(0.99) / (4.0 - PI)).cbrt();
+        let mu_z = (r / r.hypot(1.0)).copysign(skewness);
+        let delta = (mu_z * (0.5 * PI).sqrt()).clamp(-0.99, 0.99);
+        let omega = (variance / (1.0 - mu_z * mu_z)).sqrt();
+        let start = [
+            mean - omega * mu_z,
+            omega.ln(),
+            delta / (1.0 - delta * delta).sqrt(),
+        ];
+
+        let model = |p: &[f64]| Self {
+            xi: p[0],
+            omega: p[1].exp(),
+            alpha: p[2],
+        };
+        let objective = |p: &[f64]| {
+            let model = model(p);
+            let value = -z.iter().map(|x| model.ln_pdf(*x)).sum::<f64>();
+            if value.is_finite() {
+                value
+            } else {
+                f64::INFINITY
+            }
+        };
+
+        let fitted = model(&nelder_mead(objective, &start));
+
+        Ok(Self {
+            xi: centre + fitted.xi * scale,
+            omega: fitted.omega * scale,
+            alpha: fitted.alpha,
+        })
+    }
+
+    fn delta(&self) -> f64 {
+        self.alpha / self.alpha.hypot(1.0)
+    }
+
+    // Mean of the standardised variable, (X - xi) / omega.
+    fn mu_z(&self) -> f64 {
+        self.delta() * (2.0 / PI).sqrt()
+    }
+}
+
+impl Distribution for SkewNormal {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        let (omega, delta) = (self.omega, self.delta());
+        let damping = -0.5 * (omega * t).powi(2);
+
+        // exp(-omega^2 t^2 / 2) erfi(y), computed as one integral to
+        // avoid overflow.
+        let y = delta * omega * t * FRAC_1_SQRT_2;
+        let imaginary = 2.0 / PI.sqrt() * integrate(|s| (s * s + damping).exp(), 0.0, y);
+
+        Complex::new(0.0, self.xi * t).exp() * Complex::new(damping.exp(), imaginary)
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        self.ln_pdf(x).exp()
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        self.pdf(x)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let z = (x - self.xi) / self.omega;
+
+        (normal_cdf(z) - 2.0 * owens_t(z, self.alpha)).clamp(0.0, 1.0)
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        invert_cdf(|x| self.cdf(x), p, self.mean(), self.variance().sqrt())
+    }
+
+    fn mean(&self) -> f64 {
+        self.xi + self.omega * self.mu_z()
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    fn mode(&self) -> f64 {
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+        let minimizer = nelder_mead(|z| -self.ln_pdf(mean + sd * z[0]), &[0.0]);
+
+        mean + sd * minimizer[0]
+    }
+
+    fn variance(&self) -> f64 {
+        self.omega * self.omega * (1.0 - self.mu_z().powi(2))
+    }
+
+    fn skewness(&self) -> f64 {
+        let mu_z = self.mu_z();
+
+        0.5 * (4.0 - PI) * mu_z.powi(3) / (1.0 - mu_z * mu_z).powf(1.5)
+    }
+
+    fn kurtosis(&self) -> f64 {
+        let mu_z = self.mu_z();
+
+        2.0 * (PI - 3.0) * mu_z.powi(4) / (1.0 - mu_z * mu_z).powi(2)
+    }
+
+    fn entropy(&self) -> f64 {
+        real_line_integral(
+            |x| {
+                let ln_pdf = self.ln_pdf(x);
+                -ln_pdf * ln_pdf.exp()
+            },
+            self.mean(),
+            self.variance().sqrt(),
+        )
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        let omega = self.omega;
+
+        2.0 * (self.xi * t + 0.5 * (omega * t).powi(2)).exp() * normal_cdf(self.delta() * omega * t)
+    }
+
+    fn sample(&self, n: usize) -> Result<Vec<f64>, DistributionError> {
+        // IMPORT HERE TO AVOID CLASH WITH
+        // `RustQuant::distributions::Distribution`
+        use rand::thread_rng;
+        use rand_distr::{Distribution, StandardNormal};
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+        let delta = self.delta();
+
+        // X = xi + omega (delta |U_0| + sqrt(1 - delta^2) U_1).
+        let variates = (0..n)
+            .map(|_| {
+                let u_0: f64 = StandardNormal.sample(&mut rng);
+                let u_1: f64 = StandardNormal.sample(&mut rng);
+
+                self.xi + self.omega * (delta * u_0.abs() + (1.0 - delta * delta).sqrt() * u_1)
+            })
+            .collect();
+
+        Ok(variates)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x * FRAC_1_SQRT_2)
+}
+
+// Owen's T function, T(h, a) = 1 / (2 pi) int_0^a exp(-h^2 (1 + x^2) / 2) / (1 + x^2) dx.
+// The range is split at one, as the integrand decays slowly for large |a|.
+fn owens_t(h: f64, a: f64) -> f64 {
+    let integrand = |x: f64| {
+        let one_plus = 1.0 + x * x;
+        (-0.5 * h * h * one_plus).exp() / one_plus
+    };
+    let b = a.abs();
+    let integral = integrate(integrand, 0.0, b.min(1.0))
+        + if b > 1.0 {
+            integrate(integrand, 1.0, b)
+        } else {
+            0.0
+        };
+
+    (integral / (2.0 * PI)).copysign(a)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_skew_normal {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::statistics::distributions::Gaussian;
+
+    #[test]
+    fn test_skew_normal() {
+        // Zero shape is the normal distribution.
+        let sn = SkewNormal::new(1.0, 1.0, 0.0);
+        let normal = Gaussian::new(1.0, 1.0);
+        for x in [-3.0, 0.0, 1.5, 4.0] {
+            assert_approx_equal!(sn.pdf(x), normal.pdf(x), 1e-12);
+            assert_approx_equal!(sn.cdf(x), normal.cdf(x), 1e-12);
+        }
+
+        // Reference values by numerical integration of the density.
+        let sn = SkewNormal::new(0.0, 1.0, 4.0);
+        assert_approx_equal!(sn.cdf(0.0), 0.077_979_130_377_369_3, 1e-12);
+        assert_approx_equal!(sn.cdf(1.0), 0.682_690_310_106_119_8, 1e-10);
+        assert_approx_equal!(sn.cdf(-0.5), 0.001_424_756_040_110_929, 1e-10);
+        assert_approx_equal!(sn.inv_cdf(sn.cdf(0.7)), 0.7, 1e-10);
+
+        assert_approx_equal!(sn.cdf(8.0) - sn.cdf(-8.0), 1.0, 1e-12);
+        assert_approx_equal!(sn.cf(0.0).re, 1.0, 1e-12);
+        assert_approx_equal!(sn.mgf(0.0), 1.0, 1e-12);
+        assert!(sn.mode() < sn.mean());
+        assert!(sn.mode() > 0.0);
+    }
+
+    #[test]
+    fn test_skew_normal_fit() {
+        let sn = SkewNormal::new(-0.5, 1.5, 5.0);
+        let sample = sn.sample(20_000).unwrap();
+
+        let n = sample.len() as f64;
+        let mean = sample.iter().sum::<f64>() / n;
+        assert_approx_equal!(mean, sn.mean(), 0.03);
+
+        let fitted = SkewNormal::fit(&sample).unwrap();
+        assert_approx_equal!(fitted.xi, -0.5, 0.05);
+        assert_approx_equal!(fitted.omega, 1.5, 0.05);
+        assert_approx_equal!(fitted.alpha, 5.0, 1.0);
+    }
+}
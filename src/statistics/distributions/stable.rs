@@ -0,0 +1,524 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::{integrate, nelder_mead};
+use crate::statistics::distributions::{invert_cdf, real_line_integral, standardise, Distribution};
+use crate::statistics::DistributionError;
+use num_complex::Complex;
+use statrs::function::gamma::gamma;
+use std::f64::consts::{FRAC_2_PI, FRAC_PI_2, PI};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Stable distribution: X ~ S(alpha, beta, gamma, delta)
+///
+/// Uses the standard (S1) parametrization, with characteristic function
+///
+/// - cf(t) = exp(i delta t - |gamma t|^alpha (1 - i beta sgn(t) tan(pi alpha / 2))), for alpha != 1,
+/// - cf(t) = exp(i delta t - |gamma t| (1 + i beta sgn(t) 2 / pi ln|t|)), for alpha = 1.
+///
+/// The density and distribution function have no closed form, and are
+/// computed from Nolan's (1997) integral representations. `alpha = 2` is
+/// the Gaussian with variance 2 gamma^2, `alpha = 1, beta = 0` the Cauchy,
+/// and `alpha = 1/2, beta = 1` the Lévy distribution.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::statistics::distributions::*;
+///
+/// let stable = Stable::new(1.7, -0.5, 0.01, 0.0);
+///
+/// // The left tail is heavier than the right.
+/// assert!(stable.cdf(-0.05) > 1.0 - stable.cdf(0.05));
+/// assert_approx_equal!(stable.cdf(stable.inv_cdf(0.01)), 0.01, 1e-8);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Stable {
+    /// Alpha: the stability index, in (0, 2].
+    alpha: f64,
+    /// Beta: the skewness, in [-1, 1].
+    beta: f64,
+    /// Gamma: the scale.
+    gamma: f64,
+    /// Delta: the location.
+    delta: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Stable {
+    /// New instance of a stable distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if alpha is not in (0, 2], beta is not in [-1, 1], or gamma is
+    /// not positive.
+    #[must_use]
+    pub fn new(alpha: f64, beta: f64, gamma: f64, delta: f64) -> Self {
+        assert!(alpha > 0.0 && alpha <= 2.0);
+        assert!((-1.0..=1.0).contains(&beta));
+        assert!(gamma > 0.0);
+
+        Self {
+            alpha,
+            beta,
+            gamma,
+            delta,
+        }
+    }
+
+    /// Maximum likelihood fit to the observations in `data`, by the
+    /// Nelder-Mead algorithm.
+    ///
+    /// Each likelihood evaluation integrates numerically for every
+    /// observation, so this is slow for large samples.
+    ///
+    /// # Errors
+    ///
+    /// - `DistributionError::NotEnoughObservations` if there are fewer than
+    ///   four finite observations, or they are all equal.
+    pub fn fit(data: &[f64]) -> Result<Self, DistributionError> {
+        let (centre, scale, z) = standardise(data, 4)?;
+
+        // The stability index is kept in (0.1, 2).
+        let model = |p: &[f64]| Self {
+            alpha: 0.1 + 1.9 / (1.0 + (-p[0]).exp()),
+            beta: p[1].tanh(),
+            gamma: p[2].exp(),
+            delta: p[3],
+        };
+        let objective = |p: &[f64]| {
+            let model = model(p);
+            let value = -z.iter().map(|x| model.pdf(*x).ln()).sum::<f64>();
+            if value.is_finite() {
+                value
+            } else {
+                f64::INFINITY
+            }
+        };
+
+        // alpha = 1.5, and the scale of a Gaussian with unit variance.
+        let start = [(1.4_f64 / 0.5).ln(), 0.0, -0.5 * 2_f64.ln(), 0.0];
+        let fitted = model(&nelder_mead(objective, &start));
+
+        let gamma = fitted.gamma * scale;
+        let mut delta = centre + fitted.delta * scale;
+        if is_unit(fitted.alpha) {
+            delta -= FRAC_2_PI * fitted.beta * gamma * scale.ln();
+        }
+
+        Ok(Self {
+            alpha: fitted.alpha,
+            beta: fitted.beta,
+            gamma,
+            delta,
+        })
+    }
+
+    // The standardised variable, Z ~ S(alpha, beta, 1, 0), at x.
+    fn standardise(&self, x: f64) -> f64 {
+        let z = (x - self.delta) / self.gamma;
+
+        if is_unit(self.alpha) {
+            z - FRAC_2_PI * self.beta * self.gamma.ln()
+        } else {
+            z
+        }
+    }
+
+    // Density of Z ~ S(alpha, beta, 1, 0) at z.
+    fn standard_pdf(&self, z: f64) -> f64 {
+        let (alpha, beta) = (self.alpha, self.beta);
+
+        if is_unit(alpha) && beta == 0.0 {
+            return (PI * (1.0 + z * z)).recip();
+        }
+        if !is_unit(alpha) && z == 0.0 {
+            let theta_0 = theta_0(alpha, beta);
+            let zeta = -beta * (PI * alpha / 2.0).tan();
+            return gamma(1.0 + alpha.recip()) * theta_0.cos()
+                / (PI * zeta.hypot(1.0).powf(alpha.recip()));
+        }
+        if (is_unit(alpha) && beta < 0.0) || (!is_unit(alpha) && z < 0.0) {
+            return self.reflected().standard_pdf(-z);
+        }
+
+        let nolan = Nolan::new(alpha, beta, z);
+        let density = nolan.integral(|ln_g| (ln_g - ln_g.exp()).exp());
+
+        if is_unit(alpha) {
+            density / (2.0 * beta)
+        } else {
+            alpha * density / (PI * (alpha - 1.0).abs() * z)
+        }
+    }
+
+    // Distribution function of Z ~ S(alpha, beta, 1, 0) at z.
+    fn standard_cdf(&self, z: f64) -> f64 {
+        let (alpha, beta) = (self.alpha, self.beta);
+
+        if is_unit(alpha) && beta == 0.0 {
+            return 0.5 + z.atan() / PI;
+        }
+        if !is_unit(alpha) && z == 0.0 {
+            return (FRAC_PI_2 - theta_0(alpha, beta)) / PI;
+        }
+        if (is_unit(alpha) && beta < 0.0) || (!is_unit(alpha) && z < 0.0) {
+            return 1.0 - self.reflected().standard_cdf(-z);
+        }
+
+        let nolan = Nolan::new(alpha, beta, z);
+        let tail = nolan.integral(|ln_g| (-ln_g.exp()).exp()) / PI;
+
+        if is_unit(alpha) {
+            tail
+        } else if alpha < 1.0 {
+            (FRAC_PI_2 - nolan.theta_0) / PI + tail
+        } else {
+            1.0 - tail
+        }
+    }
+
+    // The distribution of -Z, S(alpha, -beta, 1, 0).
+    fn reflected(&self) -> Self {
+        Self {
+            beta: -self.beta,
+            ..*self
+        }
+    }
+}
+
+impl Distribution for Stable {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        let (alpha, beta) = (self.alpha, self.beta);
+        let scaled = (self.gamma * t).abs();
+
+        let exponent = if is_unit(alpha) {
+            let skew = if t == 0.0 {
+                0.0
+            } else {
+                FRAC_2_PI * beta * t.signum() * t.abs().ln()
+            };
+            -scaled * Complex::new(1.0, skew)
+        } else {
+            -scaled.powf(alpha) * Complex::new(1.0, -beta * t.signum() * (PI * alpha / 2.0).tan())
+        };
+
+        (Complex::new(0.0, self.delta * t) + exponent).exp()
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        self.standard_pdf(self.standardise(x)) / self.gamma
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        self.pdf(x)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        self.standard_cdf(self.standardise(x)).clamp(0.0, 1.0)
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        invert_cdf(|x| self.cdf(x), p, self.delta, self.gamma)
+    }
+
+    /// The mean exists for alpha > 1.
+    fn mean(&self) -> f64 {
+        if self.alpha > 1.0 {
+            self.delta
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    fn mode(&self) -> f64 {
+        let minimizer = nelder_mead(|z| -self.pdf(self.delta + self.gamma * z[0]).ln(), &[0.0]);
+
+        self.delta + self.gamma * minimizer[0]
+    }
+
+    /// The variance is infinite unless alpha = 2.
+    fn variance(&self) -> f64 {
+        if self.alpha >= 2.0 {
+            2.0 * self.gamma * self.gamma
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// The skewness only exists for alpha = 2.
+    fn skewness(&self) -> f64 {
+        if self.alpha >= 2.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// The kurtosis only exists for alpha = 2.
+    fn kurtosis(&self) -> f64 {
+        if self.alpha >= 2.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn entropy(&self) -> f64 {
+        real_line_integral(
+            |x| {
+                let pdf = self.pdf(x);
+                -pdf * pdf.ln()
+            },
+            self.delta,
+            self.gamma,
+        )
+    }
+
+    /// The moment generating function is finite for alpha = 2, and on the
+    /// light tailed side of a totally skewed (|beta| = 1, alpha != 1)
+    /// distribution; otherwise it is infinite for t != 0.
+    fn mgf(&self, t: f64) -> f64 {
+        let (alpha, beta, gamma) = (self.alpha, self.beta, self.gamma);
+
+        if t == 0.0 {
+            1.0
+        } else if alpha >= 2.0 {
+            (self.delta * t + (gamma * t).powi(2)).exp()
+        } else if !is_unit(alpha) && beta.abs() >= 1.0 && beta * t < 0.0 {
+            (self.delta * t - (gamma * t.abs()).powf(alpha) / (PI * alpha / 2.0).cos()).exp()
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    fn sample(&self, n: usize) -> Result<Vec<f64>, DistributionError> {
+        // IMPORT HERE TO AVOID CLASH WITH
+        // `RustQuant::distributions::Distribution`
+        use rand::{thread_rng, Rng};
+        use rand_distr::Exp1;
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+        let (alpha, beta, gamma) = (self.alpha, self.beta, self.gamma);
+
+        // Chambers, Mallows and Stuck (1976), as given by Weron (1996).
+        let variates = (0..n)
+            .map(|_| {
+                let v = PI * (rng.gen::<f64>() - 0.5);
+                let w: f64 = rng.sample(Exp1);
+
+                if is_unit(alpha) {
+                    let a = FRAC_PI_2 + beta * v;
+                    let z = FRAC_2_PI * (a * v.tan() - beta * (FRAC_PI_2 * w * v.cos() / a).ln());
+
+                    gamma * z + FRAC_2_PI * beta * gamma * gamma.ln() + self.delta
+                } else {
+                    let tan = beta * (PI * alpha / 2.0).tan();
+                    let b = tan.atan() / alpha;
+                    let s = tan.hypot(1.0).powf(alpha.recip());
+                    let z = s * (alpha * (v + b)).sin() / v.cos().powf(alpha.recip())
+                        * ((v - alpha * (v + b)).cos() / w).powf((1.0 - alpha) / alpha);
+
+                    gamma * z + self.delta
+                }
+            })
+            .collect();
+
+        Ok(variates)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Alpha = 1 is a separate case throughout the S1 parametrization.
+fn is_unit(alpha: f64) -> bool {
+    (alpha - 1.0).abs() < f64::EPSILON
+}
+
+fn theta_0(alpha: f64, beta: f64) -> f64 {
+    (beta * (PI * alpha / 2.0).tan()).atan() / alpha
+}
+
+// Nolan's (1997) representation of the standard density and distribution
+// function at z > 0 (or any z, for alpha = 1 and beta > 0), as integrals
+// over (low, pi / 2) of functions of g(theta) = z^(alpha / (alpha - 1)) V(theta),
+// which is monotone in theta.
+struct Nolan {
+    alpha: f64,
+    beta: f64,
+    theta_0: f64,
+    low: f64,
+    // ln z^(alpha / (alpha - 1)), or -pi z / (2 beta) for alpha = 1.
+    shift: f64,
+}
+
+impl Nolan {
+    fn new(alpha: f64, beta: f64, z: f64) -> Self {
+        if is_unit(alpha) {
+            Self {
+                alpha,
+                beta,
+                theta_0: FRAC_PI_2,
+                low: -FRAC_PI_2,
+                shift: -PI * z / (2.0 * beta),
+            }
+        } else {
+            let theta_0 = theta_0(alpha, beta);
+            Self {
+                alpha,
+                beta,
+                theta_0,
+                low: -theta_0,
+                shift: alpha / (alpha - 1.0) * z.ln(),
+            }
+        }
+    }
+
+    fn ln_g(&self, theta: f64) -> f64 {
+        let (alpha, beta, theta_0) = (self.alpha, self.beta, self.theta_0);
+
+        let ln_v = if is_unit(alpha) {
+            let a = FRAC_PI_2 + beta * theta;
+            (FRAC_2_PI * a / theta.cos()).ln() + a * theta.tan() / beta
+        } else {
+            (alpha * theta_0).cos().ln() / (alpha - 1.0)
+                + alpha / (alpha - 1.0) * (theta.cos() / (alpha * (theta_0 + theta)).sin()).ln()
+                + (alpha * theta_0 + (alpha - 1.0) * theta).cos().ln()
+                - theta.cos().ln()
+        };
+
+        self.shift + ln_v
+    }
+
+    // int h(ln g(theta)) d theta over (low, pi / 2). The integrands are
+    // concentrated where g is of order one, which can be a very narrow
+    // range of theta, so the range is split where ln g crosses a few levels.
+    fn integral<H: Fn(f64) -> f64>(&self, h: H) -> f64 {
+        let (low, high) = (self.low, FRAC_PI_2);
+        let quarter = 0.25 * (high - low);
+        let increasing = self.ln_g(high - quarter) > self.ln_g(low + quarter);
+
+        let mut points = vec![low, high];
+        for level in [-6.0, -2.0, 0.0, 1.5, 3.5] {
+            let (mut a, mut b) = (low, high);
+            for _ in 0..50 {
+                let middle = 0.5 * (a + b);
+                if (self.ln_g(middle) < level) == increasing {
+                    a = middle;
+                } else {
+                    b = middle;
+                }
+            }
+            points.push(0.5 * (a + b));
+        }
+        points.sort_by(f64::total_cmp);
+
+        points
+            .windows(2)
+            .map(|w| integrate(|theta| h(self.ln_g(theta)), w[0], w[1]))
+            .sum()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_stable {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::statistics::distributions::Gaussian;
+    use statrs::function::erf::erfc;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_stable_special_cases() {
+        // alpha = 2 is the Gaussian, with variance 2 gamma^2.
+        let stable = Stable::new(2.0, 0.0, FRAC_1_SQRT_2, 1.0);
+        let normal = Gaussian::new(1.0, 1.0);
+        for x in [-1.0, 0.5, 1.0, 2.5] {
+            assert_approx_equal!(stable.pdf(x), normal.pdf(x), 1e-10);
+            assert_approx_equal!(stable.cdf(x), normal.cdf(x), 1e-10);
+        }
+
+        // Cauchy.
+        let cauchy = Stable::new(1.0, 0.0, 2.0, 1.0);
+        assert_approx_equal!(cauchy.pdf(3.0), 1.0 / (4.0 * PI), 1e-12);
+        assert_approx_equal!(cauchy.inv_cdf(0.75), 3.0, 1e-8);
+
+        // Lévy, with density sqrt(c / 2 pi) exp(-c / 2x) / x^(3/2).
+        let (c, mu) = (0.5, 1.0);
+        let levy = Stable::new(0.5, 1.0, c, mu);
+        for x in [1.1, 1.5, 3.0, 20.0] {
+            let y: f64 = x - mu;
+            let pdf = (c / (2.0 * PI)).sqrt() * (-c / (2.0 * y)).exp() / y.powf(1.5);
+            assert_approx_equal!(levy.pdf(x), pdf, 1e-10);
+            assert_approx_equal!(levy.cdf(x), erfc((c / (2.0 * y)).sqrt()), 1e-10);
+        }
+        assert_approx_equal!(levy.cdf(0.5), 0.0, 1e-12);
+        assert_approx_equal!(
+            levy.mgf(-2.0),
+            (-2.0 - (2.0 * c * 2.0_f64).sqrt()).exp(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_stable_general() {
+        // Reference values by Fourier inversion of the characteristic
+        // function: the density at 0.3, and the distribution function at -1.
+        let cases = [
+            (1.5, 0.5, 0.129_735_694_850_279_25, 0.509_168_853_615_785),
+            (0.8, -0.3, 0.047_625_004_404_268_4, 0.731_255_805_304_799_8),
+            (1.0, 0.7, 0.158_547_292_644_559_05, 0.258_757_423_885_894_8),
+        ];
+
+        for (alpha, beta, pdf, cdf) in cases {
+            let stable = Stable::new(alpha, beta, 1.5, -0.5);
+
+            assert_approx_equal!(stable.pdf(0.3), pdf, 1e-10);
+            assert_approx_equal!(stable.cdf(-1.0), cdf, 1e-10);
+
+            // The density integrates to the distribution function.
+            let mass = integrate(|x| stable.pdf(x), -1.0, 2.0);
+            assert_approx_equal!(mass, stable.cdf(2.0) - stable.cdf(-1.0), 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_stable_sample_and_fit() {
+        let stable = Stable::new(1.6, 0.0, 1.0, 0.5);
+        let sample = stable.sample(10_000).unwrap();
+
+        // Compare the empirical and exact distribution functions.
+        for x in [-2.0, 0.0, 0.5, 3.0] {
+            let empirical = sample.iter().filter(|y| **y <= x).count() as f64 / 10_000.0;
+            assert_approx_equal!(empirical, stable.cdf(x), 0.02);
+        }
+
+        let fitted = Stable::fit(&sample[..500]).unwrap();
+        assert_approx_equal!(fitted.alpha, 1.6, 0.3);
+        assert_approx_equal!(fitted.gamma, 1.0, 0.2);
+        assert_approx_equal!(fitted.median(), 0.5, 0.25);
+    }
+}
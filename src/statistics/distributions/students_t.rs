@@ -0,0 +1,275 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::nelder_mead;
+use crate::statistics::distributions::{
+    generalized_hyperbolic::ln_bessel_k, invert_cdf, standardise, Distribution,
+};
+use crate::statistics::DistributionError;
+use num_complex::Complex;
+use statrs::function::{
+    beta::{beta_reg, ln_beta},
+    gamma::{digamma, ln_gamma},
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Student's t distribution: X = mu + sigma T, with T ~ t(nu)
+///
+/// The location-scale form, with `nu` degrees of freedom. Its polynomial
+/// tails make it the simplest heavy tailed model of returns.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::statistics::distributions::*;
+///
+/// let t = StudentsT::new(4.0, 0.0, 1.0);
+///
+/// // Reference values: qt(0.975, 4) and pt(-2, 4) in R.
+/// assert_approx_equal!(t.inv_cdf(0.975), 2.776_445_105_197_793, 1e-8);
+/// assert_approx_equal!(t.cdf(-2.0), 0.058_058_261_758_407_6, 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StudentsT {
+    /// Nu: the degrees of freedom.
+    nu: f64,
+    /// Mu: the location.
+    mu: f64,
+    /// Sigma: the scale.
+    sigma: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StudentsT {
+    /// New instance of a Student's t distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if nu or sigma are not positive.
+    #[must_use]
+    pub fn new(nu: f64, mu: f64, sigma: f64) -> Self {
+        assert!(nu > 0.0 && sigma > 0.0);
+
+        Self { nu, mu, sigma }
+    }
+
+    /// Natural logarithm of the density at `x`.
+    #[must_use]
+    pub fn ln_pdf(&self, x: f64) -> f64 {
+        let nu = self.nu;
+        let z = (x - self.mu) / self.sigma;
+
+        -0.5 * (nu + 1.0) * (z * z / nu).ln_1p()
+            - 0.5 * nu.ln()
+            - ln_beta(0.5 * nu, 0.5)
+            - self.sigma.ln()
+    }
+
+    /// Maximum likelihood fit of the degrees of freedom, location and scale
+    /// to the observations in `data`, by the Nelder-Mead algorithm.
+    ///
+    /// # Errors
+    ///
+    /// - `DistributionError::NotEnoughObservations` if there are fewer than
+    ///   three finite observations, or they are all equal.
+    pub fn fit(data: &[f64]) -> Result<Self, DistributionError> {
+        let (centre, scale, z) = standardise(data, 3)?;
+
+        let model = |p: &[f64]| Self {
+            nu: p[0].exp(),
+            mu: p[1],
+            sigma: p[2].exp(),
+        };
+        let objective = |p: &[f64]| {
+            let model = model(p);
+            let value = -z.iter().map(|x| model.ln_pdf(*x)).sum::<f64>();
+            if value.is_finite() {
+                value
+            } else {
+                f64::INFINITY
+            }
+        };
+
+        let minimizer = nelder_mead(objective, &[5_f64.ln(), 0.0, 0.0]);
+        let fitted = model(&minimizer);
+
+        Ok(Self {
+            nu: fitted.nu,
+            mu: centre + fitted.mu * scale,
+            sigma: fitted.sigma * scale,
+        })
+    }
+}
+
+impl Distribution for StudentsT {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        let nu = self.nu;
+        let y = nu.sqrt() * self.sigma * t.abs();
+        let shift = Complex::new(0.0, self.mu * t).exp();
+
+        if y == 0.0 {
+            return shift;
+        }
+
+        shift
+            * (ln_bessel_k(0.5 * nu, y) + 0.5 * nu * y.ln()
+                - ln_gamma(0.5 * nu)
+                - (0.5 * nu - 1.0) * 2_f64.ln())
+            .exp()
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        self.ln_pdf(x).exp()
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        self.pdf(x)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let nu = self.nu;
+        let z = (x - self.mu) / self.sigma;
+        let tail = 0.5 * beta_reg(0.5 * nu, 0.5, nu / (nu + z * z));
+
+        if z <= 0.0 {
+            tail
+        } else {
+            1.0 - tail
+        }
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        invert_cdf(|x| self.cdf(x), p, self.mu, self.sigma)
+    }
+
+    fn mean(&self) -> f64 {
+        if self.nu > 1.0 {
+            self.mu
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn median(&self) -> f64 {
+        self.mu
+    }
+
+    fn mode(&self) -> f64 {
+        self.mu
+    }
+
+    fn variance(&self) -> f64 {
+        match self.nu {
+            nu if nu > 2.0 => self.sigma * self.sigma * nu / (nu - 2.0),
+            nu if nu > 1.0 => f64::INFINITY,
+            _ => f64::NAN,
+        }
+    }
+
+    fn skewness(&self) -> f64 {
+        if self.nu > 3.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    }
+
+    fn kurtosis(&self) -> f64 {
+        match self.nu {
+            nu if nu > 4.0 => 6.0 / (nu - 4.0),
+            nu if nu > 2.0 => f64::INFINITY,
+            _ => f64::NAN,
+        }
+    }
+
+    fn entropy(&self) -> f64 {
+        let nu = self.nu;
+
+        0.5 * (nu + 1.0) * (digamma(0.5 * (nu + 1.0)) - digamma(0.5 * nu))
+            + 0.5 * nu.ln()
+            + ln_beta(0.5 * nu, 0.5)
+            + self.sigma.ln()
+    }
+
+    /// The moment generating function only exists at zero.
+    fn mgf(&self, t: f64) -> f64 {
+        if t == 0.0 {
+            1.0
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    fn sample(&self, n: usize) -> Result<Vec<f64>, DistributionError> {
+        // IMPORT HERE TO AVOID CLASH WITH
+        // `RustQuant::distributions::Distribution`
+        use rand::thread_rng;
+        use rand_distr::{Distribution, StudentT};
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+        let dist = StudentT::new(self.nu)?;
+
+        let variates = (0..n)
+            .map(|_| self.mu + self.sigma * dist.sample(&mut rng))
+            .collect();
+
+        Ok(variates)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_students_t {
+    use super::*;
+    use crate::assert_approx_equal;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_students_t() {
+        let t = StudentsT::new(4.0, 0.0, 1.0);
+
+        // Reference values: dt(x, 4), pt(x, 4) and qt(p, 4) in R.
+        assert_approx_equal!(t.pdf(0.0), 0.375, 1e-12);
+        assert_approx_equal!(t.pdf(1.5), 0.122_88, 1e-12);
+        assert_approx_equal!(t.cdf(1.5), 0.896, 1e-12);
+        assert_approx_equal!(t.cdf(0.0), 0.5, 1e-12);
+        assert_approx_equal!(t.inv_cdf(0.05), -2.131_846_786_326_649, 1e-8);
+        assert_approx_equal!(t.variance(), 2.0, 1e-12);
+        assert!(t.kurtosis().is_infinite());
+
+        // One degree of freedom is the Cauchy distribution.
+        let cauchy = StudentsT::new(1.0, 1.0, 2.0);
+        assert_approx_equal!(cauchy.pdf(3.0), 1.0 / (4.0 * PI), 1e-12);
+        assert_approx_equal!(cauchy.cf(0.7).re * (1.4_f64).exp(), 0.7_f64.cos(), 1e-10);
+        assert_approx_equal!(cauchy.entropy(), (8.0 * PI).ln(), 1e-10);
+    }
+
+    #[test]
+    fn test_students_t_fit() {
+        let t = StudentsT::new(5.0, 0.01, 0.02);
+        let sample = t.sample(5_000).unwrap();
+        let fitted = StudentsT::fit(&sample).unwrap();
+
+        assert_approx_equal!(fitted.nu, 5.0, 1.0);
+        assert_approx_equal!(fitted.mu, 0.01, 1e-3);
+        assert_approx_equal!(fitted.sigma, 0.02, 1.5e-3);
+
+        assert!(StudentsT::fit(&[1.0, f64::NAN]).is_err());
+    }
+}
@@ -0,0 +1,326 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Extreme value theory: the upper tail of a sample (e.g. of losses), beyond
+//! the largest observations.
+//!
+//! - The Hill estimator of the tail index $\xi = 1 / \alpha$ of a Pareto
+//!   type tail, $P(X > x) \sim x^{-\alpha}$, from the $k$ largest
+//!   observations.
+//! - The peaks over threshold method: the excesses over a high threshold
+//!   $u$ are approximately generalized Pareto (Pickands–Balkema–de Haan),
+//!   so that, with $\zeta_u$ the proportion of exceedances,
+//!   $P(X > x) \approx \zeta_u \left(1 + \xi (x - u) / \sigma\right)^{-1/\xi}$
+//!   for $x \ge u$. Inverted, this extrapolates the quantile (value at
+//!   risk) and the expected shortfall at levels beyond the sample
+//!   (McNeil and Frey, 2000).
+//!
+//! The mean excess function, linear in the threshold above which the
+//! excesses are generalized Pareto, guides the choice of the threshold.
+
+use crate::statistics::distributions::{Distribution, GeneralizedPareto};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Extreme value theory error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ExtremeValueError {
+    /// A parameter (e.g. a level, or the number of order statistics) is out
+    /// of range.
+    #[error("Extreme value parameter out of range")]
+    InvalidParameter,
+
+    /// There are too few observations (in the tail) for the estimate.
+    #[error("Not enough observations in the tail")]
+    NotEnoughObservations,
+}
+
+/// Peaks over threshold model of the upper tail of a sample: the
+/// exceedance probability of the threshold, with a generalized Pareto
+/// distribution of the exceedances.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// use RustQuant::statistics::*;
+///
+/// // Daily losses with a Pareto tail: P(X > x) = x^(-3) for x >= 1.
+/// let n: u32 = 20_000;
+/// let losses: Vec<f64> = (1..=n)
+///     .map(|k| (1.0 - f64::from(k) / f64::from(n + 1)).powf(-1.0 / 3.0))
+///     .collect();
+///
+/// // Fitted to the 5% largest losses.
+/// let pot = PeaksOverThreshold::fit(&losses, 20_f64.cbrt()).unwrap();
+///
+/// // The 99.99% quantile is beyond the sample: 10000^(1/3).
+/// let var = pot.quantile(0.9999).unwrap();
+/// assert_approx_equal!(var, 10_000_f64.cbrt(), 1.0);
+/// assert!(pot.expected_shortfall(0.9999).unwrap() > var);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PeaksOverThreshold {
+    /// The threshold ($u$).
+    threshold: f64,
+    /// Proportion of the observations above the threshold ($\zeta_u$).
+    exceedance_probability: f64,
+    /// Distribution of the observations above the threshold.
+    excesses: GeneralizedPareto,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PeaksOverThreshold {
+    /// Peaks over threshold model of the finite observations in `data`: the
+    /// generalized Pareto distribution fitted by maximum likelihood to the
+    /// exceedances of `threshold` (see [`GeneralizedPareto::fit`]).
+    ///
+    /// # Errors
+    ///
+    /// - `ExtremeValueError::NotEnoughObservations` if fewer than three
+    ///   observations exceed the threshold.
+    pub fn fit(data: &[f64], threshold: f64) -> Result<Self, ExtremeValueError> {
+        let finite = data.iter().filter(|x| x.is_finite()).count();
+        let exceedances = data
+            .iter()
+            .filter(|x| x.is_finite() && **x > threshold)
+            .count();
+
+        let excesses = GeneralizedPareto::fit(data, threshold)
+            .map_err(|_| ExtremeValueError::NotEnoughObservations)?;
+
+        Ok(Self {
+            threshold,
+            exceedance_probability: exceedances as f64 / finite as f64,
+            excesses,
+        })
+    }
+
+    /// The threshold.
+    #[must_use]
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Proportion of the observations above the threshold.
+    #[must_use]
+    pub fn exceedance_probability(&self) -> f64 {
+        self.exceedance_probability
+    }
+
+    /// Generalized Pareto distribution of the observations above the
+    /// threshold.
+    #[must_use]
+    pub fn excesses(&self) -> &GeneralizedPareto {
+        &self.excesses
+    }
+
+    /// Tail probability $P(X > x)$, for `x` at or above the threshold.
+    ///
+    /// # Errors
+    ///
+    /// - `ExtremeValueError::InvalidParameter` if `x` is below the
+    ///   threshold.
+    pub fn tail_probability(&self, x: f64) -> Result<f64, ExtremeValueError> {
+        if x.is_nan() || x < self.threshold {
+            return Err(ExtremeValueError::InvalidParameter);
+        }
+
+        Ok(self.exceedance_probability * (1.0 - self.excesses.cdf(x)))
+    }
+
+    /// Quantile (value at risk) at the level `p`:
+    /// $u + \frac{\sigma}{\xi} \left( \left( \frac{1 - p}{\zeta_u} \right)^{-\xi} - 1 \right)$.
+    ///
+    /// # Errors
+    ///
+    /// - `ExtremeValueError::InvalidParameter` if `p` is not in
+    ///   `[1 - exceedance_probability, 1)`, where the tail is modelled.
+    pub fn quantile(&self, p: f64) -> Result<f64, ExtremeValueError> {
+        if !(p >= 1.0 - self.exceedance_probability && p < 1.0) {
+            return Err(ExtremeValueError::InvalidParameter);
+        }
+
+        let conditional = 1.0 - (1.0 - p) / self.exceedance_probability;
+
+        Ok(self.excesses.inv_cdf(conditional.max(0.0)))
+    }
+
+    /// Expected shortfall at the level `p`, the mean of the losses beyond
+    /// the quantile: $\frac{q_p + \sigma - \xi u}{1 - \xi}$ (infinite for
+    /// $\xi \ge 1$).
+    ///
+    /// # Errors
+    ///
+    /// - `ExtremeValueError::InvalidParameter` if `p` is not in
+    ///   `[1 - exceedance_probability, 1)`, where the tail is modelled.
+    pub fn expected_shortfall(&self, p: f64) -> Result<f64, ExtremeValueError> {
+        let quantile = self.quantile(p)?;
+        let (sigma, xi) = (self.excesses.scale(), self.excesses.shape());
+
+        if xi < 1.0 {
+            Ok((quantile + sigma - xi * self.threshold) / (1.0 - xi))
+        } else {
+            Ok(f64::INFINITY)
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hill estimator of the tail index $\xi = 1 / \alpha$, from the `k`
+/// largest of the finite observations in `data`:
+/// $\frac{1}{k} \sum_{i=1}^k \ln X_{(i)} - \ln X_{(k+1)}$, with $X_{(i)}$
+/// the $i$-th largest.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// use RustQuant::statistics::hill_estimator;
+///
+/// let data = [1.0, 2.0, 4.0, 8.0];
+///
+/// // (ln 8 + ln 4) / 2 - ln 2.
+/// assert_approx_equal!(hill_estimator(&data, 2).unwrap(), 1.5 * 2_f64.ln(), 1e-12);
+/// ```
+///
+/// # Errors
+///
+/// - `ExtremeValueError::InvalidParameter` if `k` is zero.
+/// - `ExtremeValueError::NotEnoughObservations` if there are not more than
+///   `k` finite observations, or the `k + 1`-th largest is not positive.
+pub fn hill_estimator(data: &[f64], k: usize) -> Result<f64, ExtremeValueError> {
+    if k == 0 {
+        return Err(ExtremeValueError::InvalidParameter);
+    }
+
+    let mut observations: Vec<f64> = data.iter().copied().filter(|x| x.is_finite()).collect();
+    if observations.len() <= k {
+        return Err(ExtremeValueError::NotEnoughObservations);
+    }
+    observations.sort_by(|a, b| b.total_cmp(a));
+
+    let reference = observations[k];
+    if reference <= 0.0 {
+        return Err(ExtremeValueError::NotEnoughObservations);
+    }
+
+    Ok(observations[..k].iter().map(|x| x.ln()).sum::<f64>() / k as f64 - reference.ln())
+}
+
+/// Mean excess $E[X - u \mid X > u]$ of the finite observations in `data`
+/// over the threshold `u`. Above a threshold where the excesses are
+/// generalized Pareto, it is linear in `u`, with slope $\xi / (1 - \xi)$.
+///
+/// # Errors
+///
+/// - `ExtremeValueError::NotEnoughObservations` if no observation exceeds
+///   the threshold.
+pub fn mean_excess(data: &[f64], u: f64) -> Result<f64, ExtremeValueError> {
+    let excesses: Vec<f64> = data
+        .iter()
+        .filter(|x| x.is_finite() && **x > u)
+        .map(|x| x - u)
+        .collect();
+    if excesses.is_empty() {
+        return Err(ExtremeValueError::NotEnoughObservations);
+    }
+
+    Ok(excesses.iter().sum::<f64>() / excesses.len() as f64)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_extreme_value {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    // Quantiles of a Pareto distribution, P(X > x) = x^(-alpha) for x >= 1,
+    // at the plotting positions i / (n + 1).
+    fn pareto(alpha: f64, n: u32) -> Vec<f64> {
+        (1..=n)
+            .map(|i| (1.0 - f64::from(i) / f64::from(n + 1)).powf(-alpha.recip()))
+            .collect()
+    }
+
+    #[test]
+    fn test_hill_estimator() {
+        let data = pareto(2.0, 10_000);
+
+        assert_approx_equal!(hill_estimator(&data, 500).unwrap(), 0.5, 0.01);
+        assert_approx_equal!(hill_estimator(&data, 100).unwrap(), 0.5, 0.02);
+
+        assert_eq!(
+            hill_estimator(&data, 0),
+            Err(ExtremeValueError::InvalidParameter)
+        );
+        assert_eq!(
+            hill_estimator(&[1.0, f64::NAN], 1),
+            Err(ExtremeValueError::NotEnoughObservations)
+        );
+        assert_eq!(
+            hill_estimator(&[-1.0, 2.0], 1),
+            Err(ExtremeValueError::NotEnoughObservations)
+        );
+    }
+
+    #[test]
+    fn test_peaks_over_threshold() {
+        // Excesses of a Pareto tail over u are GPD with xi = 1 / alpha and
+        // sigma = u / alpha.
+        let alpha = 3.0;
+        let u = 20_f64.cbrt();
+        let pot = PeaksOverThreshold::fit(&pareto(alpha, 20_000), u).unwrap();
+
+        assert_approx_equal!(pot.exceedance_probability(), 0.05, 1e-3);
+        assert_approx_equal!(pot.excesses().shape(), 1.0 / alpha, 0.03);
+        assert_approx_equal!(pot.excesses().scale(), u / alpha, 0.05);
+
+        // Value at risk 1000^(1/3) and expected shortfall alpha / (alpha - 1)
+        // times it, at the 99.9% level.
+        let var = pot.quantile(0.999).unwrap();
+        assert_approx_equal!(var, 10.0, 0.2);
+        assert_approx_equal!(pot.expected_shortfall(0.999).unwrap(), 15.0, 0.6);
+        assert_approx_equal!(pot.tail_probability(var).unwrap(), 1e-3, 1e-12);
+        assert_approx_equal!(pot.quantile(0.95).unwrap(), u, 1e-3);
+
+        assert_eq!(pot.quantile(0.9), Err(ExtremeValueError::InvalidParameter));
+        assert_eq!(pot.quantile(1.0), Err(ExtremeValueError::InvalidParameter));
+        assert_eq!(
+            pot.tail_probability(1.0),
+            Err(ExtremeValueError::InvalidParameter)
+        );
+        assert_eq!(
+            PeaksOverThreshold::fit(&[1.0, 2.0, 3.0], 2.5).unwrap_err(),
+            ExtremeValueError::NotEnoughObservations
+        );
+    }
+
+    #[test]
+    fn test_mean_excess() {
+        // Linear mean excess, (sigma + xi u) / (1 - xi), of a Pareto tail:
+        // u / (alpha - 1).
+        let data = pareto(3.0, 100_000);
+        for u in [2.0, 4.0] {
+            assert_approx_equal!(mean_excess(&data, u).unwrap(), 0.5 * u, 0.05 * u);
+        }
+
+        assert_eq!(
+            mean_excess(&data, 1e6),
+            Err(ExtremeValueError::NotEnoughObservations)
+        );
+    }
+}
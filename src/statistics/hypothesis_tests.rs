@@ -0,0 +1,554 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Statistical hypothesis tests of the assumptions of return models:
+//!
+//! - Jarque-Bera: normality, from the sample skewness and kurtosis.
+//! - Ljung-Box: no autocorrelation up to a given lag (e.g. of residuals,
+//!   or of squared returns for volatility clustering).
+//! - Augmented Dickey-Fuller: a unit root (non-stationarity), e.g. of
+//!   log prices or of a spread.
+//! - Kolmogorov-Smirnov: the observations are drawn from a given
+//!   distribution, or two samples from the same distribution.
+//!
+//! Each returns the test statistic with its p-value: the probability,
+//! under the null hypothesis, of a statistic at least as extreme. Small
+//! p-values reject the null hypothesis.
+
+use crate::statistics::distributions::Distribution;
+use nalgebra::{DMatrix, DVector};
+use statrs::function::erf::erfc;
+use statrs::function::gamma::gamma_ur;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hypothesis test error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum HypothesisTestError {
+    /// A parameter of the test (e.g. the number of lags) is out of range.
+    #[error("Hypothesis test parameter out of range")]
+    InvalidParameter,
+
+    /// There are too few observations for the test.
+    #[error("Not enough observations for the test")]
+    NotEnoughObservations,
+
+    /// The observations are degenerate, e.g. constant.
+    #[error("Degenerate observations (e.g. constant)")]
+    Degenerate,
+}
+
+/// Result of a hypothesis test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    /// The test statistic.
+    pub statistic: f64,
+
+    /// The p-value of the statistic under the null hypothesis.
+    pub p_value: f64,
+}
+
+/// Deterministic terms of the augmented Dickey-Fuller regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdfRegression {
+    /// No constant or trend: a zero mean alternative.
+    None,
+
+    /// A constant: a stationary alternative with non-zero mean.
+    Constant,
+
+    /// A constant and a linear trend: a trend stationary alternative.
+    ConstantTrend,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TestResult {
+    /// Whether the null hypothesis is rejected at the significance level
+    /// `alpha` (e.g. 0.05), i.e. the p-value is below it.
+    #[must_use]
+    pub fn rejects(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+impl AdfRegression {
+    // MacKinnon (1994) approximate asymptotic p-value of the Dickey-Fuller
+    // statistic, with the coefficients of Table 3 for a single series (as
+    // in `statsmodels`): the p-value is the normal distribution function of
+    // a polynomial in the statistic, with separate fits for small and large
+    // p-values either side of `star`.
+    fn p_value(self, statistic: f64) -> f64 {
+        let (min, star, max, small, large) = match self {
+            Self::None => (
+                -19.04,
+                -1.04,
+                f64::INFINITY,
+                [0.6344, 1.2378, 3.2496e-2],
+                [0.4797, 9.3557e-1, -6.999e-2, 3.3066e-2],
+            ),
+            Self::Constant => (
+                -18.83,
+                -1.61,
+                2.74,
+                [2.1659, 1.4412, 3.8269e-2],
+                [1.7339, 9.3202e-1, -1.2745e-1, -1.0368e-2],
+            ),
+            Self::ConstantTrend => (
+                -16.18,
+                -2.89,
+                0.7,
+                [3.2512, 1.6047, 4.9588e-2],
+                [2.5261, 6.1654e-1, -3.7956e-1, -6.0285e-2],
+            ),
+        };
+
+        if statistic > max {
+            return 1.0;
+        }
+        if statistic < min {
+            return 0.0;
+        }
+
+        let polynomial = if statistic <= star {
+            small.iter().rev().fold(0.0, |sum, c| sum * statistic + c)
+        } else {
+            large.iter().rev().fold(0.0, |sum, c| sum * statistic + c)
+        };
+
+        normal_cdf(polynomial)
+    }
+
+    fn deterministic_terms(self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Constant => 1,
+            Self::ConstantTrend => 2,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Jarque-Bera test of normality:
+/// JB = n / 6 (S^2 + K^2 / 4), with the sample skewness S and excess
+/// kurtosis K, which is asymptotically chi-squared with two degrees of
+/// freedom for normal data.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// // Exponential scores: strongly skewed.
+/// let data: Vec<f64> = (0..500).map(|k| -(1.0 - (k as f64 + 0.5) / 500.0).ln()).collect();
+///
+/// assert!(jarque_bera(&data).unwrap().rejects(0.01));
+/// ```
+///
+/// # Errors
+///
+/// - `HypothesisTestError::NotEnoughObservations` for fewer than three
+///   observations.
+/// - `HypothesisTestError::Degenerate` if the observations are constant.
+pub fn jarque_bera(data: &[f64]) -> Result<TestResult, HypothesisTestError> {
+    if data.len() < 3 {
+        return Err(HypothesisTestError::NotEnoughObservations);
+    }
+
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let moment = |k: i32| data.iter().map(|x| (x - mean).powi(k)).sum::<f64>() / n;
+
+    let variance = moment(2);
+    if variance <= 0.0 {
+        return Err(HypothesisTestError::Degenerate);
+    }
+
+    let skewness = moment(3) / variance.powf(1.5);
+    let kurtosis = moment(4) / variance.powi(2) - 3.0;
+    let statistic = n / 6.0 * (skewness.powi(2) + 0.25 * kurtosis.powi(2));
+
+    Ok(TestResult {
+        statistic,
+        p_value: (-0.5 * statistic).exp(),
+    })
+}
+
+/// Ljung-Box test of no autocorrelation up to lag `lags`:
+/// Q = n (n + 2) sum_k rho_k^2 / (n - k), with the sample autocorrelations
+/// rho_k, which is asymptotically chi-squared with `lags - fitted`
+/// degrees of freedom. `fitted` is the number of ARMA parameters fitted,
+/// when testing the residuals of a model (zero for raw returns).
+///
+/// # Errors
+///
+/// - `HypothesisTestError::InvalidParameter` unless
+///   `fitted < lags < data.len()`.
+/// - `HypothesisTestError::Degenerate` if the observations are constant.
+pub fn ljung_box(
+    data: &[f64],
+    lags: usize,
+    fitted: usize,
+) -> Result<TestResult, HypothesisTestError> {
+    if lags >= data.len() || fitted >= lags {
+        return Err(HypothesisTestError::InvalidParameter);
+    }
+
+    let n = data.len() as f64;
+    let mean = data.iter().sum::<f64>() / n;
+    let deviations: Vec<f64> = data.iter().map(|x| x - mean).collect();
+
+    let denominator = deviations.iter().map(|x| x * x).sum::<f64>();
+    if denominator <= 0.0 {
+        return Err(HypothesisTestError::Degenerate);
+    }
+
+    let statistic = n
+        * (n + 2.0)
+        * (1..=lags)
+            .map(|k| {
+                let rho = deviations
+                    .iter()
+                    .zip(&deviations[k..])
+                    .map(|(x, y)| x * y)
+                    .sum::<f64>()
+                    / denominator;
+                rho * rho / (n - k as f64)
+            })
+            .sum::<f64>();
+
+    Ok(TestResult {
+        statistic,
+        p_value: chi_squared_survival(statistic, (lags - fitted) as f64),
+    })
+}
+
+/// Augmented Dickey-Fuller test of a unit root in `data`.
+///
+/// The differences are regressed on the lagged level, `lags` lagged
+/// differences and the deterministic terms of `regression`:
+/// dy_t = [c + d t] + gamma y_{t-1} + sum_i phi_i dy_{t-i} + e_t,
+/// and the statistic is the t-ratio of gamma, whose p-value is from the
+/// MacKinnon (1994) approximation of its (non-standard) asymptotic
+/// distribution. The null hypothesis is a unit root (gamma = 0): small
+/// p-values indicate stationarity.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// // A mean reverting AR(1) series.
+/// let mut x = vec![0.0];
+/// for t in 1..500 {
+///     x.push(0.5 * x[t - 1] + (t as f64 * 1.7).sin());
+/// }
+///
+/// let adf = augmented_dickey_fuller(&x, 1, AdfRegression::Constant).unwrap();
+/// assert!(adf.rejects(0.01));
+/// ```
+///
+/// # Errors
+///
+/// - `HypothesisTestError::NotEnoughObservations` if there are too few
+///   observations for the regression.
+/// - `HypothesisTestError::Degenerate` if the regressors are collinear,
+///   e.g. the observations are constant.
+pub fn augmented_dickey_fuller(
+    data: &[f64],
+    lags: usize,
+    regression: AdfRegression,
+) -> Result<TestResult, HypothesisTestError> {
+    let columns = 1 + lags + regression.deterministic_terms();
+    if data.len() < lags + columns + 3 {
+        return Err(HypothesisTestError::NotEnoughObservations);
+    }
+
+    let differences: Vec<f64> = data.windows(2).map(|w| w[1] - w[0]).collect();
+    let rows = differences.len() - lags;
+
+    // Row r is the difference at time t = r + lags.
+    let design = DMatrix::from_fn(rows, columns, |r, c| {
+        let t = r + lags;
+        match c {
+            0 => data[t],
+            c if c <= lags => differences[t - c],
+            c if c == lags + 1 => 1.0,
+            _ => (r + 1) as f64,
+        }
+    });
+    let response = DVector::from_fn(rows, |r, _| differences[r + lags]);
+
+    let inverse = (design.transpose() * &design)
+        .try_inverse()
+        .ok_or(HypothesisTestError::Degenerate)?;
+    let coefficients = &inverse * design.transpose() * &response;
+    let residuals = response - &design * &coefficients;
+    let variance = residuals.norm_squared() / (rows - columns) as f64;
+    if variance <= 0.0 {
+        return Err(HypothesisTestError::Degenerate);
+    }
+
+    let statistic = coefficients[0] / (variance * inverse[(0, 0)]).sqrt();
+
+    Ok(TestResult {
+        statistic,
+        p_value: regression.p_value(statistic),
+    })
+}
+
+/// One sample Kolmogorov-Smirnov test that `data` is drawn from the
+/// (continuous) `distribution`: the statistic is the largest distance
+/// between the empirical and the hypothesised distribution functions,
+/// with the p-value from the asymptotic Kolmogorov distribution (with
+/// Stephens' small sample correction).
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// let standard = Gaussian::new(0.0, 1.0);
+/// let data = standard.sample(1_000).unwrap();
+///
+/// let ks = kolmogorov_smirnov(&data, &standard).unwrap();
+/// assert!(ks.statistic < 0.1);
+/// ```
+///
+/// # Errors
+///
+/// - `HypothesisTestError::NotEnoughObservations` if `data` is empty.
+pub fn kolmogorov_smirnov<D: Distribution + ?Sized>(
+    data: &[f64],
+    distribution: &D,
+) -> Result<TestResult, HypothesisTestError> {
+    if data.is_empty() {
+        return Err(HypothesisTestError::NotEnoughObservations);
+    }
+
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+
+    let statistic = sorted
+        .iter()
+        .enumerate()
+        .map(|(k, x)| {
+            let cdf = distribution.cdf(*x);
+            ((k + 1) as f64 / n - cdf).max(cdf - k as f64 / n)
+        })
+        .fold(0.0, f64::max);
+
+    Ok(TestResult {
+        statistic,
+        p_value: kolmogorov_survival(statistic, n),
+    })
+}
+
+/// Two sample Kolmogorov-Smirnov test that `a` and `b` are drawn from the
+/// same (continuous) distribution: the statistic is the largest distance
+/// between their empirical distribution functions, with the asymptotic
+/// p-value for the effective sample size n m / (n + m).
+///
+/// # Errors
+///
+/// - `HypothesisTestError::NotEnoughObservations` if either sample is
+///   empty.
+pub fn kolmogorov_smirnov_two_sample(
+    a: &[f64],
+    b: &[f64],
+) -> Result<TestResult, HypothesisTestError> {
+    if a.is_empty() || b.is_empty() {
+        return Err(HypothesisTestError::NotEnoughObservations);
+    }
+
+    let sort = |data: &[f64]| {
+        let mut sorted = data.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        sorted
+    };
+    let (a, b) = (sort(a), sort(b));
+    let (n, m) = (a.len() as f64, b.len() as f64);
+
+    // Step through the pooled observations, taking ties together.
+    let (mut i, mut j) = (0, 0);
+    let mut statistic: f64 = 0.0;
+    while i < a.len() && j < b.len() {
+        let x = a[i].min(b[j]);
+        while i < a.len() && a[i] <= x {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= x {
+            j += 1;
+        }
+        statistic = statistic.max((i as f64 / n - j as f64 / m).abs());
+    }
+
+    Ok(TestResult {
+        statistic,
+        p_value: kolmogorov_survival(statistic, n * m / (n + m)),
+    })
+}
+
+// Standard normal distribution function.
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x * FRAC_1_SQRT_2)
+}
+
+// P(X > x) for X chi-squared with `dof` degrees of freedom.
+fn chi_squared_survival(x: f64, dof: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else {
+        gamma_ur(0.5 * dof, 0.5 * x)
+    }
+}
+
+// P(sqrt(n) D > d sqrt(n)) for the Kolmogorov distribution, at
+// (sqrt(n) + 0.12 + 0.11 / sqrt(n)) d (Stephens, 1970). Each of the two
+// series converges quickly on its side of 1.18.
+fn kolmogorov_survival(d: f64, n: f64) -> f64 {
+    let root = n.sqrt();
+    let lambda = (root + 0.12 + 0.11 / root) * d;
+
+    let survival = if lambda <= 0.0 {
+        1.0
+    } else if lambda < 1.18 {
+        let y = (-PI * PI / (8.0 * lambda * lambda)).exp();
+        1.0 - (2.0 * PI).sqrt() / lambda * (y + y.powi(9) + y.powi(25) + y.powi(49))
+    } else {
+        let x = (-2.0 * lambda * lambda).exp();
+        2.0 * (x - x.powi(4) + x.powi(9))
+    };
+
+    survival.clamp(0.0, 1.0)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hypothesis_tests {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::statistics::distributions::{DistributionClass, Gaussian, Uniform};
+
+    // Deterministic, uncorrelated noise, uniform on [-0.5, 0.5).
+    fn noise(n: u32) -> Vec<f64> {
+        (1..=n)
+            .map(|t| {
+                let x = (f64::from(t) * 12.9898).sin() * 43_758.545_3;
+                x - x.floor() - 0.5
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_jarque_bera() {
+        let result = jarque_bera(&[1.0, 2.0, 3.0, 4.0, 10.0]).unwrap();
+
+        assert_approx_equal!(result.statistic, 1.089_363_333_333_333, 1e-12);
+        assert_approx_equal!(result.p_value, 0.580_026_395_690_116, 1e-12);
+
+        // Normal scores.
+        let standard = Gaussian::new(0.0, 1.0);
+        let scores: Vec<f64> = (0..1_000_u32)
+            .map(|k| standard.inv_cdf((f64::from(k) + 0.5) / 1_000.0))
+            .collect();
+        assert!(!jarque_bera(&scores).unwrap().rejects(0.5));
+
+        assert_eq!(
+            jarque_bera(&[1.0, 1.0, 1.0]),
+            Err(HypothesisTestError::Degenerate)
+        );
+        assert_eq!(
+            jarque_bera(&[1.0, 2.0]),
+            Err(HypothesisTestError::NotEnoughObservations)
+        );
+    }
+
+    #[test]
+    fn test_ljung_box() {
+        let data = noise(200);
+        let result = ljung_box(&data, 10, 0).unwrap();
+
+        // Reference values, computed independently.
+        assert_approx_equal!(result.statistic, 5.658_058_977_914_253, 1e-9);
+        assert_approx_equal!(result.p_value, 0.843_127_129_178_657_6, 1e-9);
+        assert!(!result.rejects(0.1));
+
+        // Strong autocorrelation.
+        let mut ar = vec![0.0];
+        for (t, e) in noise(300).iter().enumerate() {
+            ar.push(0.9 * ar[t] + e);
+        }
+        assert!(ljung_box(&ar, 5, 0).unwrap().rejects(1e-6));
+
+        assert!(ljung_box(&data, 10, 10).is_err());
+        assert!(ljung_box(&data, 200, 0).is_err());
+    }
+
+    #[test]
+    fn test_augmented_dickey_fuller() {
+        // The critical values of MacKinnon (2010), at large sample sizes.
+        let cases = [
+            (AdfRegression::None, -2.565_74, -1.941_00),
+            (AdfRegression::Constant, -3.430_35, -2.861_54),
+            (AdfRegression::ConstantTrend, -3.958_77, -3.410_49),
+        ];
+        for (regression, one, five) in cases {
+            assert_approx_equal!(regression.p_value(one), 0.01, 1e-3);
+            assert_approx_equal!(regression.p_value(five), 0.05, 1e-3);
+        }
+
+        let noise = noise(300);
+
+        // A random walk has a unit root, and its increments do not.
+        let mut walk = vec![0.0];
+        for (t, e) in noise.iter().enumerate() {
+            walk.push(walk[t] + e);
+        }
+        let result = augmented_dickey_fuller(&walk, 2, AdfRegression::Constant).unwrap();
+        assert_approx_equal!(result.statistic, -1.314_249_887_223_702, 1e-8);
+        assert!(!result.rejects(0.1));
+
+        let result = augmented_dickey_fuller(&noise, 2, AdfRegression::ConstantTrend).unwrap();
+        assert!(result.rejects(0.01));
+
+        assert!(augmented_dickey_fuller(&[1.0; 20], 1, AdfRegression::Constant).is_err());
+        assert!(augmented_dickey_fuller(&noise[..5], 1, AdfRegression::Constant).is_err());
+    }
+
+    #[test]
+    fn test_kolmogorov_smirnov() {
+        let uniform = Uniform::new(0.0, 1.0, DistributionClass::Continuous);
+        let data: Vec<f64> = (0..100_u32).map(|k| (f64::from(k) + 0.5) / 100.0).collect();
+
+        let result = kolmogorov_smirnov(&data, &uniform).unwrap();
+        assert_approx_equal!(result.statistic, 0.005, 1e-12);
+        assert_approx_equal!(result.p_value, 1.0, 1e-12);
+
+        // The Kolmogorov distribution, P(K > 1) and P(K > 1.5), for large n.
+        assert_approx_equal!(kolmogorov_survival(1e-6, 1e12), 0.269_999_671_677, 1e-6);
+        assert_approx_equal!(kolmogorov_survival(1.5e-6, 1e12), 0.022_217_962_616, 1e-6);
+
+        let shifted: Vec<f64> = data.iter().map(|x| x + 0.305).collect();
+        let result = kolmogorov_smirnov_two_sample(&data, &shifted).unwrap();
+        assert_approx_equal!(result.statistic, 0.31, 1e-12);
+        assert!(result.rejects(0.01));
+
+        let result = kolmogorov_smirnov_two_sample(&data, &data).unwrap();
+        assert_approx_equal!(result.statistic, 0.0, 1e-12);
+
+        assert!(kolmogorov_smirnov(&[], &uniform).is_err());
+    }
+}
@@ -23,6 +23,59 @@
 //! - [x] Chi-Squared
 //! - [x] Gamma
 //! - [x] Exponential
+//! - [x] Student's t
+//! - [x] Skew-normal
+//! - [x] Generalized hyperbolic (incl. NIG)
+//! - [x] Stable
+//! - [x] Generalized Pareto
+//! - [x] Empirical (ECDF, interpolated quantiles)
+//! - [x] Kernel density estimate (Silverman, cross-validation bandwidths)
+//!
+//! Multivariate distributions, with marginals and conditionals:
+//!
+//! - [x] Normal
+//! - [x] Student's t
+//!
+//! Characteristic functions, for all distributions and the Lévy models,
+//! inverted to densities and distribution functions by the Gil-Pelaez
+//! formulae or the COS method.
+//!
+//! Copulas, for the dependence of random variables:
+//!
+//! - [x] Gaussian
+//! - [x] Student's t
+//! - [x] Clayton
+//! - [x] Gumbel
+//! - [x] Frank
+//!
+//! Volatility models of historical returns:
+//!
+//! - [x] EWMA (RiskMetrics)
+//! - [x] GARCH(1,1)
+//!
+//! Covariance matrix estimators:
+//!
+//! - [x] Ledoit-Wolf shrinkage
+//! - [x] Exponentially weighted (RiskMetrics)
+//! - [x] Nearest positive definite (Higham)
+//!
+//! Extreme value theory, for tail risk beyond the sample:
+//!
+//! - [x] Hill estimator
+//! - [x] Peaks over threshold (GPD quantiles and expected shortfall)
+//!
+//! Hypothesis tests of model assumptions:
+//!
+//! - [x] Jarque-Bera (normality)
+//! - [x] Ljung-Box (autocorrelation)
+//! - [x] Augmented Dickey-Fuller (unit root)
+//! - [x] Kolmogorov-Smirnov (goodness of fit)
+//!
+//! Bootstrap confidence intervals of any statistic:
+//!
+//! - [x] iid
+//! - [x] Moving block
+//! - [x] Stationary
 
 /// Base trait for statistics of a collection of data.
 pub mod statistic;
@@ -31,8 +84,9 @@ pub use statistic::*;
 /// Random variable distributions (PDFs, CDFs, CFs, etc).
 pub mod distributions {
     pub use crate::statistics::distributions::{
-        bernoulli::*, binomial::*, chi_squared::*, distribution::*, exponential::*, gamma::*,
-        gaussian::*, poisson::*, uniform::*,
+        bernoulli::*, binomial::*, chi_squared::*, distribution::*, empirical::*, exponential::*,
+        gamma::*, gaussian::*, generalized_hyperbolic::*, generalized_pareto::*, kernel_density::*,
+        poisson::*, skew_normal::*, stable::*, students_t::*, uniform::*,
     };
 
     /// Bernoulli distribution.
@@ -47,6 +101,9 @@ pub mod distributions {
     /// Base trait for all distributions.
     pub mod distribution;
 
+    /// Empirical distribution of a sample.
+    pub mod empirical;
+
     /// Exponential distribution.
     pub mod exponential;
 
@@ -56,14 +113,60 @@ pub mod distributions {
     /// Gaussian (normal) distribution.
     pub mod gaussian;
 
+    /// Generalized hyperbolic distribution.
+    pub mod generalized_hyperbolic;
+
+    /// Generalized Pareto distribution.
+    pub mod generalized_pareto;
+
+    /// Kernel density estimation.
+    pub mod kernel_density;
+
     /// Poisson distribution.
     pub mod poisson;
 
+    /// Skew-normal distribution.
+    pub mod skew_normal;
+
+    /// Stable (Lévy alpha-stable) distribution.
+    pub mod stable;
+
+    /// Student's t distribution.
+    pub mod students_t;
+
     /// Uniform distribution.
     pub mod uniform;
 }
 pub use distributions::*;
 
-// /// Copula implementations.
-// pub mod copulas;
-// pub use copulas::*;
+/// Bootstrap resampling (iid, moving block and stationary) and confidence intervals.
+pub mod bootstrap;
+pub use bootstrap::*;
+
+/// Characteristic functions and their inversion (Gil-Pelaez, COS).
+pub mod characteristic_function;
+pub use characteristic_function::*;
+
+/// Copula implementations.
+pub mod copulas;
+pub use copulas::*;
+
+/// Covariance estimators: Ledoit-Wolf shrinkage, EWMA and repair.
+pub mod covariance;
+pub use covariance::*;
+
+/// Extreme value theory: Hill estimator and peaks over threshold.
+pub mod extreme_value;
+pub use extreme_value::*;
+
+/// Hypothesis tests: Jarque-Bera, Ljung-Box, ADF and Kolmogorov-Smirnov.
+pub mod hypothesis_tests;
+pub use hypothesis_tests::*;
+
+/// Multivariate distributions: normal and Student's t.
+pub mod multivariate;
+pub use multivariate::*;
+
+/// Volatility models: EWMA and GARCH(1,1).
+pub mod volatility;
+pub use volatility::*;
@@ -0,0 +1,734 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Multivariate distributions: the joint law of several random variables,
+//! e.g. the log-returns of the assets of a basket.
+//!
+//! - Normal, $X \sim N(\mu, \Sigma)$.
+//! - Student's t, $X = \mu + Z / \sqrt{W / \nu}$ with $Z \sim N(0, \Sigma)$
+//!   and $W \sim \chi^2_\nu$: heavier tails, and joint extremes.
+//!
+//! Both are sampled with the Cholesky factor $L$ of $\Sigma$, as
+//! $\mu + L z$ for independent standard normals $z$, and are closed under
+//! marginalisation and conditioning: the variables `indices` are
+//! distributed as the sub-vector $\mu_1$ and sub-matrix $\Sigma_{11}$, and
+//! the others given $X_1 = x_1$ have the mean
+//! $\mu_2 + \Sigma_{21} \Sigma_{11}^{-1} (x_1 - \mu_1)$ and the (scale)
+//! matrix $\Sigma_{22} - \Sigma_{21} \Sigma_{11}^{-1} \Sigma_{12}$ (scaled
+//! and with more degrees of freedom, for Student's t).
+
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::{ChiSquared, StandardNormal};
+use statrs::function::gamma::ln_gamma;
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Multivariate distribution error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum MultivariateError {
+    /// A parameter of the distribution is out of its range.
+    #[error("Distribution parameter out of range")]
+    InvalidParameter,
+
+    /// The covariance (or scale) matrix is not symmetric positive definite.
+    #[error("Covariance matrix must be symmetric positive definite")]
+    InvalidCovariance,
+
+    /// The mean, covariance and values do not all have the same dimension,
+    /// of at least one.
+    #[error("Mean, covariance and values must have the same dimension")]
+    DimensionMismatch,
+
+    /// The indices of a marginal or conditional distribution are out of
+    /// range or repeated, or leave no variable.
+    #[error("Indices must be distinct, in range and leave a variable")]
+    InvalidIndices,
+
+    /// There are too few observations to fit the distribution.
+    #[error("Not enough observations to fit the distribution")]
+    NotEnoughObservations,
+}
+
+/// Base trait for multivariate distributions.
+pub trait MultivariateDistribution {
+    /// Number of variables.
+    fn dimension(&self) -> usize;
+
+    /// Mean vector (NaN if it does not exist).
+    fn mean(&self) -> DVector<f64>;
+
+    /// Covariance matrix (NaN if it does not exist).
+    fn covariance(&self) -> DMatrix<f64>;
+
+    /// Natural logarithm of the density at `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have one value per variable.
+    fn ln_pdf(&self, x: &[f64]) -> f64;
+
+    /// Sample of the variables.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64>;
+
+    /// Density at `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have one value per variable.
+    fn pdf(&self, x: &[f64]) -> f64 {
+        self.ln_pdf(x).exp()
+    }
+
+    /// `n` samples of the variables.
+    fn samples<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<Vec<f64>> {
+        (0..n).map(|_| self.sample(rng)).collect()
+    }
+
+    /// Log-likelihood of the observations `data`.
+    fn log_likelihood(&self, data: &[Vec<f64>]) -> f64 {
+        data.iter().map(|x| self.ln_pdf(x)).sum()
+    }
+}
+
+/// Multivariate normal distribution, $N(\mu, \Sigma)$.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// use RustQuant::statistics::*;
+/// use nalgebra::{DMatrix, DVector};
+///
+/// let mean = DVector::from_vec(vec![0.05, 0.03]);
+/// let covariance = DMatrix::from_row_slice(2, 2, &[0.04, 0.018, 0.018, 0.09]);
+/// let normal = MultivariateNormal::new(mean, covariance).unwrap();
+///
+/// // The second return, given the first is 10%: correlation 0.3.
+/// let conditional = normal.conditional(&[0], &[0.10]).unwrap();
+/// assert_approx_equal!(conditional.mean()[0], 0.03 + 0.018 / 0.04 * 0.05, 1e-12);
+/// assert_approx_equal!(conditional.covariance()[(0, 0)], 0.09 * (1.0 - 0.09), 1e-12);
+///
+/// let scenarios = normal.samples(10, &mut rand::thread_rng());
+/// assert!(scenarios.iter().all(|x| x.len() == 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultivariateNormal {
+    /// Mean vector ($\mu$).
+    mean: DVector<f64>,
+
+    /// Covariance matrix ($\Sigma$).
+    covariance: DMatrix<f64>,
+
+    // Lower triangular Cholesky factor and log-determinant of the covariance.
+    cholesky: DMatrix<f64>,
+    log_determinant: f64,
+}
+
+/// Multivariate Student's t distribution, with location $\mu$, scale
+/// matrix $\Sigma$ and $\nu$ degrees of freedom.
+///
+/// The covariance is $\Sigma \nu / (\nu - 2)$, for $\nu > 2$.
+///
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// use RustQuant::statistics::*;
+/// use nalgebra::{DMatrix, DVector};
+///
+/// let location = DVector::from_vec(vec![0.0, 0.0]);
+/// let scale = DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]);
+/// let t = MultivariateStudentsT::new(location, scale, 4.0).unwrap();
+///
+/// assert_approx_equal!(t.covariance()[(0, 1)], 1.0, 1e-12);
+///
+/// // The marginals are univariate Student's t distributions.
+/// let marginal = t.marginal(&[1]).unwrap();
+/// let univariate = StudentsT::new(4.0, 0.0, 1.0);
+/// assert_approx_equal!(marginal.pdf(&[0.7]), univariate.pdf(0.7), 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultivariateStudentsT {
+    /// Location vector ($\mu$).
+    location: DVector<f64>,
+
+    /// Scale matrix ($\Sigma$).
+    scale: DMatrix<f64>,
+
+    /// Degrees of freedom ($\nu$).
+    degrees_of_freedom: f64,
+
+    // Lower triangular Cholesky factor and log-determinant of the scale.
+    cholesky: DMatrix<f64>,
+    log_determinant: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MultivariateNormal {
+    /// New multivariate normal distribution with the mean `mean` and the
+    /// covariance matrix `covariance`.
+    ///
+    /// # Errors
+    ///
+    /// - `MultivariateError::DimensionMismatch` if `mean` is empty, or
+    ///   `covariance` is not square of the same dimension.
+    /// - `MultivariateError::InvalidCovariance` if `covariance` is not
+    ///   symmetric positive definite.
+    /// - `MultivariateError::InvalidParameter` if `mean` is not finite.
+    pub fn new(mean: DVector<f64>, covariance: DMatrix<f64>) -> Result<Self, MultivariateError> {
+        let (cholesky, log_determinant) = decompose(&mean, &covariance)?;
+
+        Ok(Self {
+            mean,
+            covariance,
+            cholesky,
+            log_determinant,
+        })
+    }
+
+    /// Maximum likelihood fit to the observations `data`: the sample mean,
+    /// and the sample covariance (divided by the number of observations).
+    ///
+    /// # Errors
+    ///
+    /// - `MultivariateError::NotEnoughObservations` if there are no more
+    ///   observations than variables.
+    /// - `MultivariateError::DimensionMismatch` if the observations do not
+    ///   all have the same dimension.
+    /// - `MultivariateError::InvalidCovariance` if the sample covariance is
+    ///   singular.
+    pub fn fit(data: &[Vec<f64>]) -> Result<Self, MultivariateError> {
+        let dimension = data.first().map_or(0, Vec::len);
+        if dimension == 0 || data.iter().any(|x| x.len() != dimension) {
+            return Err(MultivariateError::DimensionMismatch);
+        }
+        if data.len() <= dimension {
+            return Err(MultivariateError::NotEnoughObservations);
+        }
+
+        let n = data.len() as f64;
+        let observations: Vec<DVector<f64>> =
+            data.iter().map(|x| DVector::from_column_slice(x)).collect();
+        let mean = observations
+            .iter()
+            .fold(DVector::zeros(dimension), |sum, x| sum + x)
+            / n;
+        let covariance =
+            observations
+                .iter()
+                .fold(DMatrix::zeros(dimension, dimension), |sum, x| {
+                    let deviation = x - &mean;
+                    sum + &deviation * deviation.transpose()
+                })
+                / n;
+
+        Self::new(mean, covariance)
+    }
+
+    /// Marginal distribution of the variables `indices`, in that order.
+    ///
+    /// # Errors
+    ///
+    /// `MultivariateError::InvalidIndices` if `indices` is empty, or has
+    /// repeated or out of range indices.
+    pub fn marginal(&self, indices: &[usize]) -> Result<Self, MultivariateError> {
+        check_indices(indices, self.dimension(), false)?;
+
+        Self::new(
+            select(&self.mean, indices),
+            submatrix(&self.covariance, indices, indices),
+        )
+    }
+
+    /// Conditional distribution of the other variables (in increasing
+    /// order) given the variables `indices` equal to `values`.
+    ///
+    /// # Errors
+    ///
+    /// - `MultivariateError::InvalidIndices` if `indices` has repeated or
+    ///   out of range indices, or contains every variable.
+    /// - `MultivariateError::DimensionMismatch` if there is not one value
+    ///   per index.
+    pub fn conditional(
+        &self,
+        indices: &[usize],
+        values: &[f64],
+    ) -> Result<Self, MultivariateError> {
+        let regression = Regression::new(&self.mean, &self.covariance, indices, values)?;
+
+        Self::new(regression.mean, regression.covariance)
+    }
+}
+
+impl MultivariateDistribution for MultivariateNormal {
+    fn dimension(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn mean(&self) -> DVector<f64> {
+        self.mean.clone()
+    }
+
+    fn covariance(&self) -> DMatrix<f64> {
+        self.covariance.clone()
+    }
+
+    fn ln_pdf(&self, x: &[f64]) -> f64 {
+        let d = self.dimension() as f64;
+        let distance = mahalanobis(&self.cholesky, &self.mean, x);
+
+        -0.5 * (d * (2.0 * PI).ln() + self.log_determinant + distance)
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let z = DVector::from_fn(self.dimension(), |_, _| rng.sample(StandardNormal));
+
+        (&self.mean + &self.cholesky * z).iter().copied().collect()
+    }
+}
+
+impl MultivariateStudentsT {
+    /// New multivariate Student's t distribution with the location
+    /// `location`, the scale matrix `scale` and `degrees_of_freedom` degrees
+    /// of freedom.
+    ///
+    /// # Errors
+    ///
+    /// - `MultivariateError::DimensionMismatch` if `location` is empty, or
+    ///   `scale` is not square of the same dimension.
+    /// - `MultivariateError::InvalidCovariance` if `scale` is not symmetric
+    ///   positive definite.
+    /// - `MultivariateError::InvalidParameter` if `location` is not finite,
+    ///   or the degrees of freedom are not positive.
+    pub fn new(
+        location: DVector<f64>,
+        scale: DMatrix<f64>,
+        degrees_of_freedom: f64,
+    ) -> Result<Self, MultivariateError> {
+        if !(degrees_of_freedom > 0.0 && degrees_of_freedom.is_finite()) {
+            return Err(MultivariateError::InvalidParameter);
+        }
+        let (cholesky, log_determinant) = decompose(&location, &scale)?;
+
+        Ok(Self {
+            location,
+            scale,
+            degrees_of_freedom,
+            cholesky,
+            log_determinant,
+        })
+    }
+
+    /// Location vector ($\mu$).
+    #[must_use]
+    pub fn location(&self) -> &DVector<f64> {
+        &self.location
+    }
+
+    /// Scale matrix ($\Sigma$).
+    #[must_use]
+    pub fn scale(&self) -> &DMatrix<f64> {
+        &self.scale
+    }
+
+    /// Degrees of freedom ($\nu$).
+    #[must_use]
+    pub fn degrees_of_freedom(&self) -> f64 {
+        self.degrees_of_freedom
+    }
+
+    /// Marginal distribution of the variables `indices`, in that order,
+    /// with the same degrees of freedom.
+    ///
+    /// # Errors
+    ///
+    /// `MultivariateError::InvalidIndices` if `indices` is empty, or has
+    /// repeated or out of range indices.
+    pub fn marginal(&self, indices: &[usize]) -> Result<Self, MultivariateError> {
+        check_indices(indices, self.dimension(), false)?;
+
+        Self::new(
+            select(&self.location, indices),
+            submatrix(&self.scale, indices, indices),
+            self.degrees_of_freedom,
+        )
+    }
+
+    /// Conditional distribution of the other variables (in increasing
+    /// order) given the variables `indices` equal to `values`: Student's t
+    /// with $\nu + d_1$ degrees of freedom, and the scale matrix multiplied
+    /// by $(\nu + \delta_1) / (\nu + d_1)$, where $\delta_1$ is the
+    /// Mahalanobis distance of the values from their location.
+    ///
+    /// # Errors
+    ///
+    /// - `MultivariateError::InvalidIndices` if `indices` has repeated or
+    ///   out of range indices, or contains every variable.
+    /// - `MultivariateError::DimensionMismatch` if there is not one value
+    ///   per index.
+    pub fn conditional(
+        &self,
+        indices: &[usize],
+        values: &[f64],
+    ) -> Result<Self, MultivariateError> {
+        let regression = Regression::new(&self.location, &self.scale, indices, values)?;
+
+        let nu = self.degrees_of_freedom;
+        let given = indices.len() as f64;
+        let factor = (nu + regression.distance) / (nu + given);
+
+        Self::new(regression.mean, regression.covariance * factor, nu + given)
+    }
+}
+
+impl MultivariateDistribution for MultivariateStudentsT {
+    fn dimension(&self) -> usize {
+        self.location.len()
+    }
+
+    fn mean(&self) -> DVector<f64> {
+        if self.degrees_of_freedom > 1.0 {
+            self.location.clone()
+        } else {
+            DVector::from_element(self.dimension(), f64::NAN)
+        }
+    }
+
+    fn covariance(&self) -> DMatrix<f64> {
+        let nu = self.degrees_of_freedom;
+
+        if nu > 2.0 {
+            &self.scale * (nu / (nu - 2.0))
+        } else {
+            DMatrix::from_element(self.dimension(), self.dimension(), f64::NAN)
+        }
+    }
+
+    fn ln_pdf(&self, x: &[f64]) -> f64 {
+        let (nu, d) = (self.degrees_of_freedom, self.dimension() as f64);
+        let distance = mahalanobis(&self.cholesky, &self.location, x);
+
+        ln_gamma(0.5 * (nu + d))
+            - ln_gamma(0.5 * nu)
+            - 0.5 * d * (nu * PI).ln()
+            - 0.5 * self.log_determinant
+            - 0.5 * (nu + d) * (distance / nu).ln_1p()
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<f64> {
+        let nu = self.degrees_of_freedom;
+        let z = DVector::from_fn(self.dimension(), |_, _| rng.sample(StandardNormal));
+        let w: f64 = rng.sample(ChiSquared::new(nu).unwrap()) / nu;
+
+        (&self.location + &self.cholesky * z / w.sqrt())
+            .iter()
+            .copied()
+            .collect()
+    }
+}
+
+// Mean and covariance of the other variables regressed on the variables
+// `indices` at `values`, with the Mahalanobis distance of the values.
+struct Regression {
+    mean: DVector<f64>,
+    covariance: DMatrix<f64>,
+    distance: f64,
+}
+
+impl Regression {
+    fn new(
+        mean: &DVector<f64>,
+        covariance: &DMatrix<f64>,
+        indices: &[usize],
+        values: &[f64],
+    ) -> Result<Self, MultivariateError> {
+        check_indices(indices, mean.len(), true)?;
+        if values.len() != indices.len() {
+            return Err(MultivariateError::DimensionMismatch);
+        }
+
+        let others: Vec<usize> = (0..mean.len()).filter(|j| !indices.contains(j)).collect();
+
+        let cholesky = submatrix(covariance, indices, indices)
+            .cholesky()
+            .ok_or(MultivariateError::InvalidCovariance)?;
+        let deviation = DVector::from_column_slice(values) - select(mean, indices);
+        // Sigma_11^{-1} Sigma_12 and Sigma_11^{-1} (x_1 - mu_1).
+        let coefficients = cholesky.solve(&submatrix(covariance, indices, &others));
+        let weights = cholesky.solve(&deviation);
+
+        let conditional = submatrix(covariance, &others, &others)
+            - submatrix(covariance, &others, indices) * &coefficients;
+
+        Ok(Self {
+            mean: select(mean, &others) + coefficients.transpose() * &deviation,
+            // Symmetrised, against rounding.
+            covariance: 0.5 * (&conditional + conditional.transpose()),
+            distance: deviation.dot(&weights),
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Cholesky factor and log-determinant of a covariance matrix, checked
+// against the mean.
+fn decompose(
+    mean: &DVector<f64>,
+    covariance: &DMatrix<f64>,
+) -> Result<(DMatrix<f64>, f64), MultivariateError> {
+    const TOLERANCE: f64 = 1e-12;
+
+    let n = mean.len();
+    if n == 0 || covariance.shape() != (n, n) {
+        return Err(MultivariateError::DimensionMismatch);
+    }
+
+    let symmetric = (0..n).all(|i| {
+        (0..i).all(|j| {
+            let (a, b) = (covariance[(i, j)], covariance[(j, i)]);
+            (a - b).abs() <= TOLERANCE * a.abs().max(b.abs())
+        })
+    });
+    if !symmetric {
+        return Err(MultivariateError::InvalidCovariance);
+    }
+    if mean.iter().any(|x| !x.is_finite()) {
+        return Err(MultivariateError::InvalidParameter);
+    }
+
+    let cholesky = covariance
+        .clone()
+        .cholesky()
+        .ok_or(MultivariateError::InvalidCovariance)?
+        .l();
+    let log_determinant = 2.0 * cholesky.diagonal().iter().map(|x| x.ln()).sum::<f64>();
+
+    Ok((cholesky, log_determinant))
+}
+
+// Squared Mahalanobis distance of `x` from `mean`, with the Cholesky factor
+// of the covariance.
+fn mahalanobis(cholesky: &DMatrix<f64>, mean: &DVector<f64>, x: &[f64]) -> f64 {
+    assert_eq!(x.len(), mean.len(), "One value per variable.");
+
+    let deviation = DVector::from_column_slice(x) - mean;
+
+    cholesky
+        .solve_lower_triangular(&deviation)
+        .expect("The Cholesky factor is invertible.")
+        .norm_squared()
+}
+
+// Checks that the indices are distinct and in range, and leave a variable
+// out if `proper`.
+fn check_indices(
+    indices: &[usize],
+    dimension: usize,
+    proper: bool,
+) -> Result<(), MultivariateError> {
+    let distinct = indices
+        .iter()
+        .enumerate()
+        .all(|(k, i)| *i < dimension && !indices[..k].contains(i));
+    let size = if proper {
+        indices.len() < dimension
+    } else {
+        !indices.is_empty()
+    };
+
+    if distinct && size {
+        Ok(())
+    } else {
+        Err(MultivariateError::InvalidIndices)
+    }
+}
+
+fn select(vector: &DVector<f64>, indices: &[usize]) -> DVector<f64> {
+    DVector::from_fn(indices.len(), |i, _| vector[indices[i]])
+}
+
+fn submatrix(matrix: &DMatrix<f64>, rows: &[usize], columns: &[usize]) -> DMatrix<f64> {
+    DMatrix::from_fn(rows.len(), columns.len(), |i, j| {
+        matrix[(rows[i], columns[j])]
+    })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_multivariate {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::statistics::distributions::{Distribution, StudentsT};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn covariance() -> DMatrix<f64> {
+        DMatrix::from_row_slice(3, 3, &[4.0, 1.2, -0.6, 1.2, 1.0, 0.3, -0.6, 0.3, 2.25])
+    }
+
+    fn mean() -> DVector<f64> {
+        DVector::from_vec(vec![1.0, -0.5, 0.2])
+    }
+
+    #[test]
+    fn test_densities() {
+        // Bivariate normal density in closed form.
+        let (s_1, s_2, rho): (f64, f64, f64) = (2.0, 1.0, 0.6);
+        let normal = MultivariateNormal::new(
+            DVector::from_vec(vec![1.0, -0.5]),
+            DMatrix::from_row_slice(2, 2, &[4.0, 1.2, 1.2, 1.0]),
+        )
+        .unwrap();
+        let (z_1, z_2) = ((0.3 - 1.0) / s_1, (0.4 + 0.5) / s_2);
+        let expected =
+            (-(z_1 * z_1 - 2.0 * rho * z_1 * z_2 + z_2 * z_2) / (2.0 * (1.0 - rho * rho))).exp()
+                / (2.0 * PI * s_1 * s_2 * (1.0 - rho * rho).sqrt());
+        assert_approx_equal!(normal.pdf(&[0.3, 0.4]), expected, 1e-14);
+
+        // Bivariate t density with an identity scale: (1 + |x|^2 / nu)^(-(nu + 2) / 2) / (2 pi).
+        let nu = 5.0;
+        let t = MultivariateStudentsT::new(DVector::zeros(2), DMatrix::identity(2, 2), nu).unwrap();
+        let expected = (1.0 + (0.25 + 1.44) / nu).powf(-0.5 * (nu + 2.0)) / (2.0 * PI);
+        assert_approx_equal!(t.pdf(&[0.5, -1.2]), expected, 1e-14);
+
+        // One dimensional t against the univariate distribution.
+        let t = MultivariateStudentsT::new(
+            DVector::from_vec(vec![0.1]),
+            DMatrix::from_element(1, 1, 0.09),
+            3.5,
+        )
+        .unwrap();
+        let univariate = StudentsT::new(3.5, 0.1, 0.3);
+        assert_approx_equal!(t.ln_pdf(&[0.55]), univariate.ln_pdf(0.55), 1e-12);
+        assert_approx_equal!(t.covariance()[(0, 0)], univariate.variance(), 1e-12);
+    }
+
+    #[test]
+    fn test_marginal_and_conditional() {
+        let normal = MultivariateNormal::new(mean(), covariance()).unwrap();
+        let t = MultivariateStudentsT::new(mean(), covariance(), 4.0).unwrap();
+
+        let marginal = normal.marginal(&[2, 0]).unwrap();
+        assert_eq!(marginal.mean().as_slice(), &[0.2, 1.0]);
+        assert_approx_equal!(marginal.covariance()[(0, 1)], -0.6, 1e-15);
+
+        // The joint density factorises into the marginal of the given
+        // variables and the conditional of the others.
+        let x = [0.4, -1.1, 1.3];
+        let (indices, given, others) = ([2, 0], [1.3, 0.4], [-1.1]);
+
+        let conditional = normal.conditional(&indices, &given).unwrap();
+        assert_approx_equal!(
+            normal.ln_pdf(&x),
+            normal.marginal(&indices).unwrap().ln_pdf(&given) + conditional.ln_pdf(&others),
+            1e-12
+        );
+
+        let conditional = t.conditional(&indices, &given).unwrap();
+        assert_approx_equal!(conditional.degrees_of_freedom(), 6.0, 1e-15);
+        assert_approx_equal!(
+            t.ln_pdf(&x),
+            t.marginal(&indices).unwrap().ln_pdf(&given) + conditional.ln_pdf(&others),
+            1e-12
+        );
+
+        // Bivariate regression: mean mu_2 + rho s_2 / s_1 (x_1 - mu_1), and
+        // variance s_2^2 (1 - rho^2).
+        let conditional = normal
+            .marginal(&[0, 1])
+            .unwrap()
+            .conditional(&[0], &[2.0])
+            .unwrap();
+        assert_approx_equal!(conditional.mean()[0], -0.5 + 0.6 * 0.5 * 1.0, 1e-14);
+        assert_approx_equal!(conditional.covariance()[(0, 0)], 1.0 - 0.36, 1e-14);
+    }
+
+    #[test]
+    fn test_sampling_and_fitting() {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let normal = MultivariateNormal::new(mean(), covariance()).unwrap();
+        let fitted = MultivariateNormal::fit(&normal.samples(20_000, &mut rng)).unwrap();
+        for i in 0..3 {
+            assert_approx_equal!(fitted.mean()[i], normal.mean()[i], 0.05);
+            for j in 0..3 {
+                assert_approx_equal!(fitted.covariance()[(i, j)], covariance()[(i, j)], 0.1);
+            }
+        }
+
+        // Covariance nu / (nu - 2) times the scale.
+        let t = MultivariateStudentsT::new(mean(), covariance(), 8.0).unwrap();
+        let moments = MultivariateNormal::fit(&t.samples(20_000, &mut rng)).unwrap();
+        for i in 0..3 {
+            assert_approx_equal!(moments.mean()[i], t.mean()[i], 0.05);
+            assert_approx_equal!(
+                moments.covariance()[(i, i)],
+                t.covariance()[(i, i)],
+                0.1 * t.covariance()[(i, i)]
+            );
+        }
+        assert!(fitted.log_likelihood(&t.samples(100, &mut rng)).is_finite());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let not_positive = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 2.0, 1.0]);
+        let not_symmetric = DMatrix::from_row_slice(2, 2, &[1.0, 0.2, 0.3, 1.0]);
+        let zero = DVector::zeros(2);
+
+        assert_eq!(
+            MultivariateNormal::new(zero.clone(), not_positive).unwrap_err(),
+            MultivariateError::InvalidCovariance
+        );
+        assert_eq!(
+            MultivariateNormal::new(zero.clone(), not_symmetric).unwrap_err(),
+            MultivariateError::InvalidCovariance
+        );
+        assert_eq!(
+            MultivariateNormal::new(DVector::zeros(3), DMatrix::identity(2, 2)).unwrap_err(),
+            MultivariateError::DimensionMismatch
+        );
+        assert_eq!(
+            MultivariateStudentsT::new(zero, DMatrix::identity(2, 2), 0.0).unwrap_err(),
+            MultivariateError::InvalidParameter
+        );
+
+        let normal = MultivariateNormal::new(mean(), covariance()).unwrap();
+        assert_eq!(
+            normal.marginal(&[0, 0]).unwrap_err(),
+            MultivariateError::InvalidIndices
+        );
+        assert_eq!(
+            normal.marginal(&[3]).unwrap_err(),
+            MultivariateError::InvalidIndices
+        );
+        assert_eq!(
+            normal
+                .conditional(&[0, 1, 2], &[0.0, 0.0, 0.0])
+                .unwrap_err(),
+            MultivariateError::InvalidIndices
+        );
+        assert_eq!(
+            normal.conditional(&[0], &[0.0, 1.0]).unwrap_err(),
+            MultivariateError::DimensionMismatch
+        );
+        assert_eq!(
+            MultivariateNormal::fit(&[vec![1.0, 2.0], vec![2.0, 1.0]]).unwrap_err(),
+            MultivariateError::NotEnoughObservations
+        );
+    }
+}
@@ -0,0 +1,384 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Volatility models of historical returns.
+//!
+//! The returns $r_t = \sigma_t \epsilon_t$ (de-meaned, e.g. daily log
+//! returns) have the conditional variance:
+//!
+//! - EWMA (RiskMetrics, 1996): $\sigma_t^2 = \lambda \sigma_{t-1}^2 + (1 - \lambda) r_{t-1}^2$.
+//! - GARCH(1,1) (Bollerslev, 1986): $\sigma_t^2 = \omega + \alpha r_{t-1}^2 + \beta \sigma_{t-1}^2$,
+//!   mean reverting to the long-run variance $\omega / (1 - \alpha - \beta)$.
+//!
+//! The GARCH parameters are fitted by (quasi) maximum likelihood, with
+//! Gaussian innovations $\epsilon_t$. The variance forecasts, e.g.
+//! annualised, give volatilities for risk measures and option pricing.
+
+use crate::math::nelder_mead;
+use rand::Rng;
+use rand_distr::StandardNormal;
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS AND ENUMS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Volatility model error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum VolatilityError {
+    /// A parameter of the model is out of its range.
+    #[error("Volatility model parameter out of range")]
+    InvalidParameter,
+
+    /// There are too few returns to fit the model.
+    #[error("Not enough returns to fit the model")]
+    NotEnoughObservations,
+}
+
+/// Exponentially weighted moving average (EWMA) variance.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// let ewma = Ewma::default();
+/// let returns = [0.01, -0.02, 0.015, -0.005, 0.03];
+///
+/// // One variance per return, and the forecast for the next one.
+/// let variances = ewma.variances(&returns);
+/// let forecast = ewma.forecast(&returns);
+///
+/// assert_eq!(variances.len(), 5);
+/// assert!((forecast - (0.94 * variances[4] + 0.06 * 0.03 * 0.03)).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ewma {
+    /// Decay factor ($\lambda$).
+    pub lambda: f64,
+}
+
+/// GARCH(1,1) conditional variance model.
+///
+/// ```
+/// use RustQuant::statistics::*;
+///
+/// let garch = Garch::new(2e-6, 0.08, 0.9).unwrap();
+///
+/// // Daily returns, and the one-year variance forecast, annualised.
+/// let path = garch.simulate(garch.long_run_variance(), 1_000, &mut rand::thread_rng());
+/// let forecasts = garch.forecast(&path.returns, 252);
+/// let volatility = forecasts.iter().sum::<f64>().sqrt();
+///
+/// assert!(volatility > 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Garch {
+    /// Constant ($\omega$).
+    pub omega: f64,
+
+    /// Reaction to the last squared return ($\alpha$).
+    pub alpha: f64,
+
+    /// Persistence of the last variance ($\beta$).
+    pub beta: f64,
+}
+
+/// Simulated returns and their conditional variances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolatilityPath {
+    /// The returns.
+    pub returns: Vec<f64>,
+
+    /// The conditional variance of each return.
+    pub variances: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for Ewma {
+    /// The RiskMetrics decay factor for daily returns, 0.94.
+    fn default() -> Self {
+        Self { lambda: 0.94 }
+    }
+}
+
+impl Ewma {
+    /// New EWMA model with the decay factor `lambda`.
+    ///
+    /// # Errors
+    ///
+    /// `VolatilityError::InvalidParameter` if `lambda` is not in `[0, 1)`.
+    pub fn new(lambda: f64) -> Result<Self, VolatilityError> {
+        if (0.0..1.0).contains(&lambda) {
+            Ok(Self { lambda })
+        } else {
+            Err(VolatilityError::InvalidParameter)
+        }
+    }
+
+    /// Conditional variance of each return, given the previous returns,
+    /// starting from the first squared return.
+    #[must_use]
+    pub fn variances(&self, returns: &[f64]) -> Vec<f64> {
+        let mut variances = Vec::with_capacity(returns.len());
+        let mut variance = returns.first().map_or(0.0, |r| r * r);
+
+        for r in returns {
+            variances.push(variance);
+            variance = self.lambda * variance + (1.0 - self.lambda) * r * r;
+        }
+
+        variances
+    }
+
+    /// Variance of the next return (and of all the following ones: the
+    /// EWMA forecasts are flat).
+    #[must_use]
+    pub fn forecast(&self, returns: &[f64]) -> f64 {
+        match (self.variances(returns).last(), returns.last()) {
+            (Some(variance), Some(r)) => self.lambda * variance + (1.0 - self.lambda) * r * r,
+            _ => 0.0,
+        }
+    }
+}
+
+impl Garch {
+    /// New GARCH(1,1) model.
+    ///
+    /// # Errors
+    ///
+    /// `VolatilityError::InvalidParameter` unless `omega > 0`, `alpha >= 0`,
+    /// `beta >= 0` and `alpha + beta < 1` (covariance stationarity).
+    pub fn new(omega: f64, alpha: f64, beta: f64) -> Result<Self, VolatilityError> {
+        if omega > 0.0 && alpha >= 0.0 && beta >= 0.0 && alpha + beta < 1.0 {
+            Ok(Self { omega, alpha, beta })
+        } else {
+            Err(VolatilityError::InvalidParameter)
+        }
+    }
+
+    /// Persistence of the variance shocks, $\alpha + \beta$.
+    #[must_use]
+    pub fn persistence(&self) -> f64 {
+        self.alpha + self.beta
+    }
+
+    /// Long-run (unconditional) variance, $\omega / (1 - \alpha - \beta)$.
+    #[must_use]
+    pub fn long_run_variance(&self) -> f64 {
+        self.omega / (1.0 - self.persistence())
+    }
+
+    /// Half-life of the variance shocks, in periods.
+    #[must_use]
+    pub fn half_life(&self) -> f64 {
+        0.5_f64.ln() / self.persistence().ln()
+    }
+
+    /// Conditional variance of each return, given the previous returns,
+    /// starting from the sample variance of the returns.
+    #[must_use]
+    pub fn variances(&self, returns: &[f64]) -> Vec<f64> {
+        let mut variances = Vec::with_capacity(returns.len());
+        let mut variance = sample_variance(returns);
+
+        for r in returns {
+            variances.push(variance);
+            variance = self.omega + self.alpha * r * r + self.beta * variance;
+        }
+
+        variances
+    }
+
+    /// Gaussian log-likelihood of the returns.
+    #[must_use]
+    pub fn log_likelihood(&self, returns: &[f64]) -> f64 {
+        self.variances(returns)
+            .iter()
+            .zip(returns)
+            .map(|(variance, r)| -0.5 * ((2.0 * PI * variance).ln() + r * r / variance))
+            .sum()
+    }
+
+    /// Variance forecasts of the next `horizon` returns:
+    /// $\sigma_{t+h}^2 = V_L + (\alpha + \beta)^{h-1} (\sigma_{t+1}^2 - V_L)$,
+    /// with the long-run variance $V_L$.
+    #[must_use]
+    pub fn forecast(&self, returns: &[f64], horizon: usize) -> Vec<f64> {
+        let next = match (self.variances(returns).last(), returns.last()) {
+            (Some(variance), Some(r)) => self.omega + self.alpha * r * r + self.beta * variance,
+            _ => self.long_run_variance(),
+        };
+        let long_run = self.long_run_variance();
+
+        let mut decay = 1.0;
+        (0..horizon)
+            .map(|_| {
+                let forecast = long_run + decay * (next - long_run);
+                decay *= self.persistence();
+                forecast
+            })
+            .collect()
+    }
+
+    /// Simulated returns, with Gaussian innovations, from the conditional
+    /// variance `variance_0` of the first return.
+    pub fn simulate<R: Rng + ?Sized>(
+        &self,
+        variance_0: f64,
+        n_steps: usize,
+        rng: &mut R,
+    ) -> VolatilityPath {
+        let mut returns = Vec::with_capacity(n_steps);
+        let mut variances = Vec::with_capacity(n_steps);
+
+        let mut variance = variance_0;
+        for _ in 0..n_steps {
+            let z: f64 = rng.sample(StandardNormal);
+            let r = variance.sqrt() * z;
+
+            returns.push(r);
+            variances.push(variance);
+            variance = self.omega + self.alpha * r * r + self.beta * variance;
+        }
+
+        VolatilityPath { returns, variances }
+    }
+
+    /// GARCH(1,1) model fitted to the returns by maximum likelihood, with
+    /// the Nelder-Mead algorithm over unconstrained transformations of the
+    /// parameters.
+    ///
+    /// # Errors
+    ///
+    /// `VolatilityError::NotEnoughObservations` if there are fewer than ten
+    /// returns, or they are all zero.
+    pub fn fit(returns: &[f64]) -> Result<Self, VolatilityError> {
+        if returns.len() < 10 || returns.iter().all(|r| *r == 0.0) {
+            return Err(VolatilityError::NotEnoughObservations);
+        }
+
+        let variance = sample_variance(returns);
+        let logistic = |x: f64| 1.0 / (1.0 + (-x).exp());
+        let logit = |p: f64| (p / (1.0 - p)).ln();
+
+        // Parameters: ln(omega), logit(alpha + beta), logit(alpha / (alpha + beta)).
+        let model = |x: &[f64]| {
+            let (persistence, share) = (logistic(x[1]), logistic(x[2]));
+            Self::new(x[0].exp(), share * persistence, (1.0 - share) * persistence)
+        };
+        let objective = |x: &[f64]| {
+            model(x).map_or(f64::INFINITY, |garch| {
+                let value = -garch.log_likelihood(returns);
+                if value.is_nan() {
+                    f64::INFINITY
+                } else {
+                    value
+                }
+            })
+        };
+
+        // From a typical daily fit, with the sample variance as long-run
+        // variance.
+        let start = [(variance * 0.05).ln(), logit(0.95), logit(0.1 / 0.95)];
+        let minimizer = nelder_mead(objective, &start);
+
+        model(&minimizer)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Mean of the squared returns: the variance about a zero mean, as in the
+// models, which take the returns to be de-meaned by the caller.
+fn sample_variance(returns: &[f64]) -> f64 {
+    if returns.is_empty() {
+        return 0.0;
+    }
+
+    returns.iter().map(|r| r * r).sum::<f64>() / returns.len() as f64
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_volatility {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_ewma() {
+        let ewma = Ewma::new(0.9).unwrap();
+        let returns = [0.02, -0.01, 0.03];
+
+        let variances = ewma.variances(&returns);
+        assert_approx_equal!(variances[0], 4e-4, 1e-15);
+        assert_approx_equal!(variances[1], 0.9 * 4e-4 + 0.1 * 4e-4, 1e-15);
+        assert_approx_equal!(variances[2], 0.9 * variances[1] + 0.1 * 1e-4, 1e-15);
+        assert_approx_equal!(
+            ewma.forecast(&returns),
+            0.9 * variances[2] + 0.1 * 9e-4,
+            1e-15
+        );
+
+        assert_eq!(Ewma::new(1.0), Err(VolatilityError::InvalidParameter));
+    }
+
+    #[test]
+    fn test_garch_forecast() {
+        let garch = Garch::new(1e-5, 0.1, 0.85).unwrap();
+        assert_approx_equal!(garch.long_run_variance(), 2e-4, 1e-15);
+        assert_approx_equal!(garch.half_life(), 0.5_f64.ln() / 0.95_f64.ln(), 1e-12);
+
+        // From a calm period, the forecasts rise to the long-run variance.
+        let returns = [0.001; 50];
+        let forecasts = garch.forecast(&returns, 500);
+        let variances = garch.variances(&returns);
+        assert_approx_equal!(
+            forecasts[0],
+            1e-5 + 0.1 * 1e-6 + 0.85 * variances[49],
+            1e-15
+        );
+        assert!(forecasts.windows(2).all(|w| w[0] < w[1]));
+        assert_approx_equal!(forecasts[499], 2e-4, 1e-12);
+
+        assert_eq!(
+            Garch::new(1e-5, 0.2, 0.8),
+            Err(VolatilityError::InvalidParameter)
+        );
+    }
+
+    #[test]
+    fn test_garch_fit() {
+        let garch = Garch::new(2e-6, 0.08, 0.9).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        let path = garch.simulate(garch.long_run_variance(), 20_000, &mut rng);
+
+        let fitted = Garch::fit(&path.returns).unwrap();
+        assert_approx_equal!(fitted.alpha, 0.08, 0.015);
+        assert_approx_equal!(fitted.beta, 0.9, 0.02);
+        assert_approx_equal!(
+            fitted.long_run_variance(),
+            garch.long_run_variance(),
+            0.15 * garch.long_run_variance()
+        );
+        assert!(fitted.log_likelihood(&path.returns) >= garch.log_likelihood(&path.returns));
+
+        assert_eq!(
+            Garch::fit(&[0.01; 5]),
+            Err(VolatilityError::NotEnoughObservations)
+        );
+    }
+}
@@ -40,6 +40,10 @@ impl StochasticProcess for ArithmeticBrownianMotion {
         self.sigma.0(t)
     }
 
+    fn diffusion_derivative(&self, _x: f64, _t: f64) -> f64 {
+        0.0
+    }
+
     fn jump(&self, _x: f64, _t: f64) -> Option<f64> {
         None
     }
@@ -0,0 +1,285 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Brownian bridge construction of Brownian paths.
+//!
+//! The path is built from the terminal value first, then by filling in the
+//! midpoints of ever finer intervals from the Brownian bridge between the
+//! values already known. Most of the variance of the path is then carried
+//! by the first few Gaussian draws: with quasi-random points, whose first
+//! dimensions are the best distributed, this sharply reduces the effective
+//! dimension of path-dependent payoffs.
+//!
+//! Between two values of a path, the bridge also gives the interpolated
+//! value at any intermediate time ([`bridge_sample`]) and the probability
+//! that the path crossed a barrier ([`barrier_hit_probability`]), for
+//! continuously monitored barriers on a coarse time grid.
+
+use std::collections::VecDeque;
+
+/// Brownian bridge construction of Brownian paths on a time grid.
+///
+/// ```
+/// use RustQuant::stochastics::BrownianBridge;
+///
+/// let bridge = BrownianBridge::new(&[0.0, 0.25, 0.5, 0.75, 1.0]);
+///
+/// // The first draw sets the terminal value, the second the midpoint.
+/// let path = bridge.path(&[1.0, 0.0, 0.0, 0.0]);
+///
+/// assert_eq!(path, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BrownianBridge {
+    times: Vec<f64>,
+    steps: Vec<BridgeStep>,
+}
+
+// Construction of the path at `index`, from the path at `left` and, if any,
+// at `right`.
+#[derive(Debug, Clone, Copy)]
+struct BridgeStep {
+    index: usize,
+    left: usize,
+    right: Option<usize>,
+    left_weight: f64,
+    right_weight: f64,
+    std_dev: f64,
+}
+
+impl BrownianBridge {
+    /// New Brownian bridge on the time grid `times`, where the path starts
+    /// at zero at `times[0]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two times, or they are not strictly
+    /// increasing.
+    #[must_use]
+    pub fn new(times: &[f64]) -> Self {
+        assert!(times.len() >= 2, "At least two time points are required.");
+        assert!(
+            times.windows(2).all(|w| w[0] < w[1]),
+            "The time points must be strictly increasing."
+        );
+
+        let n = times.len() - 1;
+        let mut steps = Vec::with_capacity(n);
+
+        // The terminal value, then the midpoints breadth first.
+        steps.push(BridgeStep {
+            index: n,
+            left: 0,
+            right: None,
+            left_weight: 1.0,
+            right_weight: 0.0,
+            std_dev: (times[n] - times[0]).sqrt(),
+        });
+
+        let mut intervals = VecDeque::from([(0, n)]);
+        while let Some((left, right)) = intervals.pop_front() {
+            if right - left < 2 {
+                continue;
+            }
+
+            let index = left + (right - left) / 2;
+            let (t_l, t, t_r) = (times[left], times[index], times[right]);
+
+            steps.push(BridgeStep {
+                index,
+                left,
+                right: Some(right),
+                left_weight: (t_r - t) / (t_r - t_l),
+                right_weight: (t - t_l) / (t_r - t_l),
+                std_dev: ((t - t_l) * (t_r - t) / (t_r - t_l)).sqrt(),
+            });
+
+            intervals.push_back((left, index));
+            intervals.push_back((index, right));
+        }
+
+        Self {
+            times: times.to_vec(),
+            steps,
+        }
+    }
+
+    /// The time grid.
+    #[must_use]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Number of Gaussian draws per path (the number of time steps).
+    #[must_use]
+    pub fn dimension(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Brownian path on the time grid, starting at zero, from independent
+    /// standard Gaussian draws in order of importance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one draw per time step.
+    #[must_use]
+    pub fn path(&self, normals: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            normals.len(),
+            self.dimension(),
+            "There must be one draw per time step."
+        );
+
+        let mut path = vec![0.0; self.times.len()];
+        for (step, z) in self.steps.iter().zip(normals) {
+            let right = step.right.map_or(0.0, |right| path[right]);
+
+            path[step.index] =
+                step.left_weight * path[step.left] + step.right_weight * right + step.std_dev * z;
+        }
+
+        path
+    }
+
+    /// Brownian increments over the time steps, from independent standard
+    /// Gaussian draws in order of importance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one draw per time step.
+    #[must_use]
+    pub fn increments(&self, normals: &[f64]) -> Vec<f64> {
+        self.path(normals).windows(2).map(|w| w[1] - w[0]).collect()
+    }
+}
+
+/// Sample at time `t` of a Brownian motion with volatility `sigma`, given
+/// its values `x_s` at `s` and `x_u` at `u`, from a standard Gaussian draw
+/// `z`.
+///
+/// # Panics
+///
+/// Panics if `t` is not in `[s, u]`, with `s < u`.
+#[must_use]
+pub fn bridge_sample(s: f64, x_s: f64, u: f64, x_u: f64, t: f64, sigma: f64, z: f64) -> f64 {
+    assert!(
+        s < u && (s..=u).contains(&t),
+        "Requires s <= t <= u, s < u."
+    );
+
+    let mean = x_s + (t - s) / (u - s) * (x_u - x_s);
+    let std_dev = sigma * ((t - s) * (u - t) / (u - s)).sqrt();
+
+    mean + std_dev * z
+}
+
+/// Probability that a Brownian motion with volatility `sigma`, at `x_s` and
+/// `x_u` at the ends of a step of length `dt`, crosses `barrier` during the
+/// step. Apply it to the logarithms of a geometric Brownian motion.
+#[must_use]
+pub fn barrier_hit_probability(x_s: f64, x_u: f64, barrier: f64, sigma: f64, dt: f64) -> f64 {
+    let (d_s, d_u) = (barrier - x_s, barrier - x_u);
+
+    if d_s * d_u <= 0.0 {
+        return 1.0;
+    }
+
+    (-2.0 * d_s * d_u / (sigma * sigma * dt)).exp()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_brownian_bridge {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    #[test]
+    fn test_covariance() {
+        // An irregular grid, not a power of two.
+        let times = [0.0, 0.1, 0.3, 0.35, 0.7, 1.0, 1.6];
+        let bridge = BrownianBridge::new(&times);
+        let mut rng = StdRng::seed_from_u64(4);
+
+        let m: usize = 200_000;
+        let mut covariance = vec![vec![0.0; times.len()]; times.len()];
+        for _ in 0..m {
+            let normals: Vec<f64> = (0..bridge.dimension())
+                .map(|_| rng.sample(StandardNormal))
+                .collect();
+            let path = bridge.path(&normals);
+
+            for (i, x) in path.iter().enumerate() {
+                for (j, y) in path.iter().enumerate() {
+                    covariance[i][j] += x * y / m as f64;
+                }
+            }
+        }
+
+        // Cov(W_s, W_t) = min(s, t).
+        for (i, s) in times.iter().enumerate() {
+            for (j, t) in times.iter().enumerate() {
+                assert_approx_equal!(covariance[i][j], s.min(*t), 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn test_importance_order() {
+        let bridge = BrownianBridge::new(&[0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        // Terminal value (standard deviation 2), then the midpoint, then the
+        // quarter points.
+        let unit =
+            |k: usize| -> Vec<f64> { (0..4).map(|i| if i == k { 1.0 } else { 0.0 }).collect() };
+        assert_eq!(bridge.path(&unit(0)), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(bridge.path(&unit(1)), vec![0.0, 0.5, 1.0, 0.5, 0.0]);
+        assert_approx_equal!(bridge.path(&unit(2))[1], 0.5_f64.sqrt(), 1e-12);
+        assert_approx_equal!(bridge.path(&unit(3))[3], 0.5_f64.sqrt(), 1e-12);
+
+        let increments = bridge.increments(&unit(0));
+        assert_eq!(increments, vec![0.5; 4]);
+    }
+
+    #[test]
+    fn test_barrier_hit_probability() {
+        let (x_s, x_u, barrier, sigma, dt) = (0.0, 0.1, 0.3, 1.0, 0.25);
+        let exact = barrier_hit_probability(x_s, x_u, barrier, sigma, dt);
+        assert_approx_equal!(exact, (-0.48_f64).exp(), 1e-12);
+        assert_approx_equal!(
+            barrier_hit_probability(0.0, 0.4, barrier, sigma, dt),
+            1.0,
+            1e-12
+        );
+
+        // Finely monitored bridges between the two values, which cross
+        // slightly less often than continuously monitored ones.
+        let (m, n): (usize, usize) = (10_000, 2_000);
+        let h = dt / n as f64;
+        let mut rng = StdRng::seed_from_u64(8);
+
+        let hits = (0..m)
+            .filter(|_| {
+                let mut x = x_s;
+                (1..n).any(|k| {
+                    let t = k as f64 * h;
+                    let z: f64 = rng.sample(StandardNormal);
+                    x = bridge_sample(t - h, x, dt, x_u, t, sigma, z);
+                    x >= barrier
+                })
+            })
+            .count();
+
+        assert_approx_equal!(hits as f64 / m as f64, exact, 0.03);
+    }
+}
@@ -36,6 +36,10 @@ impl StochasticProcess for BrownianMotion {
         1.0
     }
 
+    fn diffusion_derivative(&self, _x: f64, _t: f64) -> f64 {
+        0.0
+    }
+
     fn jump(&self, _x: f64, _t: f64) -> Option<f64> {
         None
     }
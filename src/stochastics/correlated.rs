@@ -0,0 +1,444 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Joint simulation of several processes driven by correlated Brownian
+//! motions.
+//!
+//! The increments $dW = L Z \sqrt{dt}$ are built from independent standard
+//! Gaussians $Z$ and the Cholesky factor $L$ of the correlation matrix.
+//! Correlation matrices estimated from data, or assembled by hand, are
+//! often not positive semi-definite: they are then replaced by the nearest
+//! correlation matrix (in the Frobenius norm), computed with the
+//! alternating projections of Higham (2002).
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{EntropyStreams, RandomStream, StochasticProcess, Trajectories};
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+/// Errors of a [`CorrelatedProcesses`] driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CorrelationError {
+    /// The correlation matrix is not square with one row per process.
+    #[error("Correlation matrix must have one row and column per process")]
+    DimensionMismatch,
+
+    /// The correlation matrix is not symmetric with a unit diagonal and
+    /// entries in `[-1, 1]`.
+    #[error("Correlation matrix must be symmetric with a unit diagonal and entries in [-1, 1]")]
+    InvalidCorrelation,
+}
+
+/// Processes driven by correlated Brownian motions.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+/// use nalgebra::DMatrix;
+///
+/// let correlation = DMatrix::from_row_slice(2, 2, &[1.0, 0.8, 0.8, 1.0]);
+/// let processes = CorrelatedProcesses::new(
+///     vec![
+///         Box::new(GeometricBrownianMotion::new(0.05, 0.2)),
+///         Box::new(OrnsteinUhlenbeck::new(0.02, 0.01, 0.5)),
+///     ],
+///     correlation,
+/// )
+/// .unwrap();
+///
+/// // One set of trajectories per process, from its initial value.
+/// let output = processes.euler_maruyama(&[100.0, 0.03], 0.0, 1.0, 250, 10, false);
+///
+/// assert_eq!(output.len(), 2);
+/// assert_eq!(output[1].paths[0][0], 0.03);
+/// ```
+pub struct CorrelatedProcesses {
+    /// The processes.
+    pub processes: Vec<Box<dyn StochasticProcess>>,
+
+    /// Correlation matrix of the driving Brownian motions (after repair, if
+    /// the given matrix was not positive semi-definite).
+    pub correlation: DMatrix<f64>,
+
+    // Lower triangular Cholesky factor of the correlation matrix.
+    cholesky: DMatrix<f64>,
+}
+
+impl CorrelatedProcesses {
+    /// New driver, repairing the correlation matrix if it is not positive
+    /// definite.
+    ///
+    /// # Errors
+    ///
+    /// - `CorrelationError::DimensionMismatch` if the correlation matrix does
+    ///   not have one row and column per process.
+    /// - `CorrelationError::InvalidCorrelation` if it is not symmetric with
+    ///   a unit diagonal and entries in `[-1, 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the repaired matrix has no Cholesky factorisation, which
+    /// the floor on its eigenvalues rules out.
+    pub fn new(
+        processes: Vec<Box<dyn StochasticProcess>>,
+        correlation: DMatrix<f64>,
+    ) -> Result<Self, CorrelationError> {
+        const TOLERANCE: f64 = 1e-12;
+
+        let n = processes.len();
+        if correlation.shape() != (n, n) {
+            return Err(CorrelationError::DimensionMismatch);
+        }
+
+        let valid = (0..n).all(|i| {
+            (correlation[(i, i)] - 1.0).abs() < TOLERANCE
+                && (0..i).all(|j| {
+                    (correlation[(i, j)] - correlation[(j, i)]).abs() < TOLERANCE
+                        && correlation[(i, j)].abs() <= 1.0
+                })
+        });
+        if !valid {
+            return Err(CorrelationError::InvalidCorrelation);
+        }
+
+        let correlation = match correlation.clone().cholesky() {
+            Some(_) => correlation,
+            None => nearest_correlation_matrix(&correlation),
+        };
+        let cholesky = correlation
+            .clone()
+            .cholesky()
+            .expect("The repaired correlation matrix is positive definite.")
+            .l();
+
+        Ok(Self {
+            processes,
+            correlation,
+            cholesky,
+        })
+    }
+
+    /// Euler-Maruyama discretisation scheme of every process, with
+    /// correlated increments. The jumps of the processes, if any, are not
+    /// simulated.
+    ///
+    /// # Arguments:
+    /// * `x_0` - The initial values of the processes at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many joint trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one initial value per process.
+    #[must_use]
+    pub fn euler_maruyama(
+        &self,
+        x_0: &[f64],
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> Vec<Trajectories> {
+        self.euler_maruyama_with_streams(x_0, t_0, t_n, n_steps, m_paths, parallel, &EntropyStreams)
+    }
+
+    /// Euler-Maruyama discretisation scheme of every process, with
+    /// correlated increments and a choice of random seed.
+    ///
+    /// # Arguments:
+    /// * `x_0` - The initial values of the processes at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many joint trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one initial value per process.
+    #[cfg(feature = "seedable")]
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn seedable_euler_maruyama(
+        &self,
+        x_0: &[f64],
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> Vec<Trajectories> {
+        self.euler_maruyama_with_streams(
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    /// Euler-Maruyama discretisation scheme of every process, with
+    /// correlated increments, where the joint path `i` draws its random
+    /// numbers from the stream `i` of `streams`.
+    ///
+    /// # Arguments:
+    /// * `x_0` - The initial values of the processes at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many joint trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not one initial value per process.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn euler_maruyama_with_streams<S: RandomStream>(
+        &self,
+        x_0: &[f64],
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> Vec<Trajectories> {
+        assert!(t_0 < t_n);
+        assert_eq!(
+            x_0.len(),
+            self.processes.len(),
+            "There must be one initial value per process."
+        );
+
+        let n = self.processes.len();
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+        let path_generator = |path: usize| {
+            let mut rng = streams.stream(path as u64);
+
+            let mut paths: Vec<Vec<f64>> = x_0.iter().map(|x| vec![*x; n_steps + 1]).collect();
+            for t in 0..n_steps {
+                let z = DVector::<f64>::from_fn(n, |_, _| rng.sample(StandardNormal));
+                let dW = &self.cholesky * z * dt.sqrt();
+
+                for (i, (process, path)) in self.processes.iter().zip(&mut paths).enumerate() {
+                    path[t + 1] = path[t]
+                        + process.drift(path[t], times[t]) * dt
+                        + process.diffusion(path[t], times[t]) * dW[i];
+                }
+            }
+
+            paths
+        };
+
+        let joint: Vec<Vec<Vec<f64>>> = if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).collect()
+        } else {
+            (0..m_paths).map(path_generator).collect()
+        };
+
+        (0..n)
+            .map(|i| Trajectories {
+                times: times.clone(),
+                paths: joint.iter().map(|paths| paths[i].clone()).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Nearest correlation matrix (Higham, 2002): alternating projections onto
+/// the positive semi-definite matrices and the unit-diagonal matrices, with
+/// Dykstra's correction. The eigenvalues of the result are then floored at
+/// a small positive value, so that it has a Cholesky factorisation.
+///
+/// ```
+/// use RustQuant::stochastics::nearest_correlation_matrix;
+/// use nalgebra::DMatrix;
+///
+/// // Not positive semi-definite: an eigenvalue is negative.
+/// let invalid = DMatrix::from_row_slice(3, 3, &[
+///     1.0, 0.9, 0.7,
+///     0.9, 1.0, -0.4,
+///     0.7, -0.4, 1.0,
+/// ]);
+/// let repaired = nearest_correlation_matrix(&invalid);
+///
+/// assert!(repaired.clone().cholesky().is_some());
+/// assert!((0..3).all(|i| (repaired[(i, i)] - 1.0).abs() < 1e-12));
+/// ```
+#[must_use]
+pub fn nearest_correlation_matrix(matrix: &DMatrix<f64>) -> DMatrix<f64> {
+    const TOLERANCE: f64 = 1e-12;
+    const MAX_ITERATIONS: usize = 1000;
+    const MIN_EIGENVALUE: f64 = 1e-8;
+
+    let unit_diagonal = |mut x: DMatrix<f64>| {
+        x.fill_diagonal(1.0);
+        x
+    };
+
+    let mut y = matrix.clone();
+    let mut correction = DMatrix::<f64>::zeros(matrix.nrows(), matrix.ncols());
+
+    for _ in 0..MAX_ITERATIONS {
+        let r = &y - &correction;
+        let x = clip_eigenvalues(&r, 0.0);
+        correction = &x - &r;
+        let next = unit_diagonal(x.clone());
+
+        let change = (&next - &y).norm();
+        y = next;
+        if change < TOLERANCE && (&y - &x).norm() < TOLERANCE.sqrt() {
+            break;
+        }
+    }
+
+    // Positive definite, with a unit diagonal.
+    let x = clip_eigenvalues(&y, MIN_EIGENVALUE);
+    let scale = DVector::from_fn(x.nrows(), |i, _| 1.0 / x[(i, i)].sqrt());
+
+    DMatrix::from_fn(x.nrows(), x.ncols(), |i, j| {
+        if i == j {
+            1.0
+        } else {
+            x[(i, j)] * scale[i] * scale[j]
+        }
+    })
+}
+
+// Projection of a symmetric matrix on the matrices with eigenvalues of at
+// least `floor`.
+fn clip_eigenvalues(matrix: &DMatrix<f64>, floor: f64) -> DMatrix<f64> {
+    let eigen = matrix.clone().symmetric_eigen();
+    let values = eigen.eigenvalues.map(|value| value.max(floor));
+
+    &eigen.eigenvectors * DMatrix::from_diagonal(&values) * eigen.eigenvectors.transpose()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_correlated {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::stochastics::{ArithmeticBrownianMotion, GeometricBrownianMotion, SeededStreams};
+
+    #[test]
+    fn test_correlated_increments() {
+        let correlation =
+            DMatrix::from_row_slice(3, 3, &[1.0, 0.6, -0.3, 0.6, 1.0, 0.2, -0.3, 0.2, 1.0]);
+        let processes = CorrelatedProcesses::new(
+            vec![
+                Box::new(ArithmeticBrownianMotion::new(0.0, 1.0)),
+                Box::new(ArithmeticBrownianMotion::new(0.1, 2.0)),
+                Box::new(GeometricBrownianMotion::new(0.0, 0.01)),
+            ],
+            correlation.clone(),
+        )
+        .unwrap();
+        let output = processes.euler_maruyama_with_streams(
+            &[0.0, 0.0, 1.0],
+            0.0,
+            1.0,
+            1,
+            100_000,
+            true,
+            &SeededStreams::new(17),
+        );
+
+        // Sample correlation of the terminal values, all nearly Gaussian.
+        let terminal: Vec<Vec<f64>> = output
+            .iter()
+            .map(|trajectories| trajectories.paths.iter().map(|path| path[1]).collect())
+            .collect();
+        let sample_correlation = |x: &[f64], y: &[f64]| {
+            let m = x.len() as f64;
+            let (mx, my) = (x.iter().sum::<f64>() / m, y.iter().sum::<f64>() / m);
+            let cov: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+            let (vx, vy): (f64, f64) = (
+                x.iter().map(|a| (a - mx).powi(2)).sum(),
+                y.iter().map(|b| (b - my).powi(2)).sum(),
+            );
+            cov / (vx * vy).sqrt()
+        };
+
+        for i in 0..3 {
+            for j in 0..i {
+                assert_approx_equal!(
+                    sample_correlation(&terminal[i], &terminal[j]),
+                    correlation[(i, j)],
+                    0.01
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair() {
+        let invalid =
+            DMatrix::from_row_slice(3, 3, &[1.0, 0.9, 0.7, 0.9, 1.0, -0.4, 0.7, -0.4, 1.0]);
+        assert!(invalid.clone().cholesky().is_none());
+
+        let processes = || -> Vec<Box<dyn StochasticProcess>> {
+            (0..3)
+                .map(|_| {
+                    Box::new(ArithmeticBrownianMotion::new(0.0, 1.0)) as Box<dyn StochasticProcess>
+                })
+                .collect()
+        };
+        let repaired = CorrelatedProcesses::new(processes(), invalid.clone()).unwrap();
+
+        // Symmetric, unit diagonal, positive definite, and close to the input.
+        let c = &repaired.correlation;
+        assert!(c.clone().cholesky().is_some());
+        assert_approx_equal!((c - c.transpose()).norm(), 0.0, 1e-12);
+        assert!((0..3).all(|i| (c[(i, i)] - 1.0).abs() < 1e-12));
+        // Nearer than clipping the eigenvalues and rescaling the diagonal.
+        let clipped = clip_eigenvalues(&invalid, 0.0);
+        let naive = DMatrix::from_fn(3, 3, |i, j| {
+            clipped[(i, j)] / (clipped[(i, i)] * clipped[(j, j)]).sqrt()
+        });
+        assert!((c - &invalid).norm() < (&naive - &invalid).norm());
+
+        // A valid matrix is its own nearest correlation matrix.
+        let valid = DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]);
+        assert_approx_equal!(
+            (nearest_correlation_matrix(&valid) - &valid).norm(),
+            0.0,
+            1e-6
+        );
+
+        // Errors.
+        assert_eq!(
+            CorrelatedProcesses::new(processes(), valid).err(),
+            Some(CorrelationError::DimensionMismatch)
+        );
+        let asymmetric =
+            DMatrix::from_row_slice(3, 3, &[1.0, 0.5, 0.0, 0.4, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            CorrelatedProcesses::new(processes(), asymmetric).err(),
+            Some(CorrelationError::InvalidCorrelation)
+        );
+    }
+}
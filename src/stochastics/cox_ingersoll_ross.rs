@@ -8,6 +8,8 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::stochastics::{StochasticProcess, TimeDependent};
+use rand::{Rng, RngCore};
+use rand_distr::{ChiSquared, Distribution, Poisson, StandardNormal};
 
 /// Struct containing the Ornstein-Uhlenbeck process parameters.
 #[derive(Debug)]
@@ -48,6 +50,34 @@ impl StochasticProcess for CoxIngersollRoss {
         self.sigma.0(t) * x.sqrt()
     }
 
+    fn diffusion_derivative(&self, x: f64, t: f64) -> f64 {
+        0.5 * self.sigma.0(t) / x.sqrt()
+    }
+
+    fn exact_step(&self, x: f64, t: f64, dt: f64, rng: &mut dyn RngCore) -> Option<f64> {
+        // Scaled noncentral chi-squared transition, with `degrees` degrees of
+        // freedom and noncentrality `noncentrality`.
+        let (mu, sigma, theta) = (self.mu.0(t), self.sigma.0(t), self.theta.0(t));
+        let decay = (-theta * dt).exp();
+        let scale = sigma * sigma * (1.0 - decay) / (4.0 * theta);
+        let degrees = 4.0 * theta * mu / (sigma * sigma);
+        let noncentrality = x.max(0.0) * decay / scale;
+
+        let chi_squared = |k: f64, rng: &mut dyn RngCore| {
+            ChiSquared::new(k).map_or(0.0, |chi_squared| chi_squared.sample(rng))
+        };
+        let sample = if degrees > 1.0 {
+            let z: f64 = rng.sample(StandardNormal);
+            (z + noncentrality.sqrt()).powi(2) + chi_squared(degrees - 1.0, rng)
+        } else {
+            // Poisson mixture of central chi-squared variables.
+            let n = Poisson::new(0.5 * noncentrality).map_or(0.0, |poisson| poisson.sample(rng));
+            chi_squared(degrees + 2.0 * n, rng)
+        };
+
+        Some(scale * sample)
+    }
+
     fn jump(&self, _x: f64, _t: f64) -> Option<f64> {
         None
     }
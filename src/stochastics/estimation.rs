@@ -0,0 +1,312 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Parameter estimation from historical data.
+//!
+//! The parameters are estimated from a time series observed at a regular
+//! interval `dt` (in years, for annualised parameters):
+//!
+//! - Geometric Brownian motion: maximum likelihood, from the log returns,
+//!   which are i.i.d. Gaussian.
+//! - Ornstein-Uhlenbeck: exact maximum likelihood. The exact transition is
+//!   the Gaussian AR(1) regression $X_{i+1} = a + b X_i + \epsilon_i$, with
+//!   $b = e^{-\theta \Delta t}$, $a = \mu (1 - b)$ and
+//!   $\mathrm{Var}(\epsilon) = \sigma^2 (1 - b^2) / (2 \theta)$, fitted by
+//!   least squares.
+//! - Cox-Ingersoll-Ross: least squares on the Euler discretisation,
+//!   normalised by $\sqrt{X_i}$ to make the errors homoscedastic:
+//!   $\frac{X_{i+1} - X_i}{\sqrt{X_i}} = \frac{\theta \mu \Delta t}{\sqrt{X_i}}
+//!   - \theta \sqrt{X_i} \Delta t + \sigma \sqrt{\Delta t} \epsilon_i$.
+//!   The discretisation bias vanishes as `dt` goes to zero.
+//!
+//! ```
+//! use RustQuant::stochastics::*;
+//!
+//! // Weekly observations of a short rate.
+//! let rates = [0.031, 0.032, 0.030, 0.029, 0.031, 0.033, 0.032, 0.034, 0.033, 0.031];
+//!
+//! let ou = OrnsteinUhlenbeck::fit(&rates, 1.0 / 52.0).unwrap();
+//! let cir = CoxIngersollRoss::fit(&rates, 1.0 / 52.0).unwrap();
+//!
+//! // The fitted processes can be simulated directly.
+//! let paths = ou.euler_maruyama(0.031, 0.0, 1.0, 52, 10, false);
+//! ```
+
+use crate::stochastics::{CoxIngersollRoss, GeometricBrownianMotion, OrnsteinUhlenbeck};
+
+/// Parameter estimation errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum EstimationError {
+    /// Too few observations to estimate the parameters.
+    #[error("Not enough observations to estimate the parameters")]
+    NotEnoughObservations,
+
+    /// An observation is not positive (or not finite) where the process is.
+    #[error("Observations must be positive and finite")]
+    NonPositiveObservation,
+
+    /// The observations do not revert to a mean.
+    #[error("No mean reversion in the observations")]
+    NoMeanReversion,
+}
+
+impl GeometricBrownianMotion {
+    /// Maximum likelihood estimates of the drift and volatility from
+    /// observations at the interval `dt`.
+    ///
+    /// # Errors
+    ///
+    /// - `EstimationError::NotEnoughObservations` with fewer than three
+    ///   observations.
+    /// - `EstimationError::NonPositiveObservation` if an observation is not
+    ///   positive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is not positive.
+    pub fn fit(observations: &[f64], dt: f64) -> Result<Self, EstimationError> {
+        validate(observations, dt, true)?;
+
+        let returns: Vec<f64> = observations
+            .windows(2)
+            .map(|w| (w[1] / w[0]).ln())
+            .collect();
+        let n = returns.len() as f64;
+
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+
+        let sigma = (variance / dt).sqrt();
+        let mu = mean / dt + 0.5 * sigma * sigma;
+
+        Ok(Self::new(mu, sigma))
+    }
+}
+
+impl OrnsteinUhlenbeck {
+    /// Exact maximum likelihood estimates of the long-run mean, volatility
+    /// and speed of mean reversion from observations at the interval `dt`.
+    ///
+    /// # Errors
+    ///
+    /// - `EstimationError::NotEnoughObservations` with fewer than three
+    ///   observations.
+    /// - `EstimationError::NonPositiveObservation` if an observation is not
+    ///   finite.
+    /// - `EstimationError::NoMeanReversion` if the fitted autocorrelation is
+    ///   not in `(0, 1)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is not positive.
+    pub fn fit(observations: &[f64], dt: f64) -> Result<Self, EstimationError> {
+        validate(observations, dt, false)?;
+
+        let (x, y) = (&observations[..observations.len() - 1], &observations[1..]);
+        let (a, b, residual_variance) = autoregression(x, y);
+
+        if !(b > 0.0 && b < 1.0) {
+            return Err(EstimationError::NoMeanReversion);
+        }
+
+        let theta = -b.ln() / dt;
+        let mu = a / (1.0 - b);
+        let sigma = (residual_variance * 2.0 * theta / (1.0 - b * b)).sqrt();
+
+        Ok(Self::new(mu, sigma, theta))
+    }
+}
+
+impl CoxIngersollRoss {
+    /// Least squares estimates of the long-run mean, volatility and speed
+    /// of mean reversion from observations at the interval `dt`, on the
+    /// Euler discretisation of the process.
+    ///
+    /// # Errors
+    ///
+    /// - `EstimationError::NotEnoughObservations` with fewer than three
+    ///   observations.
+    /// - `EstimationError::NonPositiveObservation` if an observation is not
+    ///   positive.
+    /// - `EstimationError::NoMeanReversion` if the fitted speed of mean
+    ///   reversion or long-run mean is not positive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is not positive.
+    pub fn fit(observations: &[f64], dt: f64) -> Result<Self, EstimationError> {
+        validate(observations, dt, true)?;
+
+        // Regression of y = dx / sqrt(x) on z_1 = dt / sqrt(x) and
+        // z_2 = dt sqrt(x), without intercept: normal equations G beta = c.
+        let (mut g_11, mut g_12, mut g_22, mut c_1, mut c_2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for w in observations.windows(2) {
+            let root = w[0].sqrt();
+            let (y, z_1, z_2) = ((w[1] - w[0]) / root, dt / root, dt * root);
+
+            g_11 += z_1 * z_1;
+            g_12 += z_1 * z_2;
+            g_22 += z_2 * z_2;
+            c_1 += z_1 * y;
+            c_2 += z_2 * y;
+        }
+
+        let determinant = g_11 * g_22 - g_12 * g_12;
+        if determinant.abs() <= f64::EPSILON * g_11 * g_22 {
+            return Err(EstimationError::NoMeanReversion);
+        }
+
+        let beta_1 = (g_22 * c_1 - g_12 * c_2) / determinant;
+        let beta_2 = (g_11 * c_2 - g_12 * c_1) / determinant;
+
+        let theta = -beta_2;
+        let mu = beta_1 / theta;
+        if !(theta > 0.0 && mu > 0.0) {
+            return Err(EstimationError::NoMeanReversion);
+        }
+
+        let n = (observations.len() - 1) as f64;
+        let residual_variance = observations
+            .windows(2)
+            .map(|w| {
+                let root = w[0].sqrt();
+                ((w[1] - w[0]) / root - beta_1 * dt / root - beta_2 * dt * root).powi(2)
+            })
+            .sum::<f64>()
+            / n;
+        let sigma = (residual_variance / dt).sqrt();
+
+        Ok(Self::new(mu, sigma, theta))
+    }
+}
+
+// Checks there are enough (positive, if `positive`) finite observations.
+fn validate(observations: &[f64], dt: f64, positive: bool) -> Result<(), EstimationError> {
+    assert!(dt > 0.0, "The observation interval must be positive.");
+
+    if observations.len() < 3 {
+        return Err(EstimationError::NotEnoughObservations);
+    }
+    if observations
+        .iter()
+        .any(|x| !x.is_finite() || (positive && *x <= 0.0))
+    {
+        return Err(EstimationError::NonPositiveObservation);
+    }
+
+    Ok(())
+}
+
+// Least squares regression y = a + b x + e: the intercept, the slope, and
+// the maximum likelihood variance of the residuals.
+fn autoregression(x: &[f64], y: &[f64]) -> (f64, f64, f64) {
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+
+    let (mut variance_x, mut covariance) = (0.0, 0.0);
+    for (x_i, y_i) in x.iter().zip(y) {
+        variance_x += (x_i - mean_x) * (x_i - mean_x);
+        covariance += (x_i - mean_x) * (y_i - mean_y);
+    }
+
+    let b = covariance / variance_x;
+    let a = mean_y - b * mean_x;
+    let residual_variance = x
+        .iter()
+        .zip(y)
+        .map(|(x_i, y_i)| (y_i - a - b * x_i).powi(2))
+        .sum::<f64>()
+        / n;
+
+    (a, b, residual_variance)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_estimation {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::stochastics::{SeededStreams, SimulationScheme, StochasticProcess};
+
+    const DT: f64 = 0.01;
+    const N: usize = 100_000;
+
+    // A long series of the process, sampled exactly every `DT`.
+    fn series<P: StochasticProcess>(process: &P, x_0: f64) -> Vec<f64> {
+        process
+            .simulate_with_streams(
+                SimulationScheme::Exact,
+                x_0,
+                0.0,
+                DT * N as f64,
+                N,
+                1,
+                false,
+                &SeededStreams::new(21),
+            )
+            .paths
+            .remove(0)
+    }
+
+    #[test]
+    fn test_fit_gbm() {
+        let data = series(&GeometricBrownianMotion::new(0.08, 0.25), 100.0);
+        let fitted = GeometricBrownianMotion::fit(&data, DT).unwrap();
+
+        // The drift is only known to within sigma / sqrt(T) = 0.008.
+        assert_approx_equal!(fitted.sigma.0(0.0), 0.25, 0.003);
+        assert_approx_equal!(fitted.mu.0(0.0), 0.08, 0.025);
+    }
+
+    #[test]
+    fn test_fit_ou() {
+        let data = series(&OrnsteinUhlenbeck::new(0.05, 0.02, 2.0), 0.03);
+        let fitted = OrnsteinUhlenbeck::fit(&data, DT).unwrap();
+
+        assert_approx_equal!(fitted.theta.0(0.0), 2.0, 0.2);
+        assert_approx_equal!(fitted.mu.0(0.0), 0.05, 0.002);
+        assert_approx_equal!(fitted.sigma.0(0.0), 0.02, 0.0005);
+    }
+
+    #[test]
+    fn test_fit_cir() {
+        let data = series(&CoxIngersollRoss::new(0.05, 0.1, 1.5), 0.03);
+        let fitted = CoxIngersollRoss::fit(&data, DT).unwrap();
+
+        assert_approx_equal!(fitted.theta.0(0.0), 1.5, 0.2);
+        assert_approx_equal!(fitted.mu.0(0.0), 0.05, 0.003);
+        assert_approx_equal!(fitted.sigma.0(0.0), 0.1, 0.003);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            GeometricBrownianMotion::fit(&[1.0, 2.0], DT),
+            Err(EstimationError::NotEnoughObservations)
+        ));
+        assert!(matches!(
+            CoxIngersollRoss::fit(&[0.01, 0.0, 0.02], DT),
+            Err(EstimationError::NonPositiveObservation)
+        ));
+        assert!(matches!(
+            OrnsteinUhlenbeck::fit(&[1.0, f64::NAN, 2.0], DT),
+            Err(EstimationError::NonPositiveObservation)
+        ));
+
+        // A trending series does not revert.
+        let trend: Vec<f64> = (0..100).map(|i| f64::from(i).powi(2)).collect();
+        assert!(matches!(
+            OrnsteinUhlenbeck::fit(&trend, DT),
+            Err(EstimationError::NoMeanReversion)
+        ));
+    }
+}
@@ -8,6 +8,8 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::stochastics::{StochasticProcess, TimeDependent};
+use rand::{Rng, RngCore};
+use rand_distr::StandardNormal;
 
 /// Struct containing the Geometric Brownian Motion parameters.
 pub struct GeometricBrownianMotion {
@@ -40,6 +42,18 @@ impl StochasticProcess for GeometricBrownianMotion {
         self.sigma.0(t) * x
     }
 
+    fn diffusion_derivative(&self, _x: f64, t: f64) -> f64 {
+        self.sigma.0(t)
+    }
+
+    fn exact_step(&self, x: f64, t: f64, dt: f64, rng: &mut dyn RngCore) -> Option<f64> {
+        // Lognormal transition.
+        let (mu, sigma) = (self.mu.0(t), self.sigma.0(t));
+        let z: f64 = rng.sample(StandardNormal);
+
+        Some(x * ((mu - 0.5 * sigma * sigma) * dt + sigma * dt.sqrt() * z).exp())
+    }
+
     fn jump(&self, _x: f64, _t: f64) -> Option<f64> {
         None
     }
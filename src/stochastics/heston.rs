@@ -0,0 +1,418 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Heston (1993) stochastic volatility process:
+//!
+//! $$
+//! dS_t = \mu S_t dt + \sqrt{v_t} S_t dW_t^S, \qquad
+//! dv_t = \kappa (\theta - v_t) dt + \sigma \sqrt{v_t} dW_t^v, \qquad
+//! d\langle W^S, W^v \rangle_t = \rho dt
+//! $$
+//!
+//! An Euler scheme lets the variance go negative, and needs truncation
+//! which biases it. The Quadratic-Exponential (QE) scheme of Andersen
+//! (2008) instead samples the variance from a distribution matching the
+//! first two moments of its (non-central chi-squared) transition: a
+//! squared Gaussian when the variance is high, and a mixture of a point
+//! mass at zero and an exponential when it is low. The variance stays
+//! non-negative even when the Feller condition $2 \kappa \theta \geq
+//! \sigma^2$ fails. The log spot is then sampled conditionally on both
+//! variances, with the integral of the variance over the step
+//! approximated by the trapezoidal rule.
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{EntropyStreams, RandomStream, TimeDependent, Trajectories};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+/// Switching level of the QE scheme between its quadratic and exponential
+/// branches (Andersen recommends 1.5).
+const PSI_CRITICAL: f64 = 1.5;
+
+/// Struct containing the Heston process parameters.
+#[derive(Debug)]
+pub struct Heston {
+    /// The drift of the spot ($\mu$).
+    pub mu: TimeDependent,
+
+    /// Mean reversion speed of the variance ($\kappa$).
+    pub kappa: TimeDependent,
+
+    /// Long-run mean of the variance ($\theta$).
+    pub theta: TimeDependent,
+
+    /// Volatility of the variance ($\sigma$).
+    pub sigma: TimeDependent,
+
+    /// Correlation between the spot and the variance ($\rho$).
+    pub rho: f64,
+}
+
+/// Jointly simulated spot and variance trajectories of a [`Heston`]
+/// process.
+pub struct HestonTrajectories {
+    /// Spot trajectories.
+    pub spot: Trajectories,
+
+    /// Variance trajectories.
+    pub variance: Trajectories,
+}
+
+impl Heston {
+    /// Create a new Heston process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the correlation is not in `[-1, 1]`.
+    pub fn new(
+        mu: impl Into<TimeDependent>,
+        kappa: impl Into<TimeDependent>,
+        theta: impl Into<TimeDependent>,
+        sigma: impl Into<TimeDependent>,
+        rho: f64,
+    ) -> Self {
+        assert!(
+            (-1.0..=1.0).contains(&rho),
+            "The correlation must be in [-1, 1]."
+        );
+
+        Self {
+            mu: mu.into(),
+            kappa: kappa.into(),
+            theta: theta.into(),
+            sigma: sigma.into(),
+            rho,
+        }
+    }
+
+    /// Quadratic-Exponential discretisation scheme.
+    ///
+    /// # Arguments:
+    /// * `s_0` - The initial spot at `t_0`.
+    /// * `v_0` - The initial variance at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    ///
+    /// ```
+    /// use RustQuant::stochastics::*;
+    ///
+    /// // The Feller condition fails: 2 * 1.5 * 0.04 < 0.8^2.
+    /// let heston = Heston::new(0.05, 1.5, 0.04, 0.8, -0.7);
+    ///
+    /// let output = heston.quadratic_exponential(100.0, 0.04, 0.0, 1.0, 50, 100, false);
+    ///
+    /// // The variance never goes negative.
+    /// assert!(output.variance.paths.iter().flatten().all(|v| *v >= 0.0));
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn quadratic_exponential(
+        &self,
+        s_0: f64,
+        v_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> HestonTrajectories {
+        self.quadratic_exponential_with_streams(
+            s_0,
+            v_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    /// Quadratic-Exponential discretisation scheme with a choice of random
+    /// seed.
+    ///
+    /// # Arguments:
+    /// * `s_0` - The initial spot at `t_0`.
+    /// * `v_0` - The initial variance at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    #[cfg(feature = "seedable")]
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn seedable_quadratic_exponential(
+        &self,
+        s_0: f64,
+        v_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> HestonTrajectories {
+        self.quadratic_exponential_with_streams(
+            s_0,
+            v_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    /// Quadratic-Exponential discretisation scheme, where the path `i` draws
+    /// its random numbers from the stream `i` of `streams`.
+    ///
+    /// # Arguments:
+    /// * `s_0` - The initial spot at `t_0`.
+    /// * `v_0` - The initial variance at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`, `s_0 <= 0` or `v_0 < 0`.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn quadratic_exponential_with_streams<S: RandomStream>(
+        &self,
+        s_0: f64,
+        v_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> HestonTrajectories {
+        assert!(t_0 < t_n);
+        assert!(s_0 > 0.0 && v_0 >= 0.0);
+
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+        let path_generator = |path: usize| {
+            let mut rng = streams.stream(path as u64);
+
+            let mut spot = vec![s_0; n_steps + 1];
+            let mut variance = vec![v_0; n_steps + 1];
+
+            for t in 0..n_steps {
+                let v = self.variance_step(variance[t], times[t], dt, &mut rng);
+                let x = self.log_spot_step(spot[t].ln(), variance[t], v, times[t], dt, &mut rng);
+
+                variance[t + 1] = v;
+                spot[t + 1] = x.exp();
+            }
+
+            (spot, variance)
+        };
+
+        let (spot, variance): (Vec<Vec<f64>>, Vec<Vec<f64>>) = if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).unzip()
+        } else {
+            (0..m_paths).map(path_generator).unzip()
+        };
+
+        HestonTrajectories {
+            spot: Trajectories {
+                times: times.clone(),
+                paths: spot,
+            },
+            variance: Trajectories {
+                times,
+                paths: variance,
+            },
+        }
+    }
+
+    // Samples the variance at `t + dt` from the variance `v` at `t`.
+    fn variance_step<R: Rng>(&self, v: f64, t: f64, dt: f64, rng: &mut R) -> f64 {
+        let (kappa, theta, sigma) = (self.kappa.0(t), self.theta.0(t), self.sigma.0(t));
+        let decay = (-kappa * dt).exp();
+
+        // Conditional mean and variance of the exact transition.
+        let m = theta + (v - theta) * decay;
+        let s2 = v * sigma * sigma * decay * (1.0 - decay) / kappa
+            + theta * sigma * sigma * (1.0 - decay).powi(2) / (2.0 * kappa);
+        let psi = s2 / (m * m);
+
+        if psi <= PSI_CRITICAL {
+            // Quadratic: a (b + Z)^2.
+            let b2 = 2.0 / psi - 1.0 + (2.0 / psi).sqrt() * (2.0 / psi - 1.0).sqrt();
+            let a = m / (1.0 + b2);
+            let z: f64 = rng.sample(StandardNormal);
+
+            a * (b2.sqrt() + z).powi(2)
+        } else {
+            // Exponential, with a point mass p at zero.
+            let p = (psi - 1.0) / (psi + 1.0);
+            let beta = (1.0 - p) / m;
+            let u: f64 = rng.gen();
+
+            if u <= p {
+                0.0
+            } else {
+                ((1.0 - p) / (1.0 - u)).ln() / beta
+            }
+        }
+    }
+
+    // Samples the log spot at `t + dt`, given the variances `v` at `t` and
+    // `v_next` at `t + dt`.
+    fn log_spot_step<R: Rng>(
+        &self,
+        x: f64,
+        v: f64,
+        v_next: f64,
+        t: f64,
+        dt: f64,
+        rng: &mut R,
+    ) -> f64 {
+        let (mu, kappa, theta, sigma) = (
+            self.mu.0(t),
+            self.kappa.0(t),
+            self.theta.0(t),
+            self.sigma.0(t),
+        );
+        let rho = self.rho;
+
+        // Andersen's K_0, ..., K_4 with central weights.
+        let (gamma_1, gamma_2) = (0.5, 0.5);
+        let k_0 = -rho * kappa * theta * dt / sigma;
+        let k_1 = gamma_1 * dt * (kappa * rho / sigma - 0.5) - rho / sigma;
+        let k_2 = gamma_2 * dt * (kappa * rho / sigma - 0.5) + rho / sigma;
+        let k_3 = gamma_1 * dt * (1.0 - rho * rho);
+        let k_4 = gamma_2 * dt * (1.0 - rho * rho);
+
+        let z: f64 = rng.sample(StandardNormal);
+
+        x + mu * dt + k_0 + k_1 * v + k_2 * v_next + (k_3 * v + k_4 * v_next).sqrt() * z
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_heston_process {
+    use super::*;
+    use crate::instruments::{Heston as HestonPricer, TypeFlag};
+    use crate::stochastics::SeededStreams;
+    use crate::{assert_approx_equal, statistics::*};
+
+    #[test]
+    fn test_moments() {
+        // The Feller condition fails.
+        let (mu, kappa, theta, sigma, v_0) = (0.03, 1.5, 0.04, 0.8, 0.09);
+        let heston = Heston::new(mu, kappa, theta, sigma, -0.7);
+        let output = heston.quadratic_exponential_with_streams(
+            100.0,
+            v_0,
+            0.0,
+            2.0,
+            40,
+            20_000,
+            true,
+            &SeededStreams::new(42),
+        );
+
+        assert!(output.variance.paths.iter().flatten().all(|v| *v >= 0.0));
+
+        let last = |trajectories: &Trajectories| -> Vec<f64> {
+            trajectories
+                .paths
+                .iter()
+                .filter_map(|path| path.last().copied())
+                .collect()
+        };
+
+        // E[v_T] = theta + (v_0 - theta) exp(-kappa T), and E[S_T] = S_0 exp(mu T).
+        assert_approx_equal!(
+            last(&output.variance).mean(),
+            theta + (v_0 - theta) * (-kappa * 2.0_f64).exp(),
+            0.002
+        );
+        assert_approx_equal!(last(&output.spot).mean(), 100.0 * (mu * 2.0_f64).exp(), 0.5);
+    }
+
+    #[test]
+    fn test_call_price() {
+        // Monte Carlo price of a call against the semi-analytic price.
+        let (r, kappa, theta, sigma, rho, v_0) = (0.03, 2.0, 0.04, 0.5, -0.7, 0.05);
+        let heston = Heston::new(r, kappa, theta, sigma, rho);
+        let output = heston.quadratic_exponential_with_streams(
+            100.0,
+            v_0,
+            0.0,
+            1.0,
+            20,
+            50_000,
+            true,
+            &SeededStreams::new(7),
+        );
+
+        let payoffs: Vec<f64> = output
+            .spot
+            .paths
+            .iter()
+            .map(|path| (path[20] - 100.0).max(0.0))
+            .collect();
+        let price = f64::exp(-r) * payoffs.mean();
+
+        let pricer = HestonPricer::new(100.0, r, 0.0, 1.0, kappa, theta, sigma, rho, v_0);
+        assert_approx_equal!(price, pricer.price(100.0, TypeFlag::Call), 0.15);
+    }
+
+    #[test]
+    fn test_seeded_paths() {
+        let heston = Heston::new(0.05, 1.0, 0.04, 0.3, 0.0);
+        let first = heston.quadratic_exponential_with_streams(
+            100.0,
+            0.04,
+            0.0,
+            1.0,
+            10,
+            4,
+            false,
+            &SeededStreams::new(1),
+        );
+        let second = heston.quadratic_exponential_with_streams(
+            100.0,
+            0.04,
+            0.0,
+            1.0,
+            10,
+            4,
+            true,
+            &SeededStreams::new(1),
+        );
+
+        // The same seed gives the same paths, serially or in parallel, but
+        // each path has its own draws.
+        assert_eq!(first.spot.paths, second.spot.paths);
+        assert_ne!(first.spot.paths[0], first.spot.paths[1]);
+    }
+}
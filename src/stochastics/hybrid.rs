@@ -0,0 +1,356 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Equity with stochastic interest rates.
+//!
+//! Under the risk-neutral measure, the spot grows at the short rate, which
+//! follows its own (typically Hull-White) process, correlated with the spot:
+//!
+//! $$
+//! \begin{aligned}
+//! dS(t) &= \left[ r(t) - q(t) \right] S(t) dt + \sigma(t) S(t) dW_S(t) \\\\
+//! dr(t) &= a(r(t), t) dt + b(r(t), t) dW_r(t) \\\\
+//! dW_S(t) dW_r(t) &= \rho dt
+//! \end{aligned}
+//! $$
+//!
+//! The short rate is simulated with the Euler-Maruyama scheme, the spot with
+//! the log-Euler scheme, and the stochastic discount factor
+//! $D(t) = e^{-\int_{t_0}^t r(s) ds}$ with the trapezoidal rule. Long-dated
+//! equity payoffs, e.g. autocallables, are then priced by averaging
+//! $D(t_i) \times$ the cashflow at $t_i$ over the paths.
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{
+    EntropyStreams, RandomStream, StochasticProcess, TimeDependent, Trajectories,
+};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+/// Equity process with a stochastic short rate.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+///
+/// // Hull-White short rate: dr = (0.003 - 0.1 r) dt + 0.01 dW.
+/// let short_rate = HullWhite::new(0.1, 0.01, 0.003);
+/// let hybrid = EquityRateHybrid::new(short_rate, 0.2, 0.0, 0.3);
+///
+/// let output = hybrid.euler_maruyama(100.0, 0.03, 0.0, 10.0, 120, 10_000, true);
+///
+/// // Ten-year at-the-money call, discounted along each path.
+/// let price = output
+///     .spot
+///     .paths
+///     .iter()
+///     .zip(&output.discount.paths)
+///     .map(|(spot, discount)| discount[120] * (spot[120] - 100.0).max(0.0))
+///     .sum::<f64>()
+///     / 10_000.0;
+///
+/// assert!(price > 30.0 && price < 50.0);
+/// ```
+pub struct EquityRateHybrid<P: StochasticProcess> {
+    /// The short rate process.
+    pub short_rate: P,
+
+    /// Volatility of the spot ($\sigma$).
+    pub volatility: TimeDependent,
+
+    /// Continuous dividend yield of the spot ($q$).
+    pub dividend_yield: TimeDependent,
+
+    /// Correlation between the spot and the short rate ($\rho$).
+    pub rho: f64,
+}
+
+/// Jointly simulated spot, short rate and discount factor trajectories of an
+/// [`EquityRateHybrid`] process.
+pub struct HybridTrajectories {
+    /// Spot trajectories.
+    pub spot: Trajectories,
+
+    /// Short rate trajectories.
+    pub short_rate: Trajectories,
+
+    /// Stochastic discount factors from `t_0`: $e^{-\int_{t_0}^t r(s) ds}$.
+    pub discount: Trajectories,
+}
+
+impl<P: StochasticProcess> EquityRateHybrid<P> {
+    /// Create a new equity process with the stochastic short rate
+    /// `short_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the correlation is not in `[-1, 1]`.
+    pub fn new(
+        short_rate: P,
+        volatility: impl Into<TimeDependent>,
+        dividend_yield: impl Into<TimeDependent>,
+        rho: f64,
+    ) -> Self {
+        assert!(
+            (-1.0..=1.0).contains(&rho),
+            "The correlation must be in [-1, 1]."
+        );
+
+        Self {
+            short_rate,
+            volatility: volatility.into(),
+            dividend_yield: dividend_yield.into(),
+            rho,
+        }
+    }
+
+    /// Euler-Maruyama discretisation scheme of the short rate, and log-Euler
+    /// scheme of the spot.
+    ///
+    /// # Arguments:
+    /// * `s_0` - The initial spot at `t_0`.
+    /// * `r_0` - The initial short rate at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn euler_maruyama(
+        &self,
+        s_0: f64,
+        r_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> HybridTrajectories {
+        self.euler_maruyama_with_streams(
+            s_0,
+            r_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    /// Euler-Maruyama discretisation scheme of the short rate, and log-Euler
+    /// scheme of the spot, with a choice of random seed.
+    ///
+    /// # Arguments:
+    /// * `s_0` - The initial spot at `t_0`.
+    /// * `r_0` - The initial short rate at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    #[cfg(feature = "seedable")]
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn seedable_euler_maruyama(
+        &self,
+        s_0: f64,
+        r_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> HybridTrajectories {
+        self.euler_maruyama_with_streams(
+            s_0,
+            r_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    /// Euler-Maruyama discretisation scheme of the short rate, and log-Euler
+    /// scheme of the spot, where the path `i` draws its random numbers from
+    /// the stream `i` of `streams`.
+    ///
+    /// # Arguments:
+    /// * `s_0` - The initial spot at `t_0`.
+    /// * `r_0` - The initial short rate at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n` or `s_0 <= 0`.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn euler_maruyama_with_streams<S: RandomStream>(
+        &self,
+        s_0: f64,
+        r_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> HybridTrajectories {
+        assert!(t_0 < t_n);
+        assert!(s_0 > 0.0);
+
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+        let rho_perp = (1.0 - self.rho * self.rho).sqrt();
+
+        let path_generator = |path: usize| {
+            let mut rng = streams.stream(path as u64);
+
+            let mut spot = vec![s_0; n_steps + 1];
+            let mut rate = vec![r_0; n_steps + 1];
+            let mut discount = vec![1.0; n_steps + 1];
+
+            for t in 0..n_steps {
+                let z_r: f64 = rng.sample(StandardNormal);
+                let z_s: f64 = rng.sample(StandardNormal);
+                let z_s = self.rho * z_r + rho_perp * z_s;
+
+                let (r, time) = (rate[t], times[t]);
+                rate[t + 1] = r
+                    + self.short_rate.drift(r, time) * dt
+                    + self.short_rate.diffusion(r, time) * dt.sqrt() * z_r;
+
+                let integral = 0.5 * (r + rate[t + 1]) * dt;
+                let sigma = self.volatility.0(time);
+                spot[t + 1] = spot[t]
+                    * (integral - (self.dividend_yield.0(time) + 0.5 * sigma * sigma) * dt
+                        + sigma * dt.sqrt() * z_s)
+                        .exp();
+                discount[t + 1] = discount[t] * (-integral).exp();
+            }
+
+            (spot, (rate, discount))
+        };
+
+        let (spot, (short_rate, discount)): (Vec<_>, (Vec<_>, Vec<_>)) = if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).unzip()
+        } else {
+            (0..m_paths).map(path_generator).unzip()
+        };
+
+        HybridTrajectories {
+            spot: Trajectories {
+                times: times.clone(),
+                paths: spot,
+            },
+            short_rate: Trajectories {
+                times: times.clone(),
+                paths: short_rate,
+            },
+            discount: Trajectories {
+                times,
+                paths: discount,
+            },
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hybrid {
+    use super::*;
+    use crate::models::{ShortRateModel, VasicekModel};
+    use crate::stochastics::{HullWhite, SeededStreams};
+    use crate::{assert_approx_equal, statistics::*};
+
+    // Hull-White with a constant drift: Vasicek, with analytic bond prices.
+    const A: f64 = 0.2;
+    const LONG_RUN: f64 = 0.04;
+    const SIGMA_R: f64 = 0.015;
+
+    fn simulate(rho: f64) -> HybridTrajectories {
+        let hybrid =
+            EquityRateHybrid::new(HullWhite::new(A, SIGMA_R, A * LONG_RUN), 0.25, 0.01, rho);
+
+        hybrid.euler_maruyama_with_streams(
+            100.0,
+            0.02,
+            0.0,
+            10.0,
+            200,
+            50_000,
+            true,
+            &SeededStreams::new(3),
+        )
+    }
+
+    #[test]
+    fn test_discounting() {
+        let output = simulate(-0.4);
+        let vasicek = VasicekModel::new(0.02, A, LONG_RUN, SIGMA_R);
+
+        // The discount factors average to the zero-coupon bond prices, and
+        // the discounted spot (with dividends) is a martingale.
+        for t in [40, 200] {
+            let time = output.discount.times[t];
+            let discount: Vec<f64> = output.discount.paths.iter().map(|d| d[t]).collect();
+            let discounted_spot: Vec<f64> = output
+                .spot
+                .paths
+                .iter()
+                .zip(&output.discount.paths)
+                .map(|(s, d)| s[t] * d[t])
+                .collect();
+
+            assert_approx_equal!(discount.mean(), vasicek.discount_factor(time), 0.002);
+            assert_approx_equal!(discounted_spot.mean(), 100.0 * (-0.01 * time).exp(), 0.6);
+        }
+    }
+
+    #[test]
+    fn test_correlation() {
+        for rho in [-0.6, 0.0, 0.6] {
+            let output = simulate(rho);
+
+            // Correlation of the first increments of the log spot and the
+            // short rate.
+            let (x, y): (Vec<f64>, Vec<f64>) = output
+                .spot
+                .paths
+                .iter()
+                .zip(&output.short_rate.paths)
+                .map(|(s, r)| ((s[1] / s[0]).ln(), r[1] - r[0]))
+                .unzip();
+            let (mean_x, mean_y) = (x.mean(), y.mean());
+            let covariance = x
+                .iter()
+                .zip(&y)
+                .map(|(x, y)| (x - mean_x) * (y - mean_y))
+                .sum::<f64>()
+                / (x.len() as f64 - 1.0);
+
+            assert_approx_equal!(covariance / (x.variance() * y.variance()).sqrt(), rho, 0.02);
+        }
+    }
+}
@@ -0,0 +1,481 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Compound Poisson jumps added to any diffusion process.
+//!
+//! Jumps arrive with intensity $\lambda$, and each has a size $Y$ drawn
+//! from a [`JumpSize`] distribution: Gaussian (Merton, 1976) or double
+//! exponential (Kou, 2002). A jump moves the process either by $Y$
+//! ([`JumpEffect::Additive`]) or by the factor $e^Y$
+//! ([`JumpEffect::Multiplicative`], for prices):
+//!
+//! $$
+//! dX_t = \mu(X_t, t) dt + \sigma(X_t, t) dW_t + X_{t^-} (e^Y - 1) dN_t
+//! $$
+//!
+//! The jumps are not compensated: for a martingale (risk-neutral) price,
+//! subtract $\lambda \kappa$ from the drift rate of the diffusion, with
+//! $\kappa = E[e^Y] - 1$ given by [`JumpSize::mean_relative_jump`].
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{
+    EntropyStreams, RandomStream, SimulationScheme, StochasticProcess, Trajectories,
+};
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Exp, Normal, Poisson};
+use rayon::prelude::*;
+
+/// Distribution of the (log) jump sizes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JumpSize {
+    /// Merton (1976): Gaussian jumps.
+    Merton {
+        /// Mean of the jumps.
+        mean: f64,
+        /// Standard deviation of the jumps.
+        std_dev: f64,
+    },
+    /// Kou (2002): double exponential jumps.
+    Kou {
+        /// Probability of an upward jump.
+        p_up: f64,
+        /// Rate of the upward jumps ($\eta_1$, the inverse of their mean).
+        eta_up: f64,
+        /// Rate of the downward jumps ($\eta_2$).
+        eta_down: f64,
+    },
+}
+
+/// How a jump of size $Y$ moves the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpEffect {
+    /// $X \to X + Y$.
+    Additive,
+    /// $X \to X e^Y$.
+    Multiplicative,
+}
+
+/// A diffusion process with compound Poisson jumps.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+///
+/// // GBM with Kou jumps: 2 jumps a year, 30% up with mean 5%, down with mean 10%.
+/// let kou = JumpDiffusion::new(
+///     GeometricBrownianMotion::new(0.05, 0.2),
+///     2.0,
+///     JumpSize::Kou { p_up: 0.3, eta_up: 20.0, eta_down: 10.0 },
+///     JumpEffect::Multiplicative,
+/// );
+///
+/// let output = kou.euler_maruyama(100.0, 0.0, 1.0, 250, 100, false);
+///
+/// assert!(output.paths.iter().flatten().all(|x| *x > 0.0));
+/// ```
+#[derive(Debug)]
+pub struct JumpDiffusion<P: StochasticProcess> {
+    /// The diffusion between the jumps.
+    pub process: P,
+
+    /// Intensity of the jumps ($\lambda$), per unit of time.
+    pub intensity: f64,
+
+    /// Distribution of the jump sizes.
+    pub jump_size: JumpSize,
+
+    /// How a jump moves the process.
+    pub effect: JumpEffect,
+}
+
+impl JumpSize {
+    /// Samples a jump size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the standard deviation is negative, or a rate is not
+    /// positive.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Self::Merton { mean, std_dev } => Normal::new(mean, std_dev)
+                .expect("The standard deviation must be non-negative.")
+                .sample(rng),
+            Self::Kou {
+                p_up,
+                eta_up,
+                eta_down,
+            } => {
+                if rng.gen::<f64>() < p_up {
+                    Exp::new(eta_up)
+                        .expect("The rate must be positive.")
+                        .sample(rng)
+                } else {
+                    -Exp::new(eta_down)
+                        .expect("The rate must be positive.")
+                        .sample(rng)
+                }
+            }
+        }
+    }
+
+    /// Mean of the jump sizes, $E[Y]$.
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Self::Merton { mean, .. } => mean,
+            Self::Kou {
+                p_up,
+                eta_up,
+                eta_down,
+            } => p_up / eta_up - (1.0 - p_up) / eta_down,
+        }
+    }
+
+    /// Second moment of the jump sizes, $E[Y^2]$.
+    #[must_use]
+    pub fn second_moment(&self) -> f64 {
+        match *self {
+            Self::Merton { mean, std_dev } => mean * mean + std_dev * std_dev,
+            Self::Kou {
+                p_up,
+                eta_up,
+                eta_down,
+            } => 2.0 * p_up / (eta_up * eta_up) + 2.0 * (1.0 - p_up) / (eta_down * eta_down),
+        }
+    }
+
+    /// Mean relative jump of a multiplicative jump, $\kappa = E[e^Y] - 1$
+    /// (infinite for Kou jumps with $\eta_1 \leq 1$).
+    #[must_use]
+    pub fn mean_relative_jump(&self) -> f64 {
+        match *self {
+            Self::Merton { mean, std_dev } => (mean + 0.5 * std_dev * std_dev).exp() - 1.0,
+            Self::Kou {
+                p_up,
+                eta_up,
+                eta_down,
+            } => {
+                if eta_up <= 1.0 {
+                    return f64::INFINITY;
+                }
+                p_up * eta_up / (eta_up - 1.0) + (1.0 - p_up) * eta_down / (eta_down + 1.0) - 1.0
+            }
+        }
+    }
+}
+
+impl<P: StochasticProcess> JumpDiffusion<P> {
+    /// Adds jumps to a diffusion process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the intensity is negative.
+    pub fn new(process: P, intensity: f64, jump_size: JumpSize, effect: JumpEffect) -> Self {
+        assert!(intensity >= 0.0, "The intensity must be non-negative.");
+
+        Self {
+            process,
+            intensity,
+            jump_size,
+            effect,
+        }
+    }
+
+    // Moves `x` by a jump of size `y`.
+    fn apply(&self, x: f64, y: f64) -> f64 {
+        match self.effect {
+            JumpEffect::Additive => x + y,
+            JumpEffect::Multiplicative => x * y.exp(),
+        }
+    }
+
+    // Steps of the diffusion with `scheme`, followed by the jumps arrived
+    // over each step; each path draws from the random number stream of its
+    // index.
+    #[allow(clippy::too_many_arguments)]
+    fn simulate_with_jumps<S: RandomStream>(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> Trajectories {
+        assert!(t_0 < t_n);
+
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+        let arrivals = (self.intensity > 0.0)
+            .then(|| Poisson::new(self.intensity * dt).expect("The intensity is positive."));
+
+        let path_generator = |path: usize| {
+            let mut rng = streams.stream(path as u64);
+            let mut x = vec![x_0; n_steps + 1];
+            for t in 0..n_steps {
+                let mut next = scheme.step(&self.process, x[t], times[t], dt, &mut rng);
+
+                let mut jumps: f64 = arrivals.map_or(0.0, |poisson| poisson.sample(&mut rng));
+                while jumps >= 1.0 {
+                    next = self.apply(next, self.jump_size.sample(&mut rng));
+                    jumps -= 1.0;
+                }
+
+                x[t + 1] = next;
+            }
+
+            x
+        };
+
+        let paths = if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).collect()
+        } else {
+            (0..m_paths).map(path_generator).collect()
+        };
+
+        Trajectories { times, paths }
+    }
+}
+
+impl<P: StochasticProcess> StochasticProcess for JumpDiffusion<P> {
+    fn drift(&self, x: f64, t: f64) -> f64 {
+        self.process.drift(x, t)
+    }
+
+    fn diffusion(&self, x: f64, t: f64) -> f64 {
+        self.process.diffusion(x, t)
+    }
+
+    fn diffusion_derivative(&self, x: f64, t: f64) -> f64 {
+        self.process.diffusion_derivative(x, t)
+    }
+
+    fn exact_step(&self, x: f64, t: f64, dt: f64, rng: &mut dyn RngCore) -> Option<f64> {
+        self.process.exact_step(x, t, dt, rng)
+    }
+
+    fn jump(&self, x: f64, _t: f64) -> Option<f64> {
+        let y = self.jump_size.sample(&mut rand::thread_rng());
+
+        Some(self.apply(x, y) - x)
+    }
+
+    fn euler_maruyama(
+        &self,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> Trajectories {
+        self.simulate_with_jumps(
+            SimulationScheme::EulerMaruyama,
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    #[cfg(feature = "seedable")]
+    fn seedable_euler_maruyama(
+        &self,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> Trajectories {
+        self.simulate_with_jumps(
+            SimulationScheme::EulerMaruyama,
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    fn simulate(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> Trajectories {
+        self.simulate_with_jumps(
+            scheme,
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    #[cfg(feature = "seedable")]
+    fn seedable_simulate(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> Trajectories {
+        self.simulate_with_jumps(
+            scheme,
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    fn simulate_with_streams<S: RandomStream>(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> Trajectories {
+        self.simulate_with_jumps(scheme, x_0, t_0, t_n, n_steps, m_paths, parallel, streams)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_jump_diffusion {
+    use super::*;
+    use crate::stochastics::{ArithmeticBrownianMotion, GeometricBrownianMotion, SeededStreams};
+    use crate::{assert_approx_equal, statistics::*};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const KOU: JumpSize = JumpSize::Kou {
+        p_up: 0.4,
+        eta_up: 10.0,
+        eta_down: 5.0,
+    };
+
+    fn terminal(trajectories: &Trajectories) -> Vec<f64> {
+        trajectories
+            .paths
+            .iter()
+            .filter_map(|path| path.last().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_jump_moments() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let merton = JumpSize::Merton {
+            mean: -0.1,
+            std_dev: 0.15,
+        };
+
+        for jump_size in [merton, KOU] {
+            let draws: Vec<f64> = (0..200_000).map(|_| jump_size.sample(&mut rng)).collect();
+            let relative: Vec<f64> = draws.iter().map(|y| y.exp() - 1.0).collect();
+
+            assert_approx_equal!(draws.mean(), jump_size.mean(), 2e-3);
+            assert_approx_equal!(
+                draws.iter().map(|y| y * y).collect::<Vec<f64>>().mean(),
+                jump_size.second_moment(),
+                2e-3
+            );
+            assert_approx_equal!(relative.mean(), jump_size.mean_relative_jump(), 2e-3);
+        }
+    }
+
+    #[test]
+    fn test_additive_jumps() {
+        // Arithmetic Brownian motion with Kou jumps:
+        // E[X_T] = x_0 + (mu + lambda E[Y]) T, V[X_T] = (sigma^2 + lambda E[Y^2]) T.
+        let (mu, sigma, lambda) = (0.1, 0.2, 3.0);
+        let process = JumpDiffusion::new(
+            ArithmeticBrownianMotion::new(mu, sigma),
+            lambda,
+            KOU,
+            JumpEffect::Additive,
+        );
+        let output = process.simulate_with_jumps(
+            SimulationScheme::EulerMaruyama,
+            1.0,
+            0.0,
+            2.0,
+            100,
+            50_000,
+            true,
+            &SeededStreams::new(5),
+        );
+        let x_t = terminal(&output);
+
+        assert_approx_equal!(x_t.mean(), 1.0 + (mu + lambda * KOU.mean()) * 2.0, 0.01);
+        assert_approx_equal!(
+            x_t.variance(),
+            (sigma * sigma + lambda * KOU.second_moment()) * 2.0,
+            0.01
+        );
+    }
+
+    #[test]
+    fn test_compensated_price() {
+        // GBM with Merton jumps, the drift compensated: a martingale.
+        let merton = JumpSize::Merton {
+            mean: -0.05,
+            std_dev: 0.1,
+        };
+        let (r, lambda): (f64, f64) = (0.03, 1.0);
+        let process = JumpDiffusion::new(
+            GeometricBrownianMotion::new(r - lambda * merton.mean_relative_jump(), 0.2),
+            lambda,
+            merton,
+            JumpEffect::Multiplicative,
+        );
+        let output = process.simulate_with_jumps(
+            SimulationScheme::EulerMaruyama,
+            100.0,
+            0.0,
+            1.0,
+            250,
+            50_000,
+            true,
+            &SeededStreams::new(9),
+        );
+
+        assert_approx_equal!(terminal(&output).mean(), 100.0 * r.exp(), 0.3);
+    }
+}
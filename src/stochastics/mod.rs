@@ -26,6 +26,57 @@
 //!   - $dX(t) = \left[ \theta(t) - \alpha(t) X(t) \right] dt + \sigma dW(t)$
 //! - Black-Derman-Toy (1990)
 //!   - $d\ln[X(t)] = \left[ \theta(t) + \frac{\sigma'(t)}{\sigma(t)}\ln[X(t)] \right]dt + \sigma_t dW(t)$
+//! - Heston (1993), with the Quadratic-Exponential scheme of Andersen (2008)
+//!   - $dS(t) = \mu S(t) dt + \sqrt{v(t)} S(t) dW_S(t)$
+//!   - $dv(t) = \kappa \left[ \theta - v(t) \right] dt + \sigma \sqrt{v(t)} dW_v(t)$
+//! - Jump diffusions: any of the above with compound Poisson jumps
+//!   - Merton (1976) Gaussian or Kou (2002) double exponential jump sizes
+//! - Rough Bergomi (2016), with the hybrid scheme of Bennedsen, Lunde and Pakkanen (2017)
+//!   - $dS(t) = \sqrt{v(t)} S(t) dB(t)$
+//!   - $v(t) = \xi_0(t) \exp\left( \eta Y(t) - \frac{\eta^2}{2} t^{2H} \right)$
+//! - Equity with a stochastic (e.g. Hull-White) short rate, correlated
+//!   - $dS(t) = \left[ r(t) - q(t) \right] S(t) dt + \sigma(t) S(t) dW_S(t)$
+//! - SABR (2002), absorbed or reflected at zero
+//!   - $dF(t) = \alpha(t) F(t)^\beta dW(t)$
+//!   - $d\alpha(t) = \nu \alpha(t) dZ(t)$
+//!
+//! The dynamics of a process can switch between regimes, e.g. calm and
+//! stressed markets, following a continuous-time Markov chain, with
+//! [`RegimeSwitching`].
+//!
+//! Arrival times, e.g. of defaults or orders, are sampled from Poisson,
+//! Hawkes and Cox (doubly stochastic) processes by [`PointProcess`].
+//!
+//! The parameters are [`TimeDependent`]: constants, functions of time, or
+//! term structures, e.g. piecewise-constant forward volatilities and rates
+//! bootstrapped from implied volatilities and zero rates.
+//!
+//! Processes are simulated with the Euler-Maruyama scheme, or with the
+//! Milstein, stochastic Runge-Kutta or predictor-corrector schemes of
+//! [`SimulationScheme`].
+//!
+//! Large simulations are split into chunks generated in parallel, with
+//! random number streams depending only on a master seed, by the
+//! [`PathGenerator`].
+//!
+//! Every generator draws its random numbers from a [`RandomStream`], so
+//! simulations are reproducible and any `rand::RngCore` generator can be
+//! plugged in, e.g. counter-based generators for parallel streams.
+//!
+//! Brownian paths can also be built from the terminal value down to the
+//! finest time steps with a [`BrownianBridge`], for quasi-Monte Carlo and
+//! for barrier monitoring between time steps.
+//!
+//! Expectations of payoffs of the paths are estimated by the
+//! [`MonteCarloEngine`], with antithetic variates, control variates,
+//! importance sampling, and stratified or Latin hypercube sampling.
+//!
+//! The parameters of geometric Brownian motion, Ornstein-Uhlenbeck and
+//! Cox-Ingersoll-Ross processes can be estimated from historical data with
+//! their `fit` constructors.
+//!
+//! Several processes can be simulated jointly, driven by correlated Brownian
+//! motions, with [`CorrelatedProcesses`].
 //!
 //! ```rust
 //! use RustQuant::stochastics::*;
@@ -42,31 +93,52 @@
 
 pub use arithmetic_brownian_motion::*;
 pub use black_derman_toy::*;
+pub use brownian_bridge::*;
 pub use brownian_motion::*;
 pub use constant_elasticity_of_variance::*;
+pub use correlated::*;
 pub use cox_ingersoll_ross::*;
+pub use estimation::*;
 pub use extended_vasicek::*;
 pub use fractional_brownian_motion::*;
 pub use fractional_cox_ingersoll_ross::*;
 pub use fractional_ornstein_uhlenbeck::*;
 pub use geometric_brownian_bridge::*;
 pub use geometric_brownian_motion::*;
+pub use heston::*;
 pub use ho_lee::*;
 pub use hull_white::*;
+pub use hybrid::*;
+pub use jump_diffusion::*;
 pub use merton_jump_diffusion::*;
+pub use monte_carlo::*;
 pub use ornstein_uhlenbeck::*;
+pub use path_generator::*;
+pub use pathwise::*;
+pub use point_process::*;
 pub use process::*;
+pub use random::*;
+pub use regime_switching::*;
+pub use rough_bergomi::*;
+pub use sabr::*;
+pub use schemes::*;
 
 /// Arithmetic Brownian Motion.
 pub mod arithmetic_brownian_motion;
 /// Black-Derman-Toy short rate model.
 pub mod black_derman_toy;
+/// Brownian bridge path construction.
+pub mod brownian_bridge;
 /// Standard Brownian Motion.
 pub mod brownian_motion;
 /// Constant Elasticity of Variance process.
 pub mod constant_elasticity_of_variance;
+/// Correlated multi-process simulation.
+pub mod correlated;
 /// Cox-Ingersoll-Ross process.
 pub mod cox_ingersoll_ross;
+/// Parameter estimation from historical data.
+pub mod estimation;
 /// Extended Vasicek process.
 pub mod extended_vasicek;
 /// Fractional Brownian Motion.
@@ -79,13 +151,37 @@ pub mod fractional_ornstein_uhlenbeck;
 pub mod geometric_brownian_bridge;
 /// Geometric Brownian Motion.
 pub mod geometric_brownian_motion;
+/// Heston stochastic volatility process.
+pub mod heston;
 /// Ho-Lee process.
 pub mod ho_lee;
 /// Hull-White model process.
 pub mod hull_white;
+/// Equity with stochastic interest rates.
+pub mod hybrid;
+/// Compound Poisson jumps (Merton, Kou) added to diffusion processes.
+pub mod jump_diffusion;
 /// Merton jump diffusion process.
 pub mod merton_jump_diffusion;
+/// Monte Carlo engine with variance reduction.
+pub mod monte_carlo;
 /// Ornstein-Uhlenbeck process.
 pub mod ornstein_uhlenbeck;
+/// Parallel path generation in chunks.
+pub mod path_generator;
+/// Pathwise Greeks via Monte Carlo simulation and `autodiff`.
+pub mod pathwise;
+/// Poisson, Hawkes and Cox point processes.
+pub mod point_process;
 /// Defines `Trajectories` and `StochasticProcess`.
 pub mod process;
+/// Random number streams.
+pub mod random;
+/// Regime-switching diffusions.
+pub mod regime_switching;
+/// Rough Bergomi model, with the hybrid scheme.
+pub mod rough_bergomi;
+/// SABR stochastic volatility process.
+pub mod sabr;
+/// Discretisation schemes.
+pub mod schemes;
@@ -0,0 +1,532 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo engine with variance reduction.
+//!
+//! The expectation of a (discounted) payoff of the paths of a process is
+//! estimated with any combination of:
+//!
+//! - Antithetic variates: each path is paired with the path driven by the
+//!   opposite Gaussian draws, and the pair averaged.
+//! - Control variates: payoffs with known expectations, whose deviations
+//!   from those expectations are subtracted, with the coefficients $\beta$
+//!   estimated by least squares regression of the payoff on the controls.
+//! - Importance sampling: the Brownian motion is given a drift $\theta$,
+//!   which moves the paths towards the region that matters (e.g. above the
+//!   strike of a deep out-of-the-money option), and each path is weighted
+//!   by the likelihood ratio $\exp(-\theta W_T + \frac{1}{2} \theta^2 T)$.
+//!
+//! - Stratified or Latin hypercube sampling (see [`Sampling`]): the paths
+//!   are built by a [`BrownianBridge`] from draws spread evenly over their
+//!   distribution, so the terminal value of the Brownian motion, the first
+//!   dimension of the bridge, is stratified. With stratified sampling, the
+//!   estimate averages the means of the strata, and its standard error
+//!   adds up the variances within the strata. Latin hypercube samples are
+//!   not independent: their standard error is computed as for independent
+//!   samples, which overstates it.
+//!
+//! The result reports the variance reduction achieved: the ratio of the
+//! variance of plain Monte Carlo, with the same number of paths, to the
+//! variance of the estimator.
+
+use crate::stochastics::{
+    BrownianBridge, RandomStream, SampleDesign, Sampling, SeededStreams, StochasticProcess,
+};
+use nalgebra::{DMatrix, DVector};
+use rayon::prelude::*;
+
+/// Payoff of a path.
+pub type PathPayoff<'a> = Box<dyn Fn(&[f64]) -> f64 + Sync + 'a>;
+
+/// A payoff of the path with a known expectation, used as a control
+/// variate.
+pub struct ControlVariate<'a> {
+    /// The (discounted) payoff of the path.
+    pub payoff: PathPayoff<'a>,
+
+    /// The expectation of the payoff.
+    pub mean: f64,
+}
+
+impl<'a> ControlVariate<'a> {
+    /// New control variate, with the payoff and its expectation.
+    pub fn new(payoff: impl Fn(&[f64]) -> f64 + Sync + 'a, mean: f64) -> Self {
+        Self {
+            payoff: Box::new(payoff),
+            mean,
+        }
+    }
+}
+
+/// Monte Carlo engine with variance reduction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonteCarloEngine {
+    /// The initial time point.
+    pub t_0: f64,
+    /// The terminal time point.
+    pub t_n: f64,
+    /// The number of time steps between `t_0` and `t_n`.
+    pub n_steps: usize,
+    /// How many paths to simulate (including the antithetic paths).
+    pub n_paths: usize,
+    /// The seed for the random number generator.
+    /// Sample `i` is simulated with the stream `i` of the [`SeededStreams`]
+    /// of the seed, so the results do not depend on how the paths are
+    /// scheduled across threads.
+    pub seed: u64,
+    /// Pair each path with its antithetic path.
+    pub antithetic: bool,
+    /// Drift ($\theta$) of the Brownian motion, for importance sampling.
+    pub drift_shift: f64,
+    /// Sampling of the Gaussian draws.
+    pub sampling: Sampling,
+}
+
+/// Monte Carlo estimate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarloResult {
+    /// Estimated expectation of the payoff.
+    pub price: f64,
+    /// Standard error of the estimate.
+    pub std_error: f64,
+    /// Variance of plain Monte Carlo, with the same number of paths, over
+    /// the variance of the estimate.
+    pub variance_reduction: f64,
+    /// Estimated coefficients of the control variates.
+    pub betas: Vec<f64>,
+}
+
+// Payoff, controls, and first two moments of the plain payoff under the
+// original measure, for one sample (a pair of antithetic paths, or a path).
+struct Sample {
+    payoff: f64,
+    controls: Vec<f64>,
+    plain_moments: (f64, f64),
+}
+
+impl MonteCarloEngine {
+    /// Create a new Monte Carlo engine, without variance reduction.
+    #[must_use]
+    pub const fn new(t_0: f64, t_n: f64, n_steps: usize, n_paths: usize, seed: u64) -> Self {
+        Self {
+            t_0,
+            t_n,
+            n_steps,
+            n_paths,
+            seed,
+            antithetic: false,
+            drift_shift: 0.0,
+            sampling: Sampling::Independent,
+        }
+    }
+
+    /// With antithetic variates.
+    #[must_use]
+    pub const fn with_antithetic(self) -> Self {
+        Self {
+            antithetic: true,
+            ..self
+        }
+    }
+
+    /// With importance sampling, by a drift `theta` of the Brownian motion.
+    #[must_use]
+    pub const fn with_drift_shift(self, theta: f64) -> Self {
+        Self {
+            drift_shift: theta,
+            ..self
+        }
+    }
+
+    /// With stratified or Latin hypercube sampling of the Gaussian draws,
+    /// the paths being built by a Brownian bridge.
+    #[must_use]
+    pub const fn with_sampling(self, sampling: Sampling) -> Self {
+        Self { sampling, ..self }
+    }
+
+    /// Estimate the expectation of the payoff of the paths of the process,
+    /// simulated with the Euler-Maruyama scheme, with the control variates
+    /// `controls` (if any).
+    ///
+    /// # Arguments:
+    /// * `process` - The stochastic process to simulate.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `payoff` - The discounted payoff of the path (`n_steps + 1`
+    ///   values).
+    /// * `controls` - Control variates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`, or there are fewer than two samples.
+    ///
+    /// ```
+    /// use RustQuant::stochastics::*;
+    ///
+    /// let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+    /// let engine = MonteCarloEngine::new(0.0, 1.0, 50, 10_000, 42).with_antithetic();
+    ///
+    /// // At-the-money call, with the discounted terminal value as control.
+    /// let discount = f64::exp(-0.05);
+    /// let call = |path: &[f64]| discount * (path[50] - 100.0).max(0.0);
+    /// let forward = ControlVariate::new(|path: &[f64]| discount * path[50], 100.0);
+    ///
+    /// let result = engine.estimate(&gbm, 100.0, call, &[forward]);
+    ///
+    /// assert!((result.price - 10.45).abs() < 0.1);
+    /// assert!(result.variance_reduction > 5.0);
+    /// ```
+    pub fn estimate<P, F>(
+        &self,
+        process: &P,
+        x_0: f64,
+        payoff: F,
+        controls: &[ControlVariate],
+    ) -> MonteCarloResult
+    where
+        P: StochasticProcess + ?Sized,
+        F: Fn(&[f64]) -> f64 + Sync,
+    {
+        self.estimate_with_streams(
+            process,
+            x_0,
+            payoff,
+            controls,
+            &SeededStreams::new(self.seed),
+        )
+    }
+
+    /// Estimate the expectation of the payoff of the paths of the process,
+    /// as [`MonteCarloEngine::estimate`], where the sample `i` draws its
+    /// random numbers from the stream `i` of `streams` (the seed of the
+    /// engine is not used), and the Latin hypercube permutations, if any,
+    /// from the stream `n_samples`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`, or there are fewer than two samples.
+    pub fn estimate_with_streams<P, F, S>(
+        &self,
+        process: &P,
+        x_0: f64,
+        payoff: F,
+        controls: &[ControlVariate],
+        streams: &S,
+    ) -> MonteCarloResult
+    where
+        P: StochasticProcess + ?Sized,
+        F: Fn(&[f64]) -> f64 + Sync,
+        S: RandomStream,
+    {
+        assert!(self.t_0 < self.t_n);
+
+        let n_samples = if self.antithetic {
+            self.n_paths / 2
+        } else {
+            self.n_paths
+        };
+        assert!(n_samples > 1, "At least two samples are required.");
+
+        let dt = (self.t_n - self.t_0) / self.n_steps as f64;
+        let times: Vec<f64> = (0..=self.n_steps)
+            .map(|t| self.t_0 + dt * t as f64)
+            .collect();
+        let theta = self.drift_shift;
+
+        let design = SampleDesign::new(self.sampling, n_samples, self.n_steps, streams);
+        let bridge = (self.sampling != Sampling::Independent).then(|| BrownianBridge::new(&times));
+
+        // Path from the standard Gaussian draws `z` (the sign flipped for the
+        // antithetic path), with its likelihood ratio.
+        let simulate = |z: &[f64], sign: f64| {
+            let mut path = vec![x_0; self.n_steps + 1];
+            let mut w = 0.0;
+
+            for t in 0..self.n_steps {
+                let dW = dt.sqrt() * sign * z[t] + theta * dt;
+                path[t + 1] = path[t]
+                    + process.drift(path[t], times[t]) * dt
+                    + process.diffusion(path[t], times[t]) * dW;
+                w += dW;
+            }
+            let likelihood = (-theta * w + 0.5 * theta * theta * (self.t_n - self.t_0)).exp();
+
+            (path, likelihood)
+        };
+
+        let samples: Vec<Sample> = (0..n_samples)
+            .into_par_iter()
+            .map(|i| {
+                let normals = design.normals(i, &mut streams.stream(i as u64));
+                let z: Vec<f64> = match &bridge {
+                    Some(bridge) => bridge
+                        .increments(&normals)
+                        .iter()
+                        .map(|dW| dW / dt.sqrt())
+                        .collect(),
+                    None => normals,
+                };
+
+                let signs: &[f64] = if self.antithetic {
+                    &[1.0, -1.0]
+                } else {
+                    &[1.0]
+                };
+                let weight = 1.0 / signs.len() as f64;
+
+                let mut sample = Sample {
+                    payoff: 0.0,
+                    controls: vec![0.0; controls.len()],
+                    plain_moments: (0.0, 0.0),
+                };
+                for sign in signs {
+                    let (path, likelihood) = simulate(&z, *sign);
+                    let value = payoff(&path);
+
+                    sample.payoff += weight * likelihood * value;
+                    for (control, variate) in sample.controls.iter_mut().zip(controls) {
+                        *control += weight * likelihood * (variate.payoff)(&path);
+                    }
+                    sample.plain_moments.0 += likelihood * value;
+                    sample.plain_moments.1 += likelihood * value * value;
+                }
+
+                sample
+            })
+            .collect();
+
+        // Least squares coefficients of the controls.
+        let k = controls.len();
+        let payoffs = DVector::from_iterator(n_samples, samples.iter().map(|s| s.payoff));
+        let centred_controls = DMatrix::from_fn(n_samples, k, |i, j| samples[i].controls[j]);
+        let control_means = DVector::from_fn(k, |j, _| centred_controls.column(j).mean());
+        let centred_controls = DMatrix::from_fn(n_samples, k, |i, j| {
+            centred_controls[(i, j)] - control_means[j]
+        });
+        let centred_payoffs = payoffs.add_scalar(-payoffs.mean());
+
+        let betas = if k == 0 {
+            DVector::zeros(0)
+        } else {
+            (centred_controls.transpose() * &centred_controls)
+                .lu()
+                .solve(&(centred_controls.transpose() * &centred_payoffs))
+                .unwrap_or_else(|| DVector::zeros(k))
+        };
+
+        // Payoffs adjusted by the deviations of the controls from their
+        // expectations.
+        let adjusted: Vec<f64> = (0..n_samples)
+            .map(|i| {
+                payoffs[i]
+                    - (0..k)
+                        .map(|j| betas[j] * (samples[i].controls[j] - controls[j].mean))
+                        .sum::<f64>()
+            })
+            .collect();
+
+        let (price, variance) = stratified_mean(&design, &adjusted);
+        let std_error = variance.sqrt();
+
+        // Variance of the plain payoff, under the original measure.
+        let n = self.n_paths as f64;
+        let (first, second) = samples.iter().fold((0.0, 0.0), |(a, b), s| {
+            (a + s.plain_moments.0 / n, b + s.plain_moments.1 / n)
+        });
+        let plain_variance = (second - first * first) * n / (n - 1.0);
+
+        MonteCarloResult {
+            price,
+            std_error,
+            variance_reduction: plain_variance / n / (std_error * std_error),
+            betas: betas.iter().copied().collect(),
+        }
+    }
+}
+
+// Average of the means of the (equiprobable) strata of the samples, and
+// variance of the average from the variances within the strata.
+fn stratified_mean(design: &SampleDesign, samples: &[f64]) -> (f64, f64) {
+    let mut strata = vec![Vec::new(); design.strata()];
+    for (i, y) in samples.iter().enumerate() {
+        strata[design.stratum(i)].push(*y);
+    }
+
+    let weight = 1.0 / strata.len() as f64;
+    strata.iter().fold((0.0, 0.0), |(mean, variance), stratum| {
+        let size = stratum.len() as f64;
+        let stratum_mean = stratum.iter().sum::<f64>() / size;
+        let within = stratum
+            .iter()
+            .map(|y| (y - stratum_mean).powi(2))
+            .sum::<f64>()
+            / (size - 1.0);
+
+        (
+            mean + weight * stratum_mean,
+            variance + weight * weight * within / size,
+        )
+    })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_monte_carlo {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::{black76_greeks, TypeFlag};
+    use crate::stochastics::{ArithmeticBrownianMotion, GeometricBrownianMotion};
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    const S_0: f64 = 100.0;
+    const R: f64 = 0.05;
+    const SIGMA: f64 = 0.2;
+
+    fn call(strike: f64) -> impl Fn(&[f64]) -> f64 + Sync {
+        move |path: &[f64]| (-R).exp() * (path[path.len() - 1] - strike).max(0.0)
+    }
+
+    fn black_scholes(strike: f64) -> f64 {
+        black76_greeks(S_0 * R.exp(), strike, 1.0, R, SIGMA, TypeFlag::Call).price
+    }
+
+    #[test]
+    fn test_plain_and_antithetic() {
+        let gbm = GeometricBrownianMotion::new(R, SIGMA);
+        let engine = MonteCarloEngine::new(0.0, 1.0, 50, 40_000, 1);
+
+        let plain = engine.estimate(&gbm, S_0, call(100.0), &[]);
+        assert_approx_equal!(
+            plain.price,
+            black_scholes(100.0),
+            3.0 * plain.std_error + 0.02
+        );
+        assert_approx_equal!(plain.variance_reduction, 1.0, 0.01);
+        assert!(plain.betas.is_empty());
+
+        let antithetic = engine
+            .with_antithetic()
+            .estimate(&gbm, S_0, call(100.0), &[]);
+        assert_approx_equal!(
+            antithetic.price,
+            black_scholes(100.0),
+            3.0 * antithetic.std_error + 0.02
+        );
+        assert!(antithetic.variance_reduction > 1.5);
+    }
+
+    #[test]
+    fn test_control_variates() {
+        let gbm = GeometricBrownianMotion::new(R, SIGMA);
+        let engine = MonteCarloEngine::new(0.0, 1.0, 50, 40_000, 2);
+
+        // The discounted terminal value, and a further out-of-the-money call
+        // with its Black-Scholes price, as controls.
+        let forward = ControlVariate::new(|path: &[f64]| (-R).exp() * path[50], S_0);
+        let otm = ControlVariate::new(call(110.0), black_scholes(110.0));
+
+        let single = engine.estimate(&gbm, S_0, call(100.0), &[forward]);
+        assert_approx_equal!(
+            single.price,
+            black_scholes(100.0),
+            3.0 * single.std_error + 0.02
+        );
+        assert!(single.variance_reduction > 3.0);
+        // Close to the delta of the call.
+        assert_approx_equal!(single.betas[0], 0.64, 0.1);
+
+        let forward = ControlVariate::new(|path: &[f64]| (-R).exp() * path[50], S_0);
+        let double = engine.estimate(&gbm, S_0, call(100.0), &[forward, otm]);
+        assert_approx_equal!(
+            double.price,
+            black_scholes(100.0),
+            3.0 * double.std_error + 0.02
+        );
+        assert!(double.variance_reduction > single.variance_reduction);
+    }
+
+    #[test]
+    fn test_importance_sampling() {
+        // Deep out-of-the-money call on a Brownian motion (simulated without
+        // discretisation error), against the Bachelier price: shift the
+        // Brownian motion to the strike.
+        let abm = ArithmeticBrownianMotion::new(0.0, 1.0);
+        let strike = 3.0;
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let bachelier = -strike * normal.cdf(-strike) + normal.pdf(strike);
+        let call = |path: &[f64]| (path[path.len() - 1] - strike).max(0.0);
+
+        let engine = MonteCarloEngine::new(0.0, 1.0, 10, 40_000, 3);
+        let plain = engine.estimate(&abm, 0.0, call, &[]);
+        let shifted = engine
+            .with_drift_shift(strike)
+            .estimate(&abm, 0.0, call, &[]);
+
+        assert_approx_equal!(shifted.price, bachelier, 3.0 * shifted.std_error);
+        assert!(shifted.std_error < plain.std_error / 10.0);
+        assert!(shifted.variance_reduction > 100.0);
+
+        // All together, with the terminal value as control.
+        let terminal = ControlVariate::new(|path: &[f64]| path[path.len() - 1], 0.0);
+        let combined = engine.with_drift_shift(strike).with_antithetic().estimate(
+            &abm,
+            0.0,
+            call,
+            &[terminal],
+        );
+
+        assert_approx_equal!(combined.price, bachelier, 3.0 * combined.std_error);
+        assert!(combined.variance_reduction > shifted.variance_reduction);
+    }
+
+    #[test]
+    fn test_stratified_sampling() {
+        let gbm = GeometricBrownianMotion::new(R, SIGMA);
+        let engine = MonteCarloEngine::new(0.0, 1.0, 16, 20_000, 4);
+
+        // The call depends mostly on the terminal value: stratifying it
+        // removes most of the variance.
+        let stratified =
+            engine
+                .with_sampling(Sampling::Stratified)
+                .estimate(&gbm, S_0, call(100.0), &[]);
+        assert_approx_equal!(
+            stratified.price,
+            black_scholes(100.0),
+            3.0 * stratified.std_error + 0.05
+        );
+        assert!(stratified.variance_reduction > 20.0);
+
+        let latin =
+            engine
+                .with_sampling(Sampling::LatinHypercube)
+                .estimate(&gbm, S_0, call(100.0), &[]);
+        let plain = engine.estimate(&gbm, S_0, call(100.0), &[]);
+        assert_approx_equal!(
+            latin.price,
+            black_scholes(100.0),
+            3.0 * plain.std_error + 0.05
+        );
+
+        // With antithetic variates and controls.
+        let forward = ControlVariate::new(|path: &[f64]| (-R).exp() * path[16], S_0);
+        let combined = engine
+            .with_sampling(Sampling::Stratified)
+            .with_antithetic()
+            .estimate(&gbm, S_0, call(100.0), &[forward]);
+        assert_approx_equal!(
+            combined.price,
+            black_scholes(100.0),
+            3.0 * combined.std_error + 0.05
+        );
+    }
+}
@@ -8,6 +8,8 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::stochastics::{StochasticProcess, TimeDependent};
+use rand::{Rng, RngCore};
+use rand_distr::StandardNormal;
 
 /// Struct containing the Ornstein-Uhlenbeck process parameters.
 pub struct OrnsteinUhlenbeck {
@@ -47,6 +49,24 @@ impl StochasticProcess for OrnsteinUhlenbeck {
         self.sigma.0(t)
     }
 
+    fn diffusion_derivative(&self, _x: f64, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn exact_step(&self, x: f64, t: f64, dt: f64, rng: &mut dyn RngCore) -> Option<f64> {
+        // Gaussian transition.
+        let (mu, sigma, theta) = (self.mu.0(t), self.sigma.0(t), self.theta.0(t));
+        let decay = (-theta * dt).exp();
+        let variance = if theta > 0.0 {
+            sigma * sigma * (1.0 - decay * decay) / (2.0 * theta)
+        } else {
+            sigma * sigma * dt
+        };
+        let z: f64 = rng.sample(StandardNormal);
+
+        Some(mu + (x - mu) * decay + variance.sqrt() * z)
+    }
+
     fn jump(&self, _x: f64, _t: f64) -> Option<f64> {
         None
     }
@@ -0,0 +1,599 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Parallel path generation in chunks.
+//!
+//! The paths are split into chunks of a fixed size, which are generated in
+//! parallel by the `rayon` thread pool. Each chunk has its own random number
+//! stream, seeded from the master seed and the index of the chunk, so the
+//! paths only depend on the master seed: not on the number of threads, nor
+//! on how the chunks are scheduled.
+//!
+//! The chunks can also be consumed as they are generated, with
+//! [`PathGenerator::par_chunks`] or [`PathGenerator::chunks`], and the paths
+//! one at a time with [`PathGenerator::for_each_path`], so memory grows with
+//! the size of a chunk rather than with the number of paths.
+//! [`PathGenerator::evaluate`] accumulates the moments of a payoff of the
+//! paths this way, in parallel.
+//!
+//! The `*_with_streams` methods draw the chunk `i` from the stream `i` of
+//! any [`RandomStream`] instead, e.g. a counter-based generator.
+
+use crate::stochastics::{
+    RandomStream, SeededStreams, SimulationScheme, StochasticProcess, Trajectories,
+};
+use rayon::prelude::*;
+
+/// Parallel generator of the paths of a process, in chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathGenerator {
+    /// The number of time steps between `t_0` and `t_n`.
+    pub n_steps: usize,
+    /// The discretisation scheme.
+    pub scheme: SimulationScheme,
+    /// How many paths per chunk.
+    pub chunk_size: usize,
+    /// The master seed of the random number streams.
+    pub seed: u64,
+}
+
+impl PathGenerator {
+    /// Default number of paths per chunk.
+    pub const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+    /// Create a new path generator, with the Euler-Maruyama scheme and the
+    /// default chunk size.
+    #[must_use]
+    pub const fn new(n_steps: usize, seed: u64) -> Self {
+        Self {
+            n_steps,
+            scheme: SimulationScheme::EulerMaruyama,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            seed,
+        }
+    }
+
+    /// With the discretisation scheme `scheme`.
+    #[must_use]
+    pub const fn with_scheme(self, scheme: SimulationScheme) -> Self {
+        Self { scheme, ..self }
+    }
+
+    /// With `chunk_size` paths per chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    #[must_use]
+    pub const fn with_chunk_size(self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "The chunk size must be positive.");
+
+        Self { chunk_size, ..self }
+    }
+
+    /// Number of chunks of `m_paths` paths.
+    #[must_use]
+    pub const fn n_chunks(&self, m_paths: usize) -> usize {
+        m_paths.div_ceil(self.chunk_size)
+    }
+
+    /// Paths of the chunk `chunk` of `m_paths` paths of the process, from
+    /// `x_0` at `t_0` to `t_n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n` or the chunk is out of range.
+    #[must_use]
+    pub fn chunk<P>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        chunk: usize,
+    ) -> Trajectories
+    where
+        P: StochasticProcess + ?Sized,
+    {
+        let streams = SeededStreams::new(self.seed);
+
+        self.chunk_with_streams(process, x_0, t_0, t_n, m_paths, chunk, &streams)
+    }
+
+    /// Paths of the chunk `chunk` of `m_paths` paths of the process, from
+    /// `x_0` at `t_0` to `t_n`, drawn from the stream `chunk` of `streams`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n` or the chunk is out of range.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn chunk_with_streams<P, S>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        chunk: usize,
+        streams: &S,
+    ) -> Trajectories
+    where
+        P: StochasticProcess + ?Sized,
+        S: RandomStream,
+    {
+        assert!(t_0 < t_n);
+        assert!(chunk < self.n_chunks(m_paths), "The chunk is out of range.");
+
+        let times = self.times(t_0, t_n);
+        let mut paths = Vec::with_capacity(self.chunk_size);
+        self.visit_chunk(process, x_0, t_0, t_n, m_paths, chunk, streams, |path| {
+            paths.push(path.to_vec());
+        });
+
+        Trajectories { times, paths }
+    }
+
+    /// Parallel iterator over the chunks of `m_paths` paths of the process,
+    /// from `x_0` at `t_0` to `t_n`, in order.
+    ///
+    /// ```
+    /// use RustQuant::stochastics::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+    /// let generator = PathGenerator::new(100, 42).with_chunk_size(1000);
+    ///
+    /// // Sum of the terminal values, one chunk at a time.
+    /// let sum: f64 = generator
+    ///     .par_chunks(&gbm, 100.0, 0.0, 1.0, 10_000)
+    ///     .map(|chunk| chunk.paths.iter().map(|path| path[100]).sum::<f64>())
+    ///     .sum();
+    ///
+    /// assert!((sum / 10_000.0 - 100.0 * f64::exp(0.05)).abs() < 1.0);
+    /// ```
+    pub fn par_chunks<'a, P>(
+        &'a self,
+        process: &'a P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+    ) -> impl IndexedParallelIterator<Item = Trajectories> + 'a
+    where
+        P: StochasticProcess + ?Sized,
+    {
+        (0..self.n_chunks(m_paths))
+            .into_par_iter()
+            .map(move |chunk| self.chunk(process, x_0, t_0, t_n, m_paths, chunk))
+    }
+
+    /// Parallel iterator over the chunks of `m_paths` paths of the process,
+    /// from `x_0` at `t_0` to `t_n`, in order, the chunk `i` drawn from the
+    /// stream `i` of `streams`.
+    pub fn par_chunks_with_streams<'a, P, S>(
+        &'a self,
+        process: &'a P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        streams: &'a S,
+    ) -> impl IndexedParallelIterator<Item = Trajectories> + 'a
+    where
+        P: StochasticProcess + ?Sized,
+        S: RandomStream,
+    {
+        (0..self.n_chunks(m_paths))
+            .into_par_iter()
+            .map(move |chunk| {
+                self.chunk_with_streams(process, x_0, t_0, t_n, m_paths, chunk, streams)
+            })
+    }
+
+    /// All `m_paths` paths of the process, from `x_0` at `t_0` to `t_n`,
+    /// generated in parallel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    #[must_use]
+    pub fn generate<P>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+    ) -> Trajectories
+    where
+        P: StochasticProcess + ?Sized,
+    {
+        let streams = SeededStreams::new(self.seed);
+
+        self.generate_with_streams(process, x_0, t_0, t_n, m_paths, &streams)
+    }
+
+    /// All `m_paths` paths of the process, from `x_0` at `t_0` to `t_n`,
+    /// generated in parallel, the chunk `i` drawn from the stream `i` of
+    /// `streams`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    #[must_use]
+    pub fn generate_with_streams<P, S>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        streams: &S,
+    ) -> Trajectories
+    where
+        P: StochasticProcess + ?Sized,
+        S: RandomStream,
+    {
+        assert!(t_0 < t_n);
+
+        let times = self.times(t_0, t_n);
+        let chunks: Vec<Trajectories> = self
+            .par_chunks_with_streams(process, x_0, t_0, t_n, m_paths, streams)
+            .collect();
+        let paths = chunks.into_iter().flat_map(|chunk| chunk.paths).collect();
+
+        Trajectories { times, paths }
+    }
+
+    /// Sequential iterator over the chunks of `m_paths` paths of the
+    /// process, from `x_0` at `t_0` to `t_n`, generated lazily: only one
+    /// chunk is held in memory at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    pub fn chunks<'a, P>(
+        &'a self,
+        process: &'a P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+    ) -> impl ExactSizeIterator<Item = Trajectories> + 'a
+    where
+        P: StochasticProcess + ?Sized,
+    {
+        assert!(t_0 < t_n);
+
+        (0..self.n_chunks(m_paths))
+            .map(move |chunk| self.chunk(process, x_0, t_0, t_n, m_paths, chunk))
+    }
+
+    /// Calls `f` on each of the `m_paths` paths of the process, from `x_0`
+    /// at `t_0` to `t_n`, in order. The paths are generated one at a time
+    /// into the same buffer, so memory does not grow with `m_paths`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    pub fn for_each_path<P, F>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        f: F,
+    ) where
+        P: StochasticProcess + ?Sized,
+        F: FnMut(&[f64]),
+    {
+        let streams = SeededStreams::new(self.seed);
+
+        self.for_each_path_with_streams(process, x_0, t_0, t_n, m_paths, &streams, f);
+    }
+
+    /// Calls `f` on each of the `m_paths` paths of the process, from `x_0`
+    /// at `t_0` to `t_n`, in order, the chunk `i` drawn from the stream `i`
+    /// of `streams`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn for_each_path_with_streams<P, S, F>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        streams: &S,
+        mut f: F,
+    ) where
+        P: StochasticProcess + ?Sized,
+        S: RandomStream,
+        F: FnMut(&[f64]),
+    {
+        assert!(t_0 < t_n);
+
+        for chunk in 0..self.n_chunks(m_paths) {
+            self.visit_chunk(process, x_0, t_0, t_n, m_paths, chunk, streams, &mut f);
+        }
+    }
+
+    /// Moments of the payoff of the `m_paths` paths of the process, from
+    /// `x_0` at `t_0` to `t_n`. The chunks are generated in parallel and
+    /// their paths evaluated as they are generated: memory grows with the
+    /// number of threads, not with `m_paths`.
+    ///
+    /// ```
+    /// use RustQuant::stochastics::*;
+    ///
+    /// let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+    /// let generator = PathGenerator::new(100, 42);
+    ///
+    /// // Discounted at-the-money call, over a million paths.
+    /// let call = |path: &[f64]| f64::exp(-0.05) * (path[100] - 100.0).max(0.0);
+    /// let moments = generator.evaluate(&gbm, 100.0, 0.0, 1.0, 1_000_000, call);
+    ///
+    /// assert_eq!(moments.count, 1_000_000);
+    /// assert!((moments.mean - 10.45).abs() < 4.0 * moments.std_error() + 0.05);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    pub fn evaluate<P, F>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        payoff: F,
+    ) -> RunningMoments
+    where
+        P: StochasticProcess + ?Sized,
+        F: Fn(&[f64]) -> f64 + Sync,
+    {
+        let streams = SeededStreams::new(self.seed);
+
+        self.evaluate_with_streams(process, x_0, t_0, t_n, m_paths, &streams, payoff)
+    }
+
+    /// Moments of the payoff of the `m_paths` paths of the process, from
+    /// `x_0` at `t_0` to `t_n`, the chunk `i` drawn from the stream `i` of
+    /// `streams`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn evaluate_with_streams<P, S, F>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        streams: &S,
+        payoff: F,
+    ) -> RunningMoments
+    where
+        P: StochasticProcess + ?Sized,
+        S: RandomStream,
+        F: Fn(&[f64]) -> f64 + Sync,
+    {
+        assert!(t_0 < t_n);
+
+        (0..self.n_chunks(m_paths))
+            .into_par_iter()
+            .map(|chunk| {
+                let mut moments = RunningMoments::default();
+                self.visit_chunk(process, x_0, t_0, t_n, m_paths, chunk, streams, |path| {
+                    moments.push(payoff(path));
+                });
+                moments
+            })
+            .reduce(RunningMoments::default, RunningMoments::merge)
+    }
+
+    // The time grid from `t_0` to `t_n`.
+    fn times(&self, t_0: f64, t_n: f64) -> Vec<f64> {
+        let dt: f64 = (t_n - t_0) / (self.n_steps as f64);
+
+        (0..=self.n_steps).map(|t| t_0 + dt * (t as f64)).collect()
+    }
+
+    // Generates the paths of the chunk `chunk` one at a time into the same
+    // buffer, and calls `f` on each.
+    #[allow(clippy::too_many_arguments)]
+    fn visit_chunk<P, S, F>(
+        &self,
+        process: &P,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        chunk: usize,
+        streams: &S,
+        mut f: F,
+    ) where
+        P: StochasticProcess + ?Sized,
+        S: RandomStream,
+        F: FnMut(&[f64]),
+    {
+        let dt: f64 = (t_n - t_0) / (self.n_steps as f64);
+        let times = self.times(t_0, t_n);
+
+        let start = chunk * self.chunk_size;
+        let size = self.chunk_size.min(m_paths - start);
+        let mut rng = streams.stream(chunk as u64);
+
+        let mut x = vec![x_0; self.n_steps + 1];
+        for _ in 0..size {
+            for t in 0..self.n_steps {
+                x[t + 1] = self.scheme.step(process, x[t], times[t], dt, &mut rng);
+            }
+            f(&x);
+        }
+    }
+}
+
+/// Running mean and variance of a stream of values (Welford's algorithm),
+/// mergeable across threads (Chan et al.).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RunningMoments {
+    /// Number of values.
+    pub count: usize,
+    /// Mean of the values.
+    pub mean: f64,
+    /// Sum of the squared deviations from the mean.
+    pub sum_squares: f64,
+}
+
+impl RunningMoments {
+    /// Adds the value `x`.
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.sum_squares += delta * (x - self.mean);
+    }
+
+    /// Moments of the values of both.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        if self.count == 0 {
+            return other;
+        }
+        if other.count == 0 {
+            return self;
+        }
+
+        let count = self.count + other.count;
+        let (n_a, n_b, n) = (self.count as f64, other.count as f64, count as f64);
+        let delta = other.mean - self.mean;
+
+        Self {
+            count,
+            mean: self.mean + delta * n_b / n,
+            sum_squares: self.sum_squares + other.sum_squares + delta * delta * n_a * n_b / n,
+        }
+    }
+
+    /// Sample variance of the values.
+    #[must_use]
+    pub fn variance(&self) -> f64 {
+        self.sum_squares / (self.count as f64 - 1.0)
+    }
+
+    /// Standard error of the mean.
+    #[must_use]
+    pub fn std_error(&self) -> f64 {
+        (self.variance() / self.count as f64).sqrt()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_path_generator {
+    use super::*;
+    use crate::stochastics::{GeometricBrownianMotion, OrnsteinUhlenbeck};
+    use crate::{assert_approx_equal, statistics::*};
+
+    #[test]
+    fn test_deterministic_across_threads() {
+        let ou = OrnsteinUhlenbeck::new(0.05, 0.02, 1.0);
+        let generator = PathGenerator::new(50, 7).with_chunk_size(100);
+
+        let with_threads = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| generator.generate(&ou, 0.03, 0.0, 1.0, 1_050))
+        };
+        let single = with_threads(1);
+        let multiple = with_threads(4);
+
+        assert_eq!(single.paths, multiple.paths);
+        assert_eq!(single.paths.len(), 1_050);
+        assert_eq!(generator.n_chunks(1_050), 11);
+
+        // The chunks are slices of the paths, with distinct streams.
+        let last = generator.chunk(&ou, 0.03, 0.0, 1.0, 1_050, 10);
+        assert_eq!(last.paths, single.paths[1_000..]);
+        assert_ne!(single.paths[0], single.paths[100]);
+
+        // Another master seed, other paths.
+        let other = PathGenerator {
+            seed: 8,
+            ..generator
+        }
+        .generate(&ou, 0.03, 0.0, 1.0, 1_050);
+        assert_ne!(single.paths, other.paths);
+    }
+
+    #[test]
+    fn test_moments() {
+        let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+        let generator = PathGenerator::new(1, 11).with_scheme(SimulationScheme::Exact);
+
+        let output = generator.generate(&gbm, 100.0, 0.0, 1.0, 100_000);
+        let terminal: Vec<f64> = output.paths.iter().map(|path| path[1]).collect();
+
+        assert_approx_equal!(terminal.mean(), 100.0 * 0.05_f64.exp(), 0.2);
+        assert_approx_equal!(
+            terminal.variance(),
+            10_000.0 * 0.1_f64.exp() * (0.04_f64.exp() - 1.0),
+            10.0
+        );
+    }
+
+    #[test]
+    fn test_streaming() {
+        let ou = OrnsteinUhlenbeck::new(0.05, 0.02, 1.0);
+        let generator = PathGenerator::new(20, 3).with_chunk_size(64);
+        let all = generator.generate(&ou, 0.03, 0.0, 1.0, 1_000);
+
+        // The streamed paths are those generated at once.
+        let streamed: Vec<Trajectories> = generator.chunks(&ou, 0.03, 0.0, 1.0, 1_000).collect();
+        assert_eq!(streamed.len(), 16);
+        assert_eq!(
+            streamed
+                .into_iter()
+                .flat_map(|chunk| chunk.paths)
+                .collect::<Vec<_>>(),
+            all.paths
+        );
+
+        let mut visited = Vec::new();
+        generator.for_each_path(&ou, 0.03, 0.0, 1.0, 1_000, |path| visited.push(path[20]));
+        let terminal: Vec<f64> = all.paths.iter().map(|path| path[20]).collect();
+        assert_eq!(visited, terminal);
+
+        // The merged moments of the chunks are those of all the paths.
+        let moments = generator.evaluate(&ou, 0.03, 0.0, 1.0, 1_000, |path| path[20]);
+        assert_eq!(moments.count, 1_000);
+        assert_approx_equal!(moments.mean, terminal.mean(), 1e-12);
+        assert_approx_equal!(moments.variance(), terminal.variance(), 1e-12);
+        assert_approx_equal!(
+            moments.std_error(),
+            (terminal.variance() / 1_000.0).sqrt(),
+            1e-12
+        );
+    }
+}
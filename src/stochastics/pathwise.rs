@@ -0,0 +1,330 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pathwise Greeks: Monte Carlo simulation with reverse mode `autodiff`.
+//!
+//! The initial value and the model parameters (e.g. spot, drift and
+//! volatility) are recorded as `Variable`s, and each path is simulated with
+//! the Euler-Maruyama scheme on the tape. The reverse sweep of the
+//! discounted payoff then gives the pathwise sensitivities of that path,
+//! which are averaged over all paths.
+//!
+//! Each worker thread owns a single `Graph` which is rewound to a
+//! checkpoint (just after the inputs are recorded) after every path, so
+//! memory is bounded by the size of a single path's tape, regardless of
+//! the number of paths.
+//!
+//! Note: pathwise derivatives require the payoff to be (almost everywhere)
+//! differentiable, e.g. vanilla calls and puts, but not digitals.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Accumulate, Gradient, Graph, ParallelGradient, Variable};
+use crate::stochastics::{
+    ArithmeticBrownianMotion, CoxIngersollRoss, GeometricBrownianMotion, OrnsteinUhlenbeck,
+    RandomStream, SeededStreams,
+};
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// DIFFERENTIABLE PROCESS TRAIT AND IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Trait for stochastic processes whose drift and diffusion can be
+/// recorded on a `Graph`, with the model parameters as `Variable`s.
+pub trait DifferentiableProcess: Sync {
+    /// The values of the model parameters, in the order they are passed to
+    /// `drift` and `diffusion`. Time-dependent parameters are evaluated at
+    /// time zero, and held constant over the simulation.
+    fn parameters(&self) -> Vec<f64>;
+
+    /// The process' drift, given the model parameters.
+    fn drift<'v>(&self, x: Variable<'v>, t: f64, parameters: &[Variable<'v>]) -> Variable<'v>;
+
+    /// The process' diffusion, given the model parameters.
+    fn diffusion<'v>(&self, x: Variable<'v>, t: f64, parameters: &[Variable<'v>]) -> Variable<'v>;
+}
+
+/// Parameters: `[mu, sigma]`.
+impl DifferentiableProcess for GeometricBrownianMotion {
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.mu.0(0.0), self.sigma.0(0.0)]
+    }
+
+    fn drift<'v>(&self, x: Variable<'v>, _t: f64, parameters: &[Variable<'v>]) -> Variable<'v> {
+        parameters[0] * x
+    }
+
+    fn diffusion<'v>(&self, x: Variable<'v>, _t: f64, parameters: &[Variable<'v>]) -> Variable<'v> {
+        parameters[1] * x
+    }
+}
+
+/// Parameters: `[mu, sigma]`.
+impl DifferentiableProcess for ArithmeticBrownianMotion {
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.mu.0(0.0), self.sigma.0(0.0)]
+    }
+
+    fn drift<'v>(&self, _x: Variable<'v>, _t: f64, parameters: &[Variable<'v>]) -> Variable<'v> {
+        parameters[0]
+    }
+
+    fn diffusion<'v>(
+        &self,
+        _x: Variable<'v>,
+        _t: f64,
+        parameters: &[Variable<'v>],
+    ) -> Variable<'v> {
+        parameters[1]
+    }
+}
+
+/// Parameters: `[mu, sigma, theta]`.
+impl DifferentiableProcess for OrnsteinUhlenbeck {
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.mu.0(0.0), self.sigma.0(0.0), self.theta.0(0.0)]
+    }
+
+    fn drift<'v>(&self, x: Variable<'v>, _t: f64, parameters: &[Variable<'v>]) -> Variable<'v> {
+        parameters[2] * (parameters[0] - x)
+    }
+
+    fn diffusion<'v>(
+        &self,
+        _x: Variable<'v>,
+        _t: f64,
+        parameters: &[Variable<'v>],
+    ) -> Variable<'v> {
+        parameters[1]
+    }
+}
+
+/// Parameters: `[mu, sigma, theta]`.
+impl DifferentiableProcess for CoxIngersollRoss {
+    fn parameters(&self) -> Vec<f64> {
+        vec![self.mu.0(0.0), self.sigma.0(0.0), self.theta.0(0.0)]
+    }
+
+    fn drift<'v>(&self, x: Variable<'v>, _t: f64, parameters: &[Variable<'v>]) -> Variable<'v> {
+        parameters[2] * (parameters[0] - x)
+    }
+
+    fn diffusion<'v>(&self, x: Variable<'v>, _t: f64, parameters: &[Variable<'v>]) -> Variable<'v> {
+        parameters[1] * x.sqrt()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// PATHWISE MONTE CARLO ENGINE
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Monte Carlo engine computing pathwise Greeks via reverse mode `autodiff`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathwiseMonteCarlo {
+    /// The initial time point.
+    pub t_0: f64,
+    /// The terminal time point.
+    pub t_n: f64,
+    /// The number of time steps between `t_0` and `t_n`.
+    pub n_steps: usize,
+    /// How many paths to simulate.
+    pub n_paths: usize,
+    /// The seed for the random number generator.
+    /// Path `i` is simulated with the stream `i` of the [`SeededStreams`]
+    /// of the seed, so the results do not depend on how the paths are
+    /// scheduled across threads.
+    pub seed: u64,
+}
+
+impl PathwiseMonteCarlo {
+    /// Create a new pathwise Monte Carlo engine.
+    #[must_use]
+    pub const fn new(t_0: f64, t_n: f64, n_steps: usize, n_paths: usize, seed: u64) -> Self {
+        Self {
+            t_0,
+            t_n,
+            n_steps,
+            n_paths,
+            seed,
+        }
+    }
+
+    /// Simulate the process and average the (discounted) payoff and its
+    /// pathwise derivatives over all paths.
+    ///
+    /// The inputs are `[x_0, parameters...]` (see
+    /// [`DifferentiableProcess::parameters`]), and the gradient of the
+    /// result is with respect to the inputs, in the same order.
+    ///
+    /// # Arguments:
+    /// * `process` - The stochastic process to simulate.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `payoff` - The discounted payoff. It receives the path
+    ///   (`n_steps + 1` values) and the inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`.
+    ///
+    /// ```
+    /// use RustQuant::autodiff::*;
+    /// use RustQuant::stochastics::*;
+    ///
+    /// let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+    /// let engine = PathwiseMonteCarlo::new(0.0, 1.0, 10, 1_000, 42);
+    ///
+    /// // Discounted forward: E[exp(-mu T) S_T] = S_0.
+    /// let result = engine.simulate(&gbm, 100.0, |path, inputs| {
+    ///     path[path.len() - 1] * (-inputs[1]).exp()
+    /// });
+    ///
+    /// let delta = result.mean_gradient()[0];
+    ///
+    /// assert!((result.mean_value() - 100.0).abs() < 2.0);
+    /// assert!((delta - 1.0).abs() < 0.02);
+    /// ```
+    pub fn simulate<P, F>(&self, process: &P, x_0: f64, payoff: F) -> ParallelGradient
+    where
+        P: DifferentiableProcess,
+        F: for<'v> Fn(&[Variable<'v>], &[Variable<'v>]) -> Variable<'v> + Sync,
+    {
+        assert!(self.t_0 < self.t_n);
+
+        let inputs: Vec<f64> = std::iter::once(x_0).chain(process.parameters()).collect();
+
+        let dt = (self.t_n - self.t_0) / self.n_steps as f64;
+        let times: Vec<f64> = (0..self.n_steps)
+            .map(|t| self.t_0 + dt * t as f64)
+            .collect();
+
+        (0..self.n_paths)
+            .into_par_iter()
+            .map_init(Graph::new, |graph, i| {
+                graph.clear();
+
+                let variables = graph.vars(&inputs);
+                let checkpoint = graph.checkpoint();
+
+                let mut rng = SeededStreams::new(self.seed).stream(i as u64);
+
+                let mut path = Vec::with_capacity(self.n_steps + 1);
+                path.push(variables[0]);
+
+                for &t in &times {
+                    let x = path[path.len() - 1];
+                    let dw: f64 = StandardNormal.sample(&mut rng);
+
+                    path.push(
+                        x + process.drift(x, t, &variables[1..]) * dt
+                            + process.diffusion(x, t, &variables[1..]) * (dw * dt.sqrt()),
+                    );
+                }
+
+                let output = payoff(&path, &variables);
+
+                let result = ParallelGradient {
+                    value: output.value,
+                    gradient: output.accumulate().wrt(&variables),
+                    count: 1,
+                };
+
+                // Discard the path, keeping the inputs.
+                graph.rewind_to(checkpoint);
+
+                result
+            })
+            .reduce(
+                || ParallelGradient::zeros(inputs.len()),
+                ParallelGradient::merge,
+            )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_pathwise {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::autodiff::Max;
+    use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+    #[test]
+    fn test_black_scholes_greeks() {
+        let (s, k, t, r, v) = (100.0_f64, 105.0, 1.0_f64, 0.05, 0.2);
+
+        let gbm = GeometricBrownianMotion::new(r, v);
+        let engine = PathwiseMonteCarlo::new(0.0, t, 50, 20_000, 1234);
+
+        // Discounted call payoff, with the drift as the risk-free rate.
+        let result = engine.simulate(&gbm, s, |path, inputs| {
+            Max::max(&(path[path.len() - 1] - k), 0.0) * (-inputs[1] * t).exp()
+        });
+
+        let n = Normal::new(0.0, 1.0).unwrap();
+        let d1 = ((s / k).ln() + (r + 0.5 * v * v) * t) / (v * t.sqrt());
+        let d2 = d1 - v * t.sqrt();
+
+        let price = s * n.cdf(d1) - k * (-r * t).exp() * n.cdf(d2);
+        let delta = n.cdf(d1);
+        let rho = k * t * (-r * t).exp() * n.cdf(d2);
+        let vega = s * n.pdf(d1) * t.sqrt();
+
+        let greeks = result.mean_gradient();
+
+        assert_eq!(result.count, 20_000);
+        assert_approx_equal!(result.mean_value(), price, 0.3);
+        assert_approx_equal!(greeks[0], delta, 0.02);
+        assert_approx_equal!(greeks[1], rho, 2.0);
+        assert_approx_equal!(greeks[2], vega, 2.0);
+    }
+
+    #[test]
+    fn test_ornstein_uhlenbeck_mean() {
+        let (x_0, mu, sigma, theta, t) = (1.0_f64, 2.0, 0.3, 1.5, 1.0_f64);
+
+        let ou = OrnsteinUhlenbeck::new(mu, sigma, theta);
+        let engine = PathwiseMonteCarlo::new(0.0, t, 200, 2_000, 7);
+
+        let result = engine.simulate(&ou, x_0, |path, _| path[path.len() - 1]);
+        let gradient = result.mean_gradient();
+
+        // E[X_T] = mu + (x_0 - mu) exp(-theta T) is linear in x_0 and mu,
+        // so its pathwise derivatives do not depend on the random numbers.
+        let decay = (-theta * t).exp();
+
+        assert_approx_equal!(result.mean_value(), mu + (x_0 - mu) * decay, 0.05);
+        assert_approx_equal!(gradient[0], decay, 0.005);
+        assert_approx_equal!(gradient[1], 1.0 - decay, 0.005);
+        assert_approx_equal!(gradient[2], 0.0, 0.05);
+        assert_approx_equal!(gradient[3], (mu - x_0) * t * decay, 0.01);
+    }
+
+    #[test]
+    fn test_deterministic_given_seed() {
+        let gbm = GeometricBrownianMotion::new(0.0, 0.3);
+        let engine = PathwiseMonteCarlo::new(0.0, 1.0, 10, 100, 99);
+
+        fn payoff<'v>(path: &[Variable<'v>], _: &[Variable<'v>]) -> Variable<'v> {
+            path[path.len() - 1].ln()
+        }
+
+        let a = engine.simulate(&gbm, 50.0, payoff);
+        let b = engine.simulate(&gbm, 50.0, payoff);
+
+        assert_approx_equal!(a.value, b.value, 1e-9);
+        assert_approx_equal!(a.gradient[2], b.gradient[2], 1e-9);
+    }
+}
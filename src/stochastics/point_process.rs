@@ -0,0 +1,471 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Point processes: random arrival times, e.g. of defaults or orders.
+//!
+//! - Poisson processes, homogeneous or with an intensity $\lambda(t)$
+//!   varying in time, sampled by thinning (Lewis and Shedler, 1979): the
+//!   candidate arrivals of a homogeneous process with a bounding intensity
+//!   $\bar\lambda$ are kept with probability $\lambda(t) / \bar\lambda$.
+//! - Hawkes processes, self-exciting with an exponential kernel:
+//!   $\lambda(t) = \mu + \sum_{t_i < t} \alpha e^{-\beta (t - t_i)}$,
+//!   sampled by Ogata's (1981) thinning, where the bound is the intensity
+//!   just after the last arrival. The process is stationary if the
+//!   branching ratio $\alpha / \beta$ is below one.
+//! - Cox (doubly stochastic) processes, whose intensity is itself a
+//!   stochastic process, e.g. a Cox-Ingersoll-Ross default intensity: the
+//!   arrivals are the times at which the integrated intensity crosses the
+//!   partial sums of standard exponential draws. The first arrival is a
+//!   default time, with survival probability
+//!   $E\left[ e^{-\int_0^t \lambda(s) ds} \right]$.
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{
+    EntropyStreams, RandomStream, SimulationScheme, StochasticProcess, TimeDependent,
+};
+use rand::Rng;
+use rand_distr::{Distribution, Exp1};
+use rayon::prelude::*;
+
+/// Process of random arrival times.
+pub trait PointProcess: Sync {
+    /// Arrival times of one path over `(t_0, t_n]`, in increasing order.
+    fn sample<R: Rng>(&self, t_0: f64, t_n: f64, rng: &mut R) -> Vec<f64>;
+
+    /// Arrival times of `m_paths` paths over `(t_0, t_n]`.
+    ///
+    /// # Arguments:
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `m_paths` - How many paths to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    fn arrival_times(&self, t_0: f64, t_n: f64, m_paths: usize, parallel: bool) -> Vec<Vec<f64>> {
+        self.arrival_times_with_streams(t_0, t_n, m_paths, parallel, &EntropyStreams)
+    }
+
+    /// Arrival times of `m_paths` paths over `(t_0, t_n]`, with a choice of
+    /// random seed.
+    ///
+    /// # Arguments:
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `m_paths` - How many paths to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    #[cfg(feature = "seedable")]
+    fn seedable_arrival_times(
+        &self,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> Vec<Vec<f64>> {
+        self.arrival_times_with_streams(t_0, t_n, m_paths, parallel, &SeededStreams::new(seed))
+    }
+
+    /// Arrival times of `m_paths` paths over `(t_0, t_n]`, where the path
+    /// `i` draws its random numbers from the stream `i` of `streams`.
+    ///
+    /// # Arguments:
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `m_paths` - How many paths to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    fn arrival_times_with_streams<S: RandomStream>(
+        &self,
+        t_0: f64,
+        t_n: f64,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> Vec<Vec<f64>> {
+        assert!(t_0 < t_n);
+
+        let path_generator = |path: usize| self.sample(t_0, t_n, &mut streams.stream(path as u64));
+
+        if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).collect()
+        } else {
+            (0..m_paths).map(path_generator).collect()
+        }
+    }
+}
+
+/// Poisson process, homogeneous or with a time-dependent intensity.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+///
+/// // Intraday order arrivals, more frequent at the open and the close.
+/// let orders = PoissonProcess::inhomogeneous(|t: f64| 100.0 + 400.0 * (t - 0.5).powi(2), 200.0);
+///
+/// let arrivals = orders.arrival_times(0.0, 1.0, 10, false);
+/// assert!(arrivals.iter().all(|path| path.windows(2).all(|w| w[0] < w[1])));
+/// ```
+#[derive(Debug)]
+pub struct PoissonProcess {
+    /// The intensity ($\lambda(t)$), per unit of time.
+    pub intensity: TimeDependent,
+
+    /// An upper bound of the intensity ($\bar\lambda$), for thinning.
+    pub bound: f64,
+}
+
+impl PoissonProcess {
+    /// Homogeneous Poisson process with a constant intensity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the intensity is negative.
+    #[must_use]
+    pub fn homogeneous(intensity: f64) -> Self {
+        assert!(intensity >= 0.0, "The intensity must be non-negative.");
+
+        Self {
+            intensity: intensity.into(),
+            bound: intensity,
+        }
+    }
+
+    /// Inhomogeneous Poisson process with the intensity `intensity`, bounded
+    /// above by `bound` (the tighter the bound, the fewer candidates are
+    /// rejected).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bound is negative.
+    pub fn inhomogeneous(intensity: impl Into<TimeDependent>, bound: f64) -> Self {
+        assert!(bound >= 0.0, "The bound must be non-negative.");
+
+        Self {
+            intensity: intensity.into(),
+            bound,
+        }
+    }
+}
+
+impl PointProcess for PoissonProcess {
+    /// # Panics
+    ///
+    /// Panics if the intensity exceeds its bound at a candidate arrival.
+    fn sample<R: Rng>(&self, t_0: f64, t_n: f64, rng: &mut R) -> Vec<f64> {
+        let mut arrivals = Vec::new();
+        if self.bound <= 0.0 {
+            return arrivals;
+        }
+
+        let mut t = t_0;
+        loop {
+            let draw: f64 = Exp1.sample(rng);
+            t += draw / self.bound;
+            if t > t_n {
+                return arrivals;
+            }
+
+            let intensity = self.intensity.0(t);
+            assert!(
+                intensity <= self.bound * (1.0 + 1e-12),
+                "The intensity exceeds its bound."
+            );
+            if rng.gen::<f64>() * self.bound < intensity {
+                arrivals.push(t);
+            }
+        }
+    }
+}
+
+/// Hawkes self-exciting process with an exponential kernel.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+///
+/// // Each arrival raises the intensity by 0.8, decaying at the rate 1.2.
+/// let hawkes = HawkesProcess::new(0.5, 0.8, 1.2);
+///
+/// assert!(hawkes.branching_ratio() < 1.0);
+///
+/// let arrivals = hawkes.arrival_times(0.0, 100.0, 1, false);
+/// let intensity = hawkes.intensity(100.0, &arrivals[0]);
+/// assert!(intensity >= 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HawkesProcess {
+    /// The baseline intensity ($\mu$).
+    pub baseline: f64,
+
+    /// Jump of the intensity at each arrival ($\alpha$).
+    pub alpha: f64,
+
+    /// Decay rate of the jumps ($\beta$).
+    pub beta: f64,
+}
+
+impl HawkesProcess {
+    /// Create a new Hawkes process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a parameter is negative, or the decay rate is zero.
+    #[must_use]
+    pub fn new(baseline: f64, alpha: f64, beta: f64) -> Self {
+        assert!(
+            baseline >= 0.0 && alpha >= 0.0 && beta > 0.0,
+            "Requires baseline >= 0, alpha >= 0 and beta > 0."
+        );
+
+        Self {
+            baseline,
+            alpha,
+            beta,
+        }
+    }
+
+    /// Expected number of arrivals triggered by each arrival,
+    /// $\alpha / \beta$.
+    #[must_use]
+    pub fn branching_ratio(&self) -> f64 {
+        self.alpha / self.beta
+    }
+
+    /// Intensity at `t` given the arrivals `history` (those before `t`
+    /// count).
+    #[must_use]
+    pub fn intensity(&self, t: f64, history: &[f64]) -> f64 {
+        self.baseline
+            + history
+                .iter()
+                .take_while(|s| **s < t)
+                .map(|s| self.alpha * (-self.beta * (t - s)).exp())
+                .sum::<f64>()
+    }
+
+    /// Expected number of arrivals over `(0, t]`, from the baseline
+    /// intensity at time 0.
+    #[must_use]
+    pub fn expected_count(&self, t: f64) -> f64 {
+        let (mu, kappa) = (self.baseline, self.beta - self.alpha);
+
+        if kappa.abs() < 1e-12 {
+            // Critical: the mean intensity grows linearly.
+            return mu * t + 0.5 * mu * self.alpha * t * t;
+        }
+
+        let stationary = mu * self.beta / kappa;
+        stationary * t + (mu - stationary) * -(-kappa * t).exp_m1() / kappa
+    }
+}
+
+impl PointProcess for HawkesProcess {
+    fn sample<R: Rng>(&self, t_0: f64, t_n: f64, rng: &mut R) -> Vec<f64> {
+        let mut arrivals = Vec::new();
+
+        // Excitation above the baseline, just after the current time `t`.
+        let (mut t, mut excitation) = (t_0, 0.0);
+        loop {
+            // The intensity decreases until the next arrival: its current
+            // value bounds it.
+            let bound = self.baseline + excitation;
+            if bound <= 0.0 {
+                return arrivals;
+            }
+
+            let draw: f64 = Exp1.sample(rng);
+            let wait = draw / bound;
+            t += wait;
+            if t > t_n {
+                return arrivals;
+            }
+
+            excitation *= (-self.beta * wait).exp();
+            if rng.gen::<f64>() * bound < self.baseline + excitation {
+                arrivals.push(t);
+                excitation += self.alpha;
+            }
+        }
+    }
+}
+
+/// Cox (doubly stochastic) process, with a stochastic intensity simulated on
+/// a time grid.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+///
+/// // Cox-Ingersoll-Ross default intensity, starting at 2%.
+/// let intensity = CoxIngersollRoss::new(0.03, 0.05, 0.5);
+/// let defaults = CoxProcess::new(intensity, 0.02, 50);
+///
+/// // Default times over five years, if any.
+/// let default_times: Vec<Option<f64>> = defaults
+///     .arrival_times(0.0, 5.0, 1_000, true)
+///     .iter()
+///     .map(|arrivals| arrivals.first().copied())
+///     .collect();
+/// ```
+#[derive(Debug)]
+pub struct CoxProcess<P: StochasticProcess> {
+    /// The intensity process.
+    pub intensity: P,
+
+    /// The initial intensity.
+    pub intensity_0: f64,
+
+    /// The number of time steps of the intensity between `t_0` and `t_n`.
+    pub n_steps: usize,
+
+    /// The discretisation scheme of the intensity.
+    pub scheme: SimulationScheme,
+}
+
+impl<P: StochasticProcess> CoxProcess<P> {
+    /// Create a new Cox process, with the intensity process `intensity`
+    /// starting at `intensity_0`, simulated with the Euler-Maruyama scheme
+    /// over `n_steps` steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_steps` is zero.
+    pub fn new(intensity: P, intensity_0: f64, n_steps: usize) -> Self {
+        assert!(n_steps > 0, "At least one time step is required.");
+
+        Self {
+            intensity,
+            intensity_0,
+            n_steps,
+            scheme: SimulationScheme::EulerMaruyama,
+        }
+    }
+
+    /// With the discretisation scheme `scheme` for the intensity.
+    #[must_use]
+    pub fn with_scheme(self, scheme: SimulationScheme) -> Self {
+        Self { scheme, ..self }
+    }
+}
+
+impl<P: StochasticProcess> PointProcess for CoxProcess<P> {
+    fn sample<R: Rng>(&self, t_0: f64, t_n: f64, rng: &mut R) -> Vec<f64> {
+        let dt = (t_n - t_0) / self.n_steps as f64;
+        let mut arrivals = Vec::new();
+
+        // Integrated intensity (negative values floored at zero), and the
+        // level at which the next arrival occurs.
+        let mut integral = 0.0;
+        let mut threshold: f64 = Exp1.sample(rng);
+
+        let mut lambda = self.intensity_0;
+        for step in 0..self.n_steps {
+            let t = t_0 + dt * step as f64;
+            let next = self.scheme.step(&self.intensity, lambda, t, dt, rng);
+
+            let (left, right) = (lambda.max(0.0), next.max(0.0));
+            let increment = 0.5 * (left + right) * dt;
+
+            // Arrivals within the step, by inverting the trapezoid (the
+            // integrated intensity is quadratic in time over the step).
+            while integral + increment >= threshold && increment > 0.0 {
+                let target = threshold - integral;
+                let slope = (right - left) / dt;
+                let s = if slope.abs() < 1e-12 {
+                    target / left
+                } else {
+                    ((left * left + 2.0 * slope * target).sqrt() - left) / slope
+                };
+                arrivals.push(t + s.clamp(0.0, dt));
+
+                let draw: f64 = Exp1.sample(rng);
+                threshold += draw;
+            }
+
+            integral += increment;
+            lambda = next;
+        }
+
+        arrivals
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_point_process {
+    use super::*;
+    use crate::models::{CoxIngersollRossModel, ShortRateModel};
+    use crate::stochastics::{CoxIngersollRoss, SeededStreams};
+    use crate::{assert_approx_equal, statistics::*};
+
+    const PATHS: usize = 20_000;
+
+    fn counts<P: PointProcess>(process: &P, t_n: f64) -> Vec<f64> {
+        process
+            .arrival_times_with_streams(0.0, t_n, PATHS, true, &SeededStreams::new(13))
+            .iter()
+            .map(|arrivals| {
+                assert!(arrivals.windows(2).all(|w| w[0] <= w[1]));
+                assert!(arrivals.iter().all(|t| *t > 0.0 && *t <= t_n));
+                arrivals.len() as f64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_poisson() {
+        // Mean and variance of the counts: the integrated intensity.
+        let homogeneous = counts(&PoissonProcess::homogeneous(3.0), 2.0);
+        assert_approx_equal!(homogeneous.mean(), 6.0, 0.05);
+        assert_approx_equal!(homogeneous.variance(), 6.0, 0.2);
+
+        let seasonal = PoissonProcess::inhomogeneous(|t: f64| 2.0 + t.sin(), 3.0);
+        let expected = 2.0 * 4.0 + 1.0 - 4.0_f64.cos();
+        let inhomogeneous = counts(&seasonal, 4.0);
+        assert_approx_equal!(inhomogeneous.mean(), expected, 0.08);
+        assert_approx_equal!(inhomogeneous.variance(), expected, 0.3);
+    }
+
+    #[test]
+    fn test_hawkes() {
+        let hawkes = HawkesProcess::new(1.0, 0.6, 1.5);
+        let hawkes_counts = counts(&hawkes, 5.0);
+        assert_approx_equal!(hawkes_counts.mean(), hawkes.expected_count(5.0), 0.1);
+
+        // Self-excitation: the counts are overdispersed.
+        assert!(hawkes_counts.variance() > 1.5 * hawkes_counts.mean());
+
+        // Without excitation, a homogeneous Poisson process.
+        let poisson = HawkesProcess::new(2.0, 0.0, 1.0);
+        assert_approx_equal!(counts(&poisson, 3.0).mean(), 6.0, 0.05);
+        assert_approx_equal!(poisson.expected_count(3.0), 6.0, 1e-12);
+    }
+
+    #[test]
+    fn test_cox_survival() {
+        // The survival probability of a CIR intensity is the CIR bond price.
+        let (lambda_0, kappa, theta, sigma) = (0.05, 0.8, 0.1, 0.2);
+        let defaults = CoxProcess::new(CoxIngersollRoss::new(theta, sigma, kappa), lambda_0, 200)
+            .with_scheme(SimulationScheme::Exact);
+        let model = CoxIngersollRossModel::new(lambda_0, kappa, theta, sigma);
+
+        let arrivals =
+            defaults.arrival_times_with_streams(0.0, 5.0, PATHS, true, &SeededStreams::new(17));
+        for t in [1.0, 5.0] {
+            let survival = arrivals
+                .iter()
+                .filter(|path| !path.first().is_some_and(|tau| *tau <= t))
+                .count() as f64
+                / PATHS as f64;
+
+            assert_approx_equal!(survival, model.discount_factor(t), 0.01);
+        }
+    }
+}
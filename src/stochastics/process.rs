@@ -14,7 +14,12 @@
 //! Autonomous refers to processes where the drift and diffusion
 //! do not explicitly depend on the time `t`.
 
+use crate::stochastics::schemes::{simulate_paths, SimulationScheme};
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{EntropyStreams, RandomStream};
 use rand::prelude::Distribution;
+use rand::RngCore;
 #[cfg(feature = "seedable")]
 use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::*;
@@ -45,6 +50,146 @@ where
     }
 }
 
+impl TimeDependent {
+    /// Piecewise-constant function of time, equal to `values[i]` on
+    /// `[times[i - 1], times[i])` (from `-inf` for `i = 0`), and to the last
+    /// value after the last time. Right-continuous, so a time step starting
+    /// at a pillar uses the value of the following period.
+    ///
+    /// ```
+    /// use RustQuant::stochastics::TimeDependent;
+    ///
+    /// let sigma = TimeDependent::piecewise_constant(&[1.0, 2.0], &[0.2, 0.3]);
+    ///
+    /// assert_eq!(sigma.0(0.5), 0.2);
+    /// assert_eq!(sigma.0(1.0), 0.3);
+    /// assert_eq!(sigma.0(5.0), 0.3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `times` and `values` are empty or differ in length, or the
+    /// times are not strictly increasing.
+    #[must_use]
+    pub fn piecewise_constant(times: &[f64], values: &[f64]) -> Self {
+        validate_term_structure(times, values);
+
+        let (times, values) = (times.to_vec(), values.to_vec());
+        Self::from(move |t: f64| {
+            let i = times.partition_point(|pillar| *pillar <= t);
+            values[i.min(values.len() - 1)]
+        })
+    }
+
+    /// Piecewise-linear interpolation of `values` at `times`, constant
+    /// before the first time and after the last.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `times` and `values` are empty or differ in length, or the
+    /// times are not strictly increasing.
+    #[must_use]
+    pub fn piecewise_linear(times: &[f64], values: &[f64]) -> Self {
+        validate_term_structure(times, values);
+
+        let (times, values) = (times.to_vec(), values.to_vec());
+        Self::from(move |t: f64| {
+            let i = times.partition_point(|pillar| *pillar <= t);
+            if i == 0 {
+                return values[0];
+            }
+            if i == times.len() {
+                return values[i - 1];
+            }
+
+            let w = (t - times[i - 1]) / (times[i] - times[i - 1]);
+            values[i - 1] + w * (values[i] - values[i - 1])
+        })
+    }
+
+    /// Piecewise-constant instantaneous volatility matching the term
+    /// structure of implied volatilities `volatilities` at `expiries`: the
+    /// forward volatility between consecutive expiries,
+    /// $\sigma_i^2 = \frac{\hat\sigma_i^2 T_i - \hat\sigma_{i-1}^2 T_{i-1}}{T_i - T_{i-1}}$.
+    ///
+    /// ```
+    /// use RustQuant::stochastics::TimeDependent;
+    ///
+    /// // Implied volatilities of 20% to one year and 25% to two years.
+    /// let sigma = TimeDependent::forward_volatility(&[1.0, 2.0], &[0.2, 0.25]);
+    ///
+    /// // The total variance to two years is 0.25^2 * 2.
+    /// let total = sigma.0(0.5).powi(2) + sigma.0(1.5).powi(2);
+    /// assert!((total - 0.125).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inputs are empty or differ in length, the expiries are
+    /// not positive and strictly increasing, or the total implied variance
+    /// decreases (calendar arbitrage).
+    #[must_use]
+    pub fn forward_volatility(expiries: &[f64], volatilities: &[f64]) -> Self {
+        let variances = forward_values(expiries, volatilities, |sigma| sigma * sigma);
+        assert!(
+            variances.iter().all(|v| *v >= 0.0),
+            "The total implied variance must not decrease."
+        );
+
+        let volatilities: Vec<f64> = variances.iter().map(|v| v.sqrt()).collect();
+        Self::piecewise_constant(expiries, &volatilities)
+    }
+
+    /// Piecewise-constant instantaneous forward rate matching the term
+    /// structure of continuously compounded zero rates `rates` at
+    /// `maturities`:
+    /// $f_i = \frac{z_i T_i - z_{i-1} T_{i-1}}{T_i - T_{i-1}}$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inputs are empty or differ in length, or the maturities
+    /// are not positive and strictly increasing.
+    #[must_use]
+    pub fn forward_rate(maturities: &[f64], rates: &[f64]) -> Self {
+        let forwards = forward_values(maturities, rates, |rate| rate);
+
+        Self::piecewise_constant(maturities, &forwards)
+    }
+}
+
+// Checks the pillars of a term structure.
+fn validate_term_structure(times: &[f64], values: &[f64]) {
+    assert!(!times.is_empty(), "At least one pillar is required.");
+    assert_eq!(
+        times.len(),
+        values.len(),
+        "There must be one value per time."
+    );
+    assert!(
+        times.windows(2).all(|w| w[0] < w[1]),
+        "The times must be strictly increasing."
+    );
+}
+
+// Forward values of `g(quote)` between consecutive times, from quotes that
+// are averages from time 0: (g_i T_i - g_{i-1} T_{i-1}) / (T_i - T_{i-1}).
+fn forward_values(times: &[f64], quotes: &[f64], g: impl Fn(f64) -> f64) -> Vec<f64> {
+    validate_term_structure(times, quotes);
+    assert!(times[0] > 0.0, "The times must be positive.");
+
+    let mut previous = (0.0, 0.0);
+    times
+        .iter()
+        .zip(quotes)
+        .map(|(t, quote)| {
+            let total = g(*quote) * t;
+            let forward = (total - previous.1) / (t - previous.0);
+            previous = (*t, total);
+            forward
+        })
+        .collect()
+}
+
 /// Struct to contain the time points and path values of the process.
 pub struct Trajectories {
     /// Vector of time points.
@@ -66,6 +211,164 @@ pub trait StochasticProcess: Sync {
     /// Base method for the process' jump term (if applicable).
     fn jump(&self, x: f64, t: f64) -> Option<f64>;
 
+    /// Derivative of the diffusion in `x`, used by the Milstein and
+    /// predictor-corrector schemes. Defaults to a central finite difference.
+    fn diffusion_derivative(&self, x: f64, t: f64) -> f64 {
+        let h = 1e-6 * x.abs().max(1.0);
+
+        (self.diffusion(x + h, t) - self.diffusion(x - h, t)) / (2.0 * h)
+    }
+
+    /// Sample of the process at `t + dt` from `x` at `t`, drawn from the
+    /// transition density, for the exact simulation scheme. `None` (the
+    /// default) if the transition density is not known.
+    fn exact_step(&self, _x: f64, _t: f64, _dt: f64, _rng: &mut dyn RngCore) -> Option<f64> {
+        None
+    }
+
+    /// Simulation with a choice of discretisation scheme. The jump term, if
+    /// any, is not simulated.
+    ///
+    /// # Arguments:
+    /// * `scheme` - The discretisation scheme.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    ///
+    /// ```
+    /// use RustQuant::stochastics::*;
+    ///
+    /// let gbm = GeometricBrownianMotion::new(0.05, 0.9);
+    ///
+    /// let output = gbm.simulate(SimulationScheme::Milstein, 10.0, 0.0, 0.5, 10, 1, false);
+    ///
+    /// assert_eq!(output.paths[0].len(), 11);
+    ///
+    /// // Without discretisation bias: a single step.
+    /// let output = gbm.simulate(SimulationScheme::Exact, 10.0, 0.0, 0.5, 1, 1, false);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics with [`SimulationScheme::Exact`] if the process has no exact
+    /// transition.
+    ///
+    /// ```should_panic
+    /// use RustQuant::stochastics::*;
+    ///
+    /// let ho_lee = HoLee::new(0.01, 0.02);
+    ///
+    /// let output = ho_lee.simulate(SimulationScheme::Exact, 0.03, 0.0, 1.0, 10, 1, false);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    fn simulate(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> Trajectories {
+        simulate_paths(
+            self,
+            scheme,
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    /// Simulation with a choice of discretisation scheme and random seed.
+    /// The jump term, if any, is not simulated.
+    ///
+    /// # Panics
+    ///
+    /// Panics with [`SimulationScheme::Exact`] if the process has no exact
+    /// transition.
+    ///
+    /// # Arguments:
+    /// * `scheme` - The discretisation scheme.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    #[cfg(feature = "seedable")]
+    #[allow(clippy::too_many_arguments)]
+    fn seedable_simulate(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> Trajectories {
+        simulate_paths(
+            self,
+            scheme,
+            x_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    /// Simulation with a choice of discretisation scheme, where the path `i`
+    /// draws its random numbers from the stream `i` of `streams`. The jump
+    /// term, if any, is not simulated.
+    ///
+    /// # Panics
+    ///
+    /// Panics with [`SimulationScheme::Exact`] if the process has no exact
+    /// transition.
+    ///
+    /// # Arguments:
+    /// * `scheme` - The discretisation scheme.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    #[allow(clippy::too_many_arguments)]
+    fn simulate_with_streams<S>(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> Trajectories
+    where
+        Self: Sized,
+        S: RandomStream,
+    {
+        simulate_paths(
+            self, scheme, x_0, t_0, t_n, n_steps, m_paths, parallel, streams,
+        )
+    }
+
     /// Euler-Maruyama discretisation scheme.
     ///
     /// # Arguments:
@@ -177,9 +480,59 @@ pub trait StochasticProcess: Sync {
 #[cfg(test)]
 mod test_process {
     use super::*;
-    use crate::stochastics::GeometricBrownianMotion;
+    use crate::stochastics::{GeometricBrownianMotion, SeededStreams};
+    use crate::{assert_approx_equal, statistics::*};
     use std::time::Instant;
 
+    #[test]
+    fn test_term_structures() {
+        let linear = TimeDependent::piecewise_linear(&[1.0, 3.0], &[0.01, 0.05]);
+        assert_approx_equal!(linear.0(0.0), 0.01, 1e-15);
+        assert_approx_equal!(linear.0(2.5), 0.04, 1e-15);
+        assert_approx_equal!(linear.0(4.0), 0.05, 1e-15);
+
+        // Forward rates recover the discount factors of the zero rates.
+        let forward = TimeDependent::forward_rate(&[0.5, 2.0, 5.0], &[0.02, 0.03, 0.035]);
+        let integral = 0.5 * forward.0(0.2) + 1.5 * forward.0(1.0) + 3.0 * forward.0(4.9);
+        assert_approx_equal!(integral, 0.035 * 5.0, 1e-12);
+
+        // A geometric Brownian motion with the forward volatilities matches
+        // the implied variances at every expiry.
+        let (expiries, implied) = ([0.5, 1.0, 2.0], [0.3, 0.25, 0.22]);
+        let gbm = GeometricBrownianMotion::new(
+            TimeDependent::forward_rate(&expiries, &[0.01, 0.02, 0.03]),
+            TimeDependent::forward_volatility(&expiries, &implied),
+        );
+        let output = gbm.simulate_with_streams(
+            SimulationScheme::Exact,
+            1.0,
+            0.0,
+            2.0,
+            8,
+            100_000,
+            true,
+            &SeededStreams::new(5),
+        );
+
+        let rates = [0.01, 0.02, 0.03];
+        for (step, ((expiry, sigma), rate)) in [2, 4, 8]
+            .into_iter()
+            .zip(expiries.iter().zip(implied).zip(rates))
+        {
+            let values: Vec<f64> = output.paths.iter().map(|path| path[step]).collect();
+            let logs: Vec<f64> = values.iter().map(|x| x.ln()).collect();
+
+            assert_approx_equal!(logs.variance(), sigma * sigma * expiry, 0.003);
+            assert_approx_equal!(values.mean(), (rate * expiry).exp(), 0.005);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The total implied variance must not decrease.")]
+    fn test_calendar_arbitrage() {
+        let _ = TimeDependent::forward_volatility(&[1.0, 2.0], &[0.3, 0.2]);
+    }
+
     #[test]
     fn test_euler_maruyama() {
         let gbm = GeometricBrownianMotion::new(0.05, 0.9);
@@ -0,0 +1,459 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Random number streams for the path generators.
+//!
+//! The generators draw the random numbers of each path (or chunk of paths)
+//! from its own stream, indexed by the path (or chunk) number. The streams
+//! are given by a [`RandomStream`], so simulations are reproducible whatever
+//! the order the paths are generated in, and any generator implementing
+//! `rand::RngCore` can be used: e.g. counter-based generators, whose
+//! streams are independent by construction.
+//!
+//! ```
+//! use RustQuant::stochastics::*;
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+//!
+//! // Any function of the stream index returning a generator.
+//! let streams = |stream: u64| StdRng::seed_from_u64(stream.wrapping_mul(7919));
+//!
+//! let first = gbm.simulate_with_streams(SimulationScheme::Milstein, 100.0, 0.0, 1.0, 10, 10, true, &streams);
+//! let second = gbm.simulate_with_streams(SimulationScheme::Milstein, 100.0, 0.0, 1.0, 10, 10, false, &streams);
+//!
+//! assert_eq!(first.paths, second.paths);
+//! ```
+//!
+//! A [`SampleDesign`] turns the streams into the standard Gaussian draws of a
+//! set of samples, independent or spread evenly over their distribution by
+//! stratified or Latin hypercube [`Sampling`].
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
+use rand_distr::{Open01, StandardNormal};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// Source of independent random number streams.
+pub trait RandomStream: Sync {
+    /// The random number generator of a stream.
+    type Rng: RngCore;
+
+    /// The generator of the stream `stream`.
+    fn stream(&self, stream: u64) -> Self::Rng;
+}
+
+impl<F, R> RandomStream for F
+where
+    F: Fn(u64) -> R + Sync,
+    R: RngCore,
+{
+    type Rng = R;
+
+    fn stream(&self, stream: u64) -> R {
+        self(stream)
+    }
+}
+
+/// `StdRng` streams from a master seed. The seed of each stream is hashed
+/// from the master seed and the stream index by the SplitMix64 finaliser,
+/// so that the streams of consecutive master seeds do not overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeededStreams {
+    /// The master seed.
+    pub seed: u64,
+}
+
+impl SeededStreams {
+    /// Streams from the master seed `seed`.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl RandomStream for SeededStreams {
+    type Rng = StdRng;
+
+    fn stream(&self, stream: u64) -> StdRng {
+        let mut z = self.seed ^ stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        StdRng::seed_from_u64(z ^ (z >> 31))
+    }
+}
+
+/// `StdRng` streams seeded from the operating system's entropy source: not
+/// reproducible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EntropyStreams;
+
+impl RandomStream for EntropyStreams {
+    type Rng = StdRng;
+
+    fn stream(&self, _stream: u64) -> StdRng {
+        StdRng::from_entropy()
+    }
+}
+
+/// Sampling of the standard Gaussian draws of a set of samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    /// Independent draws.
+    #[default]
+    Independent,
+
+    /// The first draw of each sample stratified, the others independent:
+    /// the samples are allocated in equal numbers (up to one) to
+    /// equiprobable strata of the distribution of the first draw, with
+    /// [`SampleDesign::SAMPLES_PER_STRATUM`] samples per stratum.
+    Stratified,
+
+    /// Latin hypercube: in every dimension, each of the equiprobable strata,
+    /// one per sample, holds exactly one draw.
+    LatinHypercube,
+}
+
+/// Standard Gaussian draws of a set of samples, with a choice of
+/// [`Sampling`].
+///
+/// The sample `i` draws from the stream `i`, and the Latin hypercube
+/// permutations from the stream `n_samples`.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+///
+/// let design = SampleDesign::new(Sampling::Stratified, 100, 3, &SeededStreams::new(1));
+/// assert_eq!(design.strata(), 25);
+///
+/// // The first draws of the samples 0 and 99 are in the lowest and highest
+/// // strata: below and above the 4% and 96% quantiles.
+/// let streams = SeededStreams::new(1);
+/// assert!(design.normals(0, &mut streams.stream(0))[0] < -1.75);
+/// assert!(design.normals(99, &mut streams.stream(99))[0] > 1.75);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SampleDesign {
+    sampling: Sampling,
+    n_samples: usize,
+    dimension: usize,
+    strata: usize,
+    // Stratum of each sample, in each dimension (Latin hypercube only).
+    permutations: Vec<Vec<usize>>,
+}
+
+impl SampleDesign {
+    /// Number of samples per stratum of [`Sampling::Stratified`], enough to
+    /// estimate the variance within each stratum.
+    pub const SAMPLES_PER_STRATUM: usize = 4;
+
+    /// Design of `n_samples` samples of `dimension` draws each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no samples.
+    pub fn new<S: RandomStream + ?Sized>(
+        sampling: Sampling,
+        n_samples: usize,
+        dimension: usize,
+        streams: &S,
+    ) -> Self {
+        assert!(n_samples > 0, "At least one sample is required.");
+
+        let strata = match sampling {
+            Sampling::Stratified => (n_samples / Self::SAMPLES_PER_STRATUM).max(1),
+            Sampling::Independent | Sampling::LatinHypercube => 1,
+        };
+
+        let permutations = if sampling == Sampling::LatinHypercube {
+            let mut rng = streams.stream(n_samples as u64);
+            (0..dimension)
+                .map(|_| {
+                    let mut permutation: Vec<usize> = (0..n_samples).collect();
+                    permutation.shuffle(&mut rng);
+                    permutation
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            sampling,
+            n_samples,
+            dimension,
+            strata,
+            permutations,
+        }
+    }
+
+    /// Number of strata the samples are allocated to (one without
+    /// stratification).
+    #[must_use]
+    pub fn strata(&self) -> usize {
+        self.strata
+    }
+
+    /// Stratum of the sample `sample`, in `0..strata()`.
+    #[must_use]
+    pub fn stratum(&self, sample: usize) -> usize {
+        sample * self.strata / self.n_samples
+    }
+
+    /// Standard Gaussian draws of the sample `sample`, from the generator of
+    /// its stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample` is not less than the number of samples.
+    #[must_use]
+    pub fn normals<R: Rng + ?Sized>(&self, sample: usize, rng: &mut R) -> Vec<f64> {
+        assert!(sample < self.n_samples, "Unknown sample.");
+
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let quantile = |stratum: usize, strata: usize, u: f64| {
+            normal.inverse_cdf((stratum as f64 + u) / strata as f64)
+        };
+
+        match self.sampling {
+            Sampling::Independent => (0..self.dimension)
+                .map(|_| rng.sample(StandardNormal))
+                .collect(),
+            Sampling::Stratified => (0..self.dimension)
+                .map(|d| {
+                    if d == 0 {
+                        quantile(self.stratum(sample), self.strata, rng.sample(Open01))
+                    } else {
+                        rng.sample(StandardNormal)
+                    }
+                })
+                .collect(),
+            Sampling::LatinHypercube => self
+                .permutations
+                .iter()
+                .map(|permutation| {
+                    quantile(permutation[sample], self.n_samples, rng.sample(Open01))
+                })
+                .collect(),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_random {
+    use super::*;
+    use crate::stochastics::{
+        CorrelatedProcesses, GeometricBrownianMotion, Heston, JumpDiffusion, JumpEffect, JumpSize,
+        OrnsteinUhlenbeck, RoughBergomi, Sabr, SimulationScheme, StochasticProcess, ZeroBoundary,
+    };
+    use nalgebra::DMatrix;
+
+    // A counter-based generator: the output `i` of the stream `s` is a hash
+    // of `(s, i)`.
+    struct Counter {
+        stream: u64,
+        counter: u64,
+    }
+
+    impl RngCore for Counter {
+        fn next_u32(&mut self) -> u32 {
+            (self.next_u64() >> 32) as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.counter += 1;
+            let mut z = self.stream.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ self.counter;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                let bytes = self.next_u64().to_le_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    fn counter(stream: u64) -> Counter {
+        Counter { stream, counter: 0 }
+    }
+
+    #[test]
+    fn test_streams() {
+        let seeded = SeededStreams::new(5);
+        assert_eq!(seeded.stream(3).gen::<u64>(), seeded.stream(3).gen::<u64>());
+        assert_ne!(seeded.stream(3).gen::<u64>(), seeded.stream(4).gen::<u64>());
+
+        // Consecutive master seeds do not share streams.
+        assert_ne!(
+            seeded.stream(3).gen::<u64>(),
+            SeededStreams::new(6).stream(2).gen::<u64>()
+        );
+        assert_ne!(
+            EntropyStreams.stream(0).gen::<u64>(),
+            EntropyStreams.stream(0).gen::<u64>()
+        );
+
+        let mut rng = counter.stream(2);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn test_sample_design() {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let streams = SeededStreams::new(9);
+        let draws = |design: &SampleDesign| -> Vec<Vec<f64>> {
+            (0..40)
+                .map(|sample| design.normals(sample, &mut streams.stream(sample as u64)))
+                .collect()
+        };
+
+        // Independent draws are those of the streams.
+        let independent = draws(&SampleDesign::new(Sampling::Independent, 40, 3, &streams));
+        let z: f64 = streams.stream(7).sample(StandardNormal);
+        assert_eq!(independent[7][..1], [z]);
+
+        // Each stratum of the first dimension holds its share of the
+        // samples.
+        let design = SampleDesign::new(Sampling::Stratified, 40, 3, &streams);
+        for (sample, normals) in draws(&design).iter().enumerate() {
+            let stratum = design.stratum(sample);
+            let u = normal.cdf(normals[0]);
+            assert!(u * 10.0 >= stratum as f64 && u * 10.0 <= (stratum + 1) as f64);
+        }
+        assert_eq!(design.strata(), 10);
+        assert_eq!((0..40).filter(|s| design.stratum(*s) == 3).count(), 4);
+
+        // Each stratum of every dimension holds exactly one sample.
+        let latin = draws(&SampleDesign::new(
+            Sampling::LatinHypercube,
+            40,
+            3,
+            &streams,
+        ));
+        for d in 0..3 {
+            for stratum in 0..40_u32 {
+                let bounds = f64::from(stratum) / 40.0..f64::from(stratum + 1) / 40.0;
+                let count = latin
+                    .iter()
+                    .filter(|normals| bounds.contains(&normal.cdf(normals[d])))
+                    .count();
+                assert_eq!(count, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reproducible_generators() {
+        // Every generator gives the same paths from the same streams,
+        // serially or in parallel.
+        let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+        let simulate = |parallel| {
+            gbm.simulate_with_streams(
+                SimulationScheme::EulerMaruyama,
+                1.0,
+                0.0,
+                1.0,
+                10,
+                20,
+                parallel,
+                &counter,
+            )
+            .paths
+        };
+        assert_eq!(simulate(true), simulate(false));
+
+        let jumps = JumpDiffusion::new(
+            OrnsteinUhlenbeck::new(0.0, 0.1, 1.0),
+            2.0,
+            JumpSize::Merton {
+                mean: 0.0,
+                std_dev: 0.1,
+            },
+            JumpEffect::Additive,
+        );
+        let simulate = |parallel| {
+            jumps
+                .simulate_with_streams(
+                    SimulationScheme::Exact,
+                    0.0,
+                    0.0,
+                    1.0,
+                    10,
+                    20,
+                    parallel,
+                    &counter,
+                )
+                .paths
+        };
+        assert_eq!(simulate(true), simulate(false));
+
+        let heston = Heston::new(0.0, 2.0, 0.04, 0.3, -0.7);
+        let simulate = |parallel| {
+            heston
+                .quadratic_exponential_with_streams(
+                    100.0, 0.04, 0.0, 1.0, 10, 20, parallel, &counter,
+                )
+                .spot
+                .paths
+        };
+        assert_eq!(simulate(true), simulate(false));
+
+        let sabr = Sabr::new(0.5, 0.4, -0.3, ZeroBoundary::Absorbing);
+        let simulate = |parallel| {
+            sabr.euler_maruyama_with_streams(0.03, 0.05, 0.0, 1.0, 10, 20, parallel, &counter)
+                .forward
+                .paths
+        };
+        assert_eq!(simulate(true), simulate(false));
+
+        let bergomi = RoughBergomi::new(0.1, 1.9, -0.9, 0.04);
+        let simulate = |parallel| {
+            bergomi
+                .hybrid_scheme_with_streams(100.0, 1.0, 10, 20, parallel, &counter)
+                .spot
+                .paths
+        };
+        assert_eq!(simulate(true), simulate(false));
+
+        let correlated = CorrelatedProcesses::new(
+            vec![
+                Box::new(GeometricBrownianMotion::new(0.05, 0.2)),
+                Box::new(OrnsteinUhlenbeck::new(0.02, 0.01, 0.5)),
+            ],
+            DMatrix::from_row_slice(2, 2, &[1.0, 0.5, 0.5, 1.0]),
+        )
+        .unwrap();
+        let simulate = |parallel| {
+            correlated.euler_maruyama_with_streams(
+                &[1.0, 0.0],
+                0.0,
+                1.0,
+                10,
+                20,
+                parallel,
+                &counter,
+            )[1]
+            .paths
+            .clone()
+        };
+        assert_eq!(simulate(true), simulate(false));
+    }
+}
@@ -0,0 +1,458 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Regime-switching diffusions.
+//!
+//! The process follows the dynamics of one of several regimes, e.g. calm and
+//! stressed markets, and the regime $Z(t)$ switches according to a
+//! continuous-time Markov chain with generator $Q$:
+//!
+//! $$
+//! \begin{aligned}
+//! dX(t) &= a_{Z(t)}(X(t), t) dt + b_{Z(t)}(X(t), t) dW(t) \\\\
+//! P\left( Z(t + h) = j \mid Z(t) = i \right) &= q_{ij} h + o(h), \quad i \neq j
+//! \end{aligned}
+//! $$
+//!
+//! The chain is simulated exactly, from exponential holding times, and the
+//! diffusion step of each time step is split at the switching times, so
+//! every piece is simulated with the dynamics of the regime in force.
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{
+    EntropyStreams, RandomStream, SimulationScheme, StochasticProcess, Trajectories,
+};
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::{Distribution, Exp1};
+use rayon::prelude::*;
+
+/// Errors of a [`RegimeSwitching`] process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RegimeError {
+    /// The generator is not square with one row per regime.
+    #[error("Generator must have one row and column per regime")]
+    DimensionMismatch,
+
+    /// The generator has negative off-diagonal entries, or rows not summing
+    /// to zero.
+    #[error("Generator must have non-negative off-diagonal entries and rows summing to zero")]
+    InvalidGenerator,
+}
+
+/// Process whose dynamics switch between regimes following a continuous-time
+/// Markov chain.
+///
+/// ```
+/// use RustQuant::stochastics::*;
+/// use nalgebra::DMatrix;
+///
+/// // Calm markets turn stressed once every four years on average, and
+/// // recover after six months.
+/// let generator = DMatrix::from_row_slice(2, 2, &[-0.25, 0.25, 2.0, -2.0]);
+/// let process = RegimeSwitching::new(
+///     vec![
+///         Box::new(GeometricBrownianMotion::new(0.08, 0.15)),
+///         Box::new(GeometricBrownianMotion::new(-0.2, 0.45)),
+///     ],
+///     generator,
+/// )
+/// .unwrap();
+///
+/// // Stress scenarios: start in the stressed regime.
+/// let output = process.simulate(SimulationScheme::Exact, 100.0, 1, 0.0, 1.0, 250, 10, false);
+///
+/// assert_eq!(output.regimes[0][0], 1);
+/// assert_eq!(output.process.paths[0].len(), 251);
+/// ```
+pub struct RegimeSwitching {
+    /// The dynamics of each regime.
+    pub regimes: Vec<Box<dyn StochasticProcess>>,
+
+    /// Generator of the regime chain ($Q$): switching rates off the
+    /// diagonal, and rows summing to zero.
+    pub generator: DMatrix<f64>,
+}
+
+/// Jointly simulated trajectories of a [`RegimeSwitching`] process and of
+/// its regime.
+pub struct RegimeTrajectories {
+    /// Trajectories of the process.
+    pub process: Trajectories,
+
+    /// Regime in force at each time point of each trajectory.
+    pub regimes: Vec<Vec<usize>>,
+}
+
+impl RegimeSwitching {
+    /// Create a new regime-switching process.
+    ///
+    /// # Errors
+    ///
+    /// - `RegimeError::DimensionMismatch` if the generator does not have one
+    ///   row and column per regime.
+    /// - `RegimeError::InvalidGenerator` if it has negative off-diagonal
+    ///   entries or rows not summing to zero.
+    pub fn new(
+        regimes: Vec<Box<dyn StochasticProcess>>,
+        generator: DMatrix<f64>,
+    ) -> Result<Self, RegimeError> {
+        const TOLERANCE: f64 = 1e-12;
+
+        let n = regimes.len();
+        if n == 0 || generator.shape() != (n, n) {
+            return Err(RegimeError::DimensionMismatch);
+        }
+
+        let valid = generator.row_iter().enumerate().all(|(row, rates)| {
+            rates
+                .iter()
+                .enumerate()
+                .all(|(column, rate)| column == row || *rate >= 0.0)
+                && rates.sum().abs() < TOLERANCE * (1.0 + generator[(row, row)].abs())
+        });
+        if !valid {
+            return Err(RegimeError::InvalidGenerator);
+        }
+
+        Ok(Self { regimes, generator })
+    }
+
+    /// Probabilities of the regimes after a time `t`: the row `i` of
+    /// $e^{Q t}$ holds the probabilities from the regime `i`.
+    #[must_use]
+    pub fn regime_probabilities(&self, t: f64) -> DMatrix<f64> {
+        (&self.generator * t).exp()
+    }
+
+    /// Stationary distribution of the regimes ($\pi$, with $\pi Q = 0$):
+    /// the long-run fractions of time spent in each regime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stationary distribution is not unique, i.e. the chain
+    /// is not irreducible.
+    #[must_use]
+    pub fn stationary_distribution(&self) -> DVector<f64> {
+        let n = self.regimes.len();
+
+        // Transposed balance equations, with the last one replaced by the
+        // normalisation.
+        let mut system = self.generator.transpose();
+        system.row_mut(n - 1).fill(1.0);
+        let mut normalisation = DVector::zeros(n);
+        normalisation[n - 1] = 1.0;
+
+        system
+            .lu()
+            .solve(&normalisation)
+            .expect("The regime chain must be irreducible.")
+    }
+
+    /// Simulation of the regime chain and of the process, with a choice of
+    /// discretisation scheme.
+    ///
+    /// # Arguments:
+    /// * `scheme` - The discretisation scheme.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `regime_0` - The initial regime at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn simulate(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        regime_0: usize,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> RegimeTrajectories {
+        self.simulate_with_streams(
+            scheme,
+            x_0,
+            regime_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    /// Simulation of the regime chain and of the process, with a choice of
+    /// discretisation scheme and random seed.
+    ///
+    /// # Arguments:
+    /// * `scheme` - The discretisation scheme.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `regime_0` - The initial regime at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    #[cfg(feature = "seedable")]
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn seedable_simulate(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        regime_0: usize,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> RegimeTrajectories {
+        self.simulate_with_streams(
+            scheme,
+            x_0,
+            regime_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    /// Simulation of the regime chain and of the process, with a choice of
+    /// discretisation scheme, where the path `i` draws its random numbers
+    /// from the stream `i` of `streams`.
+    ///
+    /// # Arguments:
+    /// * `scheme` - The discretisation scheme.
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `regime_0` - The initial regime at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n`, if `regime_0` is not a regime, or with
+    /// [`SimulationScheme::Exact`] if a regime has no exact transition.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn simulate_with_streams<S: RandomStream>(
+        &self,
+        scheme: SimulationScheme,
+        x_0: f64,
+        regime_0: usize,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> RegimeTrajectories {
+        assert!(t_0 < t_n);
+        assert!(regime_0 < self.regimes.len(), "Unknown initial regime.");
+
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+        let path_generator = |path: usize| {
+            let mut rng = streams.stream(path as u64);
+
+            let mut values = vec![x_0; n_steps + 1];
+            let mut regimes = vec![regime_0; n_steps + 1];
+
+            let mut regime = regime_0;
+            let mut next_switch = t_0 + self.holding_time(regime, &mut rng);
+
+            for step in 0..n_steps {
+                let (mut x, mut t, end) = (values[step], times[step], times[step + 1]);
+
+                // Pieces of the time step between switches.
+                while next_switch < end {
+                    if next_switch > t {
+                        x = scheme.step(&*self.regimes[regime], x, t, next_switch - t, &mut rng);
+                        t = next_switch;
+                    }
+                    regime = self.next_regime(regime, &mut rng);
+                    next_switch += self.holding_time(regime, &mut rng);
+                }
+                if end > t {
+                    x = scheme.step(&*self.regimes[regime], x, t, end - t, &mut rng);
+                }
+
+                values[step + 1] = x;
+                regimes[step + 1] = regime;
+            }
+
+            (values, regimes)
+        };
+
+        let (paths, regimes): (Vec<_>, Vec<_>) = if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).unzip()
+        } else {
+            (0..m_paths).map(path_generator).unzip()
+        };
+
+        RegimeTrajectories {
+            process: Trajectories { times, paths },
+            regimes,
+        }
+    }
+
+    // Time spent in `regime` before switching (infinite if absorbing).
+    fn holding_time<R: Rng>(&self, regime: usize, rng: &mut R) -> f64 {
+        let rate = -self.generator[(regime, regime)];
+        let draw: f64 = Exp1.sample(rng);
+
+        if rate > 0.0 {
+            draw / rate
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    // Regime switched to from `regime`, with probabilities proportional to
+    // the switching rates.
+    fn next_regime<R: Rng>(&self, regime: usize, rng: &mut R) -> usize {
+        let rate = -self.generator[(regime, regime)];
+        let mut u = rng.gen::<f64>() * rate;
+
+        let mut last = regime;
+        for (target, switching) in self.generator.row(regime).iter().enumerate() {
+            if target == regime || *switching <= 0.0 {
+                continue;
+            }
+            if u < *switching {
+                return target;
+            }
+            u -= switching;
+            last = target;
+        }
+
+        // Rounding: the last regime with a positive rate.
+        last
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_regime_switching {
+    use super::*;
+    use crate::stochastics::{ArithmeticBrownianMotion, SeededStreams};
+    use crate::{assert_approx_equal, statistics::*};
+
+    // Switching rates out of the regimes 0 and 1.
+    const UP: f64 = 1.0;
+    const DOWN: f64 = 2.0;
+
+    fn two_regimes() -> RegimeSwitching {
+        RegimeSwitching::new(
+            vec![
+                Box::new(ArithmeticBrownianMotion::new(0.1, 0.2)),
+                Box::new(ArithmeticBrownianMotion::new(-0.3, 0.4)),
+            ],
+            DMatrix::from_row_slice(2, 2, &[-UP, UP, DOWN, -DOWN]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_regime_switching() {
+        let process = two_regimes();
+        let output = process.simulate_with_streams(
+            SimulationScheme::EulerMaruyama,
+            1.0,
+            0,
+            0.0,
+            2.0,
+            40,
+            50_000,
+            true,
+            &SeededStreams::new(5),
+        );
+
+        // Fraction of the paths in the regime 1 at the end.
+        let probabilities = process.regime_probabilities(2.0);
+        let total = UP + DOWN;
+        assert_approx_equal!(
+            probabilities[(0, 1)],
+            UP / total * (1.0 - (-total * 2.0).exp()),
+            1e-10
+        );
+
+        let stressed: Vec<f64> = output
+            .regimes
+            .iter()
+            .map(|regimes| f64::from(u8::from(regimes[40] == 1)))
+            .collect();
+        assert_approx_equal!(stressed.mean(), probabilities[(0, 1)], 0.01);
+
+        // The drift integrates the probability of the regime 1 over time.
+        let occupation = UP / total * (2.0 - (1.0 - (-total * 2.0).exp()) / total);
+        let terminal: Vec<f64> = output.process.paths.iter().map(|x| x[40]).collect();
+        assert_approx_equal!(terminal.mean(), 1.0 + 0.1 * 2.0 - 0.4 * occupation, 0.01);
+
+        let stationary = process.stationary_distribution();
+        assert_approx_equal!(stationary[0], DOWN / total, 1e-12);
+        assert_approx_equal!(stationary[1], UP / total, 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_generator() {
+        let regimes = || -> Vec<Box<dyn StochasticProcess>> {
+            vec![
+                Box::new(ArithmeticBrownianMotion::new(0.0, 0.1)),
+                Box::new(ArithmeticBrownianMotion::new(0.0, 0.2)),
+            ]
+        };
+
+        let mismatch = RegimeSwitching::new(regimes(), DMatrix::zeros(3, 3));
+        assert!(matches!(mismatch, Err(RegimeError::DimensionMismatch)));
+
+        let unbalanced = DMatrix::from_row_slice(2, 2, &[-1.0, 0.5, 1.0, -1.0]);
+        let negative = DMatrix::from_row_slice(2, 2, &[1.0, -1.0, 1.0, -1.0]);
+        for generator in [unbalanced, negative] {
+            let result = RegimeSwitching::new(regimes(), generator);
+            assert!(matches!(result, Err(RegimeError::InvalidGenerator)));
+        }
+
+        // An absorbing regime never switches.
+        let absorbing = DMatrix::from_row_slice(2, 2, &[-1.0, 1.0, 0.0, 0.0]);
+        let output = RegimeSwitching::new(regimes(), absorbing)
+            .unwrap()
+            .simulate_with_streams(
+                SimulationScheme::EulerMaruyama,
+                0.0,
+                1,
+                0.0,
+                1.0,
+                10,
+                100,
+                false,
+                &SeededStreams::new(1),
+            );
+        assert!(output.regimes.iter().flatten().all(|regime| *regime == 1));
+    }
+}
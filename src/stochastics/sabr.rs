@@ -0,0 +1,371 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! SABR (Hagan et al., 2002) stochastic volatility process:
+//!
+//! $$
+//! dF_t = \alpha_t F_t^\beta dW_t, \qquad
+//! d\alpha_t = \nu \alpha_t dZ_t, \qquad
+//! d\langle W, Z \rangle_t = \rho dt
+//! $$
+//!
+//! The volatility is a geometric Brownian motion, sampled exactly. The
+//! forward follows an Euler step (exact lognormal for $\beta = 1$). For
+//! $0 < \beta < 1$ the forward can reach zero, where the process must be
+//! either absorbed (the martingale choice, matching Hagan's density
+//! asymptotically) or reflected. The Euler step overshoots zero, and the
+//! absorbed forward is biased slightly upwards, less so with finer steps.
+//! With $\beta = 0$ (normal SABR) the forward is unbounded below.
+//!
+//! The implied volatilities of the model are approximated by Hagan's
+//! expansion, see [`crate::models::Sabr`].
+
+#[cfg(feature = "seedable")]
+use crate::stochastics::SeededStreams;
+use crate::stochastics::{EntropyStreams, RandomStream, Trajectories};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+/// Behaviour of the forward at zero, for $0 < \beta < 1$.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZeroBoundary {
+    /// The forward stays at zero once it reaches it.
+    Absorbing,
+    /// The forward is reflected back above zero.
+    Reflecting,
+}
+
+/// Struct containing the SABR process parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct Sabr {
+    /// CEV exponent of the forward ($\beta$), in `[0, 1]`.
+    pub beta: f64,
+
+    /// Volatility of the volatility ($\nu$).
+    pub nu: f64,
+
+    /// Correlation between the forward and its volatility ($\rho$).
+    pub rho: f64,
+
+    /// Behaviour of the forward at zero.
+    pub boundary: ZeroBoundary,
+}
+
+/// Jointly simulated forward and volatility trajectories of a [`Sabr`]
+/// process.
+pub struct SabrTrajectories {
+    /// Forward trajectories.
+    pub forward: Trajectories,
+
+    /// Volatility ($\alpha$) trajectories.
+    pub volatility: Trajectories,
+}
+
+impl Sabr {
+    /// Create a new SABR process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beta` is not in `[0, 1]` or the correlation not in
+    /// `[-1, 1]`.
+    #[must_use]
+    pub fn new(beta: f64, nu: f64, rho: f64, boundary: ZeroBoundary) -> Self {
+        assert!((0.0..=1.0).contains(&beta), "Beta must be in [0, 1].");
+        assert!(
+            (-1.0..=1.0).contains(&rho),
+            "The correlation must be in [-1, 1]."
+        );
+
+        Self {
+            beta,
+            nu,
+            rho,
+            boundary,
+        }
+    }
+
+    /// Euler discretisation scheme of the forward, with the volatility
+    /// sampled exactly.
+    ///
+    /// # Arguments:
+    /// * `f_0` - The initial forward at `t_0`.
+    /// * `alpha_0` - The initial volatility at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    ///
+    /// ```
+    /// use RustQuant::stochastics::*;
+    ///
+    /// let sabr = Sabr::new(0.5, 0.4, -0.3, ZeroBoundary::Absorbing);
+    ///
+    /// let output = sabr.euler_maruyama(0.03, 0.05, 0.0, 5.0, 100, 100, false);
+    ///
+    /// // The forward is absorbed at zero.
+    /// assert!(output.forward.paths.iter().flatten().all(|f| *f >= 0.0));
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn euler_maruyama(
+        &self,
+        f_0: f64,
+        alpha_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+    ) -> SabrTrajectories {
+        self.euler_maruyama_with_streams(
+            f_0,
+            alpha_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &EntropyStreams,
+        )
+    }
+
+    /// Euler discretisation scheme of the forward, with the volatility
+    /// sampled exactly, and a choice of random seed.
+    ///
+    /// # Arguments:
+    /// * `f_0` - The initial forward at `t_0`.
+    /// * `alpha_0` - The initial volatility at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `seed` - The seed for the random number generator.
+    #[cfg(feature = "seedable")]
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn seedable_euler_maruyama(
+        &self,
+        f_0: f64,
+        alpha_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        seed: u64,
+    ) -> SabrTrajectories {
+        self.euler_maruyama_with_streams(
+            f_0,
+            alpha_0,
+            t_0,
+            t_n,
+            n_steps,
+            m_paths,
+            parallel,
+            &SeededStreams::new(seed),
+        )
+    }
+
+    /// Euler discretisation scheme of the forward, with the volatility
+    /// sampled exactly, where the path `i` draws its random numbers from the
+    /// stream `i` of `streams`.
+    ///
+    /// # Arguments:
+    /// * `f_0` - The initial forward at `t_0`.
+    /// * `alpha_0` - The initial volatility at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `n_steps` - The number of time steps between `t_0` and `t_n`.
+    /// * `m_paths` - How many process trajectories to simulate.
+    /// * `parallel` - Run in parallel or not (recommended for > 1000 paths).
+    /// * `streams` - The random number streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `t_0 >= t_n` or `alpha_0 < 0`.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn euler_maruyama_with_streams<S: RandomStream>(
+        &self,
+        f_0: f64,
+        alpha_0: f64,
+        t_0: f64,
+        t_n: f64,
+        n_steps: usize,
+        m_paths: usize,
+        parallel: bool,
+        streams: &S,
+    ) -> SabrTrajectories {
+        assert!(t_0 < t_n);
+        assert!(alpha_0 >= 0.0);
+
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+        let path_generator = |path: usize| {
+            let mut rng = streams.stream(path as u64);
+
+            let mut forward = vec![f_0; n_steps + 1];
+            let mut volatility = vec![alpha_0; n_steps + 1];
+
+            for t in 0..n_steps {
+                let z_1: f64 = rng.sample(StandardNormal);
+                let z_2: f64 = rng.sample(StandardNormal);
+                let z_2 = self.rho * z_1 + (1.0 - self.rho * self.rho).sqrt() * z_2;
+
+                forward[t + 1] = self.forward_step(forward[t], volatility[t], dt, z_1);
+                volatility[t + 1] = volatility[t]
+                    * (self.nu * dt.sqrt() * z_2 - 0.5 * self.nu * self.nu * dt).exp();
+            }
+
+            (forward, volatility)
+        };
+
+        let (forward, volatility): (Vec<Vec<f64>>, Vec<Vec<f64>>) = if parallel {
+            (0..m_paths).into_par_iter().map(path_generator).unzip()
+        } else {
+            (0..m_paths).map(path_generator).unzip()
+        };
+
+        SabrTrajectories {
+            forward: Trajectories {
+                times: times.clone(),
+                paths: forward,
+            },
+            volatility: Trajectories {
+                times,
+                paths: volatility,
+            },
+        }
+    }
+
+    // Advances the forward `f` over `dt` with the volatility `alpha` and the
+    // standard Gaussian draw `z`.
+    fn forward_step(&self, f: f64, alpha: f64, dt: f64, z: f64) -> f64 {
+        if self.beta >= 1.0 {
+            return f * (alpha * dt.sqrt() * z - 0.5 * alpha * alpha * dt).exp();
+        }
+        if self.beta <= 0.0 {
+            return f + alpha * dt.sqrt() * z;
+        }
+        if f <= 0.0 && self.boundary == ZeroBoundary::Absorbing {
+            return 0.0;
+        }
+
+        let next = f + alpha * f.max(0.0).powf(self.beta) * dt.sqrt() * z;
+
+        match self.boundary {
+            ZeroBoundary::Absorbing => next.max(0.0),
+            ZeroBoundary::Reflecting => next.abs(),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_sabr_process {
+    use super::*;
+    use crate::instruments::{black76_greeks, TypeFlag};
+    use crate::models::Sabr as SabrModel;
+    use crate::stochastics::SeededStreams;
+    use crate::{assert_approx_equal, statistics::*};
+
+    fn terminal(trajectories: &Trajectories) -> Vec<f64> {
+        trajectories
+            .paths
+            .iter()
+            .filter_map(|path| path.last().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_hagan_approximation() {
+        // Short expiry and moderate parameters, where Hagan's expansion is
+        // accurate: Monte Carlo calls against Black with the SABR
+        // volatility.
+        let (f_0, alpha, beta, rho, nu, expiry) = (0.03, 0.05, 0.5, -0.3, 0.4, 1.0);
+        let sabr = Sabr::new(beta, nu, rho, ZeroBoundary::Absorbing);
+        let output = sabr.euler_maruyama_with_streams(
+            f_0,
+            alpha,
+            0.0,
+            expiry,
+            100,
+            40_000,
+            true,
+            &SeededStreams::new(11),
+        );
+        let forwards = terminal(&output.forward);
+
+        let model = SabrModel::new(alpha, beta, rho, nu);
+        for strike in [0.025, 0.03, 0.035] {
+            let payoffs: Vec<f64> = forwards.iter().map(|f| (f - strike).max(0.0)).collect();
+            let vol = model.lognormal_volatility(f_0, strike, expiry);
+            let black = black76_greeks(f_0, strike, expiry, 0.0, vol, TypeFlag::Call).price;
+
+            assert_approx_equal!(payoffs.mean(), black, 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_boundaries() {
+        // A low forward with high volatility reaches zero often.
+        let absorbing = Sabr::new(0.5, 0.3, 0.0, ZeroBoundary::Absorbing);
+        let reflecting = Sabr {
+            boundary: ZeroBoundary::Reflecting,
+            ..absorbing
+        };
+        let absorbed = absorbing.euler_maruyama_with_streams(
+            0.01,
+            0.1,
+            0.0,
+            5.0,
+            1000,
+            10_000,
+            true,
+            &SeededStreams::new(3),
+        );
+        let reflected = reflecting.euler_maruyama_with_streams(
+            0.01,
+            0.1,
+            0.0,
+            5.0,
+            1000,
+            10_000,
+            true,
+            &SeededStreams::new(3),
+        );
+
+        // Absorbed paths stay at zero, and the forward remains a martingale
+        // (up to the discretisation of the boundary).
+        let at_zero = absorbed
+            .forward
+            .paths
+            .iter()
+            .filter(|path| path.contains(&0.0))
+            .count();
+        assert!(at_zero > 1000);
+        for path in &absorbed.forward.paths {
+            if let Some(hit) = path.iter().position(|f| *f == 0.0) {
+                assert!(path[hit..].iter().all(|f| *f == 0.0));
+            }
+        }
+        assert_approx_equal!(terminal(&absorbed.forward).mean(), 0.01, 0.0005);
+
+        // Reflection adds value at the boundary.
+        assert!(reflected.forward.paths.iter().flatten().all(|f| *f >= 0.0));
+        assert!(terminal(&reflected.forward).mean() > 0.0105);
+    }
+}
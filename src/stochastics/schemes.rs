@@ -0,0 +1,343 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Discretisation schemes of a process $dX_t = a(X_t, t) dt + b(X_t, t) dW_t$
+//! over a step $\Delta t$ with Brownian increment $\Delta W$ (Kloeden and
+//! Platen, 1992).
+//!
+//! | Scheme              | Strong order | Weak order |
+//! |---------------------|--------------|------------|
+//! | Euler-Maruyama      | 1/2          | 1          |
+//! | Milstein            | 1            | 1          |
+//! | Runge-Kutta         | 1            | 1          |
+//! | Predictor-corrector | 1            | 1          |
+//! | Exact               | -            | -          |
+//!
+//! The orders hold for smooth coefficients (and, for the predictor-corrector
+//! scheme, a scalar Brownian motion, as here). The predictor-corrector
+//! scheme is also more stable, and usually has a smaller weak error, than
+//! the Euler-Maruyama scheme.
+//!
+//! The exact scheme samples the transition density of the process, without
+//! discretisation bias, where it is known: geometric Brownian motion
+//! (lognormal), Ornstein-Uhlenbeck (Gaussian) and Cox-Ingersoll-Ross
+//! (scaled noncentral chi-squared). Time-dependent parameters are taken
+//! constant over each step.
+
+use crate::stochastics::{RandomStream, StochasticProcess, Trajectories};
+use rand::{Rng, RngCore};
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+/// Discretisation scheme of a [`StochasticProcess`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimulationScheme {
+    /// Euler-Maruyama:
+    /// $X_{n+1} = X_n + a \Delta t + b \Delta W$.
+    #[default]
+    EulerMaruyama,
+
+    /// Milstein, with the Itô correction
+    /// $\frac{1}{2} b b' (\Delta W^2 - \Delta t)$.
+    Milstein,
+
+    /// Derivative-free stochastic Runge-Kutta scheme of Platen: the
+    /// Milstein scheme, with $b b'$ replaced by a finite difference of $b$
+    /// at the supporting value $X_n + a \Delta t + b \sqrt{\Delta t}$.
+    RungeKutta,
+
+    /// Predictor-corrector: an Euler-Maruyama predictor $\bar{X}$, then the
+    /// average of the coefficients at $X_n$ and $\bar{X}$, with the drift
+    /// corrected to $a - \frac{1}{2} b b'$.
+    PredictorCorrector,
+
+    /// Sampling of the transition density, see
+    /// [`StochasticProcess::exact_step`].
+    Exact,
+}
+
+impl SimulationScheme {
+    /// One step of the scheme from `x` at `t` over `dt`, drawing from `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the exact scheme if the process has no exact transition.
+    pub fn step<P, R>(self, process: &P, x: f64, t: f64, dt: f64, rng: &mut R) -> f64
+    where
+        P: StochasticProcess + ?Sized,
+        R: RngCore,
+    {
+        match self {
+            Self::Exact => process
+                .exact_step(x, t, dt, rng)
+                .expect("The process has no exact transition."),
+            _ => self.discretise(
+                process,
+                x,
+                t,
+                dt,
+                dt.sqrt() * rng.sample::<f64, _>(StandardNormal),
+            ),
+        }
+    }
+
+    // One step of a discretisation scheme from `x` at `t`, over `dt` with
+    // the Brownian increment `dW`.
+    fn discretise<P>(self, process: &P, x: f64, t: f64, dt: f64, dW: f64) -> f64
+    where
+        P: StochasticProcess + ?Sized,
+    {
+        let a = process.drift(x, t);
+        let b = process.diffusion(x, t);
+
+        match self {
+            Self::EulerMaruyama => x + a * dt + b * dW,
+            Self::Milstein => {
+                x + a * dt + b * dW + 0.5 * b * process.diffusion_derivative(x, t) * (dW * dW - dt)
+            }
+            Self::RungeKutta => {
+                let support = x + a * dt + b * dt.sqrt();
+                let b_support = process.diffusion(support, t);
+
+                x + a * dt + b * dW + (b_support - b) * (dW * dW - dt) / (2.0 * dt.sqrt())
+            }
+            Self::PredictorCorrector => {
+                let predictor = x + a * dt + b * dW;
+                let corrected_drift = |y: f64, s: f64| {
+                    process.drift(y, s)
+                        - 0.5 * process.diffusion(y, s) * process.diffusion_derivative(y, s)
+                };
+
+                x + 0.5 * (corrected_drift(predictor, t + dt) + corrected_drift(x, t)) * dt
+                    + 0.5 * (process.diffusion(predictor, t + dt) + b) * dW
+            }
+            Self::Exact => unreachable!("The exact scheme does not use the increment."),
+        }
+    }
+}
+
+// Simulates the paths of `process` with `scheme`, each with the random
+// number stream of its index.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn simulate_paths<P, S>(
+    process: &P,
+    scheme: SimulationScheme,
+    x_0: f64,
+    t_0: f64,
+    t_n: f64,
+    n_steps: usize,
+    m_paths: usize,
+    parallel: bool,
+    streams: &S,
+) -> Trajectories
+where
+    P: StochasticProcess + ?Sized,
+    S: RandomStream + ?Sized,
+{
+    assert!(t_0 < t_n);
+
+    let dt: f64 = (t_n - t_0) / (n_steps as f64);
+    let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+    let path_generator = |path: usize| {
+        let mut rng = streams.stream(path as u64);
+
+        let mut x = vec![x_0; n_steps + 1];
+        for t in 0..n_steps {
+            x[t + 1] = scheme.step(process, x[t], times[t], dt, &mut rng);
+        }
+
+        x
+    };
+
+    let paths = if parallel {
+        (0..m_paths).into_par_iter().map(path_generator).collect()
+    } else {
+        (0..m_paths).map(path_generator).collect()
+    };
+
+    Trajectories { times, paths }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_schemes {
+    use super::*;
+    use crate::stochastics::{
+        CoxIngersollRoss, GeometricBrownianMotion, HoLee, OrnsteinUhlenbeck, SeededStreams,
+    };
+    use crate::{assert_approx_equal, statistics::*};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const MU: f64 = 0.5;
+    const STEPS: [usize; 5] = [8, 16, 32, 64, 128];
+
+    // Strong and weak errors at `t = 1` of the scheme against the exact
+    // geometric Brownian motion driven by the same Brownian path.
+    fn errors(scheme: SimulationScheme, sigma: f64, n_steps: usize) -> (f64, f64) {
+        const PATHS: usize = 20_000;
+
+        let gbm = GeometricBrownianMotion::new(MU, sigma);
+        let dt = 1.0 / n_steps as f64;
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let (mut strong, mut weak) = (0.0, 0.0);
+        for _ in 0..PATHS {
+            let (mut x, mut w) = (1.0, 0.0);
+            for t in 0..n_steps {
+                let dW = dt.sqrt() * rng.sample::<f64, _>(StandardNormal);
+                x = scheme.discretise(&gbm, x, t as f64 * dt, dt, dW);
+                w += dW;
+            }
+            let exact = (MU - 0.5 * sigma * sigma + sigma * w).exp();
+
+            strong += (x - exact).abs();
+            weak += x - exact;
+        }
+
+        (strong / PATHS as f64, (weak / PATHS as f64).abs())
+    }
+
+    // Least squares slope of the log errors against the log time steps.
+    fn order(errors: &[f64]) -> f64 {
+        let points: Vec<(f64, f64)> = STEPS
+            .iter()
+            .zip(errors)
+            .map(|(n, e)| ((1.0 / *n as f64).ln(), e.ln()))
+            .collect();
+        let m = points.len() as f64;
+        let (mx, my) = points
+            .iter()
+            .fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x / m, sy + y / m));
+
+        let covariance: f64 = points.iter().map(|(x, y)| (x - mx) * (y - my)).sum();
+        let variance: f64 = points.iter().map(|(x, _)| (x - mx).powi(2)).sum();
+
+        covariance / variance
+    }
+
+    #[test]
+    fn test_strong_order() {
+        let strong =
+            |scheme| -> Vec<f64> { STEPS.iter().map(|n| errors(scheme, 0.8, *n).0).collect() };
+
+        assert_approx_equal!(order(&strong(SimulationScheme::EulerMaruyama)), 0.5, 0.15);
+        assert_approx_equal!(order(&strong(SimulationScheme::Milstein)), 1.0, 0.15);
+        assert_approx_equal!(order(&strong(SimulationScheme::RungeKutta)), 1.0, 0.15);
+        assert_approx_equal!(
+            order(&strong(SimulationScheme::PredictorCorrector)),
+            1.0,
+            0.15
+        );
+    }
+
+    #[test]
+    fn test_weak_order() {
+        // A low volatility, for the Monte Carlo error to be small against
+        // the weak error.
+        let weak =
+            |scheme| -> Vec<f64> { STEPS.iter().map(|n| errors(scheme, 0.2, *n).1).collect() };
+
+        assert_approx_equal!(order(&weak(SimulationScheme::EulerMaruyama)), 1.0, 0.15);
+        assert_approx_equal!(order(&weak(SimulationScheme::Milstein)), 1.0, 0.15);
+
+        // Smaller weak errors with the predictor-corrector.
+        let euler = weak(SimulationScheme::EulerMaruyama);
+        let corrected = weak(SimulationScheme::PredictorCorrector);
+        assert!(euler.iter().zip(&corrected).all(|(e, c)| c < e));
+    }
+
+    #[test]
+    fn test_process_simulation() {
+        // Mean of a Cox-Ingersoll-Ross process, far from zero.
+        let cir = CoxIngersollRoss::new(0.05, 0.05, 2.0);
+
+        for scheme in [
+            SimulationScheme::EulerMaruyama,
+            SimulationScheme::Milstein,
+            SimulationScheme::RungeKutta,
+            SimulationScheme::PredictorCorrector,
+        ] {
+            let output = simulate_paths(
+                &cir,
+                scheme,
+                0.03,
+                0.0,
+                1.0,
+                100,
+                10_000,
+                true,
+                &SeededStreams::new(1),
+            );
+            let mean = output.paths.iter().map(|path| path[100]).sum::<f64>() / 10_000.0;
+
+            assert_approx_equal!(mean, 0.05 - 0.02 * (-2.0_f64).exp(), 0.001);
+        }
+    }
+
+    // Terminal values of a single exact step over `[0, t]`.
+    fn exact_terminal<P: StochasticProcess>(process: &P, x_0: f64, t: f64) -> Vec<f64> {
+        simulate_paths(
+            process,
+            SimulationScheme::Exact,
+            x_0,
+            0.0,
+            t,
+            1,
+            100_000,
+            true,
+            &SeededStreams::new(2),
+        )
+        .paths
+        .iter()
+        .map(|path| path[1])
+        .collect()
+    }
+
+    #[test]
+    fn test_exact_schemes() {
+        // Moments of the transition densities over a long step.
+        let gbm = exact_terminal(&GeometricBrownianMotion::new(0.05, 0.4), 10.0, 2.0);
+        assert_approx_equal!(gbm.mean(), 10.0 * 0.1_f64.exp(), 0.05);
+        assert_approx_equal!(
+            gbm.variance(),
+            100.0 * 0.2_f64.exp() * (0.32_f64.exp() - 1.0),
+            1.5
+        );
+
+        let ou = exact_terminal(&OrnsteinUhlenbeck::new(1.0, 0.3, 0.5), 2.0, 2.0);
+        assert_approx_equal!(ou.mean(), 1.0 + (-1.0_f64).exp(), 0.005);
+        assert_approx_equal!(ou.variance(), 0.09 * (1.0 - (-2.0_f64).exp()), 0.002);
+
+        // Cox-Ingersoll-Ross, with (0.8) and without (8) the Feller condition.
+        for (theta, sigma) in [(2.0, 0.5), (2.0, 0.05)] {
+            let (mu, x_0, t) = (0.04, 0.01, 1.0);
+            let cir = exact_terminal(&CoxIngersollRoss::new(mu, sigma, theta), x_0, t);
+            let decay = f64::exp(-theta * t);
+
+            assert!(cir.iter().all(|x| *x >= 0.0));
+            assert_approx_equal!(cir.mean(), mu + (x_0 - mu) * decay, 0.0005);
+            assert_approx_equal!(
+                cir.variance(),
+                x_0 * sigma * sigma / theta * (decay - decay * decay)
+                    + mu * sigma * sigma / (2.0 * theta) * (1.0 - decay).powi(2),
+                0.0001
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The process has no exact transition.")]
+    fn test_no_exact_transition() {
+        let _ = exact_terminal(&HoLee::new(0.01, 0.02), 0.03, 1.0);
+    }
+}
@@ -61,6 +61,7 @@ pub enum BusinessDayConvention {
 /// present value. When a security such as a bond is sold between interest
 /// payment dates, the seller is eligible to some fraction of the coupon amount.
 /// """
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DayCountConvention {
     // TODO: Implement the following day count conventions.
     // There are fiddly techicalities to consider, such as leap years.
@@ -89,7 +90,7 @@ pub enum DayCountConvention {
 }
 
 /// Interest payment frequency/year enumeration.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PaymentFrequency {
     /// Daily.
     Daily = 252,
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::time::{BusinessDayConvention, DayCountConvention, PaymentFrequency};
+use crate::time::{BusinessDayConvention, DayCountConvention, DayCounter, PaymentFrequency};
 use time::{Duration, OffsetDateTime};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -116,6 +116,19 @@ impl Schedule {
         }
     }
 
+    /// Year fractions from a valuation date to the dates of the schedule
+    /// that fall after it, using the schedule's day count convention.
+    #[must_use]
+    pub fn year_fractions(&self, valuation_date: OffsetDateTime) -> Vec<f64> {
+        self.dates
+            .iter()
+            .filter(|&&date| date > valuation_date)
+            .map(|&date| {
+                DayCounter::day_count_factor(valuation_date, date, &self.day_count_convention)
+            })
+            .collect()
+    }
+
     /// Drops a given date from the schedule.
     pub fn drop(&mut self, date: OffsetDateTime) {
         // let date = date.midnight_at(UtcOffset::UTC); // Convert to OffsetDateTime for comparison
@@ -200,6 +213,16 @@ mod test_schedule {
         let _ = Schedule::new_from_dates(&dates);
     }
 
+    #[test]
+    fn test_year_fractions() {
+        let schedule =
+            Schedule::new_from_start(datetime!(2023-06-01 0:0:0 UTC), Duration::days(73), 3);
+        let fractions = schedule.year_fractions(datetime!(2023-06-01 0:0:0 UTC));
+
+        // The valuation date itself is dropped.
+        assert_eq!(fractions, vec![0.2, 0.4, 0.6]);
+    }
+
     #[test]
     fn test_drop() {
         let mut schedule =